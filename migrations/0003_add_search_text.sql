@@ -0,0 +1,4 @@
+-- sqlx migration: add hidden searchable text (e.g. OCR output) to stickers
+
+ALTER TABLE stickers
+ADD COLUMN search_text TEXT NOT NULL DEFAULT '';
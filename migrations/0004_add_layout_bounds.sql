@@ -0,0 +1,11 @@
+-- sqlx migration: remember sticker bounds per monitor layout
+
+CREATE TABLE IF NOT EXISTS sticker_layout_bounds (
+    sticker_id  INTEGER NOT NULL REFERENCES stickers(id) ON DELETE CASCADE,
+    layout      TEXT NOT NULL,
+    left        INTEGER NOT NULL,
+    top         INTEGER NOT NULL,
+    width       INTEGER NOT NULL,
+    height      INTEGER NOT NULL,
+    PRIMARY KEY (sticker_id, layout)
+);
@@ -0,0 +1,12 @@
+-- sqlx migration: history of command sticker runs
+
+CREATE TABLE IF NOT EXISTS command_runs (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    sticker_id  INTEGER NOT NULL REFERENCES stickers(id) ON DELETE CASCADE,
+    started_at  INTEGER NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    exit_code   INTEGER,
+    value       TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_command_runs_sticker ON command_runs(sticker_id, started_at);
@@ -0,0 +1,4 @@
+-- sqlx migration: per-sticker view preferences as JSON
+
+ALTER TABLE stickers
+ADD COLUMN settings TEXT NOT NULL DEFAULT '{}';
@@ -0,0 +1,14 @@
+-- sqlx migration: tags, a sticker can have many of them
+
+CREATE TABLE IF NOT EXISTS tags (
+    id    INTEGER PRIMARY KEY AUTOINCREMENT,
+    name  TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE IF NOT EXISTS sticker_tags (
+    sticker_id  INTEGER NOT NULL REFERENCES stickers(id) ON DELETE CASCADE,
+    tag_id      INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (sticker_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sticker_tags_tag ON sticker_tags(tag_id);
@@ -0,0 +1,7 @@
+-- sqlx migration: session-scoped stickers which are deleted when closed or expired
+
+ALTER TABLE stickers
+ADD COLUMN temporary INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE stickers
+ADD COLUMN expires_at INTEGER;
@@ -0,0 +1,7 @@
+-- sqlx migration: archived stickers and the trash, both hidden from the list
+
+ALTER TABLE stickers
+ADD COLUMN archived_at INTEGER;
+
+ALTER TABLE stickers
+ADD COLUMN deleted_at INTEGER;
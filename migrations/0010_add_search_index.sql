@@ -0,0 +1,29 @@
+-- sqlx migration: trigram full text index of the searchable sticker text, kept in sync by triggers
+
+CREATE VIRTUAL TABLE stickers_fts USING fts5(
+    title,
+    content,
+    search_text,
+    content = 'stickers',
+    content_rowid = 'id',
+    tokenize = 'trigram'
+);
+
+CREATE TRIGGER stickers_fts_insert AFTER INSERT ON stickers BEGIN
+    INSERT INTO stickers_fts (rowid, title, content, search_text)
+    VALUES (new.id, new.title, new.content, new.search_text);
+END;
+
+CREATE TRIGGER stickers_fts_delete AFTER DELETE ON stickers BEGIN
+    INSERT INTO stickers_fts (stickers_fts, rowid, title, content, search_text)
+    VALUES ('delete', old.id, old.title, old.content, old.search_text);
+END;
+
+CREATE TRIGGER stickers_fts_update AFTER UPDATE OF title, content, search_text ON stickers BEGIN
+    INSERT INTO stickers_fts (stickers_fts, rowid, title, content, search_text)
+    VALUES ('delete', old.id, old.title, old.content, old.search_text);
+    INSERT INTO stickers_fts (rowid, title, content, search_text)
+    VALUES (new.id, new.title, new.content, new.search_text);
+END;
+
+INSERT INTO stickers_fts (stickers_fts) VALUES ('rebuild');
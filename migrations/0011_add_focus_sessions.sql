@@ -0,0 +1,10 @@
+-- sqlx migration: finished pomodoro focus sessions, kept when their sticker is deleted
+
+CREATE TABLE IF NOT EXISTS focus_sessions (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    sticker_id  INTEGER REFERENCES stickers(id) ON DELETE SET NULL,
+    finished_at INTEGER NOT NULL,
+    duration_ms INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_focus_sessions_finished ON focus_sessions(finished_at);
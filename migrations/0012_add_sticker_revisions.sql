@@ -0,0 +1,10 @@
+-- sqlx migration: earlier contents of stickers, written whenever the content changes
+
+CREATE TABLE IF NOT EXISTS sticker_revisions (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    sticker_id  INTEGER NOT NULL REFERENCES stickers(id) ON DELETE CASCADE,
+    content     TEXT NOT NULL,
+    created_at  INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sticker_revisions_sticker ON sticker_revisions(sticker_id, id);
@@ -0,0 +1,10 @@
+-- sqlx migration: files which mirror stickers in a sync folder, with the content hash
+-- both sides agreed on after the last sync
+
+CREATE TABLE IF NOT EXISTS synced_files (
+    folder      TEXT NOT NULL,
+    sticker_id  INTEGER NOT NULL,
+    file_name   TEXT NOT NULL,
+    hash        TEXT NOT NULL,
+    PRIMARY KEY (folder, sticker_id)
+);
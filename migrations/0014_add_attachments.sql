@@ -0,0 +1,14 @@
+-- sqlx migration: binary data of stickers (images, audio, files) kept out of their content
+
+CREATE TABLE IF NOT EXISTS attachments (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    sticker_id  INTEGER NOT NULL REFERENCES stickers(id) ON DELETE CASCADE,
+    name        TEXT NOT NULL,
+    mime        TEXT NOT NULL,
+    size        INTEGER NOT NULL,
+    hash        TEXT NOT NULL,
+    data        BLOB NOT NULL,
+    created_at  INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_attachments_sticker_hash ON attachments(sticker_id, hash);
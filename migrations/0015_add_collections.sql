@@ -0,0 +1,12 @@
+-- sqlx migration: collections, named groups like "Work" or "Home", a sticker is in at most one
+
+CREATE TABLE IF NOT EXISTS collections (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    name        TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at  INTEGER NOT NULL
+);
+
+ALTER TABLE stickers
+ADD COLUMN collection_id INTEGER REFERENCES collections(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_stickers_collection ON stickers(collection_id);
@@ -0,0 +1,13 @@
+-- sqlx migration: stacks, sticker windows which move together and can collapse into tabs
+
+CREATE TABLE IF NOT EXISTS stacks (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    collapsed   INTEGER NOT NULL DEFAULT 0,
+    -- The sticker shown while collapsed.
+    active_id   INTEGER
+);
+
+ALTER TABLE stickers
+ADD COLUMN stack_id INTEGER REFERENCES stacks(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_stickers_stack ON stickers(stack_id);
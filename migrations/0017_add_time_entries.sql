@@ -0,0 +1,12 @@
+-- sqlx migration: tracked time of time tracker stickers, kept when their sticker is deleted
+
+CREATE TABLE IF NOT EXISTS time_entries (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    sticker_id  INTEGER REFERENCES stickers(id) ON DELETE SET NULL,
+    project     TEXT NOT NULL DEFAULT '',
+    task        TEXT NOT NULL DEFAULT '',
+    started_at  INTEGER NOT NULL,
+    ended_at    INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_time_entries_ended ON time_entries(ended_at);
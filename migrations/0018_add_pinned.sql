@@ -0,0 +1,4 @@
+-- sqlx migration: pinned stickers, listed before the others in the main window
+
+ALTER TABLE stickers
+ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,41 @@
+-- sqlx migration: attachment data stored once per content in hash named files, shared by stickers
+
+-- `data` only holds what was stored in the database before, it is moved to a file on open.
+CREATE TABLE IF NOT EXISTS attachment_blobs (
+    hash        TEXT PRIMARY KEY,
+    size        INTEGER NOT NULL,
+    ref_count   INTEGER NOT NULL DEFAULT 0,
+    data        BLOB,
+    created_at  INTEGER NOT NULL
+);
+
+INSERT INTO attachment_blobs (hash, size, ref_count, data, created_at)
+SELECT hash, MAX(size), COUNT(1), MAX(data), MIN(created_at) FROM attachments GROUP BY hash;
+
+CREATE TABLE attachments_new (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    sticker_id  INTEGER NOT NULL REFERENCES stickers(id) ON DELETE CASCADE,
+    name        TEXT NOT NULL,
+    mime        TEXT NOT NULL,
+    size        INTEGER NOT NULL,
+    hash        TEXT NOT NULL,
+    created_at  INTEGER NOT NULL
+);
+
+INSERT INTO attachments_new (id, sticker_id, name, mime, size, hash, created_at)
+SELECT id, sticker_id, name, mime, size, hash, created_at FROM attachments;
+
+DROP TABLE attachments;
+ALTER TABLE attachments_new RENAME TO attachments;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_attachments_sticker_hash ON attachments(sticker_id, hash);
+CREATE INDEX IF NOT EXISTS idx_attachments_hash ON attachments(hash);
+
+-- Also fired by the cascade when a sticker is deleted.
+CREATE TRIGGER attachment_blobs_ref AFTER INSERT ON attachments BEGIN
+    UPDATE attachment_blobs SET ref_count = ref_count + 1 WHERE hash = new.hash;
+END;
+
+CREATE TRIGGER attachment_blobs_unref AFTER DELETE ON attachments BEGIN
+    UPDATE attachment_blobs SET ref_count = ref_count - 1 WHERE hash = old.hash;
+END;
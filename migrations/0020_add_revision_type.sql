@@ -0,0 +1,7 @@
+-- sqlx migration: the sticker type of each earlier content, so a version from before a conversion can be restored
+
+ALTER TABLE sticker_revisions
+ADD COLUMN type TEXT NOT NULL DEFAULT 'markdown';
+
+UPDATE sticker_revisions
+SET type = (SELECT type FROM stickers WHERE stickers.id = sticker_revisions.sticker_id);
@@ -0,0 +1,195 @@
+use std::path::PathBuf;
+
+use crate::ipc::IpcEvent;
+use crate::utils::template::parse_vars;
+
+/// Command line arguments.
+///
+/// Commands are forwarded to the primary instance over IPC, e.g.
+/// `rustickers new-from-template incident --vars service=db severity=1`.
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    pub event: Option<IpcEvent>,
+    /// Print the status JSON of the running instance and exit.
+    pub status: bool,
+    /// `--data-dir <path>`, overrides where the database and settings live.
+    pub data_dir: Option<PathBuf>,
+    /// `--hidden`, start with the main window minimized, e.g. in an autostart entry.
+    pub hidden: bool,
+    /// `--restore-stickers=false`, leave the windows of open stickers closed on start.
+    pub skip_restore_stickers: bool,
+}
+
+impl CliArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut cli = Self::default();
+        let mut args = args.into_iter().peekable();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "new-from-template" => {
+                    let Some(name) = args.next() else {
+                        anyhow::bail!("new-from-template requires a template name");
+                    };
+
+                    let mut vars: Vec<String> = Vec::new();
+                    while let Some(next) = args.peek() {
+                        if next == "--vars" || next == "--var" {
+                            args.next();
+                            continue;
+                        }
+                        if next.starts_with("--") || !next.contains('=') {
+                            break;
+                        }
+                        if let Some(var) = args.next() {
+                            vars.push(var);
+                        }
+                    }
+
+                    cli.event = Some(IpcEvent::NewFromTemplate {
+                        name,
+                        vars: parse_vars(vars.iter().map(String::as_str)),
+                    });
+                }
+                "status" => {
+                    cli.status = true;
+                }
+                "annotate" => {
+                    cli.event = Some(IpcEvent::Annotate);
+                }
+                "lock" => {
+                    cli.event = Some(IpcEvent::Lock);
+                }
+                "restore-stickers" => {
+                    cli.event = Some(IpcEvent::RestoreStickers);
+                }
+                "gc-attachments" => {
+                    cli.event = Some(IpcEvent::CollectAttachments);
+                }
+                "--hidden" => {
+                    cli.hidden = true;
+                }
+                "--restore-stickers" => {
+                    cli.skip_restore_stickers = false;
+                }
+                other if other.starts_with("--restore-stickers=") => {
+                    let value = &other["--restore-stickers=".len()..];
+                    let Ok(restore) = value.parse::<bool>() else {
+                        anyhow::bail!("--restore-stickers expects true or false, got {value}");
+                    };
+                    cli.skip_restore_stickers = !restore;
+                }
+                "--data-dir" => {
+                    let Some(dir) = args.next() else {
+                        anyhow::bail!("--data-dir requires a path");
+                    };
+                    cli.data_dir = Some(PathBuf::from(dir));
+                }
+                other if other.starts_with("--data-dir=") => {
+                    cli.data_dir = Some(PathBuf::from(&other["--data-dir=".len()..]));
+                }
+                other => {
+                    anyhow::bail!("Unknown argument: {other}");
+                }
+            }
+        }
+
+        Ok(cli)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> anyhow::Result<CliArgs> {
+        CliArgs::parse(args.iter().map(|x| x.to_string()))
+    }
+
+    fn template(cli: &CliArgs) -> (&str, &[(String, String)]) {
+        match &cli.event {
+            Some(IpcEvent::NewFromTemplate { name, vars }) => (name.as_str(), vars.as_slice()),
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+
+    #[test]
+    fn new_from_template() {
+        let cli = parse(&[
+            "new-from-template",
+            "incident",
+            "--vars",
+            "service=db",
+            "sev=1",
+        ])
+        .unwrap();
+        let (name, vars) = template(&cli);
+        assert_eq!(name, "incident");
+        assert_eq!(
+            vars,
+            [
+                ("service".to_string(), "db".to_string()),
+                ("sev".to_string(), "1".to_string()),
+            ]
+        );
+
+        // Variables stop at the next option.
+        let cli = parse(&[
+            "new-from-template",
+            "standup",
+            "--var",
+            "team=core",
+            "--hidden",
+        ])
+        .unwrap();
+        assert_eq!(template(&cli).1.len(), 1);
+        assert!(cli.hidden);
+
+        let cli = parse(&["new-from-template", "empty"]).unwrap();
+        assert!(template(&cli).1.is_empty());
+
+        assert!(parse(&["new-from-template"]).is_err());
+        // A word without `=` is not a variable but an unknown argument.
+        assert!(parse(&["new-from-template", "incident", "--vars", "db"]).is_err());
+    }
+
+    #[test]
+    fn options() {
+        let cli = parse(&[]).unwrap();
+        assert!(cli.event.is_none() && !cli.status && !cli.hidden && !cli.skip_restore_stickers);
+
+        let cli = parse(&["--data-dir", "/tmp/a", "--restore-stickers=false", "status"]).unwrap();
+        assert_eq!(cli.data_dir, Some(PathBuf::from("/tmp/a")));
+        assert!(cli.skip_restore_stickers);
+        assert!(cli.status);
+
+        let cli = parse(&["--data-dir=/tmp/b", "--restore-stickers", "lock"]).unwrap();
+        assert_eq!(cli.data_dir, Some(PathBuf::from("/tmp/b")));
+        assert!(!cli.skip_restore_stickers);
+        assert!(matches!(cli.event, Some(IpcEvent::Lock)));
+
+        assert!(parse(&["--data-dir"]).is_err());
+        assert!(parse(&["--restore-stickers=maybe"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+    }
+
+    #[test]
+    fn ipc_lines() {
+        let cli = parse(&["new-from-template", "incident", "--vars", "title=a b {c}"]).unwrap();
+        let event = cli.event.unwrap();
+        let line = event.to_line();
+        assert!(line.starts_with("NEW_FROM_TEMPLATE {"));
+        let Some(IpcEvent::NewFromTemplate { name, vars }) = IpcEvent::parse_line(&line) else {
+            panic!("{line} not parsed back");
+        };
+        assert_eq!(name, "incident");
+        assert_eq!(vars, [("title".to_string(), "a b {c}".to_string())]);
+
+        assert!(matches!(
+            IpcEvent::parse_line("SHOW\n"),
+            Some(IpcEvent::Show)
+        ));
+        assert!(IpcEvent::parse_line("NEW_FROM_TEMPLATE not-json").is_none());
+        assert!(IpcEvent::parse_line("UNKNOWN").is_none());
+    }
+}
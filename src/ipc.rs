@@ -1,12 +1,16 @@
 use interprocess::local_socket::{
     GenericFilePath, GenericNamespaced, ListenerOptions, Name, Stream, prelude::*,
 };
+use serde::Deserialize;
 use std::env;
 use std::io::{self, BufRead, BufReader, Write};
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{self, Sender};
 use std::thread;
 use std::time::Duration;
 
+const STATUS_COMMAND: &str = "STATUS";
+const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Debug)]
 pub enum AcquireError {
     /// Another instance is running. We signaled it to show itself.
@@ -16,8 +20,76 @@ pub enum AcquireError {
     Io(std::io::Error),
 }
 
+#[derive(Debug, Clone)]
 pub enum IpcEvent {
     Show,
+    /// Open or close the drawing layer over the screen.
+    Annotate,
+    /// Hide every window behind the lock screen, see [`crate::native::app_lock`].
+    Lock,
+    /// Open the windows of open stickers, e.g. after starting with `--restore-stickers=false`.
+    RestoreStickers,
+    /// Remove attachment data no sticker refers to anymore.
+    CollectAttachments,
+    NewFromTemplate {
+        name: String,
+        vars: Vec<(String, String)>,
+    },
+    /// Ask for the status JSON, it is written back to the connection.
+    Status {
+        reply: Sender<String>,
+    },
+}
+
+impl IpcEvent {
+    /// Encode the event as a single protocol line (without the trailing newline).
+    pub fn to_line(&self) -> String {
+        match self {
+            Self::Show => "SHOW".to_string(),
+            Self::Annotate => "ANNOTATE".to_string(),
+            Self::Lock => "LOCK".to_string(),
+            Self::RestoreStickers => "RESTORE_STICKERS".to_string(),
+            Self::CollectAttachments => "COLLECT_ATTACHMENTS".to_string(),
+            Self::NewFromTemplate { name, vars } => {
+                let payload = serde_json::json!({ "name": name, "vars": vars });
+                format!("NEW_FROM_TEMPLATE {payload}")
+            }
+            Self::Status { .. } => STATUS_COMMAND.to_string(),
+        }
+    }
+
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let (command, payload) = line.split_once(' ').unwrap_or((line, ""));
+
+        match command {
+            "SHOW" => Some(Self::Show),
+            "ANNOTATE" => Some(Self::Annotate),
+            "LOCK" => Some(Self::Lock),
+            "RESTORE_STICKERS" => Some(Self::RestoreStickers),
+            "COLLECT_ATTACHMENTS" => Some(Self::CollectAttachments),
+            "NEW_FROM_TEMPLATE" => {
+                #[derive(Deserialize)]
+                struct Payload {
+                    name: String,
+                    #[serde(default)]
+                    vars: Vec<(String, String)>,
+                }
+
+                match serde_json::from_str::<Payload>(payload) {
+                    Ok(payload) => Some(Self::NewFromTemplate {
+                        name: payload.name,
+                        vars: payload.vars,
+                    }),
+                    Err(err) => {
+                        tracing::warn!(error = %err, "Invalid NEW_FROM_TEMPLATE payload");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
 }
 
 pub struct SingleInstance {
@@ -27,7 +99,9 @@ pub struct SingleInstance {
 
 impl SingleInstance {
     /// Attempts to become the primary instance.
-    pub fn acquire(app_id: &str) -> Result<Self, AcquireError> {
+    ///
+    /// If another instance is running, `signal` is forwarded to it instead.
+    pub fn acquire(app_id: &str, signal: &IpcEvent) -> Result<Self, AcquireError> {
         let (token, name) = create_socket_name(app_id);
         let name = name.map_err(AcquireError::Io)?;
 
@@ -46,7 +120,7 @@ impl SingleInstance {
                 // 2. Address in use: Is it a live process or a "corpse socket"?
 
                 // Try to connect to it.
-                match connect_and_signal(&name) {
+                match connect_and_signal(&name, signal) {
                     Ok(_) => {
                         // Connection worked -> The other process is alive.
                         Err(AcquireError::AlreadyRunning)
@@ -98,9 +172,26 @@ impl SingleInstance {
                     // Read a line (blocking until \n is received or connection closes)
                     if let Ok(_) = reader.read_line(&mut buffer) {
                         tracing::debug!(cmd = %buffer.trim(), "Received IPC command");
+                        if buffer.trim() == STATUS_COMMAND {
+                            // The app may take a while to answer, keep accepting meanwhile.
+                            let mut conn = reader.into_inner();
+                            let ipc_events_tx = ipc_events_tx.clone();
+                            if let Err(err) = thread::Builder::new()
+                                .name("ipc-status".to_string())
+                                .spawn(move || reply_status(&mut conn, &ipc_events_tx))
+                            {
+                                tracing::warn!(error = %err, "Failed to spawn IPC status thread");
+                            }
+                            continue;
+                        }
                         // Check protocol
-                        if buffer.trim() == "SHOW" {
-                            let _ = ipc_events_tx.send(IpcEvent::Show);
+                        match IpcEvent::parse_line(&buffer) {
+                            Some(event) => {
+                                let _ = ipc_events_tx.send(event);
+                            }
+                            None => {
+                                tracing::warn!(cmd = %buffer.trim(), "Unknown IPC command");
+                            }
                         }
                     }
                 }
@@ -111,8 +202,61 @@ impl SingleInstance {
     }
 }
 
+/// Ask the running instance for its status JSON. Gives up when it does not answer
+/// within a little more than the time the instance waits for the app.
+pub fn query_status(app_id: &str) -> io::Result<String> {
+    let (_, name) = create_socket_name(app_id);
+    let name = name?;
+    let (reply_tx, reply_rx) = mpsc::channel();
+    // The stream has no read timeout, a hung instance would block the CLI for good.
+    thread::Builder::new()
+        .name("ipc-status-query".to_string())
+        .spawn(move || {
+            let reply = Stream::connect(name).and_then(|mut stream| {
+                stream.write_all(format!("{STATUS_COMMAND}\n").as_bytes())?;
+                stream.flush()?;
+
+                let mut line = String::new();
+                BufReader::new(stream).read_line(&mut line)?;
+                Ok(line.trim_end().to_string())
+            });
+            let _ = reply_tx.send(reply);
+        })?;
+
+    reply_rx
+        .recv_timeout(STATUS_TIMEOUT + Duration::from_secs(1))
+        .unwrap_or_else(|_| {
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the running instance did not answer",
+            ))
+        })
+}
+
 // --- Helper Functions ---
 
+fn reply_status(conn: &mut Stream, ipc_events_tx: &Sender<IpcEvent>) {
+    let (reply, status_rx) = mpsc::channel();
+    if ipc_events_tx.send(IpcEvent::Status { reply }).is_err() {
+        return;
+    }
+
+    let status = match status_rx.recv_timeout(STATUS_TIMEOUT) {
+        Ok(status) => status,
+        Err(err) => {
+            tracing::warn!(error = %err, "No status reply from the app");
+            serde_json::json!({ "error": "status not available yet" }).to_string()
+        }
+    };
+
+    if let Err(err) = conn
+        .write_all(format!("{status}\n").as_bytes())
+        .and_then(|_| conn.flush())
+    {
+        tracing::warn!(error = %err, "Failed to write IPC status reply");
+    }
+}
+
 /// Filter function from the official reference
 fn handle_incoming_error(conn: io::Result<Stream>) -> Option<Stream> {
     match conn {
@@ -124,15 +268,17 @@ fn handle_incoming_error(conn: io::Result<Stream>) -> Option<Stream> {
     }
 }
 
-fn connect_and_signal(name: &Name) -> io::Result<()> {
+fn connect_and_signal(name: &Name, signal: &IpcEvent) -> io::Result<()> {
+    let line = format!("{}\n", signal.to_line());
+
     // Retry strategy for the client side (in case server is currently binding)
     let mut retries = 5;
     while retries > 0 {
         match Stream::connect(name.clone()) {
             Ok(mut stream) => {
-                stream.write_all(b"SHOW\n")?;
+                stream.write_all(line.as_bytes())?;
                 stream.flush()?;
-                tracing::info!("Signaled existing instance to show");
+                tracing::info!(signal = ?signal, "Signaled existing instance");
                 return Ok(());
             }
             Err(e) => {
@@ -151,9 +297,9 @@ fn connect_and_signal(name: &Name) -> io::Result<()> {
 
     // Final attempt
     let mut stream = Stream::connect(name.clone())?;
-    stream.write_all(b"SHOW\n")?;
+    stream.write_all(line.as_bytes())?;
     stream.flush()?;
-    tracing::info!("Signaled existing instance to show");
+    tracing::info!(signal = ?signal, "Signaled existing instance");
     Ok(())
 }
 
@@ -3,6 +3,7 @@
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
+mod cli;
 mod ipc;
 mod model;
 mod native;
@@ -15,17 +16,46 @@ use std::sync::mpsc;
 use storage::paths::AppPaths;
 
 fn main() {
-    let app_paths = AppPaths::new().expect("App paths should initialize");
+    let cli = match cli::CliArgs::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(err) => {
+            eprintln!("{err:#}");
+            return;
+        }
+    };
+
+    let app_paths = match AppPaths::new(cli.data_dir.clone()) {
+        Ok(app_paths) => app_paths,
+        Err(err) => {
+            eprintln!("Failed to resolve the data directory: {err:#}");
+            std::process::exit(1);
+        }
+    };
+    let instance_id = app_paths.instance_id();
+
+    if cli.status {
+        match ipc::query_status(&instance_id) {
+            Ok(status) => println!("{status}"),
+            Err(err) => {
+                eprintln!("Rustickers is not running: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     let _ =
         crate::utils::logging::LoggingGuards::init(&app_paths).expect("Logging should initialize");
 
     tracing::info!(
         app_version = env!("CARGO_PKG_VERSION"),
         debug_build = cfg!(debug_assertions),
+        data_dir = %app_paths.data_dir().display(),
         "Starting Rustickers"
     );
 
-    let mut single_instance = match crate::ipc::SingleInstance::acquire("rustickers") {
+    let signal = cli.event.clone().unwrap_or(IpcEvent::Show);
+    let mut single_instance = match crate::ipc::SingleInstance::acquire(&instance_id, &signal) {
         Ok(instance) => Some(instance),
         Err(ipc::AcquireError::AlreadyRunning) => {
             tracing::info!("Another instance is already running; exiting");
@@ -46,12 +76,23 @@ fn main() {
         instance.start_ipc_server(ipc_events_tx.clone());
     }
 
+    // We are the primary instance, so handle the command line command ourselves.
+    if let Some(event) = cli.event {
+        let _ = ipc_events_tx.send(event);
+    }
+
     if let Err(err) = crate::native::hotkey::start_global_hotkey_listener(ipc_events_tx.clone()) {
         tracing::error!(error = %err, "Failed to start global hotkey listener");
     }
 
+    let startup = crate::native::StartupOptions {
+        hidden: cli.hidden,
+        restore_stickers: !cli.skip_restore_stickers,
+    };
+
     crate::native::run_native(
         app_paths,
+        startup,
         ipc_events_rx,
         sticker_events_tx,
         sticker_events_rx,
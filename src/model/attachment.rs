@@ -0,0 +1,27 @@
+/// Binary data linked to a sticker, which its content refers to by id instead of
+/// holding it. The data itself is read separately.
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Attachment {
+    pub id: i64,
+    pub sticker_id: i64,
+    /// Original file name, e.g. `screenshot.png`.
+    pub name: String,
+    pub mime: String,
+    /// Bytes of the data.
+    pub size: i64,
+    /// SHA-256 of the data, which names its file. The same data is stored once, however
+    /// many stickers have it.
+    pub hash: String,
+    pub created_at: i64,
+}
+
+/// What [`crate::storage::StickerStore::collect_orphan_attachments`] removed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AttachmentCollection {
+    /// Data no attachment referred to.
+    pub blobs: usize,
+    /// Files removed from the attachments and images folders, with their bytes.
+    pub files: usize,
+    pub bytes: u64,
+}
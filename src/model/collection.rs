@@ -0,0 +1,9 @@
+/// A named group of stickers, e.g. "Work" or "Home". Unlike tags, a sticker is in at
+/// most one collection.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    /// Stickers in the collection which are neither archived nor trashed.
+    pub sticker_count: i64,
+}
@@ -0,0 +1,11 @@
+/// One finished run of a command sticker.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CommandRun {
+    pub id: i64,
+    pub sticker_id: i64,
+    pub started_at: i64,
+    pub duration_ms: i64,
+    pub exit_code: Option<i32>,
+    /// The last non-empty output line, e.g. the number a scheduled check prints.
+    pub value: String,
+}
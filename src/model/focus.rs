@@ -0,0 +1,63 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveTime, TimeZone};
+
+/// One finished focus session of a pomodoro sticker.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct FocusSession {
+    pub id: i64,
+    /// `None` once the sticker was deleted, the session still counts.
+    pub sticker_id: Option<i64>,
+    pub finished_at: i64,
+    pub duration_ms: i64,
+}
+
+/// Focus sessions finished today and this week (from Monday), in local time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FocusTotals {
+    pub today: u32,
+    pub week: u32,
+}
+
+impl FocusTotals {
+    pub fn count(sessions: &[FocusSession], now: DateTime<Local>) -> Self {
+        let day_start = start_of_day(now, 0);
+        let week_start = week_start(now);
+        sessions
+            .iter()
+            .fold(Self::default(), |mut totals, session| {
+                if session.finished_at >= week_start {
+                    totals.week += 1;
+                }
+                if session.finished_at >= day_start {
+                    totals.today += 1;
+                }
+                totals
+            })
+    }
+}
+
+/// "3/8", or only "3" when there is no goal.
+pub fn progress(done: u32, goal: u32) -> String {
+    if goal == 0 {
+        done.to_string()
+    } else {
+        format!("{done}/{goal}")
+    }
+}
+
+/// Unix millis of Monday 00:00 of the week of `now`, in local time.
+pub fn week_start(now: DateTime<Local>) -> i64 {
+    start_of_day(now, now.weekday().num_days_from_monday())
+}
+
+/// Unix millis of 00:00 local time, `days_back` days before `now`.
+pub fn start_of_day(now: DateTime<Local>, days_back: u32) -> i64 {
+    let day = now
+        .date_naive()
+        .checked_sub_days(Days::new(days_back as u64))
+        .unwrap_or(now.date_naive());
+    Local
+        .from_local_datetime(&day.and_time(NaiveTime::MIN))
+        .earliest()
+        .map(|x| x.timestamp_millis())
+        .unwrap_or(0)
+}
@@ -1 +1,9 @@
+pub mod attachment;
+pub mod collection;
+pub mod command;
+pub mod focus;
+pub mod revision;
+pub mod stack;
 pub mod sticker;
+pub mod sync;
+pub mod time_entry;
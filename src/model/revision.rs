@@ -0,0 +1,14 @@
+use crate::model::sticker::StickerType;
+
+/// An earlier content of a sticker, kept in its history.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StickerRevision {
+    pub id: i64,
+    pub sticker_id: i64,
+    /// Type of the sticker back then, it differs from the current one after a conversion.
+    #[sqlx(rename = "type")]
+    pub sticker_type: StickerType,
+    pub content: String,
+    /// When this content was saved, it was replaced later.
+    pub created_at: i64,
+}
@@ -0,0 +1,18 @@
+/// Sticker windows grouped so they move together, optionally collapsed into one window
+/// with a tab per sticker.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct Stack {
+    pub id: i64,
+    pub collapsed: bool,
+    /// The sticker shown while collapsed.
+    pub active_id: Option<i64>,
+    /// Stickers in the stack which are neither archived nor trashed, oldest first.
+    #[sqlx(skip)]
+    pub members: Vec<StackMember>,
+}
+
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct StackMember {
+    pub id: i64,
+    pub title: String,
+}
@@ -3,13 +3,39 @@ use std::str::FromStr;
 use gpui::rgb;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
 #[sqlx(rename_all = "lowercase")]
 pub enum StickerType {
     Markdown,
     Timer,
     Command,
     Paint,
+    Image,
+    Web,
+    Clock,
+    Pomodoro,
+    System,
+    Agenda,
+    Habit,
+    Kanban,
+    Code,
+    Counter,
+    Chart,
+    Currency,
+    Animation,
+    GitHub,
+    Reminder,
+    Http,
+    Palette,
+    Units,
+    Uptime,
+    Git,
+    Launcher,
+    Media,
+    Calculator,
+    MindMap,
+    TimeTracker,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,6 +44,8 @@ pub enum StickerOrderBy {
     CreatedDesc,
     UpdatedAsc,
     UpdatedDesc,
+    /// Pinned stickers first, newest first within both.
+    PinnedFirst,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
@@ -39,6 +67,63 @@ pub enum StickerColor {
     Gray,
 }
 
+/// Extra conditions for listing stickers, `None` means no restriction.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StickerFilter {
+    pub color: Option<StickerColor>,
+    pub sticker_type: Option<StickerType>,
+    /// Id of the collection, see [`crate::model::collection::Collection`].
+    pub collection: Option<i64>,
+    pub bucket: StickerBucket,
+}
+
+/// Where a sticker lives, archived and trashed stickers are hidden from the list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StickerBucket {
+    #[default]
+    Active,
+    Archived,
+    Trash,
+}
+
+impl StickerBucket {
+    pub const ALL: [Self; 3] = [Self::Active, Self::Archived, Self::Trash];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Active => "Stickers",
+            Self::Archived => "Archive",
+            Self::Trash => "Trash",
+        }
+    }
+}
+
+/// Characters of a card preview, the rest is cut with an ellipsis.
+const SUMMARY_MAX_CHARS: usize = 120;
+
+/// One line preview of a Markdown sticker: the text after its title line,
+/// without the list, quote and heading markers.
+pub fn markdown_summary(content: &str) -> String {
+    let text = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .skip(1)
+        .map(|line| {
+            line.trim_start_matches(['#', '>', '-', '*', '+', ' '])
+                .trim_start_matches("[ ] ")
+                .trim_start_matches("[x] ")
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ");
+
+    match text.char_indices().nth(SUMMARY_MAX_CHARS) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text,
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct StickerBrief {
@@ -50,6 +135,11 @@ pub struct StickerBrief {
     pub sticker_type: StickerType,
     pub created_at: i64,
     pub updated_at: i64,
+    /// Card preview, see [`markdown_summary`].
+    pub summary: String,
+    pub collection_id: Option<i64>,
+    /// Listed before the others with [`StickerOrderBy::PinnedFirst`].
+    pub pinned: bool,
 }
 
 #[allow(dead_code)]
@@ -62,15 +152,182 @@ pub struct StickerDetail {
     pub top: i32,
     pub width: i32,
     pub height: i32,
+    /// The window is kept above all other windows.
     pub top_most: bool,
     pub color: StickerColor,
     #[sqlx(rename = "type")]
     pub sticker_type: StickerType,
     pub content: String,
+    /// JSON of [`StickerSettings`], see [`Self::sticker_settings`].
+    pub settings: String,
+    /// Never restored as open, deleted when its window closes.
+    pub temporary: bool,
+    /// Unix millis after which a temporary sticker is deleted even while open.
+    pub expires_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+impl StickerDetail {
+    pub fn sticker_settings(&self) -> StickerSettings {
+        StickerSettings::parse(&self.settings)
+    }
+}
+
+/// Per-sticker view preferences, kept apart from the type specific `content`. Keeping
+/// the window on top is the `top_most` column of the sticker instead.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StickerSettings {
+    /// Scroll long lines horizontally instead of wrapping them.
+    pub no_wrap: bool,
+    pub monospace: bool,
+    /// Height of the window before it was collapsed to its header, `None` while expanded.
+    pub expanded_height: Option<i32>,
+    /// Opacity of the window, `None` for opaque. Read through [`Self::opacity`].
+    pub opacity: Option<f32>,
+    /// Rem size of the sticker view in pixels, `None` for the one of its type. Read
+    /// through [`Self::rem_size`].
+    pub rem_size: Option<f32>,
+}
+
+impl StickerSettings {
+    /// A sticker never gets fainter than this, so it can still be found.
+    pub const MIN_OPACITY: f32 = 0.3;
+    pub const MIN_REM_SIZE: f32 = 10.0;
+    pub const MAX_REM_SIZE: f32 = 24.0;
+
+    pub fn parse(json: &str) -> Self {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    /// Opacity of the window, kept in range whatever the JSON says.
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+            .filter(|x| x.is_finite())
+            .map_or(1.0, |x| x.clamp(Self::MIN_OPACITY, 1.0))
+    }
+
+    /// Rem size of the sticker view, kept in range whatever the JSON says.
+    pub fn rem_size(&self) -> Option<f32> {
+        self.rem_size
+            .filter(|x| x.is_finite())
+            .map(|x| x.clamp(Self::MIN_REM_SIZE, Self::MAX_REM_SIZE))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl StickerType {
+    pub const ALL: [Self; 29] = [
+        Self::Markdown,
+        Self::Timer,
+        Self::Command,
+        Self::Paint,
+        Self::Image,
+        Self::Web,
+        Self::Clock,
+        Self::Pomodoro,
+        Self::System,
+        Self::Agenda,
+        Self::Habit,
+        Self::Kanban,
+        Self::Code,
+        Self::Counter,
+        Self::Chart,
+        Self::Currency,
+        Self::Animation,
+        Self::GitHub,
+        Self::Reminder,
+        Self::Http,
+        Self::Palette,
+        Self::Units,
+        Self::Uptime,
+        Self::Git,
+        Self::Launcher,
+        Self::Media,
+        Self::Calculator,
+        Self::MindMap,
+        Self::TimeTracker,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Markdown => "Text",
+            Self::Timer => "Timer",
+            Self::Command => "Command",
+            Self::Paint => "Paint",
+            Self::Image => "Image",
+            Self::Web => "Web",
+            Self::Clock => "Clock",
+            Self::Pomodoro => "Pomodoro",
+            Self::System => "System",
+            Self::Agenda => "Agenda",
+            Self::Habit => "Habit",
+            Self::Kanban => "Kanban",
+            Self::Code => "Code",
+            Self::Counter => "Counter",
+            Self::Chart => "Chart",
+            Self::Currency => "Currency",
+            Self::Animation => "Animation",
+            Self::GitHub => "GitHub",
+            Self::Reminder => "Reminder",
+            Self::Http => "HTTP",
+            Self::Palette => "Palette",
+            Self::Units => "Units",
+            Self::Uptime => "Uptime",
+            Self::Git => "Git",
+            Self::Launcher => "Launcher",
+            Self::Media => "Media",
+            Self::Calculator => "Calculator",
+            Self::MindMap => "Mind map",
+            Self::TimeTracker => "Time tracker",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Timer => "timer",
+            Self::Command => "command",
+            Self::Paint => "paint",
+            Self::Image => "image",
+            Self::Web => "web",
+            Self::Clock => "clock",
+            Self::Pomodoro => "pomodoro",
+            Self::System => "system",
+            Self::Agenda => "agenda",
+            Self::Habit => "habit",
+            Self::Kanban => "kanban",
+            Self::Code => "code",
+            Self::Counter => "counter",
+            Self::Chart => "chart",
+            Self::Currency => "currency",
+            Self::Animation => "animation",
+            Self::GitHub => "github",
+            Self::Reminder => "reminder",
+            Self::Http => "http",
+            Self::Palette => "palette",
+            Self::Units => "units",
+            Self::Uptime => "uptime",
+            Self::Git => "git",
+            Self::Launcher => "launcher",
+            Self::Media => "media",
+            Self::Calculator => "calculator",
+            Self::MindMap => "mindmap",
+            Self::TimeTracker => "timetracker",
+        }
+    }
+}
+
+/// Trim a tag and drop a leading `#`, empty tags are rejected.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').trim();
+    (!tag.is_empty()).then(|| tag.to_string())
+}
+
 impl StickerColor {
     pub const ALL: [Self; 5] = [
         Self::Pink,
@@ -100,6 +357,16 @@ impl StickerColor {
         }
     }
 
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Yellow => "Yellow",
+            Self::Green => "Green",
+            Self::Blue => "Blue",
+            Self::Pink => "Pink",
+            Self::Gray => "Gray",
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Yellow => "yellow",
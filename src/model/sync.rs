@@ -0,0 +1,10 @@
+/// A file mirroring a sticker in the sync folder, see [`crate::native::folder_sync`].
+/// No foreign key, the file of a deleted sticker still has to be removed.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SyncedFile {
+    pub folder: String,
+    pub sticker_id: i64,
+    pub file_name: String,
+    /// SHA-256 of the file content when sticker and file last matched.
+    pub hash: String,
+}
@@ -0,0 +1,45 @@
+/// One tracked interval of a time tracker sticker.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TimeEntry {
+    pub id: i64,
+    /// `None` once the sticker was deleted, the entry still counts.
+    pub sticker_id: Option<i64>,
+    pub project: String,
+    pub task: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+}
+
+impl TimeEntry {
+    /// Millis of the entry from `since` on, 0 when it ended before.
+    pub fn tracked_since(&self, since: i64) -> i64 {
+        (self.ended_at - self.started_at.max(since)).max(0)
+    }
+}
+
+/// Tracked millis per project from `since` on, most tracked first. An entry which began
+/// before `since`, e.g. yesterday evening, only counts with its part after it.
+pub fn totals_by_project(entries: &[TimeEntry], since: i64) -> Vec<(String, i64)> {
+    let mut totals: Vec<(String, i64)> = Vec::new();
+    for entry in entries {
+        let tracked = entry.tracked_since(since);
+        if tracked == 0 {
+            continue;
+        }
+        match totals
+            .iter_mut()
+            .find(|(project, _)| *project == entry.project)
+        {
+            Some((_, total)) => *total += tracked,
+            None => totals.push((entry.project.clone(), tracked)),
+        }
+    }
+    totals.sort_by(|a, b| b.1.cmp(&a.1));
+    totals
+}
+
+/// "1:05" for hours and minutes, the precision of the daily totals.
+pub fn format_duration(ms: i64) -> String {
+    let mins = ms.max(0) / 60_000;
+    format!("{}:{:02}", mins / 60, mins % 60)
+}
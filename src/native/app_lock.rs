@@ -0,0 +1,261 @@
+//! Hide the content of every window behind a lock screen until the PIN is entered,
+//! after a while without input or on demand (hotkey, `rustickers lock`).
+//!
+//! The PIN is checked on the background executor, a few wrong PINs in a row make the
+//! next try wait longer and longer.
+
+use std::time::{Duration, Instant};
+
+use gpui::{AnyElement, App, AsyncApp, Global, IntoElement, Task, Window, div, prelude::*, px};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable,
+    button::{Button, ButtonVariants as _},
+    v_flex,
+};
+use ring::rand::{SecureRandom as _, SystemRandom};
+use sha2::Sha256;
+
+use crate::native::components::IconName;
+use crate::native::hotkey;
+use crate::native::windows::lock::LockWindow;
+use crate::storage::settings::{AppLockSettings, SettingsStore};
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// PBKDF2 rounds, a PIN is short so guessing it from the settings file should be slow.
+#[cfg(not(test))]
+const PIN_HASH_ROUNDS: u32 = 100_000;
+#[cfg(test)]
+const PIN_HASH_ROUNDS: u32 = 1_000;
+/// Stored hashes asking for more rounds than this are refused instead of hanging the UI.
+const MAX_PIN_HASH_ROUNDS: u32 = PIN_HASH_ROUNDS * 10;
+const PIN_SALT_LEN: usize = 16;
+const PIN_HASH_SCHEME: &str = "pbkdf2-sha256";
+/// Wrong PINs in a row before the next try has to wait.
+const FREE_PIN_ATTEMPTS: u32 = 3;
+const PIN_RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_PIN_RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Default)]
+struct AppLock {
+    locked: bool,
+    failed_attempts: u32,
+    retry_at: Option<Instant>,
+}
+
+impl Global for AppLock {}
+
+pub fn is_locked(cx: &App) -> bool {
+    cx.try_global::<AppLock>().is_some_and(|x| x.locked)
+}
+
+/// Locking needs the lock turned on and a PIN to unlock with.
+pub fn is_set_up(settings: &AppLockSettings) -> bool {
+    settings.enabled && !settings.pin_hash.is_empty()
+}
+
+pub fn lock(cx: &mut App) {
+    if !is_set_up(&SettingsStore::get(cx).app_lock) {
+        tracing::info!("Lock requested, but the app lock is not set up");
+        return;
+    }
+
+    if !is_locked(cx) {
+        tracing::info!("App locked");
+        cx.default_global::<AppLock>().locked = true;
+        // Keys would still reach the focused editor behind the overlay.
+        for handle in cx.windows() {
+            let _ = handle.update(cx, |_, window, _| window.blur());
+        }
+        cx.refresh_windows();
+    }
+    if let Err(err) = LockWindow::open(cx) {
+        tracing::error!(error = ?err, "Failed to open lock window");
+    }
+}
+
+/// How long to wait before the next PIN is checked, after too many wrong ones.
+pub fn retry_after(cx: &App) -> Option<Duration> {
+    cx.try_global::<AppLock>()?
+        .retry_at?
+        .checked_duration_since(Instant::now())
+}
+
+/// Unlock when the PIN matches, resolves to whether it did. Check [`retry_after`] first.
+pub fn unlock(cx: &mut App, pin: String) -> Task<bool> {
+    let pin_hash = SettingsStore::get(cx).app_lock.pin_hash.clone();
+    let verified = cx
+        .background_executor()
+        .spawn(async move { verify_pin(&pin_hash, &pin) });
+    cx.spawn(async move |cx| {
+        let verified = verified.await;
+        cx.update(|cx| {
+            if verified {
+                tracing::info!("App unlocked");
+                cx.set_global(AppLock::default());
+                cx.refresh_windows();
+                return true;
+            }
+
+            let lock = cx.default_global::<AppLock>();
+            lock.failed_attempts += 1;
+            tracing::warn!(
+                attempts = lock.failed_attempts,
+                "Wrong PIN entered on the lock screen"
+            );
+            if let Some(doublings) = lock.failed_attempts.checked_sub(FREE_PIN_ATTEMPTS) {
+                let delay = PIN_RETRY_DELAY.saturating_mul(1 << doublings.min(16));
+                lock.retry_at = Some(Instant::now() + delay.min(MAX_PIN_RETRY_DELAY));
+            }
+            false
+        })
+        .unwrap_or(false)
+    })
+}
+
+/// `pbkdf2-sha256$<rounds>$<salt>$<hash>`, salt and hash hex encoded.
+pub fn hash_pin(pin: &str) -> anyhow::Result<String> {
+    let mut salt = [0u8; PIN_SALT_LEN];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| anyhow::anyhow!("generate random salt"))?;
+    let salt = to_hex(&salt);
+    let hash = to_hex(&pbkdf2(pin, &salt, PIN_HASH_ROUNDS));
+    Ok(format!("{PIN_HASH_SCHEME}${PIN_HASH_ROUNDS}${salt}${hash}"))
+}
+
+fn verify_pin(pin_hash: &str, pin: &str) -> bool {
+    let mut parts = pin_hash.split('$');
+    let (Some(PIN_HASH_SCHEME), Some(rounds), Some(salt), Some(expected), None) = (
+        parts.next(),
+        parts
+            .next()
+            .and_then(|x| x.parse::<u32>().ok())
+            .filter(|x| (1..=MAX_PIN_HASH_ROUNDS).contains(x)),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        tracing::warn!("Unknown PIN hash in the app lock settings");
+        return false;
+    };
+
+    let hash = to_hex(&pbkdf2(pin, salt, rounds));
+    ring::constant_time::verify_slices_are_equal(hash.as_bytes(), expected.as_bytes()).is_ok()
+}
+
+fn pbkdf2(pin: &str, salt: &str, rounds: u32) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(pin.as_bytes(), salt.as_bytes(), rounds, &mut hash);
+    hash
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Lock once there was no input anywhere on the desktop for the configured minutes.
+pub async fn run_scheduler(cx: &mut AsyncApp) {
+    loop {
+        cx.background_executor().timer(IDLE_CHECK_INTERVAL).await;
+
+        let due = cx.update(|cx| {
+            let settings = &SettingsStore::get(cx).app_lock;
+            if !is_set_up(settings) || settings.idle_minutes == 0 || is_locked(cx) {
+                return false;
+            }
+            let limit = Duration::from_secs(settings.idle_minutes as u64 * 60);
+            hotkey::idle_duration().is_some_and(|idle| idle >= limit)
+        });
+
+        match due {
+            Ok(true) => {
+                if cx.update(lock).is_err() {
+                    return;
+                }
+            }
+            Ok(false) => {}
+            Err(_) => return,
+        }
+    }
+}
+
+/// Covers the content of a window while the app is locked, on top of everything else.
+pub fn lock_overlay(window: &mut Window, cx: &App) -> AnyElement {
+    // The overlay only stops the mouse, nothing behind it may take keys, e.g. with Tab.
+    if window.focused(cx).is_some() {
+        window.blur();
+    }
+
+    v_flex()
+        .id("app-lock-overlay")
+        .absolute()
+        .top_0()
+        .left_0()
+        .size_full()
+        .occlude()
+        .gap_2()
+        .items_center()
+        .justify_center()
+        .bg(cx.theme().background)
+        .text_color(cx.theme().muted_foreground)
+        .child(Icon::new(IconName::Lock).with_size(px(32.)))
+        .child(div().text_sm().child("Locked"))
+        .child(
+            Button::new("unlock")
+                .label("Unlock…")
+                .small()
+                .primary()
+                .on_click(|_, _, cx| {
+                    if let Err(err) = LockWindow::open(cx) {
+                        tracing::error!(error = ?err, "Failed to open lock window");
+                    }
+                }),
+        )
+        .into_any_element()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashed_pin_verifies() {
+        let pin_hash = hash_pin("1234").unwrap();
+        assert!(pin_hash.starts_with(&format!("{PIN_HASH_SCHEME}${PIN_HASH_ROUNDS}$")));
+        assert!(verify_pin(&pin_hash, "1234"));
+        assert!(!verify_pin(&pin_hash, "1235"));
+        assert!(!verify_pin(&pin_hash, ""));
+
+        // Salted, the same PIN hashes differently every time.
+        assert_ne!(hash_pin("1234").unwrap(), pin_hash);
+    }
+
+    #[test]
+    fn known_hash() {
+        // The common PBKDF2-HMAC-SHA256 vector, "password" with the salt "salt" and one round.
+        let pin_hash =
+            "pbkdf2-sha256$1$salt$120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b";
+        assert!(verify_pin(pin_hash, "password"));
+        assert!(!verify_pin(pin_hash, "Password"));
+    }
+
+    #[test]
+    fn malformed_hashes_are_refused() {
+        let pin_hash = hash_pin("1234").unwrap();
+        let (_, rest) = pin_hash.split_once('$').unwrap();
+        let (_, salt_and_hash) = rest.split_once('$').unwrap();
+        let too_many = MAX_PIN_HASH_ROUNDS + 1;
+
+        for pin_hash in [
+            String::new(),
+            "1234".to_string(),
+            format!("sha1${PIN_HASH_ROUNDS}${salt_and_hash}"),
+            format!("{PIN_HASH_SCHEME}$0${salt_and_hash}"),
+            format!("{PIN_HASH_SCHEME}${too_many}${salt_and_hash}"),
+            format!("{PIN_HASH_SCHEME}$many${salt_and_hash}"),
+            format!("{pin_hash}$extra"),
+        ] {
+            assert!(!verify_pin(&pin_hash, "1234"), "{pin_hash} was accepted");
+        }
+    }
+}
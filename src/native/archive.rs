@@ -0,0 +1,108 @@
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use gpui::AsyncApp;
+
+use crate::native::windows::StickerWindowEvent;
+use crate::native::windows::sticker::StickerWindow;
+use crate::storage::ArcStickerStore;
+use crate::storage::paths::AppPaths;
+use crate::storage::settings::{ArchiveSettings, SettingsStore};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+const LOG_FILE: &str = "archive.log";
+
+/// Apply the archive rules once a day, what they moved or deleted is appended to
+/// `archive.log` in the log directory.
+pub async fn run_scheduler(
+    cx: &mut AsyncApp,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+) {
+    let mut last_date: Option<NaiveDate> = None;
+
+    loop {
+        let Ok((settings, log_path)) = cx.update(|cx| {
+            (
+                SettingsStore::get(cx).archive.clone(),
+                cx.global::<AppPaths>().log_dir().join(LOG_FILE),
+            )
+        }) else {
+            return;
+        };
+
+        let today = Local::now().date_naive();
+        if last_date != Some(today) {
+            last_date = Some(today);
+            match apply_rules(cx, &store, &settings, &log_path).await {
+                Ok(true) => {
+                    let _ = sticker_events_tx.send(StickerWindowEvent::StoreChanged);
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    tracing::error!(error = ?err, "Failed to apply archive rules");
+                }
+            }
+        }
+
+        cx.background_executor().timer(CHECK_INTERVAL).await;
+    }
+}
+
+/// Returns whether any sticker was archived or deleted.
+async fn apply_rules(
+    cx: &mut AsyncApp,
+    store: &ArcStickerStore,
+    settings: &ArchiveSettings,
+    log_path: &Path,
+) -> anyhow::Result<bool> {
+    let now = crate::utils::time::now_unix_millis();
+    let mut lines = Vec::new();
+
+    if settings.archive_closed_after_days > 0 {
+        let updated_before = now - settings.archive_closed_after_days as i64 * DAY_MILLIS;
+        for (id, title) in store.archive_stale_stickers(updated_before).await? {
+            lines.push(format!("archived #{id} \"{title}\""));
+        }
+    }
+
+    if settings.purge_trash_after_days > 0 {
+        let deleted_before = now - settings.purge_trash_after_days as i64 * DAY_MILLIS;
+        let purged = store.purge_trash(deleted_before).await?;
+        let _ = cx.update(|cx| {
+            for (id, _) in &purged {
+                StickerWindow::try_close(*id, cx);
+            }
+        });
+        for (id, title) in purged {
+            lines.push(format!("deleted #{id} \"{title}\" from the trash"));
+        }
+    }
+
+    if lines.is_empty() {
+        return Ok(false);
+    }
+
+    tracing::info!(count = lines.len(), "Archive rules applied");
+    if let Err(err) = append_log(log_path, &lines) {
+        tracing::warn!(error = ?err, path = %log_path.display(), "Failed to write archive log");
+    }
+    Ok(true)
+}
+
+fn append_log(path: &Path, lines: &[String]) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let stamp = Local::now().format("%Y-%m-%d %H:%M");
+    for line in lines {
+        writeln!(file, "{stamp} {line}")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,232 @@
+//! Scheduled snapshots of the database into the backups directory, and putting one of
+//! them back in place of the database on the next start. Attachments and pictures are
+//! copied next to the snapshots, see [`sqlite::back_up_files`].
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context as _;
+use chrono::{DateTime, Local};
+use gpui::AsyncApp;
+
+use crate::storage::ArcStickerStore;
+use crate::storage::paths::AppPaths;
+use crate::storage::settings::{AppSettings, SettingsStore};
+use crate::storage::sqlite;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Marks the snapshots taken by the schedule, the only ones deleted when there are
+/// too many. Snapshots before upgrades and restores are kept.
+const SCHEDULED_SUFFIX: &str = "-scheduled.db";
+/// The backup to use as database, next to the database until the next start.
+const PENDING_RESTORE_FILE: &str = "stickers.db.restore";
+/// Settings, attachments, images and templates which come with a restored database,
+/// copied over the data directory on the next start.
+const PENDING_FILES_DIR: &str = "restore-files";
+/// Settings among the restored files, merged into the current settings instead of copied.
+pub const SETTINGS_ENTRY: &str = "settings.json";
+
+/// A database snapshot in the backups directory.
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub modified: DateTime<Local>,
+    pub size: u64,
+}
+
+impl Backup {
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    fn is_scheduled(&self) -> bool {
+        self.file_name().ends_with(SCHEDULED_SUFFIX)
+    }
+}
+
+/// Back up the database whenever the newest scheduled backup is older than the
+/// configured interval, and keep only the configured number of them.
+pub async fn run_scheduler(cx: &mut AsyncApp, store: ArcStickerStore) {
+    loop {
+        let Ok((settings, app_paths)) = cx.update(|cx| {
+            (
+                SettingsStore::get(cx).backup.clone(),
+                cx.global::<AppPaths>().clone(),
+            )
+        }) else {
+            return;
+        };
+
+        if settings.interval_hours > 0 {
+            let interval = Duration::from_secs(settings.interval_hours as u64 * 60 * 60);
+            if let Err(err) = back_up_when_due(&store, &app_paths, interval, settings.keep).await {
+                tracing::error!(error = ?err, "Failed to run scheduled backup");
+            }
+        }
+
+        cx.background_executor().timer(CHECK_INTERVAL).await;
+    }
+}
+
+async fn back_up_when_due(
+    store: &ArcStickerStore,
+    app_paths: &AppPaths,
+    interval: Duration,
+    keep: u32,
+) -> anyhow::Result<()> {
+    let backups_dir = &app_paths.backups_dir();
+    let backups = list_backups(backups_dir)?;
+    let last = backups.iter().find(|x| x.is_scheduled());
+    let due = last.is_none_or(|last| {
+        let age = Local::now().signed_duration_since(last.modified);
+        age.to_std().is_ok_and(|age| age >= interval)
+    });
+    if !due {
+        return Ok(());
+    }
+
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    let path = backups_dir.join(format!("stickers-{stamp}{SCHEDULED_SUFFIX}"));
+    store.backup_to(path.clone()).await?;
+    let files = sqlite::back_up_files(app_paths).await?;
+    tracing::info!(backup = %path.display(), files, "Database backed up");
+
+    // Never delete the one just taken, even when keeping 0.
+    let scheduled = list_backups(backups_dir)?
+        .into_iter()
+        .filter(|x| x.is_scheduled())
+        .skip(keep.max(1) as usize);
+    for old in scheduled {
+        match std::fs::remove_file(&old.path) {
+            Ok(()) => tracing::info!(backup = %old.path.display(), "Old backup deleted"),
+            Err(err) => {
+                tracing::warn!(backup = %old.path.display(), error = %err, "Failed to delete old backup")
+            }
+        }
+    }
+
+    // Attachments and pictures only the deleted snapshots needed.
+    let mut snapshots = list_backups(backups_dir)?
+        .into_iter()
+        .map(|x| x.path)
+        .collect::<Vec<_>>();
+    let pending = app_paths.data_dir().join(PENDING_RESTORE_FILE);
+    if pending.exists() {
+        snapshots.push(pending);
+    }
+    match sqlite::prune_backed_up_files(app_paths, &snapshots).await {
+        Ok(files) => tracing::info!(files, "Backed up files pruned"),
+        Err(err) => tracing::warn!(error = ?err, "Failed to prune backed up files"),
+    }
+
+    Ok(())
+}
+
+/// Every database snapshot in the directory, newest first.
+pub fn list_backups(backups_dir: &Path) -> anyhow::Result<Vec<Backup>> {
+    let entries = match std::fs::read_dir(backups_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("read backups directory {}", backups_dir.display()));
+        }
+    };
+
+    let mut backups = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|x| x == "db"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some(Backup {
+                path: entry.path(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH).into(),
+                size: metadata.len(),
+            })
+        })
+        .collect::<Vec<_>>();
+    backups.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(backups)
+}
+
+/// Use `backup` as the database from the next start on. The current database is
+/// snapshotted first, so the restore can be undone.
+pub async fn schedule_restore(
+    store: &ArcStickerStore,
+    app_paths: &AppPaths,
+    backup: &Path,
+) -> anyhow::Result<()> {
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    let before = app_paths
+        .backups_dir()
+        .join(format!("stickers-{stamp}-before-restore.db"));
+    store.backup_to(before.clone()).await?;
+    sqlite::back_up_files(app_paths).await?;
+    tracing::info!(backup = %before.display(), "Database backed up before restore");
+
+    std::fs::copy(backup, app_paths.data_dir().join(PENDING_RESTORE_FILE))
+        .with_context(|| format!("copy backup {}", backup.display()))?;
+    tracing::info!(backup = %backup.display(), "Restore scheduled for the next start");
+    Ok(())
+}
+
+/// Where files to copy over the data directory together with the next restore go,
+/// mirroring its layout. See [`apply_pending_restore`].
+pub fn pending_files_dir(app_paths: &AppPaths) -> PathBuf {
+    app_paths.data_dir().join(PENDING_FILES_DIR)
+}
+
+/// Put a backup chosen by [`schedule_restore`] in place of the database, before the
+/// database and the settings are loaded.
+pub fn apply_pending_restore(app_paths: &AppPaths) -> anyhow::Result<()> {
+    let pending = app_paths.data_dir().join(PENDING_RESTORE_FILE);
+    if !pending.exists() {
+        return Ok(());
+    }
+
+    let pending_files = pending_files_dir(app_paths);
+    if pending_files.exists() {
+        // Hooks, tokens, the app lock and the like stay as set up on this machine.
+        let settings_path = app_paths.settings_path();
+        let pending_settings = pending_files.join(SETTINGS_ENTRY);
+        if pending_settings.exists() {
+            AppSettings::load(&settings_path)
+                .import_portable(&pending_settings)?
+                .save(&settings_path)?;
+            std::fs::remove_file(&pending_settings).context("remove restored settings")?;
+        }
+        copy_tree(&pending_files, &app_paths.data_dir())?;
+        std::fs::remove_dir_all(&pending_files).context("remove restored files")?;
+        tracing::info!("Settings and files restored from backup");
+    }
+
+    // The journal belongs to the replaced database.
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{suffix}", app_paths.db_path.display()));
+    }
+    std::fs::rename(&pending, &app_paths.db_path).context("replace database with the backup")?;
+    tracing::info!(db_path = %app_paths.db_path.display(), "Database restored from backup");
+
+    // The backup may refer to attachments and pictures collected since.
+    let files = sqlite::restore_backed_up_files(app_paths)?;
+    tracing::info!(files, "Attachments restored from backup");
+    Ok(())
+}
+
+fn copy_tree(from: &Path, to: &Path) -> anyhow::Result<()> {
+    std::fs::create_dir_all(to).with_context(|| format!("create directory {}", to.display()))?;
+    for entry in std::fs::read_dir(from).with_context(|| format!("read {}", from.display()))? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_tree(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)
+                .with_context(|| format!("restore {}", target.display()))?;
+        }
+    }
+    Ok(())
+}
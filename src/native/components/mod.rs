@@ -4,6 +4,7 @@ use gpui_component::IconNamed;
 use rust_embed::RustEmbed;
 use std::borrow::Cow;
 
+pub mod rem_size;
 pub mod stickers;
 pub mod webview;
 
@@ -41,6 +42,9 @@ pub enum IconName {
     Command,
     DocumentText,
     Bell,
+    AlarmClock,
+    Archive,
+    Undo,
     Minus,
     Minimize,
     Search,
@@ -51,7 +55,47 @@ pub enum IconName {
     ArrowDown,
     Check,
     Paint,
+    PaintBucket,
     Eraser,
+    Pin,
+    Download,
+    Image,
+    Globe,
+    Refresh,
+    Clock,
+    Printer,
+    Timer,
+    Activity,
+    Calendar,
+    CalendarCheck,
+    Folder,
+    Kanban,
+    Code,
+    Copy,
+    Hash,
+    History,
+    ChartLine,
+    ArrowRightLeft,
+    Film,
+    GitHub,
+    Send,
+    Palette,
+    Pipette,
+    Ruler,
+    HeartPulse,
+    GitBranch,
+    Rocket,
+    Music,
+    SkipBack,
+    SkipForward,
+    Calculator,
+    Network,
+    Lock,
+    Crop,
+    RotateCw,
+    Layers,
+    Move,
+    Briefcase,
 }
 
 impl IconNamed for IconName {
@@ -66,6 +110,9 @@ impl IconNamed for IconName {
             IconName::Command => "icons/command.svg".into(),
             IconName::DocumentText => "icons/document-text.svg".into(),
             IconName::Bell => "icons/bell.svg".into(),
+            IconName::AlarmClock => "icons/alarm-clock.svg".into(),
+            IconName::Archive => "icons/archive.svg".into(),
+            IconName::Undo => "icons/undo-2.svg".into(),
             IconName::Minus => "icons/minus.svg".into(),
             IconName::Minimize => "icons/minimize.svg".into(),
             IconName::Search => "icons/search.svg".into(),
@@ -76,7 +123,47 @@ impl IconNamed for IconName {
             IconName::ArrowDown => "icons/arrow-down.svg".into(),
             IconName::Check => "icons/check.svg".into(),
             IconName::Paint => "icons/paint.svg".into(),
+            IconName::PaintBucket => "icons/paint-bucket.svg".into(),
             IconName::Eraser => "icons/eraser.svg".into(),
+            IconName::Pin => "icons/pin.svg".into(),
+            IconName::Download => "icons/download.svg".into(),
+            IconName::Image => "icons/image.svg".into(),
+            IconName::Globe => "icons/globe.svg".into(),
+            IconName::Refresh => "icons/refresh.svg".into(),
+            IconName::Clock => "icons/clock.svg".into(),
+            IconName::Printer => "icons/printer.svg".into(),
+            IconName::Timer => "icons/timer.svg".into(),
+            IconName::Activity => "icons/activity.svg".into(),
+            IconName::Calendar => "icons/calendar.svg".into(),
+            IconName::CalendarCheck => "icons/calendar-check.svg".into(),
+            IconName::Folder => "icons/folder.svg".into(),
+            IconName::Kanban => "icons/kanban.svg".into(),
+            IconName::Code => "icons/code.svg".into(),
+            IconName::Copy => "icons/copy.svg".into(),
+            IconName::Hash => "icons/hash.svg".into(),
+            IconName::History => "icons/history.svg".into(),
+            IconName::ChartLine => "icons/chart-line.svg".into(),
+            IconName::ArrowRightLeft => "icons/arrow-right-left.svg".into(),
+            IconName::Film => "icons/film.svg".into(),
+            IconName::GitHub => "icons/github.svg".into(),
+            IconName::Send => "icons/send.svg".into(),
+            IconName::Palette => "icons/palette.svg".into(),
+            IconName::Pipette => "icons/pipette.svg".into(),
+            IconName::Ruler => "icons/ruler.svg".into(),
+            IconName::HeartPulse => "icons/heart-pulse.svg".into(),
+            IconName::GitBranch => "icons/git-branch.svg".into(),
+            IconName::Rocket => "icons/rocket.svg".into(),
+            IconName::Music => "icons/music.svg".into(),
+            IconName::SkipBack => "icons/skip-back.svg".into(),
+            IconName::SkipForward => "icons/skip-forward.svg".into(),
+            IconName::Calculator => "icons/calculator.svg".into(),
+            IconName::Network => "icons/network.svg".into(),
+            IconName::Lock => "icons/lock.svg".into(),
+            IconName::Crop => "icons/crop.svg".into(),
+            IconName::RotateCw => "icons/rotate-cw.svg".into(),
+            IconName::Layers => "icons/layers.svg".into(),
+            IconName::Move => "icons/move.svg".into(),
+            IconName::Briefcase => "icons/briefcase.svg".into(),
         }
     }
 }
@@ -0,0 +1,84 @@
+//! An element which lays out and paints its child with its own rem size.
+//!
+//! Sticker views set the rem size of the window when they render, which happens after
+//! the sticker window rendered, so a rem size chosen for one sticker can not be set on
+//! the window. It is pushed while the child is drawn instead, which wins over theirs.
+
+use gpui::{
+    AnyElement, App, Bounds, Element, ElementId, GlobalElementId, InspectorElementId, IntoElement,
+    LayoutId, Pixels, Window,
+};
+
+pub struct WithRemSize {
+    rem_size: Option<Pixels>,
+    child: AnyElement,
+}
+
+/// Draw `child` with `rem_size`, or with the rem size of the window when `None`.
+pub fn with_rem_size(rem_size: Option<Pixels>, child: impl IntoElement) -> WithRemSize {
+    WithRemSize {
+        rem_size,
+        child: child.into_any_element(),
+    }
+}
+
+impl IntoElement for WithRemSize {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for WithRemSize {
+    type RequestLayoutState = ();
+    type PrepaintState = ();
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static std::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let layout_id = window.with_rem_size(self.rem_size, |window| {
+            self.child.request_layout(window, cx)
+        });
+        (layout_id, ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self::PrepaintState {
+        window.with_rem_size(self.rem_size, |window| {
+            self.child.prepaint(window, cx);
+        });
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        _prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        window.with_rem_size(self.rem_size, |window| self.child.paint(window, cx));
+    }
+}
@@ -0,0 +1,520 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use async_compat::CompatExt;
+use chrono::{Local, TimeZone, Utc};
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputState},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, dialogs, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+use crate::utils::ics;
+
+/// Redraw so finished events drop off the list.
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct AgendaContent {
+    /// Path of a local `.ics` file, or an `http(s)://` / `webcal://` URL.
+    source: String,
+    days: u32,
+    refresh_mins: u32,
+}
+
+impl Default for AgendaContent {
+    fn default() -> Self {
+        Self {
+            source: String::new(),
+            days: 3,
+            refresh_mins: 15,
+        }
+    }
+}
+
+pub struct AgendaSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: AgendaContent,
+
+    /// The last loaded calendar.
+    calendar: Option<String>,
+    loaded_at: Option<i64>,
+    loading: bool,
+    ticking: bool,
+
+    source_input: Entity<InputState>,
+    days_input: Entity<InputState>,
+    refresh_input: Entity<InputState>,
+    editing: bool,
+
+    error: Option<String>,
+}
+
+impl AgendaSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<AgendaContent>(content).unwrap_or_default();
+
+        let source_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.source.clone())
+                .placeholder("~/calendar.ics or https://…/basic.ics")
+        });
+        let days_input =
+            cx.new(|cx| InputState::new(window, cx).default_value(content.days.to_string()));
+        let refresh_input = cx
+            .new(|cx| InputState::new(window, cx).default_value(content.refresh_mins.to_string()));
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            editing: content.source.trim().is_empty(),
+            content,
+            calendar: None,
+            loaded_at: None,
+            loading: false,
+            ticking: false,
+            source_input,
+            days_input,
+            refresh_input,
+            error: None,
+        };
+        this.reload(cx);
+        this
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize agenda sticker: {err}"));
+                return false;
+            }
+        };
+        let title = source_label(&self.content.source);
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save agenda sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save agenda sticker: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let source = self.content.source.trim().to_string();
+        if source.is_empty() || self.loading {
+            return;
+        }
+
+        self.loading = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let loaded = cx
+                .background_executor()
+                .spawn(async move { load_calendar(&source).compat().await })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                this.loading = false;
+                this.loaded_at = Some(crate::utils::time::now_unix_millis());
+                match loaded {
+                    Ok(calendar) => {
+                        this.calendar = Some(calendar);
+                        this.error = None;
+                    }
+                    Err(err) => {
+                        tracing::warn!(id = this.id, error = ?err, "Failed to load calendar");
+                        this.error = Some(format!("Failed to load calendar: {err:#}"));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Redraw every minute and reload once the refresh interval passed.
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(TICK_INTERVAL).await;
+            let _ = this.update(cx, |this, cx| {
+                this.ticking = false;
+                let refresh_ms = this.content.refresh_mins.max(1) as i64 * 60 * 1000;
+                if this
+                    .loaded_at
+                    .is_some_and(|at| crate::utils::time::now_unix_millis() - at >= refresh_ms)
+                {
+                    this.reload(cx);
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn apply_form(&mut self, cx: &mut Context<Self>) {
+        let source = self.source_input.read(cx).value().trim().to_string();
+        let days = self.days_input.read(cx).value().trim().parse::<u32>();
+        let refresh_mins = self.refresh_input.read(cx).value().trim().parse::<u32>();
+
+        if source.is_empty() {
+            self.error = Some("Please enter a calendar file or URL".to_string());
+            cx.notify();
+            return;
+        }
+        let (Ok(days @ 1..=31), Ok(refresh_mins @ 1..)) = (days, refresh_mins) else {
+            self.error =
+                Some("Days must be between 1 and 31, refresh at least 1 minute".to_string());
+            cx.notify();
+            return;
+        };
+
+        self.content = AgendaContent {
+            source,
+            days,
+            refresh_mins,
+        };
+        self.editing = false;
+        self.error = None;
+        self.calendar = None;
+        self.save_config(cx);
+        self.reload(cx);
+        cx.notify();
+    }
+
+    fn pick_file(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let entity = cx.entity();
+        window
+            .spawn(cx, async move |window| {
+                let Some(path) =
+                    dialogs::pick_file("Choose a calendar", &[("Calendar", &["ics"])]).await
+                else {
+                    return;
+                };
+
+                let _ = window.update_window_entity(&entity, |this, window, cx| {
+                    let path = path.to_string_lossy().to_string();
+                    this.source_input
+                        .update(cx, |input, cx| input.set_value(path, window, cx));
+                });
+            })
+            .detach();
+    }
+
+    fn agenda_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let now = Utc::now();
+        let today = Local::now().date_naive();
+        let end_day = today + chrono::Duration::days(self.content.days as i64);
+        let to = Local
+            .from_local_datetime(&end_day.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .earliest()
+            .map(|x| x.with_timezone(&Utc))
+            .unwrap_or(now);
+
+        let occurrences = self
+            .calendar
+            .as_deref()
+            .map(|calendar| ics::occurrences(calendar, now, to))
+            .unwrap_or_default();
+
+        let mut list = v_flex().gap_1();
+        let mut last_day = None;
+        for occurrence in &occurrences {
+            // Events which started before today are listed under today.
+            let day = occurrence.start.date_naive().max(today);
+            if last_day != Some(day) {
+                last_day = Some(day);
+                let label = match (day - today).num_days() {
+                    0 => "Today".to_string(),
+                    1 => "Tomorrow".to_string(),
+                    _ => day.format("%A %-d %b").to_string(),
+                };
+                list = list.child(
+                    div()
+                        .pt_1()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(label),
+                );
+            }
+
+            let time = if occurrence.all_day {
+                "All day".to_string()
+            } else {
+                format!(
+                    "{}–{}",
+                    occurrence.start.format("%H:%M"),
+                    occurrence.end.format("%H:%M")
+                )
+            };
+            list = list.child(
+                h_flex()
+                    .gap_2()
+                    .items_start()
+                    .child(
+                        div()
+                            .flex_shrink_0()
+                            .w(px(80.0))
+                            .text_xs()
+                            .font_family(cx.theme().mono_font_family.clone())
+                            .child(time),
+                    )
+                    .child(
+                        v_flex()
+                            .overflow_hidden()
+                            .child(div().text_sm().child(occurrence.summary.clone()))
+                            .when_some(occurrence.location.clone(), |view, location| {
+                                view.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(location),
+                                )
+                            }),
+                    ),
+            );
+        }
+
+        let empty = match (&self.calendar, self.loading) {
+            (None, true) => Some("Loading…"),
+            (Some(_), _) if occurrences.is_empty() => Some("Nothing planned"),
+            _ => None,
+        };
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .child(
+                div().flex_1().overflow_hidden().child(
+                    v_flex()
+                        .overflow_y_scrollbar()
+                        .window_drag_area()
+                        .when_some(empty, |view, empty| {
+                            view.child(
+                                div()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(empty),
+                            )
+                        })
+                        .child(list),
+                ),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("edit")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Change calendar")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.editing = true;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Button::new("refresh")
+                                .icon(IconName::Refresh)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Reload now")
+                                .on_click(cx.listener(|this, _, _, cx| this.reload(cx))),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field().label("Calendar (.ics file or URL)").child(
+                    h_flex()
+                        .w_full()
+                        .gap_1()
+                        .child(div().flex_1().child(Input::new(&self.source_input).small()))
+                        .child(
+                            Button::new("pick_file")
+                                .icon(IconName::Folder)
+                                .small()
+                                .tooltip("Choose a calendar file")
+                                .on_click(
+                                    cx.listener(|this, _, window, cx| this.pick_file(window, cx)),
+                                ),
+                        ),
+                ),
+            )
+            .child(
+                field()
+                    .label("Days to show")
+                    .child(Input::new(&self.days_input).small()),
+            )
+            .child(
+                field()
+                    .label("Refresh every (minutes)")
+                    .child(Input::new(&self.refresh_input).small()),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.apply_form(cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for AgendaSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 120)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(300, 360)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        let loaded_at = self.loaded_at?;
+        Some(format!(
+            "refresh every {} min, last loaded {}",
+            self.content.refresh_mins,
+            crate::utils::time::format_unix_millis(loaded_at)
+        ))
+    }
+}
+
+impl Render for AgendaSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            self.spawn_for_tick(cx);
+            body = body.child(self.agenda_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("agenda-error", msg.as_str()).small())
+        })
+    }
+}
+
+async fn load_calendar(source: &str) -> anyhow::Result<String> {
+    let url = match source.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{rest}"),
+        None => source.to_string(),
+    };
+
+    if crate::utils::url::is_url(&url) {
+        let response = reqwest::get(&url)
+            .await
+            .and_then(|x| x.error_for_status())
+            .with_context(|| format!("download {url}"))?;
+        return response.text().await.context("read calendar response");
+    }
+
+    let path = match source.strip_prefix("~/") {
+        Some(rest) => directories::UserDirs::new()
+            .map(|dirs| dirs.home_dir().join(rest))
+            .unwrap_or_else(|| source.into()),
+        None => source.into(),
+    };
+    std::fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))
+}
+
+/// "https://example.com/team.ics" -> "team", also for file paths.
+fn source_label(source: &str) -> String {
+    let name = source
+        .trim_end_matches('/')
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(source);
+    let name = name.split('?').next().unwrap_or(name);
+    let name = name.strip_suffix(".ics").unwrap_or(name);
+    if name.is_empty() {
+        "Agenda".to_string()
+    } else {
+        format!("Agenda · {name}")
+    }
+}
@@ -0,0 +1,542 @@
+use anyhow::Context as _;
+use gpui::{
+    AnyElement, ClipboardEntry, Context, ExternalPaths, FocusHandle, ImageFormat, KeyDownEvent,
+    ObjectFit, Render, RenderImage, Rgba, Window, div, img, prelude::*, transparent_white,
+};
+use gpui_component::{ActiveTheme, Sizable, alert::Alert, button::Button, h_flex, v_flex};
+use image::AnimationDecoder;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::image::{
+    add_picture, import_picture_file, picture_format, read_picture, remove_picture,
+};
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::components::IconName;
+use crate::native::decorations::WindowDragArea;
+use crate::native::dialogs;
+use crate::native::windows::StickerWindowEvent;
+use crate::storage::ArcStickerStore;
+use crate::storage::paths::AppPaths;
+
+pub const ANIMATION_EXTENSIONS: [&str; 2] = ["gif", "webp"];
+
+/// Longer animations are cut, every frame is kept decoded in memory.
+const MAX_FRAMES: usize = 1000;
+/// Browsers show frames with a (nearly) zero delay for 100ms, so do we.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct AnimationContent {
+    /// SHA-256 of the animation, an attachment of the sticker.
+    attachment: Option<String>,
+    /// File name inside the images directory of older versions, moved into an
+    /// attachment once the sticker is opened.
+    file: Option<String>,
+    paused: bool,
+}
+
+struct AnimationFrame {
+    image: Arc<RenderImage>,
+    delay: Duration,
+}
+
+pub struct AnimationSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    images_dir: PathBuf,
+    content: AnimationContent,
+
+    frames: Vec<AnimationFrame>,
+    current: usize,
+    decoding: bool,
+    ticking: bool,
+
+    focus_handle: FocusHandle,
+    error: Option<String>,
+}
+
+impl AnimationSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<AnimationContent>(content).unwrap_or_default();
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            images_dir: cx.global::<AppPaths>().images_dir(),
+            content,
+            frames: Vec::new(),
+            current: 0,
+            decoding: false,
+            ticking: false,
+            focus_handle: cx.focus_handle(),
+            error: None,
+        };
+        this.decode(cx);
+        this
+    }
+
+    fn set_error(&mut self, message: impl Into<String>, cx: &mut Context<Self>) {
+        self.error = Some(message.into());
+        cx.notify();
+    }
+
+    /// Read the animation and decode all frames on a background thread, the old frames
+    /// show until it is done. A file of older versions is moved into an attachment.
+    fn decode(&mut self, cx: &mut Context<Self>) {
+        let attachment = self.content.attachment.clone();
+        let file = self.content.file.clone();
+        if attachment.is_none() && file.is_none() {
+            return;
+        }
+
+        let id = self.id;
+        let store = self.store.clone();
+        let images_dir = self.images_dir.clone();
+        self.decoding = true;
+        cx.notify();
+
+        cx.spawn(async move |entity, cx| {
+            let picture = match (&attachment, &file) {
+                (Some(hash), _) => read_picture(&store, id, hash).await.map(|x| (None, x)),
+                (None, Some(file)) => import_picture_file(&store, id, &images_dir.join(file)).await,
+                (None, None) => return,
+            };
+            let (imported, frames) = match picture {
+                Ok((imported, picture)) => {
+                    let frames = cx
+                        .background_executor()
+                        .spawn(async move { decode_frames(&picture) })
+                        .await;
+                    (imported, frames)
+                }
+                Err(err) => (None, Err(err)),
+            };
+
+            let _ = entity.update(cx, |this, cx| {
+                // Another file was dropped in the meantime.
+                if this.content.attachment != attachment || this.content.file != file {
+                    return;
+                }
+
+                if let Some(hash) = imported {
+                    this.content.attachment = Some(hash);
+                    this.content.file = None;
+                    this.save_state(None, cx);
+                }
+                this.decoding = false;
+                match frames {
+                    Ok(frames) => {
+                        this.frames = frames;
+                        this.current = 0;
+                        this.error = None;
+                    }
+                    Err(err) => {
+                        tracing::warn!(id, error = ?err, "Failed to decode animation");
+                        this.error = Some(format!("Failed to decode animation: {err:#}"));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn paste(&mut self, cx: &mut Context<Self>) {
+        let Some(item) = cx.read_from_clipboard() else {
+            return;
+        };
+
+        for entry in item.entries() {
+            match entry {
+                ClipboardEntry::Image(image) => {
+                    let extension = match image.format {
+                        ImageFormat::Gif => "gif",
+                        ImageFormat::Webp => "webp",
+                        _ => continue,
+                    };
+                    let title = format!(
+                        "Animation {}",
+                        crate::utils::time::format_unix_millis(
+                            crate::utils::time::now_unix_millis()
+                        )
+                    );
+                    self.set_animation(image.bytes.clone(), extension, title, cx);
+                    return;
+                }
+                ClipboardEntry::String(text) => {
+                    // Copying a file in a file manager puts its path on the clipboard.
+                    let path = PathBuf::from(text.text().trim());
+                    if is_supported_animation(&path) && path.is_file() {
+                        self.load_file(&path, cx);
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.set_error("The clipboard does not contain a GIF or WebP image", cx);
+    }
+
+    fn open_file(&mut self, cx: &mut Context<Self>) {
+        cx.spawn(async move |entity, cx| {
+            let Some(path) = dialogs::pick_file(
+                "Choose an animation",
+                &[("Animations", &ANIMATION_EXTENSIONS)],
+            )
+            .await
+            else {
+                return;
+            };
+
+            let _ = entity.update(cx, |this, cx| this.load_file(&path, cx));
+        })
+        .detach();
+    }
+
+    fn drop_paths(&mut self, paths: &ExternalPaths, cx: &mut Context<Self>) {
+        match paths
+            .paths()
+            .iter()
+            .find(|path| is_supported_animation(path))
+        {
+            Some(path) => self.load_file(&path.clone(), cx),
+            None => self.set_error("Only GIF and WebP images are supported", cx),
+        }
+    }
+
+    fn load_file(&mut self, path: &Path, cx: &mut Context<Self>) {
+        let extension = path
+            .extension()
+            .and_then(|x| x.to_str())
+            .unwrap_or("gif")
+            .to_ascii_lowercase();
+        let title = path
+            .file_stem()
+            .and_then(|x| x.to_str())
+            .unwrap_or("Animation")
+            .to_string();
+
+        match std::fs::read(path) {
+            Ok(bytes) => self.set_animation(bytes, &extension, title, cx),
+            Err(err) => self.set_error(format!("Failed to read {}: {err}", path.display()), cx),
+        }
+    }
+
+    fn set_animation(
+        &mut self,
+        bytes: Vec<u8>,
+        extension: &str,
+        title: String,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(format) = picture_format(extension) else {
+            self.set_error(format!("Unsupported animation type: {extension}"), cx);
+            return;
+        };
+        let picture = gpui::Image::from_bytes(format, bytes);
+
+        let id = self.id;
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let hash = add_picture(&store, id, &picture).await;
+            let _ = entity.update(cx, |this, cx| {
+                let hash = match hash {
+                    Ok(hash) => hash,
+                    Err(err) => {
+                        this.set_error(format!("Failed to save animation: {err:#}"), cx);
+                        return;
+                    }
+                };
+
+                // Copies of the sticker have their own attachment of the replaced animation.
+                if let Some(replaced) = this.content.attachment.take().filter(|x| *x != hash) {
+                    let store = this.store.clone();
+                    cx.spawn(async move |_, _| {
+                        if let Err(err) = remove_picture(&store, id, &replaced).await {
+                            tracing::warn!(id, error = ?err, "Failed to remove replaced animation");
+                        }
+                    })
+                    .detach();
+                }
+
+                this.content.attachment = Some(hash);
+                this.content.file = None;
+                this.error = None;
+                this.save_state(Some(title), cx);
+                this.decode(cx);
+            });
+        })
+        .detach();
+    }
+
+    fn toggle_playing(&mut self, cx: &mut Context<Self>) {
+        self.content.paused = !self.content.paused;
+        self.save_state(None, cx);
+        cx.notify();
+    }
+
+    /// Show the next frame once the delay of the current one passed, same approach as the
+    /// clock ticks: every render schedules the next one while playing.
+    fn spawn_for_frame(&mut self, cx: &mut Context<Self>) {
+        if self.ticking || self.content.paused || self.frames.len() < 2 {
+            return;
+        }
+        let Some(frame) = self.frames.get(self.current) else {
+            return;
+        };
+        self.ticking = true;
+
+        let delay = frame.delay;
+        cx.spawn(async move |e, cx| {
+            cx.background_executor().timer(delay).await;
+            let _ = e.update(cx, |this, cx| {
+                this.ticking = false;
+                if !this.content.paused && !this.frames.is_empty() {
+                    this.current = (this.current + 1) % this.frames.len();
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn save_state(&mut self, title: Option<String>, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to save animation sticker: {err}"));
+                return false;
+            }
+        };
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Some(title) = title {
+                if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.set_error(format!("Failed to save animation title: {err:#}"), cx);
+                    });
+                    return;
+                }
+
+                if let Err(err) =
+                    sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+                {
+                    tracing::warn!(id, error = %err, "Failed to send title changed event for animation sticker");
+                }
+            }
+
+            if let Err(err) = save_content(&store, &sticker_events_tx, id, json, String::new()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to save animation sticker: {err:#}"), cx);
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn empty_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_flex()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .gap_2()
+            .p_2()
+            .text_sm()
+            .child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Drop or paste (Ctrl+V) a GIF/WebP animation"),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("paste")
+                            .label("Paste")
+                            .small()
+                            .on_click(cx.listener(|this, _, _, cx| this.paste(cx))),
+                    )
+                    .child(
+                        Button::new("open")
+                            .label("Open…")
+                            .small()
+                            .on_click(cx.listener(|this, _, _, cx| this.open_file(cx))),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for AnimationSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_state(None, cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(100, 100)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(320, 240)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for AnimationSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(cx.theme().font_size);
+        self.spawn_for_frame(cx);
+
+        let body = v_flex()
+            .relative()
+            .size_full()
+            .p_1()
+            .bg(Rgba {
+                a: 0.85,
+                ..self.color.bg()
+            })
+            .track_focus(&self.focus_handle)
+            .on_mouse_down(
+                gpui::MouseButton::Left,
+                cx.listener(|this, _, window, _| window.focus(&this.focus_handle)),
+            )
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _, cx| {
+                let modifiers = &event.keystroke.modifiers;
+                if (modifiers.control || modifiers.platform)
+                    && event.keystroke.key.eq_ignore_ascii_case("v")
+                {
+                    this.paste(cx);
+                } else if event.keystroke.key == "space" && !this.frames.is_empty() {
+                    this.toggle_playing(cx);
+                }
+            }))
+            .on_drop(cx.listener(|this, paths: &ExternalPaths, _, cx| {
+                this.drop_paths(paths, cx);
+            }));
+
+        let body = match self.frames.get(self.current) {
+            Some(frame) => body.child(
+                img(frame.image.clone())
+                    .size_full()
+                    .object_fit(ObjectFit::Contain)
+                    .window_drag_area(),
+            ),
+            None if self.decoding => body.child(
+                div()
+                    .size_full()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Decoding…"),
+            ),
+            None => body.child(self.empty_view(cx)),
+        };
+
+        let body = body.when(
+            window.is_window_hovered() && self.frames.len() > 1,
+            |view| {
+                let (icon, tooltip) = match self.content.paused {
+                    true => (IconName::Play, "Play (Space)"),
+                    false => (IconName::Pause, "Pause (Space)"),
+                };
+                view.child(
+                    div().absolute().top_1().right_1().child(
+                        Button::new("toggle-playing")
+                            .icon(icon)
+                            .bg(transparent_white())
+                            .border_0()
+                            .xsmall()
+                            .tooltip(tooltip)
+                            .on_click(cx.listener(|this, _, _, cx| this.toggle_playing(cx))),
+                    ),
+                )
+            },
+        );
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(
+                div()
+                    .absolute()
+                    .bottom_0()
+                    .left_0()
+                    .right_0()
+                    .child(Alert::error("animation-error", msg.as_str()).small()),
+            )
+        })
+    }
+}
+
+/// All frames of a GIF or WebP animation, composed to full images and converted to the
+/// BGRA pixels GPUI draws.
+fn decode_frames(picture: &gpui::Image) -> anyhow::Result<Vec<AnimationFrame>> {
+    let reader = std::io::Cursor::new(&picture.bytes);
+    let frames = match matches!(picture.format, ImageFormat::Webp) {
+        true => image::codecs::webp::WebPDecoder::new(reader)
+            .context("read WebP header")?
+            .into_frames(),
+        false => image::codecs::gif::GifDecoder::new(reader)
+            .context("read GIF header")?
+            .into_frames(),
+    };
+
+    let mut result = Vec::new();
+    for frame in frames.take(MAX_FRAMES) {
+        let frame = frame.context("decode frame")?;
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let delay = Duration::from_millis(u64::from(numer) / u64::from(denom.max(1)));
+        let delay = match delay < MIN_FRAME_DELAY {
+            true => DEFAULT_FRAME_DELAY,
+            false => delay,
+        };
+
+        let mut buffer = frame.into_buffer();
+        for pixel in buffer.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+        result.push(AnimationFrame {
+            image: Arc::new(RenderImage::new(vec![image::Frame::new(buffer)])),
+            delay,
+        });
+    }
+
+    anyhow::ensure!(!result.is_empty(), "the file has no frames");
+    Ok(result)
+}
+
+pub fn is_supported_animation(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .map(|x| ANIMATION_EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
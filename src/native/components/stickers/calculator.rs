@@ -0,0 +1,353 @@
+use std::collections::BTreeMap;
+
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::Button,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::top_most::set_window_top_most;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+use crate::utils::calc::{self, ANSWER};
+
+/// Lines kept in the history, the oldest go first.
+const HISTORY_LEN: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct CalculatorContent {
+    /// Oldest first.
+    history: Vec<HistoryEntry>,
+    /// Assigned with `name = expression`, plus `ans` for the last result.
+    variables: BTreeMap<String, f64>,
+    /// Keep the calculator above other windows.
+    pinned: bool,
+}
+
+impl Default for CalculatorContent {
+    fn default() -> Self {
+        Self {
+            history: Vec::new(),
+            variables: BTreeMap::new(),
+            pinned: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HistoryEntry {
+    input: String,
+    result: String,
+}
+
+pub struct CalculatorSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: CalculatorContent,
+
+    input: Entity<InputState>,
+    /// Result of the line being typed, shown before it is entered.
+    preview: Option<String>,
+
+    error: Option<String>,
+}
+
+impl CalculatorSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<CalculatorContent>(content).unwrap_or_default();
+
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("2 * (3 + 4), x = 5"));
+        cx.subscribe_in(
+            &input,
+            window,
+            |this, _, event: &InputEvent, window, cx| match event {
+                InputEvent::Change => this.update_preview(cx),
+                InputEvent::PressEnter { .. } => this.enter(window, cx),
+                _ => {}
+            },
+        )
+        .detach();
+
+        if content.pinned {
+            set_window_top_most(window, true);
+        }
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            input,
+            preview: None,
+            error: None,
+        }
+    }
+
+    /// "12 * 3 = 36", the last calculation so it can be found in the main window.
+    fn title(&self) -> String {
+        match self.content.history.last() {
+            Some(entry) => format!("{} = {}", entry.input, entry.result),
+            None => "Calculator".to_string(),
+        }
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize calculator sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save calculator sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save calculator sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn update_preview(&mut self, cx: &mut Context<Self>) {
+        let line = self.input.read(cx).value().trim().to_string();
+        self.preview = calc::evaluate(&line, &self.content.variables)
+            .ok()
+            .map(|x| calc::format_number(x.value));
+        self.error = None;
+        cx.notify();
+    }
+
+    fn enter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let line = self.input.read(cx).value().trim().to_string();
+        if line.is_empty() {
+            return;
+        }
+
+        let evaluation = match calc::evaluate(&line, &self.content.variables) {
+            Ok(evaluation) => evaluation,
+            Err(err) => {
+                self.error = Some(format!("{err:#}"));
+                cx.notify();
+                return;
+            }
+        };
+
+        let result = calc::format_number(evaluation.value);
+        if let Some(name) = evaluation.name {
+            self.content.variables.insert(name, evaluation.value);
+        }
+        self.content
+            .variables
+            .insert(ANSWER.to_string(), evaluation.value);
+        self.content.history.push(HistoryEntry {
+            input: line,
+            result,
+        });
+        if self.content.history.len() > HISTORY_LEN {
+            let overflow = self.content.history.len() - HISTORY_LEN;
+            self.content.history.drain(..overflow);
+        }
+
+        self.input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        self.preview = None;
+        self.error = None;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn reuse(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.content.history.get(ix) else {
+            return;
+        };
+        let line = entry.input.clone();
+        self.input
+            .update(cx, |input, cx| input.set_value(line, window, cx));
+        self.update_preview(cx);
+    }
+
+    fn clear(&mut self, cx: &mut Context<Self>) {
+        self.content.history.clear();
+        self.content.variables.clear();
+        self.preview = None;
+        self.error = None;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn toggle_pinned(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.content.pinned = !self.content.pinned;
+        set_window_top_most(window, self.content.pinned);
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn history_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        let muted = cx.theme().muted_foreground;
+        let mono = cx.theme().mono_font_family.clone();
+
+        let entries = self
+            .content
+            .history
+            .iter()
+            .enumerate()
+            .map(|(ix, entry)| {
+                h_flex()
+                    .id(("history", ix))
+                    .gap_2()
+                    .justify_between()
+                    .px_1()
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .hover(|style| style.bg(cx.theme().accent))
+                    .on_click(cx.listener(move |this, _, window, cx| this.reuse(ix, window, cx)))
+                    .child(
+                        div()
+                            .flex_1()
+                            .truncate()
+                            .text_color(muted)
+                            .child(entry.input.clone()),
+                    )
+                    .child(div().child(format!("= {}", entry.result)))
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .text_sm()
+            .font_family(mono)
+            .overflow_y_scrollbar()
+            .children(entries)
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for CalculatorSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 140)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(260, 280)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for CalculatorSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let muted = cx.theme().muted_foreground;
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .bg(Rgba {
+                a: 0.85,
+                ..self.color.bg()
+            })
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .window_drag_area()
+                    .child(self.history_view(cx)),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(div().flex_1().child(Input::new(&self.input).small()))
+                    .when(window.is_window_hovered(), |view| {
+                        view.child(
+                            Button::new("pin")
+                                .icon(IconName::Pin)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .opacity(if self.content.pinned { 1.0 } else { 0.4 })
+                                .tooltip("Keep on top")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.toggle_pinned(window, cx)
+                                })),
+                        )
+                        .child(
+                            Button::new("clear")
+                                .icon(IconName::Eraser)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Clear history and variables")
+                                .on_click(cx.listener(|this, _, _, cx| this.clear(cx))),
+                        )
+                    }),
+            )
+            .when_some(self.preview.as_ref(), |view, preview| {
+                view.child(
+                    div()
+                        .text_xs()
+                        .text_color(muted)
+                        .child(format!("= {preview}")),
+                )
+            })
+            .when_some(self.error.as_ref(), |view, msg| {
+                view.child(Alert::error("calculator-error", msg.as_str()).small())
+            })
+    }
+}
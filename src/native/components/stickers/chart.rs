@@ -0,0 +1,596 @@
+use std::sync::Arc;
+
+use gpui::{
+    AnyElement, AppContext, Bounds, Context, Entity, PathBuilder, Pixels, Render, Rgba, Window,
+    canvas, div, fill, point, prelude::*, px, rgb, size, transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputState},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::SettingsStore;
+use crate::utils::dataset::{self, Dataset};
+
+const SERIES_COLORS: [u32; 6] = [0x2d9cdb, 0xf2994a, 0x27ae60, 0xeb5757, 0x9b51e0, 0xf2c94c];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ChartKind {
+    #[default]
+    Line,
+    Bar,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ChartContent {
+    /// Pasted CSV or JSON, used when there is no command.
+    data: String,
+    /// Prints CSV or JSON, rerun with the refresh button.
+    command: String,
+    kind: ChartKind,
+    /// Shown series, all of them when empty.
+    series: Vec<String>,
+}
+
+pub struct ChartSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: ChartContent,
+    dataset: Arc<Dataset>,
+    loading: bool,
+
+    data_input: Entity<InputState>,
+    command_input: Entity<InputState>,
+    editing: bool,
+
+    error: Option<String>,
+}
+
+impl ChartSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<ChartContent>(content).unwrap_or_default();
+
+        let data_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(4, 10)
+                .default_value(content.data.clone())
+                .placeholder("day,cpu,mem\nMon,12,40\nTue,30,42")
+        });
+        let command_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.command.clone())
+                .placeholder("Optional, a command printing CSV or JSON")
+        });
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            editing: content.data.trim().is_empty() && content.command.trim().is_empty(),
+            content,
+            dataset: Arc::new(Dataset::default()),
+            loading: false,
+            data_input,
+            command_input,
+            error: None,
+        };
+        this.load(cx);
+        this
+    }
+
+    fn is_shown(&self, name: &str) -> bool {
+        self.content.series.is_empty() || self.content.series.iter().any(|x| x == name)
+    }
+
+    fn shown_series(&self) -> Vec<String> {
+        self.dataset
+            .series
+            .iter()
+            .filter(|s| self.is_shown(&s.name))
+            .map(|s| s.name.clone())
+            .collect()
+    }
+
+    fn title(&self) -> String {
+        let series = self.shown_series();
+        if series.is_empty() {
+            "Chart".to_string()
+        } else {
+            format!("Chart · {}", series.join(", "))
+        }
+    }
+
+    fn set_data(&mut self, text: &str) {
+        match dataset::parse(text) {
+            Ok(dataset) => {
+                self.dataset = Arc::new(dataset);
+                self.error = None;
+            }
+            Err(err) => {
+                self.dataset = Arc::new(Dataset::default());
+                self.error = Some(format!("Failed to read chart data: {err:#}"));
+            }
+        }
+    }
+
+    /// Read the pasted data, or run the command in the background.
+    fn load(&mut self, cx: &mut Context<Self>) {
+        let command = self.content.command.trim().to_string();
+        if command.is_empty() {
+            let data = self.content.data.clone();
+            self.set_data(&data);
+            cx.notify();
+            return;
+        }
+
+        if SettingsStore::get(cx).safe_mode {
+            self.error = Some("Command execution is disabled by safe mode".to_string());
+            cx.notify();
+            return;
+        }
+        if self.loading {
+            return;
+        }
+        self.loading = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let output = cx
+                .background_executor()
+                .spawn(async move { run_command(&command) })
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                this.loading = false;
+                match output {
+                    Ok(output) => this.set_data(&output),
+                    Err(err) => this.error = Some(format!("Failed to run command: {err:#}")),
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize chart sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save chart sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save chart sticker: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn apply_form(&mut self, cx: &mut Context<Self>) {
+        self.content.data = self.data_input.read(cx).value().to_string();
+        self.content.command = self.command_input.read(cx).value().trim().to_string();
+        if self.content.data.trim().is_empty() && self.content.command.is_empty() {
+            self.error = Some("Please paste some data or enter a command".to_string());
+            cx.notify();
+            return;
+        }
+
+        self.editing = false;
+        self.load(cx);
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn toggle_series(&mut self, name: &str, cx: &mut Context<Self>) {
+        let mut shown = self.shown_series();
+        match shown.iter().position(|x| x == name) {
+            Some(index) => {
+                shown.remove(index);
+            }
+            None => shown.push(name.to_string()),
+        }
+        // Hiding the last one shows all of them again.
+        self.content.series = if shown.len() == self.dataset.series.len() {
+            Vec::new()
+        } else {
+            shown
+        };
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn set_kind(&mut self, kind: ChartKind, cx: &mut Context<Self>) {
+        self.content.kind = kind;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn legend(&self, cx: &mut Context<Self>) -> AnyElement {
+        h_flex()
+            .gap_2()
+            .flex_wrap()
+            .text_xs()
+            .children(self.dataset.series.iter().enumerate().map(|(i, s)| {
+                let name = s.name.clone();
+                let shown = self.is_shown(&s.name);
+                h_flex()
+                    .id(("series", i))
+                    .gap_1()
+                    .items_center()
+                    .cursor_pointer()
+                    .when(!shown, |v| v.opacity(0.4))
+                    .child(
+                        div()
+                            .size(px(8.0))
+                            .rounded_full()
+                            .bg(rgb(SERIES_COLORS[i % SERIES_COLORS.len()])),
+                    )
+                    .child(s.name.clone())
+                    .on_click(cx.listener(move |this, _, _, cx| this.toggle_series(&name, cx)))
+            }))
+            .into_any_element()
+    }
+
+    fn chart_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let dataset = self.dataset.clone();
+        let shown = self.shown_series();
+        let (min, max) = dataset
+            .range(shown.iter().map(String::as_str))
+            .unwrap_or((0.0, 1.0));
+        // Bars grow from zero, lines use the range of the values.
+        let (min, max) = match self.content.kind {
+            ChartKind::Bar => (min.min(0.0), max.max(0.0)),
+            ChartKind::Line => (min, max),
+        };
+        let max = if max > min { max } else { min + 1.0 };
+        let kind = self.content.kind;
+        let axis_color = cx.theme().border;
+
+        let first_label = dataset.labels.first().cloned().unwrap_or_default();
+        let last_label = dataset.labels.last().cloned().unwrap_or_default();
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_1()
+            .child(self.legend(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .gap_1()
+                    .child(
+                        v_flex()
+                            .h_full()
+                            .justify_between()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format_value(max))
+                            .child(format_value(min)),
+                    )
+                    .child(
+                        div().size_full().window_drag_area().child(
+                            canvas(
+                                move |_, _, _| {},
+                                move |bounds, _, window, _| {
+                                    paint_chart(
+                                        window, bounds, &dataset, &shown, kind, min, max,
+                                        axis_color,
+                                    )
+                                },
+                            )
+                            .size_full(),
+                        ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .justify_between()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(first_label)
+                    .child(last_label),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("edit")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Change data")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.editing = true;
+                                    cx.notify();
+                                })),
+                        )
+                        .when(!self.content.command.is_empty(), |view| {
+                            view.child(
+                                Button::new("refresh")
+                                    .icon(IconName::Refresh)
+                                    .bg(transparent_white())
+                                    .border_0()
+                                    .xsmall()
+                                    .loading(self.loading)
+                                    .tooltip("Run the command again")
+                                    .on_click(cx.listener(|this, _, _, cx| this.load(cx))),
+                            )
+                        })
+                        .child(
+                            Button::new("line")
+                                .label("line")
+                                .xsmall()
+                                .when(kind == ChartKind::Line, |v| v.primary())
+                                .on_click(
+                                    cx.listener(|this, _, _, cx| {
+                                        this.set_kind(ChartKind::Line, cx)
+                                    }),
+                                ),
+                        )
+                        .child(
+                            Button::new("bar")
+                                .label("bar")
+                                .xsmall()
+                                .when(kind == ChartKind::Bar, |v| v.primary())
+                                .on_click(
+                                    cx.listener(|this, _, _, cx| this.set_kind(ChartKind::Bar, cx)),
+                                ),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field()
+                    .label("Data (CSV or JSON)")
+                    .child(Input::new(&self.data_input).small()),
+            )
+            .child(
+                field()
+                    .label("Command (its output is charted instead of the data)")
+                    .child(Input::new(&self.command_input).small()),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.apply_form(cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for ChartSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(200, 140)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(360, 240)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for ChartSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            body = body.child(self.chart_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("chart-error", msg.as_str()).small())
+        })
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e12 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn paint_chart(
+    window: &mut Window,
+    bounds: Bounds<Pixels>,
+    dataset: &Dataset,
+    shown: &[String],
+    kind: ChartKind,
+    min: f64,
+    max: f64,
+    axis_color: gpui::Hsla,
+) {
+    let rows = dataset.labels.len();
+    if rows == 0 {
+        return;
+    }
+
+    let left = bounds.origin.x.to_f64() as f32;
+    let top = bounds.origin.y.to_f64() as f32;
+    let width = bounds.size.width.to_f64() as f32;
+    let height = bounds.size.height.to_f64() as f32;
+    let y_of = |value: f64| top + height - ((value - min) / (max - min)) as f32 * height;
+
+    // The zero line, or the bottom when zero is out of range.
+    let base = y_of(0.0_f64.clamp(min, max));
+    window.paint_quad(fill(
+        Bounds::new(point(px(left), px(base)), size(px(width), px(1.0))),
+        axis_color,
+    ));
+
+    let series = dataset
+        .series
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| shown.contains(&s.name))
+        .collect::<Vec<_>>();
+
+    match kind {
+        ChartKind::Bar => {
+            let slot = width / rows as f32;
+            let bar = (slot * 0.8 / series.len().max(1) as f32).max(1.0);
+            for (n, (i, s)) in series.iter().enumerate() {
+                let color = rgb(SERIES_COLORS[i % SERIES_COLORS.len()]);
+                for (row, value) in s.values.iter().enumerate() {
+                    let Some(value) = value else {
+                        continue;
+                    };
+                    let x = left + slot * row as f32 + slot * 0.1 + bar * n as f32;
+                    let y = y_of(*value);
+                    let (y, h) = if y < base {
+                        (y, base - y)
+                    } else {
+                        (base, y - base)
+                    };
+                    window.paint_quad(fill(
+                        Bounds::new(point(px(x), px(y)), size(px(bar), px(h.max(1.0)))),
+                        color,
+                    ));
+                }
+            }
+        }
+        ChartKind::Line => {
+            let step = if rows > 1 {
+                width / (rows - 1) as f32
+            } else {
+                0.0
+            };
+            for (i, s) in series {
+                let color = rgb(SERIES_COLORS[i % SERIES_COLORS.len()]);
+                // Gaps in the data split the line.
+                let mut builder: Option<PathBuilder> = None;
+                let mut segments = Vec::new();
+                for (row, value) in s.values.iter().enumerate() {
+                    let Some(value) = value else {
+                        segments.extend(builder.take());
+                        continue;
+                    };
+                    let p = point(px(left + step * row as f32), px(y_of(*value)));
+                    match builder.as_mut() {
+                        Some(builder) => builder.line_to(p),
+                        None => {
+                            let mut new = PathBuilder::stroke(px(2.0));
+                            new.move_to(p);
+                            builder = Some(new);
+                        }
+                    }
+                }
+                segments.extend(builder);
+
+                for segment in segments {
+                    if let Ok(path) = segment.build() {
+                        window.paint_path(path, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn run_command(command: &str) -> anyhow::Result<String> {
+    let mut args = winsplit::split(command);
+    anyhow::ensure!(!args.is_empty(), "Command cannot be empty");
+
+    let program = args.remove(0);
+    let path =
+        which::which(&program).map_err(|_| anyhow::anyhow!("Command not found: {program}"))?;
+
+    let mut cmd = std::process::Command::new(path);
+    cmd.args(args);
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd.output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "{}: {}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,326 @@
+use std::time::Duration;
+
+use chrono::{Datelike, Local, Utc};
+use chrono_tz::Tz;
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputState},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ClockContent {
+    /// IANA time zone names, e.g. "Europe/London".
+    zones: Vec<String>,
+}
+
+pub struct ClockSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    zones: Vec<Tz>,
+
+    zones_input: Entity<InputState>,
+    editing: bool,
+    ticking: bool,
+
+    error: Option<String>,
+}
+
+impl ClockSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<ClockContent>(content).unwrap_or_default();
+        let zones = content
+            .zones
+            .iter()
+            .filter_map(|zone| match zone.parse::<Tz>() {
+                Ok(tz) => Some(tz),
+                Err(err) => {
+                    tracing::warn!(id, zone = %zone, error = %err, "Unknown time zone");
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let zones_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(3, 8)
+                .default_value(content.zones.join("\n"))
+                .placeholder("One time zone per line, e.g. Europe/London")
+        });
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            editing: zones.is_empty(),
+            zones,
+            zones_input,
+            ticking: false,
+            error: None,
+        }
+    }
+
+    fn apply_zones(&mut self, cx: &mut Context<Self>) {
+        let mut zones = Vec::new();
+        let mut unknown = Vec::new();
+        for line in self.zones_input.read(cx).value().lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.parse::<Tz>() {
+                Ok(tz) => zones.push(tz),
+                Err(_) => unknown.push(line.to_string()),
+            }
+        }
+
+        if !unknown.is_empty() {
+            self.error = Some(format!("Unknown time zones: {}", unknown.join(", ")));
+            cx.notify();
+            return;
+        }
+        if zones.is_empty() {
+            self.error = Some("Please add at least one time zone".to_string());
+            cx.notify();
+            return;
+        }
+
+        self.zones = zones;
+        self.editing = false;
+        self.error = None;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let content = ClockContent {
+            zones: self.zones.iter().map(|tz| tz.name().to_string()).collect(),
+        };
+        let title = self
+            .zones
+            .iter()
+            .map(|tz| zone_label(*tz))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let json = match serde_json::to_string(&content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize clock sticker: {err}"));
+                return false;
+            }
+        };
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save clock sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save clock sticker: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    /// Redraw at the start of the next second, same approach as the timer countdown.
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        let wait_ms = 1000 - Utc::now().timestamp_subsec_millis().min(999) as u64;
+        cx.spawn(async move |e, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(wait_ms))
+                .await;
+            let _ = e.update(cx, |this, cx| {
+                this.ticking = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn clock_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let now = Utc::now();
+        let today = Local::now().date_naive();
+
+        let rows = self.zones.iter().map(|tz| {
+            let time = now.with_timezone(tz);
+            // Show how the day differs from the local one, e.g. "Tue +1".
+            let day_diff = (time.date_naive() - today).num_days();
+            let day = match day_diff {
+                0 => time.weekday().to_string(),
+                d => format!("{} {:+}", time.weekday(), d),
+            };
+
+            h_flex()
+                .justify_between()
+                .items_baseline()
+                .gap_2()
+                .child(
+                    v_flex()
+                        .overflow_hidden()
+                        .child(div().text_sm().child(zone_label(*tz)))
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(day),
+                        ),
+                )
+                .child(
+                    div()
+                        .text_xl()
+                        .font_family(cx.theme().mono_font_family.clone())
+                        .child(time.format("%H:%M:%S").to_string()),
+                )
+        });
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_1()
+                    .justify_center()
+                    .window_drag_area()
+                    .children(rows),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex().child(
+                        Button::new("edit")
+                            .icon(IconName::Adjustments)
+                            .bg(transparent_white())
+                            .border_0()
+                            .xsmall()
+                            .tooltip("Change time zones")
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.editing = true;
+                                cx.notify();
+                            })),
+                    ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field()
+                    .label("Time zones")
+                    .child(Input::new(&self.zones_input)),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.apply_zones(cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for ClockSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(140, 80)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(260, 200)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for ClockSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            self.spawn_for_tick(cx);
+            body = body.child(self.clock_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("clock-error", msg.as_str()).small())
+        })
+    }
+}
+
+/// "America/New_York" -> "New York".
+fn zone_label(tz: Tz) -> String {
+    tz.name()
+        .rsplit('/')
+        .next()
+        .unwrap_or(tz.name())
+        .replace('_', " ")
+}
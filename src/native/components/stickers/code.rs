@@ -0,0 +1,347 @@
+use std::ops::Range;
+
+use gpui::{
+    ClipboardItem, Context, Entity, FontStyle, FontWeight, HighlightStyle, KeyDownEvent,
+    MouseButton, MouseDownEvent, Rgba, StyledText, Window, div, prelude::*, px, rgb, rgba,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::Button,
+    h_flex,
+    input::{Input, InputState},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::{StickerColor, StickerSettings};
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+use crate::utils::highlight;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CodeContent {
+    /// A language name (`Rust`) or file extension (`rs`).
+    language: String,
+    code: String,
+}
+
+/// Content of a code sticker showing `code`, e.g. JSON from the clipboard.
+pub fn content_for_code(language: &str, code: &str) -> String {
+    serde_json::to_string(&CodeContent {
+        language: language.to_string(),
+        code: code.to_string(),
+    })
+    .unwrap_or_default()
+}
+
+pub struct CodeSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: CodeContent,
+    highlights: Vec<(Range<usize>, HighlightStyle)>,
+
+    language_input: Entity<InputState>,
+    editor: Entity<InputState>,
+    editing: bool,
+    copied: bool,
+    settings: StickerSettings,
+
+    error: Option<String>,
+}
+
+impl CodeSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<CodeContent>(content).unwrap_or_default();
+
+        let language_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.language.clone())
+                .placeholder("Language, e.g. rust, py, sql")
+        });
+        let editor = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .default_value(content.code.clone())
+                .placeholder("Paste code, ctrl+s to save and highlight it")
+        });
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            editing: content.code.is_empty(),
+            highlights: Vec::new(),
+            content,
+            language_input,
+            editor,
+            copied: false,
+            settings: StickerSettings::default(),
+            error: None,
+        };
+        this.update_highlights();
+        this
+    }
+
+    fn update_highlights(&mut self) {
+        self.highlights = highlight::highlight(&self.content.code, &self.content.language)
+            .into_iter()
+            .map(|span| {
+                let style = HighlightStyle {
+                    color: Some(rgb(span.color).into()),
+                    font_weight: span.bold.then_some(FontWeight::BOLD),
+                    font_style: span.italic.then_some(FontStyle::Italic),
+                    ..Default::default()
+                };
+                (span.range, style)
+            })
+            .collect();
+    }
+
+    fn save_state(&mut self, cx: &mut Context<Self>) -> bool {
+        let language = self.language_input.read(cx).value().trim().to_string();
+        if !language.is_empty() && !highlight::is_known_language(&language) {
+            self.error = Some(format!(
+                "Unknown language \"{language}\", the code is shown as plain text"
+            ));
+        } else {
+            self.error = None;
+        }
+
+        self.content = CodeContent {
+            language,
+            code: self.editor.read(cx).value().to_string(),
+        };
+        self.update_highlights();
+
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize code sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self
+            .content
+            .code
+            .lines()
+            .map(str::trim)
+            .find(|x| !x.is_empty())
+            .unwrap_or("Code snippet")
+            .to_string();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save code sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save code sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            let _ = entity.update(cx, |this, cx| {
+                this.editing = false;
+                cx.notify();
+            });
+        })
+        .detach();
+
+        true
+    }
+
+    fn copy(&mut self, cx: &mut Context<Self>) {
+        cx.write_to_clipboard(ClipboardItem::new_string(self.content.code.clone()));
+        self.copied = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(std::time::Duration::from_secs(2))
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                this.copied = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+}
+
+impl super::Sticker for CodeSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_state(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(200, 100)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(420, 300)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn has_text_output(&self) -> bool {
+        !self.editing
+    }
+
+    fn apply_settings(&mut self, settings: &StickerSettings) {
+        self.settings = settings.clone();
+    }
+}
+
+impl Render for CodeSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut body = v_flex().size_full().gap_1().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            window.set_rem_size(cx.theme().font_size);
+
+            body = body
+                .child(
+                    v_flex()
+                        .size_full()
+                        .p_1()
+                        .gap_1()
+                        .on_key_down(cx.listener(|this, event: &KeyDownEvent, _, cx| {
+                            if event.keystroke.modifiers.control
+                                && event.keystroke.key.eq_ignore_ascii_case("s")
+                            {
+                                this.save_state(cx);
+                            }
+                        }))
+                        .child(Input::new(&self.language_input).small())
+                        .child(
+                            Input::new(&self.editor)
+                                .size_full()
+                                .bordered(false)
+                                .font_family(cx.theme().mono_font_family.clone())
+                                .bg(rgba(0x000000)),
+                        ),
+                )
+                .child(
+                    h_flex().child(Button::new("save").label("save (ctrl+s)").small().on_click(
+                        cx.listener(|s, _, _, cx| {
+                            s.save_state(cx);
+                        }),
+                    )),
+                );
+        } else {
+            window.set_rem_size(px(14.0));
+
+            let code = StyledText::new(self.content.code.clone())
+                .with_highlights(self.highlights.iter().cloned());
+
+            body = body.child(
+                div()
+                    .relative()
+                    .size_full()
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|s, e: &MouseDownEvent, _, _| {
+                            if e.click_count >= 2 {
+                                s.editing = true;
+                            }
+                        }),
+                    )
+                    .child(
+                        div()
+                            .size_full()
+                            .py_1()
+                            .px_2()
+                            .text_sm()
+                            .overflow_scrollbar()
+                            .font_family(cx.theme().mono_font_family.clone())
+                            .when(self.settings.no_wrap, |v| v.whitespace_nowrap())
+                            .child(code),
+                    )
+                    .child(
+                        div()
+                            .occlude()
+                            .absolute()
+                            .left_0()
+                            .top_0()
+                            .right_0()
+                            .h_5()
+                            .window_drag_area(),
+                    )
+                    .when(window.is_window_hovered(), |view| {
+                        view.child(
+                            h_flex()
+                                .absolute()
+                                .top_1()
+                                .right_1()
+                                .gap_1()
+                                .items_center()
+                                .when(!self.content.language.is_empty(), |view| {
+                                    view.child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .child(self.content.language.clone()),
+                                    )
+                                })
+                                .child(
+                                    Button::new("copy")
+                                        .icon(if self.copied {
+                                            IconName::Check
+                                        } else {
+                                            IconName::Copy
+                                        })
+                                        .bg(transparent_white())
+                                        .border_0()
+                                        .xsmall()
+                                        .tooltip("Copy code")
+                                        .on_click(cx.listener(|this, _, _, cx| this.copy(cx))),
+                                ),
+                        )
+                    }),
+            );
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::warning("code-error", msg.as_str()).small())
+        })
+    }
+}
@@ -1,24 +1,27 @@
 use gpui::{
-    Animation, AnimationExt, AnyElement, AppContext, Context, Entity, Image, ImageFormat,
-    ImageSource, Render, Rgba, Window, div, img, prelude::*, px, transparent_white,
+    Animation, AnimationExt, AnyElement, App, AppContext, Context, Entity, FocusHandle,
+    KeyDownEvent, MouseButton, Render, Rgba, ScrollHandle, Window, div, prelude::*, px,
+    transparent_white,
 };
 use gpui_component::{
-    Sizable,
+    ActiveTheme, Sizable, WindowExt,
     alert::Alert,
+    black,
     button::{Button, ButtonVariants as _},
     form::{field, v_form},
     h_flex,
     input::{Input, InputEvent, InputState},
+    menu::{DropdownMenu, PopupMenuItem},
     scroll::ScrollableElement,
     slider::{Slider, SliderState},
     switch::Switch,
-    text::TextView,
     v_flex, yellow_500,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
+    collections::BTreeMap,
+    ops::Range,
     process::{Command, Stdio},
-    str::FromStr,
     sync::atomic::{AtomicBool, Ordering},
     sync::mpsc::{self, TryRecvError},
     sync::{Arc, Mutex, RwLock},
@@ -29,14 +32,29 @@ use std::{
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
+use super::Sticker as _;
+use super::cron::{self, CronTarget};
+use super::result_renderers::{self, RenderContext};
 use crate::native::{
-    components::IconName, components::webview::SimpleWebView, windows::StickerWindowEvent,
+    components::IconName,
+    components::webview::{self, LazyWebView, SimpleWebView, WebViewStyle},
+    dialogs,
+    sound::{self, Sound},
+    windows::StickerWindowEvent,
 };
 
-use crate::model::sticker::StickerColor;
+use super::save_content;
+use crate::model::command::CommandRun;
+use crate::model::sticker::{StickerColor, StickerSettings, StickerType};
 use crate::storage::ArcStickerStore;
+use crate::storage::settings::SettingsStore;
+use crate::utils::template;
 
 const MAX_SLEEP_CHUNK_MS: u64 = 250;
+/// Wait for changes to settle before running, saving a file often fires several events.
+const WATCH_DEBOUNCE_MS: u64 = 500;
+/// Changes in these directories never trigger a run, builds write to them all the time.
+const WATCH_IGNORED_DIRS: &[&str] = &[".git", "target", "node_modules"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CommandContent {
@@ -49,19 +67,54 @@ struct CommandContent {
     stream_result: bool,
     padding: Option<u8>,
     started_at: Option<i64>,
+    /// Ask before every manual or restored start, for destructive commands.
+    #[serde(default)]
+    confirm_before_run: bool,
+    /// Style sheet and wrapper of HTML output.
+    #[serde(default)]
+    web_style: WebViewStyle,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-enum CommandResult {
-    Text(Option<String>),
-    Html(Option<String>),
-    Svg(Option<String>),
-    Markdown(Option<String>),
+/// The output of the last run and the key of the renderer showing it.
+///
+/// Stored as `{"<renderer key>": output}`, the keys match the enum used before.
+#[derive(Debug, Clone)]
+struct CommandResult {
+    renderer: String,
+    output: Option<String>,
+}
+
+impl CommandResult {
+    fn new(renderer: &str) -> Self {
+        Self {
+            renderer: renderer.to_string(),
+            output: None,
+        }
+    }
+}
+
+impl Serialize for CommandResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(std::iter::once((&self.renderer, &self.output)))
+    }
+}
+
+impl<'de> Deserialize<'de> for CommandResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = BTreeMap::<String, Option<String>>::deserialize(deserializer)?;
+        let (renderer, output) = map
+            .into_iter()
+            .next()
+            .ok_or_else(|| serde::de::Error::custom("missing command result renderer"))?;
+        Ok(Self { renderer, output })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 enum Scheduler {
     Cron(String),
+    /// Run whenever the file, or anything in the directory, changes.
+    Watch(String),
 }
 
 impl Default for CommandContent {
@@ -73,9 +126,11 @@ impl Default for CommandContent {
             scheduler: None,
             run_immediately: true,
             stream_result: false,
-            result: CommandResult::Text(None),
+            result: CommandResult::new(result_renderers::DEFAULT_KEY),
             padding: None,
             started_at: None,
+            confirm_before_run: false,
+            web_style: WebViewStyle::default(),
         }
     }
 }
@@ -91,26 +146,62 @@ pub struct CommandSticker {
     working_dir: Entity<InputState>,
     scheduler: Option<Scheduler>,
     scheduler_cron_input: Entity<InputState>,
+    scheduler_watch_input: Entity<InputState>,
     run_immediately: bool,
     stream_result: bool,
     padding: Entity<SliderState>,
     started_at: Option<i64>,
+    confirm_before_run: bool,
+    settings: StickerSettings,
 
     result: CommandResult,
-    result_html_entity: Option<Entity<SimpleWebView>>,
+    result_webview: LazyWebView,
+    web_style: WebViewStyle,
+    web_css_input: Entity<InputState>,
+    web_wrapper_input: Entity<InputState>,
+    focus_handle: FocusHandle,
+    search: Option<OutputSearch>,
 
     process: Option<Arc<Mutex<std::process::Child>>>,
     stopping: bool,
+    /// The last output is over the size limit, it is not saved until the user decides.
+    oversized_output: bool,
 
     schedule_cancel: Option<Arc<AtomicBool>>,
     next_scheduled_at: Option<String>,
     error: Option<String>,
 }
 
+/// The Ctrl+F search bar over text output.
+struct OutputSearch {
+    input: Entity<InputState>,
+    matches: Vec<Range<usize>>,
+    current: usize,
+    /// Length of the output the matches were found in, streaming output is searched again.
+    searched_len: usize,
+    scroll: ScrollHandle,
+}
+
+impl OutputSearch {
+    fn refresh(&mut self, output: &str, cx: &App) {
+        let query = self.input.read(cx).value().to_string();
+        self.matches = result_renderers::find_matches(output, &query);
+        self.current = self.current.min(self.matches.len().saturating_sub(1));
+        self.searched_len = output.len();
+    }
+
+    fn scroll_to_current(&self, output: &str) {
+        if let Some(range) = self.matches.get(self.current) {
+            let line = output[..range.start].matches('\n').count();
+            self.scroll.scroll_to_item(line);
+        }
+    }
+}
+
 enum CmdEvent {
     Output(String),
     Error(String),
-    Done,
+    Done(Option<i32>),
 }
 
 impl CommandSticker {
@@ -156,12 +247,46 @@ impl CommandSticker {
         };
         let cron_entity = cx.new(|cx| InputState::new(window, cx).default_value(cron));
 
-        let result_html_entity = match &cmd.result {
-            CommandResult::Html(Some(x)) => {
-                Some(cx.new(|cx| SimpleWebView::new(x.as_str(), window, cx)))
-            }
-            _ => None,
+        let watch_path = match &cmd.scheduler {
+            Some(Scheduler::Watch(path)) => path.clone(),
+            _ => String::new(),
         };
+        let watch_entity = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(watch_path)
+                .placeholder("A file or directory, relative to the working directory")
+        });
+
+        let mut result_webview = LazyWebView::default();
+        result_webview.set_source(Self::webview_source(&cmd.result, &cmd.web_style));
+
+        let web_css_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(1, 5)
+                .default_value(cmd.web_style.css.clone())
+                .placeholder("body { font-size: 14px; }")
+        });
+        let web_wrapper_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(1, 5)
+                .default_value(cmd.web_style.wrapper.clone())
+                .placeholder("<div class=\"card\">{{content}}</div>")
+        });
+        for input in [&web_css_input, &web_wrapper_input] {
+            cx.subscribe(input, |this, _, evt, cx| {
+                if let InputEvent::Change = evt {
+                    this.web_style = WebViewStyle {
+                        css: this.web_css_input.read(cx).value().to_string(),
+                        wrapper: this.web_wrapper_input.read(cx).value().to_string(),
+                    };
+                    this.result_webview
+                        .set_source(Self::webview_source(&this.result, &this.web_style));
+                }
+            })
+            .detach();
+        }
 
         let padding = cx.new(|_cx| {
             SliderState::new()
@@ -179,6 +304,14 @@ impl CommandSticker {
         })
         .detach();
 
+        cx.subscribe(&watch_entity, |this, v, evt, cx| match evt {
+            InputEvent::Change => {
+                this.scheduler = Some(Scheduler::Watch(v.read(cx).value().trim().to_string()));
+            }
+            _ => {}
+        })
+        .detach();
+
         let root_entity = cx.entity();
         window
             .spawn(cx, async move |cx| {
@@ -187,7 +320,7 @@ impl CommandSticker {
                         && this.process.is_none()
                         && !this.is_schedule_active()
                     {
-                        this.start(window, cx);
+                        this.request_start(window, cx);
                     }
                 });
             })
@@ -204,15 +337,25 @@ impl CommandSticker {
             working_dir,
             scheduler: cmd.scheduler,
             scheduler_cron_input: cron_entity,
+            scheduler_watch_input: watch_entity,
             run_immediately: cmd.run_immediately,
             result: cmd.result,
-            result_html_entity,
+            result_webview,
+            web_style: cmd.web_style,
+            web_css_input,
+            web_wrapper_input,
             stream_result: cmd.stream_result,
             padding,
             started_at: cmd.started_at,
+            confirm_before_run: cmd.confirm_before_run,
+            settings: StickerSettings::default(),
 
             process: None,
             stopping: false,
+            oversized_output: false,
+
+            focus_handle: cx.focus_handle(),
+            search: None,
 
             schedule_cancel: None,
             next_scheduled_at: None,
@@ -220,6 +363,126 @@ impl CommandSticker {
         }
     }
 
+    fn open_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search.is_none() {
+            let input = cx.new(|cx| InputState::new(window, cx).placeholder("Search output"));
+            cx.subscribe(&input, |this, _, event: &InputEvent, cx| match event {
+                InputEvent::Change => {
+                    if let (Some(search), Some(output)) = (&mut this.search, &this.result.output) {
+                        search.current = 0;
+                        search.refresh(output, cx);
+                        search.scroll_to_current(output);
+                    }
+                    cx.notify();
+                }
+                InputEvent::PressEnter { secondary } => {
+                    this.step_search(!*secondary, cx);
+                }
+                _ => {}
+            })
+            .detach();
+
+            self.search = Some(OutputSearch {
+                input,
+                matches: Vec::new(),
+                current: 0,
+                searched_len: 0,
+                scroll: ScrollHandle::new(),
+            });
+        }
+
+        if let Some(search) = &self.search {
+            search.input.update(cx, |input, cx| input.focus(window, cx));
+        }
+        cx.notify();
+    }
+
+    fn close_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.search = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn step_search(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let (Some(search), Some(output)) = (&mut self.search, &self.result.output) else {
+            return;
+        };
+        let count = search.matches.len();
+        if count == 0 {
+            return;
+        }
+
+        search.current = match forward {
+            true => (search.current + 1) % count,
+            false => (search.current + count - 1) % count,
+        };
+        search.scroll_to_current(output);
+        cx.notify();
+    }
+
+    fn search_bar(&self, bg_color: Rgba, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let search = self.search.as_ref()?;
+        let position = match search.matches.len() {
+            0 => "0/0".to_string(),
+            count => format!("{}/{count}", search.current + 1),
+        };
+
+        Some(
+            h_flex()
+                .bg(bg_color)
+                .p_1()
+                .gap_1()
+                .items_center()
+                .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                    if event.keystroke.key == "escape" {
+                        this.close_search(window, cx);
+                    }
+                }))
+                .child(Input::new(&search.input).small().flex_1())
+                .child(div().text_xs().opacity(0.75).child(position))
+                .child(
+                    Button::new("search_previous")
+                        .icon(IconName::ArrowUp)
+                        .bg(transparent_white())
+                        .border_0()
+                        .xsmall()
+                        .tooltip("Previous match (shift+enter)")
+                        .on_click(cx.listener(|this, _, _, cx| this.step_search(false, cx))),
+                )
+                .child(
+                    Button::new("search_next")
+                        .icon(IconName::ArrowDown)
+                        .bg(transparent_white())
+                        .border_0()
+                        .xsmall()
+                        .tooltip("Next match (enter)")
+                        .on_click(cx.listener(|this, _, _, cx| this.step_search(true, cx))),
+                )
+                .child(
+                    Button::new("search_close")
+                        .icon(IconName::Close)
+                        .bg(transparent_white())
+                        .border_0()
+                        .xsmall()
+                        .tooltip("Close (esc)")
+                        .on_click(cx.listener(|this, _, window, cx| this.close_search(window, cx))),
+                )
+                .into_any_element(),
+        )
+    }
+
+    /// The output to show in a web view, built once the window is looked at.
+    fn webview_source(result: &CommandResult, style: &WebViewStyle) -> Option<String> {
+        result
+            .output
+            .as_deref()
+            .filter(|_| result_renderers::get(&result.renderer).use_webview())
+            .map(|output| match crate::utils::url::is_url(output.trim()) {
+                true => output.to_string(),
+                false => style.apply_to_html(output),
+            })
+    }
+
     fn build_content(&self, cx: &mut Context<Self>) -> CommandContent {
         CommandContent {
             command: self.command.read(cx).value().trim().to_string(),
@@ -227,16 +490,32 @@ impl CommandSticker {
             working_dir: self.working_dir.read(cx).value().to_string(),
             scheduler: self.scheduler.clone(),
             run_immediately: self.run_immediately,
-            result: self.result.clone(),
+            result: match self.oversized_output {
+                true => CommandResult {
+                    renderer: self.result.renderer.clone(),
+                    output: None,
+                },
+                false => self.result.clone(),
+            },
             stream_result: self.stream_result,
             padding: Some(self.padding.read(cx).value().start() as u8),
             started_at: self.started_at,
+            confirm_before_run: self.confirm_before_run,
+            web_style: self.web_style.clone(),
         }
     }
 
     fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
         let content = self.build_content(cx);
-        let title = content.command.trim().to_string();
+        let command = content.command.trim().to_string();
+        let title = match SettingsStore::get(cx).title_template(StickerType::Command) {
+            Some(title_template) => {
+                let mut vars = template::builtin_vars();
+                vars.push(("command".to_string(), command));
+                template::render_template(title_template, &vars)
+            }
+            None => command,
+        };
         let json = match serde_json::to_string(&content) {
             Ok(json) => json,
             Err(err) => {
@@ -263,7 +542,9 @@ impl CommandSticker {
                 tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
             }
 
-            if let Err(err) = store.update_sticker_content(id, json).await {
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
                 let _ = entity.update(cx, |this, cx| {
                     this.error = Some(format!("Failed to save command sticker: {err:#}"));
                     cx.notify();
@@ -285,6 +566,38 @@ impl CommandSticker {
         self.schedule_cancel.is_some()
     }
 
+    /// Start from a user action or a restored session, asking first when the sticker wants it.
+    fn request_start(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if SettingsStore::get(cx).safe_mode {
+            self.error = Some("Command execution is disabled by safe mode".to_string());
+            cx.notify();
+            return;
+        }
+
+        if !self.confirm_before_run {
+            self.start(window, cx);
+            return;
+        }
+
+        let entity = cx.entity();
+        let command = self.command.read(cx).value().trim().to_string();
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let entity = entity.clone();
+            dialog
+                .confirm()
+                .title(div().text_color(cx.theme().warning).child("Run command?"))
+                .child(div().text_xs().child(command.clone()))
+                .w(px(260.0))
+                .bg(black().opacity(0.9))
+                .text_sm()
+                .on_cancel(|_, _, _| true)
+                .on_ok(move |_, window, cx| {
+                    entity.update(cx, |this, cx| this.start(window, cx));
+                    true
+                })
+        });
+    }
+
     fn start(&mut self, window: &Window, cx: &mut Context<Self>) {
         self.started_at = Some(crate::utils::time::now_unix_millis());
 
@@ -300,16 +613,10 @@ impl CommandSticker {
                 self.run(window, cx);
             }
             Some(Scheduler::Cron(expr)) => {
-                if expr.is_empty() {
-                    self.error = Some("Cron expression cannot be empty".to_string());
-                    cx.notify();
-                    return;
-                }
-
-                let schedule = match cron::Schedule::from_str(&expr) {
+                let schedule = match cron::parse(&expr) {
                     Ok(s) => s,
                     Err(err) => {
-                        self.error = Some(format!("Invalid cron expression: {err}"));
+                        self.error = Some(err);
                         cx.notify();
                         return;
                     }
@@ -319,79 +626,124 @@ impl CommandSticker {
                     self.run(window, cx);
                 }
 
-                let cancel = Arc::new(AtomicBool::new(false));
-
                 self.error = None;
-                self.schedule_cancel = Some(cancel.clone());
+                self.schedule_cancel = Some(cron::spawn(schedule, window, cx));
+            }
+            Some(Scheduler::Watch(path)) => {
+                self.start_watch(&path, &content.working_dir, window, cx)
+            }
+        }
+    }
 
-                let entity = cx.entity();
-                window
-                    .spawn(cx, async move |window| {
-                        loop {
-                            if cancel.load(Ordering::SeqCst) {
-                                break;
-                            }
+    fn start_watch(
+        &mut self,
+        path: &str,
+        working_dir: &str,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if path.is_empty() {
+            self.error = Some("Watched path cannot be empty".to_string());
+            cx.notify();
+            return;
+        }
 
-                            let now = chrono::Local::now();
-                            let next = schedule.upcoming(chrono::Local).next();
-                            let Some(next) = next else {
-                                let _ =
-                                    window.update_entity(&entity, |this, _| this.stop_schedule());
-                                break;
-                            };
+        let mut watched = std::path::PathBuf::from(path);
+        if watched.is_relative() && !working_dir.trim().is_empty() {
+            watched = std::path::Path::new(working_dir.trim()).join(watched);
+        }
+        if !watched.exists() {
+            self.error = Some(format!("Watched path not found: {}", watched.display()));
+            cx.notify();
+            return;
+        }
 
-                            let next_str = next.format("%Y-%m-%d %H:%M:%S").to_string();
-                            let _ = window.update_entity(&entity, |this, cx| {
-                                this.next_scheduled_at = Some(next_str);
-                                cx.notify();
-                            });
+        let (tx, rx) = mpsc::channel::<()>();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if event.kind.is_access() {
+                return;
+            }
+            let ignored = event.paths.iter().all(|path| {
+                path.components()
+                    .any(|x| WATCH_IGNORED_DIRS.iter().any(|dir| x.as_os_str() == *dir))
+            });
+            if !ignored {
+                let _ = tx.send(());
+            }
+        });
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                self.error = Some(format!("Failed to watch {}: {err}", watched.display()));
+                cx.notify();
+                return;
+            }
+        };
+        if let Err(err) =
+            notify::Watcher::watch(&mut watcher, &watched, notify::RecursiveMode::Recursive)
+        {
+            self.error = Some(format!("Failed to watch {}: {err}", watched.display()));
+            cx.notify();
+            return;
+        }
 
-                            // Compute delay with signed math first to avoid underflow when
-                            // `next` is already in the past.
-                            let delay_ms_i64 = next.timestamp_millis() - now.timestamp_millis();
-                            if delay_ms_i64 <= 0 {
-                                let _ = window.update_window_entity(&entity, |this, window, cx| {
-                                    if this.process.is_none() && !this.stopping {
-                                        this.stop(cx);
-                                        this.run(window, cx);
-                                    }
-                                });
-                                continue;
-                            }
+        if self.run_immediately {
+            self.run(window, cx);
+        }
 
-                            // Make the wait cancellable: instead of awaiting one long timer (which
-                            // can't be interrupted), sleep in small chunks and check `cancel`.
-                            let mut remaining_ms = delay_ms_i64 as u64;
-                            while remaining_ms > 0 {
-                                if cancel.load(Ordering::SeqCst) {
-                                    break;
-                                }
-                                let chunk = remaining_ms.min(MAX_SLEEP_CHUNK_MS);
-                                window
-                                    .background_executor()
-                                    .timer(Duration::from_millis(chunk))
-                                    .await;
-                                remaining_ms = remaining_ms.saturating_sub(chunk);
-                            }
+        let cancel = Arc::new(AtomicBool::new(false));
 
-                            if cancel.load(Ordering::SeqCst) {
-                                break;
-                            }
+        self.error = None;
+        self.schedule_cancel = Some(cancel.clone());
 
-                            let _ = window.update_window_entity(&entity, |this, window, cx| {
-                                if this.process.is_none() && !this.stopping {
-                                    this.stop(cx);
-                                    this.run(window, cx);
-                                }
-                            });
+        let entity = cx.entity();
+        window
+            .spawn(cx, async move |window| {
+                // Dropping the watcher stops watching, so it lives as long as this task.
+                let _watcher = watcher;
+                let mut pending_since: Option<std::time::Instant> = None;
+
+                while !cancel.load(Ordering::SeqCst) {
+                    loop {
+                        match rx.try_recv() {
+                            Ok(()) => pending_since = Some(std::time::Instant::now()),
+                            Err(TryRecvError::Empty) => break,
+                            Err(TryRecvError::Disconnected) => return,
                         }
-                    })
-                    .detach();
-            }
-        }
+                    }
+
+                    let settled = pending_since.is_some_and(|since| {
+                        since.elapsed() >= Duration::from_millis(WATCH_DEBOUNCE_MS)
+                    });
+                    if settled {
+                        // Changes made while the command runs are picked up once it is done.
+                        let _ = window.update_window_entity(&entity, |this, window, cx| {
+                            if this.process.is_none() && !this.stopping {
+                                pending_since = None;
+                                this.run(window, cx);
+                            }
+                        });
+                    }
+
+                    window
+                        .background_executor()
+                        .timer(Duration::from_millis(MAX_SLEEP_CHUNK_MS))
+                        .await;
+                }
+            })
+            .detach();
     }
 
     fn run(&mut self, window: &Window, cx: &mut Context<Self>) {
+        if SettingsStore::get(cx).safe_mode {
+            self.error = Some("Command execution is disabled by safe mode".to_string());
+            cx.notify();
+            return;
+        }
+
         let content = self.build_content(cx);
         if content.command.trim().is_empty() {
             self.error = Some("Command cannot be empty".to_string());
@@ -407,6 +759,11 @@ impl CommandSticker {
         }
 
         let workdir = content.working_dir.trim();
+        if !workdir.is_empty() && !std::path::Path::new(workdir).is_dir() {
+            self.error = Some(format!("Working directory not found: {workdir}"));
+            cx.notify();
+            return;
+        }
 
         let program = args.remove(0);
         let Ok(path) = which::which(&program) else {
@@ -454,6 +811,10 @@ impl CommandSticker {
             }
         };
 
+        if !workdir.is_empty() {
+            SettingsStore::update(cx, |s| s.remember_working_dir(workdir));
+        }
+
         let (tx, rx) = mpsc::channel();
         self.handle_stdout_and_err(cx, tx, process);
         self.handle_cmd_events(window, cx, rx);
@@ -495,24 +856,24 @@ impl CommandSticker {
 
             // IMPORTANT: do not hold the mutex while waiting. If we call `wait()` while
             // holding the lock, `stop()` cannot lock the child to kill it.
-            loop {
-                let is_done = match process.lock() {
+            let exit_code = loop {
+                let done = match process.lock() {
                     Ok(mut child) => match child.try_wait() {
-                        Ok(Some(_status)) => true,
-                        Ok(None) => false,
-                        Err(_err) => true,
+                        Ok(Some(status)) => Some(status.code()),
+                        Ok(None) => None,
+                        Err(_err) => Some(None),
                     },
-                    Err(_err) => true,
+                    Err(_err) => Some(None),
                 };
 
-                if is_done {
-                    break;
+                if let Some(exit_code) = done {
+                    break exit_code;
                 }
 
                 thread::sleep(Duration::from_millis(50));
-            }
+            };
 
-            let _ = tx.send(CmdEvent::Done);
+            let _ = tx.send(CmdEvent::Done(exit_code));
             let _ = out_handle.join();
             let _ = err_handle.join();
         });
@@ -524,26 +885,16 @@ impl CommandSticker {
         cx: &Context<Self>,
         rx: mpsc::Receiver<CmdEvent>,
     ) {
+        let renderer = result_renderers::get(&self.result.renderer);
         if self.stream_result {
-            match self.result {
-                CommandResult::Text(ref mut result)
-                | CommandResult::Markdown(ref mut result)
-                | CommandResult::Html(ref mut result)
-                | CommandResult::Svg(ref mut result) => {
-                    *result = None;
-                }
-            }
-            self.result_html_entity = None;
-        } else {
-            match &self.result {
-                CommandResult::Html(_) => {}
-                _ => {
-                    self.result_html_entity = None;
-                }
-            };
+            self.result.output = None;
+            self.result_webview.set_source(None);
+        } else if !renderer.use_webview() {
+            self.result_webview.set_source(None);
         }
 
         let entity = cx.entity();
+        let run_started_at = crate::utils::time::now_unix_millis();
         window
             .spawn(cx, async move |window| {
                 window
@@ -552,50 +903,41 @@ impl CommandSticker {
                     .await;
 
                 let result_temp = Arc::new(RwLock::new(String::new()));
+                let mut last_value = String::new();
+                let mut finished: Option<Option<i32>> = None;
                 loop {
                     let result_temp = result_temp.clone();
+                    let renderer = renderer.clone();
                     match rx.try_recv() {
                         Ok(event) => match event {
                             CmdEvent::Output(line) | CmdEvent::Error(line) => {
+                                if !line.trim().is_empty() {
+                                    last_value = line.trim().to_string();
+                                }
                                 let _ = window.update_entity(
                                     &entity,
                                     move |this: &mut CommandSticker, cx| {
-                                        match this.result {
-                                            CommandResult::Text(ref mut result)
-                                            | CommandResult::Markdown(ref mut result) => {
-                                                if this.stream_result {
-                                                    let result =
-                                                        result.get_or_insert_with(String::new);
-                                                    result.push_str(&line);
-                                                    result.push('\n');
-                                                } else {
-                                                    *result_temp.write().unwrap() += &line;
-                                                    *result_temp.write().unwrap() += "\n";
-                                                }
-                                            }
-                                            CommandResult::Html(_) | CommandResult::Svg(_) => {
-                                                *result_temp.write().unwrap() += &line;
-                                                *result_temp.write().unwrap() += "\n";
-                                            }
+                                        if this.stream_result && renderer.streams() {
+                                            let result =
+                                                this.result.output.get_or_insert_with(String::new);
+                                            result.push_str(&line);
+                                            result.push('\n');
+                                        } else {
+                                            *result_temp.write().unwrap() += &line;
+                                            *result_temp.write().unwrap() += "\n";
                                         }
                                         cx.notify();
                                     },
                                 );
                             }
-                            CmdEvent::Done => {
+                            CmdEvent::Done(exit_code) => {
+                                finished = Some(exit_code);
                                 let _ = window.update_entity(
                                     &entity,
-                                    move |this: &mut CommandSticker, cx| match this.result {
-                                        CommandResult::Text(ref mut result)
-                                        | CommandResult::Markdown(ref mut result) => {
-                                            if !this.stream_result {
-                                                *result = Some(result_temp.read().unwrap().clone());
-                                                cx.notify();
-                                            }
-                                        }
-                                        CommandResult::Html(ref mut result)
-                                        | CommandResult::Svg(ref mut result) => {
-                                            *result = Some(result_temp.read().unwrap().clone());
+                                    move |this: &mut CommandSticker, cx| {
+                                        if !this.stream_result || !renderer.streams() {
+                                            this.result.output =
+                                                Some(result_temp.read().unwrap().clone());
                                             cx.notify();
                                         }
                                     },
@@ -615,25 +957,175 @@ impl CommandSticker {
                     }
                 }
 
-                let _ = window.update_window_entity(
-                    &entity,
-                    move |this: &mut CommandSticker, window, cx| {
-                        this.process = None;
-                        this.stopping = false;
-                        this.result_html_entity = match &this.result {
-                            CommandResult::Html(Some(x)) => {
-                                Some(cx.new(|cx| SimpleWebView::new(x.as_str(), window, cx)))
-                            }
-                            _ => None,
-                        };
-                        this.save_config(cx);
-                        cx.notify();
-                    },
-                );
+                let _ = window.update_entity(&entity, move |this: &mut CommandSticker, cx| {
+                    if let Some(exit_code) = finished {
+                        // A stopped command is killed, that is no failure.
+                        if exit_code != Some(0) && !this.stopping {
+                            sound::play(cx, Sound::Failure);
+                        }
+                        let _ = this
+                            .sticker_events_tx
+                            .send(StickerWindowEvent::CommandDone {
+                                id: this.id,
+                                exit_code,
+                                scheduled: this.is_schedule_active(),
+                            });
+                        this.record_run(
+                            CommandRun {
+                                id: 0,
+                                sticker_id: this.id,
+                                started_at: run_started_at,
+                                duration_ms: crate::utils::time::now_unix_millis() - run_started_at,
+                                exit_code,
+                                value: last_value,
+                            },
+                            cx,
+                        );
+                    }
+
+                    this.process = None;
+                    this.stopping = false;
+                    let max_bytes = SettingsStore::get(cx).max_content_kb as usize * 1024;
+                    this.oversized_output = max_bytes > 0
+                        && this
+                            .result
+                            .output
+                            .as_ref()
+                            .is_some_and(|x| x.len() > max_bytes);
+                    this.result_webview
+                        .set_source(Self::webview_source(&this.result, &this.web_style));
+                    this.save_config(cx);
+                    cx.notify();
+                });
             })
             .detach();
     }
 
+    /// Keep the end of an oversized output, where errors and summaries usually are.
+    fn truncate_output(&mut self, note: Option<String>, cx: &mut Context<Self>) {
+        let max_bytes = SettingsStore::get(cx).max_content_kb as usize * 1024;
+        if let Some(output) = &mut self.result.output {
+            *output = truncate_to_tail(output, max_bytes, note.as_deref());
+        }
+        self.oversized_output = false;
+        self.result_webview
+            .set_source(Self::webview_source(&self.result, &self.web_style));
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn keep_oversized_output(&mut self, cx: &mut Context<Self>) {
+        self.oversized_output = false;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    /// Write the whole output into a file and keep only its end with a pointer to the file.
+    fn save_output_to_file(&mut self, cx: &mut Context<Self>) {
+        let Some(output) = self.result.output.clone() else {
+            return;
+        };
+
+        cx.spawn(async move |entity, cx| {
+            let Some(path) =
+                dialogs::save_file("Save output", "output.txt", &[("Text", &["txt", "log"])]).await
+            else {
+                return;
+            };
+
+            let _ = entity.update(cx, |this, cx| match std::fs::write(&path, output) {
+                Ok(()) => {
+                    let note = format!("full output saved to {}", path.display());
+                    this.truncate_output(Some(note), cx);
+                }
+                Err(err) => {
+                    this.error = Some(format!(
+                        "Failed to save output to {}: {err}",
+                        path.display()
+                    ));
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn oversized_output_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        let size_kb = self.result.output.as_ref().map_or(0, |x| x.len()) / 1024;
+        let max_kb = SettingsStore::get(cx).max_content_kb;
+
+        v_flex()
+            .gap_1()
+            .child(Alert::warning(
+                "oversized-output",
+                format!(
+                    "The output is {size_kb} KB, over the {max_kb} KB limit, and is not saved yet"
+                ),
+            ).small())
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("truncate-output")
+                            .label("Keep the end")
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, _, cx| this.truncate_output(None, cx))),
+                    )
+                    .child(
+                        Button::new("save-output")
+                            .label("Save to file…")
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, _, cx| this.save_output_to_file(cx))),
+                    )
+                    .child(
+                        Button::new("keep-output")
+                            .label("Save all")
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, _, cx| this.keep_oversized_output(cx))),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    fn record_run(&self, run: CommandRun, cx: &mut Context<Self>) {
+        let store = self.store.clone();
+        cx.spawn(async move |_, _| {
+            if let Err(err) = store.insert_command_run(run).await {
+                tracing::warn!(error = ?err, "Failed to save command run");
+            }
+        })
+        .detach();
+    }
+
+    fn export_history(&mut self, cx: &mut Context<Self>) {
+        let id = self.id;
+        let store = self.store.clone();
+
+        cx.spawn(async move |entity, cx| {
+            let Some(path) = dialogs::save_file(
+                "Export run history",
+                "command-history.csv",
+                &[("CSV", &["csv"])],
+            )
+            .await
+            else {
+                return;
+            };
+
+            let result = match store.get_command_runs(id).await {
+                Ok(runs) => write_runs_csv(&path, &runs),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to export run history: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+    }
+
     fn stop(&mut self, cx: &mut Context<Self>) {
         let Some(process) = self.process.as_ref().map(|x| x.clone()) else {
             cx.notify();
@@ -665,89 +1157,101 @@ impl CommandSticker {
     }
 
     fn show_editing_view(&self) -> bool {
-        let has_result = match &self.result {
-            CommandResult::Text(Some(_))
-            | CommandResult::Markdown(Some(_))
-            | CommandResult::Html(Some(_))
-            | CommandResult::Svg(Some(_)) => true,
-            CommandResult::Text(None)
-            | CommandResult::Markdown(None)
-            | CommandResult::Html(None)
-            | CommandResult::Svg(None) => false,
-        };
+        let has_result = self.result.output.is_some();
 
         return self.process.is_none() && !has_result && !self.is_schedule_active();
     }
 
+    fn pick_working_dir(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let entity = cx.entity();
+        window
+            .spawn(cx, async move |window| {
+                let Some(path) = dialogs::pick_folder("Choose a working directory").await else {
+                    return;
+                };
+
+                let _ = window.update_window_entity(&entity, |this, window, cx| {
+                    this.set_working_dir(&path.to_string_lossy(), window, cx);
+                });
+            })
+            .detach();
+    }
+
+    fn pick_watch_path(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let entity = cx.entity();
+        window
+            .spawn(cx, async move |window| {
+                let Some(path) = dialogs::pick_folder("Choose a directory to watch").await else {
+                    return;
+                };
+
+                let _ = window.update_window_entity(&entity, |this, window, cx| {
+                    this.scheduler_watch_input.update(cx, |input, cx| {
+                        input.set_value(path.to_string_lossy().to_string(), window, cx)
+                    });
+                });
+            })
+            .detach();
+    }
+
+    fn set_working_dir(&mut self, dir: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.working_dir
+            .update(cx, |input, cx| input.set_value(dir.to_string(), window, cx));
+        SettingsStore::update(cx, |s| s.remember_working_dir(dir));
+        cx.notify();
+    }
+
+    fn working_dir_field(&self, cx: &mut Context<Self>) -> AnyElement {
+        let entity = cx.entity();
+        h_flex()
+            .w_full()
+            .gap_1()
+            .child(div().flex_1().child(Input::new(&self.working_dir)))
+            .child(
+                Button::new("pick_working_dir")
+                    .icon(IconName::Folder)
+                    .small()
+                    .tooltip("Choose a directory")
+                    .on_click(cx.listener(|this, _, window, cx| this.pick_working_dir(window, cx))),
+            )
+            .child(
+                Button::new("recent_working_dirs")
+                    .icon(IconName::ArrowDown)
+                    .small()
+                    .tooltip("Recent directories")
+                    .disabled(SettingsStore::get(cx).recent_working_dirs.is_empty())
+                    .dropdown_menu(move |menu, window, cx| {
+                        let dirs = SettingsStore::get(cx).recent_working_dirs.clone();
+                        dirs.into_iter().fold(menu, |menu, dir| {
+                            menu.item(PopupMenuItem::new(dir.clone()).on_click(
+                                window.listener_for(&entity, move |this, _, window, cx| {
+                                    this.set_working_dir(&dir, window, cx);
+                                }),
+                            ))
+                        })
+                    }),
+            )
+            .into_any_element()
+    }
+
     fn form(&mut self, cx: &mut Context<Self>) -> AnyElement {
         v_form()
             .child(field().label("Command").child(Input::new(&self.command)))
             .child(
-                field().label("Render output as").child(
-                    h_flex()
-                        .gap_1()
-                        .flex_wrap()
-                        .child(
-                            Button::new("text")
-                                .label("text")
-                                .small()
-                                .when(
-                                    match self.result {
-                                        CommandResult::Text(_) => true,
-                                        _ => false,
-                                    },
-                                    |v| v.primary(),
-                                )
-                                .on_click(cx.listener(|this, _, _, _| {
-                                    this.result = CommandResult::Text(None)
-                                })),
-                        )
-                        .child(
-                            Button::new("markdown")
-                                .label("markdown")
-                                .small()
-                                .when(
-                                    match self.result {
-                                        CommandResult::Markdown(_) => true,
-                                        _ => false,
-                                    },
-                                    |v| v.primary(),
-                                )
-                                .on_click(cx.listener(|this, _, _, _| {
-                                    this.result = CommandResult::Markdown(None)
-                                })),
-                        )
-                        .child(
-                            Button::new("html")
-                                .label("html")
-                                .small()
-                                .when(
-                                    match self.result {
-                                        CommandResult::Html(_) => true,
-                                        _ => false,
-                                    },
-                                    |v| v.primary(),
-                                )
-                                .on_click(cx.listener(|this, _, _, _| {
-                                    this.result = CommandResult::Html(None)
-                                })),
-                        )
-                        .child(
-                            Button::new("svg")
-                                .label("svg")
+                field()
+                    .label("Render output as")
+                    .child(h_flex().gap_1().flex_wrap().children(
+                        result_renderers::all().into_iter().map(|renderer| {
+                            let key = renderer.key();
+                            Button::new(key)
+                                .label(renderer.label())
                                 .small()
-                                .when(
-                                    match self.result {
-                                        CommandResult::Svg(_) => true,
-                                        _ => false,
-                                    },
-                                    |v| v.primary(),
-                                )
-                                .on_click(cx.listener(|this, _, _, _| {
-                                    this.result = CommandResult::Svg(None)
-                                })),
-                        ),
-                ),
+                                .when(self.result.renderer == key, |v| v.primary())
+                                .on_click(cx.listener(move |this, _, _, _| {
+                                    this.result = CommandResult::new(key)
+                                }))
+                        }),
+                    )),
             )
             .child(
                 field().label("Stream output").child(
@@ -794,13 +1298,65 @@ impl CommandSticker {
                                                 this.set_value(cron, window, cx)
                                             });
                                         })),
+                                )
+                                .child(
+                                    Button::new("watch")
+                                        .label("file changes")
+                                        .small()
+                                        .when(
+                                            matches!(self.scheduler, Some(Scheduler::Watch(_))),
+                                            |v| v.primary(),
+                                        )
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            let path = this
+                                                .scheduler_watch_input
+                                                .read(cx)
+                                                .value()
+                                                .trim()
+                                                .to_string();
+                                            this.scheduler = Some(Scheduler::Watch(path));
+                                        })),
                                 ),
                         )
                         .when(matches!(self.scheduler, Some(Scheduler::Cron(_))), |v| {
                             v.child(Input::new(&self.scheduler_cron_input))
+                        })
+                        .when(matches!(self.scheduler, Some(Scheduler::Watch(_))), |v| {
+                            v.child(
+                                h_flex()
+                                    .w_full()
+                                    .gap_1()
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .child(Input::new(&self.scheduler_watch_input)),
+                                    )
+                                    .child(
+                                        Button::new("pick_watch_path")
+                                            .icon(IconName::Folder)
+                                            .small()
+                                            .tooltip("Choose a directory to watch")
+                                            .on_click(cx.listener(|this, _, window, cx| {
+                                                this.pick_watch_path(window, cx)
+                                            })),
+                                    ),
+                            )
                         }),
                 ),
             )
+            .child(
+                field().label("Confirm before run").child(
+                    Switch::new("confirm_before_run")
+                        .label("ask before starting the command")
+                        .small()
+                        .checked(self.confirm_before_run)
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.confirm_before_run = !this.confirm_before_run;
+                            this.save_config(cx);
+                            cx.notify();
+                        })),
+                ),
+            )
             .when(self.scheduler.is_some(), |v| {
                 v.child(
                     field().label("Run immediately").child(
@@ -817,13 +1373,17 @@ impl CommandSticker {
             .child(
                 field()
                     .label("Working directory")
-                    .child(Input::new(&self.working_dir)),
+                    .child(self.working_dir_field(cx)),
             )
             .child(
                 field()
                     .label("Environments")
                     .child(Input::new(&self.environments)),
             )
+            .when(
+                result_renderers::get(&self.result.renderer).use_webview(),
+                |v| v.child(field().label("Page style").child(self.web_style_field(cx))),
+            )
             .child(
                 field()
                     .label(format!("Padding {}", self.padding.read(cx).value().start()))
@@ -832,51 +1392,105 @@ impl CommandSticker {
             .into_any_element()
     }
 
-    fn result_view(&mut self, bg_color: Rgba, cx: &Context<Self>) -> AnyElement {
-        let empty_view = div().size_full().bg(bg_color).into_any_element();
-        let view = match &self.result {
-            CommandResult::Text(Some(x)) => div()
-                .p(px(self.padding.read(cx).value().start()))
-                .text_sm()
-                .size_full()
-                .overflow_scrollbar()
-                .bg(bg_color)
-                .child(x.clone())
-                .into_any_element(),
-            CommandResult::Text(None) => empty_view,
-            CommandResult::Markdown(Some(x)) => TextView::markdown("output", x.clone())
-                .bg(bg_color)
-                .p(px(self.padding.read(cx).value().start()))
-                .size_full()
-                .selectable(true)
-                .scrollable(true)
-                .into_any_element(),
-            CommandResult::Markdown(None) => empty_view,
-            CommandResult::Html(Some(_)) => match self.result_html_entity.clone() {
-                Some(entity) => div()
-                    .size_full()
-                    .child(entity)
-                    .p(px(self.padding.read(cx).value().start()))
-                    .into_any_element(),
-                None => empty_view,
-            },
-            CommandResult::Html(None) => empty_view,
-            CommandResult::Svg(Some(x)) => img(ImageSource::Image(Arc::new(Image::from_bytes(
-                ImageFormat::Svg,
-                x.clone().into_bytes(),
-            ))))
-            .bg(bg_color)
-            .p(px(self.padding.read(cx).value().start()))
-            .size_full()
-            .object_fit(gpui::ObjectFit::Fill)
-            .into_any_element(),
-            CommandResult::Svg(None) => empty_view,
+    fn web_style_field(&self, cx: &mut Context<Self>) -> AnyElement {
+        let preset = |id: &'static str, label: &'static str, css: &'static str| {
+            Button::new(id).label(label).small().on_click(cx.listener(
+                move |this, _, window, cx| {
+                    this.web_css_input.update(cx, |input, cx| {
+                        let value = input.value().trim().to_string();
+                        let value = match value.is_empty() {
+                            true => css.to_string(),
+                            false => format!("{value}\n{css}"),
+                        };
+                        input.set_value(value, window, cx);
+                    });
+                },
+            ))
+        };
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(preset("web_dark", "dark mode", webview::DARK_MODE_CSS))
+                    .child(preset(
+                        "web_larger",
+                        "larger text",
+                        webview::LARGER_TEXT_CSS,
+                    )),
+            )
+            .child(Input::new(&self.web_css_input))
+            .child(Input::new(&self.web_wrapper_input))
+            .child(
+                div()
+                    .text_xs()
+                    .opacity(0.75)
+                    .child("CSS added to the page, and HTML around the output"),
+            )
+            .into_any_element()
+    }
+
+    fn result_view(
+        &mut self,
+        bg_color: Rgba,
+        webview: Option<Entity<SimpleWebView>>,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let renderer = result_renderers::get(&self.result.renderer);
+        let view = match &self.result.output {
+            Some(output) => {
+                let ctx = RenderContext {
+                    output,
+                    padding: px(self.padding.read(cx).value().start()),
+                    bg: bg_color,
+                    settings: &self.settings,
+                    webview,
+                };
+
+                // Markdown is shown as plain text while searching, so matches can be marked.
+                match &mut self.search {
+                    Some(search)
+                        if renderer.text_output() && !search.input.read(cx).value().is_empty() =>
+                    {
+                        if search.searched_len != output.len() {
+                            search.refresh(output, cx);
+                        }
+                        result_renderers::render_search(
+                            ctx,
+                            &search.matches,
+                            search.current,
+                            &search.scroll,
+                            cx,
+                        )
+                    }
+                    _ => renderer.render(ctx, cx),
+                }
+            }
+            None => div().size_full().bg(bg_color).into_any_element(),
         };
 
         div().relative().size_full().child(view).into_any_element()
     }
 }
 
+impl CronTarget for CommandSticker {
+    fn set_next_run(&mut self, next: Option<String>, _: &mut Context<Self>) {
+        match next {
+            Some(next) => self.next_scheduled_at = Some(next),
+            None => self.stop_schedule(),
+        }
+    }
+
+    fn run_scheduled(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.process.is_none() && !self.stopping {
+            self.stop(cx);
+            self.run(window, cx);
+        }
+    }
+}
+
 impl super::Sticker for CommandSticker {
     fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
         self.save_config(cx)
@@ -897,6 +1511,27 @@ impl super::Sticker for CommandSticker {
     fn disable_color_picker(&self) -> bool {
         !self.show_editing_view()
     }
+
+    fn has_text_output(&self) -> bool {
+        !self.show_editing_view()
+            && self.result.output.is_some()
+            && result_renderers::get(&self.result.renderer).text_output()
+    }
+
+    fn apply_settings(&mut self, settings: &StickerSettings) {
+        self.settings = settings.clone();
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        if !self.is_schedule_active() {
+            return self.process.is_some().then(|| "running".to_string());
+        }
+        Some(match (&self.scheduler, &self.next_scheduled_at) {
+            (Some(Scheduler::Watch(path)), _) => format!("watching {path}"),
+            (_, Some(next)) => format!("scheduled, next run at {next}"),
+            (_, None) => "scheduled".to_string(),
+        })
+    }
 }
 
 impl Render for CommandSticker {
@@ -910,6 +1545,9 @@ impl Render for CommandSticker {
 
         let mut root = v_flex().relative().size_full();
 
+        let webview = self
+            .result_webview
+            .sync(!self.show_editing_view(), window, cx);
         if self.show_editing_view() {
             root = root
                 .bg(bg_color)
@@ -928,18 +1566,39 @@ impl Render for CommandSticker {
                             .bg(transparent_white())
                             .border_0()
                             .on_click(cx.listener(|this, _, window, cx| {
-                                this.start(window, cx);
+                                this.request_start(window, cx);
                             })),
                     ),
                 );
         } else {
-            root = root.child(
-                div().h_full().flex_shrink().overflow_hidden().child(
-                    v_flex()
-                        .overflow_y_scrollbar()
-                        .child(self.result_view(bg_color, cx)),
-                ),
-            );
+            let search_bar = self.search_bar(bg_color, cx);
+            root = root
+                .track_focus(&self.focus_handle)
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _, window, _| {
+                        if this.search.is_none() {
+                            window.focus(&this.focus_handle);
+                        }
+                    }),
+                )
+                .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                    let modifiers = &event.keystroke.modifiers;
+                    if (modifiers.control || modifiers.platform)
+                        && event.keystroke.key.eq_ignore_ascii_case("f")
+                        && this.has_text_output()
+                    {
+                        this.open_search(window, cx);
+                    }
+                }))
+                .children(search_bar)
+                .child(
+                    div().h_full().flex_shrink().overflow_hidden().child(
+                        v_flex()
+                            .overflow_y_scrollbar()
+                            .child(self.result_view(bg_color, webview, cx)),
+                    ),
+                );
 
             if self.process.is_some() || self.is_schedule_active() {
                 if window.is_window_hovered() && (!self.stopping || self.is_schedule_active()) {
@@ -974,15 +1633,8 @@ impl Render for CommandSticker {
                                 .bg(transparent_white())
                                 .border_0()
                                 .on_click(cx.listener(|this, _, _, cx| {
-                                    this.result_html_entity = None;
-                                    match this.result {
-                                        CommandResult::Text(ref mut result)
-                                        | CommandResult::Markdown(ref mut result)
-                                        | CommandResult::Html(ref mut result)
-                                        | CommandResult::Svg(ref mut result) => {
-                                            *result = None;
-                                        }
-                                    }
+                                    this.result_webview.set_source(None);
+                                    this.result.output = None;
                                     cx.notify();
                                 })),
                         )
@@ -992,14 +1644,27 @@ impl Render for CommandSticker {
                                 .bg(transparent_white())
                                 .border_0()
                                 .on_click(cx.listener(|this, _, window, cx| {
-                                    this.start(window, cx);
+                                    this.request_start(window, cx);
+                                })),
+                        )
+                        .child(
+                            Button::new("export_history")
+                                .icon(IconName::Download)
+                                .bg(transparent_white())
+                                .border_0()
+                                .tooltip("Export run history as CSV")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.export_history(cx);
                                 })),
                         ),
                 );
             }
         }
 
-        root.when_some(self.error.as_ref(), |view, msg| {
+        root.when(self.oversized_output, |view| {
+            view.child(self.oversized_output_view(cx))
+        })
+        .when_some(self.error.as_ref(), |view, msg| {
             view.child(Alert::error("error", msg.as_str()).bg(bg_color))
         })
         .when(self.process.is_some(), |view| {
@@ -1022,6 +1687,40 @@ impl Render for CommandSticker {
     }
 }
 
+fn write_runs_csv(path: &std::path::Path, runs: &[CommandRun]) -> anyhow::Result<()> {
+    use anyhow::Context as _;
+
+    let mut csv = String::from("timestamp,duration_ms,exit_code,value\n");
+    for run in runs {
+        let timestamp = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(run.started_at)
+            .map(|x| {
+                x.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let exit_code = run.exit_code.map(|x| x.to_string()).unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            timestamp,
+            run.duration_ms,
+            exit_code,
+            csv_field(&run.value)
+        ));
+    }
+
+    std::fs::write(path, csv).context("write csv file")
+}
+
+/// Quote a CSV field when it has a separator, quote or line break.
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 fn kill_process(child: &mut std::process::Child) {
     #[cfg(windows)]
     {
@@ -1043,3 +1742,25 @@ fn kill_process(child: &mut std::process::Child) {
         let _ = child.kill();
     }
 }
+
+/// The last `max_bytes` of the text on a line start, after a note saying how much was cut.
+fn truncate_to_tail(text: &str, max_bytes: usize, note: Option<&str>) -> String {
+    if max_bytes == 0 || text.len() <= max_bytes {
+        return text.to_string();
+    }
+
+    let mut start = text.len() - max_bytes;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    if let Some(newline) = text[start..].find('\n') {
+        start += newline + 1;
+    }
+
+    let cut = format!("… {} KB cut", start / 1024);
+    let note = match note {
+        Some(note) => format!("{cut}, {note} …"),
+        None => format!("{cut} …"),
+    };
+    format!("{note}\n{}", &text[start..])
+}
@@ -0,0 +1,40 @@
+//! Turning a sticker into another type, keeping its id, color and bounds.
+
+use crate::model::sticker::StickerType;
+
+use super::kanban;
+
+/// Converts the stored content of one sticker type into the content of another.
+pub struct Conversion {
+    pub from: StickerType,
+    pub to: StickerType,
+    pub convert: fn(&str) -> anyhow::Result<String>,
+    /// How many lines of the content the conversion leaves out, the user is asked first
+    /// when there are any.
+    pub dropped_lines: fn(&str) -> usize,
+}
+
+/// Every supported pair, add an entry here to offer another one.
+pub const CONVERSIONS: &[Conversion] = &[
+    Conversion {
+        from: StickerType::Markdown,
+        to: StickerType::Kanban,
+        convert: kanban::content_from_markdown,
+        dropped_lines: kanban::lines_dropped_from_markdown,
+    },
+    Conversion {
+        from: StickerType::Kanban,
+        to: StickerType::Markdown,
+        convert: kanban::content_to_markdown,
+        dropped_lines: nothing_dropped,
+    },
+];
+
+fn nothing_dropped(_: &str) -> usize {
+    0
+}
+
+/// Conversions offered for a sticker of the type.
+pub fn conversions_from(sticker_type: StickerType) -> impl Iterator<Item = &'static Conversion> {
+    CONVERSIONS.iter().filter(move |x| x.from == sticker_type)
+}
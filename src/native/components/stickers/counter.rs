@@ -0,0 +1,306 @@
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputState},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct CounterContent {
+    /// What is counted, e.g. "Interruptions".
+    label: String,
+    count: i64,
+    step: i64,
+}
+
+impl Default for CounterContent {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            count: 0,
+            step: 1,
+        }
+    }
+}
+
+pub struct CounterSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: CounterContent,
+
+    label_input: Entity<InputState>,
+    step_input: Entity<InputState>,
+    editing: bool,
+
+    error: Option<String>,
+}
+
+impl CounterSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<CounterContent>(content).unwrap_or_default();
+
+        let label_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.label.clone())
+                .placeholder("e.g. Interruptions")
+        });
+        let step_input =
+            cx.new(|cx| InputState::new(window, cx).default_value(content.step.to_string()));
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            label_input,
+            step_input,
+            editing: false,
+            error: None,
+        }
+    }
+
+    /// "Interruptions: 3", so the count shows up in the main window.
+    fn title(&self) -> String {
+        let label = self.content.label.trim();
+        let label = if label.is_empty() { "Count" } else { label };
+        format!("{label}: {}", self.content.count)
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize counter sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save counter sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save counter sticker: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn set_count(&mut self, count: i64, cx: &mut Context<Self>) {
+        self.content.count = count;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn apply_form(&mut self, cx: &mut Context<Self>) {
+        let Ok(step @ 1..) = self.step_input.read(cx).value().trim().parse::<i64>() else {
+            self.error = Some("Step must be a positive number".to_string());
+            cx.notify();
+            return;
+        };
+
+        self.content.label = self.label_input.read(cx).value().trim().to_string();
+        self.content.step = step;
+        self.editing = false;
+        self.error = None;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn counter_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let viewport = window.viewport_size();
+        let digits = self.content.count.to_string().len().max(2) as f32;
+        let font_size = (viewport.height * 0.4).min(viewport.width * 1.4 / digits);
+        let step = self.content.step;
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .child(
+                v_flex()
+                    .flex_1()
+                    .items_center()
+                    .justify_center()
+                    .window_drag_area()
+                    .when(!self.content.label.is_empty(), |view| {
+                        view.child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(self.content.label.clone()),
+                        )
+                    })
+                    .child(
+                        div()
+                            .text_size(font_size)
+                            .line_height(font_size * 1.1)
+                            .font_bold()
+                            .font_family(cx.theme().mono_font_family.clone())
+                            .child(self.content.count.to_string()),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_center()
+                    .child(
+                        Button::new("decrement")
+                            .icon(IconName::Minus)
+                            .small()
+                            .tooltip(format!("−{step}"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.set_count(this.content.count.saturating_sub(step), cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("increment")
+                            .icon(IconName::Plus)
+                            .small()
+                            .primary()
+                            .tooltip(format!("+{step}"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.set_count(this.content.count.saturating_add(step), cx)
+                            })),
+                    ),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("edit")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Change label and step")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.editing = true;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Button::new("reset")
+                                .icon(IconName::Refresh)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Reset to 0")
+                                .on_click(cx.listener(|this, _, _, cx| this.set_count(0, cx))),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field()
+                    .label("Label")
+                    .child(Input::new(&self.label_input).small()),
+            )
+            .child(
+                field()
+                    .label("Step")
+                    .child(Input::new(&self.step_input).small()),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.apply_form(cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for CounterSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(120, 120)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(200, 200)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for CounterSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            body = body.child(self.counter_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("counter-error", msg.as_str()).small())
+        })
+    }
+}
@@ -0,0 +1,97 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use gpui::{Context, Window};
+
+/// The wait for the next run is slept in chunks of this, so cancelling is quick.
+const MAX_SLEEP_CHUNK_MS: u64 = 250;
+
+/// A sticker which runs something on a cron schedule, see [`spawn`].
+pub trait CronTarget: Sized + 'static {
+    /// The time of the next run changed, `None` once the schedule has no more runs.
+    fn set_next_run(&mut self, next: Option<String>, cx: &mut Context<Self>);
+
+    /// The scheduled time was reached.
+    fn run_scheduled(&mut self, window: &mut Window, cx: &mut Context<Self>);
+}
+
+/// Parse a cron expression with seconds, e.g. `0 */1 * * * *`, into a message for the user.
+pub fn parse(expr: &str) -> Result<cron::Schedule, String> {
+    if expr.is_empty() {
+        return Err("Cron expression cannot be empty".to_string());
+    }
+    cron::Schedule::from_str(expr).map_err(|err| format!("Invalid cron expression: {err}"))
+}
+
+/// Call [`CronTarget::run_scheduled`] at every upcoming time of the schedule until
+/// the returned flag is set.
+pub fn spawn<T: CronTarget>(
+    schedule: cron::Schedule,
+    window: &Window,
+    cx: &mut Context<T>,
+) -> Arc<AtomicBool> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let task_cancel = cancel.clone();
+    let entity = cx.entity();
+
+    window
+        .spawn(cx, async move |window| {
+            let cancel = task_cancel;
+            loop {
+                if cancel.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let now = chrono::Local::now();
+                let next = schedule.upcoming(chrono::Local).next();
+                let Some(next) = next else {
+                    let _ = window.update_entity(&entity, |this, cx| this.set_next_run(None, cx));
+                    break;
+                };
+
+                let next_str = next.format("%Y-%m-%d %H:%M:%S").to_string();
+                let _ = window.update_entity(&entity, |this, cx| {
+                    this.set_next_run(Some(next_str), cx);
+                    cx.notify();
+                });
+
+                // Compute delay with signed math first to avoid underflow when
+                // `next` is already in the past.
+                let delay_ms_i64 = next.timestamp_millis() - now.timestamp_millis();
+                if delay_ms_i64 <= 0 {
+                    let _ = window.update_window_entity(&entity, |this, window, cx| {
+                        this.run_scheduled(window, cx);
+                    });
+                    continue;
+                }
+
+                // Make the wait cancellable: instead of awaiting one long timer (which
+                // can't be interrupted), sleep in small chunks and check `cancel`.
+                let mut remaining_ms = delay_ms_i64 as u64;
+                while remaining_ms > 0 {
+                    if cancel.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let chunk = remaining_ms.min(MAX_SLEEP_CHUNK_MS);
+                    window
+                        .background_executor()
+                        .timer(Duration::from_millis(chunk))
+                        .await;
+                    remaining_ms = remaining_ms.saturating_sub(chunk);
+                }
+
+                if cancel.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let _ = window.update_window_entity(&entity, |this, window, cx| {
+                    this.run_scheduled(window, cx);
+                });
+            }
+        })
+        .detach();
+
+    cancel
+}
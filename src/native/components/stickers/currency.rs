@@ -0,0 +1,467 @@
+use anyhow::Context as _;
+use async_compat::CompatExt;
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::Button,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+const RATES_URL: &str = "https://open.er-api.com/v6/latest/USD";
+/// Cached rates older than this are fetched again when the sticker opens.
+const RATES_MAX_AGE_MS: i64 = 6 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct CurrencyContent {
+    amount: String,
+    from: String,
+    to: String,
+    /// Last fetched rates, units of each currency for one `base`, kept for offline use.
+    base: String,
+    rates: BTreeMap<String, f64>,
+    fetched_at: Option<i64>,
+}
+
+impl Default for CurrencyContent {
+    fn default() -> Self {
+        Self {
+            amount: "1".to_string(),
+            from: "USD".to_string(),
+            to: "EUR".to_string(),
+            base: String::new(),
+            rates: BTreeMap::new(),
+            fetched_at: None,
+        }
+    }
+}
+
+impl CurrencyContent {
+    fn rate(&self, code: &str) -> Option<f64> {
+        if code == self.base {
+            return Some(1.0);
+        }
+        self.rates.get(code).copied().filter(|x| *x > 0.0)
+    }
+
+    fn amount(&self) -> Option<f64> {
+        let amount = self.amount.replace([',', '_', ' '], "");
+        amount.parse::<f64>().ok().filter(|x| x.is_finite())
+    }
+
+    fn converted(&self) -> Option<f64> {
+        Some(self.amount()? / self.rate(&self.from)? * self.rate(&self.to)?)
+    }
+
+    fn is_stale(&self) -> bool {
+        self.fetched_at.is_none_or(|fetched_at| {
+            crate::utils::time::now_unix_millis() - fetched_at > RATES_MAX_AGE_MS
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RatesResponse {
+    base_code: String,
+    rates: BTreeMap<String, f64>,
+}
+
+pub struct CurrencySticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: CurrencyContent,
+
+    amount_input: Entity<InputState>,
+    from_input: Entity<InputState>,
+    to_input: Entity<InputState>,
+    loading: bool,
+
+    error: Option<String>,
+}
+
+impl CurrencySticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<CurrencyContent>(content).unwrap_or_default();
+
+        let amount_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.amount.clone())
+                .placeholder("Amount")
+        });
+        let from_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.from.clone())
+                .placeholder("USD")
+        });
+        let to_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.to.clone())
+                .placeholder("EUR")
+        });
+
+        for input in [&amount_input, &from_input, &to_input] {
+            cx.subscribe(input, |this, _, event: &InputEvent, cx| match event {
+                InputEvent::Change => {
+                    this.read_inputs(cx);
+                    cx.notify();
+                }
+                InputEvent::PressEnter { .. } | InputEvent::Blur => {
+                    this.save_config(cx);
+                }
+                _ => {}
+            })
+            .detach();
+        }
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            amount_input,
+            from_input,
+            to_input,
+            loading: false,
+            error: None,
+        };
+        if this.content.is_stale() {
+            this.fetch_rates(cx);
+        }
+        this
+    }
+
+    /// "100 USD → EUR", so the pair shows up in the main window.
+    fn title(&self) -> String {
+        format!(
+            "{} {} → {}",
+            self.content.amount.trim(),
+            self.content.from,
+            self.content.to
+        )
+    }
+
+    fn read_inputs(&mut self, cx: &mut Context<Self>) {
+        self.content.amount = self.amount_input.read(cx).value().trim().to_string();
+        self.content.from = self.from_input.read(cx).value().trim().to_uppercase();
+        self.content.to = self.to_input.read(cx).value().trim().to_uppercase();
+    }
+
+    fn swap(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (from, to) = (self.content.to.clone(), self.content.from.clone());
+        self.from_input
+            .update(cx, |input, cx| input.set_value(from, window, cx));
+        self.to_input
+            .update(cx, |input, cx| input.set_value(to, window, cx));
+        self.read_inputs(cx);
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize currency sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save currency sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save currency sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn fetch_rates(&mut self, cx: &mut Context<Self>) {
+        if self.loading {
+            return;
+        }
+
+        self.loading = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let fetched = cx
+                .background_executor()
+                .spawn(async move { load_rates().compat().await })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                this.loading = false;
+                match fetched {
+                    Ok(rates) => {
+                        this.content.base = rates.base_code;
+                        this.content.rates = rates.rates;
+                        this.content.fetched_at = Some(crate::utils::time::now_unix_millis());
+                        this.error = None;
+                        this.save_config(cx);
+                    }
+                    // The cached rates still work, the footer tells how old they are.
+                    Err(err) if !this.content.rates.is_empty() => {
+                        tracing::warn!(id = this.id, error = ?err, "Failed to refresh exchange rates");
+                    }
+                    Err(err) => {
+                        tracing::warn!(id = this.id, error = ?err, "Failed to load exchange rates");
+                        this.error = Some(format!("Failed to load exchange rates: {err:#}"));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn result_text(&self) -> String {
+        if self.content.rates.is_empty() {
+            return "…".to_string();
+        }
+        for code in [&self.content.from, &self.content.to] {
+            if self.content.rate(code).is_none() {
+                return format!("Unknown currency {code}");
+            }
+        }
+        match self.content.converted() {
+            Some(value) => format!("{} {}", format_amount(value), self.content.to),
+            None => "Enter an amount".to_string(),
+        }
+    }
+
+    fn footer_text(&self) -> String {
+        let fetched = self
+            .content
+            .fetched_at
+            .map(crate::utils::time::format_unix_millis)
+            .unwrap_or_else(|| "never".to_string());
+        match self.content.is_stale() && !self.loading {
+            true => format!("Offline, rates from {fetched}"),
+            false => format!("Rates from {fetched}"),
+        }
+    }
+}
+
+impl super::Sticker for CurrencySticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 140)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(260, 180)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for CurrencySticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let body = v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .bg(Rgba {
+                a: 0.85,
+                ..self.color.bg()
+            })
+            .child(Input::new(&self.amount_input).small())
+            .child(
+                h_flex()
+                    .gap_1()
+                    .items_center()
+                    .child(Input::new(&self.from_input).small().flex_1())
+                    .child(
+                        Button::new("swap")
+                            .icon(IconName::ArrowRightLeft)
+                            .bg(transparent_white())
+                            .border_0()
+                            .xsmall()
+                            .tooltip("Swap currencies")
+                            .on_click(cx.listener(|this, _, window, cx| this.swap(window, cx))),
+                    )
+                    .child(Input::new(&self.to_input).small().flex_1()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .text_xl()
+                    .font_bold()
+                    .window_drag_area()
+                    .child(self.result_text()),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .items_center()
+                    .justify_between()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(self.footer_text())
+                    .when(window.is_window_hovered(), |view| {
+                        view.child(
+                            Button::new("refresh")
+                                .icon(IconName::Refresh)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .loading(self.loading)
+                                .tooltip("Fetch the latest rates")
+                                .on_click(cx.listener(|this, _, _, cx| this.fetch_rates(cx))),
+                        )
+                    }),
+            );
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("currency-error", msg.as_str()).small())
+        })
+    }
+}
+
+async fn load_rates() -> anyhow::Result<RatesResponse> {
+    let response = reqwest::get(RATES_URL)
+        .await
+        .and_then(|x| x.error_for_status())
+        .with_context(|| format!("download {RATES_URL}"))?;
+    let text = response.text().await.context("read rates response")?;
+    serde_json::from_str(&text).context("parse rates response")
+}
+
+/// Two decimals, more for small values so e.g. 0.0042 BTC does not show as 0.00.
+fn format_amount(value: f64) -> String {
+    let decimals = match value.abs() {
+        x if x == 0.0 || x >= 1.0 => 2,
+        x => (2 - x.log10().floor() as i32).clamp(2, 8) as usize,
+    };
+    format!("{value:.decimals$}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(amount: &str, from: &str, to: &str) -> CurrencyContent {
+        CurrencyContent {
+            amount: amount.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            base: "USD".to_string(),
+            rates: [("EUR", 0.5), ("JPY", 150.0), ("XXX", 0.0)]
+                .into_iter()
+                .map(|(code, rate)| (code.to_string(), rate))
+                .collect(),
+            fetched_at: None,
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(content("10", "USD", "EUR").converted(), Some(5.0));
+        assert_eq!(content("1", "EUR", "JPY").converted(), Some(300.0));
+        assert_eq!(
+            content("1,000", "JPY", "USD").converted(),
+            Some(1000.0 / 150.0)
+        );
+        assert_eq!(content("1", "USD", "USD").converted(), Some(1.0));
+
+        assert_eq!(content("abc", "USD", "EUR").converted(), None);
+        assert_eq!(content("1", "USD", "GBP").converted(), None);
+        // A zero rate would divide by zero.
+        assert_eq!(content("1", "XXX", "EUR").converted(), None);
+    }
+
+    #[test]
+    fn stale_rates() {
+        let now = crate::utils::time::now_unix_millis();
+        let mut content = content("1", "USD", "EUR");
+        assert!(content.is_stale());
+        content.fetched_at = Some(now);
+        assert!(!content.is_stale());
+        content.fetched_at = Some(now - RATES_MAX_AGE_MS - 1);
+        assert!(content.is_stale());
+    }
+
+    #[test]
+    fn parse_rates_response() {
+        let text = r#"{"result":"success","base_code":"USD","rates":{"USD":1,"EUR":0.92}}"#;
+        let response = serde_json::from_str::<RatesResponse>(text).unwrap();
+        assert_eq!(response.base_code, "USD");
+        assert_eq!(response.rates.get("EUR"), Some(&0.92));
+
+        // Older stickers saved without the cached rates.
+        let content = serde_json::from_str::<CurrencyContent>(r#"{"amount":"5"}"#).unwrap();
+        assert_eq!(content.amount, "5");
+        assert_eq!(content.from, "USD");
+        assert!(content.rates.is_empty());
+    }
+
+    #[test]
+    fn amounts() {
+        assert_eq!(format_amount(0.0), "0.00");
+        assert_eq!(format_amount(1234.5), "1234.50");
+        assert_eq!(format_amount(0.5), "0.500");
+        assert_eq!(format_amount(0.0042), "0.00420");
+        assert_eq!(format_amount(-0.0042), "-0.00420");
+        assert_eq!(format_amount(1e-12), "0.00000000");
+    }
+}
@@ -0,0 +1,611 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use anyhow::Context as _;
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Hsla, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    green_500, h_flex,
+    input::{Input, InputState},
+    red_500,
+    scroll::ScrollableElement,
+    v_flex, yellow_500,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::dialogs;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+const MIN_REFRESH_SECS: u32 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct GitContent {
+    /// Any directory inside the work tree, `~/` is the home directory.
+    path: String,
+    refresh_secs: u32,
+}
+
+impl Default for GitContent {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            refresh_secs: 30,
+        }
+    }
+}
+
+/// What `git status` says about the work tree.
+#[derive(Debug, Clone, Default)]
+struct GitStatus {
+    /// `None` on a detached HEAD.
+    branch: Option<String>,
+    upstream: Option<String>,
+    ahead: u32,
+    behind: u32,
+    files: Vec<GitFile>,
+}
+
+#[derive(Debug, Clone)]
+struct GitFile {
+    /// Index and work tree state, e.g. `M.`, `.M`, `A.`, `??` or `UU`.
+    state: String,
+    path: String,
+}
+
+impl GitFile {
+    fn color(&self) -> Hsla {
+        match self.state.as_str() {
+            "??" => yellow_500(),
+            state if state.starts_with('U') || state.ends_with('U') || state == "AA" => red_500(),
+            state if state.ends_with('.') => green_500(),
+            _ => yellow_500(),
+        }
+    }
+
+    /// `M`, `A`, `D`, `R`, `?`, the work tree state first as it is what changes most.
+    fn letter(&self) -> char {
+        let mut chars = self.state.chars();
+        let (index, work_tree) = (chars.next().unwrap_or('.'), chars.next().unwrap_or('.'));
+        if work_tree != '.' { work_tree } else { index }
+    }
+}
+
+pub struct GitSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: GitContent,
+
+    status: Option<GitStatus>,
+    loaded_at: Option<i64>,
+    loading: bool,
+    ticking: bool,
+
+    path_input: Entity<InputState>,
+    refresh_input: Entity<InputState>,
+    editing: bool,
+
+    error: Option<String>,
+}
+
+impl GitSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<GitContent>(content).unwrap_or_default();
+
+        let path_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.path.clone())
+                .placeholder("~/projects/rustickers")
+        });
+        let refresh_input = cx
+            .new(|cx| InputState::new(window, cx).default_value(content.refresh_secs.to_string()));
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            editing: content.path.trim().is_empty(),
+            content,
+            status: None,
+            loaded_at: None,
+            loading: false,
+            ticking: false,
+            path_input,
+            refresh_input,
+            error: None,
+        };
+        if !this.editing {
+            this.reload(cx);
+        }
+        this
+    }
+
+    /// "rustickers · main · 3 changed", so the state shows up in the main window.
+    fn title(&self) -> String {
+        let name = repo_label(&self.content.path);
+        let Some(status) = &self.status else {
+            return name;
+        };
+        let branch = status.branch.as_deref().unwrap_or("detached");
+        match status.files.len() {
+            0 => format!("{name} · {branch}"),
+            count => format!("{name} · {branch} · {count} changed"),
+        }
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize Git sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save Git sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save Git sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        if self.loading {
+            return;
+        }
+
+        self.loading = true;
+        cx.notify();
+
+        let path = repo_path(&self.content.path);
+        cx.spawn(async move |this, cx| {
+            let loaded = cx
+                .background_executor()
+                .spawn(async move { git_status(&path) })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                this.loading = false;
+                this.loaded_at = Some(crate::utils::time::now_unix_millis());
+                match loaded {
+                    Ok(status) => {
+                        let title_changed = this.status.as_ref().is_none_or(|old| {
+                            old.branch != status.branch || old.files.len() != status.files.len()
+                        });
+                        this.status = Some(status);
+                        this.error = None;
+                        if title_changed {
+                            this.save_config(cx);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::warn!(id = this.id, error = ?err, "Failed to read Git status");
+                        this.error = Some(format!("{err:#}"));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Reload once the refresh interval passed.
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        let refresh = Duration::from_secs(self.content.refresh_secs.max(MIN_REFRESH_SECS) as u64);
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(refresh).await;
+            let _ = this.update(cx, |this, cx| {
+                this.ticking = false;
+                this.reload(cx);
+            });
+        })
+        .detach();
+    }
+
+    fn pick_path(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let entity = cx.entity();
+        window
+            .spawn(cx, async move |window| {
+                let Some(path) = dialogs::pick_folder("Choose a Git repository").await else {
+                    return;
+                };
+
+                let _ = window.update_window_entity(&entity, |this, window, cx| {
+                    this.path_input.update(cx, |input, cx| {
+                        input.set_value(path.to_string_lossy().to_string(), window, cx)
+                    });
+                });
+            })
+            .detach();
+    }
+
+    fn apply_form(&mut self, cx: &mut Context<Self>) {
+        let path = self.path_input.read(cx).value().trim().to_string();
+        let refresh_secs = self.refresh_input.read(cx).value().trim().parse::<u32>();
+
+        if path.is_empty() {
+            self.error = Some("Please enter the path of a repository".to_string());
+            cx.notify();
+            return;
+        }
+        if !repo_path(&path).is_dir() {
+            self.error = Some(format!("{path} is not a directory"));
+            cx.notify();
+            return;
+        }
+        let Ok(refresh_secs) = refresh_secs else {
+            self.error = Some("Refresh interval must be a whole number of seconds".to_string());
+            cx.notify();
+            return;
+        };
+        if refresh_secs < MIN_REFRESH_SECS {
+            self.error = Some(format!("Refresh at most every {MIN_REFRESH_SECS} seconds"));
+            cx.notify();
+            return;
+        }
+
+        self.content = GitContent { path, refresh_secs };
+        self.editing = false;
+        self.error = None;
+        self.status = None;
+        self.save_config(cx);
+        self.reload(cx);
+        cx.notify();
+    }
+
+    fn status_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let muted = cx.theme().muted_foreground;
+        let mono = cx.theme().mono_font_family.clone();
+
+        let header = match &self.status {
+            Some(status) => {
+                let branch = status.branch.as_deref().unwrap_or("detached HEAD");
+                let tracking = match &status.upstream {
+                    Some(upstream) => format!("↑{} ↓{} {upstream}", status.ahead, status.behind),
+                    None => "no upstream".to_string(),
+                };
+                let changes = match status.files.len() {
+                    0 => "clean".to_string(),
+                    1 => "1 changed file".to_string(),
+                    count => format!("{count} changed files"),
+                };
+                v_flex()
+                    .gap_1()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(
+                                div()
+                                    .text_lg()
+                                    .font_family(mono.clone())
+                                    .truncate()
+                                    .child(branch.to_string()),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(if status.files.is_empty() {
+                                        green_500()
+                                    } else {
+                                        yellow_500()
+                                    })
+                                    .child(changes),
+                            ),
+                    )
+                    .child(div().text_xs().text_color(muted).child(tracking))
+            }
+            None => v_flex().child(div().text_sm().text_color(muted).child(if self.loading {
+                "Loading…"
+            } else {
+                ""
+            })),
+        };
+
+        let files = self
+            .status
+            .as_ref()
+            .map(|x| x.files.as_slice())
+            .unwrap_or_default();
+        let list = v_flex().children(files.iter().map(|file| {
+            h_flex()
+                .gap_2()
+                .text_xs()
+                .font_family(mono.clone())
+                .child(
+                    div()
+                        .w(px(12.0))
+                        .text_color(file.color())
+                        .child(file.letter().to_string()),
+                )
+                .child(div().flex_1().truncate().child(file.path.clone()))
+        }));
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .child(div().window_drag_area().child(header))
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(v_flex().overflow_y_scrollbar().child(list)),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("edit")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Change repository or interval")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.editing = true;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Button::new("refresh")
+                                .icon(IconName::Refresh)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .loading(self.loading)
+                                .tooltip("Reload now")
+                                .on_click(cx.listener(|this, _, _, cx| this.reload(cx))),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field().label("Repository").child(
+                    h_flex()
+                        .w_full()
+                        .gap_1()
+                        .child(div().flex_1().child(Input::new(&self.path_input).small()))
+                        .child(
+                            Button::new("browse")
+                                .icon(IconName::Folder)
+                                .small()
+                                .tooltip("Choose a folder")
+                                .on_click(
+                                    cx.listener(|this, _, window, cx| this.pick_path(window, cx)),
+                                ),
+                        ),
+                ),
+            )
+            .child(
+                field()
+                    .label("Refresh every (seconds)")
+                    .child(Input::new(&self.refresh_input).small()),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.apply_form(cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for GitSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 120)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(300, 320)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        let loaded_at = self.loaded_at?;
+        Some(format!(
+            "refresh every {} s, last loaded {}",
+            self.content.refresh_secs,
+            crate::utils::time::format_unix_millis(loaded_at)
+        ))
+    }
+}
+
+impl Render for GitSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            self.spawn_for_tick(cx);
+            body = body.child(self.status_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("git-error", msg.as_str()).small())
+        })
+    }
+}
+
+fn repo_path(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => directories::UserDirs::new()
+            .map(|dirs| dirs.home_dir().join(rest))
+            .unwrap_or_else(|| path.into()),
+        None => path.into(),
+    }
+}
+
+/// "~/projects/rustickers/" -> "rustickers".
+fn repo_label(path: &str) -> String {
+    let name = path
+        .trim()
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or("");
+    if name.is_empty() {
+        "Git".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn git_command() -> Command {
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let mut cmd = Command::new("git");
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd
+}
+
+#[cfg(not(target_os = "windows"))]
+fn git_command() -> Command {
+    Command::new("git")
+}
+
+/// Run `git status` in the repository, blocking, so call it off the main thread.
+fn git_status(path: &Path) -> anyhow::Result<GitStatus> {
+    let output = git_command()
+        .arg("-C")
+        .arg(path)
+        .args(["status", "--porcelain=v2", "--branch", "-z"])
+        .stdin(Stdio::null())
+        .output()
+        .context("run git, is it installed?")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", stderr.trim());
+    }
+
+    Ok(parse_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git status --porcelain=v2 --branch -z`, entries end with NUL and a rename
+/// is followed by its original path.
+fn parse_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut entries = output.split('\0').filter(|x| !x.is_empty());
+
+    while let Some(entry) = entries.next() {
+        if let Some(header) = entry.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for count in value.split_whitespace() {
+                        if let Some(ahead) = count.strip_prefix('+') {
+                            status.ahead = ahead.parse().unwrap_or(0);
+                        } else if let Some(behind) = count.strip_prefix('-') {
+                            status.behind = behind.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        // The path is the last field, it may contain spaces.
+        let (state, path) = match entry.split_once(' ') {
+            Some(("1", rest)) => (rest.get(..2), rest.splitn(8, ' ').nth(7)),
+            Some(("2", rest)) => {
+                // The original path of the rename comes as an entry of its own.
+                let _ = entries.next();
+                (rest.get(..2), rest.splitn(9, ' ').nth(8))
+            }
+            Some(("u", rest)) => (rest.get(..2), rest.splitn(10, ' ').nth(9)),
+            Some(("?", path)) => (Some("??"), Some(path)),
+            _ => (None, None),
+        };
+        if let (Some(state), Some(path)) = (state, path) {
+            status.files.push(GitFile {
+                state: state.to_string(),
+                path: path.to_string(),
+            });
+        }
+    }
+
+    status
+}
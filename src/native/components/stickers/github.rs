@@ -0,0 +1,660 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use async_compat::CompatExt;
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputState},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::SettingsStore;
+
+const GITHUB_API: &str = "https://api.github.com";
+/// Redraw so the relative times stay current.
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum GitHubMode {
+    #[default]
+    Notifications,
+    Assigned,
+}
+
+impl GitHubMode {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Notifications => "Unread notifications",
+            Self::Assigned => "Issues assigned to me",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct GitHubContent {
+    mode: GitHubMode,
+    refresh_mins: u32,
+}
+
+impl Default for GitHubContent {
+    fn default() -> Self {
+        Self {
+            mode: GitHubMode::Notifications,
+            refresh_mins: 5,
+        }
+    }
+}
+
+/// A notification or an issue, whatever the mode lists.
+#[derive(Debug, Clone)]
+struct GitHubItem {
+    /// Thread id of a notification, marked as read when it is opened.
+    thread_id: Option<String>,
+    title: String,
+    repository: String,
+    kind: String,
+    url: String,
+    updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+pub struct GitHubSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: GitHubContent,
+
+    items: Option<Vec<GitHubItem>>,
+    loaded_at: Option<i64>,
+    loading: bool,
+    ticking: bool,
+
+    token_input: Entity<InputState>,
+    refresh_input: Entity<InputState>,
+    form_mode: GitHubMode,
+    editing: bool,
+
+    error: Option<String>,
+}
+
+impl GitHubSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let is_new = content.trim().is_empty();
+        let content = serde_json::from_str::<GitHubContent>(content).unwrap_or_default();
+        let token = SettingsStore::get(cx).github_token.clone();
+
+        let token_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(token.clone())
+                .placeholder("ghp_… or github_pat_…")
+                .masked(true)
+        });
+        let refresh_input = cx
+            .new(|cx| InputState::new(window, cx).default_value(content.refresh_mins.to_string()));
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            form_mode: content.mode,
+            editing: is_new || token.trim().is_empty(),
+            content,
+            items: None,
+            loaded_at: None,
+            loading: false,
+            ticking: false,
+            token_input,
+            refresh_input,
+            error: None,
+        };
+        if !this.editing {
+            this.reload(cx);
+        }
+        this
+    }
+
+    /// "GitHub · 3 unread", so the count shows up in the main window.
+    fn title(&self) -> String {
+        let count = self.items.as_ref().map(Vec::len);
+        match (self.content.mode, count) {
+            (GitHubMode::Notifications, Some(count)) => format!("GitHub · {count} unread"),
+            (GitHubMode::Assigned, Some(count)) => format!("GitHub · {count} assigned"),
+            (_, None) => "GitHub".to_string(),
+        }
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize GitHub sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save GitHub sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save GitHub sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let token = SettingsStore::get(cx).github_token.trim().to_string();
+        if token.is_empty() || self.loading {
+            return;
+        }
+
+        self.loading = true;
+        cx.notify();
+
+        let mode = self.content.mode;
+        cx.spawn(async move |this, cx| {
+            let loaded = cx
+                .background_executor()
+                .spawn(async move { load_items(&token, mode).compat().await })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                this.loading = false;
+                this.loaded_at = Some(crate::utils::time::now_unix_millis());
+                match loaded {
+                    Ok(items) => {
+                        let count_changed = this.items.as_ref().map(Vec::len) != Some(items.len());
+                        this.items = Some(items);
+                        this.error = None;
+                        if count_changed {
+                            this.save_config(cx);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::warn!(id = this.id, error = ?err, "Failed to load GitHub items");
+                        this.error = Some(format!("Failed to load from GitHub: {err:#}"));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Redraw every minute and reload once the refresh interval passed.
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(TICK_INTERVAL).await;
+            let _ = this.update(cx, |this, cx| {
+                this.ticking = false;
+                let refresh_ms = this.content.refresh_mins.max(1) as i64 * 60 * 1000;
+                if this
+                    .loaded_at
+                    .is_some_and(|at| crate::utils::time::now_unix_millis() - at >= refresh_ms)
+                {
+                    this.reload(cx);
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Open the item in the browser, a notification is marked as read and leaves the list.
+    fn open_item(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(item) = self
+            .items
+            .as_ref()
+            .and_then(|items| items.get(index))
+            .cloned()
+        else {
+            return;
+        };
+
+        if let Err(err) = webbrowser::open(&item.url) {
+            tracing::warn!(id = self.id, url = %item.url, error = %err, "Failed to open GitHub item");
+            self.error = Some(format!("Failed to open {}: {err}", item.url));
+            cx.notify();
+            return;
+        }
+
+        let Some(thread_id) = item.thread_id else {
+            return;
+        };
+        if let Some(items) = self.items.as_mut() {
+            items.remove(index);
+        }
+        self.save_config(cx);
+        cx.notify();
+
+        let token = SettingsStore::get(cx).github_token.trim().to_string();
+        let id = self.id;
+        cx.background_executor()
+            .spawn(async move {
+                if let Err(err) = mark_read(&token, &thread_id).compat().await {
+                    tracing::warn!(id, error = ?err, "Failed to mark GitHub notification as read");
+                }
+            })
+            .detach();
+    }
+
+    fn apply_form(&mut self, cx: &mut Context<Self>) {
+        let token = self.token_input.read(cx).value().trim().to_string();
+        let refresh_mins = self.refresh_input.read(cx).value().trim().parse::<u32>();
+
+        if token.is_empty() {
+            self.error = Some("Please enter a GitHub token".to_string());
+            cx.notify();
+            return;
+        }
+        let Ok(refresh_mins @ 1..) = refresh_mins else {
+            self.error = Some("Refresh at least every 1 minute".to_string());
+            cx.notify();
+            return;
+        };
+
+        if SettingsStore::get(cx).github_token != token {
+            SettingsStore::update(cx, |s| s.github_token = token);
+        }
+        self.content = GitHubContent {
+            mode: self.form_mode,
+            refresh_mins,
+        };
+        self.editing = false;
+        self.error = None;
+        self.items = None;
+        self.save_config(cx);
+        self.reload(cx);
+        cx.notify();
+    }
+
+    fn list_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let now = chrono::Utc::now();
+        let items = self.items.as_deref().unwrap_or_default();
+
+        let list = v_flex()
+            .gap_1()
+            .children(items.iter().enumerate().map(|(ix, item)| {
+                let meta = match item.updated_at {
+                    Some(at) => format!(
+                        "{} · {} · {}",
+                        item.repository,
+                        item.kind,
+                        relative_time(now - at)
+                    ),
+                    None => format!("{} · {}", item.repository, item.kind),
+                };
+                v_flex()
+                    .id(("github-item", ix))
+                    .px_1()
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .hover(|x| x.bg(gpui::black().opacity(0.08)))
+                    .child(div().text_sm().child(item.title.clone()))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(meta),
+                    )
+                    .on_click(cx.listener(move |this, _, _, cx| this.open_item(ix, cx)))
+            }));
+
+        let empty = match (&self.items, self.loading) {
+            (None, true) => Some("Loading…"),
+            (Some(items), _) if items.is_empty() => Some(match self.content.mode {
+                GitHubMode::Notifications => "No unread notifications",
+                GitHubMode::Assigned => "No open issues assigned to you",
+            }),
+            _ => None,
+        };
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .child(
+                div().flex_1().overflow_hidden().child(
+                    v_flex()
+                        .overflow_y_scrollbar()
+                        .window_drag_area()
+                        .when_some(empty, |view, empty| {
+                            view.child(
+                                div()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(empty),
+                            )
+                        })
+                        .child(list),
+                ),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("edit")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Change token or list")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.form_mode = this.content.mode;
+                                    this.editing = true;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Button::new("refresh")
+                                .icon(IconName::Refresh)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .loading(self.loading)
+                                .tooltip("Reload now")
+                                .on_click(cx.listener(|this, _, _, cx| this.reload(cx))),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        let mut mode_button = |mode: GitHubMode| {
+            Button::new(mode.label())
+                .label(mode.label())
+                .small()
+                .when(self.form_mode == mode, |x| x.primary())
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.form_mode = mode;
+                    cx.notify();
+                }))
+        };
+
+        v_form()
+            .child(
+                field()
+                    .label("Token (shared by all GitHub stickers)")
+                    .child(Input::new(&self.token_input).small()),
+            )
+            .child(
+                field().label("Show").child(
+                    h_flex()
+                        .gap_1()
+                        .flex_wrap()
+                        .child(mode_button(GitHubMode::Notifications))
+                        .child(mode_button(GitHubMode::Assigned)),
+                ),
+            )
+            .child(
+                field()
+                    .label("Refresh every (minutes)")
+                    .child(Input::new(&self.refresh_input).small()),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.apply_form(cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for GitHubSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 120)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(320, 360)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        let loaded_at = self.loaded_at?;
+        Some(format!(
+            "refresh every {} min, last loaded {}",
+            self.content.refresh_mins,
+            crate::utils::time::format_unix_millis(loaded_at)
+        ))
+    }
+}
+
+impl Render for GitHubSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            self.spawn_for_tick(cx);
+            body = body.child(self.list_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("github-error", msg.as_str()).small())
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Repository {
+    full_name: String,
+    html_url: String,
+}
+
+#[derive(Deserialize)]
+struct Notification {
+    id: String,
+    subject: NotificationSubject,
+    repository: Repository,
+    updated_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct NotificationSubject {
+    title: String,
+    /// API URL of the issue, pull request, commit…, missing e.g. for discussions.
+    url: Option<String>,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct Issue {
+    title: String,
+    number: u64,
+    html_url: String,
+    repository: Option<Repository>,
+    pull_request: Option<serde_json::Value>,
+    updated_at: Option<String>,
+}
+
+async fn github_get(token: &str, url: reqwest::Url) -> anyhow::Result<String> {
+    reqwest::Client::new()
+        .get(url.clone())
+        .bearer_auth(token)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "rustickers")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .send()
+        .await
+        .and_then(|x| x.error_for_status())
+        .with_context(|| format!("request {}", url.path()))?
+        .text()
+        .await
+        .context("read GitHub response")
+}
+
+async fn load_items(token: &str, mode: GitHubMode) -> anyhow::Result<Vec<GitHubItem>> {
+    match mode {
+        GitHubMode::Notifications => {
+            let url = reqwest::Url::parse_with_params(
+                &format!("{GITHUB_API}/notifications"),
+                [("per_page", "50")],
+            )?;
+            let text = github_get(token, url).await?;
+            let notifications = serde_json::from_str::<Vec<Notification>>(&text)
+                .context("parse GitHub notifications")?;
+
+            Ok(notifications
+                .into_iter()
+                .map(|x| GitHubItem {
+                    url: x
+                        .subject
+                        .url
+                        .as_deref()
+                        .and_then(html_url)
+                        .unwrap_or(x.repository.html_url),
+                    thread_id: Some(x.id),
+                    title: x.subject.title,
+                    repository: x.repository.full_name,
+                    kind: x.subject.kind,
+                    updated_at: parse_time(x.updated_at.as_deref()),
+                })
+                .collect())
+        }
+        GitHubMode::Assigned => {
+            let url = reqwest::Url::parse_with_params(
+                &format!("{GITHUB_API}/issues"),
+                [
+                    ("filter", "assigned"),
+                    ("state", "open"),
+                    ("per_page", "50"),
+                ],
+            )?;
+            let text = github_get(token, url).await?;
+            let issues =
+                serde_json::from_str::<Vec<Issue>>(&text).context("parse GitHub issues")?;
+
+            Ok(issues
+                .into_iter()
+                .map(|x| GitHubItem {
+                    thread_id: None,
+                    title: x.title,
+                    repository: x
+                        .repository
+                        .map(|repository| format!("{}#{}", repository.full_name, x.number))
+                        .unwrap_or_else(|| format!("#{}", x.number)),
+                    kind: match x.pull_request {
+                        Some(_) => "PullRequest".to_string(),
+                        None => "Issue".to_string(),
+                    },
+                    url: x.html_url,
+                    updated_at: parse_time(x.updated_at.as_deref()),
+                })
+                .collect())
+        }
+    }
+}
+
+async fn mark_read(token: &str, thread_id: &str) -> anyhow::Result<()> {
+    reqwest::Client::new()
+        .patch(format!("{GITHUB_API}/notifications/threads/{thread_id}"))
+        .bearer_auth(token)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "rustickers")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .send()
+        .await
+        .and_then(|x| x.error_for_status())
+        .context("mark notification as read")?;
+    Ok(())
+}
+
+/// "https://api.github.com/repos/o/r/pulls/1" -> "https://github.com/o/r/pull/1".
+fn html_url(api_url: &str) -> Option<String> {
+    let path = api_url.strip_prefix("https://api.github.com/repos/")?;
+    let path = match path.split_once("/pulls/") {
+        Some((repo, rest)) => format!("{repo}/pull/{rest}"),
+        None => path.replacen("/commits/", "/commit/", 1),
+    };
+    Some(format!("https://github.com/{path}"))
+}
+
+fn parse_time(time: Option<&str>) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(time?)
+        .ok()
+        .map(|x| x.with_timezone(&chrono::Utc))
+}
+
+/// "5m", "3h", "2d" ago.
+fn relative_time(elapsed: chrono::Duration) -> String {
+    match elapsed.num_minutes().max(0) {
+        minutes @ 0..60 => format!("{minutes}m"),
+        minutes @ 60..1440 => format!("{}h", minutes / 60),
+        minutes => format!("{}d", minutes / 1440),
+    }
+}
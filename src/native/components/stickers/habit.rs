@@ -0,0 +1,423 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    green_500, h_flex,
+    input::{Input, InputState},
+    scroll::ScrollableElement,
+    tooltip::Tooltip,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+/// How often to check whether the day changed.
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_DAYS: u32 = 60;
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct HabitContent {
+    habits: Vec<Habit>,
+    /// Number of days shown in the grid, ending today.
+    days: u32,
+}
+
+impl Default for HabitContent {
+    fn default() -> Self {
+        Self {
+            habits: Vec::new(),
+            days: 14,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Habit {
+    name: String,
+    /// Completed days as `YYYY-MM-DD`.
+    done: BTreeSet<String>,
+}
+
+impl Habit {
+    fn is_done(&self, day: NaiveDate) -> bool {
+        self.done.contains(&day.format(DATE_FORMAT).to_string())
+    }
+
+    /// Consecutive completed days up to today, an open today does not break it.
+    fn streak(&self, today: NaiveDate) -> u32 {
+        let mut day = if self.is_done(today) {
+            today
+        } else {
+            today.pred_opt().unwrap_or(today)
+        };
+
+        let mut streak = 0;
+        while self.is_done(day) {
+            streak += 1;
+            match day.pred_opt() {
+                Some(prev) => day = prev,
+                None => break,
+            }
+        }
+        streak
+    }
+}
+
+pub struct HabitSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: HabitContent,
+
+    today: NaiveDate,
+    ticking: bool,
+
+    habits_input: Entity<InputState>,
+    days_input: Entity<InputState>,
+    editing: bool,
+
+    error: Option<String>,
+}
+
+impl HabitSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<HabitContent>(content).unwrap_or_default();
+
+        let names = content
+            .habits
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let habits_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(2, 6)
+                .default_value(names)
+                .placeholder("One habit per line")
+        });
+        let days_input =
+            cx.new(|cx| InputState::new(window, cx).default_value(content.days.to_string()));
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            editing: content.habits.is_empty(),
+            content,
+            today: Local::now().date_naive(),
+            ticking: false,
+            habits_input,
+            days_input,
+            error: None,
+        }
+    }
+
+    /// "Read · 5d, Run · 2d", so the streaks show up in the main window.
+    fn title(&self) -> String {
+        if self.content.habits.is_empty() {
+            return "Habits".to_string();
+        }
+
+        self.content
+            .habits
+            .iter()
+            .map(|habit| format!("{} · {}d", habit.name, habit.streak(self.today)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize habit sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save habit sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save habit sticker: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    /// Move the grid along at midnight, streaks may have ended.
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(TICK_INTERVAL).await;
+            let _ = this.update(cx, |this, cx| {
+                this.ticking = false;
+                let today = Local::now().date_naive();
+                if today != this.today {
+                    this.today = today;
+                    this.save_config(cx);
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn toggle(&mut self, habit: usize, day: NaiveDate, cx: &mut Context<Self>) {
+        let Some(habit) = self.content.habits.get_mut(habit) else {
+            return;
+        };
+
+        let key = day.format(DATE_FORMAT).to_string();
+        if !habit.done.remove(&key) {
+            habit.done.insert(key);
+        }
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn apply_form(&mut self, cx: &mut Context<Self>) {
+        let names = self
+            .habits_input
+            .read(cx)
+            .value()
+            .lines()
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            self.error = Some("Please enter at least one habit".to_string());
+            cx.notify();
+            return;
+        }
+
+        let Ok(days @ 1..=MAX_DAYS) = self.days_input.read(cx).value().trim().parse::<u32>() else {
+            self.error = Some(format!("Days must be between 1 and {MAX_DAYS}"));
+            cx.notify();
+            return;
+        };
+
+        // Keep the history of habits which were only reordered or kept.
+        let mut old = std::mem::take(&mut self.content.habits);
+        self.content.habits = names
+            .into_iter()
+            .map(|name| match old.iter().position(|x| x.name == name) {
+                Some(index) => old.remove(index),
+                None => Habit {
+                    name,
+                    done: BTreeSet::new(),
+                },
+            })
+            .collect();
+        self.content.days = days;
+        self.editing = false;
+        self.error = None;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn habit_row(&self, index: usize, habit: &Habit, cx: &mut Context<Self>) -> AnyElement {
+        let days = (0..self.content.days)
+            .rev()
+            .filter_map(|offset| {
+                self.today
+                    .checked_sub_days(chrono::Days::new(offset as u64))
+            })
+            .collect::<Vec<_>>();
+
+        let cells = days.into_iter().enumerate().map(|(i, day)| {
+            let done = habit.is_done(day);
+            let label = day.format("%a %-d %b").to_string();
+            div()
+                .id(("habit-day", index * MAX_DAYS as usize + i))
+                .size(px(14.0))
+                .rounded_sm()
+                .border_1()
+                .border_color(cx.theme().border)
+                .when(done, |cell| cell.bg(green_500()))
+                .when(!done, |cell| cell.bg(cx.theme().muted))
+                .when(day == self.today, |cell| {
+                    cell.border_color(cx.theme().foreground)
+                })
+                .cursor_pointer()
+                .tooltip(move |window, cx| Tooltip::new(label.clone()).build(window, cx))
+                .on_click(cx.listener(move |this, _, _, cx| this.toggle(index, day, cx)))
+        });
+
+        v_flex()
+            .gap_1()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .text_xs()
+                    .child(habit.name.clone())
+                    .child(
+                        div()
+                            .font_family(cx.theme().mono_font_family.clone())
+                            .child(format!("{}d", habit.streak(self.today))),
+                    ),
+            )
+            .child(h_flex().gap(px(2.0)).flex_wrap().children(cells))
+            .into_any_element()
+    }
+
+    fn habits_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let rows = self
+            .content
+            .habits
+            .iter()
+            .enumerate()
+            .map(|(index, habit)| self.habit_row(index, habit, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .child(
+                div().flex_1().overflow_hidden().child(
+                    v_flex()
+                        .gap_2()
+                        .overflow_y_scrollbar()
+                        .window_drag_area()
+                        .children(rows),
+                ),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex().child(
+                        Button::new("edit")
+                            .icon(IconName::Adjustments)
+                            .bg(transparent_white())
+                            .border_0()
+                            .xsmall()
+                            .tooltip("Change habits")
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.editing = true;
+                                cx.notify();
+                            })),
+                    ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field()
+                    .label("Habits")
+                    .child(Input::new(&self.habits_input).small()),
+            )
+            .child(
+                field()
+                    .label("Days to show")
+                    .child(Input::new(&self.days_input).small()),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.apply_form(cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for HabitSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 100)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(300, 220)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for HabitSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+        self.spawn_for_tick(cx);
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            body = body.child(self.habits_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("habit-error", msg.as_str()).small())
+        })
+    }
+}
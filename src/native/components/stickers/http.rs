@@ -0,0 +1,799 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use async_compat::CompatExt;
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    green_500, h_flex,
+    input::{Input, InputEvent, InputState},
+    menu::{DropdownMenu, PopupMenuItem},
+    red_500,
+    scroll::ScrollableElement,
+    switch::Switch,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::cron::{self, CronTarget};
+use super::result_renderers::{self, RenderContext};
+use super::save_content;
+use crate::model::sticker::{StickerColor, StickerSettings};
+use crate::native::components::{
+    IconName,
+    webview::{LazyWebView, SimpleWebView},
+};
+use crate::native::windows::StickerWindowEvent;
+use crate::storage::ArcStickerStore;
+
+const METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longer bodies are cut, the response is stored in the sticker content.
+const MAX_BODY_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct HttpContent {
+    method: String,
+    url: String,
+    /// `Name: value` per line.
+    headers: String,
+    body: String,
+    /// Cron expression with seconds, the request is sent on every run while scheduled.
+    cron: Option<String>,
+    run_immediately: bool,
+    /// Set while the schedule runs, so it starts again with the sticker.
+    started_at: Option<i64>,
+    /// Key of the result renderer showing the body, picked from the content type when missing.
+    renderer: Option<String>,
+    response: Option<HttpResponse>,
+}
+
+impl Default for HttpContent {
+    fn default() -> Self {
+        Self {
+            method: "GET".to_string(),
+            url: String::new(),
+            headers: String::new(),
+            body: String::new(),
+            cron: None,
+            run_immediately: true,
+            started_at: None,
+            renderer: None,
+            response: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HttpResponse {
+    status: u16,
+    reason: String,
+    headers: Vec<(String, String)>,
+    body: String,
+    elapsed_ms: i64,
+    received_at: i64,
+}
+
+impl HttpResponse {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Html and JSON bodies are shown as such, everything else as text.
+    fn renderer_key(&self) -> &'static str {
+        let content_type = self.header("content-type").unwrap_or("").to_lowercase();
+        if content_type.contains("json") {
+            "Json"
+        } else if content_type.contains("html") {
+            "Html"
+        } else {
+            result_renderers::DEFAULT_KEY
+        }
+    }
+}
+
+/// What goes over the wire, read from the form when sending.
+struct HttpRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+pub struct HttpSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+
+    method: String,
+    url: Entity<InputState>,
+    headers: Entity<InputState>,
+    body: Entity<InputState>,
+    cron: Option<String>,
+    cron_input: Entity<InputState>,
+    run_immediately: bool,
+    started_at: Option<i64>,
+    renderer: Option<String>,
+    response: Option<HttpResponse>,
+    response_webview: LazyWebView,
+    settings: StickerSettings,
+
+    editing: bool,
+    show_headers: bool,
+    sending: bool,
+    schedule_cancel: Option<Arc<AtomicBool>>,
+    next_scheduled_at: Option<String>,
+    error: Option<String>,
+}
+
+impl HttpSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<HttpContent>(content).unwrap_or_default();
+
+        let url = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.url.clone())
+                .placeholder("https://api.example.com/items")
+        });
+        let headers = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(1, 5)
+                .default_value(content.headers.clone())
+                .placeholder("Name: value per line")
+        });
+        let body = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(1, 8)
+                .default_value(content.body.clone())
+                .placeholder("Optional")
+        });
+        let cron_input = cx.new(|cx| {
+            InputState::new(window, cx).default_value(content.cron.clone().unwrap_or_default())
+        });
+        cx.subscribe(&cron_input, |this, input, event, cx| {
+            if let InputEvent::Change = event {
+                this.cron = Some(input.read(cx).value().trim().to_string());
+            }
+        })
+        .detach();
+
+        let mut response_webview = LazyWebView::default();
+        response_webview.set_source(Self::webview_source(
+            content.response.as_ref(),
+            content.renderer.as_deref(),
+        ));
+
+        let root_entity = cx.entity();
+        window
+            .spawn(cx, async move |cx| {
+                let _ = cx.update_window_entity(&root_entity, |this, window, cx| {
+                    if this.started_at.is_some() && this.cron.is_some() {
+                        this.start(window, cx);
+                    }
+                });
+            })
+            .detach();
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+
+            method: content.method,
+            url,
+            headers,
+            body,
+            cron: content.cron,
+            cron_input,
+            run_immediately: content.run_immediately,
+            started_at: content.started_at,
+            renderer: content.renderer,
+            editing: content.response.is_none(),
+            response: content.response,
+            response_webview,
+            settings: StickerSettings::default(),
+
+            show_headers: false,
+            sending: false,
+            schedule_cancel: None,
+            next_scheduled_at: None,
+            error: None,
+        }
+    }
+
+    fn renderer_key(&self) -> &str {
+        match (&self.renderer, &self.response) {
+            (Some(key), _) => key.as_str(),
+            (None, Some(response)) => response.renderer_key(),
+            (None, None) => result_renderers::DEFAULT_KEY,
+        }
+    }
+
+    /// The body to show in a web view, built once the window is looked at.
+    fn webview_source(response: Option<&HttpResponse>, renderer: Option<&str>) -> Option<String> {
+        let response = response?;
+        let key = renderer.unwrap_or(response.renderer_key());
+        result_renderers::get(key)
+            .use_webview()
+            .then(|| response.body.clone())
+    }
+
+    fn build_content(&self, cx: &mut Context<Self>) -> HttpContent {
+        HttpContent {
+            method: self.method.clone(),
+            url: self.url.read(cx).value().trim().to_string(),
+            headers: self.headers.read(cx).value().to_string(),
+            body: self.body.read(cx).value().to_string(),
+            cron: self.cron.clone(),
+            run_immediately: self.run_immediately,
+            started_at: self.started_at,
+            renderer: self.renderer.clone(),
+            response: self.response.clone(),
+        }
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let content = self.build_content(cx);
+        let title = match content.url.is_empty() {
+            true => String::new(),
+            false => format!("{} {}", content.method, content.url),
+        };
+        let json = match serde_json::to_string(&content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize HTTP sticker: {err}"));
+                return false;
+            }
+        };
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save HTTP sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save HTTP sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn is_schedule_active(&self) -> bool {
+        self.schedule_cancel.is_some()
+    }
+
+    /// Send once, or start polling when a cron expression is set.
+    fn start(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.stop_schedule();
+
+        let Some(expr) = self.cron.clone() else {
+            self.started_at = None;
+            self.save_config(cx);
+            self.send(window, cx);
+            return;
+        };
+
+        let schedule = match cron::parse(&expr) {
+            Ok(schedule) => schedule,
+            Err(err) => {
+                self.error = Some(err);
+                cx.notify();
+                return;
+            }
+        };
+
+        self.started_at = Some(crate::utils::time::now_unix_millis());
+        self.save_config(cx);
+        if self.run_immediately {
+            self.send(window, cx);
+        }
+
+        self.error = None;
+        self.editing = false;
+        self.schedule_cancel = Some(cron::spawn(schedule, window, cx));
+        cx.notify();
+    }
+
+    fn stop_schedule(&mut self) {
+        if let Some(cancel) = self.schedule_cancel.take() {
+            cancel.store(true, Ordering::SeqCst);
+        }
+        self.next_scheduled_at = None;
+    }
+
+    fn read_request(&self, cx: &mut Context<Self>) -> Result<HttpRequest, String> {
+        let url = self.url.read(cx).value().trim().to_string();
+        if url.is_empty() {
+            return Err("URL cannot be empty".to_string());
+        }
+
+        let mut headers = Vec::new();
+        for line in self.headers.read(cx).value().lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(format!("Header needs a name and a value: {line}"));
+            };
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+
+        Ok(HttpRequest {
+            method: self.method.clone(),
+            url,
+            headers,
+            body: self.body.read(cx).value().to_string(),
+        })
+    }
+
+    fn send(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.sending {
+            return;
+        }
+
+        let request = match self.read_request(cx) {
+            Ok(request) => request,
+            Err(err) => {
+                self.error = Some(err);
+                cx.notify();
+                return;
+            }
+        };
+
+        self.sending = true;
+        self.error = None;
+        cx.notify();
+
+        let entity = cx.entity();
+        window
+            .spawn(cx, async move |window| {
+                let result = window
+                    .background_executor()
+                    .spawn(async move { send_request(request).compat().await })
+                    .await;
+
+                let _ = window.update_entity(&entity, |this, cx| {
+                    this.sending = false;
+                    match result {
+                        Ok(response) => {
+                            this.response_webview.set_source(Self::webview_source(
+                                Some(&response),
+                                this.renderer.as_deref(),
+                            ));
+                            this.response = Some(response);
+                            this.editing = false;
+                        }
+                        Err(err) => {
+                            tracing::warn!(id = this.id, error = ?err, "HTTP request failed");
+                            this.error = Some(format!("Request failed: {err:#}"));
+                        }
+                    }
+                    this.save_config(cx);
+                    cx.notify();
+                });
+            })
+            .detach();
+    }
+
+    fn set_renderer(&mut self, key: Option<String>, cx: &mut Context<Self>) {
+        self.renderer = key;
+        self.response_webview.set_source(Self::webview_source(
+            self.response.as_ref(),
+            self.renderer.as_deref(),
+        ));
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn method_button(&self, cx: &mut Context<Self>) -> AnyElement {
+        let entity = cx.entity();
+        Button::new("method")
+            .label(self.method.clone())
+            .small()
+            .dropdown_menu(move |menu, window, _| {
+                METHODS.into_iter().fold(menu, |menu, method| {
+                    menu.item(PopupMenuItem::new(method).on_click(window.listener_for(
+                        &entity,
+                        move |this, _, _, cx| {
+                            this.method = method.to_string();
+                            cx.notify();
+                        },
+                    )))
+                })
+            })
+            .into_any_element()
+    }
+
+    fn form(&mut self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field().label("Request").child(
+                    h_flex()
+                        .w_full()
+                        .gap_1()
+                        .child(self.method_button(cx))
+                        .child(div().flex_1().child(Input::new(&self.url))),
+                ),
+            )
+            .child(field().label("Headers").child(Input::new(&self.headers)))
+            .child(field().label("Body").child(Input::new(&self.body)))
+            .child(
+                field().label("Schedule").child(
+                    v_flex()
+                        .py_1()
+                        .w_full()
+                        .gap_1()
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Button::new("none")
+                                        .label("none")
+                                        .small()
+                                        .when(self.cron.is_none(), |v| v.primary())
+                                        .on_click(cx.listener(|this, _, _, _| {
+                                            this.cron = None;
+                                        })),
+                                )
+                                .child(
+                                    Button::new("cron")
+                                        .label("cron")
+                                        .small()
+                                        .when(self.cron.is_some(), |v| v.primary())
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            // by default, every five minutes
+                                            let cron = "0 */5 * * * *";
+                                            this.cron_input.update(cx, |this, cx| {
+                                                this.set_value(cron, window, cx)
+                                            });
+                                        })),
+                                ),
+                        )
+                        .when(self.cron.is_some(), |v| {
+                            v.child(Input::new(&self.cron_input))
+                        }),
+                ),
+            )
+            .when(self.cron.is_some(), |v| {
+                v.child(
+                    field().label("Run immediately").child(
+                        Switch::new("run_immediately")
+                            .label("send without waiting for the schedule")
+                            .small()
+                            .checked(self.run_immediately)
+                            .on_click(cx.listener(|this, _, _, _| {
+                                this.run_immediately = !this.run_immediately
+                            })),
+                    ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn response_view(
+        &self,
+        bg_color: Rgba,
+        webview: Option<Entity<SimpleWebView>>,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(response) = &self.response else {
+            return div().size_full().bg(bg_color).into_any_element();
+        };
+
+        let status_color = match response.status {
+            200..400 => green_500(),
+            _ => red_500(),
+        };
+        let current = self.renderer.clone();
+        let renderer_button = |key: Option<&'static str>, label: &'static str| {
+            Button::new(label)
+                .label(label)
+                .xsmall()
+                .when(current.as_deref() == key, |v| v.primary())
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.set_renderer(key.map(str::to_string), cx);
+                }))
+        };
+
+        let header = h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .items_center()
+            .text_xs()
+            .child(
+                div()
+                    .text_color(status_color)
+                    .child(format!("{} {}", response.status, response.reason)),
+            )
+            .child(div().opacity(0.75).child(format!(
+                "{} ms · {}",
+                response.elapsed_ms,
+                crate::utils::time::format_unix_millis(response.received_at)
+            )))
+            .child(div().flex_1())
+            .child(renderer_button(None, "auto"))
+            .child(renderer_button(Some(result_renderers::DEFAULT_KEY), "text"))
+            .child(renderer_button(Some("Json"), "json"))
+            .child(renderer_button(Some("Html"), "html"));
+
+        let headers = self.show_headers.then(|| {
+            v_flex().px_2().pb_1().text_xs().opacity(0.75).children(
+                response
+                    .headers
+                    .iter()
+                    .map(|(name, value)| div().child(format!("{name}: {value}"))),
+            )
+        });
+
+        let body = result_renderers::get(self.renderer_key()).render(
+            RenderContext {
+                output: &response.body,
+                padding: px(8.0),
+                bg: bg_color,
+                settings: &self.settings,
+                webview,
+            },
+            cx,
+        );
+
+        v_flex()
+            .size_full()
+            .bg(bg_color)
+            .child(header)
+            .children(headers)
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(v_flex().size_full().overflow_y_scrollbar().child(body)),
+            )
+            .into_any_element()
+    }
+
+    fn toolbar(&self, bg_color: Rgba, cx: &mut Context<Self>) -> AnyElement {
+        let bar = h_flex().bg(bg_color).w_full().gap_1();
+
+        if self.is_schedule_active() {
+            return bar
+                .child(
+                    Button::new("stop")
+                        .icon(IconName::Stop)
+                        .bg(transparent_white())
+                        .border_0()
+                        .when_some(self.next_scheduled_at.clone(), |view, x| {
+                            view.tooltip(format!("Next request at {x}"))
+                        })
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.stop_schedule();
+                            this.started_at = None;
+                            this.save_config(cx);
+                            cx.notify();
+                        })),
+                )
+                .into_any_element();
+        }
+
+        bar.when(!self.editing, |bar| {
+            bar.child(
+                Button::new("edit")
+                    .icon(IconName::Adjustments)
+                    .bg(transparent_white())
+                    .border_0()
+                    .tooltip("Edit the request")
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.editing = true;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("toggle_headers")
+                    .label("headers")
+                    .xsmall()
+                    .bg(transparent_white())
+                    .border_0()
+                    .when(self.show_headers, |v| v.primary())
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.show_headers = !this.show_headers;
+                        cx.notify();
+                    })),
+            )
+        })
+        .child(
+            Button::new("send")
+                .icon(IconName::Send)
+                .bg(transparent_white())
+                .border_0()
+                .loading(self.sending)
+                .tooltip("Send")
+                .on_click(cx.listener(|this, _, window, cx| this.start(window, cx))),
+        )
+        .into_any_element()
+    }
+}
+
+impl CronTarget for HttpSticker {
+    fn set_next_run(&mut self, next: Option<String>, _: &mut Context<Self>) {
+        match next {
+            Some(next) => self.next_scheduled_at = Some(next),
+            None => self.stop_schedule(),
+        }
+    }
+
+    fn run_scheduled(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.send(window, cx);
+    }
+}
+
+impl super::Sticker for HttpSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(200, 150)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(360, 420)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn has_text_output(&self) -> bool {
+        !self.editing
+            && self.response.is_some()
+            && result_renderers::get(self.renderer_key()).text_output()
+    }
+
+    fn apply_settings(&mut self, settings: &StickerSettings) {
+        self.settings = settings.clone();
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        if !self.is_schedule_active() {
+            return self.sending.then(|| "sending".to_string());
+        }
+        Some(match &self.next_scheduled_at {
+            Some(next) => format!("scheduled, next request at {next}"),
+            None => "scheduled".to_string(),
+        })
+    }
+}
+
+impl Render for HttpSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let bg_color = Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        };
+
+        window.set_rem_size(px(14.0));
+
+        let editing = self.editing && !self.is_schedule_active();
+        let webview = self.response_webview.sync(!editing, window, cx);
+        let view = if editing {
+            div()
+                .p_2()
+                .size_full()
+                .bg(bg_color)
+                .overflow_hidden()
+                .child(v_flex().overflow_y_scrollbar().child(self.form(cx)))
+                .into_any_element()
+        } else {
+            self.response_view(bg_color, webview, cx)
+        };
+
+        v_flex()
+            .relative()
+            .size_full()
+            .child(div().flex_1().overflow_hidden().child(view))
+            .child(self.toolbar(bg_color, cx))
+            .when_some(self.error.as_ref(), |view, msg| {
+                view.child(Alert::error("error", msg.as_str()).bg(bg_color))
+            })
+    }
+}
+
+async fn send_request(request: HttpRequest) -> anyhow::Result<HttpResponse> {
+    let method = reqwest::Method::from_bytes(request.method.as_bytes())
+        .with_context(|| format!("invalid method {}", request.method))?;
+    let url = reqwest::Url::parse(&request.url).context("invalid URL")?;
+
+    let mut builder = reqwest::Client::new()
+        .request(method, url)
+        .timeout(REQUEST_TIMEOUT);
+    if !request
+        .headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("user-agent"))
+    {
+        builder = builder.header("User-Agent", "rustickers");
+    }
+    for (name, value) in request.headers {
+        builder = builder.header(name, value);
+    }
+    if !request.body.is_empty() {
+        builder = builder.body(request.body);
+    }
+
+    let started = Instant::now();
+    let response = builder.send().await.context("send request")?;
+    let status = response.status();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = value.to_str().unwrap_or("<binary>");
+            (name.to_string(), value.to_string())
+        })
+        .collect();
+    let mut body = response.text().await.context("read response body")?;
+    let elapsed_ms = started.elapsed().as_millis() as i64;
+
+    if body.len() > MAX_BODY_BYTES {
+        let mut end = MAX_BODY_BYTES;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("\n…");
+    }
+
+    Ok(HttpResponse {
+        status: status.as_u16(),
+        reason: status.canonical_reason().unwrap_or("").to_string(),
+        headers,
+        body,
+        elapsed_ms,
+        received_at: crate::utils::time::now_unix_millis(),
+    })
+}
@@ -0,0 +1,1037 @@
+use gpui::{
+    AnyElement, Bounds, ClipboardEntry, ClipboardItem, ContentMask, Context, ExternalPaths,
+    FocusHandle, ImageFormat, KeyDownEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, ObjectFit, Pixels, Point, Render, RenderImage, Rgba, Window, canvas, div, img,
+    point, prelude::*, px, rgba, transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Selectable as _, Sizable, alert::Alert, button::Button, h_flex, v_flex, white,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use anyhow::Context as _;
+
+use crate::model::sticker::StickerColor;
+use crate::native::components::IconName;
+use crate::native::decorations::WindowDragArea;
+use crate::native::dialogs;
+use crate::native::windows::StickerWindowEvent;
+use crate::storage::ArcStickerStore;
+use crate::storage::paths::AppPaths;
+use crate::utils::image_edit::{self, Transform};
+use crate::utils::ocr;
+
+use super::paint::{PAINT_COLORS, PaintPoint, PaintStroke, PaintStrokeState, PaintView};
+use super::save_content;
+
+pub const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Width of annotation strokes on screen, stored in pixels of the original image.
+const ANNOTATION_WIDTH: f32 = 3.0;
+/// Drags shorter than this on screen are clicks, not crops.
+const MIN_CROP_DRAG: f32 = 4.0;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ImageContent {
+    /// SHA-256 of the picture, an attachment of the sticker, see [`add_picture`].
+    attachment: Option<String>,
+    /// File name inside the images directory of older versions, moved into an
+    /// attachment once the sticker is opened.
+    file: Option<String>,
+    /// Crop and rotation shown on top of the file, which is never changed.
+    transform: Transform,
+    /// Annotations in pixels of the original file.
+    strokes: Vec<PaintStroke>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EditMode {
+    View,
+    Crop,
+    Annotate,
+}
+
+pub struct ImageSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    images_dir: PathBuf,
+    content: ImageContent,
+
+    /// The encoded picture read from the attachment, `None` while loading.
+    picture: Option<Arc<gpui::Image>>,
+    loading: bool,
+    /// Width and height of the file, the edits are stored in its pixels.
+    original_size: Option<(u32, u32)>,
+    /// The file with crop and rotation applied, in the BGRA pixels GPUI draws.
+    edited: Option<Arc<RenderImage>>,
+    /// The annotations moved onto the cropped and rotated image.
+    display_strokes: Arc<Vec<PaintStrokeState>>,
+    /// Where the image area was laid out in the window last frame.
+    image_bounds: Rc<Cell<Bounds<Pixels>>>,
+    mode: EditMode,
+    crop_drag: Option<(Point<Pixels>, Point<Pixels>)>,
+    annotating: bool,
+    current_color: u32,
+
+    focus_handle: FocusHandle,
+    error: Option<String>,
+}
+
+impl ImageSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<ImageContent>(content).unwrap_or_default();
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            images_dir: cx.global::<AppPaths>().images_dir(),
+            content,
+            picture: None,
+            loading: false,
+            original_size: None,
+            edited: None,
+            display_strokes: Arc::default(),
+            image_bounds: Rc::default(),
+            mode: EditMode::View,
+            crop_drag: None,
+            annotating: false,
+            current_color: PAINT_COLORS[2],
+            focus_handle: cx.focus_handle(),
+            error: None,
+        };
+        this.load_picture(cx);
+        this
+    }
+
+    /// Read the picture of the content in the background, a file of older versions is
+    /// moved into an attachment on the way.
+    fn load_picture(&mut self, cx: &mut Context<Self>) {
+        let attachment = self.content.attachment.clone();
+        let file = self.content.file.clone();
+        if attachment.is_none() && file.is_none() {
+            return;
+        }
+
+        let id = self.id;
+        let store = self.store.clone();
+        let images_dir = self.images_dir.clone();
+        self.loading = true;
+        cx.spawn(async move |entity, cx| {
+            let result = match (&attachment, &file) {
+                (Some(hash), _) => read_picture(&store, id, hash).await.map(|x| (None, x)),
+                (None, Some(file)) => import_picture_file(&store, id, &images_dir.join(file)).await,
+                (None, None) => return,
+            };
+
+            let _ = entity.update(cx, |this, cx| {
+                this.loading = false;
+                // Another picture was set in the meantime.
+                if this.content.attachment != attachment || this.content.file != file {
+                    return;
+                }
+
+                match result {
+                    Ok((imported, picture)) => {
+                        if let Some(hash) = imported {
+                            this.content.attachment = Some(hash);
+                            this.content.file = None;
+                            this.save_state(None, cx);
+                        }
+                        this.picture = Some(picture);
+                        this.load_edits(cx);
+                    }
+                    Err(err) => {
+                        tracing::warn!(id, error = ?err, "Failed to load image");
+                        this.error = Some(format!("Failed to load image: {err:#}"));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn set_error(&mut self, message: impl Into<String>, cx: &mut Context<Self>) {
+        self.error = Some(message.into());
+        cx.notify();
+    }
+
+    fn paste(&mut self, cx: &mut Context<Self>) {
+        let Some(item) = cx.read_from_clipboard() else {
+            return;
+        };
+
+        for entry in item.entries() {
+            match entry {
+                ClipboardEntry::Image(image) => {
+                    let extension = match image.format {
+                        ImageFormat::Png => "png",
+                        ImageFormat::Jpeg => "jpg",
+                        _ => continue,
+                    };
+                    let title = format!(
+                        "Image {}",
+                        crate::utils::time::format_unix_millis(
+                            crate::utils::time::now_unix_millis()
+                        )
+                    );
+                    self.set_image(image.bytes.clone(), extension, title, cx);
+                    return;
+                }
+                ClipboardEntry::String(text) => {
+                    // Copying a file in a file manager puts its path on the clipboard.
+                    let path = PathBuf::from(text.text().trim());
+                    if is_supported_image(&path) && path.is_file() {
+                        self.load_file(&path, cx);
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.set_error("The clipboard does not contain a PNG or JPEG image", cx);
+    }
+
+    fn open_file(&mut self, cx: &mut Context<Self>) {
+        cx.spawn(async move |entity, cx| {
+            let Some(path) =
+                dialogs::pick_file("Choose an image", &[("Images", &IMAGE_EXTENSIONS)]).await
+            else {
+                return;
+            };
+
+            let _ = entity.update(cx, |this, cx| this.load_file(&path, cx));
+        })
+        .detach();
+    }
+
+    fn drop_paths(&mut self, paths: &ExternalPaths, cx: &mut Context<Self>) {
+        match paths.paths().iter().find(|path| is_supported_image(path)) {
+            Some(path) => self.load_file(&path.clone(), cx),
+            None => self.set_error("Only PNG and JPEG images are supported", cx),
+        }
+    }
+
+    fn load_file(&mut self, path: &Path, cx: &mut Context<Self>) {
+        let extension = path
+            .extension()
+            .and_then(|x| x.to_str())
+            .unwrap_or("png")
+            .to_ascii_lowercase();
+        let title = path
+            .file_stem()
+            .and_then(|x| x.to_str())
+            .unwrap_or("Image")
+            .to_string();
+
+        match std::fs::read(path) {
+            Ok(bytes) => self.set_image(bytes, &extension, title, cx),
+            Err(err) => self.set_error(format!("Failed to read {}: {err}", path.display()), cx),
+        }
+    }
+
+    fn set_image(
+        &mut self,
+        bytes: Vec<u8>,
+        extension: &str,
+        title: String,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(format) = picture_format(extension) else {
+            self.set_error(format!("Unsupported image type: {extension}"), cx);
+            return;
+        };
+        let picture = Arc::new(gpui::Image::from_bytes(format, bytes));
+
+        let id = self.id;
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let hash = add_picture(&store, id, &picture).await;
+            let _ = entity.update(cx, |this, cx| {
+                let hash = match hash {
+                    Ok(hash) => hash,
+                    Err(err) => {
+                        this.set_error(format!("Failed to save image: {err:#}"), cx);
+                        return;
+                    }
+                };
+
+                // Copies of the sticker have their own attachment of the replaced picture.
+                if let Some(replaced) = this.content.attachment.take().filter(|x| *x != hash) {
+                    let store = this.store.clone();
+                    cx.spawn(async move |_, _| {
+                        if let Err(err) = remove_picture(&store, id, &replaced).await {
+                            tracing::warn!(id, error = ?err, "Failed to remove replaced image");
+                        }
+                    })
+                    .detach();
+                }
+
+                // Edits belong to the replaced picture.
+                this.content = ImageContent {
+                    attachment: Some(hash),
+                    ..Default::default()
+                };
+                this.picture = Some(picture);
+                this.mode = EditMode::View;
+                this.error = None;
+                this.load_edits(cx);
+                this.save_state(Some(title), cx);
+                this.spawn_ocr(cx);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Read the size of the picture and show it with the stored edits.
+    fn load_edits(&mut self, cx: &mut Context<Self>) {
+        self.original_size = self.picture.as_ref().and_then(|picture| {
+            image::ImageReader::new(std::io::Cursor::new(&picture.bytes))
+                .with_guessed_format()
+                .ok()?
+                .into_dimensions()
+                .inspect_err(|err| {
+                    tracing::warn!(id = self.id, error = %err, "Failed to read image size");
+                })
+                .ok()
+        });
+        self.rebuild_display_strokes();
+        self.spawn_render_edited(cx);
+    }
+
+    fn edits_changed(&mut self, cx: &mut Context<Self>) {
+        self.rebuild_display_strokes();
+        self.spawn_render_edited(cx);
+        self.save_state(None, cx);
+        cx.notify();
+    }
+
+    fn has_edits(&self) -> bool {
+        !self.content.transform.is_identity() || !self.content.strokes.is_empty()
+    }
+
+    fn rebuild_display_strokes(&mut self) {
+        let Some(size) = self.original_size else {
+            self.display_strokes = Arc::default();
+            return;
+        };
+        let transform = self.content.transform;
+        self.display_strokes = Arc::new(
+            self.content
+                .strokes
+                .iter()
+                .map(|stroke| PaintStrokeState::new(display_stroke(stroke, &transform, size)))
+                .collect(),
+        );
+    }
+
+    fn spawn_render_edited(&mut self, cx: &mut Context<Self>) {
+        let transform = self.content.transform;
+        let Some(picture) = self.picture.clone().filter(|_| !transform.is_identity()) else {
+            self.edited = None;
+            return;
+        };
+
+        cx.spawn(async move |entity, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move { render_edited(&picture, &transform) })
+                .await;
+
+            let _ = entity.update(cx, |this, cx| {
+                // A later edit is rendering already.
+                if this.content.transform != transform {
+                    return;
+                }
+                match result {
+                    Ok(image) => this.edited = Some(image),
+                    Err(err) => this.set_error(format!("Failed to edit image: {err:#}"), cx),
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// How the edited image sits in the window, contained and centered like
+    /// [`ObjectFit::Contain`]: a window position is `edited * zoom + pan`.
+    fn image_view(&self) -> Option<PaintView> {
+        let (width, height) = self.content.transform.size(self.original_size?);
+        let bounds = self.image_bounds.get();
+        let (bounds_width, bounds_height) = (
+            bounds.size.width.to_f64() as f32,
+            bounds.size.height.to_f64() as f32,
+        );
+        if width == 0 || height == 0 || bounds_width <= 0.0 || bounds_height <= 0.0 {
+            return None;
+        }
+
+        let zoom = (bounds_width / width as f32).min(bounds_height / height as f32);
+        Some(PaintView {
+            zoom,
+            pan_x: bounds.origin.x.to_f64() as f32 + (bounds_width - width as f32 * zoom) / 2.0,
+            pan_y: bounds.origin.y.to_f64() as f32 + (bounds_height - height as f32 * zoom) / 2.0,
+        })
+    }
+
+    fn to_original(&self, view: &PaintView, position: Point<Pixels>) -> Option<PaintPoint> {
+        let p = PaintPoint::from(position);
+        let (x, y) = self.content.transform.backward(
+            self.original_size?,
+            (p.x - view.pan_x) / view.zoom,
+            (p.y - view.pan_y) / view.zoom,
+        );
+        Some(PaintPoint { x, y })
+    }
+
+    fn rotate(&mut self, cx: &mut Context<Self>) {
+        self.content.transform.turns = (self.content.transform.turns + 1) % 4;
+        self.edits_changed(cx);
+    }
+
+    fn reset_edits(&mut self, cx: &mut Context<Self>) {
+        self.content.transform = Transform::default();
+        self.content.strokes.clear();
+        self.mode = EditMode::View;
+        self.edits_changed(cx);
+    }
+
+    fn undo_stroke(&mut self, cx: &mut Context<Self>) {
+        if self.content.strokes.pop().is_some() {
+            self.edits_changed(cx);
+        }
+    }
+
+    fn toggle_mode(&mut self, mode: EditMode, cx: &mut Context<Self>) {
+        self.mode = match self.mode == mode {
+            true => EditMode::View,
+            false => mode,
+        };
+        self.crop_drag = None;
+        cx.notify();
+    }
+
+    fn apply_crop(&mut self, from: Point<Pixels>, to: Point<Pixels>, cx: &mut Context<Self>) {
+        let (Some(view), Some(size)) = (self.image_view(), self.original_size) else {
+            return;
+        };
+        let (from, to) = (PaintPoint::from(from), PaintPoint::from(to));
+        if (from.x - to.x).abs() < MIN_CROP_DRAG || (from.y - to.y).abs() < MIN_CROP_DRAG {
+            return;
+        }
+
+        // Clamped to the shown image, so a crop only ever shrinks the current one.
+        let (width, height) = self.content.transform.size(size);
+        let to_edited = |p: PaintPoint| {
+            (
+                ((p.x - view.pan_x) / view.zoom).clamp(0.0, width as f32),
+                ((p.y - view.pan_y) / view.zoom).clamp(0.0, height as f32),
+            )
+        };
+        self.content
+            .transform
+            .crop_to(size, to_edited(from), to_edited(to));
+        self.mode = EditMode::View;
+        self.edits_changed(cx);
+    }
+
+    fn start_stroke(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let Some(view) = self.image_view() else {
+            return;
+        };
+        let Some(p) = self.to_original(&view, position) else {
+            return;
+        };
+
+        self.annotating = true;
+        self.content.strokes.push(PaintStroke {
+            points: vec![p],
+            color: self.current_color,
+            width: ANNOTATION_WIDTH / view.zoom,
+        });
+        self.rebuild_display_strokes();
+        cx.notify();
+    }
+
+    fn extend_stroke(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let (Some(view), Some(size)) = (self.image_view(), self.original_size) else {
+            return;
+        };
+        let Some(p) = self.to_original(&view, position) else {
+            return;
+        };
+        let transform = self.content.transform;
+        let Some(stroke) = self.content.strokes.last_mut() else {
+            return;
+        };
+        if let Some(last) = stroke.points.last() {
+            let (dx, dy) = (p.x - last.x, p.y - last.y);
+            if (dx * dx + dy * dy).sqrt() * view.zoom < 1.0 {
+                return;
+            }
+        }
+
+        stroke.points.push(p);
+        let display = display_stroke(stroke, &transform, size);
+        if let Some(last) = Arc::make_mut(&mut self.display_strokes).last_mut() {
+            *last = PaintStrokeState::new(display);
+        }
+        cx.notify();
+    }
+
+    fn copy_image(&mut self, cx: &mut Context<Self>) {
+        let Some(picture) = self.picture.clone() else {
+            return;
+        };
+        let transform = self.content.transform;
+        let strokes = self.content.strokes.clone();
+
+        cx.spawn(async move |entity, cx| {
+            let bytes = cx
+                .background_executor()
+                .spawn(async move { export_png(&picture, &transform, &strokes) })
+                .await;
+
+            let _ = entity.update(cx, |this, cx| match bytes {
+                Ok(bytes) => cx.write_to_clipboard(ClipboardItem::new_image(
+                    &gpui::Image::from_bytes(ImageFormat::Png, bytes),
+                )),
+                Err(err) => this.set_error(format!("Failed to copy image: {err:#}"), cx),
+            });
+        })
+        .detach();
+    }
+
+    fn save_state(&mut self, title: Option<String>, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to save image sticker: {err}"));
+                return false;
+            }
+        };
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Some(title) = title {
+                if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.set_error(format!("Failed to save image title: {err:#}"), cx);
+                    });
+                    return;
+                }
+
+                if let Err(err) =
+                    sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+                {
+                    tracing::warn!(id, error = %err, "Failed to send title changed event for image sticker");
+                }
+            }
+
+            if let Err(err) = save_content(&store, &sticker_events_tx, id, json, String::new()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to save image sticker: {err:#}"), cx);
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    /// Make the text in the picture searchable when tesseract is installed.
+    fn spawn_ocr(&self, cx: &mut Context<Self>) {
+        let Some(picture) = self.picture.clone().filter(|_| ocr::is_available()) else {
+            return;
+        };
+
+        let id = self.id;
+        let store = self.store.clone();
+        cx.spawn(async move |_, cx| {
+            let text = cx
+                .background_executor()
+                .spawn(async move {
+                    let (extension, _) = format_names(picture.format);
+                    ocr::extract_text_from_bytes(&picture.bytes, extension)
+                })
+                .await;
+
+            match text {
+                Ok(text) => {
+                    if let Err(err) = store.update_sticker_search_text(id, text).await {
+                        tracing::warn!(id, error = ?err, "Failed to save image search text");
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(id, error = ?err, "Failed to extract text from image");
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn empty_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_flex()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .gap_2()
+            .p_2()
+            .text_sm()
+            .child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Drop or paste (Ctrl+V) a PNG/JPEG image"),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("paste")
+                            .label("Paste")
+                            .small()
+                            .on_click(cx.listener(|this, _, _, cx| this.paste(cx))),
+                    )
+                    .child(
+                        Button::new("open")
+                            .label("Open…")
+                            .small()
+                            .on_click(cx.listener(|this, _, _, cx| this.open_file(cx))),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    fn image_view_element(&self, picture: Arc<gpui::Image>, cx: &mut Context<Self>) -> AnyElement {
+        let picture = match (&self.edited, self.content.transform.is_identity()) {
+            (Some(edited), false) => img(edited.clone()),
+            _ => img(picture),
+        };
+
+        let image_bounds = self.image_bounds.clone();
+        let view = self.image_view();
+        let edited_size = self
+            .original_size
+            .map(|size| self.content.transform.size(size));
+        let strokes = self.display_strokes.clone();
+        let crop_drag = self.crop_drag;
+
+        div()
+            .id("image")
+            .relative()
+            .size_full()
+            .child(
+                picture
+                    .size_full()
+                    .object_fit(ObjectFit::Contain)
+                    .when(self.mode == EditMode::View, |v| v.window_drag_area()),
+            )
+            .child(
+                canvas(
+                    move |bounds, _, _| image_bounds.set(bounds),
+                    move |_, _, window, _| {
+                        if let (Some(view), Some((width, height))) = (view, edited_size) {
+                            // Strokes cut away by a crop stay stored, but are not shown.
+                            let mask = ContentMask {
+                                bounds: Bounds::new(
+                                    point(px(view.pan_x), px(view.pan_y)),
+                                    gpui::size(
+                                        px(width as f32 * view.zoom),
+                                        px(height as f32 * view.zoom),
+                                    ),
+                                ),
+                            };
+                            window.with_content_mask(Some(mask), |window| {
+                                super::paint::paint_strokes(window, &strokes, &view);
+                            });
+                        }
+                        if let Some((from, to)) = crop_drag {
+                            let selection = Bounds::from_corners(
+                                point(from.x.min(to.x), from.y.min(to.y)),
+                                point(from.x.max(to.x), from.y.max(to.y)),
+                            );
+                            window.paint_quad(gpui::fill(selection, rgba(0xffffff55)));
+                        }
+                    },
+                )
+                .absolute()
+                .top_0()
+                .left_0()
+                .size_full(),
+            )
+            .when(self.mode != EditMode::View, |v| v.cursor_crosshair())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, ev: &MouseDownEvent, _, cx| match this.mode {
+                    EditMode::View => {}
+                    EditMode::Crop => {
+                        this.crop_drag = Some((ev.position, ev.position));
+                        cx.notify();
+                    }
+                    EditMode::Annotate => this.start_stroke(ev.position, cx),
+                }),
+            )
+            .on_mouse_move(cx.listener(|this, ev: &MouseMoveEvent, _, cx| {
+                if let Some((from, _)) = this.crop_drag {
+                    this.crop_drag = Some((from, ev.position));
+                    cx.notify();
+                } else if this.annotating {
+                    this.extend_stroke(ev.position, cx);
+                }
+            }))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, ev: &MouseUpEvent, _, cx| {
+                    if let Some((from, _)) = this.crop_drag.take() {
+                        this.apply_crop(from, ev.position, cx);
+                        cx.notify();
+                    } else if this.annotating {
+                        this.annotating = false;
+                        this.save_state(None, cx);
+                    }
+                }),
+            )
+            .into_any_element()
+    }
+
+    fn toolbar_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        let button = |id: &'static str, icon: IconName, tooltip: &'static str| {
+            Button::new(id)
+                .icon(icon)
+                .bg(transparent_white())
+                .border_0()
+                .xsmall()
+                .tooltip(tooltip)
+        };
+
+        let mut toolbar = h_flex().gap_1().items_center().flex_wrap().justify_end();
+
+        if self.mode == EditMode::Annotate {
+            for &c in PAINT_COLORS.iter() {
+                let is_selected = c == self.current_color;
+                toolbar = toolbar.child(
+                    div()
+                        .w(px(12.0))
+                        .h(px(12.0))
+                        .bg(rgba(c))
+                        .rounded_full()
+                        .cursor_pointer()
+                        .border_1()
+                        .border_color(match is_selected {
+                            true => white(),
+                            false => transparent_white(),
+                        })
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _, _, cx| {
+                                this.current_color = c;
+                                cx.stop_propagation();
+                                cx.notify();
+                            }),
+                        ),
+                );
+            }
+            toolbar = toolbar.child(
+                button("undo-stroke", IconName::Undo, "Undo the last annotation")
+                    .disabled(self.content.strokes.is_empty())
+                    .on_click(cx.listener(|this, _, _, cx| this.undo_stroke(cx))),
+            );
+        }
+
+        toolbar = toolbar
+            .child(
+                button("rotate", IconName::RotateCw, "Rotate clockwise")
+                    .on_click(cx.listener(|this, _, _, cx| this.rotate(cx))),
+            )
+            .child(
+                button(
+                    "crop",
+                    IconName::Crop,
+                    "Crop, drag over the image (Esc to cancel)",
+                )
+                .selected(self.mode == EditMode::Crop)
+                .on_click(cx.listener(|this, _, _, cx| this.toggle_mode(EditMode::Crop, cx))),
+            )
+            .child(
+                button("annotate", IconName::Paint, "Draw on the image")
+                    .selected(self.mode == EditMode::Annotate)
+                    .on_click(
+                        cx.listener(|this, _, _, cx| this.toggle_mode(EditMode::Annotate, cx)),
+                    ),
+            )
+            .child(
+                button("copy", IconName::Copy, "Copy the edited image")
+                    .on_click(cx.listener(|this, _, _, cx| this.copy_image(cx))),
+            )
+            .when(self.has_edits(), |v| {
+                v.child(
+                    button(
+                        "reset-edits",
+                        IconName::Refresh,
+                        "Back to the original image",
+                    )
+                    .on_click(cx.listener(|this, _, _, cx| this.reset_edits(cx))),
+                )
+            });
+
+        div()
+            .absolute()
+            .top_1()
+            .right_1()
+            .left_1()
+            .child(toolbar)
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for ImageSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_state(None, cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(100, 100)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(400, 300)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for ImageSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(cx.theme().font_size);
+
+        let body = v_flex()
+            .relative()
+            .size_full()
+            .p_1()
+            .bg(Rgba {
+                a: 0.85,
+                ..self.color.bg()
+            })
+            .track_focus(&self.focus_handle)
+            .on_mouse_down(
+                gpui::MouseButton::Left,
+                cx.listener(|this, _, window, _| window.focus(&this.focus_handle)),
+            )
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _, cx| {
+                let modifiers = &event.keystroke.modifiers;
+                if (modifiers.control || modifiers.platform)
+                    && event.keystroke.key.eq_ignore_ascii_case("v")
+                {
+                    this.paste(cx);
+                } else if event.keystroke.key == "escape" && this.mode != EditMode::View {
+                    this.toggle_mode(this.mode, cx);
+                }
+            }))
+            .on_drop(cx.listener(|this, paths: &ExternalPaths, _, cx| {
+                this.drop_paths(paths, cx);
+            }));
+
+        let body = match self.picture.clone() {
+            Some(picture) => body.child(self.image_view_element(picture, cx)),
+            None if self.loading => body.child(
+                div()
+                    .size_full()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Loading…"),
+            ),
+            None => body.child(self.empty_view(cx)),
+        };
+
+        let body = body.when(
+            window.is_window_hovered() && self.picture.is_some(),
+            |view| view.child(self.toolbar_view(cx)),
+        );
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(
+                div()
+                    .absolute()
+                    .bottom_0()
+                    .left_0()
+                    .right_0()
+                    .child(Alert::error("image-error", msg.as_str()).small()),
+            )
+        })
+    }
+}
+
+/// Store the picture of a sticker as its attachment, returns the hash its content refers
+/// to it by. Copies of the sticker get their own attachment with the same hash.
+pub async fn add_picture(
+    store: &ArcStickerStore,
+    id: i64,
+    picture: &gpui::Image,
+) -> anyhow::Result<String> {
+    let (extension, mime) = format_names(picture.format);
+    let name = format!("{id}-{}.{extension}", crate::utils::time::now_unix_millis());
+    let attachment = store
+        .add_attachment(id, name, mime.to_string(), picture.bytes.clone())
+        .await?;
+    Ok(store.get_attachment(attachment).await?.hash)
+}
+
+/// The picture of the attachment of the sticker with the hash.
+pub async fn read_picture(
+    store: &ArcStickerStore,
+    id: i64,
+    hash: &str,
+) -> anyhow::Result<Arc<gpui::Image>> {
+    let attachment = store
+        .get_sticker_attachments(id)
+        .await?
+        .into_iter()
+        .find(|x| x.hash == hash)
+        .context("the picture is missing")?;
+    let format = picture_format(&attachment.mime)
+        .with_context(|| format!("unsupported picture type {}", attachment.mime))?;
+    let bytes = store.get_attachment_data(attachment.id).await?;
+    Ok(Arc::new(gpui::Image::from_bytes(format, bytes)))
+}
+
+/// Drop the attachment of the sticker with the hash, its data stays while other
+/// stickers have it.
+pub async fn remove_picture(store: &ArcStickerStore, id: i64, hash: &str) -> anyhow::Result<()> {
+    for attachment in store.get_sticker_attachments(id).await? {
+        if attachment.hash == hash {
+            store.delete_attachment(attachment.id).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Read a picture file older versions kept in the images directory and move it into an
+/// attachment of the sticker, returns its hash unless the store is read-only. The file
+/// stays for other stickers referring to it until the attachments are collected.
+pub async fn import_picture_file(
+    store: &ArcStickerStore,
+    id: i64,
+    path: &Path,
+) -> anyhow::Result<(Option<String>, Arc<gpui::Image>)> {
+    let format = path
+        .extension()
+        .and_then(|x| x.to_str())
+        .and_then(picture_format)
+        .with_context(|| format!("unsupported picture {}", path.display()))?;
+    let bytes = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    let picture = Arc::new(gpui::Image::from_bytes(format, bytes));
+    if store.is_read_only() {
+        return Ok((None, picture));
+    }
+
+    let hash = add_picture(store, id, &picture).await?;
+    tracing::info!(id, path = %path.display(), "Moved picture file into an attachment");
+    Ok((Some(hash), picture))
+}
+
+/// Content of an image sticker showing a picture stored by [`add_picture`].
+pub fn content_for_attachment(hash: String) -> String {
+    serde_json::to_string(&ImageContent {
+        attachment: Some(hash),
+        ..Default::default()
+    })
+    .unwrap_or_default()
+}
+
+/// Format of a picture by its file extension or MIME type.
+pub fn picture_format(name: &str) -> Option<ImageFormat> {
+    match name.to_ascii_lowercase().as_str() {
+        "png" | "image/png" => Some(ImageFormat::Png),
+        "jpg" | "jpeg" | "image/jpeg" => Some(ImageFormat::Jpeg),
+        "gif" | "image/gif" => Some(ImageFormat::Gif),
+        "webp" | "image/webp" => Some(ImageFormat::Webp),
+        _ => None,
+    }
+}
+
+/// File extension and MIME type of a picture format.
+fn format_names(format: ImageFormat) -> (&'static str, &'static str) {
+    match format {
+        ImageFormat::Jpeg => ("jpg", "image/jpeg"),
+        ImageFormat::Gif => ("gif", "image/gif"),
+        ImageFormat::Webp => ("webp", "image/webp"),
+        _ => ("png", "image/png"),
+    }
+}
+
+pub fn is_supported_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .map(|x| IMAGE_EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn display_stroke(stroke: &PaintStroke, transform: &Transform, size: (u32, u32)) -> PaintStroke {
+    PaintStroke {
+        points: stroke
+            .points
+            .iter()
+            .map(|p| {
+                let (x, y) = transform.forward(size, p.x, p.y);
+                PaintPoint { x, y }
+            })
+            .collect(),
+        color: stroke.color,
+        width: stroke.width,
+    }
+}
+
+/// The picture cropped and rotated, converted to the BGRA pixels GPUI draws.
+fn render_edited(picture: &gpui::Image, transform: &Transform) -> anyhow::Result<Arc<RenderImage>> {
+    let original = image::load_from_memory(&picture.bytes)
+        .context("decode image")?
+        .to_rgba8();
+    let mut buffer = transform.apply(&original);
+    for pixel in buffer.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    Ok(Arc::new(RenderImage::new(vec![image::Frame::new(buffer)])))
+}
+
+/// The picture with annotations drawn on it, cropped and rotated, as PNG.
+fn export_png(
+    picture: &gpui::Image,
+    transform: &Transform,
+    strokes: &[PaintStroke],
+) -> anyhow::Result<Vec<u8>> {
+    let mut original = image::load_from_memory(&picture.bytes)
+        .context("decode image")?
+        .to_rgba8();
+    for stroke in strokes {
+        let points = stroke.points.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+        image_edit::draw_polyline(&mut original, &points, stroke.color, stroke.width);
+    }
+    image_edit::encode_png(&transform.apply(&original))
+}
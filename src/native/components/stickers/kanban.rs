@@ -0,0 +1,686 @@
+use gpui::{
+    AnyElement, App, AppContext, Context, Entity, Render, Rgba, SharedString, Window, div,
+    prelude::*, px, transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+const MIN_COLUMNS: usize = 2;
+const MAX_COLUMNS: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct KanbanContent {
+    columns: Vec<Column>,
+}
+
+impl Default for KanbanContent {
+    fn default() -> Self {
+        Self {
+            columns: ["To do", "Doing", "Done"]
+                .into_iter()
+                .map(|title| Column {
+                    title: title.to_string(),
+                    cards: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Column {
+    title: String,
+    cards: Vec<String>,
+}
+
+/// The card being dragged, it also renders itself as the drag preview.
+#[derive(Clone)]
+struct DraggedCard {
+    column: usize,
+    index: usize,
+    text: SharedString,
+}
+
+impl Render for DraggedCard {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .w(px(140.0))
+            .p_1()
+            .rounded_sm()
+            .text_xs()
+            .bg(cx.theme().background.opacity(0.9))
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(self.text.clone())
+    }
+}
+
+pub struct KanbanSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: KanbanContent,
+
+    /// "Add a card" input of each column.
+    card_inputs: Vec<Entity<InputState>>,
+    columns_input: Entity<InputState>,
+    editing: bool,
+
+    error: Option<String>,
+}
+
+impl KanbanSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let mut content = serde_json::from_str::<KanbanContent>(content).unwrap_or_default();
+        if content.columns.len() < MIN_COLUMNS {
+            content = KanbanContent::default();
+        }
+
+        let columns = column_titles(&content);
+        let columns_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(MIN_COLUMNS, MAX_COLUMNS)
+                .default_value(columns)
+                .placeholder("One column per line")
+        });
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            card_inputs: Vec::new(),
+            columns_input,
+            editing: false,
+            error: None,
+        };
+        this.create_card_inputs(window, cx);
+        this
+    }
+
+    fn create_card_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.card_inputs = (0..self.content.columns.len())
+            .map(|column| {
+                let input = cx.new(|cx| InputState::new(window, cx).placeholder("Add a card"));
+                cx.subscribe_in(
+                    &input,
+                    window,
+                    move |this, input, event: &InputEvent, window, cx| {
+                        if let InputEvent::PressEnter { .. } = event {
+                            let text = input.read(cx).value().trim().to_string();
+                            if !text.is_empty() {
+                                input.update(cx, |input, cx| input.set_value("", window, cx));
+                                this.add_card(column, text, cx);
+                            }
+                        }
+                    },
+                )
+                .detach();
+                input
+            })
+            .collect();
+    }
+
+    /// "To do 3 · Doing 1 · Done 5", so the board shows up in the main window.
+    fn title(&self) -> String {
+        self.content
+            .columns
+            .iter()
+            .map(|column| format!("{} {}", column.title, column.cards.len()))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize kanban sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save kanban sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save kanban sticker: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn add_card(&mut self, column: usize, text: String, cx: &mut Context<Self>) {
+        let Some(column) = self.content.columns.get_mut(column) else {
+            return;
+        };
+
+        column.cards.push(text);
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn remove_card(&mut self, column: usize, index: usize, cx: &mut Context<Self>) {
+        let Some(column) = self.content.columns.get_mut(column) else {
+            return;
+        };
+        if index >= column.cards.len() {
+            return;
+        }
+
+        column.cards.remove(index);
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    /// Move a card in front of the card at `before`, or to the end of the column.
+    fn move_card(
+        &mut self,
+        card: &DraggedCard,
+        to_column: usize,
+        before: Option<usize>,
+        cx: &mut Context<Self>,
+    ) {
+        if to_column >= self.content.columns.len() {
+            return;
+        }
+        let Some(from) = self.content.columns.get_mut(card.column) else {
+            return;
+        };
+        if card.index >= from.cards.len() {
+            return;
+        }
+
+        let text = from.cards.remove(card.index);
+        let cards = &mut self.content.columns[to_column].cards;
+        let mut at = before.unwrap_or(cards.len() + 1);
+        // The card itself was removed above, later positions moved up by one.
+        if card.column == to_column && at > card.index {
+            at -= 1;
+        }
+        cards.insert(at.min(cards.len()), text);
+
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn apply_form(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let titles = self
+            .columns_input
+            .read(cx)
+            .value()
+            .lines()
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if !(MIN_COLUMNS..=MAX_COLUMNS).contains(&titles.len()) {
+            self.error = Some(format!(
+                "A board has {MIN_COLUMNS} to {MAX_COLUMNS} columns, one per line"
+            ));
+            cx.notify();
+            return;
+        }
+
+        // Columns are matched by title, cards of a removed column move to the first one.
+        let mut old = std::mem::take(&mut self.content.columns);
+        let mut columns = titles
+            .into_iter()
+            .map(|title| match old.iter().position(|x| x.title == title) {
+                Some(index) => old.remove(index),
+                None => Column {
+                    title,
+                    cards: Vec::new(),
+                },
+            })
+            .collect::<Vec<_>>();
+        for column in old {
+            columns[0].cards.extend(column.cards);
+        }
+
+        self.content.columns = columns;
+        self.create_card_inputs(window, cx);
+        self.editing = false;
+        self.error = None;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn card(
+        &self,
+        column: usize,
+        index: usize,
+        text: &str,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let dragged = DraggedCard {
+            column,
+            index,
+            text: SharedString::from(text.to_string()),
+        };
+
+        h_flex()
+            .id(SharedString::from(format!("card-{column}-{index}")))
+            .w_full()
+            .p_1()
+            .gap_1()
+            .items_start()
+            .rounded_sm()
+            .text_xs()
+            .bg(cx.theme().background.opacity(0.6))
+            .cursor_grab()
+            .on_drag(dragged, |card, _, _, cx: &mut App| cx.new(|_| card.clone()))
+            .drag_over::<DraggedCard>(|style, _, _, cx| style.bg(cx.theme().accent))
+            .on_drop(cx.listener(move |this, card: &DraggedCard, _, cx| {
+                this.move_card(card, column, Some(index), cx);
+            }))
+            .child(div().flex_1().child(text.to_string()))
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    Button::new(SharedString::from(format!("remove-{column}-{index}")))
+                        .icon(IconName::Close)
+                        .bg(transparent_white())
+                        .border_0()
+                        .xsmall()
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.remove_card(column, index, cx);
+                        })),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn board_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let columns = self
+            .content
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(column_index, column)| {
+                let cards = column
+                    .cards
+                    .iter()
+                    .enumerate()
+                    .map(|(index, text)| self.card(column_index, index, text, window, cx))
+                    .collect::<Vec<_>>();
+
+                v_flex()
+                    .id(SharedString::from(format!("column-{column_index}")))
+                    .flex_1()
+                    .min_w_0()
+                    .h_full()
+                    .gap_1()
+                    .p_1()
+                    .rounded_md()
+                    .bg(cx.theme().muted.opacity(0.4))
+                    .drag_over::<DraggedCard>(|style, _, _, cx| {
+                        style.bg(cx.theme().accent.opacity(0.4))
+                    })
+                    .on_drop(cx.listener(move |this, card: &DraggedCard, _, cx| {
+                        this.move_card(card, column_index, None, cx);
+                    }))
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .window_drag_area()
+                            .child(column.title.clone())
+                            .child(column.cards.len().to_string()),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .overflow_hidden()
+                            .child(v_flex().gap_1().overflow_y_scrollbar().children(cards)),
+                    )
+                    .when_some(self.card_inputs.get(column_index), |view, input| {
+                        view.child(Input::new(input).xsmall())
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .p_1()
+            .gap_1()
+            .child(h_flex().flex_1().gap_1().items_start().children(columns))
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex().child(
+                        Button::new("edit")
+                            .icon(IconName::Adjustments)
+                            .bg(transparent_white())
+                            .border_0()
+                            .xsmall()
+                            .tooltip("Change columns")
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.editing = true;
+                                cx.notify();
+                            })),
+                    ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field()
+                    .label(format!("Columns ({MIN_COLUMNS}–{MAX_COLUMNS})"))
+                    .child(Input::new(&self.columns_input).small()),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, window, cx| this.apply_form(window, cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for KanbanSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(240, 160)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(460, 320)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn content(&self, _cx: &App) -> Option<String> {
+        serde_json::to_string(&self.content).ok()
+    }
+}
+
+impl Render for KanbanSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            body = body.child(self.board_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("kanban-error", msg.as_str()).small())
+        })
+    }
+}
+
+fn column_titles(content: &KanbanContent) -> String {
+    content
+        .columns
+        .iter()
+        .map(|x| x.title.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Board from the checkbox lines of a Markdown sticker.
+///
+/// Headings with checkbox lines below become the columns, so a board converted to
+/// Markdown and back keeps its columns. Without enough of them open items go to the
+/// first default column and checked ones to the last. Other lines are dropped, see
+/// [`lines_dropped_from_markdown`].
+pub(super) fn content_from_markdown(markdown: &str) -> anyhow::Result<String> {
+    let mut sections: Vec<(Option<String>, Vec<(bool, String)>)> = vec![(None, Vec::new())];
+    for line in markdown.lines().map(str::trim) {
+        if line.starts_with('#') {
+            let heading = line.trim_start_matches('#').trim().to_string();
+            sections.push((Some(heading), Vec::new()));
+        } else if let Some(item) = checkbox_item(line) {
+            sections.last_mut().unwrap().1.push(item);
+        }
+    }
+    sections.retain(|(_, items)| !items.is_empty());
+
+    let by_heading = sections.iter().all(|(heading, _)| heading.is_some())
+        && (MIN_COLUMNS..=MAX_COLUMNS).contains(&sections.len());
+    let content = if by_heading {
+        KanbanContent {
+            columns: sections
+                .into_iter()
+                .map(|(heading, items)| Column {
+                    title: heading.unwrap_or_default(),
+                    cards: items.into_iter().map(|(_, text)| text).collect(),
+                })
+                .collect(),
+        }
+    } else {
+        let mut content = KanbanContent::default();
+        for (checked, text) in sections.into_iter().flat_map(|(_, items)| items) {
+            let column = if checked {
+                content.columns.len() - 1
+            } else {
+                0
+            };
+            content.columns[column].cards.push(text);
+        }
+        content
+    };
+
+    Ok(serde_json::to_string(&content)?)
+}
+
+/// Lines of a Markdown sticker which are neither headings nor checkbox lines, so
+/// [`content_from_markdown`] leaves them out.
+pub(super) fn lines_dropped_from_markdown(markdown: &str) -> usize {
+    markdown
+        .lines()
+        .map(str::trim)
+        .filter(|x| !x.is_empty() && !x.starts_with('#') && checkbox_item(x).is_none())
+        .count()
+}
+
+/// A heading per column with its cards as checkbox lines, checked in the last column.
+pub(super) fn content_to_markdown(content: &str) -> anyhow::Result<String> {
+    let content = serde_json::from_str::<KanbanContent>(content)?;
+    let last = content.columns.len().saturating_sub(1);
+
+    let sections = content
+        .columns
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| !column.cards.is_empty())
+        .map(|(ix, column)| {
+            let mark = if ix == last { "x" } else { " " };
+            let items = column
+                .cards
+                .iter()
+                .map(|card| format!("- [{mark}] {card}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("## {}\n\n{items}", column.title)
+        })
+        .collect::<Vec<_>>();
+
+    Ok(sections.join("\n\n"))
+}
+
+/// `- [ ] Buy milk` -> (false, "Buy milk"), `* [x] Done` -> (true, "Done").
+fn checkbox_item(line: &str) -> Option<(bool, String)> {
+    let line = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?;
+    let (checked, text) = if let Some(text) = line.strip_prefix("[ ]") {
+        (false, text)
+    } else if let Some(text) = line
+        .strip_prefix("[x]")
+        .or_else(|| line.strip_prefix("[X]"))
+    {
+        (true, text)
+    } else {
+        return None;
+    };
+
+    let text = text.trim();
+    (!text.is_empty()).then(|| (checked, text.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(content: &str) -> Vec<(String, Vec<String>)> {
+        serde_json::from_str::<KanbanContent>(content)
+            .unwrap()
+            .columns
+            .into_iter()
+            .map(|x| (x.title, x.cards))
+            .collect()
+    }
+
+    fn column(title: &str, cards: &[&str]) -> (String, Vec<String>) {
+        (
+            title.to_string(),
+            cards.iter().map(|x| x.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn checkbox_items() {
+        assert_eq!(
+            checkbox_item("- [ ] Buy milk"),
+            Some((false, "Buy milk".to_string()))
+        );
+        assert_eq!(
+            checkbox_item("* [x] Done"),
+            Some((true, "Done".to_string()))
+        );
+        assert_eq!(
+            checkbox_item("+ [X]  Shout "),
+            Some((true, "Shout".to_string()))
+        );
+        assert_eq!(checkbox_item("- [ ]"), None);
+        assert_eq!(checkbox_item("- plain item"), None);
+        assert_eq!(checkbox_item("[ ] no bullet"), None);
+    }
+
+    #[test]
+    fn checklist_to_default_columns() {
+        let markdown = "# Groceries\n- [ ] milk\n* [x] eggs\nsome note\n  + [X] bread\n- [ ]   \n";
+        assert_eq!(
+            columns(&content_from_markdown(markdown).unwrap()),
+            [
+                column("To do", &["milk"]),
+                column("Doing", &[]),
+                column("Done", &["eggs", "bread"]),
+            ]
+        );
+        assert_eq!(lines_dropped_from_markdown(markdown), 2);
+    }
+
+    #[test]
+    fn headings_to_columns() {
+        let markdown = "## Backlog\n- [ ] a\n\n## Review\n- [ ] b\n- [x] c\n\n## Notes\nnot a card";
+        assert_eq!(
+            columns(&content_from_markdown(markdown).unwrap()),
+            [column("Backlog", &["a"]), column("Review", &["b", "c"])]
+        );
+        assert_eq!(lines_dropped_from_markdown(markdown), 1);
+
+        // Too many headings for a board, they are ignored.
+        let markdown = "# 1\n- [ ] a\n# 2\n- [ ] b\n# 3\n- [ ] c\n# 4\n- [ ] d\n# 5\n- [x] e";
+        assert_eq!(
+            columns(&content_from_markdown(markdown).unwrap()),
+            [
+                column("To do", &["a", "b", "c", "d"]),
+                column("Doing", &[]),
+                column("Done", &["e"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn board_round_trip() {
+        let content = r#"{"columns":[{"title":"To do","cards":["a"]},{"title":"Doing","cards":["b"]},{"title":"Done","cards":["c","d"]}]}"#;
+        let markdown = content_to_markdown(content).unwrap();
+        assert_eq!(
+            markdown,
+            "## To do\n\n- [ ] a\n\n## Doing\n\n- [ ] b\n\n## Done\n\n- [x] c\n- [x] d"
+        );
+        assert_eq!(lines_dropped_from_markdown(&markdown), 0);
+        assert_eq!(content_from_markdown(&markdown).unwrap(), content);
+    }
+
+    #[test]
+    fn empty_columns_are_left_out() {
+        let content =
+            r#"{"columns":[{"title":"To do","cards":["a"]},{"title":"Done","cards":[]}]}"#;
+        assert_eq!(content_to_markdown(content).unwrap(), "## To do\n\n- [ ] a");
+        assert!(content_to_markdown("not json").is_err());
+    }
+}
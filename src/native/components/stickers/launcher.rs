@@ -0,0 +1,513 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use gpui::{
+    AnyElement, App, AppContext, Context, Entity, ExternalPaths, MouseButton, Render, Rgba, Window,
+    div, prelude::*, px, transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputState},
+    scroll::ScrollableElement,
+    tooltip::Tooltip,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+const TILE_SIZE: f32 = 64.0;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct LauncherContent {
+    items: Vec<LaunchItem>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct LaunchItem {
+    label: String,
+    /// A URL, a folder, an executable or any file opened with its default app.
+    target: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LaunchKind {
+    Url,
+    Folder,
+    Executable,
+    File,
+}
+
+impl LaunchItem {
+    fn from_path(path: &Path) -> Self {
+        // "Code.exe" -> "Code", but keep "notes.txt" apart from "notes.md".
+        let label = if is_executable(path) {
+            path.file_stem()
+        } else {
+            path.file_name()
+        };
+        Self {
+            label: label
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string()),
+            target: path.to_string_lossy().to_string(),
+        }
+    }
+
+    /// `Label | target` or just the target, one item per line of the editor.
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        let (label, target) = match line.split_once(" | ") {
+            Some((label, target)) => (label.trim().to_string(), target.trim().to_string()),
+            None => (String::new(), line.to_string()),
+        };
+        if target.is_empty() {
+            return None;
+        }
+        let mut item = if is_url(&target) {
+            Self {
+                label: String::new(),
+                target,
+            }
+        } else {
+            Self::from_path(Path::new(&target))
+        };
+        if !label.is_empty() {
+            item.label = label;
+        } else if item.label.is_empty() {
+            item.label = url_host(&item.target).to_string();
+        }
+        Some(item)
+    }
+
+    fn to_line(&self) -> String {
+        format!("{} | {}", self.label, self.target)
+    }
+
+    fn kind(&self) -> LaunchKind {
+        if is_url(&self.target) {
+            return LaunchKind::Url;
+        }
+        let path = Path::new(&self.target);
+        if path.is_dir() {
+            LaunchKind::Folder
+        } else if is_executable(path) {
+            LaunchKind::Executable
+        } else {
+            LaunchKind::File
+        }
+    }
+
+    fn icon(&self) -> IconName {
+        match self.kind() {
+            LaunchKind::Url => IconName::Globe,
+            LaunchKind::Folder => IconName::Folder,
+            LaunchKind::Executable => IconName::Command,
+            LaunchKind::File => IconName::DocumentText,
+        }
+    }
+
+    fn launch(&self, cx: &App) -> anyhow::Result<()> {
+        match self.kind() {
+            LaunchKind::Url => webbrowser::open(&self.target)?,
+            // Windows runs programs through the shell, which also picks their working directory.
+            LaunchKind::Executable if !cfg!(target_os = "windows") => {
+                let path = PathBuf::from(&self.target);
+                let mut child = Command::new(&path)
+                    .current_dir(path.parent().unwrap_or(Path::new(".")))
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()?;
+                // Reap the process once it exits so it does not linger as a zombie.
+                std::thread::spawn(move || {
+                    let _ = child.wait();
+                });
+            }
+            _ => {
+                let path = Path::new(&self.target);
+                if !path.exists() {
+                    anyhow::bail!("{} does not exist", self.target);
+                }
+                cx.open_with_system(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_url(target: &str) -> bool {
+    target.contains("://") || target.starts_with("mailto:")
+}
+
+/// "https://github.com/slaveOftime" -> "github.com".
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    rest.split(['/', '?', '#']).next().unwrap_or(rest)
+}
+
+#[cfg(target_os = "windows")]
+fn is_executable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .is_some_and(|x| ["exe", "bat", "cmd", "lnk"].contains(&x.to_ascii_lowercase().as_str()))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+pub struct LauncherSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: LauncherContent,
+
+    items_input: Entity<InputState>,
+    editing: bool,
+
+    error: Option<String>,
+}
+
+impl LauncherSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<LauncherContent>(content).unwrap_or_default();
+
+        let items_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(3, 10)
+                .placeholder("One per line: Label | https://…, a folder or a program")
+        });
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            items_input,
+            editing: false,
+            error: None,
+        }
+    }
+
+    /// "Code, Downloads, github.com", so the shortcuts can be searched for in the main window.
+    fn title(&self) -> String {
+        if self.content.items.is_empty() {
+            return "Launcher".to_string();
+        }
+        self.content
+            .items
+            .iter()
+            .map(|x| x.label.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize launcher sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save launcher sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save launcher sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn drop_paths(&mut self, paths: &ExternalPaths, cx: &mut Context<Self>) {
+        let mut added = false;
+        for path in paths.paths() {
+            let item = LaunchItem::from_path(path);
+            if !self.content.items.iter().any(|x| x.target == item.target) {
+                self.content.items.push(item);
+                added = true;
+            }
+        }
+        if added {
+            self.error = None;
+            self.save_config(cx);
+        }
+        cx.notify();
+    }
+
+    fn launch(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some(item) = self.content.items.get(ix) else {
+            return;
+        };
+        match item.launch(cx) {
+            Ok(()) => self.error = None,
+            Err(err) => {
+                tracing::warn!(id = self.id, target = %item.target, error = ?err, "Failed to launch");
+                self.error = Some(format!("Failed to open {}: {err:#}", item.label));
+            }
+        }
+        cx.notify();
+    }
+
+    fn remove_item(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix < self.content.items.len() {
+            self.content.items.remove(ix);
+            self.save_config(cx);
+            cx.notify();
+        }
+    }
+
+    fn start_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self
+            .content
+            .items
+            .iter()
+            .map(LaunchItem::to_line)
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.items_input
+            .update(cx, |input, cx| input.set_value(text, window, cx));
+        self.editing = true;
+        cx.notify();
+    }
+
+    fn apply_form(&mut self, cx: &mut Context<Self>) {
+        let text = self.items_input.read(cx).value().to_string();
+        let mut items = Vec::<LaunchItem>::new();
+        for item in text.lines().filter_map(LaunchItem::parse) {
+            if !items.iter().any(|x| x.target == item.target) {
+                items.push(item);
+            }
+        }
+
+        self.content.items = items;
+        self.editing = false;
+        self.error = None;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn tile(&self, ix: usize, item: &LaunchItem, cx: &mut Context<Self>) -> AnyElement {
+        v_flex()
+            .id(("launch-item", ix))
+            .w(px(TILE_SIZE))
+            .h(px(TILE_SIZE))
+            .p_1()
+            .gap_1()
+            .items_center()
+            .justify_center()
+            .rounded_md()
+            .cursor_pointer()
+            .hover(|style| style.bg(cx.theme().accent))
+            .tooltip({
+                let target = item.target.clone();
+                move |window, cx| Tooltip::new(target.clone()).build(window, cx)
+            })
+            .on_click(cx.listener(move |this, _, _, cx| this.launch(ix, cx)))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, _, _, cx| this.remove_item(ix, cx)),
+            )
+            .child(Icon::new(item.icon()).with_size(px(24.)))
+            .child(
+                div()
+                    .w_full()
+                    .text_xs()
+                    .text_center()
+                    .truncate()
+                    .child(item.label.clone()),
+            )
+            .into_any_element()
+    }
+
+    fn grid_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let tiles = self
+            .content
+            .items
+            .iter()
+            .enumerate()
+            .map(|(ix, item)| self.tile(ix, item, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .child(
+                div()
+                    .id("launcher-drop")
+                    .flex_1()
+                    .overflow_hidden()
+                    .rounded_md()
+                    .window_drag_area()
+                    .drag_over::<ExternalPaths>(|style, _, _, cx| style.bg(cx.theme().accent))
+                    .on_drop(cx.listener(|this, paths: &ExternalPaths, _, cx| {
+                        this.drop_paths(paths, cx);
+                    }))
+                    .map(|view| {
+                        if tiles.is_empty() {
+                            view.child(
+                                div()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child("Drop programs, folders or files here"),
+                            )
+                        } else {
+                            view.child(
+                                v_flex()
+                                    .overflow_y_scrollbar()
+                                    .child(h_flex().gap_1().flex_wrap().children(tiles)),
+                            )
+                        }
+                    }),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("edit")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Edit shortcuts")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.start_editing(window, cx)
+                                })),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("click to open, right click to remove"),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field()
+                    .label("Shortcuts")
+                    .child(Input::new(&self.items_input).small()),
+            )
+            .child(
+                field().child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("save")
+                                .label("Save")
+                                .small()
+                                .primary()
+                                .on_click(cx.listener(|this, _, _, cx| this.apply_form(cx))),
+                        )
+                        .child(Button::new("cancel").label("Cancel").small().on_click(
+                            cx.listener(|this, _, _, cx| {
+                                this.editing = false;
+                                this.error = None;
+                                cx.notify();
+                            }),
+                        )),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for LauncherSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(120, 120)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(300, 200)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for LauncherSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            body = body.child(self.grid_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("launcher-error", msg.as_str()).small())
+        })
+    }
+}
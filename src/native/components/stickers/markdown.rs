@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
 use gpui::{
-    Context, Entity, KeyDownEvent, MouseButton, MouseDownEvent, Rgba, Window, WindowControlArea,
-    div, prelude::*, px, rgba,
+    Context, Entity, KeyDownEvent, MouseButton, MouseDownEvent, Rgba, Window, div, prelude::*, px,
+    rgba,
 };
 use gpui_component::text::TextView;
 use gpui_component::{ActiveTheme, Sizable, h_flex};
@@ -10,9 +14,12 @@ use gpui_component::{
     v_flex,
 };
 
-use crate::model::sticker::StickerColor;
+use super::save_content;
+use crate::model::sticker::{StickerColor, StickerSettings, markdown_summary};
+use crate::native::decorations::WindowDragArea;
 use crate::native::windows::StickerWindowEvent;
 use crate::storage::ArcStickerStore;
+use crate::utils::ocr;
 
 pub struct MarkdownSticker {
     id: i64,
@@ -21,7 +28,10 @@ pub struct MarkdownSticker {
     sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
     editor: Entity<InputState>,
     editing: bool,
+    settings: StickerSettings,
     error: Option<String>,
+    /// Text of the linked images by path, with the modification time it was read at.
+    ocr_texts: HashMap<PathBuf, (Option<SystemTime>, String)>,
 }
 
 impl MarkdownSticker {
@@ -49,7 +59,9 @@ impl MarkdownSticker {
             sticker_events_tx,
             editor,
             editing: content.is_empty(),
+            settings: StickerSettings::default(),
             error: None,
+            ocr_texts: HashMap::new(),
         }
     }
 
@@ -63,6 +75,9 @@ impl MarkdownSticker {
             .unwrap_or("")
             .to_string();
 
+        self.spawn_ocr(&content, cx);
+
+        let summary = markdown_summary(&content);
         let id = self.id;
         let store = self.store.clone();
         let sticker_events_tx = self.sticker_events_tx.clone();
@@ -85,7 +100,7 @@ impl MarkdownSticker {
                 );
             }
 
-            if let Err(err) = store.update_sticker_content(id, content).await {
+            if let Err(err) = save_content(&store, &sticker_events_tx, id, content, summary).await {
                 let _ = entity.update(cx, |this, cx| {
                     this.error = Some(format!("{err:#}"));
                     cx.notify();
@@ -103,6 +118,61 @@ impl MarkdownSticker {
 
         true
     }
+
+    /// Make the text of local screenshots the note links to searchable, when tesseract
+    /// is installed. Only new and changed images are read again.
+    fn spawn_ocr(&self, content: &str, cx: &mut Context<Self>) {
+        if !ocr::is_available() {
+            return;
+        }
+
+        let images = ocr::local_image_paths(content);
+        if images.is_empty() && self.ocr_texts.is_empty() {
+            return;
+        }
+        let mut known = self.ocr_texts.clone();
+        let id = self.id;
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let (texts, changed) = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut changed = images.len() != known.len();
+                    let mut texts = HashMap::new();
+                    for path in images {
+                        let modified = std::fs::metadata(&path).and_then(|x| x.modified()).ok();
+                        let text = match known.remove(&path) {
+                            Some((at, text)) if at == modified => text,
+                            _ => {
+                                changed = true;
+                                ocr::extract_text_from_file(&path).unwrap_or_else(|err| {
+                                    tracing::warn!(path = %path.display(), error = ?err, "OCR failed");
+                                    String::new()
+                                })
+                            }
+                        };
+                        texts.insert(path, (modified, text));
+                    }
+                    (texts, changed)
+                })
+                .await;
+            if !changed {
+                return;
+            }
+
+            let text = texts
+                .values()
+                .map(|(_, text)| text.as_str())
+                .filter(|x| !x.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let _ = entity.update(cx, |this, _| this.ocr_texts = texts);
+            if let Err(err) = store.update_sticker_search_text(id, text).await {
+                tracing::warn!(id, error = ?err, "Failed to save OCR text");
+            }
+        })
+        .detach();
+    }
 }
 
 impl super::Sticker for MarkdownSticker {
@@ -121,6 +191,38 @@ impl super::Sticker for MarkdownSticker {
     fn set_color(&mut self, color: StickerColor) {
         self.color = color;
     }
+
+    fn has_text_output(&self) -> bool {
+        !self.editing
+    }
+
+    fn apply_settings(&mut self, settings: &StickerSettings) {
+        self.settings = settings.clone();
+    }
+
+    fn can_print(&self) -> bool {
+        true
+    }
+
+    fn print_html(&self, cx: &gpui::App) -> Option<String> {
+        Some(crate::utils::print::markdown_to_html(
+            &self.editor.read(cx).value(),
+        ))
+    }
+
+    fn set_content(&mut self, content: &str, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        if self.editing {
+            return false;
+        }
+        self.editor.update(cx, |editor, cx| {
+            editor.set_value(content.to_string(), window, cx)
+        });
+        true
+    }
+
+    fn content(&self, cx: &gpui::App) -> Option<String> {
+        Some(self.editor.read(cx).value().to_string())
+    }
 }
 
 impl Render for MarkdownSticker {
@@ -177,6 +279,10 @@ impl Render for MarkdownSticker {
                             .py_1()
                             .px_2()
                             .size_full()
+                            .when(self.settings.monospace, |v| {
+                                v.font_family(cx.theme().mono_font_family.clone())
+                            })
+                            .when(self.settings.no_wrap, |v| v.whitespace_nowrap())
                             .selectable(true)
                             .scrollable(true),
                     )
@@ -188,7 +294,7 @@ impl Render for MarkdownSticker {
                             .top_0()
                             .right_0()
                             .h_5()
-                            .window_control_area(WindowControlArea::Drag),
+                            .window_drag_area(),
                     ),
             );
         }
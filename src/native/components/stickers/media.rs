@@ -0,0 +1,274 @@
+use std::time::Duration;
+
+use gpui::{
+    AnyElement, Context, FontWeight, Render, Rgba, Window, div, prelude::*, px, transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::media::{self, MediaAction, NowPlaying};
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+/// Asking the media session is cheap on Linux but starts PowerShell on Windows.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+pub struct MediaSticker {
+    id: i64,
+    color: StickerColor,
+
+    now_playing: Option<NowPlaying>,
+    loaded: bool,
+    loading: bool,
+    ticking: bool,
+    /// A control was sent, the buttons are disabled until it is done.
+    sending: bool,
+
+    error: Option<String>,
+}
+
+impl MediaSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        _store: ArcStickerStore,
+        _content: &str,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+        _sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let mut this = Self {
+            id,
+            color,
+            now_playing: None,
+            loaded: false,
+            loading: false,
+            ticking: false,
+            sending: false,
+            error: None,
+        };
+        this.reload(cx);
+        this
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        if self.loading {
+            return;
+        }
+        self.loading = true;
+
+        cx.spawn(async move |this, cx| {
+            let loaded = cx
+                .background_executor()
+                .spawn(async move { media::now_playing() })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                this.loading = false;
+                this.loaded = true;
+                match loaded {
+                    Ok(now_playing) => {
+                        this.now_playing = now_playing;
+                        this.error = None;
+                    }
+                    Err(err) => {
+                        tracing::debug!(id = this.id, error = ?err, "Failed to read media session");
+                        this.now_playing = None;
+                        this.error = Some(format!("{err:#}"));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(POLL_INTERVAL).await;
+            let _ = this.update(cx, |this, cx| {
+                this.ticking = false;
+                this.reload(cx);
+            });
+        })
+        .detach();
+    }
+
+    fn send(&mut self, action: MediaAction, cx: &mut Context<Self>) {
+        if self.sending {
+            return;
+        }
+        self.sending = true;
+        // Show the new state right away, the next poll confirms it.
+        if action == MediaAction::PlayPause
+            && let Some(now_playing) = self.now_playing.as_mut()
+        {
+            now_playing.playing = !now_playing.playing;
+        }
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let sent = cx
+                .background_executor()
+                .spawn(async move { media::control(action) })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                this.sending = false;
+                if let Err(err) = sent {
+                    tracing::warn!(id = this.id, ?action, error = ?err, "Failed to control media");
+                    this.error = Some(format!("{err:#}"));
+                }
+                this.reload(cx);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn control_button(
+        &self,
+        id: &'static str,
+        icon: IconName,
+        tooltip: &'static str,
+        action: MediaAction,
+        cx: &mut Context<Self>,
+    ) -> Button {
+        Button::new(id)
+            .icon(icon)
+            .bg(transparent_white())
+            .border_0()
+            .small()
+            .tooltip(tooltip)
+            .disabled(self.sending || self.now_playing.is_none())
+            .on_click(cx.listener(move |this, _, _, cx| this.send(action, cx)))
+    }
+
+    fn player_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        let muted = cx.theme().muted_foreground;
+
+        let info = match &self.now_playing {
+            Some(now_playing) => v_flex()
+                .gap_1()
+                .child(
+                    div()
+                        .text_lg()
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .truncate()
+                        .child(if now_playing.title.is_empty() {
+                            "Unknown title".to_string()
+                        } else {
+                            now_playing.title.clone()
+                        }),
+                )
+                .child(div().text_sm().truncate().child(now_playing.artist.clone()))
+                .when(!now_playing.player.is_empty(), |view| {
+                    view.child(
+                        div()
+                            .text_xs()
+                            .text_color(muted)
+                            .truncate()
+                            .child(now_playing.player.clone()),
+                    )
+                }),
+            None => v_flex().child(div().text_sm().text_color(muted).child(if self.loaded {
+                "Nothing is playing"
+            } else {
+                "Loading…"
+            })),
+        };
+
+        let playing = self.now_playing.as_ref().is_some_and(|x| x.playing);
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .justify_between()
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .window_drag_area()
+                    .child(info),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_center()
+                    .child(self.control_button(
+                        "previous",
+                        IconName::SkipBack,
+                        "Previous",
+                        MediaAction::Previous,
+                        cx,
+                    ))
+                    .child(self.control_button(
+                        "play-pause",
+                        if playing {
+                            IconName::Pause
+                        } else {
+                            IconName::Play
+                        },
+                        if playing { "Pause" } else { "Play" },
+                        MediaAction::PlayPause,
+                        cx,
+                    ))
+                    .child(self.control_button(
+                        "next",
+                        IconName::SkipForward,
+                        "Next",
+                        MediaAction::Next,
+                        cx,
+                    )),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for MediaSticker {
+    fn save_on_close(&mut self, _cx: &mut Context<Self>) -> bool {
+        true
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 110)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(280, 140)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for MediaSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+        self.spawn_for_tick(cx);
+
+        v_flex()
+            .size_full()
+            .bg(Rgba {
+                a: 0.85,
+                ..self.color.bg()
+            })
+            .child(self.player_view(cx))
+            .when_some(self.error.as_ref(), |view, msg| {
+                view.child(Alert::error("media-error", msg.as_str()).small())
+            })
+    }
+}
@@ -0,0 +1,602 @@
+use std::time::Duration;
+
+use gpui::{
+    AnyElement, AppContext, Context, Entity, FocusHandle, KeyDownEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PathBuilder, Pixels, Point, Render, Rgba, Window,
+    canvas, div, point, prelude::*, px,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::windows::StickerWindowEvent;
+use crate::storage::ArcStickerStore;
+
+/// Nodes have a fixed size, so they can be hit tested without asking the layout.
+const NODE_WIDTH: f32 = 120.0;
+const NODE_HEIGHT: f32 = 34.0;
+/// Room between a node and the child added to it with Tab.
+const CHILD_GAP: f32 = 40.0;
+const EDGE_WIDTH: f32 = 1.5;
+
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct MindMapContent {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    /// A window position is `canvas + pan`.
+    pan_x: f32,
+    pan_y: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Node {
+    id: u64,
+    text: String,
+    /// Canvas position of the top left corner.
+    x: f32,
+    y: f32,
+}
+
+impl Node {
+    fn center(&self) -> (f32, f32) {
+        (self.x + NODE_WIDTH / 2.0, self.y + NODE_HEIGHT / 2.0)
+    }
+
+    fn contains(&self, x: f32, y: f32) -> bool {
+        (self.x..=self.x + NODE_WIDTH).contains(&x) && (self.y..=self.y + NODE_HEIGHT).contains(&y)
+    }
+}
+
+/// Undirected, `from` is the node the link was dragged from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Edge {
+    from: u64,
+    to: u64,
+}
+
+impl Edge {
+    fn connects(&self, a: u64, b: u64) -> bool {
+        (self.from == a && self.to == b) || (self.from == b && self.to == a)
+    }
+}
+
+impl MindMapContent {
+    fn to_canvas(&self, position: Point<Pixels>) -> (f32, f32) {
+        (
+            position.x.to_f64() as f32 - self.pan_x,
+            position.y.to_f64() as f32 - self.pan_y,
+        )
+    }
+
+    /// The topmost node at the window position, later nodes are drawn above earlier ones.
+    fn node_at(&self, position: Point<Pixels>) -> Option<u64> {
+        let (x, y) = self.to_canvas(position);
+        self.nodes
+            .iter()
+            .rev()
+            .find(|node| node.contains(x, y))
+            .map(|node| node.id)
+    }
+
+    fn node(&self, id: u64) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    fn node_mut(&mut self, id: u64) -> Option<&mut Node> {
+        self.nodes.iter_mut().find(|node| node.id == id)
+    }
+
+    fn add_node(&mut self, x: f32, y: f32) -> u64 {
+        let id = self.nodes.iter().map(|node| node.id + 1).max().unwrap_or(0);
+        self.nodes.push(Node {
+            id,
+            text: String::new(),
+            x,
+            y,
+        });
+        id
+    }
+
+    fn remove_node(&mut self, id: u64) {
+        self.nodes.retain(|node| node.id != id);
+        self.edges.retain(|edge| edge.from != id && edge.to != id);
+    }
+
+    /// Connect the nodes, or disconnect them when they already are.
+    fn toggle_edge(&mut self, from: u64, to: u64) {
+        if from == to {
+            return;
+        }
+        if let Some(ix) = self.edges.iter().position(|edge| edge.connects(from, to)) {
+            self.edges.remove(ix);
+        } else {
+            self.edges.push(Edge { from, to });
+        }
+    }
+
+    /// Move the node to the top, so it is drawn and hit first.
+    fn raise(&mut self, id: u64) {
+        if let Some(ix) = self.nodes.iter().position(|node| node.id == id) {
+            let node = self.nodes.remove(ix);
+            self.nodes.push(node);
+        }
+    }
+}
+
+enum Drag {
+    Node {
+        id: u64,
+        /// Canvas offset of the mouse from the node corner.
+        offset: (f32, f32),
+        moved: bool,
+    },
+    /// Shift+drag from a node, released on another node it connects them.
+    Link {
+        from: u64,
+        to: Point<Pixels>,
+    },
+    Pan {
+        from: Point<Pixels>,
+    },
+}
+
+pub struct MindMapSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: MindMapContent,
+
+    focus_handle: FocusHandle,
+    selected: Option<u64>,
+    drag: Option<Drag>,
+    /// The node whose text is in the editor.
+    editing: Option<u64>,
+    editor: Entity<InputState>,
+    save_debounce_generation: u64,
+
+    error: Option<String>,
+}
+
+impl MindMapSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<MindMapContent>(content).unwrap_or_default();
+
+        let editor = cx.new(|cx| InputState::new(window, cx).placeholder("Idea"));
+        cx.subscribe_in(
+            &editor,
+            window,
+            |this, _, event: &InputEvent, window, cx| match event {
+                InputEvent::PressEnter { .. } | InputEvent::Blur => this.finish_editing(window, cx),
+                _ => {}
+            },
+        )
+        .detach();
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            focus_handle: cx.focus_handle(),
+            selected: None,
+            drag: None,
+            editing: None,
+            editor,
+            save_debounce_generation: 0,
+            error: None,
+        }
+    }
+
+    /// The text of the first node, which is usually the central idea.
+    fn title(&self) -> String {
+        self.content
+            .nodes
+            .iter()
+            .map(|node| node.text.trim())
+            .find(|text| !text.is_empty())
+            .unwrap_or("Mind map")
+            .to_string()
+    }
+
+    fn save_state(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize mind map sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save mind map sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save mind map sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    /// Arranging sends many small changes, only the last one is saved.
+    fn save_state_debounced(&mut self, cx: &mut Context<Self>) {
+        self.save_debounce_generation = self.save_debounce_generation.wrapping_add(1);
+        let generation = self.save_debounce_generation;
+
+        cx.spawn(async move |entity, cx| {
+            cx.background_executor().timer(SAVE_DEBOUNCE).await;
+            let _ = entity.update(cx, |this, cx| {
+                if this.save_debounce_generation == generation && this.drag.is_none() {
+                    this.save_state(cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn start_editing(&mut self, id: u64, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(node) = self.content.node(id) else {
+            return;
+        };
+        let text = node.text.clone();
+        self.selected = Some(id);
+        self.editing = Some(id);
+        self.editor.update(cx, |editor, cx| {
+            editor.set_value(text, window, cx);
+            editor.focus(window, cx);
+        });
+        cx.notify();
+    }
+
+    /// Take the edited text, a node left without text is removed.
+    fn finish_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(id) = self.editing.take() else {
+            return;
+        };
+        let text = self.editor.read(cx).value().trim().to_string();
+        if text.is_empty() {
+            self.content.remove_node(id);
+            self.selected = None;
+        } else if let Some(node) = self.content.node_mut(id) {
+            node.text = text;
+        }
+
+        window.focus(&self.focus_handle);
+        self.save_state(cx);
+        cx.notify();
+    }
+
+    /// A new node right of `parent`, below its other children, connected to it.
+    fn add_child(&mut self, parent: u64, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(node) = self.content.node(parent) else {
+            return;
+        };
+        let x = node.x + NODE_WIDTH + CHILD_GAP;
+        let y = self
+            .content
+            .edges
+            .iter()
+            .filter(|edge| edge.from == parent)
+            .filter_map(|edge| self.content.node(edge.to))
+            .filter(|child| child.x > node.x)
+            .map(|child| child.y + NODE_HEIGHT + CHILD_GAP / 2.0)
+            .fold(node.y, f32::max);
+
+        let child = self.content.add_node(x, y);
+        self.content.edges.push(Edge {
+            from: parent,
+            to: child,
+        });
+        self.start_editing(child, window, cx);
+    }
+
+    fn on_mouse_down(&mut self, ev: &MouseDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing.is_some() {
+            self.finish_editing(window, cx);
+        }
+        window.focus(&self.focus_handle);
+
+        let hit = self.content.node_at(ev.position);
+        if ev.click_count == 2 {
+            let id = hit.unwrap_or_else(|| {
+                let (x, y) = self.content.to_canvas(ev.position);
+                self.content
+                    .add_node(x - NODE_WIDTH / 2.0, y - NODE_HEIGHT / 2.0)
+            });
+            self.drag = None;
+            self.start_editing(id, window, cx);
+            return;
+        }
+
+        self.selected = hit;
+        self.drag = Some(match hit {
+            Some(id) if ev.modifiers.shift => Drag::Link {
+                from: id,
+                to: ev.position,
+            },
+            Some(id) => {
+                self.content.raise(id);
+                let (x, y) = self.content.to_canvas(ev.position);
+                let node = self.content.node(id).map(|node| (node.x, node.y));
+                let (node_x, node_y) = node.unwrap_or((x, y));
+                Drag::Node {
+                    id,
+                    offset: (x - node_x, y - node_y),
+                    moved: false,
+                }
+            }
+            None => Drag::Pan { from: ev.position },
+        });
+        cx.notify();
+    }
+
+    fn on_mouse_move(&mut self, ev: &MouseMoveEvent, cx: &mut Context<Self>) {
+        let (x, y) = self.content.to_canvas(ev.position);
+        match self.drag.as_mut() {
+            Some(Drag::Node { id, offset, moved }) => {
+                *moved = true;
+                let (id, offset) = (*id, *offset);
+                if let Some(node) = self.content.node_mut(id) {
+                    node.x = x - offset.0;
+                    node.y = y - offset.1;
+                }
+            }
+            Some(Drag::Link { to, .. }) => *to = ev.position,
+            Some(Drag::Pan { from }) => {
+                let dx = (ev.position.x - from.x).to_f64() as f32;
+                let dy = (ev.position.y - from.y).to_f64() as f32;
+                *from = ev.position;
+                self.content.pan_x += dx;
+                self.content.pan_y += dy;
+            }
+            None => return,
+        }
+        cx.notify();
+    }
+
+    fn on_mouse_up(&mut self, ev: &MouseUpEvent, cx: &mut Context<Self>) {
+        match self.drag.take() {
+            Some(Drag::Link { from, .. }) => {
+                if let Some(to) = self.content.node_at(ev.position) {
+                    self.content.toggle_edge(from, to);
+                    self.save_state(cx);
+                }
+            }
+            Some(Drag::Node { moved: true, .. }) | Some(Drag::Pan { .. }) => {
+                self.save_state_debounced(cx);
+            }
+            Some(Drag::Node { moved: false, .. }) | None => {}
+        }
+        cx.notify();
+    }
+
+    fn on_key_down(&mut self, ev: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing.is_some() {
+            if ev.keystroke.key == "escape" {
+                self.finish_editing(window, cx);
+            }
+            return;
+        }
+        let Some(selected) = self.selected else {
+            return;
+        };
+
+        match ev.keystroke.key.as_str() {
+            "delete" | "backspace" => {
+                self.content.remove_node(selected);
+                self.selected = None;
+                self.save_state(cx);
+            }
+            "enter" | "f2" => self.start_editing(selected, window, cx),
+            "tab" => self.add_child(selected, window, cx),
+            _ => return,
+        }
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn edges_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        let pan = (self.content.pan_x, self.content.pan_y);
+        let lines = self
+            .content
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                let from = self.content.node(edge.from)?.center();
+                let to = self.content.node(edge.to)?.center();
+                Some((from, to))
+            })
+            .map(|(from, to)| {
+                (
+                    point(px(from.0 + pan.0), px(from.1 + pan.1)),
+                    point(px(to.0 + pan.0), px(to.1 + pan.1)),
+                )
+            })
+            .chain(match &self.drag {
+                Some(Drag::Link { from, to }) => self.content.node(*from).map(|node| {
+                    let center = node.center();
+                    (point(px(center.0 + pan.0), px(center.1 + pan.1)), *to)
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let color = cx.theme().muted_foreground;
+
+        canvas(
+            move |_, _, _| {},
+            move |_, _, window, _| {
+                for (from, to) in &lines {
+                    let mut builder = PathBuilder::stroke(px(EDGE_WIDTH));
+                    builder.move_to(*from);
+                    builder.line_to(*to);
+                    if let Ok(path) = builder.build() {
+                        window.paint_path(path, color);
+                    }
+                }
+            },
+        )
+        .absolute()
+        .size_full()
+        .into_any_element()
+    }
+
+    fn node_view(&self, node: &Node, cx: &mut Context<Self>) -> AnyElement {
+        let selected = self.selected == Some(node.id);
+        let left = px(node.x + self.content.pan_x);
+        let top = px(node.y + self.content.pan_y);
+
+        if self.editing == Some(node.id) {
+            return div()
+                .absolute()
+                .left(left)
+                .top(top)
+                .w(px(NODE_WIDTH))
+                .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                .child(Input::new(&self.editor).small())
+                .into_any_element();
+        }
+
+        div()
+            .absolute()
+            .left(left)
+            .top(top)
+            .w(px(NODE_WIDTH))
+            .h(px(NODE_HEIGHT))
+            .px_2()
+            .flex()
+            .items_center()
+            .justify_center()
+            .rounded_md()
+            .border_1()
+            .border_color(if selected {
+                cx.theme().primary
+            } else {
+                cx.theme().border
+            })
+            .bg(cx.theme().background.opacity(0.8))
+            .text_sm()
+            .child(div().truncate().child(node.text.clone()))
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for MindMapSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_state(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(200, 150)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(480, 360)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for MindMapSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let nodes = self
+            .content
+            .nodes
+            .iter()
+            .map(|node| self.node_view(node, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .relative()
+            .overflow_hidden()
+            .bg(Rgba {
+                a: 0.85,
+                ..self.color.bg()
+            })
+            .track_focus(&self.focus_handle)
+            .when(matches!(self.drag, Some(Drag::Pan { .. })), |view| {
+                view.cursor_grabbing()
+            })
+            .on_key_down(cx.listener(Self::on_key_down))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+            .on_mouse_move(cx.listener(|this, ev, _, cx| this.on_mouse_move(ev, cx)))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, ev, _, cx| this.on_mouse_up(ev, cx)),
+            )
+            .child(self.edges_view(cx))
+            .children(nodes)
+            .when(self.content.nodes.is_empty(), |view| {
+                view.child(
+                    div()
+                        .size_full()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .p_4()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(
+                            "Double-click to add an idea, Tab adds a connected one, \
+                             Shift+drag between ideas to connect them",
+                        ),
+                )
+            })
+            .when_some(self.error.as_ref(), |view, msg| {
+                view.child(
+                    div()
+                        .absolute()
+                        .bottom_0()
+                        .left_0()
+                        .right_0()
+                        .child(Alert::error("mindmap-error", msg.as_str()).small()),
+                )
+            })
+    }
+}
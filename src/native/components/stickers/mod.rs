@@ -1,11 +1,57 @@
-use gpui::{AnyElement, App, Context, Entity, IntoElement, Render, Size};
+use std::sync::mpsc;
 
-use crate::model::sticker::StickerColor;
+use gpui::{AnyElement, App, Context, Entity, IntoElement, Render, Size, Window};
 
+use crate::model::sticker::{StickerColor, StickerSettings};
+use crate::native::windows::StickerWindowEvent;
+use crate::storage::ArcStickerStore;
+
+pub mod agenda;
+pub mod animation;
+pub mod calculator;
+pub mod chart;
+pub mod clock;
+pub mod code;
 pub mod command;
+pub mod convert;
+pub mod counter;
+pub mod cron;
+pub mod currency;
+pub mod git;
+pub mod github;
+pub mod habit;
+pub mod http;
+pub mod image;
+pub mod kanban;
+pub mod launcher;
 pub mod markdown;
+pub mod media;
+pub mod mindmap;
 pub mod paint;
+pub mod palette;
+pub mod pomodoro;
+pub mod reminder;
+pub mod result_renderers;
+pub mod system;
+pub mod time_tracker;
 pub mod timer;
+pub mod units;
+pub mod uptime;
+pub mod web;
+
+/// Save the content of a sticker and let the main window refresh its card, `summary` is
+/// the new card preview (empty for types without one).
+pub async fn save_content(
+    store: &ArcStickerStore,
+    sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+    id: i64,
+    content: String,
+    summary: String,
+) -> anyhow::Result<()> {
+    store.update_sticker_content(id, content).await?;
+    let _ = sticker_events_tx.send(StickerWindowEvent::ContentChanged { id, summary });
+    Ok(())
+}
 
 pub trait Sticker: Sized {
     // If return false, it means we should not close the sticker window.
@@ -19,6 +65,50 @@ pub trait Sticker: Sized {
     fn disable_color_picker(&self) -> bool {
         false
     }
+
+    // If return true, the sticker window does not show its header and footer on hover.
+    fn hide_window_chrome(&self) -> bool {
+        false
+    }
+
+    // If return true, the sticker window offers the wrap and monospace toggles.
+    fn has_text_output(&self) -> bool {
+        false
+    }
+
+    fn apply_settings(&mut self, _settings: &StickerSettings) {}
+
+    // If return true, the sticker window offers the print action.
+    fn can_print(&self) -> bool {
+        false
+    }
+
+    // HTML body of a printable page with the content.
+    fn print_html(&self, _cx: &App) -> Option<String> {
+        None
+    }
+
+    // Human readable state of a running schedule or countdown, for the status command.
+    fn scheduler_state(&self) -> Option<String> {
+        None
+    }
+
+    // Show content which was saved outside of the window, e.g. by the task sync. If return
+    // false, the content was not taken, e.g. because it is being edited.
+    fn set_content(
+        &mut self,
+        _content: &str,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> bool {
+        false
+    }
+
+    // Content in the stored format including unsaved edits, for the conversions to other
+    // sticker types, see [`convert::CONVERSIONS`].
+    fn content(&self, _cx: &App) -> Option<String> {
+        None
+    }
 }
 
 pub trait StickerView {
@@ -26,6 +116,14 @@ pub trait StickerView {
     fn save_on_close(&self, cx: &mut App) -> bool;
     fn set_color(&mut self, cx: &mut App, color: StickerColor);
     fn disable_color_picker(&self, cx: &App) -> bool;
+    fn hide_window_chrome(&self, cx: &App) -> bool;
+    fn has_text_output(&self, cx: &App) -> bool;
+    fn apply_settings(&mut self, cx: &mut App, settings: &StickerSettings);
+    fn can_print(&self, cx: &App) -> bool;
+    fn print_html(&self, cx: &App) -> Option<String>;
+    fn scheduler_state(&self, cx: &App) -> Option<String>;
+    fn set_content(&self, content: &str, window: &mut Window, cx: &mut App) -> bool;
+    fn content(&self, cx: &App) -> Option<String>;
 }
 
 pub struct StickerViewEntity<T: Render + Sticker + 'static> {
@@ -60,4 +158,43 @@ impl<T: Render + Sticker + 'static> StickerView for StickerViewEntity<T> {
     fn disable_color_picker(&self, cx: &App) -> bool {
         self.entity.read(cx).disable_color_picker()
     }
+
+    fn hide_window_chrome(&self, cx: &App) -> bool {
+        self.entity.read(cx).hide_window_chrome()
+    }
+
+    fn has_text_output(&self, cx: &App) -> bool {
+        self.entity.read(cx).has_text_output()
+    }
+
+    fn apply_settings(&mut self, cx: &mut App, settings: &StickerSettings) {
+        let _ = self.entity.update(cx, |this, cx| {
+            this.apply_settings(settings);
+            cx.notify();
+        });
+    }
+
+    fn can_print(&self, cx: &App) -> bool {
+        self.entity.read(cx).can_print()
+    }
+
+    fn print_html(&self, cx: &App) -> Option<String> {
+        self.entity.read(cx).print_html(cx)
+    }
+
+    fn scheduler_state(&self, cx: &App) -> Option<String> {
+        self.entity.read(cx).scheduler_state()
+    }
+
+    fn set_content(&self, content: &str, window: &mut Window, cx: &mut App) -> bool {
+        self.entity.update(cx, |this, cx| {
+            let taken = this.set_content(content, window, cx);
+            cx.notify();
+            taken
+        })
+    }
+
+    fn content(&self, cx: &App) -> Option<String> {
+        self.entity.read(cx).content(cx)
+    }
 }
@@ -1,20 +1,28 @@
 use gpui::{
-    AnyElement, Context, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PathBuilder,
-    PathStyle, Pixels, Point, Render, Rgba, StrokeOptions, Window, canvas, div, point, prelude::*,
-    px, rgb, rgba, size, transparent_black,
+    AnyElement, AppContext, Context, Entity, FocusHandle, KeyDownEvent, KeyUpEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PathBuilder, PathStyle, Pixels, Point, Render,
+    Rgba, ScrollWheelEvent, StrokeOptions, Window, canvas, div, point, prelude::*, px, rgb, rgba,
+    size, transparent_black,
+};
+use gpui_component::{
+    Sizable,
+    button::Button,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex, white,
 };
-use gpui_component::{Sizable, button::Button, h_flex, v_flex, white};
 use serde::{Deserialize, Serialize};
 use std::{
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
     time::{Duration, Instant},
 };
 
-use crate::native::{components::IconName, windows::StickerWindowEvent};
+use super::save_content;
 use crate::model::sticker::StickerColor;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
 use crate::storage::ArcStickerStore;
 
-const PAINT_COLORS: [u32; 8] = [
+pub(crate) const PAINT_COLORS: [u32; 8] = [
     0x000000ff, // black
     0xffffffff, // white
     0xeb5757ff, // red
@@ -25,16 +33,26 @@ const PAINT_COLORS: [u32; 8] = [
     0x9b51e0ff, // purple
 ];
 
-const PAINT_STROKE_WIDTHS: [f32; 5] = [1.0, 2.0, 3.0, 4.0, 6.0];
+pub(crate) const PAINT_STROKE_WIDTHS: [f32; 5] = [1.0, 2.0, 3.0, 4.0, 6.0];
 
 const PAINT_SAVE_DEBOUNCE: Duration = Duration::from_millis(3000);
 
 const PAINT_NOTIFY_MIN_INTERVAL: Duration = Duration::from_millis(50);
 
+const PAINT_MIN_ZOOM: f32 = 0.1;
+const PAINT_MAX_ZOOM: f32 = 8.0;
+/// Zoom change per scrolled pixel with Ctrl held.
+const PAINT_ZOOM_SPEED: f32 = 0.005;
+
+/// Size of the grid cells the strokes are rasterized into for filling, in canvas pixels.
+const PAINT_FILL_CELL: f32 = 2.0;
+/// Bigger regions are filled on a coarser grid, so a fill stays quick.
+const PAINT_FILL_MAX_CELLS: usize = 2_000_000;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct PaintPoint {
-    x: f32,
-    y: f32,
+pub(crate) struct PaintPoint {
+    pub(crate) x: f32,
+    pub(crate) y: f32,
 }
 
 impl From<Point<Pixels>> for PaintPoint {
@@ -52,27 +70,91 @@ impl PaintPoint {
     }
 }
 
+/// How the canvas is shown in the window: a window position is `canvas * zoom + pan`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PaintView {
+    pub(crate) zoom: f32,
+    pub(crate) pan_x: f32,
+    pub(crate) pan_y: f32,
+}
+
+impl Default for PaintView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
+        }
+    }
+}
+
+impl PaintView {
+    fn to_canvas(&self, position: Point<Pixels>) -> PaintPoint {
+        let p = PaintPoint::from(position);
+        PaintPoint {
+            x: (p.x - self.pan_x) / self.zoom,
+            y: (p.y - self.pan_y) / self.zoom,
+        }
+    }
+
+    fn to_window(&self, p: Point<Pixels>) -> Point<Pixels> {
+        let x = p.x.to_f64() as f32 * self.zoom + self.pan_x;
+        let y = p.y.to_f64() as f32 * self.zoom + self.pan_y;
+        point(px(x), px(y))
+    }
+
+    /// Zoom by `factor` keeping the canvas point under `anchor` in place.
+    fn zoom_at(&mut self, anchor: Point<Pixels>, factor: f32) {
+        let target = self.to_canvas(anchor);
+        let anchor = PaintPoint::from(anchor);
+        self.zoom = (self.zoom * factor).clamp(PAINT_MIN_ZOOM, PAINT_MAX_ZOOM);
+        self.pan_x = anchor.x - target.x * self.zoom;
+        self.pan_y = anchor.y - target.y * self.zoom;
+    }
+}
+
 fn default_stroke_width() -> f32 {
     2.0
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct PaintStroke {
-    points: Vec<PaintPoint>,
-    color: u32,
+pub(crate) struct PaintStroke {
+    pub(crate) points: Vec<PaintPoint>,
+    pub(crate) color: u32,
 
     #[serde(default = "default_stroke_width")]
-    width: f32,
+    pub(crate) width: f32,
+}
+
+/// A closed region filled by the bucket tool, as runs of grid cells in canvas coordinates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PaintFill {
+    pub(crate) color: u32,
+    /// Canvas position of the top left corner of cell (0, 0).
+    pub(crate) origin: PaintPoint,
+    pub(crate) cell: f32,
+    /// `[row, first column, last column]` of filled cells.
+    pub(crate) runs: Vec<[i32; 3]>,
+}
+
+impl PaintFill {
+    fn contains(&self, p: &PaintPoint) -> bool {
+        let row = ((p.y - self.origin.y) / self.cell).floor() as i32;
+        let column = ((p.x - self.origin.x) / self.cell).floor() as i32;
+        self.runs
+            .iter()
+            .any(|[r, first, last]| *r == row && (*first..=*last).contains(&column))
+    }
 }
 
 #[derive(Debug, Clone)]
-struct PaintStrokeState {
-    stroke: PaintStroke,
+pub(crate) struct PaintStrokeState {
+    pub(crate) stroke: PaintStroke,
     deduped_points: Vec<Point<Pixels>>,
 }
 
 impl PaintStrokeState {
-    fn new(stroke: PaintStroke) -> Self {
+    pub(crate) fn new(stroke: PaintStroke) -> Self {
         let mut this = Self {
             stroke,
             deduped_points: Vec::new(),
@@ -81,7 +163,7 @@ impl PaintStrokeState {
         this
     }
 
-    fn rebuild_cache(&mut self) {
+    pub(crate) fn rebuild_cache(&mut self) {
         let min_distance = min_point_distance_for_width(self.stroke.width);
         dedupe_close_points_into(&self.stroke.points, min_distance, &mut self.deduped_points);
     }
@@ -100,6 +182,21 @@ struct PaintContent {
 
     #[serde(default = "default_stroke_width")]
     current_width: f32,
+
+    /// User provided title, when empty the title is generated automatically.
+    #[serde(default)]
+    title: Option<String>,
+
+    #[serde(default)]
+    view: PaintView,
+
+    /// Painted below the strokes, in the order they were made.
+    #[serde(default)]
+    fills: Vec<PaintFill>,
+
+    /// Canvas color set by filling outside of closed regions, the sticker color when missing.
+    #[serde(default)]
+    background: Option<u32>,
 }
 
 impl Default for PaintContent {
@@ -108,10 +205,22 @@ impl Default for PaintContent {
             strokes: Vec::new(),
             current_color: PAINT_COLORS[0],
             current_width: default_stroke_width(),
+            title: None,
+            view: PaintView::default(),
+            fills: Vec::new(),
+            background: None,
         }
     }
 }
 
+/// Content of a new paint sticker with the strokes, e.g. a saved screen annotation.
+pub(crate) fn content_json(strokes: Vec<PaintStroke>) -> serde_json::Result<String> {
+    serde_json::to_string(&PaintContent {
+        strokes,
+        ..Default::default()
+    })
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 enum PaintContentAny {
@@ -123,12 +232,23 @@ pub struct PaintSticker {
     id: i64,
     color: StickerColor,
     store: ArcStickerStore,
-    _sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    created_at: i64,
+
+    title: Entity<InputState>,
 
     strokes: Arc<RwLock<Vec<PaintStrokeState>>>,
+    fills: Arc<Vec<PaintFill>>,
+    background: Option<u32>,
     current_color: u32,
     current_width: f32,
     painting: bool,
+    view: PaintView,
+    focus_handle: FocusHandle,
+    /// Space is held, dragging pans the canvas instead of drawing.
+    space_held: bool,
+    /// Window position the pan drag was at last.
+    panning_from: Option<Point<Pixels>>,
 
     last_notify_at: Option<Instant>,
 
@@ -143,6 +263,7 @@ pub struct PaintSticker {
 enum PaintTool {
     Pen,
     Eraser,
+    Fill,
 }
 
 impl Default for PaintTool {
@@ -157,6 +278,9 @@ impl PaintSticker {
         color: StickerColor,
         store: ArcStickerStore,
         content: &str,
+        created_at: i64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
         sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
     ) -> Self {
         let content = serde_json::from_str::<PaintContentAny>(content)
@@ -174,14 +298,35 @@ impl PaintSticker {
                         .collect(),
                     current_color: PAINT_COLORS[0],
                     current_width: default_stroke_width(),
+                    title: None,
+                    view: PaintView::default(),
+                    fills: Vec::new(),
+                    background: None,
                 },
             })
             .unwrap_or_default();
+
+        let title = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.title.clone().unwrap_or_default())
+                .placeholder("Auto title")
+        });
+
+        cx.subscribe(&title, |this, _, event: &InputEvent, cx| match event {
+            InputEvent::PressEnter { .. } | InputEvent::Blur => {
+                this.save_state(cx);
+            }
+            _ => {}
+        })
+        .detach();
+
         Self {
             id,
             color,
             store,
-            _sticker_events_tx: sticker_events_tx,
+            sticker_events_tx,
+            created_at,
+            title,
             strokes: Arc::new(RwLock::new(
                 content
                     .strokes
@@ -189,9 +334,15 @@ impl PaintSticker {
                     .map(PaintStrokeState::new)
                     .collect(),
             )),
+            fills: Arc::new(content.fills),
+            background: content.background,
             current_color: content.current_color,
             current_width: content.current_width,
             painting: false,
+            view: content.view,
+            focus_handle: cx.focus_handle(),
+            space_held: false,
+            panning_from: None,
             last_notify_at: None,
             tool: PaintTool::default(),
             save_debounce_generation: 0,
@@ -254,6 +405,28 @@ impl PaintSticker {
         }
     }
 
+    fn custom_title(&self, cx: &Context<Self>) -> Option<String> {
+        let title = self.title.read(cx).value().trim().to_string();
+        (!title.is_empty()).then_some(title)
+    }
+
+    fn auto_title(&self) -> String {
+        let created = crate::utils::time::format_unix_millis(self.created_at);
+        let mut title = if created.is_empty() {
+            "Paint".to_string()
+        } else {
+            format!("Paint {created}")
+        };
+
+        match self.strokes_read().len() {
+            0 => {}
+            1 => title.push_str(" · 1 stroke"),
+            count => title.push_str(&format!(" · {count} strokes")),
+        }
+
+        title
+    }
+
     fn save_state(&mut self, cx: &mut Context<Self>) -> bool {
         // Avoid cloning the entire strokes vector (and all point data) just to serialize.
         #[derive(Serialize)]
@@ -261,8 +434,15 @@ impl PaintSticker {
             strokes: Vec<&'a PaintStroke>,
             current_color: u32,
             current_width: f32,
+            title: Option<String>,
+            view: PaintView,
+            fills: &'a [PaintFill],
+            background: Option<u32>,
         }
 
+        let custom_title = self.custom_title(cx);
+        let title = custom_title.clone().unwrap_or_else(|| self.auto_title());
+
         // Keep the read lock in a tight scope so we can update `self.error` on failure.
         let json = {
             let strokes_guard = self.strokes_read();
@@ -270,6 +450,10 @@ impl PaintSticker {
                 strokes: strokes_guard.iter().map(|s| &s.stroke).collect(),
                 current_color: self.current_color,
                 current_width: self.current_width,
+                title: custom_title,
+                view: self.view,
+                fills: &self.fills,
+                background: self.background,
             };
             serde_json::to_string(&borrowed)
         };
@@ -283,10 +467,30 @@ impl PaintSticker {
         };
 
         let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
         let id = self.id;
 
         cx.spawn(async move |entity, cx| {
-            if let Err(err) = store.update_sticker_content(id, json).await {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save paint sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(
+                    id,
+                    error = %err,
+                    "Failed to send title changed event for paint sticker"
+                );
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
                 let _ = entity.update(cx, |this, cx| {
                     this.error = Some(format!("Failed to save paint sticker: {err:#}"));
                     cx.notify();
@@ -305,12 +509,13 @@ impl PaintSticker {
     }
 
     fn eraser_radius(&self) -> f32 {
-        // Reasonable default that still feels usable when stroke width is small.
-        (self.current_width * 3.0).max(8.0)
+        // Reasonable default that still feels usable when stroke width is small,
+        // the same size on screen at any zoom.
+        (self.current_width * 3.0).max(8.0) / self.view.zoom
     }
 
     fn erase_at(&mut self, position: Point<Pixels>) {
-        let target = PaintPoint::from(position);
+        let target = self.view.to_canvas(position);
         let radius = self.eraser_radius();
         let radius_sq = radius * radius;
 
@@ -360,6 +565,26 @@ impl PaintSticker {
         *strokes = new_strokes;
     }
 
+    /// Fill the closed region around the window position, or the background when it is open.
+    fn fill_at(&mut self, position: Point<Pixels>) {
+        let seed = self.view.to_canvas(position);
+        let region = flood_fill(&self.strokes_read(), &seed);
+        let color = self.current_color;
+
+        match region {
+            Some(mut fill) => {
+                fill.color = color;
+                // Filling a filled region again recolors it instead of stacking fills.
+                let fills = Arc::make_mut(&mut self.fills);
+                fills.retain(|x| !x.contains(&seed));
+                fills.push(fill);
+            }
+            // Filling the background with its own color again brings back the sticker color.
+            None if self.background == Some(color) => self.background = None,
+            None => self.background = Some(color),
+        }
+    }
+
     fn toolbar_view(&self, cx: &mut Context<Self>) -> AnyElement {
         let current_color = self.current_color;
         let current_width = self.current_width;
@@ -367,7 +592,7 @@ impl PaintSticker {
         let eraser = Button::new("eraser")
             .icon(match self.tool {
                 PaintTool::Eraser => IconName::Eraser,
-                PaintTool::Pen => IconName::Paint,
+                PaintTool::Pen | PaintTool::Fill => IconName::Paint,
             })
             .small()
             .border_0()
@@ -382,6 +607,25 @@ impl PaintSticker {
                 cx.notify();
             }));
 
+        let bucket = Button::new("fill")
+            .icon(IconName::PaintBucket)
+            .small()
+            .border_0()
+            .bg(transparent_black())
+            .tooltip("Fill a closed region or the background")
+            .map(|v| match self.tool {
+                PaintTool::Fill => v.text_color(rgba(current_color)),
+                _ => v.opacity(0.5),
+            })
+            .on_click(cx.listener(|this, _, _, cx| {
+                this.tool = if this.tool == PaintTool::Fill {
+                    PaintTool::Pen
+                } else {
+                    PaintTool::Fill
+                };
+                cx.notify();
+            }));
+
         let mut color_picker = h_flex().gap_1().py_1().items_center();
         for &c in PAINT_COLORS.iter() {
             let is_selected = c == current_color;
@@ -441,63 +685,96 @@ impl PaintSticker {
                     .gap_1()
                     .flex_wrap()
                     .child(eraser)
+                    .child(bucket)
                     .child(div().child("|").opacity(0.2))
                     .child(stroke_picker)
                     .child(div().child("|").opacity(0.2))
-                    .child(color_picker),
+                    .child(color_picker)
+                    .child(div().child("|").opacity(0.2))
+                    .child(
+                        Button::new("reset_view")
+                            .label(format!("{:.0}%", self.view.zoom * 100.0))
+                            .xsmall()
+                            .border_0()
+                            .bg(transparent_black())
+                            .tooltip("Ctrl+scroll to zoom, space+drag to pan, click to reset")
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.view = PaintView::default();
+                                cx.notify();
+                                this.save_state_debounced(cx);
+                            })),
+                    )
+                    .child(div().child("|").opacity(0.2))
+                    .child(
+                        div()
+                            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                            .child(Input::new(&self.title).small().w(px(120.0))),
+                    ),
             )
             .into_any_element()
     }
 
     fn canvas_view(&self, cx: &mut Context<Self>) -> AnyElement {
         let strokes = self.strokes.clone();
+        let fills = self.fills.clone();
+        let background = self.background;
+        let view = self.view;
 
         div()
             .size_full()
+            .when(self.space_held, |v| match self.panning_from {
+                Some(_) => v.cursor_grabbing(),
+                None => v.cursor_grab(),
+            })
             .child(
                 canvas(
                     move |_, _, _| {},
-                    move |_, _, window, _| {
+                    move |bounds, _, window, _| {
                         println!("PaintSticker: canvas redraw");
+                        if let Some(color) = background {
+                            window.paint_quad(gpui::fill(bounds, rgba(color)));
+                        }
+                        paint_fills(window, &fills, &view);
                         let strokes = match strokes.read() {
                             Ok(guard) => guard,
                             Err(err) => err.into_inner(),
                         };
-
-                        for stroke in strokes.iter() {
-                            if stroke.deduped_points.len() < 2 {
-                                continue;
-                            }
-
-                            let points = &stroke.deduped_points;
-
-                            // Use round caps/joins and a tighter tolerance to reduce jagged edges.
-                            // Also paint a subtle wider pass first to visually anti-alias pixel edges.
-                            let base_color = rgba(stroke.stroke.color);
-                            let feather_color = Rgba {
-                                a: (base_color.a * 0.25).min(1.0),
-                                ..base_color
-                            };
-
-                            // Feather pass (slightly wider) + main pass.
-                            paint_spline(window, points, stroke.stroke.width + 1.25, feather_color);
-                            paint_spline(window, points, stroke.stroke.width, base_color);
-                        }
+                        paint_strokes(window, &strokes, &view);
                     },
                 )
                 .size_full(),
             )
+            .on_scroll_wheel(cx.listener(|this, ev: &ScrollWheelEvent, _, cx| {
+                let delta = ev.delta.pixel_delta(px(20.0));
+                let (dx, dy) = (delta.x.to_f64() as f32, delta.y.to_f64() as f32);
+                if ev.modifiers.control || ev.modifiers.platform {
+                    this.view
+                        .zoom_at(ev.position, (dy * PAINT_ZOOM_SPEED).exp());
+                } else {
+                    this.view.pan_x += dx;
+                    this.view.pan_y += dy;
+                }
+                cx.notify();
+                this.save_state_debounced(cx);
+            }))
             .on_mouse_down(
                 MouseButton::Left,
-                cx.listener(|this, ev: &MouseDownEvent, _, cx| {
+                cx.listener(|this, ev: &MouseDownEvent, window, cx| {
+                    window.focus(&this.focus_handle);
                     // Starting a new stroke should cancel any pending debounced save.
                     this.cancel_debounced_save();
+
+                    if this.space_held {
+                        this.panning_from = Some(ev.position);
+                        cx.notify();
+                        return;
+                    }
                     this.painting = true;
 
                     match this.tool {
                         PaintTool::Pen => {
                             let mut points = Vec::with_capacity(64);
-                            points.push(PaintPoint::from(ev.position));
+                            points.push(this.view.to_canvas(ev.position));
                             let stroke = PaintStroke {
                                 points,
                                 color: this.current_color,
@@ -510,20 +787,35 @@ impl PaintSticker {
                             this.erase_at(ev.position);
                             cx.notify();
                         }
+                        PaintTool::Fill => {
+                            this.painting = false;
+                            this.fill_at(ev.position);
+                            cx.notify();
+                        }
                     }
                 }),
             )
             .on_mouse_move(cx.listener(|this, ev: &MouseMoveEvent, _, cx| {
+                if let Some(from) = this.panning_from {
+                    let (from, to) = (PaintPoint::from(from), PaintPoint::from(ev.position));
+                    this.view.pan_x += to.x - from.x;
+                    this.view.pan_y += to.y - from.y;
+                    this.panning_from = Some(ev.position);
+                    this.throttled_notify(cx);
+                    return;
+                }
+
                 if !this.painting {
                     return;
                 }
 
+                let view = this.view;
                 match this.tool {
                     PaintTool::Pen => {
                         let mut strokes = this.strokes_write();
 
                         if let Some(stroke) = strokes.last_mut() {
-                            let p = PaintPoint::from(ev.position);
+                            let p = view.to_canvas(ev.position);
 
                             if let Some(last) = stroke.stroke.points.last() {
                                 let min_distance =
@@ -543,6 +835,7 @@ impl PaintSticker {
                     PaintTool::Eraser => {
                         this.erase_at(ev.position);
                     }
+                    PaintTool::Fill => return,
                 }
 
                 this.throttled_notify(cx);
@@ -551,6 +844,7 @@ impl PaintSticker {
                 MouseButton::Left,
                 cx.listener(|this, _: &MouseUpEvent, _, cx| {
                     this.painting = false;
+                    this.panning_from = None;
                     this.last_notify_at = None;
                     cx.notify();
                     this.save_state_debounced(cx);
@@ -576,6 +870,19 @@ impl super::Sticker for PaintSticker {
     fn set_color(&mut self, color: StickerColor) {
         self.color = color;
     }
+
+    fn can_print(&self) -> bool {
+        true
+    }
+
+    fn print_html(&self, _cx: &gpui::App) -> Option<String> {
+        let background = self.background.map(rgba).unwrap_or(self.color.bg());
+        Some(strokes_to_svg(
+            &self.strokes_read(),
+            &self.fills,
+            background,
+        ))
+    }
 }
 
 impl Render for PaintSticker {
@@ -588,6 +895,20 @@ impl Render for PaintSticker {
                 ..self.color.bg()
             })
             .relative()
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, ev: &KeyDownEvent, window, cx| {
+                if ev.keystroke.key == "space" && this.focus_handle.is_focused(window) {
+                    this.space_held = true;
+                    cx.notify();
+                }
+            }))
+            .on_key_up(cx.listener(|this, ev: &KeyUpEvent, _, cx| {
+                if ev.keystroke.key == "space" {
+                    this.space_held = false;
+                    this.panning_from = None;
+                    cx.notify();
+                }
+            }))
             .child(self.canvas_view(cx))
             .when(window.is_window_hovered(), |v| {
                 v.child(self.toolbar_view(cx))
@@ -595,7 +916,238 @@ impl Render for PaintSticker {
     }
 }
 
-fn make_dot(w: f32, color: u32, is_selected: bool) -> AnyElement {
+/// Vector copy of the drawing for printing, on the sticker background so that
+/// white strokes stay visible.
+fn strokes_to_svg(strokes: &[PaintStrokeState], fills: &[PaintFill], background: Rgba) -> String {
+    // Strokes can be anywhere on the canvas, also left of or above the origin.
+    let (mut left, mut top) = (0.0f32, 0.0f32);
+    let (mut right, mut bottom) = (100.0f32, 100.0f32);
+    for point in strokes.iter().flat_map(|s| s.stroke.points.iter()) {
+        left = left.min(point.x - 10.0);
+        top = top.min(point.y - 10.0);
+        right = right.max(point.x + 10.0);
+        bottom = bottom.max(point.y + 10.0);
+    }
+    let (width, height) = (right - left, bottom - top);
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{left} {top} {width} {height}" width="{width}" height="{height}"><rect x="{left}" y="{top}" width="100%" height="100%" fill="rgb({},{},{})"/>"#,
+        (background.r * 255.0) as u8,
+        (background.g * 255.0) as u8,
+        (background.b * 255.0) as u8,
+    );
+    for fill in fills {
+        for [row, first, last] in &fill.runs {
+            svg.push_str(&format!(
+                r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="#{:06x}" fill-opacity="{:.2}"/>"##,
+                fill.origin.x + *first as f32 * fill.cell,
+                fill.origin.y + *row as f32 * fill.cell,
+                (last - first + 1) as f32 * fill.cell,
+                fill.cell,
+                fill.color >> 8,
+                (fill.color & 0xff) as f32 / 255.0,
+            ));
+        }
+    }
+    for stroke in strokes {
+        let points = stroke
+            .stroke
+            .points
+            .iter()
+            .map(|p| format!("{:.1},{:.1}", p.x, p.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let color = stroke.stroke.color;
+        svg.push_str(&format!(
+            r##"<polyline points="{points}" fill="none" stroke="#{:06x}" stroke-opacity="{:.2}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"/>"##,
+            color >> 8,
+            (color & 0xff) as f32 / 255.0,
+            stroke.stroke.width,
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+pub(crate) fn paint_strokes(window: &mut Window, strokes: &[PaintStrokeState], view: &PaintView) {
+    let identity = *view == PaintView::default();
+    let mut transformed = Vec::new();
+    for stroke in strokes {
+        if stroke.deduped_points.len() < 2 {
+            continue;
+        }
+
+        let points = if identity {
+            &stroke.deduped_points
+        } else {
+            transformed.clear();
+            transformed.extend(stroke.deduped_points.iter().map(|p| view.to_window(*p)));
+            &transformed
+        };
+        let width = stroke.stroke.width * view.zoom;
+
+        // Use round caps/joins and a tighter tolerance to reduce jagged edges.
+        // Also paint a subtle wider pass first to visually anti-alias pixel edges.
+        let base_color = rgba(stroke.stroke.color);
+        let feather_color = Rgba {
+            a: (base_color.a * 0.25).min(1.0),
+            ..base_color
+        };
+
+        // Feather pass (slightly wider) + main pass.
+        paint_spline(window, points, width + 1.25, feather_color);
+        paint_spline(window, points, width, base_color);
+    }
+}
+
+fn paint_fills(window: &mut Window, fills: &[PaintFill], view: &PaintView) {
+    for fill in fills {
+        let color = rgba(fill.color);
+        let height = fill.cell * view.zoom;
+        for [row, first, last] in &fill.runs {
+            let corner = point(
+                px(fill.origin.x + *first as f32 * fill.cell),
+                px(fill.origin.y + *row as f32 * fill.cell),
+            );
+            let width = (last - first + 1) as f32 * fill.cell * view.zoom;
+            // A hair taller, so rows do not leave seams on fractional pixels.
+            let bounds =
+                gpui::Bounds::new(view.to_window(corner), size(px(width), px(height + 0.5)));
+            window.paint_quad(gpui::fill(bounds, color));
+        }
+    }
+}
+
+/// The closed region around `seed` on a grid of the strokes, `None` when it reaches
+/// the edge of the drawing, i.e. the seed is in the open background.
+fn flood_fill(strokes: &[PaintStrokeState], seed: &PaintPoint) -> Option<PaintFill> {
+    let points = || strokes.iter().flat_map(|s| s.stroke.points.iter());
+    let (mut left, mut top) = (f32::MAX, f32::MAX);
+    let (mut right, mut bottom) = (f32::MIN, f32::MIN);
+    for p in points() {
+        left = left.min(p.x);
+        top = top.min(p.y);
+        right = right.max(p.x);
+        bottom = bottom.max(p.y);
+    }
+    if left > right || seed.x <= left || seed.x >= right || seed.y <= top || seed.y >= bottom {
+        return None;
+    }
+
+    let mut cell = PAINT_FILL_CELL;
+    let margin = PAINT_STROKE_WIDTHS[PAINT_STROKE_WIDTHS.len() - 1];
+    let origin = PaintPoint {
+        x: left - margin,
+        y: top - margin,
+    };
+    let (columns, rows) = loop {
+        let columns = ((right + margin - origin.x) / cell).ceil() as usize + 1;
+        let rows = ((bottom + margin - origin.y) / cell).ceil() as usize + 1;
+        if columns * rows <= PAINT_FILL_MAX_CELLS {
+            break (columns, rows);
+        }
+        cell *= 2.0;
+    };
+
+    // Cells under a stroke are walls.
+    let mut wall = vec![false; columns * rows];
+    for stroke in strokes {
+        let radius = stroke.stroke.width / 2.0 + cell / 2.0;
+        let reach = (radius / cell).ceil() as i32;
+        let mut mark = |x: f32, y: f32| {
+            let cx = ((x - origin.x) / cell).floor() as i32;
+            let cy = ((y - origin.y) / cell).floor() as i32;
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    let (column, row) = (cx + dx, cy + dy);
+                    if column < 0 || row < 0 || column as usize >= columns || row as usize >= rows {
+                        continue;
+                    }
+                    let center_x = origin.x + (column as f32 + 0.5) * cell;
+                    let center_y = origin.y + (row as f32 + 0.5) * cell;
+                    if (center_x - x).powi(2) + (center_y - y).powi(2) <= radius * radius {
+                        wall[row as usize * columns + column as usize] = true;
+                    }
+                }
+            }
+        };
+
+        for pair in stroke.stroke.points.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            let length = ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt();
+            let steps = (length / (cell / 2.0)).ceil().max(1.0) as usize;
+            for step in 0..=steps {
+                let t = step as f32 / steps as f32;
+                mark(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t);
+            }
+        }
+    }
+
+    let start_column = ((seed.x - origin.x) / cell).floor() as usize;
+    let start_row = ((seed.y - origin.y) / cell).floor() as usize;
+    let start = start_row * columns + start_column;
+    if wall[start] {
+        return None;
+    }
+
+    let mut filled = vec![false; columns * rows];
+    let mut stack = vec![start];
+    filled[start] = true;
+    while let Some(index) = stack.pop() {
+        let (row, column) = (index / columns, index % columns);
+        if row == 0 || column == 0 || row == rows - 1 || column == columns - 1 {
+            return None;
+        }
+        for next in [index - 1, index + 1, index - columns, index + columns] {
+            if !wall[next] && !filled[next] {
+                filled[next] = true;
+                stack.push(next);
+            }
+        }
+    }
+
+    // Grow into the walls by a cell, so no gap shows between the fill and its strokes.
+    let grown: Vec<bool> = (0..columns * rows)
+        .map(|index| {
+            filled[index]
+                || (wall[index]
+                    && [
+                        index.checked_sub(1),
+                        Some(index + 1),
+                        index.checked_sub(columns),
+                        Some(index + columns),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .any(|next| filled.get(next).copied().unwrap_or(false)))
+        })
+        .collect();
+
+    let mut runs = Vec::new();
+    for row in 0..rows {
+        let mut column = 0;
+        while column < columns {
+            if !grown[row * columns + column] {
+                column += 1;
+                continue;
+            }
+            let first = column;
+            while column < columns && grown[row * columns + column] {
+                column += 1;
+            }
+            runs.push([row as i32, first as i32, column as i32 - 1]);
+        }
+    }
+
+    Some(PaintFill {
+        color: 0,
+        origin,
+        cell,
+        runs,
+    })
+}
+
+pub(crate) fn make_dot(w: f32, color: u32, is_selected: bool) -> AnyElement {
     div()
         .w(px(14.0))
         .h(px(14.0))
@@ -622,7 +1174,7 @@ fn midpoint(a: Point<Pixels>, b: Point<Pixels>) -> Point<Pixels> {
     point(px((ax + bx) * 0.5), px((ay + by) * 0.5))
 }
 
-fn min_point_distance_for_width(width: f32) -> f32 {
+pub(crate) fn min_point_distance_for_width(width: f32) -> f32 {
     // Skip ultra-close points to reduce jitter and make curves smoother.
     // Tuned to keep thin strokes responsive while stabilizing wider ones.
     (width * 0.25).max(0.75)
@@ -0,0 +1,409 @@
+use std::time::Duration;
+
+use gpui::{
+    AnyElement, AppContext, ClipboardItem, Context, Entity, MouseButton, Render, Rgba, Window, div,
+    prelude::*, px, rgb, transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputState},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{
+    components::IconName,
+    windows::{StickerWindowEvent, eyedropper::EyedropperWindow},
+};
+use crate::storage::ArcStickerStore;
+
+const SWATCH_SIZE: f32 = 56.0;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct PaletteContent {
+    /// `#RRGGBB` values in the order they were added.
+    colors: Vec<String>,
+}
+
+/// `#RRGGBB` of a 0xRRGGBB color.
+pub fn hex(color: u32) -> String {
+    format!("#{:06X}", color & 0xFFFFFF)
+}
+
+/// Parse `#RGB` or `#RRGGBB`, the `#` is optional.
+fn parse_hex(text: &str) -> Option<u32> {
+    let digits = text.trim().trim_start_matches('#');
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match digits.len() {
+        6 => u32::from_str_radix(digits, 16).ok(),
+        3 => {
+            let long = digits.chars().flat_map(|c| [c, c]).collect::<String>();
+            u32::from_str_radix(&long, 16).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Black or white, whichever reads better on `color`.
+fn label_color(color: u32) -> u32 {
+    let [_, r, g, b] = color.to_be_bytes();
+    let luminance = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    if luminance > 150.0 {
+        0x000000
+    } else {
+        0xFFFFFF
+    }
+}
+
+pub struct PaletteSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: PaletteContent,
+
+    colors_input: Entity<InputState>,
+    editing: bool,
+    /// Index of the swatch whose value was just copied.
+    copied: Option<usize>,
+
+    error: Option<String>,
+}
+
+impl PaletteSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<PaletteContent>(content).unwrap_or_default();
+
+        let colors_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(3, 8)
+                .default_value(content.colors.join("\n"))
+                .placeholder("One color per line, e.g. #1E90FF")
+        });
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            colors_input,
+            editing: false,
+            copied: None,
+            error: None,
+        }
+    }
+
+    /// "#1E90FF, #FFD700", so the colors can be searched for in the main window.
+    fn title(&self) -> String {
+        if self.content.colors.is_empty() {
+            return "Palette".to_string();
+        }
+        self.content.colors.join(", ")
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize palette sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save palette sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save palette sticker: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn add_color(&mut self, color: u32, cx: &mut Context<Self>) {
+        let color = hex(color);
+        if !self.content.colors.contains(&color) {
+            self.content.colors.push(color);
+            self.save_config(cx);
+        }
+        cx.notify();
+    }
+
+    fn remove_color(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix < self.content.colors.len() {
+            self.content.colors.remove(ix);
+            self.copied = None;
+            self.save_config(cx);
+            cx.notify();
+        }
+    }
+
+    fn copy(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some(color) = self.content.colors.get(ix) else {
+            return;
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(color.clone()));
+        self.copied = Some(ix);
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(Duration::from_secs(2)).await;
+            let _ = this.update(cx, |this, cx| {
+                if this.copied == Some(ix) {
+                    this.copied = None;
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn pick_from_screen(&mut self, cx: &mut Context<Self>) {
+        let entity = cx.entity().downgrade();
+        EyedropperWindow::open(cx, move |color, cx| {
+            let _ = entity.update(cx, |this, cx| this.add_color(color, cx));
+        });
+    }
+
+    fn start_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.content.colors.join("\n");
+        self.colors_input
+            .update(cx, |input, cx| input.set_value(text, window, cx));
+        self.editing = true;
+        cx.notify();
+    }
+
+    fn apply_form(&mut self, cx: &mut Context<Self>) {
+        let text = self.colors_input.read(cx).value().to_string();
+        let mut colors = Vec::new();
+        for value in text.split(|c: char| c.is_whitespace() || c == ',') {
+            if value.is_empty() {
+                continue;
+            }
+            let Some(color) = parse_hex(value) else {
+                self.error = Some(format!("Invalid color: {value}"));
+                cx.notify();
+                return;
+            };
+            let color = hex(color);
+            if !colors.contains(&color) {
+                colors.push(color);
+            }
+        }
+
+        self.content.colors = colors;
+        self.editing = false;
+        self.copied = None;
+        self.error = None;
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn swatch(&self, ix: usize, value: &str, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let color = parse_hex(value)?;
+        let copied = self.copied == Some(ix);
+
+        Some(
+            v_flex()
+                .id(("swatch", ix))
+                .size(px(SWATCH_SIZE))
+                .justify_end()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().border)
+                .bg(rgb(color))
+                .cursor_pointer()
+                .on_click(cx.listener(move |this, _, _, cx| this.copy(ix, cx)))
+                .on_mouse_down(
+                    MouseButton::Right,
+                    cx.listener(move |this, _, _, cx| this.remove_color(ix, cx)),
+                )
+                .child(
+                    div()
+                        .pb_1()
+                        .text_center()
+                        .text_xs()
+                        .text_color(rgb(label_color(color)))
+                        .font_family(cx.theme().mono_font_family.clone())
+                        .child(if copied {
+                            "Copied".to_string()
+                        } else {
+                            value.to_string()
+                        }),
+                )
+                .into_any_element(),
+        )
+    }
+
+    fn palette_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let swatches = self
+            .content
+            .colors
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, value)| self.swatch(ix, value, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .child(div().flex_1().window_drag_area().map(|view| {
+                if swatches.is_empty() {
+                    view.child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("Pick colors from the screen or add hex values"),
+                    )
+                } else {
+                    view.child(h_flex().gap_2().flex_wrap().children(swatches))
+                }
+            }))
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("eyedropper")
+                                .icon(IconName::Pipette)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Pick a color from the screen")
+                                .on_click(cx.listener(|this, _, _, cx| this.pick_from_screen(cx))),
+                        )
+                        .child(
+                            Button::new("edit")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Edit colors")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.start_editing(window, cx)
+                                })),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("click to copy, right click to remove"),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field()
+                    .label("Colors")
+                    .child(Input::new(&self.colors_input).small()),
+            )
+            .child(
+                field().child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("save")
+                                .label("Save")
+                                .small()
+                                .primary()
+                                .on_click(cx.listener(|this, _, _, cx| this.apply_form(cx))),
+                        )
+                        .child(Button::new("cancel").label("Cancel").small().on_click(
+                            cx.listener(|this, _, _, cx| {
+                                this.editing = false;
+                                this.error = None;
+                                cx.notify();
+                            }),
+                        )),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for PaletteSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(160, 120)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(280, 220)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for PaletteSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            body = body.child(self.palette_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("palette-error", msg.as_str()).small())
+        })
+    }
+}
@@ -0,0 +1,737 @@
+use std::time::Duration;
+
+use gpui::{
+    Animation, AnimationExt, AnyElement, AppContext, Context, Entity, Hsla, Render, Rgba, Window,
+    div, prelude::*, px, transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    green_500, h_flex,
+    input::{Input, InputState},
+    red_500,
+    tooltip::Tooltip,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::focus::{self, FocusSession, FocusTotals};
+use crate::model::sticker::StickerColor;
+use crate::native::sound::{self, Sound};
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::SettingsStore;
+
+/// Finished phases kept for the cycle history.
+const HISTORY_LIMIT: usize = 48;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+enum PomodoroPhase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl PomodoroPhase {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Work => "Focus",
+            Self::ShortBreak => "Short break",
+            Self::LongBreak => "Long break",
+        }
+    }
+
+    fn color(&self) -> Hsla {
+        match self {
+            Self::Work => red_500(),
+            Self::ShortBreak | Self::LongBreak => green_500(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PomodoroEntry {
+    phase: PomodoroPhase,
+    finished_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct PomodoroContent {
+    work_mins: u32,
+    short_break_mins: u32,
+    long_break_mins: u32,
+    /// A long break follows every this many work sessions.
+    sessions_before_long_break: u32,
+
+    phase: PomodoroPhase,
+    /// Remaining seconds of the phase when it was last started or paused.
+    remaining_secs: i32,
+    /// Set while the phase is counting down.
+    started_at_ms: Option<i64>,
+
+    completed_sessions: u32,
+    history: Vec<PomodoroEntry>,
+}
+
+impl Default for PomodoroContent {
+    fn default() -> Self {
+        Self {
+            work_mins: 25,
+            short_break_mins: 5,
+            long_break_mins: 15,
+            sessions_before_long_break: 4,
+            phase: PomodoroPhase::Work,
+            remaining_secs: 25 * 60,
+            started_at_ms: None,
+            completed_sessions: 0,
+            history: Vec::new(),
+        }
+    }
+}
+
+/// Focus sessions of the pomodoro in `content` finished at `since` or later.
+pub fn focus_sessions_since(content: &str, since: i64) -> usize {
+    serde_json::from_str::<PomodoroContent>(content)
+        .map(|content| {
+            content
+                .history
+                .iter()
+                .filter(|x| x.phase == PomodoroPhase::Work && x.finished_at_ms >= since)
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+impl PomodoroContent {
+    fn phase_secs(&self, phase: PomodoroPhase) -> i32 {
+        let mins = match phase {
+            PomodoroPhase::Work => self.work_mins,
+            PomodoroPhase::ShortBreak => self.short_break_mins,
+            PomodoroPhase::LongBreak => self.long_break_mins,
+        };
+        (mins.max(1) * 60) as i32
+    }
+
+    fn effective_remaining_secs(&self) -> i32 {
+        match self.started_at_ms {
+            Some(started_at_ms) => {
+                let elapsed_secs =
+                    ((crate::utils::time::now_unix_millis() - started_at_ms) / 1000) as i32;
+                self.remaining_secs - elapsed_secs
+            }
+            None => self.remaining_secs,
+        }
+    }
+
+    fn next_phase(&self) -> PomodoroPhase {
+        match self.phase {
+            PomodoroPhase::Work
+                if self.completed_sessions > 0
+                    && self.completed_sessions % self.sessions_before_long_break.max(1) == 0 =>
+            {
+                PomodoroPhase::LongBreak
+            }
+            PomodoroPhase::Work => PomodoroPhase::ShortBreak,
+            PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => PomodoroPhase::Work,
+        }
+    }
+}
+
+pub struct PomodoroSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    pomodoro: PomodoroContent,
+    /// Recorded focus sessions of all pomodoros, compared with the goal in settings.
+    totals: FocusTotals,
+
+    work_mins: Entity<InputState>,
+    short_break_mins: Entity<InputState>,
+    long_break_mins: Entity<InputState>,
+    sessions_before_long_break: Entity<InputState>,
+    show_settings: bool,
+
+    ticking: bool,
+    last_save_time_while_countdown: i64,
+    is_just_finished: bool,
+
+    error: Option<String>,
+}
+
+impl PomodoroSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let pomodoro = serde_json::from_str::<PomodoroContent>(content).unwrap_or_default();
+
+        let mut number_input =
+            |value: u32| cx.new(|cx| InputState::new(window, cx).default_value(value.to_string()));
+        let work_mins = number_input(pomodoro.work_mins);
+        let short_break_mins = number_input(pomodoro.short_break_mins);
+        let long_break_mins = number_input(pomodoro.long_break_mins);
+        let sessions_before_long_break = number_input(pomodoro.sessions_before_long_break);
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            pomodoro,
+            totals: FocusTotals::default(),
+            work_mins,
+            short_break_mins,
+            long_break_mins,
+            sessions_before_long_break,
+            show_settings: false,
+            ticking: false,
+            last_save_time_while_countdown: 0,
+            is_just_finished: false,
+            error: None,
+        };
+        this.spawn_load_totals(cx);
+        this
+    }
+
+    fn is_running(&self) -> bool {
+        self.pomodoro.started_at_ms.is_some()
+    }
+
+    fn save_state(&mut self, cx: &mut Context<Self>) -> bool {
+        let title = match self.pomodoro.completed_sessions {
+            0 => "Pomodoro".to_string(),
+            1 => "Pomodoro · 1 session".to_string(),
+            n => format!("Pomodoro · {n} sessions"),
+        };
+
+        let json = match serde_json::to_string(&self.pomodoro) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to save pomodoro state: {err}"));
+                return false;
+            }
+        };
+
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+        let id = self.id;
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save pomodoro title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send title changed event for pomodoro sticker");
+            }
+
+            if let Err(err) = save_content(&store, &sticker_events_tx, id, json, String::new()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save pomodoro state: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn spawn_load_totals(&mut self, cx: &mut Context<Self>) {
+        let store = self.store.clone();
+        cx.spawn(async move |this, cx| {
+            let now = chrono::Local::now();
+            match store.get_focus_sessions_since(focus::week_start(now)).await {
+                Ok(sessions) => {
+                    let _ = this.update(cx, |this, cx| {
+                        this.totals = FocusTotals::count(&sessions, now);
+                        cx.notify();
+                    });
+                }
+                Err(err) => tracing::warn!(error = ?err, "Failed to load focus sessions"),
+            }
+        })
+        .detach();
+    }
+
+    /// Keep the finished focus session for the daily and weekly totals.
+    fn record_focus_session(&mut self, finished_at: i64, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let session = FocusSession {
+            id: 0,
+            sticker_id: Some(self.id),
+            finished_at,
+            duration_ms: self.pomodoro.phase_secs(PomodoroPhase::Work) as i64 * 1000,
+        };
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+        let id = self.id;
+
+        cx.spawn(async move |this, cx| {
+            if let Err(err) = store.insert_focus_session(session).await {
+                let _ = this.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to record focus session: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::FocusSessionRecorded) {
+                tracing::warn!(id, error = %err, "Failed to send focus session recorded event");
+            }
+            let _ = this.update(cx, |this, cx| this.spawn_load_totals(cx));
+        })
+        .detach();
+    }
+
+    fn start(&mut self, cx: &mut Context<Self>) {
+        self.is_just_finished = false;
+        if self.pomodoro.remaining_secs <= 0 {
+            self.pomodoro.remaining_secs = self.pomodoro.phase_secs(self.pomodoro.phase);
+        }
+        self.pomodoro.started_at_ms = Some(crate::utils::time::now_unix_millis());
+        self.save_state(cx);
+        cx.notify();
+    }
+
+    fn pause(&mut self, cx: &mut Context<Self>) {
+        self.pomodoro.remaining_secs = self.pomodoro.effective_remaining_secs().max(0);
+        self.pomodoro.started_at_ms = None;
+        self.save_state(cx);
+        cx.notify();
+    }
+
+    /// Go to the next phase without counting the current one.
+    fn skip(&mut self, cx: &mut Context<Self>) {
+        self.is_just_finished = false;
+        let next = self.pomodoro.next_phase();
+        self.enter_phase(next);
+        self.save_state(cx);
+        cx.notify();
+    }
+
+    fn reset(&mut self, cx: &mut Context<Self>) {
+        self.is_just_finished = false;
+        self.pomodoro.completed_sessions = 0;
+        self.pomodoro.history.clear();
+        self.enter_phase(PomodoroPhase::Work);
+        self.save_state(cx);
+        cx.notify();
+    }
+
+    fn enter_phase(&mut self, phase: PomodoroPhase) {
+        self.pomodoro.phase = phase;
+        self.pomodoro.remaining_secs = self.pomodoro.phase_secs(phase);
+        self.pomodoro.started_at_ms = None;
+    }
+
+    fn finish_phase(&mut self, cx: &mut Context<Self>) {
+        let overdue_secs = -self.pomodoro.effective_remaining_secs();
+        let finished_at_ms =
+            crate::utils::time::now_unix_millis() - overdue_secs.max(0) as i64 * 1000;
+
+        if self.pomodoro.phase == PomodoroPhase::Work {
+            self.pomodoro.completed_sessions += 1;
+            self.record_focus_session(finished_at_ms, cx);
+        }
+        self.pomodoro.history.push(PomodoroEntry {
+            phase: self.pomodoro.phase,
+            finished_at_ms,
+        });
+        if self.pomodoro.history.len() > HISTORY_LIMIT {
+            let excess = self.pomodoro.history.len() - HISTORY_LIMIT;
+            self.pomodoro.history.drain(..excess);
+        }
+
+        let next = self.pomodoro.next_phase();
+        self.enter_phase(next);
+        self.save_state(cx);
+
+        // A phase which ended while the app was closed is only recorded.
+        if overdue_secs < 5 {
+            self.is_just_finished = true;
+            self.on_finished(cx);
+        }
+    }
+
+    /// Same finish behavior as the timer sticker, silent while do not disturb is on.
+    fn on_finished(&self, cx: &mut Context<Self>) {
+        let summary = match self.pomodoro.history.last() {
+            Some(entry) => format!("{} is over", entry.phase.label()),
+            None => "Pomodoro finished".to_string(),
+        };
+        cx.spawn(async move |this, cx| {
+            let dnd = cx
+                .background_executor()
+                .spawn(async { crate::native::dnd::is_active() })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                if !dnd {
+                    cx.activate(true);
+                }
+                this.spawn_for_beep(dnd, cx);
+            });
+
+            if dnd {
+                tracing::info!("Do not disturb is on, pomodoro notification queued");
+                crate::native::dnd::notify_when_inactive(
+                    cx,
+                    summary,
+                    "Finished while do not disturb was on".to_string(),
+                )
+                .await;
+            }
+        })
+        .detach();
+    }
+
+    fn spawn_for_beep(&self, silent: bool, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let start = crate::utils::time::now_unix_millis();
+            while crate::utils::time::now_unix_millis() - start < 10000
+                && let Ok(true) = this.read_with(cx, |this, _| this.is_just_finished)
+            {
+                if !silent {
+                    let _ = cx.update(|cx| sound::play(cx, Sound::Alarm));
+                }
+                cx.background_executor()
+                    .timer(Duration::from_millis(500))
+                    .await;
+            }
+            let _ = this.update(cx, |this, cx| {
+                this.is_just_finished = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Redraw every second while counting down, like the timer sticker.
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        cx.spawn(async move |e, cx| {
+            cx.background_executor()
+                .timer(Duration::from_secs_f64(0.8))
+                .await;
+            let _ = e.update(cx, |this, cx| {
+                this.ticking = false;
+                if !this.is_running() {
+                    return;
+                }
+
+                if this.pomodoro.effective_remaining_secs() <= 0 {
+                    this.finish_phase(cx);
+                } else if crate::utils::time::now_unix_millis()
+                    - this.last_save_time_while_countdown
+                    >= 3000
+                {
+                    this.save_state(cx);
+                    this.last_save_time_while_countdown = crate::utils::time::now_unix_millis();
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn apply_settings_form(&mut self, cx: &mut Context<Self>) {
+        let read = |input: &Entity<InputState>, cx: &Context<Self>| {
+            input
+                .read(cx)
+                .value()
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|x| *x > 0)
+        };
+
+        let (Some(work), Some(short_break), Some(long_break), Some(sessions)) = (
+            read(&self.work_mins, cx),
+            read(&self.short_break_mins, cx),
+            read(&self.long_break_mins, cx),
+            read(&self.sessions_before_long_break, cx),
+        ) else {
+            self.error = Some("All values must be whole numbers greater than zero".to_string());
+            cx.notify();
+            return;
+        };
+
+        self.pomodoro.work_mins = work;
+        self.pomodoro.short_break_mins = short_break;
+        self.pomodoro.long_break_mins = long_break;
+        self.pomodoro.sessions_before_long_break = sessions;
+        if !self.is_running() {
+            self.pomodoro.remaining_secs = self.pomodoro.phase_secs(self.pomodoro.phase);
+        }
+        self.show_settings = false;
+        self.error = None;
+        self.save_state(cx);
+        cx.notify();
+    }
+
+    fn settings_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .child(
+                v_form()
+                    .child(
+                        field()
+                            .label("Focus")
+                            .child(Input::new(&self.work_mins).small()),
+                    )
+                    .child(
+                        field()
+                            .label("Short break")
+                            .child(Input::new(&self.short_break_mins).small()),
+                    )
+                    .child(
+                        field()
+                            .label("Long break")
+                            .child(Input::new(&self.long_break_mins).small()),
+                    )
+                    .child(
+                        field()
+                            .label("Long break every")
+                            .child(Input::new(&self.sessions_before_long_break).small()),
+                    ),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Lengths in minutes, long break every N focus sessions"),
+            )
+            .child(
+                h_flex().gap_1().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.apply_settings_form(cx))),
+                ),
+            )
+            .into_any_element()
+    }
+
+    fn history_view(&self) -> AnyElement {
+        h_flex()
+            .gap_1()
+            .flex_wrap()
+            .justify_center()
+            .children(self.pomodoro.history.iter().enumerate().map(|(ix, entry)| {
+                let tooltip = format!(
+                    "{} · {}",
+                    entry.phase.label(),
+                    crate::utils::time::format_unix_millis(entry.finished_at_ms)
+                );
+                div()
+                    .id(("pomodoro-history", ix))
+                    .size(px(if entry.phase == PomodoroPhase::Work {
+                        10.0
+                    } else {
+                        6.0
+                    }))
+                    .rounded_full()
+                    .bg(entry.phase.color())
+                    .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+            }))
+            .into_any_element()
+    }
+
+    /// "Today 3/8 · week 12/40", green once the daily goal is reached.
+    fn goal_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        let goal = SettingsStore::get(cx).focus_goal.clone();
+        let reached = goal.daily_sessions > 0 && self.totals.today >= goal.daily_sessions;
+
+        div()
+            .text_xs()
+            .text_color(if reached {
+                green_500()
+            } else {
+                cx.theme().muted_foreground
+            })
+            .child(format!(
+                "Today {} · week {}",
+                focus::progress(self.totals.today, goal.daily_sessions),
+                focus::progress(self.totals.week, goal.weekly_sessions)
+            ))
+            .into_any_element()
+    }
+
+    fn countdown_view(&mut self, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+        let remaining_secs = self.pomodoro.effective_remaining_secs().max(0);
+        let label = format!("{:02}:{:02}", remaining_secs / 60, remaining_secs % 60);
+        let phase = self.pomodoro.phase;
+        let running = self.is_running();
+
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_1()
+            .items_center()
+            .justify_center()
+            .relative()
+            .when(self.is_just_finished, |view| {
+                view.child(
+                    div()
+                        .absolute()
+                        .left_0()
+                        .top_0()
+                        .bottom_0()
+                        .right_0()
+                        .bg(phase.color())
+                        .with_animation(
+                            "pomodoro-finished",
+                            Animation::new(Duration::from_millis(800)).repeat(),
+                            |v, x| v.opacity(0.3 * x),
+                        ),
+                )
+            })
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(phase.color())
+                    .child(phase.label()),
+            )
+            .child(div().text_2xl().font_bold().child(label))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!("Sessions: {}", self.pomodoro.completed_sessions)),
+            )
+            .child(self.goal_view(cx))
+            .child(self.history_view())
+            .when(window.is_window_hovered() || !running, |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(if running {
+                            Button::new("pause")
+                                .icon(IconName::Pause)
+                                .bg(transparent_white())
+                                .border_0()
+                                .on_click(cx.listener(|this, _, _, cx| this.pause(cx)))
+                        } else {
+                            Button::new("start")
+                                .icon(IconName::Play)
+                                .bg(transparent_white())
+                                .border_0()
+                                .on_click(cx.listener(|this, _, _, cx| this.start(cx)))
+                        })
+                        .child(
+                            Button::new("skip")
+                                .icon(IconName::Forward)
+                                .bg(transparent_white())
+                                .border_0()
+                                .tooltip("Skip to the next phase")
+                                .on_click(cx.listener(|this, _, _, cx| this.skip(cx))),
+                        )
+                        .child(
+                            Button::new("reset")
+                                .icon(IconName::Stop)
+                                .bg(transparent_white())
+                                .border_0()
+                                .tooltip("Reset sessions and history")
+                                .on_click(cx.listener(|this, _, _, cx| this.reset(cx))),
+                        )
+                        .child(
+                            Button::new("settings")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .tooltip("Change lengths")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.show_settings = true;
+                                    cx.notify();
+                                })),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for PomodoroSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_state(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 160)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(260, 240)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        let remaining_secs = self.pomodoro.effective_remaining_secs().max(0);
+        Some(format!(
+            "{} {}, {:02}:{:02} remaining, {} sessions",
+            self.pomodoro.phase.label().to_lowercase(),
+            if self.is_running() {
+                "running"
+            } else {
+                "paused"
+            },
+            remaining_secs / 60,
+            remaining_secs % 60,
+            self.pomodoro.completed_sessions
+        ))
+    }
+}
+
+impl Render for PomodoroSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        if self.is_running() {
+            self.spawn_for_tick(cx);
+        }
+
+        let body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        let body = if self.show_settings {
+            body.child(self.settings_view(cx))
+        } else {
+            body.child(self.countdown_view(window, cx))
+        };
+
+        body.when_some(self.error.as_ref(), |view, err| {
+            view.child(Alert::error("pomodoro-error", err.as_str()).small())
+        })
+    }
+}
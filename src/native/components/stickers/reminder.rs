@@ -0,0 +1,565 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone};
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputState},
+    switch::Switch,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::sound::{self, Sound};
+use crate::native::top_most::set_window_top_most;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+const TICK_INTERVAL: Duration = Duration::from_millis(500);
+/// A due reminder which is neither done nor snoozed grabs attention again this often.
+const ESCALATE_INTERVAL_MS: i64 = 5 * 60 * 1000;
+/// How long it beeps each time it grabs attention.
+const BEEP_DURATION_MS: i64 = 10_000;
+const SNOOZE_MINUTES: i64 = 10;
+const DUE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ReminderContent {
+    /// Unix millis.
+    due_at: Option<i64>,
+    note: String,
+    beep: bool,
+    /// Dismissed after it fired.
+    done: bool,
+}
+
+impl ReminderContent {
+    fn is_due(&self, now: i64) -> bool {
+        !self.done && self.due_at.is_some_and(|due_at| due_at <= now)
+    }
+}
+
+/// Whether a reminder sticker with this content should fire now, used to open the
+/// window of reminders which are due while closed.
+pub fn is_due(content: &str, now: i64) -> bool {
+    serde_json::from_str::<ReminderContent>(content).is_ok_and(|x| x.is_due(now))
+}
+
+pub struct ReminderSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: ReminderContent,
+
+    note_input: Entity<InputState>,
+    due_input: Entity<InputState>,
+    form_beep: bool,
+    editing: bool,
+
+    ticking: bool,
+    flash_on: bool,
+    /// When it last grabbed attention while due.
+    alerted_at: Option<i64>,
+    /// Raise the window on the next render, it needs the window.
+    raise_window: bool,
+
+    error: Option<String>,
+}
+
+impl ReminderSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<ReminderContent>(content).unwrap_or_default();
+
+        let note_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.note.clone())
+                .placeholder("Call the dentist")
+        });
+        let due_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.due_at.map(format_due).unwrap_or_default())
+                .placeholder("2026-01-31 09:30, 09:30 or +15m")
+        });
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            form_beep: content.beep,
+            editing: content.due_at.is_none(),
+            content,
+            note_input,
+            due_input,
+            ticking: false,
+            flash_on: false,
+            alerted_at: None,
+            raise_window: false,
+            error: None,
+        }
+    }
+
+    /// "Call the dentist · 09:30", so the due time shows up in the main window.
+    fn title(&self) -> String {
+        let note = self.content.note.lines().next().unwrap_or("").trim();
+        let note = if note.is_empty() { "Reminder" } else { note };
+        match self.content.due_at.and_then(local_time) {
+            Some(due) => format!("{note} · {}", due.format("%a %-d %b %H:%M")),
+            None => note.to_string(),
+        }
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize reminder sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save reminder sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save reminder sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    /// Redraw for the countdown and the flashing while due.
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(TICK_INTERVAL).await;
+            let _ = this.update(cx, |this, cx| {
+                this.ticking = false;
+                this.flash_on = !this.flash_on;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Bring the window to the front and beep, or only flash and queue a notification
+    /// while the OS is in do not disturb mode, same as the timer.
+    fn alert(&mut self, cx: &mut Context<Self>) {
+        let now = crate::utils::time::now_unix_millis();
+        self.alerted_at = Some(now);
+        tracing::info!(id = self.id, "Reminder is due");
+        let _ = self
+            .sticker_events_tx
+            .send(StickerWindowEvent::ReminderDue { id: self.id });
+
+        let summary = self.title();
+        let beep = self.content.beep;
+        cx.spawn(async move |this, cx| {
+            let dnd = cx
+                .background_executor()
+                .spawn(async { crate::native::dnd::is_active() })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                if !dnd {
+                    cx.activate(true);
+                    this.raise_window = true;
+                }
+                cx.notify();
+            });
+
+            if dnd {
+                tracing::info!("Do not disturb is on, reminder notification queued");
+                crate::native::dnd::notify_when_inactive(
+                    cx,
+                    summary,
+                    "Due while do not disturb was on".to_string(),
+                )
+                .await;
+                return;
+            }
+
+            while beep
+                && crate::utils::time::now_unix_millis() - now < BEEP_DURATION_MS
+                && let Ok(true) = this.read_with(cx, |this, _| {
+                    this.content.is_due(crate::utils::time::now_unix_millis())
+                })
+            {
+                let _ = cx.update(|cx| sound::play(cx, Sound::Alarm));
+                cx.background_executor()
+                    .timer(Duration::from_millis(500))
+                    .await;
+            }
+        })
+        .detach();
+    }
+
+    fn dismiss(&mut self, snooze: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if snooze {
+            self.content.due_at =
+                Some(crate::utils::time::now_unix_millis() + SNOOZE_MINUTES * 60 * 1000);
+        } else {
+            self.content.done = true;
+        }
+        self.alerted_at = None;
+        set_window_top_most(window, false);
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn edit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let due = self.content.due_at.map(format_due).unwrap_or_default();
+        self.due_input
+            .update(cx, |input, cx| input.set_value(due, window, cx));
+        self.form_beep = self.content.beep;
+        self.editing = true;
+        cx.notify();
+    }
+
+    fn set_due_text(&mut self, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        self.due_input
+            .update(cx, |input, cx| input.set_value(text, window, cx));
+    }
+
+    fn apply_form(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let note = self.note_input.read(cx).value().trim().to_string();
+        let due_text = self.due_input.read(cx).value().trim().to_string();
+
+        let now = Local::now();
+        let Some(due) = parse_due(&due_text, now) else {
+            self.error =
+                Some("Enter the due time as 2026-01-31 09:30, 09:30 or +15m / +2h".to_string());
+            cx.notify();
+            return;
+        };
+        if due <= now {
+            self.error = Some("The due time is in the past".to_string());
+            cx.notify();
+            return;
+        }
+
+        self.content = ReminderContent {
+            due_at: Some(due.timestamp_millis()),
+            note,
+            beep: self.form_beep,
+            done: false,
+        };
+        self.editing = false;
+        self.alerted_at = None;
+        self.error = None;
+        set_window_top_most(window, false);
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        let quick_button = |id: &'static str, label: &'static str, text: fn() -> String| {
+            Button::new(id).label(label).xsmall().on_click(cx.listener(
+                move |this, _, window, cx| {
+                    this.set_due_text(text(), window, cx);
+                },
+            ))
+        };
+        let quick_buttons = h_flex()
+            .gap_1()
+            .flex_wrap()
+            .child(quick_button("in-10m", "+10 min", || "+10m".to_string()))
+            .child(quick_button("in-1h", "+1 hour", || "+1h".to_string()))
+            .child(quick_button("tomorrow", "Tomorrow 9:00", || {
+                let tomorrow = Local::now().date_naive() + chrono::Duration::days(1);
+                format!("{} 09:00", tomorrow.format("%Y-%m-%d"))
+            }));
+
+        v_form()
+            .child(
+                field()
+                    .label("Note")
+                    .child(Input::new(&self.note_input).small()),
+            )
+            .child(
+                field()
+                    .label("Due")
+                    .child(Input::new(&self.due_input).small()),
+            )
+            .child(field().child(quick_buttons))
+            .child(
+                field().child(
+                    Switch::new("reminder-beep")
+                        .label("Beep when due")
+                        .small()
+                        .checked(self.form_beep)
+                        .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                            this.form_beep = *checked;
+                            cx.notify();
+                        })),
+                ),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, window, cx| this.apply_form(window, cx))),
+                ),
+            )
+            .into_any_element()
+    }
+
+    fn reminder_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let now = crate::utils::time::now_unix_millis();
+        let due = self.content.is_due(now);
+        let due_at = self.content.due_at.unwrap_or(now);
+
+        let status = if self.content.done {
+            "Done".to_string()
+        } else if due && now - due_at < 60_000 {
+            "Due now".to_string()
+        } else if due {
+            format!("Due {} ago", relative(now - due_at))
+        } else {
+            format!("In {}", relative(due_at - now))
+        };
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_1()
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_1()
+                    .overflow_hidden()
+                    .window_drag_area()
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_bold()
+                            .child(if self.content.note.is_empty() {
+                                "Reminder".to_string()
+                            } else {
+                                self.content.note.clone()
+                            }),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!(
+                                "{} · {status}",
+                                local_time(due_at)
+                                    .map(|x| x.format("%a %-d %b %H:%M").to_string())
+                                    .unwrap_or_default()
+                            )),
+                    ),
+            )
+            .when(due, |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("done")
+                                .label("Done")
+                                .small()
+                                .primary()
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.dismiss(false, window, cx)
+                                })),
+                        )
+                        .child(
+                            Button::new("snooze")
+                                .label(format!("Snooze {SNOOZE_MINUTES} min"))
+                                .small()
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.dismiss(true, window, cx)
+                                })),
+                        ),
+                )
+            })
+            .when(!due && window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex().child(
+                        Button::new("edit")
+                            .icon(IconName::Adjustments)
+                            .bg(transparent_white())
+                            .border_0()
+                            .xsmall()
+                            .tooltip("Change reminder")
+                            .on_click(cx.listener(|this, _, window, cx| this.edit(window, cx))),
+                    ),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for ReminderSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        // Closing a due reminder dismisses it, otherwise it would open again right away.
+        if self.content.is_due(crate::utils::time::now_unix_millis()) {
+            self.content.done = true;
+        }
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 110)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(280, 220)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        let due_at = self.content.due_at?;
+        let due = crate::utils::time::format_unix_millis(due_at);
+        match self.content.done {
+            true => Some(format!("done, was due {due}")),
+            false => Some(format!("due {due}")),
+        }
+    }
+}
+
+impl Render for ReminderSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let now = crate::utils::time::now_unix_millis();
+        let due = !self.editing && self.content.is_due(now);
+        if due
+            && self
+                .alerted_at
+                .is_none_or(|at| now - at >= ESCALATE_INTERVAL_MS)
+        {
+            self.alert(cx);
+        }
+        if self.raise_window {
+            self.raise_window = false;
+            window.activate_window();
+            set_window_top_most(window, true);
+        }
+
+        let bg = match due && self.flash_on {
+            true => gpui::red().into(),
+            false => self.color.bg(),
+        };
+        let mut body = v_flex().size_full().bg(Rgba { a: 0.85, ..bg });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            if self.content.due_at.is_some() && !self.content.done {
+                self.spawn_for_tick(cx);
+            }
+            body = body.child(self.reminder_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("reminder-error", msg.as_str()).small())
+        })
+    }
+}
+
+fn local_time(millis: i64) -> Option<DateTime<Local>> {
+    Local.timestamp_millis_opt(millis).single()
+}
+
+fn format_due(millis: i64) -> String {
+    local_time(millis)
+        .map(|x| x.format(DUE_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+/// "2026-01-31 09:30", "09:30" (the next one) or "+15m" / "+2h" from now.
+fn parse_due(text: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    if let Some(offset) = text.strip_prefix('+') {
+        let offset = offset.trim();
+        let split = offset
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(offset.len());
+        let (number, unit) = offset.split_at(split);
+        let number = number.parse::<i64>().ok()?;
+        let minutes = match unit.trim() {
+            "" | "m" | "min" => number,
+            "h" => number * 60,
+            _ => return None,
+        };
+        return Some(now + chrono::Duration::minutes(minutes));
+    }
+
+    if let Ok(time) = NaiveTime::parse_from_str(text, "%H:%M") {
+        let today = now.date_naive().and_time(time);
+        let due = Local.from_local_datetime(&today).earliest()?;
+        return match due > now {
+            true => Some(due),
+            false => Local
+                .from_local_datetime(&(today + chrono::Duration::days(1)))
+                .earliest(),
+        };
+    }
+
+    let date_time = NaiveDateTime::parse_from_str(text, DUE_FORMAT).ok()?;
+    Local.from_local_datetime(&date_time).earliest()
+}
+
+/// "<1m", "3m", "1h 05m", "2d 4h".
+fn relative(millis: i64) -> String {
+    match millis.max(0) / 60_000 {
+        0 => "<1m".to_string(),
+        minutes @ 1..60 => format!("{minutes}m"),
+        minutes @ 60..1440 => format!("{}h {:02}m", minutes / 60, minutes % 60),
+        minutes => format!("{}d {}h", minutes / 1440, minutes % 1440 / 60),
+    }
+}
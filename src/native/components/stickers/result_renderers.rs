@@ -0,0 +1,577 @@
+use std::ops::Range;
+use std::sync::{Arc, LazyLock, RwLock};
+
+use gpui::{
+    AnyElement, App, Entity, FontWeight, HighlightStyle, Image, ImageFormat, ImageSource, Pixels,
+    Rgba, ScrollHandle, StyledText, div, img, prelude::*, px, rgb, rgba,
+};
+use gpui_component::{ActiveTheme, h_flex, scroll::ScrollableElement, text::TextView, v_flex};
+
+use crate::model::sticker::StickerSettings;
+use crate::native::components::webview::SimpleWebView;
+use crate::utils::highlight;
+
+/// What a renderer gets to show the output of a command run.
+pub struct RenderContext<'a> {
+    pub output: &'a str,
+    pub padding: Pixels,
+    pub bg: Rgba,
+    pub settings: &'a StickerSettings,
+    /// A web view of the output, only created for renderers which `use_webview`.
+    pub webview: Option<Entity<SimpleWebView>>,
+}
+
+/// Shows the output of a command sticker, e.g. as text, markdown or a chart.
+pub trait ResultRenderer: Send + Sync {
+    /// Stored in the sticker content, so it must never change.
+    fn key(&self) -> &'static str;
+
+    /// Shown in the "Render output as" choice.
+    fn label(&self) -> &'static str;
+
+    /// Whether partial output can be shown while the command is still running.
+    fn streams(&self) -> bool {
+        true
+    }
+
+    /// If true, the sticker window offers the wrap and monospace toggles.
+    fn text_output(&self) -> bool {
+        false
+    }
+
+    fn use_webview(&self) -> bool {
+        false
+    }
+
+    fn render(&self, ctx: RenderContext, cx: &App) -> AnyElement;
+}
+
+static RENDERERS: LazyLock<RwLock<Vec<Arc<dyn ResultRenderer>>>> = LazyLock::new(|| {
+    RwLock::new(vec![
+        Arc::new(TextRenderer),
+        Arc::new(MarkdownRenderer),
+        Arc::new(HtmlRenderer),
+        Arc::new(SvgRenderer),
+        Arc::new(JsonRenderer),
+        Arc::new(TableRenderer),
+        Arc::new(ChartRenderer),
+        Arc::new(AnsiRenderer),
+    ])
+});
+
+/// Add a renderer, e.g. from a plugin; one with the same key is replaced.
+#[allow(dead_code)]
+pub fn register(renderer: Arc<dyn ResultRenderer>) {
+    if let Ok(mut renderers) = RENDERERS.write() {
+        match renderers.iter().position(|x| x.key() == renderer.key()) {
+            Some(index) => renderers[index] = renderer,
+            None => renderers.push(renderer),
+        }
+    }
+}
+
+pub fn all() -> Vec<Arc<dyn ResultRenderer>> {
+    RENDERERS.read().map(|x| x.clone()).unwrap_or_default()
+}
+
+/// The renderer for the key, plain text when it is unknown (e.g. its plugin is gone).
+pub fn get(key: &str) -> Arc<dyn ResultRenderer> {
+    RENDERERS
+        .read()
+        .ok()
+        .and_then(|renderers| renderers.iter().find(|x| x.key() == key).cloned())
+        .unwrap_or_else(|| Arc::new(TextRenderer))
+}
+
+pub const DEFAULT_KEY: &str = "Text";
+
+/// More matches are not highlighted, the search gets slow and the count useless.
+const MAX_SEARCH_MATCHES: usize = 10_000;
+
+/// Byte ranges of `query` in `text`, ignoring ASCII case.
+pub fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    let (haystack, needle) = (text.as_bytes(), query.as_bytes());
+    let mut matches = Vec::new();
+    if needle.is_empty() {
+        return matches;
+    }
+
+    let mut start = 0;
+    while start + needle.len() <= haystack.len() && matches.len() < MAX_SEARCH_MATCHES {
+        let end = start + needle.len();
+        if haystack[start..end].eq_ignore_ascii_case(needle)
+            && text.is_char_boundary(start)
+            && text.is_char_boundary(end)
+        {
+            matches.push(start..end);
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+    matches
+}
+
+/// Text output with the search matches highlighted, one child per line so the
+/// current match can be scrolled to with [`ScrollHandle::scroll_to_item`].
+pub fn render_search(
+    ctx: RenderContext,
+    matches: &[Range<usize>],
+    current: usize,
+    scroll: &ScrollHandle,
+    cx: &App,
+) -> AnyElement {
+    let match_style = HighlightStyle {
+        background_color: Some(rgba(0xfacc1566).into()),
+        ..Default::default()
+    };
+    let current_style = HighlightStyle {
+        background_color: Some(rgba(0xf97316cc).into()),
+        ..Default::default()
+    };
+
+    let mut line_start = 0;
+    let mut next_match = 0;
+    let lines = ctx.output.split('\n').map(|line| {
+        let line_end = line_start + line.len();
+        let mut highlights = Vec::new();
+        while let Some(range) = matches.get(next_match)
+            && range.end <= line_end
+        {
+            let style = if next_match == current {
+                current_style
+            } else {
+                match_style
+            };
+            highlights.push((range.start - line_start..range.end - line_start, style));
+            next_match += 1;
+        }
+        line_start = line_end + 1;
+
+        let text = match line.trim_end_matches('\r') {
+            "" => " ".to_string(),
+            text => text.to_string(),
+        };
+        div().child(StyledText::new(text).with_highlights(highlights))
+    });
+
+    div()
+        .id("output-search")
+        .track_scroll(scroll)
+        .overflow_y_scroll()
+        .p(ctx.padding)
+        .text_sm()
+        .size_full()
+        .bg(ctx.bg)
+        .when(ctx.settings.monospace, |v| {
+            v.font_family(cx.theme().mono_font_family.clone())
+        })
+        .when(ctx.settings.no_wrap, |v| v.whitespace_nowrap())
+        .children(lines.collect::<Vec<_>>())
+        .into_any_element()
+}
+
+fn text_view(text: impl IntoElement, ctx: &RenderContext, cx: &App) -> AnyElement {
+    div()
+        .p(ctx.padding)
+        .text_sm()
+        .size_full()
+        .overflow_scrollbar()
+        .bg(ctx.bg)
+        .when(ctx.settings.monospace, |v| {
+            v.font_family(cx.theme().mono_font_family.clone())
+        })
+        .when(ctx.settings.no_wrap, |v| v.whitespace_nowrap())
+        .child(text)
+        .into_any_element()
+}
+
+struct TextRenderer;
+
+impl ResultRenderer for TextRenderer {
+    fn key(&self) -> &'static str {
+        DEFAULT_KEY
+    }
+
+    fn label(&self) -> &'static str {
+        "text"
+    }
+
+    fn text_output(&self) -> bool {
+        true
+    }
+
+    fn render(&self, ctx: RenderContext, cx: &App) -> AnyElement {
+        text_view(ctx.output.to_string(), &ctx, cx)
+    }
+}
+
+struct MarkdownRenderer;
+
+impl ResultRenderer for MarkdownRenderer {
+    fn key(&self) -> &'static str {
+        "Markdown"
+    }
+
+    fn label(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn text_output(&self) -> bool {
+        true
+    }
+
+    fn render(&self, ctx: RenderContext, cx: &App) -> AnyElement {
+        TextView::markdown("output", ctx.output.to_string())
+            .bg(ctx.bg)
+            .p(ctx.padding)
+            .size_full()
+            .when(ctx.settings.monospace, |v| {
+                v.font_family(cx.theme().mono_font_family.clone())
+            })
+            .when(ctx.settings.no_wrap, |v| v.whitespace_nowrap())
+            .selectable(true)
+            .scrollable(true)
+            .into_any_element()
+    }
+}
+
+struct HtmlRenderer;
+
+impl ResultRenderer for HtmlRenderer {
+    fn key(&self) -> &'static str {
+        "Html"
+    }
+
+    fn label(&self) -> &'static str {
+        "html"
+    }
+
+    fn streams(&self) -> bool {
+        false
+    }
+
+    fn use_webview(&self) -> bool {
+        true
+    }
+
+    fn render(&self, ctx: RenderContext, _: &App) -> AnyElement {
+        match ctx.webview {
+            Some(entity) => div()
+                .size_full()
+                .child(entity)
+                .p(ctx.padding)
+                .into_any_element(),
+            None => div().size_full().bg(ctx.bg).into_any_element(),
+        }
+    }
+}
+
+struct SvgRenderer;
+
+impl ResultRenderer for SvgRenderer {
+    fn key(&self) -> &'static str {
+        "Svg"
+    }
+
+    fn label(&self) -> &'static str {
+        "svg"
+    }
+
+    fn streams(&self) -> bool {
+        false
+    }
+
+    fn render(&self, ctx: RenderContext, _: &App) -> AnyElement {
+        img(ImageSource::Image(Arc::new(Image::from_bytes(
+            ImageFormat::Svg,
+            ctx.output.as_bytes().to_vec(),
+        ))))
+        .bg(ctx.bg)
+        .p(ctx.padding)
+        .size_full()
+        .object_fit(gpui::ObjectFit::Fill)
+        .into_any_element()
+    }
+}
+
+/// Pretty printed and highlighted, invalid JSON is shown as it is.
+struct JsonRenderer;
+
+impl ResultRenderer for JsonRenderer {
+    fn key(&self) -> &'static str {
+        "Json"
+    }
+
+    fn label(&self) -> &'static str {
+        "json"
+    }
+
+    fn streams(&self) -> bool {
+        false
+    }
+
+    fn text_output(&self) -> bool {
+        true
+    }
+
+    fn render(&self, ctx: RenderContext, cx: &App) -> AnyElement {
+        let text = serde_json::from_str::<serde_json::Value>(ctx.output)
+            .ok()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+            .unwrap_or_else(|| ctx.output.to_string());
+
+        let highlights = highlight::highlight(&text, "json")
+            .into_iter()
+            .map(|span| {
+                let style = HighlightStyle {
+                    color: Some(rgb(span.color).into()),
+                    font_weight: span.bold.then_some(FontWeight::BOLD),
+                    ..Default::default()
+                };
+                (span.range, style)
+            })
+            .collect::<Vec<_>>();
+
+        text_view(StyledText::new(text).with_highlights(highlights), &ctx, cx)
+    }
+}
+
+/// Tab separated output, or comma separated when there are no tabs; the first
+/// row is the header.
+struct TableRenderer;
+
+impl ResultRenderer for TableRenderer {
+    fn key(&self) -> &'static str {
+        "Table"
+    }
+
+    fn label(&self) -> &'static str {
+        "table"
+    }
+
+    fn render(&self, ctx: RenderContext, cx: &App) -> AnyElement {
+        let separator = if ctx.output.contains('\t') { '\t' } else { ',' };
+        let rows = ctx
+            .output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .when(index == 0, |row| row.font_weight(FontWeight::BOLD))
+                    .children(line.split(separator).map(|cell| {
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .overflow_hidden()
+                            .child(cell.trim().trim_matches('"').to_string())
+                    }))
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .p(ctx.padding)
+            .size_full()
+            .text_xs()
+            .bg(ctx.bg)
+            .overflow_scrollbar()
+            .children(rows)
+            .into_any_element()
+    }
+}
+
+/// A horizontal bar per line, lines are `value` or `label value`.
+struct ChartRenderer;
+
+impl ResultRenderer for ChartRenderer {
+    fn key(&self) -> &'static str {
+        "Chart"
+    }
+
+    fn label(&self) -> &'static str {
+        "chart"
+    }
+
+    fn render(&self, ctx: RenderContext, cx: &App) -> AnyElement {
+        let bars = ctx
+            .output
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                let (label, value) = match line.rsplit_once(char::is_whitespace) {
+                    Some((label, value)) => (label.trim().trim_end_matches(':'), value),
+                    None => ("", line),
+                };
+                let value = value.parse::<f64>().ok()?;
+                Some((label.to_string(), value))
+            })
+            .collect::<Vec<_>>();
+        let max = bars
+            .iter()
+            .map(|(_, value)| value.abs())
+            .fold(0.0, f64::max);
+
+        v_flex()
+            .p(ctx.padding)
+            .size_full()
+            .gap_1()
+            .text_xs()
+            .bg(ctx.bg)
+            .overflow_scrollbar()
+            .children(bars.into_iter().map(|(label, value)| {
+                let ratio = if max > 0.0 { value.abs() / max } else { 0.0 };
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .when(!label.is_empty(), |row| {
+                        row.child(div().w(px(80.0)).overflow_hidden().child(label))
+                    })
+                    .child(
+                        div().flex_1().h(px(10.0)).child(
+                            div()
+                                .h_full()
+                                .rounded_sm()
+                                .w(gpui::relative(ratio as f32))
+                                .bg(cx.theme().primary),
+                        ),
+                    )
+                    .child(
+                        div()
+                            .font_family(cx.theme().mono_font_family.clone())
+                            .child(format!("{value}")),
+                    )
+            }))
+            .into_any_element()
+    }
+}
+
+/// Terminal output with ANSI colors (SGR escape codes), other escape codes are dropped.
+struct AnsiRenderer;
+
+impl ResultRenderer for AnsiRenderer {
+    fn key(&self) -> &'static str {
+        "Ansi"
+    }
+
+    fn label(&self) -> &'static str {
+        "ansi"
+    }
+
+    fn text_output(&self) -> bool {
+        true
+    }
+
+    fn render(&self, ctx: RenderContext, cx: &App) -> AnyElement {
+        let (text, highlights) = parse_ansi(ctx.output);
+        text_view(StyledText::new(text).with_highlights(highlights), &ctx, cx)
+    }
+}
+
+const ANSI_COLORS: [u32; 16] = [
+    0x000000, 0xcd3131, 0x0dbc79, 0xe5e510, 0x2472c8, 0xbc3fbc, 0x11a8cd, 0xe5e5e5, 0x666666,
+    0xf14c4c, 0x23d18b, 0xf5f543, 0x3b8eea, 0xd670d6, 0x29b8db, 0xffffff,
+];
+
+/// Colors of the 256 color palette, see https://en.wikipedia.org/wiki/ANSI_escape_code#8-bit
+fn ansi_256(n: u8) -> u32 {
+    match n {
+        0..=15 => ANSI_COLORS[n as usize],
+        16..=231 => {
+            let n = n - 16;
+            let level = |x: u8| if x == 0 { 0 } else { 55 + x as u32 * 40 };
+            (level(n / 36) << 16) | (level(n / 6 % 6) << 8) | level(n % 6)
+        }
+        232..=255 => {
+            let gray = 8 + (n - 232) as u32 * 10;
+            (gray << 16) | (gray << 8) | gray
+        }
+    }
+}
+
+fn parse_ansi(input: &str) -> (String, Vec<(Range<usize>, HighlightStyle)>) {
+    let mut text = String::with_capacity(input.len());
+    let mut highlights = Vec::new();
+    let mut style = HighlightStyle::default();
+    let mut style_start = 0;
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            text.push(c);
+            continue;
+        }
+        if chars.peek() != Some(&'[') {
+            continue;
+        }
+        chars.next();
+
+        let mut params = String::new();
+        let mut command = None;
+        for c in chars.by_ref() {
+            if ('\x40'..='\x7e').contains(&c) {
+                command = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        if command != Some('m') {
+            continue;
+        }
+
+        if text.len() > style_start && style != HighlightStyle::default() {
+            highlights.push((style_start..text.len(), style));
+        }
+        style_start = text.len();
+
+        let codes = params
+            .split(';')
+            .map(|x| x.parse::<u8>().unwrap_or(0))
+            .collect::<Vec<_>>();
+        let mut codes = codes.iter().copied();
+        while let Some(code) = codes.next() {
+            match code {
+                0 => style = HighlightStyle::default(),
+                1 => style.font_weight = Some(FontWeight::BOLD),
+                22 => style.font_weight = None,
+                30..=37 => style.color = Some(rgb(ANSI_COLORS[(code - 30) as usize]).into()),
+                90..=97 => style.color = Some(rgb(ANSI_COLORS[(code - 82) as usize]).into()),
+                39 => style.color = None,
+                40..=47 => {
+                    style.background_color = Some(rgb(ANSI_COLORS[(code - 40) as usize]).into())
+                }
+                100..=107 => {
+                    style.background_color = Some(rgb(ANSI_COLORS[(code - 92) as usize]).into())
+                }
+                49 => style.background_color = None,
+                38 | 48 => {
+                    let color = match codes.next() {
+                        Some(5) => codes.next().map(ansi_256),
+                        Some(2) => match (codes.next(), codes.next(), codes.next()) {
+                            (Some(r), Some(g), Some(b)) => Some(u32::from_be_bytes([0, r, g, b])),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    let color = color.map(|x| rgb(x).into());
+                    if code == 38 {
+                        style.color = color;
+                    } else {
+                        style.background_color = color;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if text.len() > style_start && style != HighlightStyle::default() {
+        highlights.push((style_start..text.len(), style));
+    }
+
+    (text, highlights)
+}
@@ -0,0 +1,425 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Hsla, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    green_500, h_flex,
+    input::{Input, InputState},
+    red_500,
+    switch::Switch,
+    v_flex, yellow_500,
+};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Disks, System};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+/// Samples kept for the sparklines.
+const HISTORY_LEN: usize = 40;
+const MIN_INTERVAL_SECS: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct SystemContent {
+    interval_secs: u32,
+    show_cpu: bool,
+    show_memory: bool,
+    show_disk: bool,
+}
+
+impl Default for SystemContent {
+    fn default() -> Self {
+        Self {
+            interval_secs: 3,
+            show_cpu: true,
+            show_memory: true,
+            show_disk: true,
+        }
+    }
+}
+
+/// Usage in percent, 0..=100.
+#[derive(Debug, Clone, Copy, Default)]
+struct Sample {
+    cpu: f32,
+    memory: f32,
+    disk: f32,
+}
+
+pub struct SystemSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: SystemContent,
+
+    samples: VecDeque<Sample>,
+    sampling: bool,
+
+    interval_input: Entity<InputState>,
+    editing: bool,
+
+    error: Option<String>,
+}
+
+impl SystemSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<SystemContent>(content).unwrap_or_default();
+        let interval_input = cx
+            .new(|cx| InputState::new(window, cx).default_value(content.interval_secs.to_string()));
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            samples: VecDeque::with_capacity(HISTORY_LEN),
+            sampling: false,
+            interval_input,
+            editing: false,
+            error: None,
+        }
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize system sticker: {err}"));
+                return false;
+            }
+        };
+        let title = "System monitor".to_string();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save system sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save system sticker: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    /// Sample in the background for as long as the sticker is alive.
+    fn spawn_for_sampling(&mut self, cx: &mut Context<Self>) {
+        if self.sampling {
+            return;
+        }
+        self.sampling = true;
+
+        cx.spawn(async move |this, cx| {
+            let mut system = System::new();
+            loop {
+                let (sample, back) = cx
+                    .background_executor()
+                    .spawn(async move {
+                        let sample = take_sample(&mut system);
+                        (sample, system)
+                    })
+                    .await;
+                system = back;
+
+                let Ok(interval_secs) = this.update(cx, |this, cx| {
+                    if this.samples.len() >= HISTORY_LEN {
+                        this.samples.pop_front();
+                    }
+                    this.samples.push_back(sample);
+                    cx.notify();
+                    this.content.interval_secs.max(MIN_INTERVAL_SECS)
+                }) else {
+                    break;
+                };
+
+                cx.background_executor()
+                    .timer(Duration::from_secs(interval_secs as u64))
+                    .await;
+            }
+        })
+        .detach();
+    }
+
+    fn apply_form(&mut self, cx: &mut Context<Self>) {
+        let value = self.interval_input.read(cx).value().trim().to_string();
+        match value.parse::<u32>() {
+            Ok(secs) if secs >= MIN_INTERVAL_SECS => {
+                self.content.interval_secs = secs;
+                self.editing = false;
+                self.error = None;
+                self.save_config(cx);
+            }
+            _ => {
+                self.error = Some(format!(
+                    "Interval must be a whole number of seconds, at least {MIN_INTERVAL_SECS}"
+                ));
+            }
+        }
+        cx.notify();
+    }
+
+    fn metric_row(
+        &self,
+        label: &'static str,
+        value: impl Fn(&Sample) -> f32,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let current = self.samples.back().map(&value).unwrap_or(0.0);
+
+        let sparkline = h_flex()
+            .h(px(24.0))
+            .flex_1()
+            .items_end()
+            .gap(px(1.0))
+            .children(self.samples.iter().map(|sample| {
+                let percent = value(sample);
+                div()
+                    .flex_1()
+                    .h(px((24.0 * percent / 100.0).max(1.0)))
+                    .bg(usage_color(percent).opacity(0.7))
+            }));
+
+        v_flex()
+            .gap_1()
+            .child(
+                h_flex().justify_between().text_xs().child(label).child(
+                    div()
+                        .font_family(cx.theme().mono_font_family.clone())
+                        .child(format!("{current:.0}%")),
+                ),
+            )
+            .child(
+                div()
+                    .h(px(4.0))
+                    .w_full()
+                    .rounded_sm()
+                    .bg(cx.theme().muted)
+                    .child(
+                        div()
+                            .h_full()
+                            .rounded_sm()
+                            .w(gpui::relative(current / 100.0))
+                            .bg(usage_color(current)),
+                    ),
+            )
+            .child(sparkline)
+            .into_any_element()
+    }
+
+    fn monitor_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let mut rows = Vec::new();
+        if self.content.show_cpu {
+            rows.push(self.metric_row("CPU", |x| x.cpu, cx));
+        }
+        if self.content.show_memory {
+            rows.push(self.metric_row("Memory", |x| x.memory, cx));
+        }
+        if self.content.show_disk {
+            rows.push(self.metric_row("Disk", |x| x.disk, cx));
+        }
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_2()
+                    .justify_center()
+                    .window_drag_area()
+                    .children(rows),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex().child(
+                        Button::new("edit")
+                            .icon(IconName::Adjustments)
+                            .bg(transparent_white())
+                            .border_0()
+                            .xsmall()
+                            .tooltip("Change metrics and interval")
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.editing = true;
+                                cx.notify();
+                            })),
+                    ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field()
+                    .label("Interval (seconds)")
+                    .child(Input::new(&self.interval_input).small()),
+            )
+            .child(
+                field().label("Metrics").child(
+                    v_flex()
+                        .gap_1()
+                        .child(
+                            Switch::new("show_cpu")
+                                .label("CPU")
+                                .small()
+                                .checked(self.content.show_cpu)
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    this.content.show_cpu = *checked;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Switch::new("show_memory")
+                                .label("Memory")
+                                .small()
+                                .checked(self.content.show_memory)
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    this.content.show_memory = *checked;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Switch::new("show_disk")
+                                .label("Disk")
+                                .small()
+                                .checked(self.content.show_disk)
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    this.content.show_disk = *checked;
+                                    cx.notify();
+                                })),
+                        ),
+                ),
+            )
+            .child(
+                field().child(
+                    Button::new("save")
+                        .label("Save")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.apply_form(cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for SystemSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(160, 100)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(240, 240)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for SystemSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+        self.spawn_for_sampling(cx);
+
+        let mut body = v_flex().size_full().bg(Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            body = body.child(self.monitor_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("system-error", msg.as_str()).small())
+        })
+    }
+}
+
+fn take_sample(system: &mut System) -> Sample {
+    system.refresh_cpu_usage();
+    system.refresh_memory();
+
+    let memory = match system.total_memory() {
+        0 => 0.0,
+        total => system.used_memory() as f32 * 100.0 / total as f32,
+    };
+
+    // All mounted disks together, removable ones included.
+    let disks = Disks::new_with_refreshed_list();
+    let (total, available) = disks.iter().fold((0u64, 0u64), |(total, available), disk| {
+        (
+            total + disk.total_space(),
+            available + disk.available_space(),
+        )
+    });
+    let disk = match total {
+        0 => 0.0,
+        total => (total - available) as f32 * 100.0 / total as f32,
+    };
+
+    Sample {
+        cpu: system.global_cpu_usage(),
+        memory,
+        disk,
+    }
+}
+
+fn usage_color(percent: f32) -> Hsla {
+    if percent >= 90.0 {
+        red_500()
+    } else if percent >= 70.0 {
+        yellow_500()
+    } else {
+        green_500()
+    }
+}
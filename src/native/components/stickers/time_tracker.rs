@@ -0,0 +1,523 @@
+use std::time::Duration;
+
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    green_500, h_flex,
+    input::{Input, InputEvent, InputState},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::command::csv_field;
+use super::save_content;
+use crate::model::focus;
+use crate::model::sticker::StickerColor;
+use crate::model::time_entry::{self, TimeEntry};
+use crate::native::{components::IconName, dialogs, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+use crate::utils::time::{now_unix_millis, secs_to_hms};
+
+/// Projects offered to start again with one click.
+const RECENT_PROJECTS: usize = 6;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RunningEntry {
+    project: String,
+    task: String,
+    started_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct TimeTrackerContent {
+    /// Set while a task is tracked, it becomes a time entry when stopped.
+    running: Option<RunningEntry>,
+    /// Newest first.
+    recent_projects: Vec<String>,
+}
+
+pub struct TimeTrackerSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: TimeTrackerContent,
+    /// Entries of all time trackers which ended today, for the totals.
+    today: Vec<TimeEntry>,
+
+    project: Entity<InputState>,
+    task: Entity<InputState>,
+
+    ticking: bool,
+    error: Option<String>,
+}
+
+impl TimeTrackerSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<TimeTrackerContent>(content).unwrap_or_default();
+
+        let last_project = content.recent_projects.first().cloned().unwrap_or_default();
+        let project = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Project")
+                .default_value(last_project)
+        });
+        let task = cx.new(|cx| InputState::new(window, cx).placeholder("Task"));
+        for input in [&project, &task] {
+            cx.subscribe_in(input, window, |this, _, event: &InputEvent, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.start(window, cx);
+                }
+            })
+            .detach();
+        }
+
+        let mut this = Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            today: Vec::new(),
+            project,
+            task,
+            ticking: false,
+            error: None,
+        };
+        this.spawn_load_today(cx);
+        this
+    }
+
+    fn elapsed_ms(&self) -> i64 {
+        self.content
+            .running
+            .as_ref()
+            .map(|x| (now_unix_millis() - x.started_at_ms).max(0))
+            .unwrap_or(0)
+    }
+
+    /// "Tracking: project", so the running task can be found in the main window.
+    fn title(&self) -> String {
+        match &self.content.running {
+            Some(running) if !running.project.is_empty() => {
+                format!("Tracking: {}", running.project)
+            }
+            Some(_) => "Tracking time".to_string(),
+            None => "Time tracker".to_string(),
+        }
+    }
+
+    fn save_content(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize time tracker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save time tracker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send title changed event for time tracker sticker");
+            }
+
+            if let Err(err) = save_content(&store, &sticker_events_tx, id, json, String::new()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save time tracker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn spawn_load_today(&mut self, cx: &mut Context<Self>) {
+        let store = self.store.clone();
+        cx.spawn(async move |this, cx| {
+            let since = focus::start_of_day(chrono::Local::now(), 0);
+            match store.get_time_entries_since(since).await {
+                Ok(entries) => {
+                    let _ = this.update(cx, |this, cx| {
+                        this.today = entries;
+                        cx.notify();
+                    });
+                }
+                Err(err) => tracing::warn!(error = ?err, "Failed to load time entries"),
+            }
+        })
+        .detach();
+    }
+
+    /// Start tracking the task in the inputs, a running one is stopped first.
+    fn start(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let project = self.project.read(cx).value().trim().to_string();
+        let task = self.task.read(cx).value().trim().to_string();
+        if self.content.running.is_some() {
+            self.stop(cx);
+        }
+
+        if !project.is_empty() {
+            self.content.recent_projects.retain(|x| *x != project);
+            self.content.recent_projects.insert(0, project.clone());
+            self.content.recent_projects.truncate(RECENT_PROJECTS);
+        }
+        self.content.running = Some(RunningEntry {
+            project,
+            task,
+            started_at_ms: now_unix_millis(),
+        });
+        self.task
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        self.error = None;
+        self.save_content(cx);
+        cx.notify();
+    }
+
+    fn start_project(&mut self, project: String, window: &mut Window, cx: &mut Context<Self>) {
+        self.project
+            .update(cx, |input, cx| input.set_value(project, window, cx));
+        self.start(window, cx);
+    }
+
+    fn stop(&mut self, cx: &mut Context<Self>) {
+        let Some(running) = self.content.running.take() else {
+            return;
+        };
+        let entry = TimeEntry {
+            id: 0,
+            sticker_id: Some(self.id),
+            project: running.project,
+            task: running.task,
+            started_at: running.started_at_ms,
+            ended_at: now_unix_millis(),
+        };
+        // A start clicked by mistake is not worth an entry.
+        if entry.ended_at - entry.started_at >= 1000 {
+            self.record_entry(entry, cx);
+        }
+        self.save_content(cx);
+        cx.notify();
+    }
+
+    fn record_entry(&mut self, entry: TimeEntry, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            self.error = Some("The database is read only, tracked time is not kept".to_string());
+            return;
+        }
+
+        let store = self.store.clone();
+        cx.spawn(async move |this, cx| {
+            if let Err(err) = store.insert_time_entry(entry).await {
+                let _ = this.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to record tracked time: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+            let _ = this.update(cx, |this, cx| this.spawn_load_today(cx));
+        })
+        .detach();
+    }
+
+    fn export_csv(&mut self, cx: &mut Context<Self>) {
+        let store = self.store.clone();
+
+        cx.spawn(async move |entity, cx| {
+            let Some(path) = dialogs::save_file(
+                "Export tracked time",
+                "time-entries.csv",
+                &[("CSV", &["csv"])],
+            )
+            .await
+            else {
+                return;
+            };
+
+            let result = match store.get_time_entries_since(0).await {
+                Ok(entries) => write_entries_csv(&path, &entries),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to export tracked time: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Redraw every second while tracking, like the timer sticker.
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        cx.spawn(async move |e, cx| {
+            cx.background_executor().timer(Duration::from_secs(1)).await;
+            let _ = e.update(cx, |this, cx| {
+                this.ticking = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn running_view(&self, running: &RunningEntry, cx: &mut Context<Self>) -> AnyElement {
+        let (h, m, s) = secs_to_hms(self.elapsed_ms() / 1000);
+
+        v_flex()
+            .items_center()
+            .gap_1()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(green_500())
+                    .child(match running.project.as_str() {
+                        "" => "No project".to_string(),
+                        project => project.to_string(),
+                    }),
+            )
+            .when(!running.task.is_empty(), |view| {
+                view.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(running.task.clone()),
+                )
+            })
+            .child(
+                div()
+                    .text_2xl()
+                    .font_bold()
+                    .child(format!("{h}:{m:02}:{s:02}")),
+            )
+            .child(
+                Button::new("stop")
+                    .icon(IconName::Stop)
+                    .label("Stop")
+                    .small()
+                    .on_click(cx.listener(|this, _, _, cx| this.stop(cx))),
+            )
+            .into_any_element()
+    }
+
+    fn start_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_flex()
+            .gap_1()
+            .child(Input::new(&self.project).small())
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(div().flex_1().child(Input::new(&self.task).small()))
+                    .child(
+                        Button::new("start")
+                            .icon(IconName::Play)
+                            .small()
+                            .primary()
+                            .tooltip("Start tracking")
+                            .on_click(cx.listener(|this, _, window, cx| this.start(window, cx))),
+                    ),
+            )
+            .when(!self.content.recent_projects.is_empty(), |view| {
+                view.child(
+                    h_flex().gap_1().flex_wrap().children(
+                        self.content
+                            .recent_projects
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, project)| {
+                                let project = project.clone();
+                                Button::new(("recent-project", ix))
+                                    .label(project.clone())
+                                    .xsmall()
+                                    .tooltip("Start tracking this project")
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.start_project(project.clone(), window, cx)
+                                    }))
+                            }),
+                    ),
+                )
+            })
+            .into_any_element()
+    }
+
+    /// Tracked time per project today, the running task included.
+    fn totals_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        let since = focus::start_of_day(chrono::Local::now(), 0);
+        let mut entries = self.today.clone();
+        if let Some(running) = &self.content.running {
+            entries.push(TimeEntry {
+                id: 0,
+                sticker_id: Some(self.id),
+                project: running.project.clone(),
+                task: running.task.clone(),
+                started_at: running.started_at_ms,
+                ended_at: now_unix_millis(),
+            });
+        }
+        let totals = time_entry::totals_by_project(&entries, since);
+        let total = totals.iter().map(|(_, ms)| ms).sum::<i64>();
+
+        v_flex()
+            .flex_1()
+            .min_h_0()
+            .gap_1()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .text_xs()
+                    .font_semibold()
+                    .child("Today")
+                    .child(time_entry::format_duration(total)),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scrollbar()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .children(totals.into_iter().map(|(project, ms)| {
+                        h_flex()
+                            .justify_between()
+                            .gap_2()
+                            .child(div().truncate().child(match project.as_str() {
+                                "" => "No project".to_string(),
+                                _ => project,
+                            }))
+                            .child(time_entry::format_duration(ms))
+                    })),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for TimeTrackerSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_content(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(200, 200)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(280, 320)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        let running = self.content.running.as_ref()?;
+        let (h, m, s) = secs_to_hms(self.elapsed_ms() / 1000);
+        Some(format!(
+            "tracking {}, {h}:{m:02}:{s:02} elapsed",
+            match running.project.as_str() {
+                "" => "without project",
+                project => project,
+            }
+        ))
+    }
+}
+
+impl Render for TimeTrackerSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        if self.content.running.is_some() {
+            self.spawn_for_tick(cx);
+        }
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .bg(Rgba {
+                a: 0.85,
+                ..self.color.bg()
+            })
+            .child(match &self.content.running {
+                Some(running) => self.running_view(running, cx),
+                None => self.start_view(cx),
+            })
+            .child(self.totals_view(cx))
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex().justify_end().child(
+                        Button::new("export")
+                            .icon(IconName::Download)
+                            .bg(transparent_white())
+                            .border_0()
+                            .tooltip("Export tracked time as CSV")
+                            .on_click(cx.listener(|this, _, _, cx| this.export_csv(cx))),
+                    ),
+                )
+            })
+            .when_some(self.error.as_ref(), |view, err| {
+                view.child(Alert::error("time-tracker-error", err.as_str()).small())
+            })
+    }
+}
+
+fn write_entries_csv(path: &std::path::Path, entries: &[TimeEntry]) -> anyhow::Result<()> {
+    use anyhow::Context as _;
+
+    let format = |ms: i64| {
+        chrono::DateTime::<chrono::Utc>::from_timestamp_millis(ms)
+            .map(|x| {
+                x.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default()
+    };
+    let mut csv = String::from("project,task,started,ended,duration_mins\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{},{:.1}\n",
+            csv_field(&entry.project),
+            csv_field(&entry.task),
+            format(entry.started_at),
+            format(entry.ended_at),
+            (entry.ended_at - entry.started_at).max(0) as f64 / 60_000.0
+        ));
+    }
+
+    std::fs::write(path, csv).context("write csv file")
+}
@@ -1,8 +1,8 @@
 use std::time::Duration;
 
 use gpui::{
-    Animation, AnimationExt, AnyElement, AppContext, Context, Empty, Entity, Rgba, Size, Window,
-    div, prelude::*, px, transparent_white,
+    Animation, AnimationExt, AnyElement, AppContext, Context, Empty, Entity, Hsla, MouseButton,
+    Rgba, Size, Window, div, prelude::*, px, size, transparent_white, white,
 };
 use gpui_component::{
     IndexPath, Sizable, StyledExt,
@@ -10,16 +10,28 @@ use gpui_component::{
     button::Button,
     green_500, h_flex,
     input::{Input, InputState},
+    red_500,
     select::{SearchableVec, Select, SelectState},
-    v_flex,
+    switch::Switch,
+    tooltip::Tooltip,
+    v_flex, yellow_500,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::native::{components::IconName, windows::StickerWindowEvent};
 use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::sound::{self, Sound};
+use crate::native::{components::IconName, windows::StickerWindowEvent};
 use crate::storage::ArcStickerStore;
 
 use super::Sticker;
+use super::save_content;
+
+/// Window size of the compact "pill" layout.
+const PILL_SIZE: Size<i32> = Size {
+    width: 110,
+    height: 36,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 enum TimerState {
@@ -40,6 +52,15 @@ struct TimerContent {
     title: Option<String>,
     duration_secs: i32,
     start_info: Option<TimerStartInfo>,
+    /// Show only the remaining time in a small pill while counting down.
+    #[serde(default)]
+    compact: bool,
+    /// Window size to restore when leaving the compact layout.
+    #[serde(default)]
+    expanded_size: Option<(i32, i32)>,
+    /// Read the title aloud when finished instead of beeping.
+    #[serde(default)]
+    speak: bool,
 }
 
 impl Default for TimerContent {
@@ -48,10 +69,21 @@ impl Default for TimerContent {
             title: None,
             duration_secs: 0,
             start_info: None,
+            compact: false,
+            expanded_size: None,
+            speak: false,
         }
     }
 }
 
+/// When the timer in `content` was last started and the seconds it was set to,
+/// `None` when it never ran.
+pub fn last_started(content: &str) -> Option<(i64, i32)> {
+    let content = serde_json::from_str::<TimerContent>(content).ok()?;
+    let start_info = content.start_info?;
+    Some((start_info.started_at_ms, content.duration_secs))
+}
+
 pub struct TimerSticker {
     id: i64,
     color: StickerColor,
@@ -177,7 +209,9 @@ impl TimerSticker {
                 );
             }
 
-            if let Err(err) = store.update_sticker_content(id, json).await {
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
                 let _ = entity.update(cx, |this, cx| {
                     this.error = Some(format!("Failed to save timer state: {:?}", err));
                     cx.notify();
@@ -230,6 +264,9 @@ impl TimerSticker {
                 remaining_secs: duration_secs,
                 state: TimerState::Running,
             }),
+            compact: false,
+            expanded_size: None,
+            speak: self.timer.speak,
         };
 
         self.save_timer_state(cx);
@@ -274,14 +311,111 @@ impl TimerSticker {
         }
     }
 
-    fn spawn_for_beep(&self, cx: &mut Context<Self>) {
-        cx.spawn(async |this, cx| {
+    fn toggle_compact(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.timer.compact {
+            let (width, height) = self.timer.expanded_size.take().unwrap_or_else(|| {
+                let size = Self::default_window_size();
+                (size.width, size.height)
+            });
+            self.timer.compact = false;
+            window.resize(size(px(width as f32), px(height as f32)));
+        } else {
+            let bounds = window.bounds();
+            self.timer.expanded_size = Some((
+                bounds.size.width.to_f64() as i32,
+                bounds.size.height.to_f64() as i32,
+            ));
+            self.timer.compact = true;
+            window.resize(PILL_SIZE.map(|x| px(x as f32)));
+        }
+
+        self.save_timer_state(cx);
+        cx.notify();
+    }
+
+    fn is_compact(&self) -> bool {
+        self.timer.compact && self.timer.start_info.is_some()
+    }
+
+    fn displayed_remaining_secs(&self) -> i32 {
+        match self.timer.start_info.as_ref().map(|x| &x.state) {
+            Some(TimerState::Running) => effective_remaining_secs(&self.timer),
+            Some(TimerState::Paused) => self
+                .timer
+                .start_info
+                .as_ref()
+                .map(|x| x.remaining_secs)
+                .unwrap_or(0),
+            Some(TimerState::Finished) => 0,
+            None => self.timer.duration_secs,
+        }
+    }
+
+    /// Beep (or speak the title) and grab attention, or only flash and queue a
+    /// notification while the OS is in do not disturb mode.
+    fn on_finished(&self, cx: &mut Context<Self>) {
+        let _ = self
+            .sticker_events_tx
+            .send(StickerWindowEvent::TimerFinished { id: self.id });
+        let title = self.title.read(cx).value().trim().to_string();
+        let speak = self.timer.speak;
+        cx.spawn(async move |this, cx| {
+            let dnd = cx
+                .background_executor()
+                .spawn(async { crate::native::dnd::is_active() })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                if !dnd {
+                    cx.activate(true);
+                }
+                this.spawn_for_beep(dnd || speak, cx);
+            });
+
+            if speak && !dnd {
+                let text = if title.is_empty() {
+                    "Timer finished".to_string()
+                } else {
+                    title.clone()
+                };
+                let spoken = cx
+                    .background_executor()
+                    .spawn(async move { crate::native::speech::speak(&text) })
+                    .await;
+                if let Err(err) = spoken {
+                    tracing::warn!(error = ?err, "Failed to speak timer title, beeping instead");
+                    let _ = cx.update(|cx| sound::play(cx, Sound::Alarm));
+                }
+            }
+
+            if dnd {
+                tracing::info!("Do not disturb is on, timer notification queued");
+                let summary = if title.is_empty() {
+                    "Timer finished".to_string()
+                } else {
+                    title
+                };
+                crate::native::dnd::notify_when_inactive(
+                    cx,
+                    summary,
+                    "Finished while do not disturb was on".to_string(),
+                )
+                .await;
+            }
+        })
+        .detach();
+    }
+
+    fn spawn_for_beep(&self, silent: bool, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
             let start = crate::utils::time::now_unix_millis();
             loop {
                 if crate::utils::time::now_unix_millis() - start < 10000
                     && let Ok(true) = this.read_with(cx, |this, _| this.is_just_finished)
                 {
-                    play_beep();
+                    if !silent {
+                        let _ = cx.update(|cx| sound::play(cx, Sound::Alarm));
+                    }
                     cx.background_executor()
                         .timer(Duration::from_millis(500))
                         .await;
@@ -313,7 +447,6 @@ impl TimerSticker {
                     if remaining_secs <= 0 {
                         is_just_finished = true;
                         start_info.state = TimerState::Finished;
-                        cx.activate(true);
                     }
 
                     cx.notify();
@@ -321,7 +454,7 @@ impl TimerSticker {
 
                 this.is_just_finished = is_just_finished;
                 if is_just_finished {
-                    this.spawn_for_beep(cx);
+                    this.on_finished(cx);
                 }
 
                 if remaining_secs <= 0
@@ -356,6 +489,16 @@ impl TimerSticker {
                     .child(":")
                     .child(Select::new(&self.seconds).small()),
             )
+            .child(
+                Switch::new("timer-speak")
+                    .label("Speak the title when done")
+                    .small()
+                    .checked(self.timer.speak)
+                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                        this.timer.speak = *checked;
+                        cx.notify();
+                    })),
+            )
             .child(
                 Button::new("timer-start")
                     .icon(IconName::Play)
@@ -367,16 +510,11 @@ impl TimerSticker {
     }
 
     fn countdown_view(&mut self, cx: &mut Context<Self>, window: &mut Window) -> AnyElement {
+        let remaining_secs = self.displayed_remaining_secs();
         let Some(start_info) = &self.timer.start_info else {
             return Empty.into_any_element();
         };
 
-        let remaining_secs = match start_info.state {
-            TimerState::Running => effective_remaining_secs(&self.timer),
-            TimerState::Paused => start_info.remaining_secs,
-            TimerState::Finished => 0,
-        };
-
         let title = self.title.read(cx).value();
 
         let (h, m, s) = crate::utils::time::secs_to_hms(remaining_secs as i64);
@@ -429,13 +567,18 @@ impl TimerSticker {
         view = match start_info.state {
             TimerState::Running => view.when(window.is_window_hovered(), |view| {
                 view.child(
-                    Button::new("pause")
-                        .icon(IconName::Pause)
-                        .bg(transparent_white())
-                        .border_0()
-                        .on_click(
-                            cx.listener(|s, _, _, cx| s.change_state(cx, TimerState::Paused)),
-                        ),
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("pause")
+                                .icon(IconName::Pause)
+                                .bg(transparent_white())
+                                .border_0()
+                                .on_click(cx.listener(|s, _, _, cx| {
+                                    s.change_state(cx, TimerState::Paused)
+                                })),
+                        )
+                        .child(compact_button(cx)),
                 )
             }),
             TimerState::Paused => view.child(
@@ -460,7 +603,8 @@ impl TimerSticker {
                                 this.is_just_finished = false;
                                 this.change_state(cx, TimerState::Running)
                             })),
-                    ),
+                    )
+                    .child(compact_button(cx)),
             ),
             TimerState::Finished => view.when(window.is_window_hovered(), |view| {
                 view.child(
@@ -484,13 +628,60 @@ impl TimerSticker {
                                 .on_click(cx.listener(|this, _, _, cx| {
                                     this.restart(cx);
                                 })),
-                        ),
+                        )
+                        .child(compact_button(cx)),
                 )
             }),
         };
 
         return view.into_any_element();
     }
+
+    fn pill_view(&mut self, cx: &mut Context<Self>) -> AnyElement {
+        let remaining_secs = self.displayed_remaining_secs();
+        let label = format!("{:02}:{:02}", remaining_secs / 60, remaining_secs % 60);
+        let color = urgency_color(remaining_secs, self.timer.duration_secs);
+        let title = self.title.read(cx).value().to_string();
+
+        let pill = div()
+            .id("timer-pill")
+            .size_full()
+            .flex()
+            .items_center()
+            .justify_center()
+            .rounded_full()
+            .bg(color.opacity(0.85))
+            .text_color(white())
+            .font_bold()
+            .window_drag_area()
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(|this, _, window, cx| {
+                    this.is_just_finished = false;
+                    this.toggle_compact(window, cx);
+                }),
+            )
+            .tooltip(move |window, cx| {
+                let text = if title.is_empty() {
+                    "Right click to expand".to_string()
+                } else {
+                    format!("{title} (right click to expand)")
+                };
+                Tooltip::new(text).build(window, cx)
+            })
+            .child(label);
+
+        if self.is_just_finished {
+            pill.with_animation(
+                "pill-indicator",
+                Animation::new(Duration::from_millis(800)).repeat(),
+                |v, x| v.opacity(0.4 + 0.6 * x),
+            )
+            .into_any_element()
+        } else {
+            pill.into_any_element()
+        }
+    }
 }
 
 impl Sticker for TimerSticker {
@@ -499,7 +690,7 @@ impl Sticker for TimerSticker {
     }
 
     fn min_window_size() -> gpui::Size<i32> {
-        Size::new(200, 100)
+        PILL_SIZE
     }
 
     fn default_window_size() -> gpui::Size<i32> {
@@ -513,10 +704,31 @@ impl Sticker for TimerSticker {
     fn disable_color_picker(&self) -> bool {
         self.timer.start_info.is_some()
     }
+
+    fn hide_window_chrome(&self) -> bool {
+        self.is_compact()
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        let state = match self.timer.start_info.as_ref()?.state {
+            TimerState::Running => "running",
+            TimerState::Paused => "paused",
+            TimerState::Finished => return Some("finished".to_string()),
+        };
+        let (h, m, s) = crate::utils::time::secs_to_hms(self.displayed_remaining_secs() as i64);
+        Some(format!("{state}, {h:02}:{m:02}:{s:02} remaining"))
+    }
 }
 
 impl Render for TimerSticker {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.is_compact() {
+            if let Some(TimerState::Running) = self.timer.start_info.as_ref().map(|x| &x.state) {
+                self.spawn_for_timer(cx);
+            }
+            return self.pill_view(cx);
+        }
+
         let mut body = v_flex().size_full().bg(Rgba {
             a: 0.85,
             ..self.color.bg()
@@ -539,6 +751,32 @@ impl Render for TimerSticker {
     }
 }
 
+fn compact_button(cx: &mut Context<TimerSticker>) -> Button {
+    Button::new("compact")
+        .icon(IconName::Minimize)
+        .bg(transparent_white())
+        .border_0()
+        .tooltip("Compact")
+        .on_click(cx.listener(|this, _, window, cx| this.toggle_compact(window, cx)))
+}
+
+/// Green while plenty of time is left, then yellow and finally red.
+fn urgency_color(remaining_secs: i32, duration_secs: i32) -> Hsla {
+    let ratio = if duration_secs > 0 {
+        remaining_secs as f32 / duration_secs as f32
+    } else {
+        0.0
+    };
+
+    if ratio > 0.5 {
+        green_500()
+    } else if ratio > 0.2 {
+        yellow_500()
+    } else {
+        red_500()
+    }
+}
+
 fn parse_content(content: &str) -> TimerContent {
     let trimmed = content.trim();
     if trimmed.is_empty() {
@@ -558,19 +796,3 @@ fn effective_remaining_secs(timer: &TimerContent) -> i32 {
         timer.duration_secs.max(0)
     }
 }
-
-fn play_beep() {
-    #[cfg(windows)]
-    unsafe {
-        // Beep(frequency_hz, duration_ms)
-        let _ = windows_sys::Win32::System::Diagnostics::Debug::Beep(880, 200);
-    }
-
-    #[cfg(not(windows))]
-    {
-        // Best-effort fallback: terminal bell.
-        use std::io::Write;
-        let _ = std::io::stdout().write_all(b"\x07");
-        let _ = std::io::stdout().flush();
-    }
-}
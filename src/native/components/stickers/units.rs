@@ -0,0 +1,587 @@
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    menu::{DropdownMenu, PopupMenuItem},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+/// `value * factor + offset` is the value in the base unit of the category.
+struct Unit {
+    key: &'static str,
+    name: &'static str,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(key: &'static str, name: &'static str, factor: f64) -> Unit {
+    Unit {
+        key,
+        name,
+        factor,
+        offset: 0.0,
+    }
+}
+
+/// Base unit: meter.
+const LENGTH_UNITS: &[Unit] = &[
+    unit("mm", "millimeter", 0.001),
+    unit("cm", "centimeter", 0.01),
+    unit("m", "meter", 1.0),
+    unit("km", "kilometer", 1000.0),
+    unit("in", "inch", 0.0254),
+    unit("ft", "foot", 0.3048),
+    unit("yd", "yard", 0.9144),
+    unit("mi", "mile", 1609.344),
+];
+
+/// Base unit: kilogram.
+const WEIGHT_UNITS: &[Unit] = &[
+    unit("mg", "milligram", 0.000_001),
+    unit("g", "gram", 0.001),
+    unit("kg", "kilogram", 1.0),
+    unit("t", "tonne", 1000.0),
+    unit("oz", "ounce", 0.028_349_523_125),
+    unit("lb", "pound", 0.453_592_37),
+    unit("st", "stone", 6.350_293_18),
+];
+
+/// Base unit: kelvin.
+const TEMPERATURE_UNITS: &[Unit] = &[
+    Unit {
+        key: "°C",
+        name: "celsius",
+        factor: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        key: "°F",
+        name: "fahrenheit",
+        factor: 5.0 / 9.0,
+        offset: 273.15 - 32.0 * 5.0 / 9.0,
+    },
+    unit("K", "kelvin", 1.0),
+];
+
+/// Base unit: byte.
+const DATA_UNITS: &[Unit] = &[
+    unit("bit", "bit", 0.125),
+    unit("B", "byte", 1.0),
+    unit("KB", "kilobyte", 1e3),
+    unit("MB", "megabyte", 1e6),
+    unit("GB", "gigabyte", 1e9),
+    unit("TB", "terabyte", 1e12),
+    unit("KiB", "kibibyte", 1024.0),
+    unit("MiB", "mebibyte", 1024.0 * 1024.0),
+    unit("GiB", "gibibyte", 1024.0 * 1024.0 * 1024.0),
+    unit("TiB", "tebibyte", 1024.0 * 1024.0 * 1024.0 * 1024.0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum UnitCategory {
+    Length,
+    Weight,
+    Temperature,
+    Data,
+}
+
+impl UnitCategory {
+    const ALL: [Self; 4] = [Self::Length, Self::Weight, Self::Temperature, Self::Data];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Length => "length",
+            Self::Weight => "weight",
+            Self::Temperature => "temp",
+            Self::Data => "data",
+        }
+    }
+
+    fn units(&self) -> &'static [Unit] {
+        match self {
+            Self::Length => LENGTH_UNITS,
+            Self::Weight => WEIGHT_UNITS,
+            Self::Temperature => TEMPERATURE_UNITS,
+            Self::Data => DATA_UNITS,
+        }
+    }
+
+    /// The pair a category starts with, e.g. km → mi.
+    fn default_units(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Length => ("km", "mi"),
+            Self::Weight => ("kg", "lb"),
+            Self::Temperature => ("°C", "°F"),
+            Self::Data => ("GB", "GiB"),
+        }
+    }
+
+    fn unit(&self, key: &str) -> &'static Unit {
+        let units = self.units();
+        units.iter().find(|x| x.key == key).unwrap_or(&units[0])
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct UnitsContent {
+    category: UnitCategory,
+    from: String,
+    to: String,
+    /// The value in `from` units as typed, the other side is computed from it.
+    value: String,
+}
+
+impl Default for UnitsContent {
+    fn default() -> Self {
+        let category = UnitCategory::Length;
+        let (from, to) = category.default_units();
+        Self {
+            category,
+            from: from.to_string(),
+            to: to.to_string(),
+            value: "1".to_string(),
+        }
+    }
+}
+
+fn parse_value(text: &str) -> Option<f64> {
+    let text = text.replace([',', '_', ' '], "");
+    text.parse::<f64>().ok().filter(|x| x.is_finite())
+}
+
+fn convert(value: f64, from: &Unit, to: &Unit) -> f64 {
+    (value * from.factor + from.offset - to.offset) / to.factor
+}
+
+/// Up to 6 decimals without trailing zeros, tiny values in scientific notation.
+fn format_value(value: f64) -> String {
+    if value != 0.0 && value.abs() < 0.000_001 {
+        return format!("{value:e}");
+    }
+    let text = format!("{value:.6}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Side {
+    From,
+    To,
+}
+
+pub struct UnitsSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: UnitsContent,
+
+    from_input: Entity<InputState>,
+    to_input: Entity<InputState>,
+    /// Text last filled into an input by converting the other one, its change event
+    /// must not be converted back.
+    echo: Option<(Side, String)>,
+
+    error: Option<String>,
+}
+
+impl UnitsSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let mut content = serde_json::from_str::<UnitsContent>(content).unwrap_or_default();
+        // Keep the pair valid for the category, e.g. after a unit was renamed.
+        content.from = content.category.unit(&content.from).key.to_string();
+        content.to = content.category.unit(&content.to).key.to_string();
+
+        let to_value = Self::converted(&content, &content.value, Side::From).unwrap_or_default();
+        let from_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.value.clone())
+                .placeholder("0")
+        });
+        let to_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(to_value)
+                .placeholder("0")
+        });
+
+        for (input, side) in [(&from_input, Side::From), (&to_input, Side::To)] {
+            cx.subscribe_in(
+                input,
+                window,
+                move |this, _, event: &InputEvent, window, cx| match event {
+                    InputEvent::Change => {
+                        this.on_change(side, window, cx);
+                    }
+                    InputEvent::PressEnter { .. } | InputEvent::Blur => {
+                        this.save_config(cx);
+                    }
+                    _ => {}
+                },
+            )
+            .detach();
+        }
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            content,
+            from_input,
+            to_input,
+            echo: None,
+            error: None,
+        }
+    }
+
+    /// "1 km = 0.621371 mi", so the conversion shows up in the main window.
+    fn title(&self) -> String {
+        let to_value =
+            Self::converted(&self.content, &self.content.value, Side::From).unwrap_or_default();
+        format!(
+            "{} {} = {} {}",
+            self.content.value.trim(),
+            self.content.from,
+            to_value,
+            self.content.to
+        )
+    }
+
+    /// The value typed on `side` converted to the other side, `None` when it is no number.
+    fn converted(content: &UnitsContent, text: &str, side: Side) -> Option<String> {
+        let value = parse_value(text)?;
+        let from = content.category.unit(&content.from);
+        let to = content.category.unit(&content.to);
+        let value = match side {
+            Side::From => convert(value, from, to),
+            Side::To => convert(value, to, from),
+        };
+        Some(format_value(value))
+    }
+
+    fn on_change(&mut self, side: Side, window: &mut Window, cx: &mut Context<Self>) {
+        let input = match side {
+            Side::From => &self.from_input,
+            Side::To => &self.to_input,
+        };
+        let text = input.read(cx).value().to_string();
+        if self
+            .echo
+            .take_if(|(echo_side, echo)| *echo_side == side && *echo == text)
+            .is_some()
+        {
+            return;
+        }
+        self.sync_from(side, window, cx);
+    }
+
+    /// Fill `side` without converting it back to the other side.
+    fn write(&mut self, side: Side, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        let input = match side {
+            Side::From => self.from_input.clone(),
+            Side::To => self.to_input.clone(),
+        };
+        if input.read(cx).value() == text.as_str() {
+            return;
+        }
+        self.echo = Some((side, text.clone()));
+        input.update(cx, |input, cx| input.set_value(text, window, cx));
+    }
+
+    /// Convert what was typed on `side` into the other input.
+    fn sync_from(&mut self, side: Side, window: &mut Window, cx: &mut Context<Self>) {
+        let (source, target) = match side {
+            Side::From => (&self.from_input, Side::To),
+            Side::To => (&self.to_input, Side::From),
+        };
+        let text = source.read(cx).value().trim().to_string();
+        let converted = if text.is_empty() {
+            Some(String::new())
+        } else {
+            Self::converted(&self.content, &text, side)
+        };
+
+        if let Some(converted) = converted {
+            self.write(target, converted.clone(), window, cx);
+            self.content.value = match side {
+                Side::From => text,
+                Side::To => converted,
+            };
+        }
+        cx.notify();
+    }
+
+    fn set_category(
+        &mut self,
+        category: UnitCategory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.content.category == category {
+            return;
+        }
+        let (from, to) = category.default_units();
+        self.content.category = category;
+        self.content.from = from.to_string();
+        self.content.to = to.to_string();
+        self.sync_from(Side::From, window, cx);
+        self.save_config(cx);
+    }
+
+    fn set_unit(&mut self, side: Side, key: &str, window: &mut Window, cx: &mut Context<Self>) {
+        match side {
+            Side::From => self.content.from = key.to_string(),
+            Side::To => self.content.to = key.to_string(),
+        }
+        // The typed value stays, the other side follows the new unit.
+        self.sync_from(Side::From, window, cx);
+        self.save_config(cx);
+    }
+
+    fn swap(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        std::mem::swap(&mut self.content.from, &mut self.content.to);
+        let to_value = self.to_input.read(cx).value().to_string();
+        self.write(Side::From, to_value, window, cx);
+        self.sync_from(Side::From, window, cx);
+        self.save_config(cx);
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize units sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save units sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save units sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn unit_button(&self, side: Side, cx: &mut Context<Self>) -> AnyElement {
+        let entity = cx.entity();
+        let category = self.content.category;
+        let (id, current) = match side {
+            Side::From => ("from_unit", self.content.from.clone()),
+            Side::To => ("to_unit", self.content.to.clone()),
+        };
+        Button::new(id)
+            .label(current.clone())
+            .small()
+            .dropdown_menu(move |menu, window, _| {
+                category.units().iter().fold(menu, |menu, unit| {
+                    let key = unit.key;
+                    menu.item(
+                        PopupMenuItem::new(format!("{} ({})", unit.key, unit.name))
+                            .checked(unit.key == current)
+                            .on_click(window.listener_for(&entity, move |this, _, window, cx| {
+                                this.set_unit(side, key, window, cx);
+                            })),
+                    )
+                })
+            })
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for UnitsSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(200, 140)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(280, 170)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for UnitsSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        let current = self.content.category;
+        let categories = UnitCategory::ALL.map(|category| {
+            Button::new(category.label())
+                .label(category.label())
+                .xsmall()
+                .when(category == current, |button| button.primary())
+                .on_click(
+                    cx.listener(move |this, _, window, cx| this.set_category(category, window, cx)),
+                )
+        });
+
+        let body = v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .bg(Rgba {
+                a: 0.85,
+                ..self.color.bg()
+            })
+            .child(h_flex().gap_1().children(categories))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Input::new(&self.from_input).small().flex_1())
+                    .child(self.unit_button(Side::From, cx)),
+            )
+            .child(
+                h_flex().justify_center().child(
+                    Button::new("swap")
+                        .icon(IconName::ArrowRightLeft)
+                        .bg(transparent_white())
+                        .border_0()
+                        .xsmall()
+                        .tooltip("Swap units")
+                        .on_click(cx.listener(|this, _, window, cx| this.swap(window, cx))),
+                ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Input::new(&self.to_input).small().flex_1())
+                    .child(self.unit_button(Side::To, cx)),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .window_drag_area()
+                    .child("type on either side"),
+            );
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("units-error", msg.as_str()).small())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converted(category: UnitCategory, value: f64, from: &str, to: &str) -> String {
+        format_value(convert(value, category.unit(from), category.unit(to)))
+    }
+
+    #[test]
+    fn conversions() {
+        use UnitCategory::*;
+
+        assert_eq!(converted(Length, 1.0, "km", "mi"), "0.621371");
+        assert_eq!(converted(Length, 12.0, "in", "ft"), "1");
+        assert_eq!(converted(Weight, 1.0, "st", "lb"), "14");
+        assert_eq!(converted(Weight, 16.0, "oz", "lb"), "1");
+        assert_eq!(converted(Data, 1.0, "GB", "GiB"), "0.931323");
+        assert_eq!(converted(Data, 1.0, "B", "bit"), "8");
+    }
+
+    #[test]
+    fn temperatures() {
+        use UnitCategory::Temperature;
+
+        assert_eq!(converted(Temperature, 100.0, "°C", "°F"), "212");
+        assert_eq!(converted(Temperature, -40.0, "°F", "°C"), "-40");
+        assert_eq!(converted(Temperature, 0.0, "K", "°C"), "-273.15");
+        assert_eq!(converted(Temperature, 32.0, "°F", "K"), "273.15");
+    }
+
+    #[test]
+    fn unit_tables() {
+        for category in UnitCategory::ALL {
+            let units = category.units();
+            let (from, to) = category.default_units();
+            assert!(units.iter().any(|x| x.key == from), "{from}");
+            assert!(units.iter().any(|x| x.key == to), "{to}");
+            for (ix, unit) in units.iter().enumerate() {
+                assert!(
+                    !units[ix + 1..].iter().any(|x| x.key == unit.key),
+                    "{}",
+                    unit.key
+                );
+            }
+        }
+        // Unknown keys, e.g. of a newer version, fall back to the first unit.
+        assert_eq!(UnitCategory::Length.unit("parsec").key, "mm");
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(parse_value("1,234.5"), Some(1234.5));
+        assert_eq!(parse_value(" 1_000 "), Some(1000.0));
+        assert_eq!(parse_value("-2e3"), Some(-2000.0));
+        assert_eq!(parse_value("inf"), None);
+        assert_eq!(parse_value("abc"), None);
+
+        assert_eq!(format_value(0.0), "0");
+        assert_eq!(format_value(-0.000_000_1), "-1e-7");
+        assert_eq!(format_value(-0.0), "0");
+        assert_eq!(format_value(2.5), "2.5");
+        assert_eq!(format_value(1.0 / 3.0), "0.333333");
+    }
+}
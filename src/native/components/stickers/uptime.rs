@@ -0,0 +1,704 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use async_compat::CompatExt;
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    green_500, h_flex,
+    input::{Input, InputState},
+    red_500, v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::cron::{self, CronTarget};
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::decorations::WindowDragArea;
+use crate::native::probe::ProbeTarget;
+use crate::native::sound::{self, Sound};
+use crate::native::top_most::set_window_top_most;
+use crate::native::{components::IconName, windows::StickerWindowEvent};
+use crate::storage::ArcStickerStore;
+
+/// Checks kept for the sparkline and the uptime.
+const HISTORY_LEN: usize = 60;
+const TICK_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct UptimeContent {
+    /// A URL, `host:port` or a host to ping, see [`ProbeTarget::parse`].
+    target: String,
+    /// Cron expression with seconds, checked on every run.
+    cron: String,
+    /// Consecutive failures before grabbing attention, 0 never does.
+    alert_after: u32,
+    /// Set while monitoring, so it starts again with the sticker.
+    running: bool,
+    history: Vec<Check>,
+}
+
+impl Default for UptimeContent {
+    fn default() -> Self {
+        Self {
+            target: String::new(),
+            cron: "*/30 * * * * *".to_string(),
+            alert_after: 3,
+            running: false,
+            history: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Check {
+    at: i64,
+    latency_ms: Option<u64>,
+    error: Option<String>,
+}
+
+impl Check {
+    fn is_up(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+pub struct UptimeSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    content: UptimeContent,
+
+    target_input: Entity<InputState>,
+    cron_input: Entity<InputState>,
+    alert_after_input: Entity<InputState>,
+    editing: bool,
+
+    checking: bool,
+    schedule_cancel: Option<Arc<AtomicBool>>,
+    next_scheduled_at: Option<String>,
+
+    /// Attention was grabbed for the current run of failures.
+    alerting: bool,
+    ticking: bool,
+    flash_on: bool,
+    /// Raise the window on the next render, it needs the window.
+    raise_window: bool,
+
+    error: Option<String>,
+}
+
+impl UptimeSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<UptimeContent>(content).unwrap_or_default();
+
+        let target_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.target.clone())
+                .placeholder("https://example.com/health, example.com:22 or 192.168.1.1")
+        });
+        let cron_input =
+            cx.new(|cx| InputState::new(window, cx).default_value(content.cron.clone()));
+        let alert_after_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.alert_after.to_string())
+                .placeholder("0 never alerts")
+        });
+
+        let root_entity = cx.entity();
+        window
+            .spawn(cx, async move |cx| {
+                let _ = cx.update_window_entity(&root_entity, |this, window, cx| {
+                    if this.content.running {
+                        this.start(window, cx);
+                    }
+                });
+            })
+            .detach();
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            editing: content.target.is_empty(),
+            content,
+            target_input,
+            cron_input,
+            alert_after_input,
+            checking: false,
+            schedule_cancel: None,
+            next_scheduled_at: None,
+            alerting: false,
+            ticking: false,
+            flash_on: false,
+            raise_window: false,
+            error: None,
+        }
+    }
+
+    /// "example.com · up 42 ms", so the state shows up in the main window.
+    fn title(&self) -> String {
+        let target = self.content.target.trim();
+        if target.is_empty() {
+            return String::new();
+        }
+        match self.content.history.last() {
+            Some(Check {
+                latency_ms: Some(ms),
+                error: None,
+                ..
+            }) => format!("{target} · up {ms} ms"),
+            Some(Check { error: Some(_), .. }) => format!("{target} · down"),
+            _ => target.to_string(),
+        }
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let json = match serde_json::to_string(&self.content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize uptime sticker: {err}"));
+                return false;
+            }
+        };
+        let title = self.title();
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save uptime sticker title: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+
+            if let Err(err) = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+            {
+                tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save uptime sticker: {err:#}"));
+                    cx.notify();
+                });
+                return;
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn is_schedule_active(&self) -> bool {
+        self.schedule_cancel.is_some()
+    }
+
+    /// Check now and then on every run of the schedule.
+    fn start(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.stop_schedule();
+
+        let schedule = match cron::parse(&self.content.cron) {
+            Ok(schedule) => schedule,
+            Err(err) => {
+                self.error = Some(err);
+                cx.notify();
+                return;
+            }
+        };
+
+        self.content.running = true;
+        self.error = None;
+        self.check(window, cx);
+        self.schedule_cancel = Some(cron::spawn(schedule, window, cx));
+        cx.notify();
+    }
+
+    fn stop_schedule(&mut self) {
+        if let Some(cancel) = self.schedule_cancel.take() {
+            cancel.store(true, Ordering::SeqCst);
+        }
+        self.next_scheduled_at = None;
+    }
+
+    fn stop(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.stop_schedule();
+        self.content.running = false;
+        self.clear_alert(window);
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    fn check(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.checking {
+            return;
+        }
+        let target = match ProbeTarget::parse(&self.content.target) {
+            Ok(target) => target,
+            Err(err) => {
+                self.error = Some(err);
+                cx.notify();
+                return;
+            }
+        };
+
+        self.checking = true;
+        cx.notify();
+
+        let entity = cx.entity();
+        window
+            .spawn(cx, async move |cx| {
+                // The ping and TCP checks block, reqwest needs a tokio runtime.
+                let result = cx
+                    .background_executor()
+                    .spawn(async move { target.check().compat().await })
+                    .await;
+
+                let _ = cx.update_window_entity(&entity, |this, window, cx| {
+                    this.checking = false;
+                    let check = match result {
+                        Ok(latency_ms) => Check {
+                            at: crate::utils::time::now_unix_millis(),
+                            latency_ms: Some(latency_ms),
+                            error: None,
+                        },
+                        Err(err) => {
+                            tracing::debug!(id = this.id, error = ?err, "Uptime check failed");
+                            Check {
+                                at: crate::utils::time::now_unix_millis(),
+                                latency_ms: None,
+                                error: Some(format!("{err:#}")),
+                            }
+                        }
+                    };
+                    this.record(check, window, cx);
+                });
+            })
+            .detach();
+    }
+
+    fn record(&mut self, check: Check, window: &mut Window, cx: &mut Context<Self>) {
+        let up = check.is_up();
+        self.content.history.push(check);
+        let overflow = self.content.history.len().saturating_sub(HISTORY_LEN);
+        self.content.history.drain(..overflow);
+
+        if up {
+            self.clear_alert(window);
+        } else if !self.alerting
+            && self.content.alert_after > 0
+            && self.failures() >= self.content.alert_after as usize
+        {
+            self.alert(cx);
+        }
+
+        self.save_config(cx);
+        cx.notify();
+    }
+
+    /// Consecutive failed checks up to the last one.
+    fn failures(&self) -> usize {
+        self.content
+            .history
+            .iter()
+            .rev()
+            .take_while(|x| !x.is_up())
+            .count()
+    }
+
+    fn uptime_percent(&self) -> Option<f32> {
+        let total = self.content.history.len();
+        let up = self.content.history.iter().filter(|x| x.is_up()).count();
+        (total > 0).then(|| up as f32 * 100.0 / total as f32)
+    }
+
+    /// Bring the window to the front and sound the alarm once per run of failures,
+    /// or only flash and queue a notification while the OS is in do not disturb mode.
+    fn alert(&mut self, cx: &mut Context<Self>) {
+        self.alerting = true;
+        tracing::info!(id = self.id, target = %self.content.target, "Uptime target is down");
+
+        let summary = self.title();
+        let body = format!("{} failed checks in a row", self.failures());
+        cx.spawn(async move |this, cx| {
+            let dnd = cx
+                .background_executor()
+                .spawn(async { crate::native::dnd::is_active() })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                if !dnd {
+                    cx.activate(true);
+                    this.raise_window = true;
+                }
+                cx.notify();
+            });
+
+            if dnd {
+                tracing::info!("Do not disturb is on, uptime notification queued");
+                crate::native::dnd::notify_when_inactive(cx, summary, body).await;
+                return;
+            }
+
+            let _ = cx.update(|cx| sound::play(cx, Sound::Alarm));
+        })
+        .detach();
+    }
+
+    fn clear_alert(&mut self, window: &mut Window) {
+        if self.alerting {
+            self.alerting = false;
+            set_window_top_most(window, false);
+        }
+    }
+
+    /// Redraw for the flashing while alerting.
+    fn spawn_for_tick(&mut self, cx: &mut Context<Self>) {
+        if self.ticking {
+            return;
+        }
+        self.ticking = true;
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(TICK_INTERVAL).await;
+            let _ = this.update(cx, |this, cx| {
+                this.ticking = false;
+                this.flash_on = !this.flash_on;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn edit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let content = &self.content;
+        let (target, cron, alert_after) = (
+            content.target.clone(),
+            content.cron.clone(),
+            content.alert_after.to_string(),
+        );
+        self.target_input
+            .update(cx, |input, cx| input.set_value(target, window, cx));
+        self.cron_input
+            .update(cx, |input, cx| input.set_value(cron, window, cx));
+        self.alert_after_input
+            .update(cx, |input, cx| input.set_value(alert_after, window, cx));
+        self.editing = true;
+        cx.notify();
+    }
+
+    fn apply_form(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let target = self.target_input.read(cx).value().trim().to_string();
+        let cron = self.cron_input.read(cx).value().trim().to_string();
+        let alert_after = self.alert_after_input.read(cx).value().trim().to_string();
+
+        if let Err(err) = ProbeTarget::parse(&target) {
+            self.error = Some(err);
+            cx.notify();
+            return;
+        }
+        if let Err(err) = cron::parse(&cron) {
+            self.error = Some(err);
+            cx.notify();
+            return;
+        }
+        let Ok(alert_after) = alert_after.parse::<u32>() else {
+            self.error = Some("Alert after must be a number of checks".to_string());
+            cx.notify();
+            return;
+        };
+
+        // Another target has another history.
+        if target != self.content.target {
+            self.content.history.clear();
+        }
+        self.content.target = target;
+        self.content.cron = cron;
+        self.content.alert_after = alert_after;
+        self.editing = false;
+        self.clear_alert(window);
+        self.start(window, cx);
+        self.save_config(cx);
+    }
+
+    fn sparkline(&self) -> AnyElement {
+        let max = self
+            .content
+            .history
+            .iter()
+            .filter_map(|x| x.latency_ms)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        h_flex()
+            .h(px(32.0))
+            .w_full()
+            .items_end()
+            .gap(px(1.0))
+            .children(self.content.history.iter().map(|check| {
+                let (height, color) = match check.latency_ms {
+                    Some(ms) if check.is_up() => {
+                        ((32.0 * ms as f32 / max as f32).max(2.0), green_500())
+                    }
+                    _ => (32.0, red_500()),
+                };
+                div().flex_1().h(px(height)).bg(color.opacity(0.7))
+            }))
+            .into_any_element()
+    }
+
+    fn status_view(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let last = self.content.history.last();
+        let (status_color, status) = match last {
+            Some(check) if check.is_up() => (
+                green_500(),
+                match check.latency_ms {
+                    Some(ms) => format!("up · {ms} ms"),
+                    None => "up".to_string(),
+                },
+            ),
+            Some(_) => (red_500(), format!("down · {} failed", self.failures())),
+            None => (cx.theme().muted_foreground, "not checked yet".to_string()),
+        };
+
+        let footer = match (self.uptime_percent(), &self.next_scheduled_at) {
+            (Some(percent), Some(next)) => format!("{percent:.0}% up · next {next}"),
+            (Some(percent), None) => format!("{percent:.0}% up"),
+            (None, Some(next)) => format!("next {next}"),
+            (None, None) => "stopped".to_string(),
+        };
+
+        let error = last.and_then(|x| x.error.clone());
+        let running = self.is_schedule_active();
+
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_2()
+                    .justify_center()
+                    .window_drag_area()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(div().size(px(10.0)).rounded_full().bg(status_color))
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_sm()
+                                    .truncate()
+                                    .child(self.content.target.clone()),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_family(cx.theme().mono_font_family.clone())
+                            .text_color(status_color)
+                            .child(status),
+                    )
+                    .when_some(error, |view, error| {
+                        view.child(div().text_xs().opacity(0.75).child(error))
+                    })
+                    .child(self.sparkline())
+                    .child(div().text_xs().opacity(0.75).child(footer)),
+            )
+            .when(window.is_window_hovered(), |view| {
+                view.child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("edit")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip("Change the target and schedule")
+                                .on_click(cx.listener(|this, _, window, cx| this.edit(window, cx))),
+                        )
+                        .child(
+                            Button::new("check")
+                                .icon(IconName::Refresh)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .loading(self.checking)
+                                .tooltip("Check now")
+                                .on_click(
+                                    cx.listener(|this, _, window, cx| this.check(window, cx)),
+                                ),
+                        )
+                        .child(
+                            Button::new("toggle")
+                                .icon(if running {
+                                    IconName::Stop
+                                } else {
+                                    IconName::Play
+                                })
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .tooltip(if running {
+                                    "Stop monitoring"
+                                } else {
+                                    "Start monitoring"
+                                })
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    if running {
+                                        this.stop(window, cx);
+                                    } else {
+                                        this.start(window, cx);
+                                        this.save_config(cx);
+                                    }
+                                })),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        v_form()
+            .child(
+                field()
+                    .label("Target")
+                    .child(Input::new(&self.target_input).small()),
+            )
+            .child(
+                field()
+                    .label("Schedule (cron with seconds)")
+                    .child(Input::new(&self.cron_input).small()),
+            )
+            .child(
+                field()
+                    .label("Alert after failed checks")
+                    .child(Input::new(&self.alert_after_input).small()),
+            )
+            .child(
+                field().child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("save")
+                                .label("Start")
+                                .small()
+                                .primary()
+                                .on_click(
+                                    cx.listener(|this, _, window, cx| this.apply_form(window, cx)),
+                                ),
+                        )
+                        .when(!self.content.target.is_empty(), |view| {
+                            view.child(Button::new("cancel").label("Cancel").small().on_click(
+                                cx.listener(|this, _, _, cx| {
+                                    this.editing = false;
+                                    this.error = None;
+                                    cx.notify();
+                                }),
+                            ))
+                        }),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl CronTarget for UptimeSticker {
+    fn set_next_run(&mut self, next: Option<String>, _: &mut Context<Self>) {
+        match next {
+            Some(next) => self.next_scheduled_at = Some(next),
+            None => self.stop_schedule(),
+        }
+    }
+
+    fn run_scheduled(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.check(window, cx);
+    }
+}
+
+impl super::Sticker for UptimeSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(180, 120)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(260, 200)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+
+    fn scheduler_state(&self) -> Option<String> {
+        if !self.is_schedule_active() {
+            return self.checking.then(|| "checking".to_string());
+        }
+        Some(match &self.next_scheduled_at {
+            Some(next) => format!("monitoring, next check at {next}"),
+            None => "monitoring".to_string(),
+        })
+    }
+}
+
+impl Render for UptimeSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        window.set_rem_size(px(14.0));
+
+        if self.raise_window {
+            self.raise_window = false;
+            window.activate_window();
+            set_window_top_most(window, true);
+        }
+        if self.alerting {
+            self.spawn_for_tick(cx);
+        }
+
+        let bg = match self.alerting && self.flash_on {
+            true => gpui::red().into(),
+            false => self.color.bg(),
+        };
+        let mut body = v_flex().size_full().bg(Rgba { a: 0.85, ..bg });
+
+        if self.editing {
+            body = body.child(div().p_2().child(self.form(cx)));
+        } else {
+            body = body.child(self.status_view(window, cx));
+        }
+
+        body.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("uptime-error", msg.as_str()).small())
+        })
+    }
+}
@@ -0,0 +1,315 @@
+use gpui::{
+    AnyElement, AppContext, Context, Entity, Render, Rgba, Window, div, prelude::*,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use super::save_content;
+use crate::model::sticker::StickerColor;
+use crate::native::components::{
+    IconName,
+    webview::{self, SimpleWebView, WebViewStyle},
+};
+use crate::native::windows::StickerWindowEvent;
+use crate::storage::ArcStickerStore;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct WebContent {
+    url: String,
+    /// Only the style sheet applies, a page loaded from a URL is not wrapped.
+    style: WebViewStyle,
+}
+
+/// Content of a web sticker showing the URL.
+pub fn content_for_url(url: &str) -> String {
+    serde_json::to_string(&WebContent {
+        url: url.to_string(),
+        ..Default::default()
+    })
+    .unwrap_or_default()
+}
+
+pub struct WebSticker {
+    id: i64,
+    color: StickerColor,
+    store: ArcStickerStore,
+    sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+
+    url: Entity<InputState>,
+    css: Entity<InputState>,
+    /// `None` while the URL form is shown.
+    webview: Option<Entity<SimpleWebView>>,
+    /// The URL last saved, the title follows it until it is renamed.
+    saved_url: String,
+    error: Option<String>,
+}
+
+impl WebSticker {
+    pub fn new(
+        id: i64,
+        color: StickerColor,
+        store: ArcStickerStore,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        sticker_events_tx: std::sync::mpsc::Sender<StickerWindowEvent>,
+    ) -> Self {
+        let content = serde_json::from_str::<WebContent>(content).unwrap_or_default();
+
+        let url = cx.new(|cx| {
+            InputState::new(window, cx)
+                .default_value(content.url.clone())
+                .placeholder("https://grafana.example.com/d-solo/...")
+        });
+        cx.subscribe_in(&url, window, |this, _, event: &InputEvent, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.open_url(window, cx);
+            }
+        })
+        .detach();
+
+        let css = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(1, 5)
+                .default_value(content.style.css.clone())
+                .placeholder("body { font-size: 14px; }")
+        });
+
+        let webview = (!content.url.is_empty()).then(|| {
+            cx.new(|cx| SimpleWebView::with_style(content.url.as_str(), &content.style, window, cx))
+        });
+
+        Self {
+            id,
+            color,
+            store,
+            sticker_events_tx,
+            url,
+            css,
+            webview,
+            saved_url: content.url,
+            error: None,
+        }
+    }
+
+    fn current_url(&self, cx: &Context<Self>) -> String {
+        let url = self.url.read(cx).value().trim().to_string();
+        if url.is_empty() || crate::utils::url::is_url(&url) {
+            url
+        } else {
+            format!("https://{url}")
+        }
+    }
+
+    fn open_url(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let url = self.current_url(cx);
+        if url.is_empty() {
+            self.error = Some("Please enter a URL".to_string());
+            cx.notify();
+            return;
+        }
+
+        self.error = None;
+        self.save_config(cx);
+        self.refresh(window, cx);
+    }
+
+    fn current_style(&self, cx: &Context<Self>) -> WebViewStyle {
+        WebViewStyle {
+            css: self.css.read(cx).value().to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Reload the page by building a fresh webview for the current URL.
+    fn refresh(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let url = self.current_url(cx);
+        let style = self.current_style(cx);
+        self.webview =
+            Some(cx.new(|cx| SimpleWebView::with_style(url.as_str(), &style, window, cx)));
+        cx.notify();
+    }
+
+    fn save_config(&mut self, cx: &mut Context<Self>) -> bool {
+        let content = WebContent {
+            url: self.current_url(cx),
+            style: self.current_style(cx),
+        };
+        let title = content.url.clone();
+        let previous_url = std::mem::replace(&mut self.saved_url, content.url.clone());
+        let json = match serde_json::to_string(&content) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize web sticker: {err}"));
+                return false;
+            }
+        };
+
+        let id = self.id;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+
+        cx.spawn(async move |entity, cx| {
+            // A title given by hand stays, only the default one follows the URL: empty,
+            // the previous URL, or the one of a new sticker without a URL yet.
+            let renamed = !previous_url.is_empty()
+                && store
+                    .get_sticker(id)
+                    .await
+                    .is_ok_and(|x| !x.title.is_empty() && x.title != previous_url);
+            if !renamed && title != previous_url {
+                if let Err(err) = store.update_sticker_title(id, title.clone()).await {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.error = Some(format!("Failed to save web sticker title: {err:#}"));
+                        cx.notify();
+                    });
+                    return;
+                }
+
+                if let Err(err) =
+                    sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title })
+                {
+                    tracing::warn!(id, error = %err, "Failed to send sticker title changed event");
+                }
+            }
+
+            if let Err(err) =
+                save_content(&store, &sticker_events_tx, id, json, String::new()).await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to save web sticker: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+
+        true
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        let preset = |id: &'static str, label: &'static str, css: &'static str| {
+            Button::new(id).label(label).small().on_click(cx.listener(
+                move |this, _, window, cx| {
+                    this.css.update(cx, |input, cx| {
+                        let value = input.value().trim().to_string();
+                        let value = match value.is_empty() {
+                            true => css.to_string(),
+                            false => format!("{value}\n{css}"),
+                        };
+                        input.set_value(value, window, cx);
+                    });
+                },
+            ))
+        };
+
+        v_form()
+            .child(field().label("URL").child(Input::new(&self.url)))
+            .child(
+                field().label("Page style").child(
+                    v_flex()
+                        .w_full()
+                        .gap_1()
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(preset("web_dark", "dark mode", webview::DARK_MODE_CSS))
+                                .child(preset(
+                                    "web_larger",
+                                    "larger text",
+                                    webview::LARGER_TEXT_CSS,
+                                )),
+                        )
+                        .child(Input::new(&self.css)),
+                ),
+            )
+            .child(
+                field().child(
+                    Button::new("open")
+                        .label("Open")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, window, cx| this.open_url(window, cx))),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl super::Sticker for WebSticker {
+    fn save_on_close(&mut self, cx: &mut Context<Self>) -> bool {
+        self.save_config(cx)
+    }
+
+    fn min_window_size() -> gpui::Size<i32> {
+        gpui::size(150, 100)
+    }
+
+    fn default_window_size() -> gpui::Size<i32> {
+        gpui::size(480, 360)
+    }
+
+    fn set_color(&mut self, color: StickerColor) {
+        self.color = color;
+    }
+}
+
+impl Render for WebSticker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let bg_color = Rgba {
+            a: 0.85,
+            ..self.color.bg()
+        };
+
+        window.set_rem_size(cx.theme().font_size);
+
+        let root = v_flex().relative().size_full().bg(bg_color);
+
+        let root = match self.webview.clone() {
+            Some(webview) => root
+                .child(div().size_full().flex_shrink().p_1().child(webview))
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_1()
+                        .child(
+                            Button::new("edit")
+                                .icon(IconName::Adjustments)
+                                .bg(transparent_white())
+                                .border_0()
+                                .tooltip("Change URL")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.webview = None;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Button::new("refresh")
+                                .icon(IconName::Refresh)
+                                .bg(transparent_white())
+                                .border_0()
+                                .tooltip("Refresh")
+                                .on_click(
+                                    cx.listener(|this, _, window, cx| this.refresh(window, cx)),
+                                ),
+                        ),
+                ),
+            None => root.child(div().p_2().h_full().child(self.form(cx))),
+        };
+
+        root.when_some(self.error.as_ref(), |view, msg| {
+            view.child(Alert::error("web-error", msg.as_str()).small().bg(bg_color))
+        })
+    }
+}
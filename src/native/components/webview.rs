@@ -1,6 +1,57 @@
 use gpui::{AppContext, IntoElement, Render};
 use gpui::{Context, Entity, Window};
 use gpui_wry::WebView;
+use serde::{Deserialize, Serialize};
+
+/// Inverts the page and puts pictures back, for pages without a dark theme.
+pub const DARK_MODE_CSS: &str = "html { filter: invert(1) hue-rotate(180deg); } img, video, picture, canvas, svg { filter: invert(1) hue-rotate(180deg); }";
+pub const LARGER_TEXT_CSS: &str = "html { zoom: 1.25; }";
+
+/// Per sticker changes to what a web view shows.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebViewStyle {
+    /// Added to the page as a style sheet.
+    pub css: String,
+    /// HTML around an HTML result, `{{content}}` marks where the result goes.
+    pub wrapper: String,
+}
+
+impl WebViewStyle {
+    /// `html` inside the wrapper, with the style sheet in its head.
+    pub fn apply_to_html(&self, html: &str) -> String {
+        let html = match self.wrapper.trim() {
+            "" => html.to_string(),
+            wrapper if wrapper.contains("{{content}}") => wrapper.replace("{{content}}", html),
+            wrapper => format!("{wrapper}{html}"),
+        };
+
+        let css = self.css.trim();
+        if css.is_empty() {
+            return html;
+        }
+        let style = format!("<style>{css}</style>");
+        // Lowercasing ASCII keeps byte offsets, so positions apply to `html` as is.
+        let lower = html.to_ascii_lowercase();
+        let at = lower
+            .find("</head>")
+            .or_else(|| lower.find("<body"))
+            .unwrap_or(0);
+        format!("{}{style}{}", &html[..at], &html[at..])
+    }
+
+    /// A script adding the style sheet to every page loaded from a URL.
+    fn init_script(&self) -> Option<String> {
+        let css = self.css.trim();
+        if css.is_empty() {
+            return None;
+        }
+        let css = serde_json::to_string(css).ok()?;
+        Some(format!(
+            "(() => {{ const add = () => {{ const style = document.createElement('style'); style.textContent = {css}; (document.head || document.documentElement).appendChild(style); }}; if (document.readyState === 'loading') {{ document.addEventListener('DOMContentLoaded', add); }} else {{ add(); }} }})();"
+        ))
+    }
+}
 
 pub struct SimpleWebView {
     webview: Entity<WebView>,
@@ -8,6 +59,17 @@ pub struct SimpleWebView {
 
 impl SimpleWebView {
     pub fn new(source: &str, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        Self::with_style(source, &WebViewStyle::default(), window, cx)
+    }
+
+    /// Like [`SimpleWebView::new`], with the style sheet of `style` added to URL pages.
+    /// HTML is expected to be styled with [`WebViewStyle::apply_to_html`] already.
+    pub fn with_style(
+        source: &str,
+        style: &WebViewStyle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
         let webview = cx.new(|cx| {
             let mut builder = wry::WebViewBuilder::new()
                 .with_user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
@@ -21,6 +83,9 @@ impl SimpleWebView {
 
             builder = if crate::utils::url::is_url(source) {
                 tracing::debug!(url = %source, "Loading URL in webview");
+                if let Some(script) = style.init_script() {
+                    builder = builder.with_initialization_script(script.as_str());
+                }
                 builder.with_url(source)
             } else {
                 tracing::debug!("Loading HTML in webview");
@@ -39,3 +104,48 @@ impl Render for SimpleWebView {
         self.webview.clone()
     }
 }
+
+/// A [`SimpleWebView`] which is only built once its window is visible, so restoring
+/// several HTML dashboards at launch does not start a browser engine for each of
+/// them before they are looked at. See [`LazyWebView::sync`].
+#[derive(Default)]
+pub struct LazyWebView {
+    /// URL or HTML to show.
+    source: Option<String>,
+    webview: Option<Entity<SimpleWebView>>,
+}
+
+impl LazyWebView {
+    /// Show `source` from the next [`LazyWebView::sync`] on, `None` drops the web view.
+    pub fn set_source(&mut self, source: Option<String>) {
+        if self.source != source {
+            self.source = source;
+            self.webview = None;
+        }
+    }
+
+    /// Call while rendering with whether the web view is `shown` at all. It is built
+    /// the first time it is shown while the window is active or hovered, a window
+    /// restored in the background keeps a blank placeholder until then, and dropped
+    /// while it is not shown, e.g. behind an edit form.
+    pub fn sync<T>(
+        &mut self,
+        shown: bool,
+        window: &mut Window,
+        cx: &mut Context<T>,
+    ) -> Option<Entity<SimpleWebView>> {
+        if !shown {
+            self.webview = None;
+            return None;
+        }
+
+        if self.webview.is_none()
+            && (window.is_window_active() || window.is_window_hovered())
+            && let Some(source) = self.source.as_deref()
+        {
+            tracing::debug!("Creating deferred webview");
+            self.webview = Some(cx.new(|cx| SimpleWebView::new(source, window, cx)));
+        }
+        self.webview.clone()
+    }
+}
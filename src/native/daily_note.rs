@@ -0,0 +1,100 @@
+use std::sync::mpsc;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use gpui::AsyncApp;
+
+use crate::model::sticker::*;
+use crate::native::components::stickers::Sticker;
+use crate::native::components::stickers::markdown::MarkdownSticker;
+use crate::native::windows::StickerWindowEvent;
+use crate::native::windows::sticker::StickerWindow;
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::{DailyNoteSettings, SettingsStore};
+use crate::utils::template::{builtin_vars, render_template};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Create (or open) the daily note once the configured time of the day has passed.
+pub async fn run_scheduler(
+    cx: &mut AsyncApp,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+) {
+    let mut last_date: Option<NaiveDate> = None;
+
+    loop {
+        let Ok(settings) = cx.update(|cx| SettingsStore::get(cx).daily_note.clone()) else {
+            return;
+        };
+
+        let now = Local::now();
+        let today = now.date_naive();
+        if settings.enabled
+            && last_date != Some(today)
+            && let Some(time) = settings.parse_time()
+            && now.time() >= time
+        {
+            last_date = Some(today);
+            if let Err(err) =
+                open_daily_note(cx, store.clone(), sticker_events_tx.clone(), &settings).await
+            {
+                tracing::error!(error = ?err, "Failed to open daily note");
+            }
+        }
+
+        cx.background_executor().timer(CHECK_INTERVAL).await;
+    }
+}
+
+async fn open_daily_note(
+    cx: &mut AsyncApp,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+    settings: &DailyNoteSettings,
+) -> anyhow::Result<i64> {
+    let content = render_template(&settings.template, &builtin_vars());
+    // Same rule as the markdown sticker uses for its title.
+    let title = content
+        .lines()
+        .find(|x| !x.is_empty())
+        .unwrap_or("")
+        .to_string();
+
+    let id = match store
+        .find_sticker_by_title(title.clone(), StickerType::Markdown)
+        .await?
+    {
+        Some(id) => id,
+        None => {
+            let size = MarkdownSticker::default_window_size();
+            let id = store
+                .insert_sticker(StickerDetail {
+                    id: 0,
+                    title: title.clone(),
+                    state: StickerState::Open,
+                    left: 100,
+                    top: 100,
+                    width: size.width,
+                    height: size.height,
+                    top_most: false,
+                    color: StickerColor::Yellow,
+                    sticker_type: StickerType::Markdown,
+                    content,
+                    settings: String::new(),
+                    temporary: false,
+                    expires_at: None,
+                    created_at: 0,
+                    updated_at: 0,
+                })
+                .await?;
+            tracing::info!(id, title = %title, "Daily note created");
+            let _ = sticker_events_tx.send(StickerWindowEvent::Created { id });
+            let _ = sticker_events_tx.send(StickerWindowEvent::StoreChanged);
+            id
+        }
+    };
+
+    StickerWindow::open_async(cx, sticker_events_tx, store, id).await?;
+    Ok(id)
+}
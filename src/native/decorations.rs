@@ -0,0 +1,308 @@
+use std::cell::Cell;
+
+use gpui::{
+    AnyElement, App, CursorStyle, Decorations, InteractiveElement, IntoElement, MouseButton,
+    MouseDownEvent, Pixels, ResizeEdge, SharedString, Tiling, TitlebarOptions, Window,
+    WindowControlArea, WindowDecorations, div, prelude::*, px, white,
+};
+use gpui_component::TitleBar;
+
+/// Room left of our own title bar for the native traffic lights on macOS.
+pub const TRAFFIC_LIGHTS_WIDTH: Pixels = px(72.0);
+/// How far from the edge a client decorated window can be grabbed to resize it.
+const RESIZE_BORDER: Pixels = px(5.0);
+
+thread_local! {
+    /// Set by a mouse down in a drag area, the window moves once the mouse does.
+    static PENDING_MOVE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Options for windows which draw their own title bar.
+///
+/// macOS keeps the native traffic lights on top of it, the other platforms get none.
+pub fn titlebar_options(title: impl Into<SharedString>) -> TitlebarOptions {
+    TitlebarOptions {
+        title: Some(title.into()),
+        ..TitleBar::title_bar_options()
+    }
+}
+
+/// Ask Linux compositors to leave the decorations to us, Wayland ones without server side
+/// decorations would do so anyway. Other platforms decide by the title bar options.
+pub fn window_decorations() -> Option<WindowDecorations> {
+    if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        Some(WindowDecorations::Client)
+    } else {
+        None
+    }
+}
+
+/// Whether the platform draws minimize and close buttons itself, so ours are left out.
+pub fn has_native_window_controls() -> bool {
+    cfg!(target_os = "macos")
+}
+
+pub trait WindowDragArea: InteractiveElement + Sized {
+    /// Drag the window by this element and maximize it on a double click, the way the
+    /// native title bar of the platform would.
+    ///
+    /// Windows handles both for the hit tested control area, macOS moves the window by
+    /// its transparent title bar, Linux needs the compositor to be asked for a move.
+    fn window_drag_area(self) -> Self {
+        drag_area(self, None)
+    }
+
+    /// Like [`Self::window_drag_area`], but a double click runs `listener` instead of
+    /// maximizing the window.
+    fn window_drag_area_on_double_click(
+        self,
+        listener: impl Fn(&MouseDownEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        drag_area(self, Some(Box::new(listener)))
+    }
+}
+
+type DoubleClickListener = Box<dyn Fn(&MouseDownEvent, &mut Window, &mut App)>;
+
+fn drag_area<E: InteractiveElement>(element: E, on_double_click: Option<DoubleClickListener>) -> E {
+    let element = element.window_control_area(WindowControlArea::Drag);
+    if cfg!(target_os = "windows") {
+        // A double click which is handled before it reaches the control area does not
+        // maximize the window.
+        return match on_double_click {
+            Some(listener) => element.on_mouse_down(MouseButton::Left, move |event, window, cx| {
+                if event.click_count == 2 {
+                    cx.stop_propagation();
+                    window.prevent_default();
+                    listener(event, window, cx);
+                }
+            }),
+            None => element,
+        };
+    }
+
+    element
+        .on_mouse_down(MouseButton::Left, move |event, window, cx| {
+            if event.click_count == 2 {
+                PENDING_MOVE.set(false);
+                if let Some(listener) = &on_double_click {
+                    listener(event, window, cx);
+                } else if cfg!(target_os = "macos") {
+                    window.titlebar_double_click();
+                } else {
+                    window.zoom_window();
+                }
+            } else {
+                PENDING_MOVE.set(!cfg!(target_os = "macos"));
+            }
+        })
+        .on_mouse_up(MouseButton::Left, |_, _, _| PENDING_MOVE.set(false))
+        .on_mouse_up_out(MouseButton::Left, |_, _, _| PENDING_MOVE.set(false))
+        .on_mouse_move(|event, window, _| {
+            if event.pressed_button == Some(MouseButton::Left) && PENDING_MOVE.replace(false) {
+                window.start_window_move();
+            }
+        })
+}
+
+impl<E: InteractiveElement> WindowDragArea for E {}
+
+/// Resize handles along the edges of a window which draws its own decorations, e.g. on
+/// GNOME, none when the compositor decorates it or it is maximized or tiled.
+pub fn resize_handles(window: &Window) -> Vec<AnyElement> {
+    match window.window_decorations() {
+        Decorations::Server => Vec::new(),
+        Decorations::Client { tiling } => edge_handles(window, tiling, false),
+    }
+}
+
+/// Resize grips on all edges and corners of a frameless sticker window, shown while
+/// `visible` (the window is hovered) and grabbable anyway.
+///
+/// macOS resizes the window from its edges natively, so it gets none.
+pub fn resize_grips(window: &Window, visible: bool) -> Vec<AnyElement> {
+    if cfg!(target_os = "macos") {
+        return Vec::new();
+    }
+    let tiling = match window.window_decorations() {
+        Decorations::Server => Tiling::default(),
+        Decorations::Client { tiling } => tiling,
+    };
+    edge_handles(window, tiling, visible)
+}
+
+/// Let DWM round the corners of a frameless window on Windows 11, it clips the window
+/// so clicks on the cut off corners go to the window below. Other platforms round
+/// titled windows themselves or leave it to the compositor.
+pub fn round_corners(window: &Window) {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::Graphics::Dwm::{
+            DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_ROUND, DwmSetWindowAttribute,
+        };
+
+        let Some(hwnd) = hwnd(window) else {
+            return;
+        };
+        let preference = DWMWCP_ROUND;
+        let hr = unsafe {
+            DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_WINDOW_CORNER_PREFERENCE,
+                &preference as *const _ as *const std::ffi::c_void,
+                std::mem::size_of_val(&preference) as u32,
+            )
+        };
+        // Windows 10 does not know the attribute and keeps square corners.
+        if hr != 0 {
+            tracing::debug!(hr, "DwmSetWindowAttribute corner preference failed");
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = window;
+    }
+}
+
+#[cfg(windows)]
+fn hwnd(window: &Window) -> Option<windows_sys::Win32::Foundation::HWND> {
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+    match window.window_handle().map(|handle| handle.as_raw()) {
+        Ok(RawWindowHandle::Win32(handle)) => {
+            Some(handle.hwnd.get() as windows_sys::Win32::Foundation::HWND)
+        }
+        Ok(_) => {
+            tracing::warn!("Unexpected window handle");
+            None
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to get native window handle");
+            None
+        }
+    }
+}
+
+/// gpui only resizes client decorated windows on Linux, on Windows the resize is handed
+/// to the system as if the native border was grabbed.
+fn start_resize(window: &Window, edge: ResizeEdge) {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::ReleaseCapture;
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            HTBOTTOM, HTBOTTOMLEFT, HTBOTTOMRIGHT, HTLEFT, HTRIGHT, HTTOP, HTTOPLEFT, HTTOPRIGHT,
+            PostMessageW, WM_NCLBUTTONDOWN,
+        };
+
+        let Some(hwnd) = hwnd(window) else {
+            return;
+        };
+        let hit = match edge {
+            ResizeEdge::Top => HTTOP,
+            ResizeEdge::Bottom => HTBOTTOM,
+            ResizeEdge::Left => HTLEFT,
+            ResizeEdge::Right => HTRIGHT,
+            ResizeEdge::TopLeft => HTTOPLEFT,
+            ResizeEdge::TopRight => HTTOPRIGHT,
+            ResizeEdge::BottomLeft => HTBOTTOMLEFT,
+            ResizeEdge::BottomRight => HTBOTTOMRIGHT,
+        };
+        // Posted, so the modal resize loop starts after gpui is done with the mouse down.
+        unsafe {
+            ReleaseCapture();
+            PostMessageW(hwnd, WM_NCLBUTTONDOWN, hit as usize, 0);
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        window.start_window_resize(edge);
+    }
+}
+
+fn edge_handles(window: &Window, tiling: Tiling, visible: bool) -> Vec<AnyElement> {
+    if window.is_maximized() || window.is_fullscreen() {
+        return Vec::new();
+    }
+
+    let corner = RESIZE_BORDER * 2.0;
+    let handles = [
+        (ResizeEdge::Top, !tiling.top),
+        (ResizeEdge::Bottom, !tiling.bottom),
+        (ResizeEdge::Left, !tiling.left),
+        (ResizeEdge::Right, !tiling.right),
+        (ResizeEdge::TopLeft, !tiling.top && !tiling.left),
+        (ResizeEdge::TopRight, !tiling.top && !tiling.right),
+        (ResizeEdge::BottomLeft, !tiling.bottom && !tiling.left),
+        (ResizeEdge::BottomRight, !tiling.bottom && !tiling.right),
+    ];
+
+    handles
+        .into_iter()
+        .filter(|(_, free)| *free)
+        .map(|(edge, _)| {
+            let handle = div()
+                .id(SharedString::from(format!("resize-{edge:?}")))
+                .absolute()
+                .occlude()
+                .rounded(RESIZE_BORDER)
+                .when(visible, |handle| {
+                    handle
+                        .bg(white().opacity(0.15))
+                        .hover(|style| style.bg(white().opacity(0.4)))
+                })
+                .on_mouse_down(MouseButton::Left, move |_, window, cx| {
+                    cx.stop_propagation();
+                    start_resize(window, edge);
+                });
+            match edge {
+                ResizeEdge::Top => handle
+                    .top_0()
+                    .left(corner)
+                    .right(corner)
+                    .h(RESIZE_BORDER)
+                    .cursor(CursorStyle::ResizeUpDown),
+                ResizeEdge::Bottom => handle
+                    .bottom_0()
+                    .left(corner)
+                    .right(corner)
+                    .h(RESIZE_BORDER)
+                    .cursor(CursorStyle::ResizeUpDown),
+                ResizeEdge::Left => handle
+                    .left_0()
+                    .top(corner)
+                    .bottom(corner)
+                    .w(RESIZE_BORDER)
+                    .cursor(CursorStyle::ResizeLeftRight),
+                ResizeEdge::Right => handle
+                    .right_0()
+                    .top(corner)
+                    .bottom(corner)
+                    .w(RESIZE_BORDER)
+                    .cursor(CursorStyle::ResizeLeftRight),
+                ResizeEdge::TopLeft => handle
+                    .top_0()
+                    .left_0()
+                    .size(corner)
+                    .cursor(CursorStyle::ResizeUpLeftDownRight),
+                ResizeEdge::BottomRight => handle
+                    .bottom_0()
+                    .right_0()
+                    .size(corner)
+                    .cursor(CursorStyle::ResizeUpLeftDownRight),
+                ResizeEdge::TopRight => handle
+                    .top_0()
+                    .right_0()
+                    .size(corner)
+                    .cursor(CursorStyle::ResizeUpRightDownLeft),
+                ResizeEdge::BottomLeft => handle
+                    .bottom_0()
+                    .left_0()
+                    .size(corner)
+                    .cursor(CursorStyle::ResizeUpRightDownLeft),
+            }
+            .into_any_element()
+        })
+        .collect()
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use rfd::AsyncFileDialog;
+
+/// A named group of file extensions offered by a dialog, e.g. `("CSV", &["csv"])`.
+pub type Filter<'a> = (&'a str, &'a [&'a str]);
+
+/// Where file dialogs start, the home directory when it is known.
+pub fn default_dir() -> PathBuf {
+    directories::UserDirs::new()
+        .map(|dirs| dirs.home_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn dialog(title: &str, filters: &[Filter]) -> AsyncFileDialog {
+    filters.iter().fold(
+        AsyncFileDialog::new()
+            .set_title(title)
+            .set_directory(default_dir()),
+        |dialog, (name, extensions)| dialog.add_filter(*name, extensions),
+    )
+}
+
+/// Ask for an existing file, `None` when the dialog was cancelled.
+///
+/// The dialogs do not block the UI thread, await them from a spawned task.
+pub async fn pick_file(title: &str, filters: &[Filter<'_>]) -> Option<PathBuf> {
+    let file = dialog(title, filters).pick_file().await?;
+    Some(file.path().to_path_buf())
+}
+
+pub async fn pick_folder(title: &str) -> Option<PathBuf> {
+    let folder = dialog(title, &[]).pick_folder().await?;
+    Some(folder.path().to_path_buf())
+}
+
+/// Ask where to write a file, starting with the suggested `file_name`.
+pub async fn save_file(title: &str, file_name: &str, filters: &[Filter<'_>]) -> Option<PathBuf> {
+    let file = dialog(title, filters)
+        .set_file_name(file_name)
+        .save_file()
+        .await?;
+    Some(file.path().to_path_buf())
+}
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use gpui::AsyncApp;
+
+use crate::native::sound::{self, Sound};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Whether the OS "do not disturb" (focus assist on Windows) mode is on.
+///
+/// Best effort: unknown desktops and errors are treated as not active.
+pub fn is_active() -> bool {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::Shell::{
+            QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME, QUNS_RUNNING_D3D_FULL_SCREEN,
+            SHQueryUserNotificationState,
+        };
+
+        let mut state = 0;
+        let hr = unsafe { SHQueryUserNotificationState(&mut state) };
+        if hr != 0 {
+            tracing::debug!(hr, "SHQueryUserNotificationState failed");
+            return false;
+        }
+        matches!(
+            state,
+            QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE | QUNS_QUIET_TIME
+        )
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Focus modes are recorded as assertions, the list is empty when no focus is on.
+        let Some(home) = directories::UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf())
+        else {
+            return false;
+        };
+        let path = home.join("Library/DoNotDisturb/DB/Assertions.json");
+        let Ok(json) = std::fs::read_to_string(path) else {
+            return false;
+        };
+        serde_json::from_str::<serde_json::Value>(&json)
+            .ok()
+            .and_then(|value| {
+                value["data"].as_array().map(|data| {
+                    data.iter().any(|x| {
+                        x["storeAssertionRecords"]
+                            .as_array()
+                            .is_some_and(|records| !records.is_empty())
+                    })
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    #[cfg(all(not(windows), not(target_os = "macos")))]
+    {
+        // GNOME hides banners while do not disturb is on.
+        if which::which("gsettings").is_err() {
+            return false;
+        }
+        std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "false")
+            .unwrap_or(false)
+    }
+}
+
+/// Wait until do not disturb is turned off, then show a desktop notification.
+pub async fn notify_when_inactive(cx: &mut AsyncApp, summary: String, body: String) {
+    loop {
+        cx.background_executor().timer(POLL_INTERVAL).await;
+        if !cx.background_executor().spawn(async { is_active() }).await {
+            break;
+        }
+    }
+
+    let shown = cx
+        .background_executor()
+        .spawn(async move {
+            notify_rust::Notification::new()
+                .appname("Rustickers")
+                .summary(&summary)
+                .body(&body)
+                .show()
+                .map(|_| ())
+        })
+        .await;
+    match shown {
+        Ok(()) => {
+            let _ = cx.update(|cx| sound::play(cx, Sound::Ding));
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to show queued notification");
+        }
+    }
+}
@@ -0,0 +1,555 @@
+//! The database, settings, attachments, images and templates packed into one file
+//! encrypted with a passphrase, safe to keep in cloud storage, and unpacking it again
+//! as a restore.
+//!
+//! The file is `RSTKENC2`, the PBKDF2 rounds (u32 LE), a salt and a nonce prefix, then
+//! the packed entries in AES-256-GCM sealed chunks of [`CHUNK_LEN`] bytes. The nonce of
+//! a chunk is the prefix, its number (u32 BE) and whether it is the last one, so chunks
+//! can not be reordered, dropped or cut off. Each entry is its path in the data
+//! directory (u32 LE length, UTF-8) and its bytes (u64 LE length).
+//!
+//! Archives are streamed from and to disk, attachments and pictures never sit in memory
+//! as a whole.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context as _;
+use chrono::Local;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom as _, SystemRandom};
+use sha2::Sha256;
+
+use crate::native::backup;
+use crate::storage::ArcStickerStore;
+use crate::storage::paths::AppPaths;
+use crate::storage::settings::AppSettings;
+
+pub const FILE_EXTENSION: &str = "rstk";
+pub const MIN_PASSPHRASE_CHARS: usize = 8;
+
+const MAGIC: &[u8; 8] = b"RSTKENC2";
+#[cfg(not(test))]
+const KDF_ROUNDS: u32 = 600_000;
+/// Tests derive a key for every archive.
+#[cfg(test)]
+const KDF_ROUNDS: u32 = 1_000;
+const SALT_LEN: usize = 16;
+/// The nonce is this prefix, the chunk number and the last chunk flag.
+const NONCE_PREFIX_LEN: usize = NONCE_LEN - 4 - 1;
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN + NONCE_PREFIX_LEN;
+const CHUNK_LEN: usize = 1024 * 1024;
+const TAG_LEN: usize = 16;
+/// Entry of the database snapshot, the other entries keep their names.
+const DB_ENTRY: &str = "stickers.db";
+/// Written next to the database while exporting, removed once packed.
+const EXPORT_SNAPSHOT_FILE: &str = "stickers.db.export";
+
+/// Write the encrypted archive of everything in the data directory worth keeping to
+/// `dest`. Heavy on purpose, run it on the background executor.
+pub async fn export(
+    store: &ArcStickerStore,
+    app_paths: &AppPaths,
+    passphrase: &str,
+    dest: &Path,
+) -> anyhow::Result<()> {
+    let snapshot = app_paths.data_dir().join(EXPORT_SNAPSHOT_FILE);
+    // VACUUM INTO refuses to overwrite a file left by an interrupted export.
+    let _ = std::fs::remove_file(&snapshot);
+    store.backup_to(snapshot.clone()).await?;
+
+    // Written next to the destination first, an interrupted export keeps the old archive.
+    let mut temp_path = dest.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    let written = write_archive(app_paths, &snapshot, passphrase, &temp_path).and_then(|()| {
+        std::fs::rename(&temp_path, dest).with_context(|| format!("write {}", dest.display()))
+    });
+    let _ = std::fs::remove_file(&snapshot);
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    written?;
+
+    tracing::info!(archive = %dest.display(), "Encrypted backup exported");
+    Ok(())
+}
+
+/// Decrypt `archive` and use it from the next start on, like a restore from a backup.
+/// The imported database also lands in the backups directory.
+pub async fn schedule_import(
+    store: &ArcStickerStore,
+    app_paths: &AppPaths,
+    archive: &Path,
+    passphrase: &str,
+) -> anyhow::Result<()> {
+    let file = File::open(archive).with_context(|| format!("read {}", archive.display()))?;
+    let packed = open(BufReader::new(file), passphrase)?;
+
+    let backups_dir = app_paths.backups_dir();
+    std::fs::create_dir_all(&backups_dir)
+        .with_context(|| format!("create directory {}", backups_dir.display()))?;
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    let imported_db = backups_dir.join(format!("stickers-{stamp}-imported.db"));
+
+    let pending_files = backup::pending_files_dir(app_paths);
+    let _ = std::fs::remove_dir_all(&pending_files);
+    if let Err(err) = unpack(packed, &pending_files, &imported_db) {
+        let _ = std::fs::remove_dir_all(&pending_files);
+        let _ = std::fs::remove_file(&imported_db);
+        return Err(err);
+    }
+
+    if let Err(err) = backup::schedule_restore(store, app_paths, &imported_db).await {
+        let _ = std::fs::remove_dir_all(&pending_files);
+        return Err(err);
+    }
+    tracing::info!(archive = %archive.display(), "Encrypted backup imported");
+    Ok(())
+}
+
+fn write_archive(
+    app_paths: &AppPaths,
+    snapshot: &Path,
+    passphrase: &str,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let file = File::create(path).with_context(|| format!("create {}", path.display()))?;
+    let mut sealed = seal(BufWriter::new(file), passphrase)?;
+    pack(&mut sealed, app_paths, snapshot)?;
+    sealed
+        .finish()
+        .and_then(|x| x.into_inner().map_err(|err| err.into_error()))
+        .and_then(|x| x.sync_all())
+        .with_context(|| format!("write {}", path.display()))
+}
+
+fn pack(packed: &mut impl Write, app_paths: &AppPaths, snapshot: &Path) -> anyhow::Result<()> {
+    push_file(packed, DB_ENTRY, snapshot).context("pack database snapshot")?;
+
+    let data_dir = app_paths.data_dir();
+    let settings = app_paths.settings_path();
+    if settings.exists() {
+        // Only what is fine on another machine, a restore merges it the same way.
+        let json = AppSettings::load(&settings).portable_json()?;
+        push_entry(packed, backup::SETTINGS_ENTRY, json.len() as u64)?;
+        packed.write_all(json.as_bytes()).context("pack settings")?;
+    }
+    for dir in [
+        app_paths.attachments_dir(),
+        app_paths.images_dir(),
+        app_paths.templates_dir(),
+    ] {
+        pack_dir(packed, &data_dir, &dir)?;
+    }
+    Ok(())
+}
+
+fn pack_dir(packed: &mut impl Write, data_dir: &Path, dir: &Path) -> anyhow::Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err).with_context(|| format!("read {}", dir.display())),
+    };
+
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            pack_dir(packed, data_dir, &path)?;
+        } else {
+            push_file(packed, &entry_name(data_dir, &path)?, &path)
+                .with_context(|| format!("pack {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// The path below the data directory with `/` separators, the same on every OS.
+fn entry_name(data_dir: &Path, path: &Path) -> anyhow::Result<String> {
+    let relative = path
+        .strip_prefix(data_dir)
+        .with_context(|| format!("{} is outside of the data directory", path.display()))?;
+    Ok(relative
+        .components()
+        .map(|x| x.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+fn push_file(packed: &mut impl Write, name: &str, path: &Path) -> anyhow::Result<()> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    push_entry(packed, name, len)?;
+    let copied = io::copy(&mut file.take(len), packed)?;
+    anyhow::ensure!(copied == len, "{} changed while packing", path.display());
+    Ok(())
+}
+
+/// The entry header, its bytes follow.
+fn push_entry(packed: &mut impl Write, name: &str, len: u64) -> anyhow::Result<()> {
+    packed.write_all(&(name.len() as u32).to_le_bytes())?;
+    packed.write_all(name.as_bytes())?;
+    packed.write_all(&len.to_le_bytes())?;
+    Ok(())
+}
+
+/// Write the entries to `pending_files`, the database to `imported_db`.
+fn unpack(mut packed: impl Read, pending_files: &Path, imported_db: &Path) -> anyhow::Result<()> {
+    let mut has_db = false;
+    loop {
+        let mut name_len = [0u8; 4];
+        if packed.read(&mut name_len[..1])? == 0 {
+            break;
+        }
+        read_exact(&mut packed, &mut name_len[1..])?;
+        let mut name = vec![0u8; u32::from_le_bytes(name_len) as usize];
+        read_exact(&mut packed, &mut name)?;
+        let name = String::from_utf8(name).context("entry name")?;
+        // Only plain relative paths, an entry must not write outside the data directory.
+        let safe = !name.is_empty()
+            && Path::new(&name)
+                .components()
+                .all(|x| matches!(x, Component::Normal(_)));
+        anyhow::ensure!(safe, "unexpected entry {name} in the archive");
+        let mut data_len = [0u8; 8];
+        read_exact(&mut packed, &mut data_len)?;
+        let data_len = u64::from_le_bytes(data_len);
+
+        let path = match name.as_str() {
+            DB_ENTRY => {
+                has_db = true;
+                imported_db.to_path_buf()
+            }
+            name => pending_files.join(name),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("create directory {}", parent.display()))?;
+        }
+        let mut file = File::create(&path).with_context(|| format!("write {}", path.display()))?;
+        let copied = io::copy(&mut (&mut packed).take(data_len), &mut file)
+            .with_context(|| format!("write {}", path.display()))?;
+        anyhow::ensure!(copied == data_len, "the archive is truncated");
+    }
+    anyhow::ensure!(has_db, "the archive has no database");
+    Ok(())
+}
+
+fn read_exact(packed: &mut impl Read, buf: &mut [u8]) -> anyhow::Result<()> {
+    packed.read_exact(buf).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => anyhow::anyhow!("the archive is truncated"),
+        _ => err.into(),
+    })
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], rounds: u32) -> anyhow::Result<LessSafeKey> {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, rounds, &mut key);
+    let key = UnboundKey::new(&AES_256_GCM, &key)
+        .map_err(|_| anyhow::anyhow!("create encryption key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn chunk_nonce(prefix: &[u8; NONCE_PREFIX_LEN], counter: u32, last: bool) -> Nonce {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..NONCE_LEN - 1].copy_from_slice(&counter.to_be_bytes());
+    nonce[NONCE_LEN - 1] = last as u8;
+    Nonce::assume_unique_for_key(nonce)
+}
+
+/// Write the header to `inner`, everything written to the returned writer is sealed
+/// after it. Call [`SealWriter::finish`] to seal the last chunk.
+fn seal<W: Write>(mut inner: W, passphrase: &str) -> anyhow::Result<SealWriter<W>> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut prefix = [0u8; NONCE_PREFIX_LEN];
+    rng.fill(&mut salt)
+        .and_then(|()| rng.fill(&mut prefix))
+        .map_err(|_| anyhow::anyhow!("generate random salt"))?;
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&KDF_ROUNDS.to_le_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&prefix);
+    inner
+        .write_all(&header)
+        .context("write the archive header")?;
+
+    Ok(SealWriter {
+        inner,
+        key: derive_key(passphrase, &salt, KDF_ROUNDS)?,
+        header,
+        prefix,
+        counter: 0,
+        chunk: Vec::with_capacity(CHUNK_LEN),
+    })
+}
+
+/// Read the header from `inner` and derive the key, the returned reader yields the
+/// packed entries.
+fn open<R: BufRead>(mut inner: R, passphrase: &str) -> anyhow::Result<OpenReader<R>> {
+    let mut header = vec![0u8; HEADER_LEN];
+    if inner.read_exact(&mut header).is_err() || !header.starts_with(MAGIC) {
+        anyhow::bail!("not an encrypted Rustickers backup");
+    }
+    let rounds = u32::from_le_bytes(header[MAGIC.len()..MAGIC.len() + 4].try_into()?);
+    anyhow::ensure!(
+        (1..=KDF_ROUNDS * 10).contains(&rounds),
+        "damaged file header"
+    );
+    let salt = &header[MAGIC.len() + 4..MAGIC.len() + 4 + SALT_LEN];
+    let key = derive_key(passphrase, salt, rounds)?;
+    let prefix = header[HEADER_LEN - NONCE_PREFIX_LEN..].try_into()?;
+
+    Ok(OpenReader {
+        inner,
+        key,
+        header,
+        prefix,
+        counter: 0,
+        chunk: Vec::with_capacity(CHUNK_LEN + TAG_LEN),
+        pos: 0,
+        done: false,
+    })
+}
+
+struct SealWriter<W: Write> {
+    inner: W,
+    key: LessSafeKey,
+    header: Vec<u8>,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    chunk: Vec<u8>,
+}
+
+impl<W: Write> SealWriter<W> {
+    /// Seal what is left as the last chunk, an archive without it does not open.
+    fn finish(mut self) -> io::Result<W> {
+        self.seal_chunk(true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn seal_chunk(&mut self, last: bool) -> io::Result<()> {
+        let tag = self
+            .key
+            .seal_in_place_separate_tag(
+                chunk_nonce(&self.prefix, self.counter, last),
+                Aad::from(&self.header),
+                &mut self.chunk,
+            )
+            .map_err(|_| io::Error::other("encrypt the archive"))?;
+        self.inner.write_all(&self.chunk)?;
+        self.inner.write_all(tag.as_ref())?;
+        self.chunk.clear();
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("the archive is too large"))?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for SealWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        // A full chunk is only sealed once more data comes, the last one is sealed by finish.
+        if self.chunk.len() == CHUNK_LEN {
+            self.seal_chunk(false)?;
+        }
+        let len = data.len().min(CHUNK_LEN - self.chunk.len());
+        self.chunk.extend_from_slice(&data[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct OpenReader<R: BufRead> {
+    inner: R,
+    key: LessSafeKey,
+    header: Vec<u8>,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    chunk: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<R: BufRead> OpenReader<R> {
+    fn open_chunk(&mut self) -> io::Result<()> {
+        self.chunk.resize(CHUNK_LEN + TAG_LEN, 0);
+        let mut len = 0;
+        while len < self.chunk.len() {
+            match self.inner.read(&mut self.chunk[len..]) {
+                Ok(0) => break,
+                Ok(read) => len += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        self.chunk.truncate(len);
+        let last = len < CHUNK_LEN + TAG_LEN || self.inner.fill_buf()?.is_empty();
+
+        let damaged = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "wrong passphrase or damaged file",
+            )
+        };
+        if len < TAG_LEN {
+            return Err(damaged());
+        }
+        self.key
+            .open_in_place(
+                chunk_nonce(&self.prefix, self.counter, last),
+                Aad::from(&self.header),
+                &mut self.chunk,
+            )
+            .map_err(|_| damaged())?;
+        self.chunk.truncate(len - TAG_LEN);
+        self.pos = 0;
+        self.done = last;
+        self.counter = self.counter.checked_add(1).ok_or_else(damaged)?;
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for OpenReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.chunk.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.open_chunk()?;
+        }
+        let len = buf.len().min(self.chunk.len() - self.pos);
+        buf[..len].copy_from_slice(&self.chunk[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sealed(data: &[u8], passphrase: &str) -> Vec<u8> {
+        let mut writer = seal(Vec::new(), passphrase).unwrap();
+        writer.write_all(data).unwrap();
+        writer.finish().unwrap()
+    }
+
+    fn opened(archive: &[u8], passphrase: &str) -> anyhow::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        open(archive, passphrase)?.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    #[test]
+    fn seal_and_open() {
+        for len in [0, 10, CHUNK_LEN, 2 * CHUNK_LEN + 123] {
+            let data = (0..len).map(|x| (x % 251) as u8).collect::<Vec<_>>();
+            let archive = sealed(&data, "correct horse");
+            let chunks = len.div_ceil(CHUNK_LEN).max(1);
+            assert_eq!(archive.len(), HEADER_LEN + len + chunks * TAG_LEN);
+            assert_eq!(opened(&archive, "correct horse").unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn wrong_passphrase_or_damage() {
+        let data = vec![7u8; CHUNK_LEN + 10];
+        let archive = sealed(&data, "correct horse");
+
+        assert!(opened(&archive, "wrong horse").is_err());
+        assert!(opened(b"PK\x03\x04 not an archive", "correct horse").is_err());
+
+        let mut flipped = archive.clone();
+        flipped[HEADER_LEN + 5] ^= 1;
+        assert!(opened(&flipped, "correct horse").is_err());
+
+        // The header is authenticated too.
+        let mut salted = archive.clone();
+        salted[MAGIC.len() + 4] ^= 1;
+        assert!(opened(&salted, "correct horse").is_err());
+
+        // Cut after a whole chunk, which was not sealed as the last one.
+        let cut = &archive[..HEADER_LEN + CHUNK_LEN + TAG_LEN];
+        assert!(opened(cut, "correct horse").is_err());
+    }
+
+    #[test]
+    fn pack_and_unpack() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let app_paths = AppPaths {
+            db_path: data_dir.path().join("stickers.db"),
+            custom_data_dir: true,
+        };
+        let snapshot = data_dir.path().join(EXPORT_SNAPSHOT_FILE);
+        std::fs::write(&snapshot, "database").unwrap();
+        std::fs::write(
+            app_paths.settings_path(),
+            r#"{ "popup_mode": true, "github_token": "secret" }"#,
+        )
+        .unwrap();
+        std::fs::create_dir_all(app_paths.attachments_dir().join("ab")).unwrap();
+        std::fs::write(
+            app_paths.attachments_dir().join("ab").join("cd"),
+            "attached",
+        )
+        .unwrap();
+        std::fs::create_dir_all(app_paths.templates_dir()).unwrap();
+        std::fs::write(app_paths.templates_dir().join("todo.md"), "- [ ] ").unwrap();
+
+        let mut packed = Vec::new();
+        pack(&mut packed, &app_paths, &snapshot).unwrap();
+
+        let target = tempfile::tempdir().unwrap();
+        let pending_files = target.path().join("restore-files");
+        let imported_db = target.path().join("imported.db");
+        unpack(packed.as_slice(), &pending_files, &imported_db).unwrap();
+
+        let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(imported_db), "database");
+        assert_eq!(read(pending_files.join("attachments/ab/cd")), "attached");
+        assert_eq!(read(pending_files.join("templates/todo.md")), "- [ ] ");
+        let settings = read(pending_files.join(backup::SETTINGS_ENTRY));
+        assert!(settings.contains("popup_mode"));
+        assert!(!settings.contains("secret"));
+    }
+
+    fn unpacked(packed: &[u8]) -> anyhow::Result<()> {
+        let target = tempfile::tempdir().unwrap();
+        unpack(
+            packed,
+            &target.path().join("restore-files"),
+            &target.path().join("imported.db"),
+        )
+    }
+
+    #[test]
+    fn unpack_rejects_bad_entries() {
+        let entry = |name: &str, data: &[u8]| {
+            let mut packed = Vec::new();
+            push_entry(&mut packed, name, data.len() as u64).unwrap();
+            packed.extend_from_slice(data);
+            packed
+        };
+        let db = entry(DB_ENTRY, b"database");
+        assert!(unpacked(&db).is_ok());
+
+        for name in ["../evil", "/etc/evil", "a/../../evil", ""] {
+            let packed = [db.clone(), entry(name, b"x")].concat();
+            assert!(unpacked(&packed).is_err(), "{name} was unpacked");
+        }
+
+        assert!(unpacked(&entry("templates/todo.md", b"x")).is_err());
+        assert!(unpacked(&db[..db.len() - 1]).is_err());
+        assert!(unpacked(&db[..3]).is_err());
+    }
+}
@@ -0,0 +1,630 @@
+//! Mirror every sticker to a plain file in a folder which Syncthing, Dropbox and the
+//! like keep in sync between machines, and merge edits of those files back.
+//!
+//! Markdown stickers become `.md` files with the content as is, the title is its first
+//! line. Every other type becomes a `.json` file with its type, title, color and
+//! content. A new file in the folder becomes a new sticker, a deleted one moves its
+//! sticker to the trash. When both sides changed since the last sync the sticker wins
+//! and the edited file is kept next to it as a conflict copy.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use chrono::Local;
+use gpui::AsyncApp;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+use crate::model::sticker::*;
+use crate::model::sync::SyncedFile;
+use crate::native::components::stickers::Sticker;
+use crate::native::components::stickers::markdown::MarkdownSticker;
+use crate::native::windows::StickerWindowEvent;
+use crate::native::windows::sticker::StickerWindow;
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::SettingsStore;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// Local edits have no watcher, they are written out at least this often.
+const SYNC_INTERVAL: Duration = Duration::from_secs(15);
+/// Size of stickers created from JSON files, Markdown ones use the default size.
+const IMPORTED_SIZE: (i32, i32) = (360, 300);
+const MAX_SLUG_CHARS: usize = 40;
+/// Part of the name of conflict copies, which are left alone by the sync.
+const CONFLICT_MARKER: &str = ".conflict-";
+
+/// The file format of stickers which are not Markdown.
+#[derive(Debug, Serialize, Deserialize)]
+struct StickerFile {
+    #[serde(rename = "type")]
+    sticker_type: StickerType,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    color: String,
+    /// The stored JSON content inline, content which is no JSON as a string.
+    #[serde(default)]
+    content: serde_json::Value,
+}
+
+/// Sync whenever a file in the folder changed, otherwise every [`SYNC_INTERVAL`].
+pub async fn run_scheduler(
+    cx: &mut AsyncApp,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+) {
+    let changed = Arc::new(AtomicBool::new(false));
+    // The watcher of the synced folder, `None` when it could not be watched.
+    let mut watched: Option<(PathBuf, Option<notify::RecommendedWatcher>)> = None;
+    let mut last_sync: Option<Instant> = None;
+    // Stickers updated before this were written out by the last sync, in ms.
+    let mut synced_since = 0;
+
+    loop {
+        let Ok(settings) = cx.update(|cx| SettingsStore::get(cx).folder_sync.clone()) else {
+            return;
+        };
+
+        let folder = settings.folder.trim();
+        if !settings.enabled || folder.is_empty() {
+            watched = None;
+            last_sync = None;
+            synced_since = 0;
+        } else {
+            let folder = PathBuf::from(folder);
+            if watched.as_ref().is_none_or(|(path, _)| *path != folder) {
+                let watcher = watch(&folder, changed.clone())
+                    .inspect_err(|err| {
+                        tracing::warn!(folder = %folder.display(), error = ?err, "Failed to watch sync folder");
+                    })
+                    .ok();
+                watched = Some((folder.clone(), watcher));
+                last_sync = None;
+                synced_since = 0;
+            }
+
+            let due = changed.swap(false, Ordering::Relaxed)
+                || last_sync.is_none_or(|at| at.elapsed() >= SYNC_INTERVAL);
+            if due {
+                let started_at = crate::utils::time::now_unix_millis();
+                match sync(cx, &store, &sticker_events_tx, &folder, synced_since).await {
+                    Ok(changed) => {
+                        synced_since = started_at;
+                        if changed {
+                            let _ = sticker_events_tx.send(StickerWindowEvent::StoreChanged);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::warn!(folder = %folder.display(), error = ?err, "Failed to sync folder");
+                    }
+                }
+                last_sync = Some(Instant::now());
+            }
+        }
+
+        cx.background_executor().timer(CHECK_INTERVAL).await;
+    }
+}
+
+fn watch(folder: &Path, changed: Arc<AtomicBool>) -> anyhow::Result<notify::RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|event| !event.kind.is_access()) {
+            changed.store(true, Ordering::Relaxed);
+        }
+    })?;
+    notify::Watcher::watch(&mut watcher, folder, notify::RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// One pass over stickers and files, returns whether any sticker changed. Stickers not
+/// updated since `since` are only loaded when their file changed.
+async fn sync(
+    cx: &mut AsyncApp,
+    store: &ArcStickerStore,
+    sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+    folder: &Path,
+    since: i64,
+) -> anyhow::Result<bool> {
+    let folder_key = folder.to_string_lossy().to_string();
+    let mut synced = store
+        .get_synced_files(folder_key.clone())
+        .await?
+        .into_iter()
+        .map(|x| (x.sticker_id, x))
+        .collect::<HashMap<_, _>>();
+
+    if !folder.is_dir() {
+        // An unmounted drive must not look like every file was deleted.
+        anyhow::ensure!(synced.is_empty(), "{} is missing", folder.display());
+        std::fs::create_dir_all(folder)
+            .with_context(|| format!("create directory {}", folder.display()))?;
+    }
+    let files = list_files(folder)?;
+    if files.is_empty() && !synced.is_empty() {
+        anyhow::bail!("{} is empty, not deleting every sticker", folder.display());
+    }
+
+    let ids = store.get_sticker_update_times().await?;
+    let mut updated = store
+        .get_stickers_updated_since(since)
+        .await?
+        .into_iter()
+        .map(|x| (x.id, x))
+        .collect::<HashMap<_, _>>();
+
+    let mut changed = false;
+    let mut claimed = HashSet::new();
+    for (id, _) in ids {
+        let Some(file) = synced.remove(&id) else {
+            let detail = match updated.remove(&id) {
+                Some(detail) => detail,
+                None => store.get_sticker(id).await?,
+            };
+            let body = file_body(&detail);
+            let file_name = unique_file_name(&detail, &files, &claimed);
+            write_file(folder, &file_name, &body)?;
+            claimed.insert(file_name.clone());
+            store
+                .set_synced_file(SyncedFile {
+                    folder: folder_key.clone(),
+                    sticker_id: id,
+                    file_name,
+                    hash: hash(&body),
+                })
+                .await?;
+            continue;
+        };
+        claimed.insert(file.file_name.clone());
+
+        let path = folder.join(&file.file_name);
+        let on_disk = match std::fs::read_to_string(&path) {
+            Ok(on_disk) => on_disk,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                store.move_sticker(id, StickerBucket::Trash).await?;
+                cx.update(|cx| StickerWindow::try_close(id, cx))?;
+                store.delete_synced_file(folder_key.clone(), id).await?;
+                tracing::info!(id, file = %file.file_name, "Synced file deleted, sticker trashed");
+                changed = true;
+                continue;
+            }
+            Err(err) => return Err(err).with_context(|| format!("read {}", path.display())),
+        };
+        let disk_hash = hash(&on_disk);
+
+        let detail = match updated.remove(&id) {
+            Some(detail) => detail,
+            // Neither side changed since the last sync.
+            None if disk_hash == file.hash => continue,
+            None => store.get_sticker(id).await?,
+        };
+        let body = file_body(&detail);
+        let local_hash = hash(&body);
+
+        // The hash both sides agree on after this sync.
+        let agreed = match decide(&local_hash, &disk_hash, &file.hash) {
+            Decision::InSync => disk_hash,
+            Decision::TakeFile => match apply_file(
+                cx,
+                store,
+                sticker_events_tx,
+                &detail,
+                &file.file_name,
+                &on_disk,
+            )
+            .await
+            {
+                Ok(true) => {
+                    changed = true;
+                    disk_hash
+                }
+                // Try again on the next sync.
+                Ok(false) => continue,
+                Err(err) => {
+                    tracing::warn!(file = %file.file_name, error = ?err, "Failed to apply synced file");
+                    continue;
+                }
+            },
+            decision => {
+                if decision == Decision::Conflict {
+                    let copy = conflict_file_name(&file.file_name);
+                    write_file(folder, &copy, &on_disk)?;
+                    tracing::warn!(id, file = %copy, "Sticker and synced file both changed, kept the file as conflict copy");
+                }
+                write_file(folder, &file.file_name, &body)?;
+                local_hash
+            }
+        };
+
+        if agreed != file.hash {
+            store
+                .set_synced_file(SyncedFile {
+                    hash: agreed,
+                    ..file
+                })
+                .await?;
+        }
+    }
+
+    // Whatever is left belongs to stickers which were trashed or deleted here.
+    for (sticker_id, file) in synced {
+        claimed.insert(file.file_name.clone());
+        match std::fs::remove_file(folder.join(&file.file_name)) {
+            Ok(()) => tracing::info!(sticker_id, file = %file.file_name, "Synced file deleted"),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err).with_context(|| format!("delete {}", file.file_name));
+            }
+        }
+        store
+            .delete_synced_file(folder_key.clone(), sticker_id)
+            .await?;
+    }
+
+    for file_name in files.difference(&claimed) {
+        let path = folder.join(file_name);
+        let on_disk =
+            std::fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        match import_file(store, file_name, &on_disk).await {
+            Ok(sticker_id) => {
+                store
+                    .set_synced_file(SyncedFile {
+                        folder: folder_key.clone(),
+                        sticker_id,
+                        file_name: file_name.clone(),
+                        hash: hash(&on_disk),
+                    })
+                    .await?;
+                tracing::info!(id = sticker_id, file = %file_name, "Sticker created from synced file");
+                let _ = sticker_events_tx.send(StickerWindowEvent::Created { id: sticker_id });
+                changed = true;
+            }
+            Err(err) => {
+                tracing::warn!(file = %file_name, error = ?err, "Failed to import synced file");
+            }
+        }
+    }
+
+    Ok(changed)
+}
+
+/// What a sync does with a sticker and its file, from the hashes of the sticker's file
+/// body, of the file on disk and of what both were at the last sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    InSync,
+    /// Only the file changed.
+    TakeFile,
+    /// Only the sticker changed.
+    WriteSticker,
+    /// Both changed, the sticker wins and the file is kept as a conflict copy.
+    Conflict,
+}
+
+fn decide(local_hash: &str, disk_hash: &str, synced_hash: &str) -> Decision {
+    if disk_hash == local_hash {
+        Decision::InSync
+    } else if local_hash == synced_hash {
+        Decision::TakeFile
+    } else if disk_hash == synced_hash {
+        Decision::WriteSticker
+    } else {
+        Decision::Conflict
+    }
+}
+
+/// Names of the sticker files in the folder, without hidden files and conflict copies.
+fn list_files(folder: &Path) -> anyhow::Result<HashSet<String>> {
+    let entries =
+        std::fs::read_dir(folder).with_context(|| format!("read {}", folder.display()))?;
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|x| x.is_file()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| {
+            !name.starts_with('.')
+                && !name.contains(CONFLICT_MARKER)
+                && (name.ends_with(".md") || name.ends_with(".json"))
+        })
+        .collect())
+}
+
+fn file_body(detail: &StickerDetail) -> String {
+    if detail.sticker_type == StickerType::Markdown {
+        return detail.content.clone();
+    }
+
+    let file = StickerFile {
+        sticker_type: detail.sticker_type,
+        title: detail.title.clone(),
+        color: detail.color.as_str().to_string(),
+        content: serde_json::from_str(&detail.content)
+            .unwrap_or_else(|_| serde_json::Value::String(detail.content.clone())),
+    };
+    let mut body = serde_json::to_string_pretty(&file).unwrap_or_default();
+    body.push('\n');
+    body
+}
+
+/// Sticker type, title, color and content of a file, the color is `None` for Markdown.
+fn parse_file(
+    file_name: &str,
+    body: &str,
+) -> anyhow::Result<(StickerType, String, Option<StickerColor>, String)> {
+    if file_name.ends_with(".md") {
+        let title = body
+            .lines()
+            .find(|x| !x.trim().is_empty())
+            .unwrap_or("")
+            .to_string();
+        return Ok((StickerType::Markdown, title, None, body.to_string()));
+    }
+
+    let file = serde_json::from_str::<StickerFile>(body).context("parse sticker file")?;
+    let content = match file.content {
+        serde_json::Value::String(content) => content,
+        serde_json::Value::Null => String::new(),
+        content => content.to_string(),
+    };
+    let color = file.color.parse::<StickerColor>().ok();
+    Ok((file.sticker_type, file.title, color, content))
+}
+
+/// Take the edited file into the sticker, false when the open window refused it.
+async fn apply_file(
+    cx: &mut AsyncApp,
+    store: &ArcStickerStore,
+    sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+    detail: &StickerDetail,
+    file_name: &str,
+    body: &str,
+) -> anyhow::Result<bool> {
+    let (sticker_type, title, color, content) = parse_file(file_name, body)?;
+    anyhow::ensure!(
+        sticker_type == detail.sticker_type,
+        "the file is a {} sticker, the sticker a {} one",
+        sticker_type.as_str(),
+        detail.sticker_type.as_str()
+    );
+    let id = detail.id;
+    let content_changed = content_changed(sticker_type, &content, &detail.content);
+
+    // Only Markdown stickers take content while open, the others are reopened below.
+    let reopen = if sticker_type == StickerType::Markdown {
+        if cx.update(|cx| StickerWindow::try_set_content(id, &content, cx))? == Some(false) {
+            tracing::debug!(id, "Sticker is being edited, synced file postponed");
+            return Ok(false);
+        }
+        false
+    } else {
+        content_changed && cx.update(|cx| StickerWindow::try_close(id, cx))?
+    };
+
+    if title != detail.title {
+        store.update_sticker_title(id, title.clone()).await?;
+        let _ = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title });
+    }
+    if let Some(color) = color.filter(|x| *x != detail.color) {
+        store
+            .update_sticker_color(id, color.as_str().to_string())
+            .await?;
+        cx.update(|cx| StickerWindow::try_set_color(id, color, cx))?;
+    }
+    if content_changed {
+        store.update_sticker_content(id, content).await?;
+    }
+    if reopen {
+        StickerWindow::open_async(cx, sticker_events_tx.clone(), store.clone(), id).await?;
+    }
+
+    tracing::info!(id, file = %file_name, "Sticker updated from synced file");
+    Ok(true)
+}
+
+/// JSON content is compared by value, the file has its keys in another order than the
+/// stored content.
+fn content_changed(sticker_type: StickerType, content: &str, stored: &str) -> bool {
+    if sticker_type == StickerType::Markdown {
+        return content != stored;
+    }
+    match (
+        serde_json::from_str::<serde_json::Value>(content),
+        serde_json::from_str::<serde_json::Value>(stored),
+    ) {
+        (Ok(content), Ok(stored)) => content != stored,
+        _ => content != stored,
+    }
+}
+
+async fn import_file(store: &ArcStickerStore, file_name: &str, body: &str) -> anyhow::Result<i64> {
+    let (sticker_type, title, color, content) = parse_file(file_name, body)?;
+    let (width, height) = match sticker_type {
+        StickerType::Markdown => {
+            let size = MarkdownSticker::default_window_size();
+            (size.width, size.height)
+        }
+        _ => IMPORTED_SIZE,
+    };
+
+    store
+        .insert_sticker(StickerDetail {
+            id: 0,
+            title,
+            state: StickerState::Close,
+            left: 100,
+            top: 100,
+            width,
+            height,
+            top_most: false,
+            color: color.unwrap_or(StickerColor::Yellow),
+            sticker_type,
+            content,
+            settings: String::new(),
+            temporary: false,
+            expires_at: None,
+            created_at: 0,
+            updated_at: 0,
+        })
+        .await
+}
+
+/// `<title slug>-<id>.<md|json>`, the id keeps stickers with the same title apart.
+fn unique_file_name(
+    detail: &StickerDetail,
+    files: &HashSet<String>,
+    claimed: &HashSet<String>,
+) -> String {
+    let slug = detail
+        .title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .take(MAX_SLUG_CHARS)
+        .collect::<String>();
+    let slug = slug.trim_end_matches('-');
+    let stem = match slug {
+        "" => format!("{}-{}", detail.sticker_type.as_str(), detail.id),
+        slug => format!("{slug}-{}", detail.id),
+    };
+    let extension = match detail.sticker_type {
+        StickerType::Markdown => "md",
+        _ => "json",
+    };
+
+    // Another machine may have used the id for a different sticker.
+    let mut file_name = format!("{stem}.{extension}");
+    let mut n = 2;
+    while files.contains(&file_name) || claimed.contains(&file_name) {
+        file_name = format!("{stem}-{n}.{extension}");
+        n += 1;
+    }
+    file_name
+}
+
+fn conflict_file_name(file_name: &str) -> String {
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    match file_name.rsplit_once('.') {
+        Some((stem, extension)) => format!("{stem}{CONFLICT_MARKER}{stamp}.{extension}"),
+        None => format!("{file_name}{CONFLICT_MARKER}{stamp}"),
+    }
+}
+
+/// Written next to the target and renamed over it, so a sync tool never picks up half
+/// a file.
+fn write_file(folder: &Path, file_name: &str, body: &str) -> anyhow::Result<()> {
+    let path = folder.join(file_name);
+    let temp = folder.join(format!(".{file_name}.tmp"));
+    std::fs::write(&temp, body).with_context(|| format!("write {}", temp.display()))?;
+    std::fs::rename(&temp, &path).with_context(|| format!("write {}", path.display()))
+}
+
+fn hash(body: &str) -> String {
+    Sha256::digest(body.as_bytes())
+        .iter()
+        .map(|x| format!("{x:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detail(id: i64, sticker_type: StickerType, title: &str, content: &str) -> StickerDetail {
+        StickerDetail {
+            id,
+            title: title.to_string(),
+            state: StickerState::Close,
+            left: 0,
+            top: 0,
+            width: 300,
+            height: 300,
+            top_most: false,
+            color: StickerColor::Green,
+            sticker_type,
+            content: content.to_string(),
+            settings: String::new(),
+            temporary: false,
+            expires_at: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn file_names() {
+        let none = HashSet::new();
+        let note = detail(3, StickerType::Markdown, "Hello, World!", "");
+        assert_eq!(unique_file_name(&note, &none, &none), "hello-world-3.md");
+
+        let timer = detail(7, StickerType::Timer, "  ", "");
+        assert_eq!(unique_file_name(&timer, &none, &none), "timer-7.json");
+
+        let long = detail(1, StickerType::Markdown, &"a ".repeat(30), "");
+        let name = unique_file_name(&long, &none, &none);
+        assert!(name.starts_with("a-a-"));
+        assert!(name.ends_with("a-1.md"));
+
+        let files = HashSet::from(["hello-world-3.md".to_string()]);
+        let claimed = HashSet::from(["hello-world-3-2.md".to_string()]);
+        assert_eq!(
+            unique_file_name(&note, &files, &claimed),
+            "hello-world-3-3.md"
+        );
+    }
+
+    #[test]
+    fn markdown_round_trip() {
+        let note = detail(1, StickerType::Markdown, "", "\n# Groceries\n- milk\n");
+        let (sticker_type, title, color, content) =
+            parse_file("groceries-1.md", &file_body(&note)).unwrap();
+        assert_eq!(sticker_type, StickerType::Markdown);
+        assert_eq!(title, "# Groceries");
+        assert_eq!(color, None);
+        assert_eq!(content, note.content);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let counter = detail(2, StickerType::Counter, "Cups", r#"{"value":3,"step":1}"#);
+        let (sticker_type, title, color, content) =
+            parse_file("cups-2.json", &file_body(&counter)).unwrap();
+        assert_eq!(sticker_type, StickerType::Counter);
+        assert_eq!(title, "Cups");
+        assert_eq!(color, Some(StickerColor::Green));
+        assert!(!content_changed(sticker_type, &content, &counter.content));
+        assert!(content_changed(
+            sticker_type,
+            r#"{"value":4,"step":1}"#,
+            &counter.content
+        ));
+
+        // Content which is no JSON is kept as a string.
+        let code = detail(4, StickerType::Code, "Snippet", "fn main() {}");
+        let (_, _, _, content) = parse_file("snippet-4.json", &file_body(&code)).unwrap();
+        assert_eq!(content, code.content);
+
+        assert!(parse_file("broken.json", "{").is_err());
+    }
+
+    #[test]
+    fn markdown_compared_as_text() {
+        assert!(content_changed(StickerType::Markdown, "1\n", "1"));
+        assert!(!content_changed(StickerType::Markdown, "1", "1"));
+    }
+
+    #[test]
+    fn decisions() {
+        assert_eq!(decide("a", "a", "a"), Decision::InSync);
+        // Both sides made the same edit.
+        assert_eq!(decide("b", "b", "a"), Decision::InSync);
+        assert_eq!(decide("a", "b", "a"), Decision::TakeFile);
+        assert_eq!(decide("b", "a", "a"), Decision::WriteSticker);
+        assert_eq!(decide("b", "c", "a"), Decision::Conflict);
+    }
+}
@@ -0,0 +1,249 @@
+//! User scripts run on sticker lifecycle events, e.g. to append a line to a log or call
+//! a webhook with curl, without changing Rustickers itself.
+//!
+//! The hook is a command line like those of command stickers. The event is described
+//! in `RUSTICKERS_*` environment variables and the sticker content is written to its
+//! stdin. Nothing runs in safe mode. A hook running longer than [`HOOK_TIMEOUT`] is
+//! killed.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+use std::io::{Read as _, Write as _};
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use gpui::{App, Global};
+
+use crate::native::windows::StickerWindowEvent;
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::{HookSettings, SettingsStore};
+
+const HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+/// Stickers save while typing, `on_save` runs once the edits settle.
+const SAVE_HOOK_DEBOUNCE: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Per sticker, the latest save and the content `on_save` last ran with.
+#[derive(Default)]
+struct SaveHooks {
+    generations: HashMap<i64, u64>,
+    content_hashes: HashMap<i64, u64>,
+}
+
+impl Global for SaveHooks {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    Create,
+    Save,
+    Close,
+    TimerFinish,
+    CommandDone,
+}
+
+impl Hook {
+    pub const ALL: [Self; 5] = [
+        Self::Create,
+        Self::Save,
+        Self::Close,
+        Self::TimerFinish,
+        Self::CommandDone,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Create => "on_create",
+            Self::Save => "on_save",
+            Self::Close => "on_close",
+            Self::TimerFinish => "on_timer_finish",
+            Self::CommandDone => "on_command_done",
+        }
+    }
+
+    pub fn command(self, hooks: &HookSettings) -> &str {
+        match self {
+            Self::Create => &hooks.on_create,
+            Self::Save => &hooks.on_save,
+            Self::Close => &hooks.on_close,
+            Self::TimerFinish => &hooks.on_timer_finish,
+            Self::CommandDone => &hooks.on_command_done,
+        }
+    }
+
+    pub fn set_command(self, hooks: &mut HookSettings, command: String) {
+        match self {
+            Self::Create => hooks.on_create = command,
+            Self::Save => hooks.on_save = command,
+            Self::Close => hooks.on_close = command,
+            Self::TimerFinish => hooks.on_timer_finish = command,
+            Self::CommandDone => hooks.on_command_done = command,
+        }
+    }
+
+    /// The hook of an event with its sticker id and extra environment variables, `None`
+    /// for events without a hook.
+    fn of_event(event: &StickerWindowEvent) -> Option<(Self, i64, Vec<(&'static str, String)>)> {
+        match event {
+            StickerWindowEvent::Created { id } => Some((Self::Create, *id, Vec::new())),
+            StickerWindowEvent::ContentChanged { id, .. } => Some((Self::Save, *id, Vec::new())),
+            StickerWindowEvent::Closed { id } => Some((Self::Close, *id, Vec::new())),
+            StickerWindowEvent::TimerFinished { id } => Some((Self::TimerFinish, *id, Vec::new())),
+            StickerWindowEvent::CommandDone { id, exit_code, .. } => Some((
+                Self::CommandDone,
+                *id,
+                // Empty when the command was killed.
+                vec![(
+                    "RUSTICKERS_EXIT_CODE",
+                    exit_code.map(|x| x.to_string()).unwrap_or_default(),
+                )],
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Run the user script of the event, if one is set.
+pub fn run_for_event(event: &StickerWindowEvent, store: &ArcStickerStore, cx: &mut App) {
+    let Some((hook, id, env)) = Hook::of_event(event) else {
+        return;
+    };
+    if hook == Hook::Close && cx.has_global::<SaveHooks>() {
+        let save_hooks = cx.global_mut::<SaveHooks>();
+        save_hooks.generations.remove(&id);
+        save_hooks.content_hashes.remove(&id);
+    }
+    let settings = SettingsStore::get(cx);
+    let command = hook.command(&settings.hooks).trim().to_string();
+    if command.is_empty() || settings.safe_mode {
+        return;
+    }
+
+    let generation = (hook == Hook::Save).then(|| {
+        let generation = cx
+            .default_global::<SaveHooks>()
+            .generations
+            .entry(id)
+            .or_default();
+        *generation = generation.wrapping_add(1);
+        *generation
+    });
+
+    let store = store.clone();
+    cx.spawn(async move |cx| {
+        if let Some(generation) = generation {
+            cx.background_executor().timer(SAVE_HOOK_DEBOUNCE).await;
+            let latest = cx.update(|cx| {
+                cx.try_global::<SaveHooks>()
+                    .and_then(|x| x.generations.get(&id).copied())
+            });
+            if !matches!(latest, Ok(Some(latest)) if latest == generation) {
+                return;
+            }
+        }
+
+        let detail = match store.get_sticker(id).await {
+            Ok(detail) => detail,
+            Err(err) => {
+                tracing::warn!(id, hook = hook.name(), error = ?err, "Failed to load sticker for hook");
+                return;
+            }
+        };
+        if hook == Hook::Save {
+            // Polling stickers save the same content again, only changes count.
+            let mut hasher = DefaultHasher::new();
+            detail.content.hash(&mut hasher);
+            let content_hash = hasher.finish();
+            let unchanged = cx.update(|cx| {
+                cx.default_global::<SaveHooks>()
+                    .content_hashes
+                    .insert(id, content_hash)
+                    == Some(content_hash)
+            });
+            if unchanged.unwrap_or(true) {
+                return;
+            }
+        }
+
+        let mut env = env;
+        env.extend([
+            ("RUSTICKERS_HOOK", hook.name().to_string()),
+            ("RUSTICKERS_STICKER_ID", id.to_string()),
+            ("RUSTICKERS_STICKER_TITLE", detail.title),
+            ("RUSTICKERS_STICKER_TYPE", detail.sticker_type.as_str().to_string()),
+            ("RUSTICKERS_STICKER_COLOR", detail.color.as_str().to_string()),
+        ]);
+        let content = detail.content;
+        let ran = cx
+            .background_executor()
+            .spawn(async move { run_command(&command, &env, &content) })
+            .await;
+        if let Err(err) = ran {
+            tracing::warn!(id, hook = hook.name(), error = ?err, "Hook failed");
+        }
+    })
+    .detach();
+}
+
+fn run_command(command: &str, env: &[(&str, String)], stdin: &str) -> anyhow::Result<()> {
+    let mut args = winsplit::split(command);
+    anyhow::ensure!(!args.is_empty(), "Command cannot be empty");
+
+    let program = args.remove(0);
+    let path =
+        which::which(&program).map_err(|_| anyhow::anyhow!("Command not found: {program}"))?;
+
+    let mut cmd = Command::new(path);
+    cmd.args(args)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = cmd.spawn().context("start hook")?;
+    // Written from its own thread, a hook which prints a lot before reading its stdin
+    // would otherwise wait for us while we wait for it.
+    if let Some(mut input) = child.stdin.take() {
+        let stdin = stdin.to_string();
+        std::thread::spawn(move || {
+            // A script which does not read its stdin closes it early, that is fine.
+            let _ = input.write_all(stdin.as_bytes());
+        });
+    }
+    let (stderr_tx, stderr_rx) = mpsc::channel();
+    if let Some(mut output) = child.stderr.take() {
+        std::thread::spawn(move || {
+            let mut stderr = Vec::new();
+            let _ = output.read_to_end(&mut stderr);
+            let _ = stderr_tx.send(stderr);
+        });
+    }
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().context("wait for hook")? {
+            break status;
+        }
+        if started.elapsed() >= HOOK_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("killed after {} seconds", HOOK_TIMEOUT.as_secs());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+    if !status.success() {
+        // A process started by the hook may keep stderr open, do not wait for it long.
+        let stderr = stderr_rx.recv_timeout(POLL_INTERVAL).unwrap_or_default();
+        anyhow::bail!("{status}: {}", String::from_utf8_lossy(&stderr).trim());
+    }
+    Ok(())
+}
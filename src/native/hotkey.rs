@@ -1,7 +1,56 @@
-use std::sync::{Arc, Mutex, mpsc::Sender};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicI64, AtomicU8, Ordering},
+    mpsc::Sender,
+};
+use std::time::Duration;
 
 use crate::ipc::IpcEvent;
 
+/// Unix millis of the last keyboard or mouse input anywhere on the desktop, 0 before any.
+static LAST_INPUT_AT: AtomicI64 = AtomicI64::new(0);
+static ACTIVE_BACKEND: AtomicU8 = AtomicU8::new(HotkeyBackend::None as u8);
+
+/// What delivers the global hotkeys. On Linux `rdev` sees nothing under Wayland, so
+/// the desktop portal and X11 key grabs are tried before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HotkeyBackend {
+    None,
+    Portal,
+    X11,
+    Rdev,
+}
+
+impl HotkeyBackend {
+    pub fn label(self) -> &'static str {
+        match self {
+            HotkeyBackend::None => "Not running",
+            HotkeyBackend::Portal => "XDG desktop portal",
+            HotkeyBackend::X11 => "X11 key grabs",
+            HotkeyBackend::Rdev => "Global input listener (rdev)",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => HotkeyBackend::Portal,
+            2 => HotkeyBackend::X11,
+            3 => HotkeyBackend::Rdev,
+            _ => HotkeyBackend::None,
+        }
+    }
+}
+
+pub fn active_backend() -> HotkeyBackend {
+    HotkeyBackend::from_u8(ACTIVE_BACKEND.load(Ordering::Relaxed))
+}
+
+fn set_active_backend(backend: HotkeyBackend) {
+    tracing::info!(backend = backend.label(), "Global hotkey backend active");
+    ACTIVE_BACKEND.store(backend as u8, Ordering::Relaxed);
+}
+
 #[derive(Default, Debug, Clone, Copy)]
 struct KeyState {
     ctrl: bool,
@@ -9,6 +58,8 @@ struct KeyState {
     alt: bool,
     meta: bool,
     r_down: bool,
+    d_down: bool,
+    l_down: bool,
 }
 
 fn primary_modifier_down(state: KeyState) -> bool {
@@ -20,26 +71,63 @@ fn primary_modifier_down(state: KeyState) -> bool {
     }
 }
 
+/// How long there was no keyboard or mouse input, `None` when the listener never saw
+/// any, e.g. on Wayland where global input can not be observed.
+pub fn idle_duration() -> Option<Duration> {
+    let last = LAST_INPUT_AT.load(Ordering::Relaxed);
+    if last == 0 {
+        return None;
+    }
+    let idle = crate::utils::time::now_unix_millis().saturating_sub(last);
+    Some(Duration::from_millis(idle.max(0) as u64))
+}
+
 pub fn start_global_hotkey_listener(ipc_events_tx: Sender<IpcEvent>) -> anyhow::Result<()> {
     std::thread::Builder::new()
         .name("global-hotkey-listener".to_string())
         .spawn(move || {
             tracing::info!("Global hotkey listener started");
-            if let Err(err) = start_listen(ipc_events_tx) {
+
+            #[cfg(target_os = "linux")]
+            let native = linux::start_native_backend(&ipc_events_tx);
+            #[cfg(not(target_os = "linux"))]
+            let native = None;
+
+            // rdev keeps running next to a native backend to track idle time, without
+            // firing the hotkeys a second time.
+            let hotkeys = match native {
+                Some(backend) => {
+                    set_active_backend(backend);
+                    false
+                }
+                None => {
+                    set_active_backend(HotkeyBackend::Rdev);
+                    true
+                }
+            };
+            if let Err(err) = start_listen(ipc_events_tx, hotkeys) {
                 tracing::error!(error = %err, "Global hotkey listener stopped");
+                if hotkeys {
+                    set_active_backend(HotkeyBackend::None);
+                }
             }
         })?;
 
     Ok(())
 }
 
-fn start_listen(ipc_events_tx: Sender<IpcEvent>) -> anyhow::Result<()> {
+fn start_listen(ipc_events_tx: Sender<IpcEvent>, hotkeys: bool) -> anyhow::Result<()> {
     use rdev::{Event, EventType, Key, listen};
 
     let state = Arc::new(Mutex::new(KeyState::default()));
     let state_for_cb = state.clone();
 
     let callback = move |event: Event| {
+        LAST_INPUT_AT.store(crate::utils::time::now_unix_millis(), Ordering::Relaxed);
+        if !hotkeys {
+            return;
+        }
+
         let mut state = match state_for_cb.lock() {
             Ok(guard) => guard,
             Err(poisoned) => poisoned.into_inner(),
@@ -67,6 +155,24 @@ fn start_listen(ipc_events_tx: Sender<IpcEvent>) -> anyhow::Result<()> {
                             }
                         }
                     }
+                    Key::KeyD => {
+                        if !state.d_down {
+                            state.d_down = true;
+                            if state.alt && primary_modifier_down(*state) {
+                                tracing::debug!("Hotkey triggered: annotate");
+                                let _ = ipc_events_tx.send(IpcEvent::Annotate);
+                            }
+                        }
+                    }
+                    Key::KeyL => {
+                        if !state.l_down {
+                            state.l_down = true;
+                            if state.alt && primary_modifier_down(*state) {
+                                tracing::debug!("Hotkey triggered: lock");
+                                let _ = ipc_events_tx.send(IpcEvent::Lock);
+                            }
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -76,6 +182,8 @@ fn start_listen(ipc_events_tx: Sender<IpcEvent>) -> anyhow::Result<()> {
                 Key::ShiftLeft | Key::ShiftRight => state.shift = false,
                 Key::MetaLeft | Key::MetaRight => state.meta = false,
                 Key::KeyR => state.r_down = false,
+                Key::KeyD => state.d_down = false,
+                Key::KeyL => state.l_down = false,
                 _ => {}
             },
             _ => {}
@@ -84,3 +192,246 @@ fn start_listen(ipc_events_tx: Sender<IpcEvent>) -> anyhow::Result<()> {
 
     listen(callback).map_err(|err| anyhow::anyhow!("rdev listen failed: {err:?}"))
 }
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::sync::mpsc::{SendError, Sender, SyncSender};
+    use std::time::{Duration, Instant};
+
+    use anyhow::Context as _;
+
+    use super::HotkeyBackend;
+    use crate::ipc::IpcEvent;
+
+    /// The portal may ask the user to confirm the shortcuts first.
+    const BIND_TIMEOUT: Duration = Duration::from_secs(60);
+    /// Key repeat while a hotkey is held fires it only once.
+    const REPEAT_GAP: Duration = Duration::from_millis(250);
+
+    struct Hotkey {
+        id: &'static str,
+        description: &'static str,
+        /// In the shortcut syntax of the XDG specification.
+        trigger: &'static str,
+        /// The X11 keysym of the key pressed together with Ctrl and Alt.
+        keysym: u32,
+    }
+
+    const HOTKEYS: [Hotkey; 3] = [
+        Hotkey {
+            id: "show",
+            description: "Show the main window",
+            trigger: "CTRL+ALT+r",
+            keysym: 0x72,
+        },
+        Hotkey {
+            id: "annotate",
+            description: "Draw on the screen",
+            trigger: "CTRL+ALT+d",
+            keysym: 0x64,
+        },
+        Hotkey {
+            id: "lock",
+            description: "Lock the app",
+            trigger: "CTRL+ALT+l",
+            keysym: 0x6c,
+        },
+    ];
+
+    fn hotkey_event(id: &str) -> Option<IpcEvent> {
+        match id {
+            "show" => Some(IpcEvent::Show),
+            "annotate" => Some(IpcEvent::Annotate),
+            "lock" => Some(IpcEvent::Lock),
+            _ => None,
+        }
+    }
+
+    /// The desktop portal under Wayland, X11 key grabs under X11, `None` when neither
+    /// could bind the hotkeys.
+    pub fn start_native_backend(ipc_events_tx: &Sender<IpcEvent>) -> Option<HotkeyBackend> {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            match spawn_backend("global-hotkey-portal", ipc_events_tx.clone(), listen_portal) {
+                Ok(()) => return Some(HotkeyBackend::Portal),
+                Err(err) => {
+                    tracing::warn!(error = %format!("{err:#}"), "Global shortcuts portal unavailable")
+                }
+            }
+        }
+        if std::env::var_os("DISPLAY").is_some() {
+            match spawn_backend("global-hotkey-x11", ipc_events_tx.clone(), listen_x11) {
+                Ok(()) => return Some(HotkeyBackend::X11),
+                Err(err) => {
+                    tracing::warn!(error = %format!("{err:#}"), "X11 key grabs unavailable")
+                }
+            }
+        }
+        None
+    }
+
+    /// Run a backend on its own thread and wait until it reports whether the hotkeys
+    /// are bound.
+    fn spawn_backend(
+        name: &'static str,
+        ipc_events_tx: Sender<IpcEvent>,
+        listen: fn(Sender<IpcEvent>, &SyncSender<anyhow::Result<()>>) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let (ready_tx, ready_rx) = std::sync::mpsc::sync_channel(1);
+        std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                if let Err(err) = listen(ipc_events_tx, &ready_tx) {
+                    // Nobody waits any more once the hotkeys were bound.
+                    if let Err(SendError(Err(err))) = ready_tx.send(Err(err)) {
+                        tracing::error!(backend = name, error = %format!("{err:#}"), "Global hotkey listener stopped");
+                    }
+                }
+            })?;
+
+        ready_rx
+            .recv_timeout(BIND_TIMEOUT)
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("{name} did not bind the hotkeys in time")))
+    }
+
+    fn listen_portal(
+        ipc_events_tx: Sender<IpcEvent>,
+        ready_tx: &SyncSender<anyhow::Result<()>>,
+    ) -> anyhow::Result<()> {
+        use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+        use futures::StreamExt as _;
+
+        futures::executor::block_on(async move {
+            let portal = GlobalShortcuts::new()
+                .await
+                .context("connect to the GlobalShortcuts portal")?;
+            let session = portal
+                .create_session()
+                .await
+                .context("create a global shortcuts session")?;
+            let shortcuts = HOTKEYS
+                .iter()
+                .map(|hotkey| {
+                    NewShortcut::new(hotkey.id, hotkey.description)
+                        .preferred_trigger(hotkey.trigger)
+                })
+                .collect::<Vec<_>>();
+            let bound = portal
+                .bind_shortcuts(&session, &shortcuts, None)
+                .await
+                .context("bind global shortcuts")?
+                .response()
+                .context("bind global shortcuts")?;
+            if bound.shortcuts().is_empty() {
+                anyhow::bail!("the portal bound no shortcuts");
+            }
+            for shortcut in bound.shortcuts() {
+                tracing::info!(
+                    id = shortcut.id(),
+                    trigger = shortcut.trigger_description(),
+                    "Global shortcut bound"
+                );
+            }
+
+            let mut activated = portal
+                .receive_activated()
+                .await
+                .context("listen for global shortcuts")?;
+            let _ = ready_tx.send(Ok(()));
+
+            while let Some(event) = activated.next().await {
+                if let Some(ipc_event) = hotkey_event(event.shortcut_id()) {
+                    tracing::debug!(id = event.shortcut_id(), "Hotkey triggered");
+                    let _ = ipc_events_tx.send(ipc_event);
+                }
+            }
+            anyhow::bail!("the portal closed the global shortcuts session")
+        })
+    }
+
+    fn listen_x11(
+        ipc_events_tx: Sender<IpcEvent>,
+        ready_tx: &SyncSender<anyhow::Result<()>>,
+    ) -> anyhow::Result<()> {
+        use x11rb::connection::Connection as _;
+        use x11rb::protocol::Event;
+        use x11rb::protocol::xproto::{ConnectionExt as _, GrabMode, ModMask};
+
+        let (conn, screen) = x11rb::connect(None).context("connect to the X server")?;
+        let setup = conn.setup();
+        let root = setup.roots[screen].root;
+        let (min_keycode, max_keycode) = (setup.min_keycode, setup.max_keycode);
+        let mapping = conn
+            .get_keyboard_mapping(min_keycode, max_keycode - min_keycode + 1)?
+            .reply()
+            .context("read the keyboard mapping")?;
+        let keysyms_per_keycode = (mapping.keysyms_per_keycode as usize).max(1);
+        let keycode_of = |keysym: u32| {
+            mapping
+                .keysyms
+                .chunks(keysyms_per_keycode)
+                .position(|keysyms| keysyms.contains(&keysym))
+                .map(|index| min_keycode + index as u8)
+        };
+
+        let mut grabbed = Vec::new();
+        for hotkey in &HOTKEYS {
+            let Some(keycode) = keycode_of(hotkey.keysym) else {
+                tracing::warn!(
+                    id = hotkey.id,
+                    "No key for the hotkey in the keyboard layout"
+                );
+                continue;
+            };
+            // Caps Lock and Num Lock are modifiers to X11, grab the hotkey with each of them.
+            for locks in [
+                ModMask::from(0u16),
+                ModMask::LOCK,
+                ModMask::M2,
+                ModMask::LOCK | ModMask::M2,
+            ] {
+                conn.grab_key(
+                    false,
+                    root,
+                    ModMask::CONTROL | ModMask::M1 | locks,
+                    keycode,
+                    GrabMode::ASYNC,
+                    GrabMode::ASYNC,
+                )?
+                .check()
+                .with_context(|| {
+                    format!("grab {}, another application may own it", hotkey.trigger)
+                })?;
+            }
+            grabbed.push((keycode, hotkey.id));
+        }
+        if grabbed.is_empty() {
+            anyhow::bail!("no hotkey could be grabbed");
+        }
+        conn.flush()?;
+        let _ = ready_tx.send(Ok(()));
+
+        let mut last_press: Option<(u8, Instant)> = None;
+        loop {
+            let Event::KeyPress(press) = conn.wait_for_event().context("read X11 events")? else {
+                continue;
+            };
+            let Some((_, id)) = grabbed.iter().find(|(keycode, _)| *keycode == press.detail) else {
+                continue;
+            };
+
+            let now = Instant::now();
+            let repeated = last_press.is_some_and(|(keycode, at)| {
+                keycode == press.detail && now.duration_since(at) < REPEAT_GAP
+            });
+            last_press = Some((press.detail, now));
+            if repeated {
+                continue;
+            }
+
+            if let Some(ipc_event) = hotkey_event(id) {
+                tracing::debug!(id, "Hotkey triggered");
+                let _ = ipc_events_tx.send(ipc_event);
+            }
+        }
+    }
+}
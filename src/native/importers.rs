@@ -0,0 +1,428 @@
+//! Notes of other sticky note apps turned into Markdown stickers, so switching does not
+//! mean copying them by hand.
+//!
+//! - Microsoft Sticky Notes keeps its notes in `plum.sqlite`, found in
+//!   `%LOCALAPPDATA%\Packages\Microsoft.MicrosoftStickyNotes_8wekyb3d8bbwe\LocalState`.
+//! - Google Keep is read from the `Keep` folder of an extracted Takeout export, one
+//!   `.json` per note. Trashed notes are skipped, archived ones go to the archive and
+//!   labels become tags. Attached images are not imported.
+//!
+//! Colors and the created/updated times of the notes are kept.
+
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions as _, Row as _};
+
+use crate::model::sticker::*;
+use crate::native::components::stickers::Sticker;
+use crate::native::components::stickers::markdown::MarkdownSticker;
+use crate::native::windows::StickerWindowEvent;
+use crate::storage::ArcStickerStore;
+
+/// .NET ticks (100 ns since 0001-01-01) at the unix epoch.
+const TICKS_AT_UNIX_EPOCH: i64 = 621_355_968_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteSource {
+    StickyNotes,
+    GoogleKeep,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedNote {
+    pub content: String,
+    pub color: StickerColor,
+    pub tags: Vec<String>,
+    pub archived: bool,
+    /// Unix millis, 0 when unknown.
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Notes of a Microsoft Sticky Notes `plum.sqlite`, deleted ones are skipped.
+pub async fn read_sticky_notes(db_path: &Path) -> anyhow::Result<Vec<ImportedNote>> {
+    // Read only, Sticky Notes may be running and has the database open.
+    let mut conn = SqliteConnectOptions::new()
+        .filename(db_path)
+        .read_only(true)
+        .connect()
+        .await
+        .context("open Sticky Notes database")?;
+
+    // Columns differ between versions, so every one of them is optional.
+    let rows = sqlx::query("SELECT * FROM Note")
+        .fetch_all(&mut conn)
+        .await
+        .context("read Sticky Notes notes")?;
+
+    let notes = rows
+        .iter()
+        .filter(|row| {
+            row.try_get::<Option<i64>, _>("DeletedAt")
+                .ok()
+                .flatten()
+                .is_none_or(|x| x == 0)
+        })
+        .filter_map(|row| {
+            let text = row.try_get::<Option<String>, _>("Text").ok().flatten()?;
+            let content = sticky_notes_text(&text);
+            if content.trim().is_empty() {
+                return None;
+            }
+            let theme = row
+                .try_get::<Option<String>, _>("Theme")
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            let ticks = |column: &str| {
+                row.try_get::<Option<i64>, _>(column)
+                    .ok()
+                    .flatten()
+                    .map(ticks_to_unix_millis)
+                    .unwrap_or(0)
+            };
+            let created_at = ticks("CreatedAt");
+            Some(ImportedNote {
+                content,
+                color: sticky_notes_color(&theme),
+                tags: Vec::new(),
+                archived: false,
+                created_at,
+                updated_at: ticks("UpdatedAt").max(created_at),
+            })
+        })
+        .collect();
+    Ok(notes)
+}
+
+/// Every paragraph of a Sticky Notes text starts with `\id=<guid> `, which is dropped.
+fn sticky_notes_text(text: &str) -> String {
+    text.lines()
+        .map(|line| match line.strip_prefix("\\id=") {
+            Some(rest) => rest.split_once(' ').map(|(_, x)| x).unwrap_or(""),
+            None => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn sticky_notes_color(theme: &str) -> StickerColor {
+    match theme.to_lowercase().as_str() {
+        "green" => StickerColor::Green,
+        "blue" => StickerColor::Blue,
+        "pink" | "purple" => StickerColor::Pink,
+        "gray" | "charcoal" => StickerColor::Gray,
+        _ => StickerColor::Yellow,
+    }
+}
+
+fn ticks_to_unix_millis(ticks: i64) -> i64 {
+    if ticks <= TICKS_AT_UNIX_EPOCH {
+        return 0;
+    }
+    (ticks - TICKS_AT_UNIX_EPOCH) / 10_000
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct KeepNote {
+    color: String,
+    is_trashed: bool,
+    is_archived: bool,
+    title: String,
+    text_content: String,
+    list_content: Vec<KeepListItem>,
+    labels: Vec<KeepLabel>,
+    created_timestamp_usec: i64,
+    user_edited_timestamp_usec: i64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct KeepListItem {
+    text: String,
+    is_checked: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KeepLabel {
+    name: String,
+}
+
+/// Notes of the `Keep` folder of a Google Takeout export. Files which are not notes,
+/// e.g. `Labels.txt` or images, are skipped.
+pub fn read_keep_folder(folder: &Path) -> anyhow::Result<Vec<ImportedNote>> {
+    let mut paths = fs::read_dir(folder)
+        .with_context(|| format!("read folder {}", folder.display()))?
+        .filter_map(|entry| entry.ok().map(|x| x.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|x| x.eq_ignore_ascii_case("json"))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut notes = Vec::new();
+    for path in paths {
+        let note = match fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|body| Ok(serde_json::from_str::<KeepNote>(&body)?))
+        {
+            Ok(note) => note,
+            Err(err) => {
+                tracing::warn!(path = %path.display(), error = ?err, "Skipping Keep file");
+                continue;
+            }
+        };
+        if note.is_trashed {
+            continue;
+        }
+        if let Some(note) = keep_note(note) {
+            notes.push(note);
+        }
+    }
+    Ok(notes)
+}
+
+fn keep_note(note: KeepNote) -> Option<ImportedNote> {
+    let body = match note.list_content.is_empty() {
+        true => note.text_content.trim_end().to_string(),
+        false => note
+            .list_content
+            .iter()
+            .map(|item| {
+                let mark = if item.is_checked { "x" } else { " " };
+                format!("- [{mark}] {}", item.text.trim())
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    let title = note.title.trim();
+    let content = match (title.is_empty(), body.is_empty()) {
+        (true, true) => return None,
+        (true, false) => body,
+        (false, true) => format!("# {title}"),
+        (false, false) => format!("# {title}\n\n{body}"),
+    };
+
+    let created_at = note.created_timestamp_usec / 1000;
+    Some(ImportedNote {
+        content,
+        color: keep_color(&note.color),
+        tags: note
+            .labels
+            .into_iter()
+            .map(|x| x.name.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect(),
+        archived: note.is_archived,
+        created_at,
+        updated_at: (note.user_edited_timestamp_usec / 1000).max(created_at),
+    })
+}
+
+fn keep_color(color: &str) -> StickerColor {
+    match color {
+        "GREEN" | "TEAL" => StickerColor::Green,
+        "BLUE" | "CERULEAN" | "PURPLE" => StickerColor::Blue,
+        "RED" | "ORANGE" | "PINK" => StickerColor::Pink,
+        "GRAY" => StickerColor::Gray,
+        _ => StickerColor::Yellow,
+    }
+}
+
+/// Create a closed Markdown sticker for every note, returns how many were created.
+pub async fn create_stickers(
+    store: &ArcStickerStore,
+    sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+    notes: Vec<ImportedNote>,
+) -> anyhow::Result<usize> {
+    let size = MarkdownSticker::default_window_size();
+    let mut created = 0;
+    for note in notes {
+        let title = note
+            .content
+            .lines()
+            .find(|x| !x.is_empty())
+            .unwrap_or("")
+            .to_string();
+        let id = store
+            .insert_sticker(StickerDetail {
+                id: 0,
+                title,
+                state: StickerState::Close,
+                left: 100,
+                top: 100,
+                width: size.width,
+                height: size.height,
+                top_most: false,
+                color: note.color,
+                sticker_type: StickerType::Markdown,
+                content: note.content,
+                settings: String::new(),
+                temporary: false,
+                expires_at: None,
+                created_at: note.created_at,
+                updated_at: note.updated_at,
+            })
+            .await?;
+        if !note.tags.is_empty() {
+            store.set_sticker_tags(id, note.tags).await?;
+        }
+        if note.archived {
+            store.move_sticker(id, StickerBucket::Archived).await?;
+        }
+        let _ = sticker_events_tx.send(StickerWindowEvent::Created { id });
+        created += 1;
+    }
+
+    tracing::info!(created, "Notes imported");
+    if created > 0 {
+        let _ = sticker_events_tx.send(StickerWindowEvent::StoreChanged);
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sticky_notes_paragraph_ids() {
+        assert_eq!(
+            sticky_notes_text("\\id=3f2a-11 Groceries\n\\id=9c1b-22 - milk\nplain line\n\\id=77"),
+            "Groceries\n- milk\nplain line\n"
+        );
+    }
+
+    #[test]
+    fn sticky_notes_colors_and_times() {
+        assert_eq!(sticky_notes_color("Charcoal"), StickerColor::Gray);
+        assert_eq!(sticky_notes_color("purple"), StickerColor::Pink);
+        assert_eq!(sticky_notes_color(""), StickerColor::Yellow);
+
+        assert_eq!(ticks_to_unix_millis(0), 0);
+        assert_eq!(ticks_to_unix_millis(TICKS_AT_UNIX_EPOCH), 0);
+        assert_eq!(
+            ticks_to_unix_millis(TICKS_AT_UNIX_EPOCH + 1_700_000_000_000 * 10_000),
+            1_700_000_000_000
+        );
+    }
+
+    #[test]
+    fn sticky_notes_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("plum.sqlite");
+        let created = TICKS_AT_UNIX_EPOCH + 1_000 * 10_000;
+
+        let notes = smol::block_on(async {
+            let mut conn = SqliteConnectOptions::new()
+                .filename(&db_path)
+                .create_if_missing(true)
+                .connect()
+                .await
+                .unwrap();
+            sqlx::query(
+                "CREATE TABLE Note (Text TEXT, Theme TEXT, CreatedAt INTEGER, UpdatedAt INTEGER, DeletedAt INTEGER)",
+            )
+            .execute(&mut conn)
+            .await
+            .unwrap();
+            for (text, theme, deleted_at) in [
+                ("\\id=1 Kept", "Blue", None),
+                ("\\id=2 Deleted", "Green", Some(created)),
+                ("\\id=3 ", "Green", None),
+            ] {
+                sqlx::query("INSERT INTO Note VALUES (?1, ?2, ?3, NULL, ?4)")
+                    .bind(text)
+                    .bind(theme)
+                    .bind(created)
+                    .bind(deleted_at)
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+            sqlx::Connection::close(conn).await.unwrap();
+
+            read_sticky_notes(&db_path).await.unwrap()
+        });
+
+        assert_eq!(
+            notes,
+            [ImportedNote {
+                content: "Kept".to_string(),
+                color: StickerColor::Blue,
+                tags: Vec::new(),
+                archived: false,
+                created_at: 1_000,
+                updated_at: 1_000,
+            }]
+        );
+    }
+
+    fn parse_keep(json: &str) -> Option<ImportedNote> {
+        keep_note(serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn keep_notes() {
+        let note = parse_keep(
+            r#"{
+                "color": "TEAL",
+                "isArchived": true,
+                "title": " Trip ",
+                "listContent": [
+                    { "text": "passport ", "isChecked": true },
+                    { "text": "tickets", "isChecked": false }
+                ],
+                "labels": [{ "name": "travel" }, { "name": " " }],
+                "createdTimestampUsec": 2000000,
+                "userEditedTimestampUsec": 5000000
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            note,
+            ImportedNote {
+                content: "# Trip\n\n- [x] passport\n- [ ] tickets".to_string(),
+                color: StickerColor::Green,
+                tags: vec!["travel".to_string()],
+                archived: true,
+                created_at: 2000,
+                updated_at: 5000,
+            }
+        );
+
+        let text = parse_keep(r#"{ "textContent": "just text\n", "color": "RED" }"#).unwrap();
+        assert_eq!(text.content, "just text");
+        assert_eq!(text.color, StickerColor::Pink);
+        assert_eq!(
+            parse_keep(r#"{ "title": "Only a title" }"#)
+                .unwrap()
+                .content,
+            "# Only a title"
+        );
+        assert!(parse_keep(r#"{ "title": " ", "textContent": "" }"#).is_none());
+    }
+
+    #[test]
+    fn keep_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, body: &str| fs::write(dir.path().join(name), body).unwrap();
+        write("a.json", r#"{ "title": "First" }"#);
+        write("b.JSON", r#"{ "title": "Second" }"#);
+        write("c.json", r#"{ "title": "Trashed", "isTrashed": true }"#);
+        write("d.json", "not json");
+        write("Labels.txt", "travel");
+
+        let notes = read_keep_folder(dir.path()).unwrap();
+        let contents = notes.iter().map(|x| x.content.as_str()).collect::<Vec<_>>();
+        assert_eq!(contents, ["# First", "# Second"]);
+    }
+}
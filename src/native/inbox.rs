@@ -0,0 +1,278 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use anyhow::Context as _;
+use gpui::{AsyncApp, ClipboardEntry, ClipboardItem, ImageFormat};
+
+use crate::model::sticker::{StickerColor, StickerDetail, StickerState, StickerType};
+use crate::native::components::stickers::{
+    Sticker, code, code::CodeSticker, image, image::ImageSticker, markdown::MarkdownSticker, web,
+    web::WebSticker,
+};
+use crate::native::windows::{StickerWindowEvent, sticker::StickerWindow};
+use crate::storage::ArcStickerStore;
+use crate::utils::ocr;
+
+/// Larger files are linked instead of copied into a sticker.
+const MAX_TEXT_FILE_BYTES: u64 = 1024 * 1024;
+const TEXT_EXTENSIONS: [&str; 9] = [
+    "md", "markdown", "txt", "log", "json", "yaml", "yml", "toml", "csv",
+];
+
+/// Something dropped onto or pasted into the inbox bubble.
+#[derive(Clone)]
+pub enum InboxItem {
+    Text(String),
+    Image {
+        bytes: Vec<u8>,
+        extension: &'static str,
+    },
+    File(PathBuf),
+}
+
+/// The sticker an inbox item turns into: links become web stickers, images image
+/// stickers, JSON code stickers, everything else Markdown.
+#[derive(Clone)]
+pub struct Draft {
+    pub sticker_type: StickerType,
+    pub title: String,
+    content: String,
+    image: Option<(Vec<u8>, &'static str)>,
+}
+
+impl Draft {
+    pub fn from_item(item: InboxItem) -> anyhow::Result<Self> {
+        let (sticker_type, title, content, image) = match item {
+            InboxItem::Text(text)
+                if crate::utils::url::is_url(&text) && !text.trim().contains('\n') =>
+            {
+                let url = text.trim().to_string();
+                (
+                    StickerType::Web,
+                    url.clone(),
+                    web::content_for_url(&url),
+                    None,
+                )
+            }
+            InboxItem::Text(text) => match json_object(&text) {
+                Some(code) => (
+                    StickerType::Code,
+                    "JSON".to_string(),
+                    code::content_for_code("json", &code),
+                    None,
+                ),
+                None => return Ok(Self::text(text)),
+            },
+            InboxItem::Image { bytes, extension } => {
+                let title = format!(
+                    "Image {}",
+                    crate::utils::time::format_unix_millis(crate::utils::time::now_unix_millis())
+                );
+                (
+                    StickerType::Image,
+                    title,
+                    String::new(),
+                    Some((bytes, extension)),
+                )
+            }
+            InboxItem::File(path) if image::is_supported_image(&path) => {
+                let bytes =
+                    std::fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+                let extension = match path.extension().and_then(|x| x.to_str()) {
+                    Some(x) if x.eq_ignore_ascii_case("png") => "png",
+                    _ => "jpg",
+                };
+                (
+                    StickerType::Image,
+                    file_title(&path),
+                    String::new(),
+                    Some((bytes, extension)),
+                )
+            }
+            InboxItem::File(path) => {
+                let content = read_text_file(&path).unwrap_or_else(|| {
+                    format!(
+                        "[{}](file:///{})",
+                        file_title(&path),
+                        path.to_string_lossy()
+                            .replace('\\', "/")
+                            .trim_start_matches('/')
+                    )
+                });
+                (StickerType::Markdown, file_title(&path), content, None)
+            }
+        };
+
+        Ok(Self {
+            sticker_type,
+            title,
+            content,
+            image,
+        })
+    }
+}
+
+impl Draft {
+    /// A Markdown sticker with the text, whatever it looks like.
+    pub fn text(text: String) -> Self {
+        Self {
+            sticker_type: StickerType::Markdown,
+            title: first_line(&text),
+            content: text,
+            image: None,
+        }
+    }
+}
+
+/// The first usable clipboard entry: a PNG/JPEG image, a copied file or text.
+pub fn clipboard_item(item: &ClipboardItem) -> Option<InboxItem> {
+    item.entries().iter().find_map(|entry| match entry {
+        ClipboardEntry::Image(image) => {
+            let extension = match image.format {
+                ImageFormat::Png => "png",
+                ImageFormat::Jpeg => "jpg",
+                _ => return None,
+            };
+            Some(InboxItem::Image {
+                bytes: image.bytes.clone(),
+                extension,
+            })
+        }
+        ClipboardEntry::String(text) => {
+            let text = text.text().trim().to_string();
+            // Copying a file in a file manager puts its path on the clipboard.
+            let path = PathBuf::from(&text);
+            if !text.contains('\n') && path.is_file() {
+                Some(InboxItem::File(path))
+            } else if !text.is_empty() {
+                Some(InboxItem::Text(text))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    })
+}
+
+/// An existing sticker with the same content, images are never compared.
+pub async fn find_duplicate(store: &ArcStickerStore, draft: &Draft) -> anyhow::Result<Option<i64>> {
+    if draft.image.is_some() || draft.content.trim().is_empty() {
+        return Ok(None);
+    }
+
+    store
+        .find_sticker_by_content(draft.content.clone(), draft.sticker_type)
+        .await
+}
+
+/// Create the sticker and open it.
+pub async fn create_sticker(
+    cx: &mut AsyncApp,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+    draft: Draft,
+) -> anyhow::Result<i64> {
+    let Draft {
+        sticker_type,
+        title,
+        content,
+        image,
+    } = draft;
+
+    let size = match sticker_type {
+        StickerType::Web => WebSticker::default_window_size(),
+        StickerType::Image => ImageSticker::default_window_size(),
+        StickerType::Code => CodeSticker::default_window_size(),
+        _ => MarkdownSticker::default_window_size(),
+    };
+    let id = store
+        .insert_sticker(StickerDetail {
+            id: 0,
+            title: title.clone(),
+            state: StickerState::Open,
+            left: 100,
+            top: 100,
+            width: size.width,
+            height: size.height,
+            top_most: false,
+            color: StickerColor::Yellow,
+            sticker_type,
+            content,
+            settings: String::new(),
+            temporary: false,
+            expires_at: None,
+            created_at: 0,
+            updated_at: 0,
+        })
+        .await?;
+    tracing::info!(id, sticker_type = ?sticker_type, "Sticker created from inbox");
+
+    if let Some((bytes, extension)) = image {
+        let format = image::picture_format(extension).context("unsupported inbox image")?;
+        let picture = gpui::Image::from_bytes(format, bytes);
+        let hash = image::add_picture(&store, id, &picture)
+            .await
+            .context("save inbox image")?;
+        store
+            .update_sticker_content(id, image::content_for_attachment(hash))
+            .await?;
+
+        if ocr::is_available() {
+            let text = cx
+                .background_executor()
+                .spawn(async move { ocr::extract_text_from_bytes(&picture.bytes, extension) })
+                .await;
+            match text {
+                Ok(text) => {
+                    if let Err(err) = store.update_sticker_search_text(id, text).await {
+                        tracing::warn!(id, error = ?err, "Failed to save image search text");
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(id, error = ?err, "Failed to extract text from image");
+                }
+            }
+        }
+    }
+
+    let _ = sticker_events_tx.send(StickerWindowEvent::Created { id });
+    let _ = sticker_events_tx.send(StickerWindowEvent::StoreChanged);
+    StickerWindow::open_async(cx, sticker_events_tx, store, id).await?;
+    Ok(id)
+}
+
+fn read_text_file(path: &Path) -> Option<String> {
+    let is_text = path
+        .extension()
+        .and_then(|x| x.to_str())
+        .is_some_and(|x| TEXT_EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()));
+    if !is_text || std::fs::metadata(path).ok()?.len() > MAX_TEXT_FILE_BYTES {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
+/// Pretty printed JSON when the text is an object or array, plain numbers or strings
+/// are left as text.
+fn json_object(text: &str) -> Option<String> {
+    let value = serde_json::from_str::<serde_json::Value>(text.trim()).ok()?;
+    if !value.is_object() && !value.is_array() {
+        return None;
+    }
+    serde_json::to_string_pretty(&value).ok()
+}
+
+fn first_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .find(|x| !x.is_empty())
+        .unwrap_or("Inbox")
+        .to_string()
+}
+
+fn file_title(path: &Path) -> String {
+    path.file_name()
+        .and_then(|x| x.to_str())
+        .unwrap_or("File")
+        .to_string()
+}
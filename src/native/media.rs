@@ -0,0 +1,170 @@
+use std::process::{Command, Stdio};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use anyhow::Context as _;
+
+/// What the system media session (SMTC on Windows, MPRIS on Linux) is playing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NowPlaying {
+    pub title: String,
+    pub artist: String,
+    /// The app playing it, e.g. `spotify` or `Spotify.exe`.
+    pub player: String,
+    pub playing: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaAction {
+    PlayPause,
+    Next,
+    Previous,
+}
+
+/// The current media session, `None` when nothing is playing or paused.
+///
+/// Blocking, so call it off the main thread.
+pub fn now_playing() -> anyhow::Result<Option<NowPlaying>> {
+    let output = query_command()?
+        .stdin(Stdio::null())
+        .output()
+        .context("run media session query")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // playerctl fails when no player is running, which is nothing playing to us.
+        if stderr.contains("No players found") {
+            return Ok(None);
+        }
+        anyhow::bail!("{}", stderr.trim());
+    }
+
+    Ok(parse(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Send play/pause, next or previous to the current media session, blocking.
+pub fn control(action: MediaAction) -> anyhow::Result<()> {
+    let output = control_command(action)?
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .output()
+        .context("run media control")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// `status<TAB>title<TAB>artist<TAB>player`, the line both platforms print.
+fn parse(output: &str) -> Option<NowPlaying> {
+    let line = output.lines().find(|x| !x.trim().is_empty())?;
+    let mut fields = line.split('\t').map(str::trim);
+    let status = fields.next()?;
+    let title = fields.next().unwrap_or_default().to_string();
+    let artist = fields.next().unwrap_or_default().to_string();
+    let player = fields.next().unwrap_or_default().to_string();
+    if title.is_empty() && artist.is_empty() {
+        return None;
+    }
+
+    Some(NowPlaying {
+        title,
+        artist,
+        player,
+        playing: status.eq_ignore_ascii_case("playing"),
+    })
+}
+
+/// WinRT is reached through PowerShell, its async operations are awaited as tasks.
+#[cfg(target_os = "windows")]
+const SMTC_PRELUDE: &str = r#"
+Add-Type -AssemblyName System.Runtime.WindowsRuntime
+$asTask = ([System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object {
+    $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and
+    $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1'
+})[0]
+function Await($op, $type) {
+    $task = $asTask.MakeGenericMethod($type).Invoke($null, @($op))
+    $task.Wait(-1) | Out-Null
+    $task.Result
+}
+$managerType = [Windows.Media.Control.GlobalSystemMediaTransportControlsSessionManager, Windows.Media.Control, ContentType = WindowsRuntime]
+$manager = Await ($managerType::RequestAsync()) $managerType
+$session = $manager.GetCurrentSession()
+if (-not $session) { exit 0 }
+"#;
+
+#[cfg(target_os = "windows")]
+fn powershell(script: &str) -> Command {
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .creation_flags(CREATE_NO_WINDOW);
+    cmd
+}
+
+#[cfg(target_os = "windows")]
+fn query_command() -> anyhow::Result<Command> {
+    let script = format!(
+        "{SMTC_PRELUDE}{}",
+        r#"
+$propsType = [Windows.Media.Control.GlobalSystemMediaTransportControlsSessionMediaProperties, Windows.Media.Control, ContentType = WindowsRuntime]
+$props = Await ($session.TryGetMediaPropertiesAsync()) $propsType
+$status = $session.GetPlaybackInfo().PlaybackStatus
+"$status`t$($props.Title)`t$($props.Artist)`t$($session.SourceAppUserModelId)"
+"#
+    );
+    Ok(powershell(&script))
+}
+
+#[cfg(target_os = "windows")]
+fn control_command(action: MediaAction) -> anyhow::Result<Command> {
+    let call = match action {
+        MediaAction::PlayPause => "TryTogglePlayPauseAsync",
+        MediaAction::Next => "TrySkipNextAsync",
+        MediaAction::Previous => "TrySkipPreviousAsync",
+    };
+    let script = format!("{SMTC_PRELUDE}Await ($session.{call}()) ([bool]) | Out-Null");
+    Ok(powershell(&script))
+}
+
+#[cfg(target_os = "macos")]
+fn query_command() -> anyhow::Result<Command> {
+    anyhow::bail!("Now playing is not supported on macOS yet")
+}
+
+#[cfg(target_os = "macos")]
+fn control_command(_action: MediaAction) -> anyhow::Result<Command> {
+    anyhow::bail!("Media controls are not supported on macOS yet")
+}
+
+/// MPRIS players are reached through `playerctl`.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn playerctl() -> anyhow::Result<Command> {
+    let path = which::which("playerctl").context("playerctl not found, please install it")?;
+    Ok(Command::new(path))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn query_command() -> anyhow::Result<Command> {
+    let mut cmd = playerctl()?;
+    cmd.args([
+        "metadata",
+        "--format",
+        "{{status}}\t{{title}}\t{{artist}}\t{{playerName}}",
+    ]);
+    Ok(cmd)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn control_command(action: MediaAction) -> anyhow::Result<Command> {
+    let mut cmd = playerctl()?;
+    cmd.arg(match action {
+        MediaAction::PlayPause => "play-pause",
+        MediaAction::Next => "next",
+        MediaAction::Previous => "previous",
+    });
+    Ok(cmd)
+}
@@ -1,24 +1,71 @@
 use std::{
     sync::{Arc, OnceLock, mpsc},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use gpui::{AnyWindowHandle, App, Application, rgb};
+use gpui::{AnyWindowHandle, App, Application, AsyncApp, rgb};
 use gpui_component::{Theme, ThemeMode};
 
 use crate::{
     ipc::IpcEvent,
-    native::windows::{StickerWindowEvent, main::MainWindow, sticker::StickerWindow},
-    storage::{ArcStickerStore, open_sqlite, paths::AppPaths},
+    model::sticker::StickerState,
+    native::windows::{
+        StickerWindowEvent, annotation::AnnotationWindow, inbox::InboxWindow, main::MainWindow,
+        startup_error::StartupErrorWindow, sticker::StickerWindow,
+    },
+    storage::{
+        ArcStickerStore, open_sqlite, paths::AppPaths, settings::SettingsStore,
+        templates::StickerTemplate,
+    },
 };
 
+pub mod app_lock;
+pub mod archive;
+pub mod backup;
 pub mod components;
+pub mod daily_note;
+pub mod decorations;
+pub mod dialogs;
+pub mod dnd;
+pub mod encrypted_backup;
+pub mod folder_sync;
+pub mod hooks;
 pub mod hotkey;
 pub mod http;
+pub mod importers;
+pub mod inbox;
+pub mod media;
+pub mod monitor_layout;
+pub mod probe;
+pub mod reminders;
+pub mod screen;
+pub mod sound;
+pub mod speech;
+pub mod status;
+pub mod task_sync;
+pub mod top_most;
+pub mod webhook;
+pub mod weekly_review;
 pub mod windows;
 
+const RESTORE_ATTEMPTS: u32 = 3;
+/// Wait before the second attempt, doubled before each further one.
+const RESTORE_RETRY_DELAY: Duration = Duration::from_millis(500);
+const STORE_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+const TEMPORARY_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How to come up, from the command line flags.
+#[derive(Debug, Clone, Copy)]
+pub struct StartupOptions {
+    /// Minimize the main window right away, IPC `SHOW` brings it back.
+    pub hidden: bool,
+    /// Open the windows of stickers which were open on last exit.
+    pub restore_stickers: bool,
+}
+
 pub fn run_native(
     app_paths: AppPaths,
+    startup: StartupOptions,
     ipc_events_rx: mpsc::Receiver<IpcEvent>,
     sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
     sticker_events_rx: mpsc::Receiver<StickerWindowEvent>,
@@ -27,7 +74,9 @@ pub fn run_native(
         .with_assets(components::Assets)
         .with_http_client(http::ReqwestClient::new());
 
+    let started_at = Instant::now();
     let main_window_handle = Arc::new(OnceLock::<AnyWindowHandle>::new());
+    let store_cell = Arc::new(OnceLock::<ArcStickerStore>::new());
 
     app.run(move |cx: &mut App| {
         gpui_component::init(cx);
@@ -35,21 +84,138 @@ pub fn run_native(
         let theme = cx.global_mut::<Theme>();
         theme.background = rgb(0x151104).into();
 
+        // Before the settings are loaded, a restore may bring its own settings.json.
+        if let Err(err) = backup::apply_pending_restore(&app_paths) {
+            tracing::error!(error = ?err, "Failed to restore database from backup");
+        }
+        SettingsStore::init(cx, app_paths.settings_path());
+        cx.set_global(app_paths.clone());
+
+        cx.spawn(async move |cx| app_lock::run_scheduler(cx).await)
+            .detach();
+        cx.background_executor()
+            .spawn(async { crate::utils::print::remove_old_pages() })
+            .detach();
+
         let main_window_handle_clone = main_window_handle.clone();
+        let store_cell_clone = store_cell.clone();
+        let ipc_app_paths = app_paths.clone();
+        let ipc_sticker_events_tx = sticker_events_tx.clone();
         cx.spawn(async move |cx| {
+            let mut pending: Vec<IpcEvent> = Vec::new();
             loop {
                 cx.background_executor()
                     .timer(Duration::from_millis(120))
                     .await;
                 while let Ok(event) = ipc_events_rx.try_recv() {
+                    pending.push(event);
+                }
+                for event in std::mem::take(&mut pending) {
                     match event {
                         crate::ipc::IpcEvent::Show => {
                             if let Some(handle) = main_window_handle_clone.get() {
                                 let _ = handle.update(cx, |_, window, _| {
-                                    window.activate_window();
+                                    top_most::show_window(window);
                                 });
                             }
                         }
+                        crate::ipc::IpcEvent::Lock => {
+                            let _ = cx.update(app_lock::lock);
+                        }
+                        crate::ipc::IpcEvent::RestoreStickers => {
+                            let Some(store) = store_cell_clone.get().cloned() else {
+                                pending.push(IpcEvent::RestoreStickers);
+                                continue;
+                            };
+                            let sticker_events_tx = ipc_sticker_events_tx.clone();
+                            cx.spawn(async move |cx| {
+                                let orphaned =
+                                    restore_open_stickers(cx, &store, &sticker_events_tx).await;
+                                if !orphaned.is_empty() {
+                                    tracing::warn!(?orphaned, "Some stickers could not be restored");
+                                }
+                            })
+                            .detach();
+                        }
+                        crate::ipc::IpcEvent::CollectAttachments => {
+                            let Some(store) = store_cell_clone.get().cloned() else {
+                                pending.push(IpcEvent::CollectAttachments);
+                                continue;
+                            };
+                            if store.is_read_only() {
+                                tracing::warn!("Not collecting attachments of a read-only store");
+                                continue;
+                            }
+                            cx.background_executor()
+                                .spawn(async move {
+                                    match store.collect_orphan_attachments().await {
+                                        Ok(collection) => tracing::info!(
+                                            blobs = collection.blobs,
+                                            files = collection.files,
+                                            bytes = collection.bytes,
+                                            "Orphan attachments collected"
+                                        ),
+                                        Err(err) => {
+                                            tracing::error!(error = ?err, "Failed to collect orphan attachments")
+                                        }
+                                    }
+                                })
+                                .detach();
+                        }
+                        crate::ipc::IpcEvent::Annotate => {
+                            let Some(store) = store_cell_clone.get().cloned() else {
+                                pending.push(IpcEvent::Annotate);
+                                continue;
+                            };
+                            let sticker_events_tx = ipc_sticker_events_tx.clone();
+                            let _ = cx.update(|cx| {
+                                AnnotationWindow::toggle(cx, store, sticker_events_tx)
+                            });
+                        }
+                        crate::ipc::IpcEvent::NewFromTemplate { name, vars } => {
+                            let Some(store) = store_cell_clone.get().cloned() else {
+                                // The store is still opening, retry on the next tick.
+                                pending.push(IpcEvent::NewFromTemplate { name, vars });
+                                continue;
+                            };
+                            let app_paths = ipc_app_paths.clone();
+                            let sticker_events_tx = ipc_sticker_events_tx.clone();
+                            cx.spawn(async move |cx| {
+                                if let Err(err) = new_sticker_from_template(
+                                    cx,
+                                    &app_paths,
+                                    store,
+                                    sticker_events_tx,
+                                    &name,
+                                    &vars,
+                                )
+                                .await
+                                {
+                                    tracing::error!(template = %name, error = ?err, "Failed to create sticker from template");
+                                }
+                            })
+                            .detach();
+                        }
+                        crate::ipc::IpcEvent::Status { reply } => {
+                            let Some(store) = store_cell_clone.get().cloned() else {
+                                pending.push(IpcEvent::Status { reply });
+                                continue;
+                            };
+                            let app_paths = ipc_app_paths.clone();
+                            cx.spawn(async move |cx| {
+                                let status =
+                                    match status::collect(cx, &app_paths, &store, started_at).await
+                                    {
+                                        Ok(status) => status,
+                                        Err(err) => {
+                                            tracing::error!(error = ?err, "Failed to collect status");
+                                            serde_json::json!({ "error": format!("{err:#}") })
+                                        }
+                                    };
+                                let _ = reply.send(status.to_string());
+                            })
+                            .detach();
+                        }
                     }
                 }
             }
@@ -57,41 +223,153 @@ pub fn run_native(
         .detach();
 
         let main_window_handle_clone = main_window_handle.clone();
+        let store_cell_clone = store_cell.clone();
         cx.spawn(async move |cx| {
             tracing::info!(db_path = %app_paths.db_path.display(), "Opening sticker store");
-            let store: ArcStickerStore = match open_sqlite(app_paths.db_path).await {
-                Ok(store) => store,
-                Err(err) => {
-                    tracing::error!(error = ?err, "Failed to open store");
-                    return;
+            let store: ArcStickerStore =
+                match open_sqlite(&app_paths).await {
+                    Ok(store) => store,
+                    Err(err) => {
+                        tracing::error!(error = ?err, "Failed to open store");
+                        let message = format!("{err:#}");
+                        let _ = cx.update(|cx| {
+                            if let Err(err) = StartupErrorWindow::open(cx, message) {
+                                tracing::error!(error = ?err, "Failed to open startup error window");
+                            }
+                        });
+                        return;
+                    }
+                };
+
+            tracing::info!(read_only = store.is_read_only(), "Sticker store opened");
+            let _ = store_cell_clone.set(store.clone());
+
+            // Temporary stickers only live for one session, drop the ones left by a crash.
+            if !store.is_read_only() {
+                if let Err(err) = store.delete_temporary_stickers(None).await {
+                    tracing::error!(error = ?err, "Failed to delete leftover temporary stickers");
                 }
-            };
 
-            tracing::info!("Sticker store opened");
-
-            match store.get_open_sticker_ids().await {
-                Ok(sticker_ids) => {
-                    tracing::debug!(count = sticker_ids.len(), "Restoring open stickers");
-                    for id in sticker_ids {
-                        let store = store.clone();
-                        let sticker_events_tx = sticker_events_tx.clone();
-                        if let Err(err) =
-                            StickerWindow::open_async(cx, sticker_events_tx, store, id).await
-                        {
-                            tracing::warn!(id, error = ?err, "Failed to open sticker window");
+                let sweep_store = store.clone();
+                cx.spawn(async move |cx| {
+                    expire_temporary_stickers(cx, sweep_store).await;
+                })
+                .detach();
+
+                // Rebuilt on every start, so a damaged index heals by itself and the
+                // first search finds it warm.
+                let index_store = store.clone();
+                cx.background_executor()
+                    .spawn(async move {
+                        let started = Instant::now();
+                        match index_store.rebuild_search_index().await {
+                            Ok(()) => tracing::info!(
+                                elapsed_ms = started.elapsed().as_millis() as u64,
+                                "Search index rebuilt"
+                            ),
+                            Err(err) => {
+                                tracing::error!(error = ?err, "Failed to rebuild search index")
+                            }
                         }
-                    }
-                }
-                Err(err) => {
-                    tracing::error!(error = ?err, "Failed to get open sticker ids from store");
+                    })
+                    .detach();
+            }
+
+            // Skipped ones stay open in the DB, so a later restore or start brings them.
+            let orphaned = match startup.restore_stickers {
+                true => restore_open_stickers(cx, &store, &sticker_events_tx).await,
+                false => {
+                    tracing::info!("Not restoring open stickers on start");
+                    Vec::new()
                 }
+            };
+
+            let startup_warning = (!orphaned.is_empty()).then(|| {
+                format!(
+                    "Some stickers could not be restored and were closed:\n{}",
+                    orphaned.join("\n")
+                )
+            });
+
+            if store.is_read_only() {
+                tracing::warn!(
+                    "Sticker store is read-only, daily note, task sync, folder sync, reminder and archive schedulers disabled"
+                );
+            } else {
+                let daily_note_store = store.clone();
+                let daily_note_events_tx = sticker_events_tx.clone();
+                cx.spawn(async move |cx| {
+                    daily_note::run_scheduler(cx, daily_note_store, daily_note_events_tx).await;
+                })
+                .detach();
+
+                let task_sync_store = store.clone();
+                let task_sync_events_tx = sticker_events_tx.clone();
+                cx.spawn(async move |cx| {
+                    task_sync::run_scheduler(cx, task_sync_store, task_sync_events_tx).await;
+                })
+                .detach();
+
+                let folder_sync_store = store.clone();
+                let folder_sync_events_tx = sticker_events_tx.clone();
+                cx.spawn(async move |cx| {
+                    folder_sync::run_scheduler(cx, folder_sync_store, folder_sync_events_tx).await;
+                })
+                .detach();
+
+                let reminders_store = store.clone();
+                let reminders_events_tx = sticker_events_tx.clone();
+                cx.spawn(async move |cx| {
+                    reminders::run_scheduler(cx, reminders_store, reminders_events_tx).await;
+                })
+                .detach();
+
+                let archive_store = store.clone();
+                let archive_events_tx = sticker_events_tx.clone();
+                cx.spawn(async move |cx| {
+                    archive::run_scheduler(cx, archive_store, archive_events_tx).await;
+                })
+                .detach();
             }
 
+            let backup_store = store.clone();
+            cx.spawn(async move |cx| {
+                backup::run_scheduler(cx, backup_store).await;
+            })
+            .detach();
+
+            let watch_store = store.clone();
+            let watch_events_tx = sticker_events_tx.clone();
+            cx.spawn(async move |cx| {
+                watch_store_changes(cx, watch_store, watch_events_tx).await;
+            })
+            .detach();
+
+            let inbox_store = store.clone();
+            let inbox_events_tx = sticker_events_tx.clone();
             let _ = cx.update(move |cx| {
-                match MainWindow::open(cx, sticker_events_rx, sticker_events_tx.clone(), store) {
+                InboxWindow::sync_with_settings(cx, &inbox_store, &inbox_events_tx);
+                cx.observe_global::<SettingsStore>(move |cx| {
+                    InboxWindow::sync_with_settings(cx, &inbox_store, &inbox_events_tx);
+                })
+                .detach();
+            });
+
+            let _ = cx.update(move |cx| {
+                match MainWindow::open(
+                    cx,
+                    sticker_events_rx,
+                    sticker_events_tx.clone(),
+                    store,
+                    startup_warning,
+                ) {
                     Ok(window) => {
                         let _ = main_window_handle_clone.set(window.clone());
-                        tracing::info!("Main window opened");
+                        tracing::info!(hidden = startup.hidden, "Main window opened");
+                        // It still has to exist, it handles the sticker window events.
+                        if startup.hidden {
+                            let _ = window.update(cx, |_, window, _| window.minimize_window());
+                        }
                     }
                     Err(err) => {
                         tracing::error!(error = ?err, "Failed to open main window");
@@ -102,3 +380,137 @@ pub fn run_native(
         .detach();
     });
 }
+
+/// Open the windows of stickers which were open on last exit, returns a line for each
+/// one which could not be restored.
+async fn restore_open_stickers(
+    cx: &mut AsyncApp,
+    store: &ArcStickerStore,
+    sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+) -> Vec<String> {
+    let mut orphaned: Vec<String> = Vec::new();
+    match store.get_open_sticker_ids().await {
+        Ok(sticker_ids) => {
+            tracing::debug!(count = sticker_ids.len(), "Restoring open stickers");
+            for id in sticker_ids {
+                if let Err(err) = restore_sticker_window(cx, store, sticker_events_tx, id).await {
+                    orphaned.push(format!("#{id}: {err:#}"));
+                }
+            }
+        }
+        Err(err) => {
+            tracing::error!(error = ?err, "Failed to get open sticker ids from store");
+        }
+    }
+    orphaned
+}
+
+/// Open a sticker which was open on last exit, retrying a few times with a growing delay.
+///
+/// When it still fails the sticker is marked closed, so a broken sticker does not
+/// stay a "ghost" open state forever.
+async fn restore_sticker_window(
+    cx: &mut AsyncApp,
+    store: &ArcStickerStore,
+    sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+    id: i64,
+) -> anyhow::Result<()> {
+    let mut last_err = None;
+    for attempt in 1..=RESTORE_ATTEMPTS {
+        if attempt > 1 {
+            let delay = RESTORE_RETRY_DELAY * 2u32.pow(attempt - 2);
+            cx.background_executor().timer(delay).await;
+        }
+        match StickerWindow::open_async(cx, sticker_events_tx.clone(), store.clone(), id).await {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                tracing::warn!(id, attempt, error = ?err, "Failed to open sticker window");
+                last_err = Some(err);
+            }
+        }
+    }
+
+    if let Err(err) = store.update_sticker_state(id, StickerState::Close).await {
+        tracing::error!(id, error = ?err, "Failed to close orphaned sticker");
+    }
+
+    Err(last_err.unwrap_or_else(|| anyhow::anyhow!("Failed to open sticker window")))
+}
+
+/// Notify the windows when another process, e.g. the sqlite CLI or a sync tool,
+/// commits to the DB. Our own writes do not change the data version.
+async fn watch_store_changes(
+    cx: &mut AsyncApp,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+) {
+    let mut last_version = None;
+    loop {
+        cx.background_executor().timer(STORE_WATCH_INTERVAL).await;
+        let version = match store.data_version().await {
+            Ok(version) => version,
+            Err(err) => {
+                tracing::warn!(error = ?err, "Failed to read store data version");
+                continue;
+            }
+        };
+
+        if last_version.is_some_and(|last| last != version) {
+            tracing::debug!(version, "Store changed externally");
+            if sticker_events_tx
+                .send(StickerWindowEvent::StoreChanged)
+                .is_err()
+            {
+                break;
+            }
+        }
+        last_version = Some(version);
+    }
+}
+
+/// Delete temporary stickers whose time is up and close their windows.
+async fn expire_temporary_stickers(cx: &mut AsyncApp, store: ArcStickerStore) {
+    loop {
+        cx.background_executor()
+            .timer(TEMPORARY_SWEEP_INTERVAL)
+            .await;
+        let now = crate::utils::time::now_unix_millis();
+        let ids = match store.delete_temporary_stickers(Some(now)).await {
+            Ok(ids) => ids,
+            Err(err) => {
+                tracing::warn!(error = ?err, "Failed to delete expired temporary stickers");
+                continue;
+            }
+        };
+
+        for id in ids {
+            tracing::debug!(id, "Temporary sticker expired");
+            if cx.update(|cx| StickerWindow::try_close(id, cx)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+async fn new_sticker_from_template(
+    cx: &mut AsyncApp,
+    app_paths: &AppPaths,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+    name: &str,
+    vars: &[(String, String)],
+) -> anyhow::Result<i64> {
+    let template = StickerTemplate::load(app_paths, name)?;
+    let id = store.insert_sticker(template.instantiate(vars)).await?;
+    tracing::info!(id, template = %name, "Sticker created from template");
+    let _ = sticker_events_tx.send(StickerWindowEvent::Created { id });
+    let _ = sticker_events_tx.send(StickerWindowEvent::StoreChanged);
+
+    let tags = template.render_tags(vars);
+    if !tags.is_empty() {
+        store.set_sticker_tags(id, tags).await?;
+    }
+
+    StickerWindow::open_async(cx, sticker_events_tx, store, id).await?;
+    Ok(id)
+}
@@ -0,0 +1,40 @@
+use gpui::App;
+
+/// Identify the current monitor setup, e.g. "laptop only" vs "docked dual-monitor".
+///
+/// The key is a stable hash of every display's bounds, so it stays the same across
+/// restarts and only changes when monitors are added, removed, moved or resized.
+pub fn layout_key(cx: &App) -> String {
+    let mut displays: Vec<(i32, i32, i32, i32)> = cx
+        .displays()
+        .iter()
+        .map(|display| {
+            let bounds = display.bounds();
+            (
+                bounds.origin.x.to_f64() as i32,
+                bounds.origin.y.to_f64() as i32,
+                bounds.size.width.to_f64() as i32,
+                bounds.size.height.to_f64() as i32,
+            )
+        })
+        .collect();
+    displays.sort();
+
+    let canonical = displays
+        .iter()
+        .map(|(x, y, w, h)| format!("{x},{y},{w}x{h}"))
+        .collect::<Vec<_>>()
+        .join(";");
+
+    format!("{:016x}", fnv1a(canonical.as_bytes()))
+}
+
+// std's hasher is not guaranteed to be stable between releases, the key is persisted.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
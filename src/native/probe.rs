@@ -0,0 +1,147 @@
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use anyhow::Context as _;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What an uptime check reaches out to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeTarget {
+    /// `http(s)://…`, up when it answers with a success or redirect status.
+    Http(String),
+    /// `host:port`, up when a TCP connection opens.
+    Tcp(String),
+    /// A bare host, pinged with the `ping` command of the OS.
+    Host(String),
+}
+
+impl ProbeTarget {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("Target cannot be empty".to_string());
+        }
+        if text.starts_with("http://") || text.starts_with("https://") {
+            return reqwest::Url::parse(text)
+                .map(|_| Self::Http(text.to_string()))
+                .map_err(|err| format!("Invalid URL: {err}"));
+        }
+
+        // A leading dash would be taken as an option of `ping`.
+        let valid_host = |host: &str| {
+            !host.is_empty()
+                && !host.starts_with('-')
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '_'))
+        };
+        match text.rsplit_once(':') {
+            // More than one colon without brackets is an IPv6 address, not a port.
+            Some((host, port)) if !host.contains(':') => {
+                if !valid_host(host) || port.parse::<u16>().is_err() {
+                    return Err(format!("Invalid host and port: {text}"));
+                }
+                Ok(Self::Tcp(text.to_string()))
+            }
+            _ if valid_host(text) => Ok(Self::Host(text.to_string())),
+            _ => Err(format!("Invalid host: {text}")),
+        }
+    }
+
+    /// Check once and return the round trip time in milliseconds. The TCP and ping
+    /// checks block, so call this off the main thread.
+    pub async fn check(&self) -> anyhow::Result<u64> {
+        match self {
+            Self::Http(url) => check_http(url).await,
+            Self::Tcp(addr) => check_tcp(addr),
+            Self::Host(host) => ping(host),
+        }
+    }
+}
+
+async fn check_http(url: &str) -> anyhow::Result<u64> {
+    let started = Instant::now();
+    let response = reqwest::Client::new()
+        .get(url)
+        .header("User-Agent", "rustickers")
+        .timeout(TIMEOUT)
+        .send()
+        .await
+        .context("send request")?;
+    let elapsed = started.elapsed().as_millis() as u64;
+
+    let status = response.status();
+    anyhow::ensure!(
+        status.is_success() || status.is_redirection(),
+        "HTTP {}",
+        status.as_u16()
+    );
+    Ok(elapsed)
+}
+
+fn check_tcp(addr: &str) -> anyhow::Result<u64> {
+    let addr = addr
+        .to_socket_addrs()
+        .context("resolve host")?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Host has no address"))?;
+
+    let started = Instant::now();
+    TcpStream::connect_timeout(&addr, TIMEOUT).context("connect")?;
+    Ok(started.elapsed().as_millis() as u64)
+}
+
+fn ping(host: &str) -> anyhow::Result<u64> {
+    let started = Instant::now();
+    let output = ping_command(host)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .context("run ping")?;
+    let elapsed = started.elapsed().as_millis() as u64;
+    anyhow::ensure!(output.status.success(), "No reply from {host}");
+
+    // The reported time is more exact than timing the whole process.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(reply_time(&stdout).unwrap_or(elapsed))
+}
+
+#[cfg(target_os = "windows")]
+fn ping_command(host: &str) -> Command {
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let mut cmd = Command::new("ping");
+    cmd.args(["-n", "1", "-w", &TIMEOUT.as_millis().to_string(), host])
+        .creation_flags(CREATE_NO_WINDOW);
+    cmd
+}
+
+#[cfg(target_os = "macos")]
+fn ping_command(host: &str) -> Command {
+    let mut cmd = Command::new("ping");
+    // The wait is in milliseconds on macOS.
+    cmd.args(["-c", "1", "-W", &TIMEOUT.as_millis().to_string(), host]);
+    cmd
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn ping_command(host: &str) -> Command {
+    let mut cmd = Command::new("ping");
+    cmd.args(["-c", "1", "-W", &TIMEOUT.as_secs().to_string(), host]);
+    cmd
+}
+
+/// The `time=12.3 ms` (or `time<1ms` on Windows) of a ping reply, in whole milliseconds.
+fn reply_time(output: &str) -> Option<u64> {
+    let start = output.find("time=").or_else(|| output.find("time<"))? + "time=".len();
+    let number = output[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect::<String>();
+    number.parse::<f64>().ok().map(|ms| ms.round() as u64)
+}
@@ -0,0 +1,58 @@
+use std::sync::mpsc;
+use std::time::Duration;
+
+use gpui::AsyncApp;
+
+use crate::model::sticker::{StickerFilter, StickerOrderBy, StickerType};
+use crate::native::components::stickers::reminder;
+use crate::native::windows::StickerWindowEvent;
+use crate::native::windows::sticker::StickerWindow;
+use crate::storage::ArcStickerStore;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_REMINDERS: i64 = 10000;
+
+/// Open the window of reminders which became due while it was closed, the open
+/// reminder stickers fire by themselves.
+pub async fn run_scheduler(
+    cx: &mut AsyncApp,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+) {
+    loop {
+        if let Err(err) = open_due_reminders(cx, &store, &sticker_events_tx).await {
+            tracing::warn!(error = ?err, "Failed to check reminders");
+        }
+
+        cx.background_executor().timer(CHECK_INTERVAL).await;
+    }
+}
+
+async fn open_due_reminders(
+    cx: &mut AsyncApp,
+    store: &ArcStickerStore,
+    sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+) -> anyhow::Result<()> {
+    let filter = StickerFilter {
+        sticker_type: Some(StickerType::Reminder),
+        ..StickerFilter::default()
+    };
+    let reminders = store
+        .query_stickers(None, filter, StickerOrderBy::CreatedAsc, MAX_REMINDERS, 0)
+        .await?;
+
+    let now = crate::utils::time::now_unix_millis();
+    for brief in reminders {
+        if StickerWindow::is_open(brief.id) {
+            continue;
+        }
+
+        let detail = store.get_sticker(brief.id).await?;
+        if reminder::is_due(&detail.content, now) {
+            tracing::info!(id = brief.id, "Opening due reminder");
+            StickerWindow::open_async(cx, sticker_events_tx.clone(), store.clone(), brief.id)
+                .await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use image::RgbaImage;
+
+/// A still of the primary display, to read the color of any pixel on it.
+pub struct ScreenCapture {
+    image: RgbaImage,
+}
+
+impl ScreenCapture {
+    /// Capture the primary display, this takes a moment so better not on the main thread.
+    pub fn primary() -> anyhow::Result<Self> {
+        let monitors = xcap::Monitor::all()?;
+        let monitor = monitors
+            .iter()
+            .find(|monitor| monitor.is_primary().unwrap_or(false))
+            .or(monitors.first())
+            .ok_or_else(|| anyhow::anyhow!("No display found"))?;
+        let image = monitor.capture_image()?;
+        Ok(Self { image })
+    }
+
+    /// The color at `x`, `y` given as fractions (0..1) of the display width and
+    /// height as 0xRRGGBB, so it does not matter how the display is scaled.
+    pub fn color_at(&self, x: f32, y: f32) -> Option<u32> {
+        let (width, height) = self.image.dimensions();
+        if width == 0 || height == 0 || !(0.0..=1.0).contains(&x) || !(0.0..=1.0).contains(&y) {
+            return None;
+        }
+        let px = ((x * width as f32) as u32).min(width - 1);
+        let py = ((y * height as f32) as u32).min(height - 1);
+        let [r, g, b, _] = self.image.get_pixel(px, py).0;
+        Some(u32::from_be_bytes([0, r, g, b]))
+    }
+}
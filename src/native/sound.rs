@@ -0,0 +1,93 @@
+use std::sync::mpsc;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use gpui::App;
+use rodio::Source as _;
+
+use crate::storage::settings::SettingsStore;
+
+/// Fade the tones in, a hard start clicks.
+const FADE: Duration = Duration::from_millis(5);
+
+static PLAYER: OnceLock<Mutex<mpsc::Sender<(Sound, f32)>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    /// A timer, pomodoro phase or reminder is due, repeated until it is noticed.
+    Alarm,
+    /// A desktop notification was shown.
+    Ding,
+    /// A command exited with an error.
+    Failure,
+}
+
+impl Sound {
+    /// (frequency in Hz, duration in ms) played one after the other.
+    fn tones(&self) -> &'static [(f32, u64)] {
+        match self {
+            Self::Alarm => &[(880.0, 200)],
+            Self::Ding => &[(988.0, 90), (1319.0, 160)],
+            Self::Failure => &[(330.0, 150), (220.0, 250)],
+        }
+    }
+}
+
+/// Play the sound at the configured volume, unless it is muted or turned off in settings.
+pub fn play(cx: &App, sound: Sound) {
+    let settings = &SettingsStore::get(cx).sound;
+    let enabled = match sound {
+        Sound::Alarm => settings.alarms,
+        Sound::Ding => settings.dings,
+        Sound::Failure => settings.command_failures,
+    };
+    if settings.muted || !enabled || settings.volume == 0 {
+        return;
+    }
+
+    let volume = settings.volume.min(100) as f32 / 100.0;
+    let player = PLAYER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || run_player(rx));
+        Mutex::new(tx)
+    });
+    if let Ok(player) = player.lock() {
+        let _ = player.send((sound, volume));
+    }
+}
+
+/// The output stream can not move between threads, so one thread owns it and
+/// plays what is sent to it.
+fn run_player(rx: mpsc::Receiver<(Sound, f32)>) {
+    let (_stream, handle) = match rodio::OutputStream::try_default() {
+        Ok(output) => output,
+        Err(err) => {
+            tracing::warn!(error = %err, "No audio output, sounds are not played");
+            // Keep receiving so senders never block or error.
+            for _ in rx {}
+            return;
+        }
+    };
+
+    for (sound, volume) in rx {
+        let sink = match rodio::Sink::try_new(&handle) {
+            Ok(sink) => sink,
+            Err(err) => {
+                tracing::warn!(error = %err, ?sound, "Failed to play sound");
+                continue;
+            }
+        };
+        sink.set_volume(volume);
+        for &(frequency, ms) in sound.tones() {
+            let duration = Duration::from_millis(ms);
+            sink.append(
+                rodio::source::SineWave::new(frequency)
+                    .take_duration(duration)
+                    .fade_in(FADE)
+                    .amplify(0.5),
+            );
+        }
+        sink.detach();
+    }
+}
@@ -0,0 +1,67 @@
+use std::process::{Command, Stdio};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use anyhow::Context as _;
+
+/// Read the text aloud with the speech engine of the OS and wait until it is done.
+///
+/// Windows uses System.Speech through PowerShell, macOS `say` and Linux
+/// `spd-say` (speech-dispatcher) or `espeak`.
+pub fn speak(text: &str) -> anyhow::Result<()> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let status = command(text)?
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("run speech command")?;
+    anyhow::ensure!(status.success(), "Speech command failed: {status}");
+
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn command(text: &str) -> anyhow::Result<Command> {
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    // The text goes through the environment so it is never parsed as a script.
+    let mut cmd = Command::new("powershell");
+    cmd.args([
+        "-NoProfile",
+        "-NonInteractive",
+        "-Command",
+        "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak($env:RUSTICKERS_SPEAK)",
+    ])
+    .env("RUSTICKERS_SPEAK", text)
+    .creation_flags(CREATE_NO_WINDOW);
+    Ok(cmd)
+}
+
+#[cfg(target_os = "macos")]
+fn command(text: &str) -> anyhow::Result<Command> {
+    let mut cmd = Command::new("say");
+    cmd.arg("--").arg(text);
+    Ok(cmd)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn command(text: &str) -> anyhow::Result<Command> {
+    if let Ok(path) = which::which("spd-say") {
+        let mut cmd = Command::new(path);
+        // Wait for the speech to finish, like the other platforms do.
+        cmd.arg("--wait").arg("--").arg(text);
+        return Ok(cmd);
+    }
+
+    let path = which::which("espeak")
+        .or_else(|_| which::which("espeak-ng"))
+        .context("No speech engine found, install speech-dispatcher or espeak")?;
+    let mut cmd = Command::new(path);
+    cmd.arg("--").arg(text);
+    Ok(cmd)
+}
@@ -0,0 +1,67 @@
+use std::time::Instant;
+
+use gpui::AsyncApp;
+use serde_json::json;
+
+use crate::model::sticker::{StickerFilter, StickerType};
+use crate::native::windows::sticker::StickerWindow;
+use crate::storage::ArcStickerStore;
+use crate::storage::paths::AppPaths;
+use crate::storage::settings::SettingsStore;
+
+/// Status JSON of the running app for the `status` command, used by scripts and
+/// for troubleshooting user reports.
+pub async fn collect(
+    cx: &mut AsyncApp,
+    app_paths: &AppPaths,
+    store: &ArcStickerStore,
+    started_at: Instant,
+) -> anyhow::Result<serde_json::Value> {
+    let total = store.count_stickers(None, StickerFilter::default()).await?;
+    let open = store.get_open_sticker_ids().await?.len();
+
+    let mut by_type = serde_json::Map::new();
+    for sticker_type in StickerType::ALL {
+        let filter = StickerFilter {
+            sticker_type: Some(sticker_type),
+            ..StickerFilter::default()
+        };
+        let count = store.count_stickers(None, filter).await?;
+        by_type.insert(sticker_type.as_str().to_string(), json!(count));
+    }
+
+    let (windows, daily_note, safe_mode) = cx.update(|cx| {
+        let windows = StickerWindow::open_window_states(cx)
+            .into_iter()
+            .map(|(id, sticker_type, scheduler)| {
+                json!({
+                    "id": id,
+                    "type": sticker_type.as_str(),
+                    "scheduler": scheduler,
+                })
+            })
+            .collect::<Vec<_>>();
+        let settings = SettingsStore::get(cx);
+        let daily_note = json!({
+            "enabled": settings.daily_note.enabled,
+            "time": settings.daily_note.time,
+        });
+        (windows, daily_note, settings.safe_mode)
+    })?;
+
+    Ok(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "uptime_secs": started_at.elapsed().as_secs(),
+        "db_path": app_paths.db_path.display().to_string(),
+        "safe_mode": safe_mode,
+        "stickers": {
+            "total": total,
+            "open": open,
+            "by_type": by_type,
+        },
+        "open_windows": windows,
+        "schedulers": {
+            "daily_note": daily_note,
+        },
+    }))
+}
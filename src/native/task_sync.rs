@@ -0,0 +1,428 @@
+//! Show the open tasks of a Todoist or CalDAV account as a checklist in a Markdown
+//! sticker. Checking a task off there (and saving) completes it on the next sync.
+
+use std::collections::HashSet;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use async_compat::CompatExt;
+use gpui::AsyncApp;
+use serde::Deserialize;
+
+use crate::model::sticker::*;
+use crate::native::components::stickers::markdown::MarkdownSticker;
+use crate::native::components::stickers::{Sticker, save_content};
+use crate::native::windows::StickerWindowEvent;
+use crate::native::windows::sticker::StickerWindow;
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::{SettingsStore, TaskProvider, TaskSyncSettings};
+use crate::utils::ics;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const TASKS_HEADING: &str = "## Tasks";
+const TODOIST_API: &str = "https://api.todoist.com/api/v1";
+
+const CALDAV_QUERY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/><c:calendar-data/></d:prop>
+  <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter></c:filter>
+</c:calendar-query>"#;
+
+/// Sync whenever the interval passed or the settings changed.
+pub async fn run_scheduler(
+    cx: &mut AsyncApp,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+) {
+    let mut last_sync: Option<(TaskSyncSettings, Instant)> = None;
+
+    loop {
+        let Ok(settings) = cx.update(|cx| SettingsStore::get(cx).task_sync.clone()) else {
+            return;
+        };
+
+        let interval = Duration::from_secs(u64::from(settings.interval_minutes.max(1)) * 60);
+        let due = settings.enabled
+            && last_sync.as_ref().is_none_or(|(last_settings, at)| {
+                *last_settings != settings || at.elapsed() >= interval
+            });
+
+        if due {
+            if let Err(err) = sync(cx, &store, &sticker_events_tx, &settings).await {
+                tracing::warn!(error = ?err, "Failed to sync tasks");
+            }
+            // The first sync may have stored the sticker id, that is no reason to sync again.
+            let Ok(settings) = cx.update(|cx| SettingsStore::get(cx).task_sync.clone()) else {
+                return;
+            };
+            last_sync = Some((settings, Instant::now()));
+        }
+
+        cx.background_executor().timer(CHECK_INTERVAL).await;
+    }
+}
+
+async fn sync(
+    cx: &mut AsyncApp,
+    store: &ArcStickerStore,
+    sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+    settings: &TaskSyncSettings,
+) -> anyhow::Result<()> {
+    let detail = match settings.sticker_id {
+        Some(id) => match store.get_sticker(id).await {
+            Ok(detail) => Some(detail),
+            Err(err) => {
+                tracing::warn!(id, error = ?err, "Task sticker is gone, creating a new one");
+                None
+            }
+        },
+        None => None,
+    };
+    let detail = match detail {
+        Some(detail) => detail,
+        None => create_sticker(cx, store, sticker_events_tx).await?,
+    };
+    let id = detail.id;
+
+    let checked = checked_tasks(&detail.content);
+    let provider = settings.clone();
+    let titles = cx
+        .background_executor()
+        .spawn(async move { sync_remote(&provider, &checked).compat().await })
+        .await?;
+
+    let content = tasks_content(&detail.content, &titles);
+    if content == detail.content {
+        return Ok(());
+    }
+
+    // Try again next time instead of overwriting what is being typed.
+    if cx.update(|cx| StickerWindow::try_set_content(id, &content, cx))? == Some(false) {
+        tracing::debug!(id, "Task sticker is being edited, sync postponed");
+        return Ok(());
+    }
+
+    let title = content
+        .lines()
+        .find(|x| !x.is_empty())
+        .unwrap_or("")
+        .to_string();
+    store.update_sticker_title(id, title.clone()).await?;
+    let _ = sticker_events_tx.send(StickerWindowEvent::TitleChanged { id, title });
+    let summary = markdown_summary(&content);
+    save_content(store, sticker_events_tx, id, content, summary).await?;
+
+    tracing::info!(id, tasks = titles.len(), "Tasks synced");
+    Ok(())
+}
+
+async fn create_sticker(
+    cx: &mut AsyncApp,
+    store: &ArcStickerStore,
+    sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+) -> anyhow::Result<StickerDetail> {
+    let size = MarkdownSticker::default_window_size();
+    let id = store
+        .insert_sticker(StickerDetail {
+            id: 0,
+            title: TASKS_HEADING.to_string(),
+            state: StickerState::Open,
+            left: 100,
+            top: 100,
+            width: size.width,
+            height: size.height,
+            top_most: false,
+            color: StickerColor::Yellow,
+            sticker_type: StickerType::Markdown,
+            content: TASKS_HEADING.to_string(),
+            settings: String::new(),
+            temporary: false,
+            expires_at: None,
+            created_at: 0,
+            updated_at: 0,
+        })
+        .await?;
+    tracing::info!(id, "Task sticker created");
+    let _ = sticker_events_tx.send(StickerWindowEvent::Created { id });
+
+    cx.update(|cx| SettingsStore::update(cx, |s| s.task_sync.sticker_id = Some(id)))?;
+    let _ = sticker_events_tx.send(StickerWindowEvent::StoreChanged);
+    StickerWindow::open_async(cx, sticker_events_tx.clone(), store.clone(), id).await?;
+
+    store.get_sticker(id).await
+}
+
+/// Titles of the tasks checked off in the sticker, e.g. `- [x] Buy milk`.
+pub fn checked_tasks(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            let line = line
+                .strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))?;
+            line.strip_prefix("[x] ")
+                .or_else(|| line.strip_prefix("[X] "))
+                .map(|title| title.trim().to_string())
+        })
+        .collect()
+}
+
+/// The heading of the sticker (kept when the user changed it) followed by the open tasks.
+fn tasks_content(old: &str, titles: &[String]) -> String {
+    let heading = old
+        .lines()
+        .find(|x| !x.is_empty())
+        .filter(|x| !x.trim_start().starts_with(['-', '*']))
+        .unwrap_or(TASKS_HEADING);
+
+    let mut content = heading.to_string();
+    if !titles.is_empty() {
+        content.push_str("\n\n");
+        let items = titles
+            .iter()
+            .map(|title| format!("- [ ] {title}"))
+            .collect::<Vec<_>>();
+        content.push_str(&items.join("\n"));
+    }
+    content
+}
+
+struct RemoteTask {
+    title: String,
+    handle: TaskHandle,
+}
+
+enum TaskHandle {
+    Todoist {
+        id: String,
+    },
+    /// The calendar object is sent back with the VTODO completed.
+    CalDav {
+        href: String,
+        etag: String,
+        data: String,
+    },
+}
+
+/// Complete the checked tasks, returns the titles of the ones still open.
+async fn sync_remote(
+    settings: &TaskSyncSettings,
+    checked: &HashSet<String>,
+) -> anyhow::Result<Vec<String>> {
+    let client = reqwest::Client::new();
+    let tasks = match settings.provider {
+        TaskProvider::Todoist => todoist_tasks(&client, settings).await?,
+        TaskProvider::CalDav => caldav_tasks(&client, settings).await?,
+    };
+
+    let mut open = Vec::new();
+    for task in tasks {
+        if checked.contains(&task.title) {
+            match complete_task(&client, settings, &task.handle).await {
+                Ok(()) => {
+                    tracing::info!(title = %task.title, "Task completed");
+                    continue;
+                }
+                Err(err) => {
+                    tracing::warn!(title = %task.title, error = ?err, "Failed to complete task");
+                }
+            }
+        }
+        open.push(task.title);
+    }
+    Ok(open)
+}
+
+async fn complete_task(
+    client: &reqwest::Client,
+    settings: &TaskSyncSettings,
+    handle: &TaskHandle,
+) -> anyhow::Result<()> {
+    let request = match handle {
+        TaskHandle::Todoist { id } => client
+            .post(format!("{TODOIST_API}/tasks/{id}/close"))
+            .bearer_auth(settings.todoist_token.trim()),
+        TaskHandle::CalDav { href, etag, data } => {
+            let url = reqwest::Url::parse(settings.caldav_url.trim())?.join(href)?;
+            let request = client
+                .put(url)
+                .basic_auth(&settings.caldav_username, Some(&settings.caldav_password))
+                .header("Content-Type", "text/calendar; charset=utf-8")
+                .body(ics::complete_todo(data, chrono::Utc::now()));
+            match etag.is_empty() {
+                true => request,
+                false => request.header("If-Match", etag.as_str()),
+            }
+        }
+    };
+
+    request
+        .send()
+        .await
+        .and_then(|x| x.error_for_status())
+        .context("complete task")?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct TodoistPage {
+    results: Vec<TodoistTask>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TodoistTask {
+    id: String,
+    content: String,
+}
+
+async fn todoist_tasks(
+    client: &reqwest::Client,
+    settings: &TaskSyncSettings,
+) -> anyhow::Result<Vec<RemoteTask>> {
+    anyhow::ensure!(
+        !settings.todoist_token.trim().is_empty(),
+        "No Todoist token"
+    );
+
+    let mut tasks = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut params = vec![("limit", "200".to_string())];
+        let project_id = settings.todoist_project_id.trim();
+        if !project_id.is_empty() {
+            params.push(("project_id", project_id.to_string()));
+        }
+        if let Some(cursor) = &cursor {
+            params.push(("cursor", cursor.clone()));
+        }
+        let url = reqwest::Url::parse_with_params(&format!("{TODOIST_API}/tasks"), &params)?;
+
+        let text = client
+            .get(url)
+            .bearer_auth(settings.todoist_token.trim())
+            .send()
+            .await
+            .and_then(|x| x.error_for_status())
+            .context("list Todoist tasks")?
+            .text()
+            .await
+            .context("read Todoist tasks")?;
+        let page = serde_json::from_str::<TodoistPage>(&text).context("parse Todoist tasks")?;
+
+        tasks.extend(page.results.into_iter().map(|task| RemoteTask {
+            title: task.content.trim().to_string(),
+            handle: TaskHandle::Todoist { id: task.id },
+        }));
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            return Ok(tasks);
+        }
+    }
+}
+
+async fn caldav_tasks(
+    client: &reqwest::Client,
+    settings: &TaskSyncSettings,
+) -> anyhow::Result<Vec<RemoteTask>> {
+    let url = settings.caldav_url.trim();
+    anyhow::ensure!(!url.is_empty(), "No CalDAV URL");
+
+    let text = client
+        .request(reqwest::Method::from_bytes(b"REPORT")?, url)
+        .basic_auth(&settings.caldav_username, Some(&settings.caldav_password))
+        .header("Depth", "1")
+        .header("Content-Type", "application/xml; charset=utf-8")
+        .body(CALDAV_QUERY)
+        .send()
+        .await
+        .and_then(|x| x.error_for_status())
+        .context("query CalDAV tasks")?
+        .text()
+        .await
+        .context("read CalDAV tasks")?;
+
+    let mut tasks = Vec::new();
+    for response in xml_elements(&text, "response") {
+        let href = xml_elements(response, "href")
+            .first()
+            .map(|x| xml_text(x))
+            .unwrap_or_default();
+        let etag = xml_elements(response, "getetag")
+            .first()
+            .map(|x| xml_text(x))
+            .unwrap_or_default();
+        let Some(data) = xml_elements(response, "calendar-data")
+            .first()
+            .map(|x| xml_text(x))
+        else {
+            continue;
+        };
+
+        for todo in ics::todos(&data) {
+            if todo.done || todo.summary.trim().is_empty() {
+                continue;
+            }
+            tasks.push(RemoteTask {
+                title: todo.summary.trim().to_string(),
+                handle: TaskHandle::CalDav {
+                    href: href.clone(),
+                    etag: etag.clone(),
+                    data: data.clone(),
+                },
+            });
+        }
+    }
+    Ok(tasks)
+}
+
+/// Contents of the elements with this local name, whatever namespace prefix they use.
+fn xml_elements<'a>(xml: &'a str, local_name: &str) -> Vec<&'a str> {
+    let mut elements = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = xml[pos..].find('<') {
+        let tag_start = pos + offset + 1;
+        let Some(tag_len) = xml[tag_start..].find('>') else {
+            break;
+        };
+        let tag = &xml[tag_start..tag_start + tag_len];
+        pos = tag_start + tag_len + 1;
+        if tag.starts_with(['/', '?', '!']) || tag.ends_with('/') {
+            continue;
+        }
+
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        if name.rsplit(':').next() != Some(local_name) {
+            continue;
+        }
+
+        let close = format!("</{name}>");
+        let Some(end) = xml[pos..].find(&close) else {
+            break;
+        };
+        elements.push(&xml[pos..pos + end]);
+        pos += end + close.len();
+    }
+
+    elements
+}
+
+fn xml_text(text: &str) -> String {
+    let text = text.trim();
+    if let Some(data) = text
+        .strip_prefix("<![CDATA[")
+        .and_then(|x| x.strip_suffix("]]>"))
+    {
+        return data.to_string();
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#13;", "\r")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,206 @@
+use gpui::Window;
+
+/// Whether [`set_window_top_most`] does anything here, controls for it are hidden
+/// where it does not.
+pub const TOP_MOST_SUPPORTED: bool = cfg!(windows);
+
+/// Keep a window above all other windows.
+///
+/// gpui decides the window level when the window is created, so we toggle it on
+/// the native handle instead.
+pub fn set_window_top_most(window: &Window, top_most: bool) {
+    #[cfg(windows)]
+    {
+        use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            HWND_NOTOPMOST, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SetWindowPos,
+        };
+
+        match window.window_handle().map(|handle| handle.as_raw()) {
+            Ok(RawWindowHandle::Win32(handle)) => {
+                let hwnd = handle.hwnd.get() as windows_sys::Win32::Foundation::HWND;
+                let insert_after = if top_most {
+                    HWND_TOPMOST
+                } else {
+                    HWND_NOTOPMOST
+                };
+                let ok = unsafe {
+                    SetWindowPos(
+                        hwnd,
+                        insert_after,
+                        0,
+                        0,
+                        0,
+                        0,
+                        SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+                    )
+                };
+                if ok == 0 {
+                    tracing::warn!(top_most, "SetWindowPos failed");
+                }
+            }
+            Ok(_) => tracing::warn!("Unexpected window handle for top most"),
+            Err(err) => tracing::warn!(error = %err, "Failed to get native window handle"),
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = window;
+        tracing::debug!(
+            top_most,
+            "Top most windows are not supported on this platform yet"
+        );
+    }
+}
+
+/// Where a window was in the stacking order before [`start_peek`] raised it.
+pub struct PeekRestore {
+    /// The window right above it, null when it was the topmost normal window.
+    #[cfg(windows)]
+    above: windows_sys::Win32::Foundation::HWND,
+}
+
+/// Show a window above all others for a moment without activating it, returns how to
+/// put it back with [`end_peek`]. `None` when it is kept on top already.
+pub fn start_peek(window: &Window) -> Option<PeekRestore> {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            GW_HWNDPREV, GWL_EXSTYLE, GetWindow, GetWindowLongW, HWND_TOPMOST, SWP_NOACTIVATE,
+            SWP_NOMOVE, SWP_NOSIZE, SetWindowPos, WS_EX_TOPMOST,
+        };
+
+        let hwnd = native_hwnd(window)?;
+        let ex_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
+        if ex_style & WS_EX_TOPMOST != 0 {
+            return None;
+        }
+
+        let above = unsafe { GetWindow(hwnd, GW_HWNDPREV) };
+        let flags = SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE;
+        if unsafe { SetWindowPos(hwnd, HWND_TOPMOST, 0, 0, 0, 0, flags) } == 0 {
+            tracing::warn!("SetWindowPos failed to start a peek");
+            return None;
+        }
+        Some(PeekRestore { above })
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = window;
+        tracing::debug!("Peeking at windows is not supported on this platform yet");
+        None
+    }
+}
+
+/// Put a window raised by [`start_peek`] back where it was.
+pub fn end_peek(window: &Window, restore: PeekRestore) {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            HWND_NOTOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SetWindowPos,
+        };
+
+        let Some(hwnd) = native_hwnd(window) else {
+            return;
+        };
+        let flags = SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE;
+        // Leaving the topmost band puts it above all normal windows, then below the
+        // one which covered it before.
+        let ok = unsafe {
+            SetWindowPos(hwnd, HWND_NOTOPMOST, 0, 0, 0, 0, flags) != 0
+                && (restore.above.is_null()
+                    || SetWindowPos(hwnd, restore.above, 0, 0, 0, 0, flags) != 0)
+        };
+        if !ok {
+            tracing::warn!("SetWindowPos failed to end a peek");
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = (window, restore);
+    }
+}
+
+/// Move a window to `left`, `top` in logical pixels, e.g. along with its stack.
+///
+/// gpui can not move a window once it is open, so it is done on the native handle.
+pub fn move_window(window: &Window, left: i32, top: i32) {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER, SetWindowPos,
+        };
+
+        let Some(hwnd) = native_hwnd(window) else {
+            return;
+        };
+        let scale = window.scale_factor();
+        let (x, y) = ((left as f32 * scale) as i32, (top as f32 * scale) as i32);
+        let flags = SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE;
+        if unsafe { SetWindowPos(hwnd, std::ptr::null_mut(), x, y, 0, 0, flags) } == 0 {
+            tracing::warn!("SetWindowPos failed to move the window");
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = (window, left, top);
+        tracing::debug!("Moving windows is not supported on this platform yet");
+    }
+}
+
+/// Hide a window without closing it, e.g. from the taskbar, [`show_window`] brings it
+/// back. It is minimized where hiding is not supported.
+pub fn hide_window(window: &Window) {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{SW_HIDE, ShowWindow};
+
+        if let Some(hwnd) = native_hwnd(window) {
+            unsafe { ShowWindow(hwnd, SW_HIDE) };
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        window.minimize_window();
+    }
+}
+
+/// Show and activate a window, also one hidden by [`hide_window`].
+pub fn show_window(window: &Window) {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{IsWindowVisible, SW_SHOW, ShowWindow};
+
+        if let Some(hwnd) = native_hwnd(window)
+            && unsafe { IsWindowVisible(hwnd) } == 0
+        {
+            unsafe { ShowWindow(hwnd, SW_SHOW) };
+        }
+    }
+
+    window.activate_window();
+}
+
+#[cfg(windows)]
+fn native_hwnd(window: &Window) -> Option<windows_sys::Win32::Foundation::HWND> {
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+    match window.window_handle().map(|handle| handle.as_raw()) {
+        Ok(RawWindowHandle::Win32(handle)) => {
+            Some(handle.hwnd.get() as windows_sys::Win32::Foundation::HWND)
+        }
+        Ok(_) => {
+            tracing::warn!("Unexpected native window handle");
+            None
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to get native window handle");
+            None
+        }
+    }
+}
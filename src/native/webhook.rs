@@ -0,0 +1,113 @@
+//! JSON posted to a user configured URL on selected events, so Slack, Discord, ntfy and
+//! the like can pass them on.
+//!
+//! The payload has the message as `text` (Slack) and `content` (Discord), next to the
+//! event name, the sticker id and title, the time and, for commands, the exit code.
+
+use async_compat::CompatExt;
+use chrono::Local;
+use gpui::App;
+use serde::Serialize;
+
+use crate::native::windows::StickerWindowEvent;
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::{SettingsStore, WebhookSettings};
+
+#[derive(Debug, Serialize)]
+struct Payload {
+    event: &'static str,
+    sticker_id: i64,
+    title: String,
+    text: String,
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exit_code: Option<i32>,
+    /// RFC 3339 local time.
+    at: String,
+}
+
+/// Name and message prefix of the events which are posted, `None` for the others.
+fn describe(
+    event: &StickerWindowEvent,
+    settings: &WebhookSettings,
+) -> Option<(&'static str, &'static str, i64, Option<i32>)> {
+    match event {
+        StickerWindowEvent::TimerFinished { id } if settings.timer_finished => {
+            Some(("timer_finished", "Timer finished", *id, None))
+        }
+        StickerWindowEvent::ReminderDue { id } if settings.reminder_due => {
+            Some(("reminder_due", "Reminder due", *id, None))
+        }
+        StickerWindowEvent::CommandDone {
+            id,
+            exit_code: Some(code),
+            scheduled: true,
+        } if *code != 0 && settings.command_failed => Some((
+            "command_failed",
+            "Scheduled command failed",
+            *id,
+            Some(*code),
+        )),
+        _ => None,
+    }
+}
+
+/// Post the event to the webhook, if one is set and the event is selected.
+pub fn send_for_event(event: &StickerWindowEvent, store: &ArcStickerStore, cx: &mut App) {
+    let settings = &SettingsStore::get(cx).webhook;
+    let url = settings.url.trim().to_string();
+    if url.is_empty() {
+        return;
+    }
+    let Some((name, message, id, exit_code)) = describe(event, settings) else {
+        return;
+    };
+
+    let store = store.clone();
+    cx.spawn(async move |cx| {
+        let title = match store.get_sticker(id).await {
+            Ok(detail) => detail.title,
+            Err(err) => {
+                tracing::warn!(id, error = ?err, "Failed to load sticker for webhook");
+                String::new()
+            }
+        };
+        let text = match (title.trim(), exit_code) {
+            ("", None) => message.to_string(),
+            ("", Some(code)) => format!("{message} (exit code {code})"),
+            (title, None) => format!("{message}: {title}"),
+            (title, Some(code)) => format!("{message}: {title} (exit code {code})"),
+        };
+        let payload = Payload {
+            event: name,
+            sticker_id: id,
+            title,
+            content: text.clone(),
+            text,
+            exit_code,
+            at: Local::now().to_rfc3339(),
+        };
+
+        let posted = cx
+            .background_executor()
+            .spawn(async move { post(&url, &payload).compat().await })
+            .await;
+        match posted {
+            Ok(()) => tracing::info!(id, event = name, "Webhook sent"),
+            Err(err) => tracing::warn!(id, event = name, error = ?err, "Failed to send webhook"),
+        }
+    })
+    .detach();
+}
+
+async fn post(url: &str, payload: &Payload) -> anyhow::Result<()> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_string(payload)?)
+        .send()
+        .await?;
+    let status = response.status();
+    anyhow::ensure!(status.is_success(), "webhook answered {status}");
+    Ok(())
+}
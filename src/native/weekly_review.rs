@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::sync::mpsc;
+
+use chrono::{DateTime, Local};
+use gpui::AsyncApp;
+
+use crate::model::command::CommandRun;
+use crate::model::sticker::*;
+use crate::native::components::stickers::markdown::MarkdownSticker;
+use crate::native::components::stickers::{Sticker, pomodoro, timer};
+use crate::native::task_sync::checked_tasks;
+use crate::native::windows::StickerWindowEvent;
+use crate::native::windows::sticker::StickerWindow;
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::SettingsStore;
+use crate::utils::time::{format_unix_millis, now_unix_millis, secs_to_hms};
+
+const WEEK_MILLIS: i64 = 7 * 24 * 60 * 60 * 1000;
+const HEADING: &str = "# Weekly review";
+
+/// Compile what happened to the stickers in the past 7 days into a new Markdown
+/// sticker and open it.
+pub async fn create(
+    cx: &mut AsyncApp,
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+) -> anyhow::Result<i64> {
+    let now = now_unix_millis();
+    let since = now - WEEK_MILLIS;
+
+    let stickers = store
+        .get_stickers_updated_since(since)
+        .await?
+        .into_iter()
+        // Earlier reviews are no news.
+        .filter(|x| !x.title.starts_with(HEADING))
+        .collect::<Vec<_>>();
+
+    // A failing scheduled command does not update its sticker, so the titles of
+    // the commands come from the store.
+    let runs = store.get_command_runs_since(since).await?;
+    let mut command_titles = HashMap::new();
+    for run in &runs {
+        if !command_titles.contains_key(&run.sticker_id) {
+            let title = match store.get_sticker(run.sticker_id).await {
+                Ok(sticker) => sticker.title,
+                Err(_) => continue,
+            };
+            command_titles.insert(run.sticker_id, title);
+        }
+    }
+
+    let content = report(since, now, &stickers, &runs, &command_titles);
+    let title = content.lines().next().unwrap_or(HEADING).to_string();
+    let color = cx.update(|cx| SettingsStore::get(cx).default_color(StickerType::Markdown))?;
+
+    let size = MarkdownSticker::default_window_size();
+    let id = store
+        .insert_sticker(StickerDetail {
+            id: 0,
+            title: title.clone(),
+            state: StickerState::Open,
+            left: 100,
+            top: 100,
+            width: size.width,
+            height: size.height,
+            top_most: false,
+            color,
+            sticker_type: StickerType::Markdown,
+            content,
+            settings: String::new(),
+            temporary: false,
+            expires_at: None,
+            created_at: 0,
+            updated_at: 0,
+        })
+        .await?;
+    tracing::info!(id, title = %title, "Weekly review created");
+    let _ = sticker_events_tx.send(StickerWindowEvent::Created { id });
+    let _ = sticker_events_tx.send(StickerWindowEvent::StoreChanged);
+
+    StickerWindow::open_async(cx, sticker_events_tx, store, id).await?;
+    Ok(id)
+}
+
+fn report(
+    since: i64,
+    now: i64,
+    stickers: &[StickerDetail],
+    runs: &[CommandRun],
+    command_titles: &HashMap<i64, String>,
+) -> String {
+    let mut created = Vec::new();
+    let mut edited = Vec::new();
+    let mut done = Vec::new();
+    let mut timers = Vec::new();
+    let mut focus = Vec::new();
+
+    for sticker in stickers {
+        let title = display_title(&sticker.title);
+        let entry = format!("- {title} ({})", sticker.sticker_type.label());
+        if sticker.created_at >= since {
+            created.push(entry);
+        } else {
+            edited.push(entry);
+        }
+
+        match sticker.sticker_type {
+            // Checked items carry no time, those of stickers edited this week count.
+            StickerType::Markdown => {
+                let mut tasks = checked_tasks(&sticker.content)
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                tasks.sort();
+                done.extend(
+                    tasks
+                        .into_iter()
+                        .map(|task| format!("- [x] {task} — {title}")),
+                );
+            }
+            StickerType::Timer => {
+                if let Some((started_at, secs)) = timer::last_started(&sticker.content)
+                    && started_at >= since
+                {
+                    let (h, m, s) = secs_to_hms(secs as i64);
+                    let duration = if h > 0 {
+                        format!("{h}:{m:02}:{s:02}")
+                    } else {
+                        format!("{m:02}:{s:02}")
+                    };
+                    timers.push(format!(
+                        "- {title}: {duration}, started {}",
+                        format_unix_millis(started_at)
+                    ));
+                }
+            }
+            StickerType::Pomodoro => {
+                let sessions = pomodoro::focus_sessions_since(&sticker.content, since);
+                if sessions > 0 {
+                    focus.push(format!("- {title}: {sessions} focus sessions"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut failures = Vec::new();
+    let mut runs_by_sticker: Vec<(i64, Vec<&CommandRun>)> = Vec::new();
+    for run in runs {
+        match runs_by_sticker
+            .iter_mut()
+            .find(|(id, _)| *id == run.sticker_id)
+        {
+            Some((_, list)) => list.push(run),
+            None => runs_by_sticker.push((run.sticker_id, vec![run])),
+        }
+    }
+    for (id, list) in runs_by_sticker {
+        let failed = list
+            .iter()
+            .filter(|run| run.exit_code != Some(0))
+            .collect::<Vec<_>>();
+        let Some(last) = failed.last() else {
+            continue;
+        };
+        let Some(title) = command_titles.get(&id) else {
+            continue;
+        };
+        let exit_code = last
+            .exit_code
+            .map(|code| format!("exit code {code}"))
+            .unwrap_or_else(|| "killed".to_string());
+        failures.push(format!(
+            "- {}: {} of {} runs failed, last {} ({exit_code})",
+            display_title(title),
+            failed.len(),
+            list.len(),
+            format_unix_millis(last.started_at),
+        ));
+    }
+
+    let mut content = format!("{HEADING} {} – {}", format_date(since), format_date(now));
+    let sections = [
+        ("Created", created),
+        ("Edited", edited),
+        ("Done", done),
+        ("Timers", timers),
+        ("Focus", focus),
+        ("Command failures", failures),
+    ];
+    let mut empty = true;
+    for (heading, lines) in sections {
+        if lines.is_empty() {
+            continue;
+        }
+        empty = false;
+        content.push_str(&format!("\n\n## {heading} ({})\n\n", lines.len()));
+        content.push_str(&lines.join("\n"));
+    }
+    if empty {
+        content.push_str("\n\nNo sticker was created or changed this week.");
+    }
+    content.push('\n');
+    content
+}
+
+fn display_title(title: &str) -> &str {
+    let title = title.trim().trim_start_matches('#').trim();
+    if title.is_empty() { "Untitled" } else { title }
+}
+
+fn format_date(ms: i64) -> String {
+    DateTime::from_timestamp_millis(ms)
+        .map(|x| x.with_timezone(&Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
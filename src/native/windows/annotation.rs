@@ -0,0 +1,436 @@
+use std::sync::{Arc, RwLock, mpsc};
+use std::time::{Duration, Instant};
+
+use gpui::{
+    AnyWindowHandle, App, AppContext, Bounds, Context, FocusHandle, KeyDownEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Point, Render, Window,
+    WindowBackgroundAppearance, WindowBounds, WindowOptions, canvas, div, prelude::*, px, rgba,
+    transparent_black,
+};
+use gpui_component::{
+    ActiveTheme, Root, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+};
+
+use crate::model::sticker::{StickerColor, StickerDetail, StickerState, StickerType};
+use crate::native::components::{
+    IconName,
+    stickers::{
+        Sticker as _,
+        paint::{
+            self, PAINT_COLORS, PAINT_STROKE_WIDTHS, PaintPoint, PaintStroke, PaintStrokeState,
+        },
+    },
+};
+use crate::native::top_most::set_window_top_most;
+use crate::native::windows::{StickerWindowEvent, sticker::StickerWindow};
+use crate::storage::ArcStickerStore;
+
+static ANNOTATION_WINDOW: RwLock<Option<AnyWindowHandle>> = RwLock::new(None);
+
+const NOTIFY_MIN_INTERVAL: Duration = Duration::from_millis(16);
+/// Room around the strokes when they are saved as a paint sticker.
+const SAVE_MARGIN: f32 = 16.0;
+
+/// A transparent layer over the whole screen to draw on, e.g. while sharing the
+/// screen. Esc clears the drawing, or closes the layer when it is empty.
+pub struct AnnotationWindow {
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+    focus_handle: FocusHandle,
+    /// Top left of the display in screen coordinates, strokes are relative to it.
+    origin: Point<Pixels>,
+
+    strokes: Arc<RwLock<Vec<PaintStrokeState>>>,
+    current_color: u32,
+    current_width: f32,
+    painting: bool,
+    last_notify_at: Option<Instant>,
+
+    error: Option<String>,
+}
+
+impl AnnotationWindow {
+    /// Cover the primary display with the drawing layer, or close it when it is already open.
+    pub fn toggle(
+        cx: &mut App,
+        store: ArcStickerStore,
+        sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+    ) {
+        let handle = ANNOTATION_WINDOW.read().ok().and_then(|x| *x);
+        if let Some(handle) = handle {
+            let _ = handle.update(cx, |_, window, _| window.remove_window());
+            Self::forget_window();
+            return;
+        }
+
+        if let Err(err) = Self::open(cx, store, sticker_events_tx) {
+            tracing::error!(error = ?err, "Failed to open annotation window");
+        }
+    }
+
+    fn open(
+        cx: &mut App,
+        store: ArcStickerStore,
+        sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+    ) -> anyhow::Result<()> {
+        let display = cx
+            .primary_display()
+            .ok_or_else(|| anyhow::anyhow!("No display found"))?;
+        let bounds: Bounds<Pixels> = display.bounds();
+
+        let handle = cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                display_id: Some(display.id()),
+                window_background: WindowBackgroundAppearance::Transparent,
+                titlebar: None,
+                focus: true,
+                is_movable: false,
+                ..Default::default()
+            },
+            |window, cx| {
+                set_window_top_most(window, true);
+                window.on_window_should_close(cx, |_, _| {
+                    Self::forget_window();
+                    true
+                });
+                let view = cx.new(|cx| {
+                    let focus_handle = cx.focus_handle();
+                    window.focus(&focus_handle);
+                    AnnotationWindow {
+                        store,
+                        sticker_events_tx,
+                        focus_handle,
+                        origin: bounds.origin,
+                        strokes: Arc::new(RwLock::new(Vec::new())),
+                        current_color: PAINT_COLORS[2],
+                        current_width: 4.0,
+                        painting: false,
+                        last_notify_at: None,
+                        error: None,
+                    }
+                });
+                cx.new(|cx| Root::new(view, window, cx).bg(transparent_black().alpha(0.0)))
+            },
+        )?;
+
+        if let Ok(mut annotation_window) = ANNOTATION_WINDOW.write() {
+            *annotation_window = Some(handle.into());
+        }
+
+        Ok(())
+    }
+
+    fn forget_window() {
+        if let Ok(mut annotation_window) = ANNOTATION_WINDOW.write() {
+            *annotation_window = None;
+        }
+    }
+
+    fn close(&mut self, window: &mut Window) {
+        Self::forget_window();
+        window.remove_window();
+    }
+
+    fn strokes_write(&self) -> std::sync::RwLockWriteGuard<'_, Vec<PaintStrokeState>> {
+        match self.strokes.write() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self.strokes.read() {
+            Ok(guard) => guard.is_empty(),
+            Err(err) => err.into_inner().is_empty(),
+        }
+    }
+
+    fn clear(&mut self, cx: &mut Context<Self>) {
+        self.strokes_write().clear();
+        cx.notify();
+    }
+
+    /// Keep the drawing as a paint sticker at the same place on the screen.
+    fn save_as_sticker(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let strokes = self
+            .strokes_write()
+            .iter()
+            .map(|x| x.stroke.clone())
+            .collect::<Vec<_>>();
+        let Some((min, max)) = stroke_bounds(&strokes) else {
+            self.error = Some("Nothing drawn yet".to_string());
+            cx.notify();
+            return;
+        };
+
+        let offset_x = min.x - SAVE_MARGIN;
+        let offset_y = min.y - SAVE_MARGIN;
+        let strokes = strokes
+            .into_iter()
+            .map(|stroke| PaintStroke {
+                points: stroke
+                    .points
+                    .iter()
+                    .map(|p| PaintPoint {
+                        x: p.x - offset_x,
+                        y: p.y - offset_y,
+                    })
+                    .collect(),
+                ..stroke
+            })
+            .collect();
+        let content = match paint::content_json(strokes) {
+            Ok(content) => content,
+            Err(err) => {
+                self.error = Some(format!("Failed to serialize annotation: {err}"));
+                cx.notify();
+                return;
+            }
+        };
+
+        let min_size = paint::PaintSticker::min_window_size();
+        let detail = StickerDetail {
+            id: 0,
+            title: "Annotation".to_string(),
+            content,
+            color: StickerColor::Gray,
+            sticker_type: StickerType::Paint,
+            state: StickerState::Open,
+            left: (self.origin.x.to_f64() as f32 + offset_x) as i32,
+            top: (self.origin.y.to_f64() as f32 + offset_y) as i32,
+            width: ((max.x - min.x + SAVE_MARGIN * 2.0) as i32).max(min_size.width),
+            height: ((max.y - min.y + SAVE_MARGIN * 2.0) as i32).max(min_size.height),
+            top_most: false,
+            settings: String::new(),
+            temporary: false,
+            expires_at: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+        cx.spawn(async move |_, cx| {
+            let id = match store.insert_sticker(detail).await {
+                Ok(id) => id,
+                Err(err) => {
+                    tracing::error!(error = ?err, "Failed to save annotation as sticker");
+                    return;
+                }
+            };
+            tracing::info!(id, "Annotation saved as paint sticker");
+            let _ = sticker_events_tx.send(StickerWindowEvent::Created { id });
+            let _ = sticker_events_tx.send(StickerWindowEvent::StoreChanged);
+
+            if let Err(err) = StickerWindow::open_async(cx, sticker_events_tx, store, id).await {
+                tracing::error!(id, error = ?err, "Failed to open annotation sticker");
+            }
+        })
+        .detach();
+
+        self.close(window);
+    }
+
+    fn throttled_notify(&mut self, cx: &mut Context<Self>) {
+        let now = Instant::now();
+        if self
+            .last_notify_at
+            .is_none_or(|last| now.duration_since(last) >= NOTIFY_MIN_INTERVAL)
+        {
+            self.last_notify_at = Some(now);
+            cx.notify();
+        }
+    }
+
+    fn toolbar_view(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let current_color = self.current_color;
+
+        let colors = PAINT_COLORS.iter().map(|&c| {
+            let is_selected = c == current_color;
+            div()
+                .size(px(16.0))
+                .bg(rgba(c))
+                .rounded_full()
+                .cursor_pointer()
+                .border_1()
+                .border_color(if is_selected {
+                    gpui::white()
+                } else {
+                    transparent_black()
+                })
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _, _, cx| {
+                        this.current_color = c;
+                        cx.stop_propagation();
+                        cx.notify();
+                    }),
+                )
+        });
+
+        let widths = PAINT_STROKE_WIDTHS.iter().map(|&w| {
+            let is_selected = (w - self.current_width).abs() < f32::EPSILON;
+            div()
+                .cursor_pointer()
+                .child(paint::make_dot(w, current_color, is_selected))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _, _, cx| {
+                        this.current_width = w;
+                        cx.stop_propagation();
+                        cx.notify();
+                    }),
+                )
+        });
+
+        h_flex()
+            .absolute()
+            .top_2()
+            .left_0()
+            .right_0()
+            .justify_center()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .items_center()
+                    .rounded_md()
+                    .bg(cx.theme().background.opacity(0.9))
+                    .text_xs()
+                    .text_color(cx.theme().foreground)
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .children(widths)
+                    .child(div().child("|").opacity(0.2))
+                    .children(colors)
+                    .child(div().child("|").opacity(0.2))
+                    .child(
+                        Button::new("clear")
+                            .icon(IconName::Eraser)
+                            .xsmall()
+                            .tooltip("Clear (Esc)")
+                            .on_click(cx.listener(|this, _, _, cx| this.clear(cx))),
+                    )
+                    .child(
+                        Button::new("save")
+                            .icon(IconName::Pin)
+                            .xsmall()
+                            .primary()
+                            .tooltip("Save as paint sticker")
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.save_as_sticker(window, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("close")
+                            .icon(IconName::Close)
+                            .xsmall()
+                            .tooltip("Close (Esc when empty)")
+                            .on_click(cx.listener(|this, _, window, _| this.close(window))),
+                    )
+                    .when_some(self.error.clone(), |view, msg| {
+                        view.child(div().text_color(cx.theme().danger).child(msg))
+                    }),
+            )
+    }
+}
+
+impl Render for AnnotationWindow {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let strokes = self.strokes.clone();
+
+        div()
+            .size_full()
+            .relative()
+            // Nearly invisible, yet enough for the OS to send us the mouse events.
+            .bg(rgba(0x00000010))
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key == "escape" {
+                    if this.is_empty() {
+                        this.close(window);
+                    } else {
+                        this.clear(cx);
+                    }
+                }
+            }))
+            .child(
+                canvas(
+                    move |_, _, _| {},
+                    move |_, _, window, _| {
+                        let strokes = match strokes.read() {
+                            Ok(guard) => guard,
+                            Err(err) => err.into_inner(),
+                        };
+                        paint::paint_strokes(window, &strokes, &paint::PaintView::default());
+                    },
+                )
+                .size_full(),
+            )
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, ev: &MouseDownEvent, window, cx| {
+                    window.focus(&this.focus_handle);
+                    this.painting = true;
+                    this.error = None;
+                    let stroke = PaintStroke {
+                        points: vec![PaintPoint::from(ev.position)],
+                        color: this.current_color,
+                        width: this.current_width,
+                    };
+                    this.strokes_write().push(PaintStrokeState::new(stroke));
+                    cx.notify();
+                }),
+            )
+            .on_mouse_move(cx.listener(|this, ev: &MouseMoveEvent, _, cx| {
+                if !this.painting {
+                    return;
+                }
+
+                {
+                    let mut strokes = this.strokes_write();
+                    let Some(stroke) = strokes.last_mut() else {
+                        return;
+                    };
+                    let p = PaintPoint::from(ev.position);
+                    if let Some(last) = stroke.stroke.points.last() {
+                        let min_distance = paint::min_point_distance_for_width(stroke.stroke.width);
+                        let (dx, dy) = (p.x - last.x, p.y - last.y);
+                        if dx * dx + dy * dy < min_distance * min_distance {
+                            return;
+                        }
+                    }
+                    stroke.stroke.points.push(p);
+                    stroke.rebuild_cache();
+                }
+
+                this.throttled_notify(cx);
+            }))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _: &MouseUpEvent, _, cx| {
+                    this.painting = false;
+                    this.last_notify_at = None;
+                    cx.notify();
+                }),
+            )
+            .child(self.toolbar_view(cx))
+    }
+}
+
+/// Smallest and largest point of all strokes, `None` when nothing was drawn.
+fn stroke_bounds(strokes: &[PaintStroke]) -> Option<(PaintPoint, PaintPoint)> {
+    let mut points = strokes.iter().flat_map(|s| s.points.iter());
+    let first = points.next()?;
+    let (mut min, mut max) = (first.clone(), first.clone());
+    for p in points {
+        min.x = min.x.min(p.x);
+        min.y = min.y.min(p.y);
+        max.x = max.x.max(p.x);
+        max.y = max.y.max(p.y);
+    }
+    Some((min, max))
+}
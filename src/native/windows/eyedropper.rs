@@ -0,0 +1,188 @@
+use std::sync::{Arc, RwLock};
+
+use gpui::{
+    AnyWindowHandle, App, AppContext, Bounds, Context, FocusHandle, KeyDownEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, Pixels, Point, Render, Window, WindowBackgroundAppearance,
+    WindowBounds, WindowOptions, div, prelude::*, px, rgb, rgba, transparent_black,
+};
+use gpui_component::{ActiveTheme, Root, h_flex};
+
+use crate::native::components::stickers::palette;
+use crate::native::screen::ScreenCapture;
+use crate::native::top_most::set_window_top_most;
+
+static EYEDROPPER_WINDOW: RwLock<Option<AnyWindowHandle>> = RwLock::new(None);
+
+type OnPick = Box<dyn FnOnce(u32, &mut App)>;
+
+/// A transparent layer over the primary display showing the color under the cursor,
+/// a click picks it and Esc cancels. The colors come from a capture taken before
+/// the layer opened, so the layer itself never tints them.
+pub struct EyedropperWindow {
+    capture: Arc<ScreenCapture>,
+    focus_handle: FocusHandle,
+    hover: Option<(Point<Pixels>, u32)>,
+    on_pick: Option<OnPick>,
+}
+
+impl EyedropperWindow {
+    /// Capture the screen in the background, then let the user pick a color from it.
+    pub fn open(cx: &mut App, on_pick: impl FnOnce(u32, &mut App) + 'static) {
+        let handle = EYEDROPPER_WINDOW.read().ok().and_then(|x| *x);
+        if let Some(handle) = handle {
+            let _ = handle.update(cx, |_, window, _| window.remove_window());
+            Self::forget_window();
+        }
+
+        let capture = cx
+            .background_executor()
+            .spawn(async { ScreenCapture::primary() });
+        cx.spawn(async move |cx| {
+            let capture = match capture.await {
+                Ok(capture) => Arc::new(capture),
+                Err(err) => {
+                    tracing::error!(error = ?err, "Failed to capture the screen");
+                    return;
+                }
+            };
+            let _ = cx.update(|cx| {
+                if let Err(err) = Self::open_window(cx, capture, Box::new(on_pick)) {
+                    tracing::error!(error = ?err, "Failed to open eyedropper window");
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn open_window(
+        cx: &mut App,
+        capture: Arc<ScreenCapture>,
+        on_pick: OnPick,
+    ) -> anyhow::Result<()> {
+        let display = cx
+            .primary_display()
+            .ok_or_else(|| anyhow::anyhow!("No display found"))?;
+        let bounds: Bounds<Pixels> = display.bounds();
+
+        let handle = cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                display_id: Some(display.id()),
+                window_background: WindowBackgroundAppearance::Transparent,
+                titlebar: None,
+                focus: true,
+                is_movable: false,
+                ..Default::default()
+            },
+            |window, cx| {
+                set_window_top_most(window, true);
+                window.on_window_should_close(cx, |_, _| {
+                    Self::forget_window();
+                    true
+                });
+                let view = cx.new(|cx| {
+                    let focus_handle = cx.focus_handle();
+                    window.focus(&focus_handle);
+                    EyedropperWindow {
+                        capture,
+                        focus_handle,
+                        hover: None,
+                        on_pick: Some(on_pick),
+                    }
+                });
+                cx.new(|cx| Root::new(view, window, cx).bg(transparent_black().alpha(0.0)))
+            },
+        )?;
+
+        if let Ok(mut eyedropper_window) = EYEDROPPER_WINDOW.write() {
+            *eyedropper_window = Some(handle.into());
+        }
+
+        Ok(())
+    }
+
+    fn forget_window() {
+        if let Ok(mut eyedropper_window) = EYEDROPPER_WINDOW.write() {
+            *eyedropper_window = None;
+        }
+    }
+
+    fn close(&mut self, window: &mut Window) {
+        Self::forget_window();
+        window.remove_window();
+    }
+
+    fn color_at(&self, position: Point<Pixels>, window: &Window) -> Option<u32> {
+        let size = window.viewport_size();
+        if size.width <= px(0.0) || size.height <= px(0.0) {
+            return None;
+        }
+        self.capture
+            .color_at(position.x / size.width, position.y / size.height)
+    }
+
+    fn pick(&mut self, position: Point<Pixels>, window: &mut Window, cx: &mut Context<Self>) {
+        let color = self.color_at(position, window);
+        let on_pick = self.on_pick.take();
+        self.close(window);
+        if let (Some(color), Some(on_pick)) = (color, on_pick) {
+            on_pick(color, cx);
+        }
+    }
+}
+
+impl Render for EyedropperWindow {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .relative()
+            // Nearly invisible, yet enough for the OS to send us the mouse events.
+            .bg(rgba(0x00000010))
+            .cursor_crosshair()
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, _| {
+                if event.keystroke.key == "escape" {
+                    this.close(window);
+                }
+            }))
+            .on_mouse_move(cx.listener(|this, ev: &MouseMoveEvent, window, cx| {
+                this.hover = this
+                    .color_at(ev.position, window)
+                    .map(|color| (ev.position, color));
+                cx.notify();
+            }))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, ev: &MouseDownEvent, window, cx| {
+                    this.pick(ev.position, window, cx)
+                }),
+            )
+            .when_some(self.hover, |view, (position, color)| {
+                view.child(
+                    h_flex()
+                        .absolute()
+                        .left(position.x + px(16.0))
+                        .top(position.y + px(16.0))
+                        .gap_2()
+                        .p_1()
+                        .rounded_md()
+                        .bg(cx.theme().background.opacity(0.9))
+                        .child(
+                            div()
+                                .size(px(24.0))
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(cx.theme().border)
+                                .bg(rgb(color)),
+                        )
+                        .child(
+                            div()
+                                .pr_1()
+                                .text_sm()
+                                .font_family(cx.theme().mono_font_family.clone())
+                                .child(palette::hex(color)),
+                        ),
+                )
+            })
+    }
+}
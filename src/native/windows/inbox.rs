@@ -0,0 +1,338 @@
+use std::collections::VecDeque;
+use std::sync::{RwLock, mpsc};
+
+use gpui::{
+    AnyWindowHandle, App, AppContext, Bounds, Context, ExternalPaths, FocusHandle, IntoElement,
+    KeyDownEvent, Pixels, Render, Size, Window, WindowBackgroundAppearance, WindowBounds,
+    WindowOptions, div, prelude::*, px, size, transparent_black,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Root, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    tooltip::Tooltip,
+    v_flex,
+};
+
+use crate::native::components::IconName;
+use crate::native::decorations::WindowDragArea;
+use crate::native::inbox::{self, Draft, InboxItem};
+use crate::native::top_most::set_window_top_most;
+use crate::native::windows::{StickerWindowEvent, sticker::StickerWindow};
+use crate::storage::ArcStickerStore;
+use crate::storage::settings::SettingsStore;
+
+static INBOX_WINDOW: RwLock<Option<AnyWindowHandle>> = RwLock::new(None);
+
+const BUBBLE_SIZE: Size<Pixels> = size(px(64.0), px(64.0));
+/// Room for the question about a duplicate.
+const PROMPT_SIZE: Size<Pixels> = size(px(260.0), px(120.0));
+
+enum DuplicateChoice {
+    OpenExisting,
+    CreateAnyway,
+    Skip,
+}
+
+/// A small always-on-top bubble, a desktop inbox which turns dropped files and
+/// pasted text, links and images into stickers.
+pub struct InboxWindow {
+    store: ArcStickerStore,
+    sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+    focus_handle: FocusHandle,
+    busy: bool,
+    /// Items whose content is already in a sticker, with the id of that sticker.
+    duplicates: VecDeque<(Draft, i64)>,
+    error: Option<String>,
+}
+
+impl InboxWindow {
+    /// Open or close the bubble to match the settings.
+    pub fn sync_with_settings(
+        cx: &mut App,
+        store: &ArcStickerStore,
+        sticker_events_tx: &mpsc::Sender<StickerWindowEvent>,
+    ) {
+        let enabled = SettingsStore::get(cx).inbox_bubble && !store.is_read_only();
+        let handle = INBOX_WINDOW.read().ok().and_then(|x| *x);
+
+        match (enabled, handle) {
+            (true, None) => {
+                if let Err(err) = Self::open(cx, store.clone(), sticker_events_tx.clone()) {
+                    tracing::error!(error = ?err, "Failed to open inbox bubble");
+                }
+            }
+            (false, Some(handle)) => {
+                let _ = handle.update(cx, |_, window, _| window.remove_window());
+                Self::forget_window();
+            }
+            _ => {}
+        }
+    }
+
+    fn open(
+        cx: &mut App,
+        store: ArcStickerStore,
+        sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
+    ) -> anyhow::Result<()> {
+        let bounds = Bounds::centered(None, BUBBLE_SIZE, cx);
+
+        let handle = cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(48.0), px(48.0))),
+                window_background: WindowBackgroundAppearance::Transparent,
+                titlebar: None,
+                focus: false,
+                ..Default::default()
+            },
+            |window, cx| {
+                set_window_top_most(window, true);
+                window.on_window_should_close(cx, |_, _| {
+                    Self::forget_window();
+                    true
+                });
+                let view = cx.new(|cx| InboxWindow {
+                    store,
+                    sticker_events_tx,
+                    focus_handle: cx.focus_handle(),
+                    busy: false,
+                    duplicates: VecDeque::new(),
+                    error: None,
+                });
+                cx.new(|cx| Root::new(view, window, cx).bg(transparent_black().alpha(0.0)))
+            },
+        )?;
+
+        if let Ok(mut inbox_window) = INBOX_WINDOW.write() {
+            *inbox_window = Some(handle.into());
+        }
+
+        Ok(())
+    }
+
+    fn forget_window() {
+        if let Ok(mut inbox_window) = INBOX_WINDOW.write() {
+            *inbox_window = None;
+        }
+    }
+
+    fn paste(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(item) = cx.read_from_clipboard() else {
+            return;
+        };
+
+        let items = inbox::clipboard_item(&item).into_iter().collect();
+        self.create(items, window, cx);
+    }
+
+    fn drop_paths(&mut self, paths: &ExternalPaths, window: &mut Window, cx: &mut Context<Self>) {
+        let items = paths.paths().iter().cloned().map(InboxItem::File).collect();
+        self.create(items, window, cx);
+    }
+
+    fn create(&mut self, items: Vec<InboxItem>, window: &mut Window, cx: &mut Context<Self>) {
+        if items.is_empty() {
+            self.error = Some("Nothing to turn into a sticker".to_string());
+            cx.notify();
+            return;
+        }
+
+        self.busy = true;
+        self.error = None;
+        cx.notify();
+
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+        let entity = cx.entity();
+        window
+            .spawn(cx, async move |cx| {
+                let mut errors = Vec::new();
+                let mut duplicates = Vec::new();
+                for item in items {
+                    let draft = match Draft::from_item(item) {
+                        Ok(draft) => draft,
+                        Err(err) => {
+                            tracing::error!(error = ?err, "Failed to read inbox item");
+                            errors.push(format!("{err:#}"));
+                            continue;
+                        }
+                    };
+
+                    match inbox::find_duplicate(&store, &draft).await {
+                        Ok(Some(existing)) => {
+                            duplicates.push((draft, existing));
+                            continue;
+                        }
+                        Ok(None) => {}
+                        Err(err) => {
+                            tracing::warn!(error = ?err, "Failed to look for a duplicate sticker");
+                        }
+                    }
+
+                    if let Err(err) =
+                        inbox::create_sticker(cx, store.clone(), sticker_events_tx.clone(), draft)
+                            .await
+                    {
+                        tracing::error!(error = ?err, "Failed to create sticker from inbox");
+                        errors.push(format!("{err:#}"));
+                    }
+                }
+
+                let _ = cx.update_window_entity(&entity, |this, window, cx| {
+                    this.busy = false;
+                    this.error = (!errors.is_empty()).then(|| errors.join("\n"));
+                    if !duplicates.is_empty() {
+                        this.duplicates.extend(duplicates);
+                        window.resize(PROMPT_SIZE);
+                    }
+                    cx.notify();
+                });
+            })
+            .detach();
+    }
+
+    fn resolve_duplicate(
+        &mut self,
+        choice: DuplicateChoice,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((draft, existing)) = self.duplicates.pop_front() else {
+            return;
+        };
+        if self.duplicates.is_empty() {
+            window.resize(BUBBLE_SIZE);
+        }
+        cx.notify();
+
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_tx.clone();
+        cx.spawn(async move |this, cx| {
+            let result = match choice {
+                DuplicateChoice::OpenExisting => {
+                    StickerWindow::open_async(cx, sticker_events_tx, store, existing).await
+                }
+                DuplicateChoice::CreateAnyway => {
+                    inbox::create_sticker(cx, store, sticker_events_tx, draft)
+                        .await
+                        .map(|_| ())
+                }
+                DuplicateChoice::Skip => Ok(()),
+            };
+
+            if let Err(err) = result {
+                tracing::error!(error = ?err, "Failed to handle duplicate inbox item");
+                let _ = this.update(cx, |this, cx| {
+                    this.error = Some(format!("{err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn duplicate_prompt(&self, draft: &Draft, cx: &mut Context<Self>) -> gpui::AnyElement {
+        v_flex()
+            .size_full()
+            .p_2()
+            .gap_2()
+            .rounded_lg()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().background.opacity(0.95))
+            .text_color(cx.theme().foreground)
+            .text_xs()
+            .child(div().window_drag_area().child(format!(
+                "A {} sticker with the same content as \"{}\" already exists.",
+                draft.sticker_type.label(),
+                draft.title
+            )))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("open-existing")
+                            .label("Open it")
+                            .xsmall()
+                            .primary()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.resolve_duplicate(DuplicateChoice::OpenExisting, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("create-anyway")
+                            .label("Create anyway")
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.resolve_duplicate(DuplicateChoice::CreateAnyway, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("skip")
+                            .label("Skip")
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.resolve_duplicate(DuplicateChoice::Skip, window, cx)
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+impl Render for InboxWindow {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if let Some((draft, _)) = self.duplicates.front() {
+            return div()
+                .size_full()
+                .child(self.duplicate_prompt(draft, cx))
+                .children(Root::render_dialog_layer(window, cx))
+                .into_any_element();
+        }
+
+        let tooltip = self
+            .error
+            .clone()
+            .unwrap_or_else(|| "Drop files here, or click and paste (Ctrl+V)".to_string());
+        let border = if self.error.is_some() {
+            cx.theme().danger
+        } else {
+            cx.theme().border
+        };
+
+        v_flex()
+            .id("inbox")
+            .size_full()
+            .items_center()
+            .justify_center()
+            .rounded_full()
+            .border_2()
+            .border_color(border)
+            .bg(cx.theme().background.opacity(0.85))
+            .text_color(cx.theme().foreground)
+            .when(self.busy, |view| view.opacity(0.5))
+            .window_drag_area()
+            .track_focus(&self.focus_handle)
+            .on_mouse_down(
+                gpui::MouseButton::Left,
+                cx.listener(|this, _, window, _| window.focus(&this.focus_handle)),
+            )
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                let modifiers = &event.keystroke.modifiers;
+                if (modifiers.control || modifiers.platform)
+                    && event.keystroke.key.eq_ignore_ascii_case("v")
+                {
+                    this.paste(window, cx);
+                }
+            }))
+            .drag_over::<ExternalPaths>(|style, _, _, cx| style.bg(cx.theme().accent))
+            .on_drop(cx.listener(|this, paths: &ExternalPaths, window, cx| {
+                this.drop_paths(paths, window, cx);
+            }))
+            .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+            .child(div().child(Icon::new(IconName::Download).with_size(px(24.0))))
+            .children(Root::render_dialog_layer(window, cx))
+            .into_any_element()
+    }
+}
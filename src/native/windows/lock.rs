@@ -0,0 +1,156 @@
+use std::sync::RwLock;
+
+use gpui::{
+    AnyWindowHandle, App, AppContext, Bounds, Context, Entity, IntoElement, Render, Window,
+    WindowBackgroundAppearance, WindowBounds, WindowOptions, div, prelude::*, px, size,
+    transparent_black,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Root, Sizable,
+    alert::Alert,
+    black,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+
+use crate::native::app_lock;
+use crate::native::components::IconName;
+use crate::native::decorations::{self, WindowDragArea};
+use crate::native::top_most::set_window_top_most;
+
+static LOCK_WINDOW: RwLock<Option<AnyWindowHandle>> = RwLock::new(None);
+
+/// Asks for the PIN while the app is locked, closes itself once it matches.
+pub struct LockWindow {
+    pin: Entity<InputState>,
+    error: Option<String>,
+    checking: bool,
+}
+
+impl LockWindow {
+    pub fn open(cx: &mut App) -> anyhow::Result<()> {
+        if let Ok(lock_window) = LOCK_WINDOW.read()
+            && let Some(handle) = lock_window.as_ref()
+            && handle
+                .update(cx, |_, window, _| window.activate_window())
+                .is_ok()
+        {
+            return Ok(());
+        }
+
+        let bounds = Bounds::centered(None, size(px(320.), px(180.0)), cx);
+
+        let handle = cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(240.0), px(160.0))),
+                window_background: WindowBackgroundAppearance::Transparent,
+                titlebar: Some(decorations::titlebar_options("Rustickers locked")),
+                window_decorations: decorations::window_decorations(),
+                focus: true,
+                ..Default::default()
+            },
+            |window, cx| {
+                set_window_top_most(window, true);
+                // Closing it keeps the app locked, the overlays open it again.
+                window.on_window_should_close(cx, |_, _| {
+                    Self::forget_window();
+                    true
+                });
+                let view = cx.new(|cx| LockWindow::new(window, cx));
+                cx.new(|cx| Root::new(view, window, cx).bg(transparent_black().alpha(0.0)))
+            },
+        )?;
+
+        if let Ok(mut lock_window) = LOCK_WINDOW.write() {
+            *lock_window = Some(handle.into());
+        }
+
+        Ok(())
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let pin = cx.new(|cx| InputState::new(window, cx).placeholder("PIN").masked(true));
+        cx.subscribe_in(&pin, window, |this, _, event: &InputEvent, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.unlock(window, cx);
+            }
+        })
+        .detach();
+        pin.update(cx, |input, cx| input.focus(window, cx));
+
+        Self {
+            pin,
+            error: None,
+            checking: false,
+        }
+    }
+
+    fn forget_window() {
+        if let Ok(mut lock_window) = LOCK_WINDOW.write() {
+            *lock_window = None;
+        }
+    }
+
+    fn unlock(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.checking {
+            return;
+        }
+        if let Some(wait) = app_lock::retry_after(cx) {
+            self.error = Some(format!(
+                "Too many wrong PINs, try again in {} s",
+                wait.as_secs() + 1
+            ));
+            cx.notify();
+            return;
+        }
+
+        let pin = self.pin.read(cx).value().to_string();
+        let unlocked = app_lock::unlock(cx, pin);
+        self.checking = true;
+        self.error = None;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let unlocked = unlocked.await;
+            let _ = this.update_in(cx, |this, window, cx| {
+                this.checking = false;
+                if unlocked {
+                    Self::forget_window();
+                    window.remove_window();
+                    return;
+                }
+
+                this.pin.update(cx, |input, cx| {
+                    input.set_value("", window, cx);
+                    input.focus(window, cx);
+                });
+                this.error = Some("Wrong PIN".to_string());
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+}
+
+impl Render for LockWindow {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .p_3()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .text_color(cx.theme().foreground)
+            .bg(black().opacity(0.85))
+            .window_drag_area()
+            .child(Icon::new(IconName::Lock).with_size(px(24.)))
+            .child(div().text_sm().child("Enter the PIN to unlock Rustickers"))
+            .child(Input::new(&self.pin).small().disabled(self.checking))
+            .when_some(self.error.as_ref(), |view, msg| {
+                view.child(Alert::error("lock-error", msg.as_str()).small())
+            })
+            .children(decorations::resize_handles(window))
+            .children(Root::render_dialog_layer(window, cx))
+    }
+}
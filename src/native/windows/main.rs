@@ -1,34 +1,95 @@
 use gpui::{
     AnyWindowHandle, App, AsyncApp, Bounds, Context, Entity, IntoElement, MouseButton,
-    MouseUpEvent, Render, SharedString, TitlebarOptions, WeakEntity, Window,
-    WindowBackgroundAppearance, WindowBounds, WindowControlArea, WindowOptions, div, prelude::*,
-    px, rgb, rgba, size, transparent_black,
+    MouseUpEvent, Render, ScrollHandle, SharedString, WeakEntity, Window,
+    WindowBackgroundAppearance, WindowBounds, WindowOptions, div, prelude::*, px, rgb, rgba, size,
+    transparent_black,
 };
 use gpui_component::Root;
 use gpui_component::alert::Alert;
 use gpui_component::button::Button;
 use gpui_component::input::{Input, InputEvent, InputState};
-use gpui_component::menu::{DropdownMenu, PopupMenuItem};
+use gpui_component::menu::{DropdownMenu, PopupMenu, PopupMenuItem};
 use gpui_component::scroll::ScrollableElement;
 use gpui_component::spinner::Spinner;
 use gpui_component::*;
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::mpsc::{self};
 use std::time::Duration;
 
+use crate::model::collection::Collection;
+use crate::model::focus::{self, FocusTotals};
 use crate::model::sticker::*;
+use crate::native::app_lock;
+use crate::native::backup::{self, Backup};
 use crate::native::components::IconName;
 use crate::native::components::stickers::Sticker;
+use crate::native::components::stickers::agenda::AgendaSticker;
+use crate::native::components::stickers::animation::AnimationSticker;
+use crate::native::components::stickers::calculator::CalculatorSticker;
+use crate::native::components::stickers::chart::ChartSticker;
+use crate::native::components::stickers::clock::ClockSticker;
+use crate::native::components::stickers::code::CodeSticker;
 use crate::native::components::stickers::command::CommandSticker;
+use crate::native::components::stickers::counter::CounterSticker;
+use crate::native::components::stickers::currency::CurrencySticker;
+use crate::native::components::stickers::git::GitSticker;
+use crate::native::components::stickers::github::GitHubSticker;
+use crate::native::components::stickers::habit::HabitSticker;
+use crate::native::components::stickers::http::HttpSticker;
+use crate::native::components::stickers::image::ImageSticker;
+use crate::native::components::stickers::kanban::KanbanSticker;
+use crate::native::components::stickers::launcher::LauncherSticker;
 use crate::native::components::stickers::markdown::MarkdownSticker;
+use crate::native::components::stickers::media::MediaSticker;
+use crate::native::components::stickers::mindmap::MindMapSticker;
 use crate::native::components::stickers::paint::PaintSticker;
+use crate::native::components::stickers::palette::PaletteSticker;
+use crate::native::components::stickers::pomodoro::PomodoroSticker;
+use crate::native::components::stickers::reminder::ReminderSticker;
+use crate::native::components::stickers::system::SystemSticker;
+use crate::native::components::stickers::time_tracker::TimeTrackerSticker;
 use crate::native::components::stickers::timer::TimerSticker;
+use crate::native::components::stickers::units::UnitsSticker;
+use crate::native::components::stickers::uptime::UptimeSticker;
+use crate::native::components::stickers::web::WebSticker;
+use crate::native::decorations::{self, WindowDragArea};
+use crate::native::dialogs;
+use crate::native::encrypted_backup;
+use crate::native::hooks;
+use crate::native::importers::{self, NoteSource};
+use crate::native::inbox::{self, Draft, InboxItem};
+use crate::native::top_most::{TOP_MOST_SUPPORTED, hide_window, set_window_top_most};
+use crate::native::webhook;
+use crate::native::weekly_review;
 use crate::native::windows::StickerWindowEvent;
+use crate::native::windows::settings::SettingsWindow;
 use crate::native::windows::sticker::StickerWindow;
 use crate::storage::ArcStickerStore;
+use crate::storage::paths::AppPaths;
+use crate::storage::settings::SettingsStore;
+use crate::utils::template;
 
-const STICKER_LOAD_LIMIT: i64 = 10000;
+/// Stickers loaded at once, more are loaded when the list is scrolled near its end.
+const STICKER_PAGE_SIZE: i64 = 100;
+/// Distance from the end of the list at which the next page is loaded.
+const LOAD_MORE_THRESHOLD: f32 = 400.0;
 const STICKER_EVENT_PUMP_INTERVAL: Duration = Duration::from_millis(120);
+const CURSOR_OFFSET: i32 = 24;
+/// How long popup mode waits before hiding, a window opened from here is activated
+/// only after the main window lost focus.
+const POPUP_HIDE_DELAY: Duration = Duration::from_millis(150);
+const CLIPBOARD_LABEL_MAX_CHARS: usize = 40;
+const READ_ONLY_MESSAGE: &str = "Read-only: the database could not be opened for writing (locked by another program or no permission). Stickers can be viewed, changes are not saved.";
+
+/// What the sticker list shows, a reload for the same query keeps the loaded pages.
+#[derive(Debug, Clone, PartialEq)]
+struct ListQuery {
+    search: Option<String>,
+    filter: StickerFilter,
+    order_by: StickerOrderBy,
+    tag: Option<String>,
+}
 
 pub struct MainWindow {
     store: ArcStickerStore,
@@ -36,10 +97,52 @@ pub struct MainWindow {
 
     query: Entity<InputState>,
     order: StickerOrderBy,
+    color_filter: Option<StickerColor>,
+    /// Only list stickers with this tag.
+    tag_filter: Option<String>,
+    /// Every tag in use, offered by the tag filter.
+    tags: Vec<String>,
+    /// Only list stickers of this collection.
+    collection_filter: Option<i64>,
+    collections: Vec<Collection>,
+    show_collections: bool,
+    /// Name of a new collection, typed in the collections sidebar.
+    collection_name: Entity<InputState>,
+    group_by_tag: bool,
+    bucket: StickerBucket,
+    /// Create scratch stickers which are deleted when closed, see [`StickerDetail::temporary`].
+    create_temporary: bool,
     stickers: Vec<StickerBrief>,
+    /// The search and filters `stickers` were loaded for, see [`ListQuery`].
+    loaded_query: Option<ListQuery>,
+    /// Stickers matching the search, `None` with a tag filter.
+    stickers_total: Option<i64>,
+    /// More stickers match than are loaded.
+    has_more: bool,
+    list_scroll: ScrollHandle,
+    sticker_tags: HashMap<i64, Vec<String>>,
+    /// Tag sections folded in the group by tag mode, untagged is the empty string.
+    collapsed_tags: HashSet<String>,
+    /// Pomodoro focus sessions of today and this week.
+    focus_totals: FocusTotals,
+
+    /// Rule of the bulk recolor dialog: stickers matching the query and type get the color.
+    recolor_query: Entity<InputState>,
+    recolor_type: Option<StickerType>,
+    recolor_color: StickerColor,
+
+    /// Database snapshots offered by the restore dialog, newest first.
+    backups: Vec<Backup>,
+    restore_choice: Option<usize>,
+    /// Passphrase of the encrypted backup dialogs, typed twice when exporting.
+    passphrase: Entity<InputState>,
+    passphrase_confirm: Entity<InputState>,
 
     loading: bool,
+    /// The search or filter changed while loading, load again once done.
+    reload_pending: bool,
     error: Option<String>,
+    warning: Option<String>,
 }
 
 impl MainWindow {
@@ -48,6 +151,7 @@ impl MainWindow {
         sticker_events_rx: mpsc::Receiver<StickerWindowEvent>,
         sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
         store: ArcStickerStore,
+        startup_warning: Option<String>,
     ) -> anyhow::Result<AnyWindowHandle> {
         let bounds = Bounds::centered(None, size(px(340.), px(550.0)), cx);
 
@@ -56,15 +160,20 @@ impl MainWindow {
                 window_bounds: Some(WindowBounds::Windowed(bounds)),
                 window_min_size: Some(size(px(300.0), px(400.0))),
                 window_background: WindowBackgroundAppearance::Transparent,
-                titlebar: Some(TitlebarOptions {
-                    title: Some(SharedString::new("Rustiers")),
-                    ..TitleBar::title_bar_options()
-                }),
+                titlebar: Some(decorations::titlebar_options("Rustiers")),
+                window_decorations: decorations::window_decorations(),
                 ..Default::default()
             },
             |window, cx| {
                 let view = cx.new(|cx| {
-                    MainWindow::new(window, cx, sticker_events_rx, sticker_events_tx, store)
+                    MainWindow::new(
+                        window,
+                        cx,
+                        sticker_events_rx,
+                        sticker_events_tx,
+                        store,
+                        startup_warning,
+                    )
                 });
                 cx.new(|cx| Root::new(view, window, cx).bg(transparent_black().alpha(0.0)))
             },
@@ -78,41 +187,117 @@ impl MainWindow {
         sticker_events_rx: mpsc::Receiver<StickerWindowEvent>,
         sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
         store: ArcStickerStore,
+        startup_warning: Option<String>,
     ) -> Self {
         let query = cx.new(|cx| InputState::new(window, cx).placeholder("Rustickers"));
+        let recolor_query =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Any text, empty for all"));
+        let collection_name =
+            cx.new(|cx| InputState::new(window, cx).placeholder("New collection"));
+        let passphrase = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Passphrase")
+                .masked(true)
+        });
+        let passphrase_confirm = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Passphrase again")
+                .masked(true)
+        });
 
         window.on_window_should_close(cx, |_, cx| {
             cx.quit();
             true
         });
 
+        if SettingsStore::get(cx).main_window_top_most {
+            set_window_top_most(window, true);
+        }
+
+        cx.observe_window_activation(window, |_, window, cx| {
+            if window.is_window_active() || !SettingsStore::get(cx).popup_mode {
+                return;
+            }
+            cx.spawn_in(window, async move |this, cx| {
+                cx.background_executor().timer(POPUP_HIDE_DELAY).await;
+                let _ = this.update_in(cx, |_, window, cx| {
+                    // Settings are opened from here, using them keeps the window.
+                    if !window.is_window_active() && !SettingsWindow::is_active(cx) {
+                        hide_window(window);
+                    }
+                });
+            })
+            .detach();
+        })
+        .detach();
+
         cx.spawn(async move |this, cx| {
             let _ = this.update(cx, |this, cx| {
                 this.spawn_load_stickers(cx);
+                this.spawn_load_focus_totals(cx);
             });
 
             Self::loop_events(this, sticker_events_rx, cx).await;
         })
         .detach();
 
+        // The search index answers quickly enough to search while typing.
         cx.subscribe(&query, |this, _, event: &InputEvent, cx| match event {
-            InputEvent::PressEnter { .. } => {
+            InputEvent::Change | InputEvent::PressEnter { .. } => {
                 this.spawn_load_stickers(cx);
             }
             _ => {}
         })
         .detach();
 
+        cx.subscribe_in(
+            &collection_name,
+            window,
+            |this, _, event: &InputEvent, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.create_collection(window, cx);
+                }
+            },
+        )
+        .detach();
+
         Self {
             store,
             sticker_events_sender: sticker_events_tx,
 
             query,
-            order: StickerOrderBy::CreatedDesc,
+            order: StickerOrderBy::PinnedFirst,
+            color_filter: None,
+            tag_filter: None,
+            tags: Vec::new(),
+            collection_filter: None,
+            collections: Vec::new(),
+            show_collections: false,
+            collection_name,
+            group_by_tag: false,
+            bucket: StickerBucket::Active,
+            create_temporary: false,
             stickers: Vec::new(),
+            loaded_query: None,
+            stickers_total: None,
+            has_more: false,
+            list_scroll: ScrollHandle::new(),
+            sticker_tags: HashMap::new(),
+            collapsed_tags: HashSet::new(),
+            focus_totals: FocusTotals::default(),
+
+            recolor_query,
+            recolor_type: None,
+            recolor_color: StickerColor::Gray,
+            backups: Vec::new(),
+            restore_choice: None,
+            passphrase,
+            passphrase_confirm,
 
             loading: false,
+            reload_pending: false,
             error: None,
+            warning: startup_warning,
         }
     }
 
@@ -138,7 +323,7 @@ impl MainWindow {
             let updated = this.update(cx, |this, cx| {
                 let mut changed = false;
                 for ev in events {
-                    changed |= this.apply_event(ev);
+                    changed |= this.apply_event(ev, cx);
                 }
                 if changed {
                     cx.notify();
@@ -151,7 +336,10 @@ impl MainWindow {
         }
     }
 
-    fn apply_event(&mut self, event: StickerWindowEvent) -> bool {
+    fn apply_event(&mut self, event: StickerWindowEvent, cx: &mut Context<Self>) -> bool {
+        hooks::run_for_event(&event, &self.store, cx);
+        webhook::send_for_event(&event, &self.store, cx);
+
         match event {
             StickerWindowEvent::TitleChanged { id, title } => {
                 if let Some(sticker) = self.stickers.iter_mut().find(|s| s.id == id)
@@ -163,6 +351,14 @@ impl MainWindow {
                 }
                 false
             }
+            StickerWindowEvent::ContentChanged { id, summary } => {
+                if let Some(sticker) = self.stickers.iter_mut().find(|s| s.id == id) {
+                    sticker.summary = summary;
+                    sticker.updated_at = crate::utils::time::now_unix_millis();
+                    return true;
+                }
+                false
+            }
             StickerWindowEvent::ColorChanged { id, color } => {
                 if let Some(sticker) = self.stickers.iter_mut().find(|s| s.id == id)
                     && sticker.color != color
@@ -181,11 +377,30 @@ impl MainWindow {
                 }
                 false
             }
+            StickerWindowEvent::Created { .. }
+            | StickerWindowEvent::TimerFinished { .. }
+            | StickerWindowEvent::CommandDone { .. }
+            | StickerWindowEvent::ReminderDue { .. } => false,
+            StickerWindowEvent::FocusSessionRecorded => {
+                self.spawn_load_focus_totals(cx);
+                false
+            }
+            StickerWindowEvent::StoreChanged => {
+                self.store.invalidate_sticker_cache();
+                self.spawn_load_stickers(cx);
+                self.spawn_load_focus_totals(cx);
+                false
+            }
         }
     }
 
-    fn create_sticker(&mut self, cx: &mut Context<Self>, sticker_type: &StickerType) {
-        if self.loading {
+    fn create_sticker(
+        &mut self,
+        window: &Window,
+        cx: &mut Context<Self>,
+        sticker_type: &StickerType,
+    ) {
+        if self.loading || self.store.is_read_only() {
             return;
         }
 
@@ -196,27 +411,111 @@ impl MainWindow {
             StickerType::Command => CommandSticker::default_window_size(),
             StickerType::Timer => TimerSticker::default_window_size(),
             StickerType::Paint => PaintSticker::default_window_size(),
+            StickerType::Image => ImageSticker::default_window_size(),
+            StickerType::Web => WebSticker::default_window_size(),
+            StickerType::Clock => ClockSticker::default_window_size(),
+            StickerType::Pomodoro => PomodoroSticker::default_window_size(),
+            StickerType::System => SystemSticker::default_window_size(),
+            StickerType::Agenda => AgendaSticker::default_window_size(),
+            StickerType::Habit => HabitSticker::default_window_size(),
+            StickerType::Kanban => KanbanSticker::default_window_size(),
+            StickerType::Code => CodeSticker::default_window_size(),
+            StickerType::Counter => CounterSticker::default_window_size(),
+            StickerType::Chart => ChartSticker::default_window_size(),
+            StickerType::Currency => CurrencySticker::default_window_size(),
+            StickerType::Animation => AnimationSticker::default_window_size(),
+            StickerType::GitHub => GitHubSticker::default_window_size(),
+            StickerType::Reminder => ReminderSticker::default_window_size(),
+            StickerType::Http => HttpSticker::default_window_size(),
+            StickerType::Palette => PaletteSticker::default_window_size(),
+            StickerType::Units => UnitsSticker::default_window_size(),
+            StickerType::Uptime => UptimeSticker::default_window_size(),
+            StickerType::Git => GitSticker::default_window_size(),
+            StickerType::Launcher => LauncherSticker::default_window_size(),
+            StickerType::Media => MediaSticker::default_window_size(),
+            StickerType::Calculator => CalculatorSticker::default_window_size(),
+            StickerType::MindMap => MindMapSticker::default_window_size(),
+            StickerType::TimeTracker => TimeTrackerSticker::default_window_size(),
         };
 
-        let title = match sticker_type {
+        let default_title = match sticker_type {
             StickerType::Markdown => "New Text Sticker",
             StickerType::Command => "New Command Sticker",
             StickerType::Timer => "New Timer Sticker",
             StickerType::Paint => "New Paint Sticker",
+            StickerType::Image => "New Image Sticker",
+            StickerType::Web => "New Web Sticker",
+            StickerType::Clock => "New Clock Sticker",
+            StickerType::Pomodoro => "New Pomodoro Sticker",
+            StickerType::System => "New System Sticker",
+            StickerType::Agenda => "New Agenda Sticker",
+            StickerType::Habit => "New Habit Sticker",
+            StickerType::Kanban => "New Kanban Sticker",
+            StickerType::Code => "New Code Sticker",
+            StickerType::Counter => "New Counter Sticker",
+            StickerType::Chart => "New Chart Sticker",
+            StickerType::Currency => "New Currency Sticker",
+            StickerType::Animation => "New Animation Sticker",
+            StickerType::GitHub => "New GitHub Sticker",
+            StickerType::Reminder => "New Reminder Sticker",
+            StickerType::Http => "New HTTP Sticker",
+            StickerType::Palette => "New Palette Sticker",
+            StickerType::Units => "New Units Sticker",
+            StickerType::Uptime => "New Uptime Sticker",
+            StickerType::Git => "New Git Sticker",
+            StickerType::Launcher => "New Launcher Sticker",
+            StickerType::Media => "New Media Sticker",
+            StickerType::Calculator => "New Calculator Sticker",
+            StickerType::MindMap => "New Mind map Sticker",
+            StickerType::TimeTracker => "New Time tracker Sticker",
+        };
+
+        let settings = SettingsStore::get(cx);
+        let color = settings.default_color(*sticker_type);
+        let title = match settings.title_template(*sticker_type) {
+            Some(title_template) => {
+                let mut vars = template::builtin_vars();
+                vars.push(("type".to_string(), sticker_type.label().to_string()));
+                template::render_template(title_template, &vars)
+            }
+            None => default_title.to_string(),
+        };
+
+        // A little up and left, so the cursor lands in the sticker instead of on its corner.
+        let (left, top) = if SettingsStore::get(cx).open_at_cursor {
+            let cursor = window.bounds().origin + window.mouse_position();
+            (
+                cursor.x.to_f64() as i32 - CURSOR_OFFSET,
+                cursor.y.to_f64() as i32 - CURSOR_OFFSET,
+            )
+        } else {
+            (100, 100)
         };
 
+        let temporary = self.create_temporary;
+        let ttl_minutes = SettingsStore::get(cx).temporary_ttl_minutes;
+        let expires_at = (temporary && ttl_minutes > 0)
+            .then(|| crate::utils::time::now_unix_millis() + i64::from(ttl_minutes) * 60_000);
+
         let detail = StickerDetail {
             id: 0,
-            title: title.to_string(),
+            title,
             content: "".to_string(),
-            color: StickerColor::Yellow,
+            color,
             sticker_type: *sticker_type,
-            state: StickerState::Open,
-            left: 100,
-            top: 100,
+            state: if temporary {
+                StickerState::Close
+            } else {
+                StickerState::Open
+            },
+            left,
+            top,
             width: size.width,
             height: size.height,
             top_most: false,
+            settings: String::new(),
+            temporary,
+            expires_at,
             created_at: 0,
             updated_at: 0,
         };
@@ -226,6 +525,7 @@ impl MainWindow {
         cx.spawn(
             async move |entity, cx| match store.insert_sticker(detail).await {
                 Ok(id) => {
+                    let _ = sticker_events_tx.send(StickerWindowEvent::Created { id });
                     if let Err(err) =
                         StickerWindow::open_async(cx, sticker_events_tx, store.clone(), id).await
                     {
@@ -250,8 +550,92 @@ impl MainWindow {
         .detach();
     }
 
+    /// Create the sticker the clipboard turned into, the store change reloads the list.
+    fn create_from_clipboard(&mut self, draft: Draft, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        self.error = None;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_sender.clone();
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = inbox::create_sticker(cx, store, sticker_events_tx, draft).await {
+                tracing::error!(error = ?err, "Failed to create sticker from clipboard");
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to create sticker from clipboard: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn create_weekly_review(&mut self, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        self.error = None;
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_sender.clone();
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = weekly_review::create(cx, store, sticker_events_tx).await {
+                tracing::error!(error = ?err, "Failed to create weekly review");
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to create weekly review: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn list_query(&self, cx: &App) -> ListQuery {
+        let search = self.query.read(cx).value().to_string();
+        ListQuery {
+            search: (!search.is_empty()).then_some(search),
+            filter: StickerFilter {
+                color: self.color_filter,
+                sticker_type: None,
+                collection: self.collection_filter,
+                bucket: self.bucket,
+            },
+            order_by: self.order,
+            tag: self.tag_filter.clone(),
+        }
+    }
+
+    async fn query_page(
+        store: &ArcStickerStore,
+        query: ListQuery,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<StickerBrief>> {
+        match query.tag {
+            Some(tag) => {
+                store
+                    .query_stickers_by_tag(
+                        tag,
+                        query.search,
+                        query.filter,
+                        query.order_by,
+                        limit,
+                        offset,
+                    )
+                    .await
+            }
+            None => {
+                store
+                    .query_stickers(query.search, query.filter, query.order_by, limit, offset)
+                    .await
+            }
+        }
+    }
+
     fn spawn_load_stickers(&mut self, cx: &mut Context<Self>) {
         if self.loading {
+            self.reload_pending = true;
             return;
         }
 
@@ -259,40 +643,161 @@ impl MainWindow {
         self.error = None;
         cx.notify();
 
-        let query = self.query.read(cx).value().to_string();
-        let order_by = self.order;
+        let query = self.list_query(cx);
+        // Refreshing the same list keeps what was scrolled to, a new search starts over.
+        let limit = if self.loaded_query.as_ref() == Some(&query) {
+            (self.stickers.len() as i64).max(STICKER_PAGE_SIZE)
+        } else {
+            STICKER_PAGE_SIZE
+        };
         let store = self.store.clone();
 
         cx.spawn(async move |entity, cx| {
-            let query = (!query.is_empty()).then_some(query);
-            let Ok(stickers) = store
-                .query_stickers(query, order_by, STICKER_LOAD_LIMIT, 0)
-                .await
-            else {
+            let stickers = Self::query_page(&store, query.clone(), limit, 0).await;
+            let Ok(stickers) = stickers else {
                 let _ = entity.update(cx, move |this, cx| {
                     this.error = Some("Failed to query stickers".to_string());
                     this.loading = false;
+                    this.reload_pending = false;
                     cx.notify();
                 });
                 return;
             };
+            let total = match query.tag {
+                Some(_) => None,
+                None => store
+                    .count_stickers(query.search.clone(), query.filter.clone())
+                    .await
+                    .inspect_err(|err| tracing::warn!(error = ?err, "Failed to count stickers"))
+                    .ok(),
+            };
+
+            let mut sticker_tags: HashMap<i64, Vec<String>> = HashMap::new();
+            match store.get_all_sticker_tags().await {
+                Ok(pairs) => {
+                    for (id, tag) in pairs {
+                        sticker_tags.entry(id).or_default().push(tag);
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(error = ?err, "Failed to load sticker tags");
+                }
+            }
+            let tags = store.get_tags().await.unwrap_or_else(|err| {
+                tracing::warn!(error = ?err, "Failed to load tags");
+                Vec::new()
+            });
+            let collections = store.get_collections().await.unwrap_or_else(|err| {
+                tracing::warn!(error = ?err, "Failed to load collections");
+                Vec::new()
+            });
 
             let _ = entity.update(cx, move |this, cx| {
+                if this.loaded_query.as_ref() != Some(&query) {
+                    this.list_scroll.set_offset(gpui::Point::default());
+                }
+                this.has_more = match total {
+                    Some(total) => (stickers.len() as i64) < total,
+                    None => stickers.len() as i64 >= limit,
+                };
+                this.stickers_total = total;
                 this.stickers = stickers;
+                this.loaded_query = Some(query);
+                this.sticker_tags = sticker_tags;
+                this.tags = tags;
+                this.collections = collections;
+                this.loading = false;
+                if std::mem::take(&mut this.reload_pending) {
+                    this.spawn_load_stickers(cx);
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Append the next page of the loaded query, tags were loaded with the first one.
+    fn spawn_load_more(&mut self, cx: &mut Context<Self>) {
+        let Some(query) = self.loaded_query.clone() else {
+            return;
+        };
+        if self.loading || !self.has_more {
+            return;
+        }
+
+        self.loading = true;
+        let offset = self.stickers.len() as i64;
+        let store = self.store.clone();
+
+        cx.spawn(async move |entity, cx| {
+            let page = Self::query_page(&store, query.clone(), STICKER_PAGE_SIZE, offset).await;
+            let _ = entity.update(cx, move |this, cx| {
                 this.loading = false;
+                match page {
+                    // Dropped when the list was reloaded for another query meanwhile.
+                    Ok(page) if this.loaded_query.as_ref() == Some(&query) => {
+                        this.has_more = page.len() as i64 >= STICKER_PAGE_SIZE;
+                        // Edits move stickers between pages, do not show one twice.
+                        let loaded = this.stickers.iter().map(|x| x.id).collect::<HashSet<_>>();
+                        this.stickers
+                            .extend(page.into_iter().filter(|x| !loaded.contains(&x.id)));
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        tracing::warn!(error = ?err, "Failed to load more stickers");
+                        this.has_more = false;
+                    }
+                }
+                if std::mem::take(&mut this.reload_pending) {
+                    this.spawn_load_stickers(cx);
+                }
                 cx.notify();
             });
         })
         .detach();
     }
 
-    fn delete_sticker(
+    /// Load the next page once the list is scrolled close to its end, or when the
+    /// loaded stickers do not even fill it.
+    fn load_more_when_near_end(&mut self, cx: &mut Context<Self>) {
+        if !self.has_more || self.loading {
+            return;
+        }
+        let scrolled = -self.list_scroll.offset().y;
+        let max = self.list_scroll.max_offset().height;
+        if max - scrolled <= px(LOAD_MORE_THRESHOLD) {
+            self.spawn_load_more(cx);
+        }
+    }
+
+    fn spawn_load_focus_totals(&mut self, cx: &mut Context<Self>) {
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let now = chrono::Local::now();
+            match store.get_focus_sessions_since(focus::week_start(now)).await {
+                Ok(sessions) => {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.focus_totals = FocusTotals::count(&sessions, now);
+                        cx.notify();
+                    });
+                }
+                Err(err) => tracing::warn!(error = ?err, "Failed to load focus sessions"),
+            }
+        })
+        .detach();
+    }
+
+    fn purge_sticker(
         &mut self,
         id: i64,
         title: String,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if self.store.is_read_only() {
+            return;
+        }
+
         let entity = cx.entity();
         let store = self.store.clone();
         window.open_dialog(cx, move |dialog, _, cx| {
@@ -301,7 +806,7 @@ impl MainWindow {
             dialog
                 .confirm()
                 .title(div().text_color(cx.theme().warning).child("Warning"))
-                .child(format!("Are you confirm to delete: \"{title}\"?"))
+                .child(format!("Are you confirm to delete forever: \"{title}\"?"))
                 .w(px(300.0))
                 .bg(black().opacity(0.9))
                 .text_sm()
@@ -309,13 +814,17 @@ impl MainWindow {
                 .on_ok(move |_, _, cx| {
                     let store = store.clone();
                     let entity = entity.clone();
-                    cx.spawn(async move |cx| match store.delete_sticker(id).await {
-                        Ok(()) => {
+                    cx.spawn(async move |cx| match store.purge_sticker(id).await {
+                        Ok(true) => {
                             let _ = entity.update(cx, |this, cx| {
                                 StickerWindow::try_close(id, cx);
                                 this.stickers.retain(|s| s.id != id);
                             });
                         }
+                        // Restored meanwhile, e.g. from another window.
+                        Ok(false) => {
+                            let _ = entity.update(cx, |this, cx| this.spawn_load_stickers(cx));
+                        }
                         Err(err) => {
                             let _ = entity.update(cx, |this, cx| {
                                 this.error = Some(format!("Failed to delete sticker: {err:#}"));
@@ -329,114 +838,1193 @@ impl MainWindow {
         });
     }
 
-    fn status_banner(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
-        if let Some(err) = &self.error {
-            return div()
-                .p(px(8.0))
-                .child(Alert::error("main-load-error", err.as_str()))
-                .into_any_element();
+    fn empty_trash(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
         }
-        if self.loading {
-            return div()
-                .p(px(8.0))
-                .child(Spinner::new().color(cx.theme().accent))
-                .into_any_element();
+
+        let entity = cx.entity();
+        let store = self.store.clone();
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let store = store.clone();
+            let entity = entity.clone();
+            dialog
+                .confirm()
+                .title(div().text_color(cx.theme().warning).child("Warning"))
+                .child("Delete every sticker in the trash forever?")
+                .w(px(300.0))
+                .bg(black().opacity(0.9))
+                .text_sm()
+                .on_cancel(|_, _, _| true)
+                .on_ok(move |_, _, cx| {
+                    let store = store.clone();
+                    let entity = entity.clone();
+                    // Everything trashed until now, newer ones can not exist.
+                    cx.spawn(async move |cx| match store.purge_trash(i64::MAX).await {
+                        Ok(purged) => {
+                            tracing::info!(count = purged.len(), "Emptied the trash");
+                            let _ = entity.update(cx, |this, cx| this.spawn_load_stickers(cx));
+                        }
+                        Err(err) => {
+                            let _ = entity.update(cx, |this, cx| {
+                                this.error = Some(format!("Failed to empty the trash: {err:#}"));
+                                cx.notify();
+                            });
+                        }
+                    })
+                    .detach();
+                    true
+                })
+        });
+    }
+
+    fn move_sticker(&mut self, id: i64, bucket: StickerBucket, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
         }
-        gpui::Empty.into_any_element()
+
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let moved = match bucket {
+                StickerBucket::Active => store.restore_sticker(id).await,
+                _ => store.move_sticker(id, bucket).await,
+            };
+            match moved {
+                Ok(()) => {
+                    let _ = entity.update(cx, |this, cx| {
+                        if bucket != StickerBucket::Active {
+                            StickerWindow::try_close(id, cx);
+                        }
+                        this.stickers.retain(|s| s.id != id);
+                        cx.notify();
+                    });
+                }
+                Err(err) => {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.error = Some(format!("Failed to move sticker: {err:#}"));
+                        cx.notify();
+                    });
+                }
+            }
+        })
+        .detach();
     }
 
-    fn sort_button(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
-        let root_entity = cx.entity();
-        Button::new("sort")
-            .icon(match self.order {
-                StickerOrderBy::CreatedAsc | StickerOrderBy::UpdatedAsc => IconName::SortAscending,
-                _ => IconName::SortDescending,
-            })
-            .bg(rgba(0x00000000))
-            .border_0()
-            .opacity(0.8)
-            .dropdown_menu(move |menu, window, cx| {
-                let order_by = root_entity.read(cx).order;
-                menu.item(
-                    PopupMenuItem::new(order_label(StickerOrderBy::CreatedDesc))
-                        .checked(order_by == StickerOrderBy::CreatedDesc)
-                        .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
-                            this.order = StickerOrderBy::CreatedDesc;
-                            this.spawn_load_stickers(cx);
-                        })),
-                )
-                .item(
-                    PopupMenuItem::new(order_label(StickerOrderBy::CreatedAsc))
-                        .checked(order_by == StickerOrderBy::CreatedAsc)
-                        .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
-                            this.order = StickerOrderBy::CreatedAsc;
-                            this.spawn_load_stickers(cx);
-                        })),
-                )
-                .item(
-                    PopupMenuItem::new(order_label(StickerOrderBy::UpdatedDesc))
-                        .checked(order_by == StickerOrderBy::UpdatedDesc)
-                        .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
-                            this.order = StickerOrderBy::UpdatedDesc;
-                            this.spawn_load_stickers(cx);
-                        })),
-                )
-                .item(
-                    PopupMenuItem::new(order_label(StickerOrderBy::UpdatedAsc))
-                        .checked(order_by == StickerOrderBy::UpdatedAsc)
-                        .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
-                            this.order = StickerOrderBy::UpdatedAsc;
-                            this.spawn_load_stickers(cx);
-                        })),
-                )
-            })
-            .into_any_element()
+    fn set_sticker_pinned(&mut self, id: i64, pinned: bool, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let saved = store.set_sticker_pinned(id, pinned).await;
+            let _ = entity.update(cx, |this, cx| match saved {
+                Ok(()) => this.spawn_load_stickers(cx),
+                Err(err) => {
+                    this.error = Some(format!("Failed to pin sticker: {err:#}"));
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
     }
 
-    fn create_button(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
-        let root_entity = cx.entity();
-        Button::new("create")
-            .border_0()
-            .bg(rgba(0x00000000))
-            .icon(IconName::Plus)
-            .dropdown_menu(move |menu, window, _| {
-                let root_entity = root_entity.clone();
-                menu.item(
-                    PopupMenuItem::new("text")
-                        .icon(sticker_type_icon(&StickerType::Markdown))
-                        .on_click(window.listener_for(&root_entity, |this, _, _, cx| {
-                            this.create_sticker(cx, &StickerType::Markdown);
-                        })),
+    fn create_collection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.collection_name.read(cx).value().trim().to_string();
+        if name.is_empty() || self.store.is_read_only() {
+            return;
+        }
+        self.collection_name
+            .update(cx, |input, cx| input.set_value("", window, cx));
+
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let created = store.create_collection(name).await;
+            let _ = entity.update(cx, |this, cx| match created {
+                Ok(_) => this.spawn_load_stickers(cx),
+                Err(err) => {
+                    this.error = Some(format!("Failed to create collection: {err:#}"));
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// The stickers of the collection are kept, without a collection.
+    fn delete_collection(&mut self, id: i64, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let deleted = store.delete_collection(id).await;
+            let _ = entity.update(cx, |this, cx| match deleted {
+                Ok(()) => {
+                    if this.collection_filter == Some(id) {
+                        this.collection_filter = None;
+                    }
+                    this.spawn_load_stickers(cx);
+                }
+                Err(err) => {
+                    this.error = Some(format!("Failed to delete collection: {err:#}"));
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Move a sticker into the collection, `None` takes it out of its collection.
+    fn set_sticker_collection(&mut self, id: i64, collection: Option<i64>, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let moved = store.set_sticker_collection(id, collection).await;
+            let _ = entity.update(cx, |this, cx| match moved {
+                Ok(()) => this.spawn_load_stickers(cx),
+                Err(err) => {
+                    this.error = Some(format!("Failed to move sticker: {err:#}"));
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn open_recolor_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        // Start from what the list currently shows.
+        let query = self.query.read(cx).value().to_string();
+        self.recolor_query
+            .update(cx, |input, cx| input.set_value(query, window, cx));
+
+        let entity = cx.entity();
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let this = entity.read(cx);
+            let recolor_type = this.recolor_type;
+            let recolor_color = this.recolor_color;
+            let recolor_query = this.recolor_query.clone();
+
+            let type_button = |sticker_type: Option<StickerType>| {
+                let entity = entity.clone();
+                Button::new(SharedString::from(format!(
+                    "recolor-type-{}",
+                    sticker_type.map(|x| x.label()).unwrap_or("any")
+                )))
+                .label(sticker_type.map(|x| x.label()).unwrap_or("Any"))
+                .xsmall()
+                .selected(recolor_type == sticker_type)
+                .on_click(move |_, _, cx| {
+                    entity.update(cx, |this, cx| {
+                        this.recolor_type = sticker_type;
+                        cx.notify();
+                    });
+                })
+            };
+
+            let color_button = |color: StickerColor| {
+                let entity = entity.clone();
+                div()
+                    .id(SharedString::from(format!(
+                        "recolor-color-{}",
+                        color.as_str()
+                    )))
+                    .size(px(18.0))
+                    .rounded_full()
+                    .cursor_pointer()
+                    .bg(color.swatch())
+                    .border_2()
+                    .border_color(if recolor_color == color {
+                        cx.theme().foreground
+                    } else {
+                        transparent_black()
+                    })
+                    .on_click(move |_, _, cx| {
+                        entity.update(cx, |this, cx| {
+                            this.recolor_color = color;
+                            cx.notify();
+                        });
+                    })
+            };
+
+            let entity = entity.clone();
+            dialog
+                .confirm()
+                .title("Recolor stickers")
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child("Stickers matching")
+                        .child(Input::new(&recolor_query).small())
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .flex_wrap()
+                                .child(type_button(None))
+                                .children(StickerType::ALL.map(|x| type_button(Some(x)))),
+                        )
+                        .child(format!(
+                            "Change to {}",
+                            SettingsStore::get(cx).color_display_name(recolor_color)
+                        ))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .children(StickerColor::ALL.map(color_button)),
+                        ),
+                )
+                .w(px(300.0))
+                .bg(black().opacity(0.9))
+                .text_sm()
+                .on_cancel(|_, _, _| true)
+                .on_ok(move |_, _, cx| {
+                    entity.update(cx, |this, cx| this.apply_recolor(cx));
+                    true
+                })
+        });
+    }
+
+    fn open_restore_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let backups_dir = cx.global::<AppPaths>().backups_dir();
+        match backup::list_backups(&backups_dir) {
+            Ok(backups) => self.backups = backups,
+            Err(err) => {
+                self.error = Some(format!("Failed to list backups: {err:#}"));
+                cx.notify();
+                return;
+            }
+        }
+        self.restore_choice = None;
+
+        let entity = cx.entity();
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let this = entity.read(cx);
+            let restore_choice = this.restore_choice;
+            let rows = this
+                .backups
+                .iter()
+                .enumerate()
+                .map(|(index, backup)| {
+                    let entity = entity.clone();
+                    Button::new(("backup", index as u64))
+                        .label(format!(
+                            "{} · {} KB · {}",
+                            backup.modified.format("%Y-%m-%d %H:%M"),
+                            backup.size / 1024,
+                            backup.file_name()
+                        ))
+                        .xsmall()
+                        .w_full()
+                        .selected(restore_choice == Some(index))
+                        .on_click(move |_, _, cx| {
+                            entity.update(cx, |this, cx| {
+                                this.restore_choice = Some(index);
+                                cx.notify();
+                            });
+                        })
+                })
+                .collect::<Vec<_>>();
+            let empty = rows.is_empty();
+
+            let entity = entity.clone();
+            dialog
+                .confirm()
+                .title("Restore from backup")
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .when(empty, |v| v.child("There are no backups yet."))
+                        .child(
+                            v_flex()
+                                .gap_1()
+                                .max_h(px(240.0))
+                                .overflow_y_scrollbar()
+                                .children(rows),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .opacity(0.75)
+                                .child("Rustickers closes and uses the backup from the next start on. The current database is backed up first."),
+                        ),
+                )
+                .w(px(360.0))
+                .bg(black().opacity(0.9))
+                .text_sm()
+                .on_cancel(|_, _, _| true)
+                .on_ok(move |_, _, cx| {
+                    entity.update(cx, |this, cx| this.restore_backup(cx));
+                    true
+                })
+        });
+    }
+
+    fn restore_backup(&mut self, cx: &mut Context<Self>) {
+        let Some(backup) = self
+            .restore_choice
+            .and_then(|index| self.backups.get(index))
+            .map(|x| x.path.clone())
+        else {
+            return;
+        };
+
+        let store = self.store.clone();
+        let app_paths = cx.global::<AppPaths>().clone();
+        cx.spawn(async move |entity, cx| {
+            match backup::schedule_restore(&store, &app_paths, &backup).await {
+                Ok(()) => {
+                    let _ = cx.update(|cx| cx.quit());
+                }
+                Err(err) => {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.error = Some(format!("Failed to restore backup: {err:#}"));
+                        cx.notify();
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Ask for the passphrase of an encrypted export, `import` asks once and for the
+    /// file after that.
+    fn open_encrypted_backup_dialog(
+        &mut self,
+        import: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if import && self.store.is_read_only() {
+            return;
+        }
+
+        for input in [&self.passphrase, &self.passphrase_confirm] {
+            input.update(cx, |input, cx| input.set_value("", window, cx));
+        }
+
+        let entity = cx.entity();
+        let passphrase = self.passphrase.clone();
+        let passphrase_confirm = self.passphrase_confirm.clone();
+        window.open_dialog(cx, move |dialog, _, _| {
+            let entity = entity.clone();
+            dialog
+                .confirm()
+                .title(match import {
+                    true => "Import encrypted backup",
+                    false => "Export encrypted backup",
+                })
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(Input::new(&passphrase).small())
+                        .when(!import, |v| v.child(Input::new(&passphrase_confirm).small()))
+                        .child(div().text_xs().opacity(0.75).child(match import {
+                            true => "Choose the backup file next. Rustickers closes and uses it from the next start on. The current database is backed up first.",
+                            false => "Stickers, settings, images and templates in one file. Without the passphrase it can not be opened, not even by Rustickers.",
+                        })),
+                )
+                .w(px(300.0))
+                .bg(black().opacity(0.9))
+                .text_sm()
+                .on_cancel(|_, _, _| true)
+                .on_ok(move |_, _, cx| {
+                    entity.update(cx, |this, cx| match import {
+                        true => this.import_encrypted_backup(cx),
+                        false => this.export_encrypted_backup(cx),
+                    });
+                    true
+                })
+        });
+    }
+
+    fn export_encrypted_backup(&mut self, cx: &mut Context<Self>) {
+        let passphrase = self.passphrase.read(cx).value().to_string();
+        if passphrase.chars().count() < encrypted_backup::MIN_PASSPHRASE_CHARS {
+            self.error = Some(format!(
+                "The passphrase needs at least {} characters",
+                encrypted_backup::MIN_PASSPHRASE_CHARS
+            ));
+            cx.notify();
+            return;
+        }
+        if self.passphrase_confirm.read(cx).value().to_string() != passphrase {
+            self.error = Some("The passphrases do not match".to_string());
+            cx.notify();
+            return;
+        }
+
+        let store = self.store.clone();
+        let app_paths = cx.global::<AppPaths>().clone();
+        let file_name = format!(
+            "rustickers-{}.{}",
+            chrono::Local::now().format("%Y%m%d"),
+            encrypted_backup::FILE_EXTENSION
+        );
+        cx.spawn(async move |entity, cx| {
+            let filters = [("Rustickers backup", &[encrypted_backup::FILE_EXTENSION][..])];
+            let Some(dest) =
+                dialogs::save_file("Export encrypted backup", &file_name, &filters).await
+            else {
+                return;
+            };
+
+            let exported = cx
+                .background_executor()
+                .spawn(async move {
+                    encrypted_backup::export(&store, &app_paths, &passphrase, &dest)
+                        .await
+                        .map(|()| dest)
+                })
+                .await;
+            let _ = entity.update(cx, |this, cx| {
+                match exported {
+                    Ok(dest) => {
+                        this.warning =
+                            Some(format!("Encrypted backup saved to {}", dest.display()));
+                    }
+                    Err(err) => {
+                        this.error = Some(format!("Failed to export encrypted backup: {err:#}"));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn import_encrypted_backup(&mut self, cx: &mut Context<Self>) {
+        let passphrase = self.passphrase.read(cx).value().to_string();
+        if passphrase.is_empty() {
+            return;
+        }
+
+        let store = self.store.clone();
+        let app_paths = cx.global::<AppPaths>().clone();
+        cx.spawn(async move |entity, cx| {
+            let filters = [("Rustickers backup", &[encrypted_backup::FILE_EXTENSION][..])];
+            let Some(archive) = dialogs::pick_file("Import encrypted backup", &filters).await
+            else {
+                return;
+            };
+
+            let imported = cx
+                .background_executor()
+                .spawn(async move {
+                    encrypted_backup::schedule_import(&store, &app_paths, &archive, &passphrase)
+                        .await
+                })
+                .await;
+            match imported {
+                Ok(()) => {
+                    let _ = cx.update(|cx| cx.quit());
+                }
+                Err(err) => {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.error = Some(format!("Failed to import encrypted backup: {err:#}"));
+                        cx.notify();
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn import_notes(&mut self, source: NoteSource, cx: &mut Context<Self>) {
+        let store = self.store.clone();
+        let sticker_events_tx = self.sticker_events_sender.clone();
+        cx.spawn(async move |entity, cx| {
+            let notes = match source {
+                NoteSource::StickyNotes => {
+                    let filters = [("Sticky Notes database", &["sqlite"][..])];
+                    let Some(db_path) =
+                        dialogs::pick_file("Import from Sticky Notes (plum.sqlite)", &filters)
+                            .await
+                    else {
+                        return;
+                    };
+                    importers::read_sticky_notes(&db_path).await
+                }
+                NoteSource::GoogleKeep => {
+                    let Some(folder) =
+                        dialogs::pick_folder("Import from Google Keep (Keep folder of Takeout)")
+                            .await
+                    else {
+                        return;
+                    };
+                    cx.background_executor()
+                        .spawn(async move { importers::read_keep_folder(&folder) })
+                        .await
+                }
+            };
+
+            let imported = match notes {
+                Ok(notes) => importers::create_stickers(&store, &sticker_events_tx, notes).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = imported {
+                let _ = entity.update(cx, |this, cx| {
+                    this.error = Some(format!("Failed to import notes: {err:#}"));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn apply_recolor(&mut self, cx: &mut Context<Self>) {
+        let query = self.recolor_query.read(cx).value().trim().to_string();
+        let query = (!query.is_empty()).then_some(query);
+        let filter = StickerFilter {
+            sticker_type: self.recolor_type,
+            ..StickerFilter::default()
+        };
+        let color = self.recolor_color;
+        let store = self.store.clone();
+
+        cx.spawn(async move |entity, cx| {
+            match store.update_stickers_color(query, filter, color).await {
+                Ok(ids) => {
+                    tracing::info!(
+                        count = ids.len(),
+                        color = color.as_str(),
+                        "Stickers recolored"
+                    );
+                    let _ = entity.update(cx, |this, cx| {
+                        for id in ids {
+                            StickerWindow::try_set_color(id, color, cx);
+                        }
+                        this.spawn_load_stickers(cx);
+                    });
+                }
+                Err(err) => {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.error = Some(format!("Failed to recolor stickers: {err:#}"));
+                        cx.notify();
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Focus sessions against the goal, once there was one this week.
+    fn focus_stats(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        if self.focus_totals.week == 0 {
+            return gpui::Empty.into_any_element();
+        }
+
+        let goal = SettingsStore::get(cx).focus_goal.clone();
+        h_flex()
+            .gap_1()
+            .px(px(8.0))
+            .items_center()
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .child(Icon::new(IconName::Timer).with_size(px(12.0)))
+            .child(format!(
+                "Focus today {} · this week {}",
+                focus::progress(self.focus_totals.today, goal.daily_sessions),
+                focus::progress(self.focus_totals.week, goal.weekly_sessions)
+            ))
+            .into_any_element()
+    }
+
+    fn status_banner(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        if let Some(err) = &self.error {
+            return div()
+                .p(px(8.0))
+                .child(Alert::error("main-load-error", err.as_str()))
+                .into_any_element();
+        }
+        if self.loading {
+            return div()
+                .p(px(8.0))
+                .child(Spinner::new().color(cx.theme().accent))
+                .into_any_element();
+        }
+        if let Some(warning) = &self.warning {
+            return h_flex()
+                .p(px(8.0))
+                .gap_1()
+                .items_start()
+                .child(Alert::warning("main-warning", warning.as_str()).small())
+                .child(
+                    Button::new("dismiss-warning")
+                        .icon(IconName::Close)
+                        .border_0()
+                        .bg(rgba(0x00000000))
+                        .opacity(0.8)
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.warning = None;
+                            cx.notify();
+                        })),
+                )
+                .into_any_element();
+        }
+        gpui::Empty.into_any_element()
+    }
+
+    fn sort_button(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        let root_entity = cx.entity();
+        Button::new("sort")
+            .icon(match self.order {
+                StickerOrderBy::CreatedAsc | StickerOrderBy::UpdatedAsc => IconName::SortAscending,
+                _ => IconName::SortDescending,
+            })
+            .bg(rgba(0x00000000))
+            .border_0()
+            .opacity(0.8)
+            .when_some(self.color_filter, |button, color| {
+                button.text_color(color.swatch())
+            })
+            .dropdown_menu(move |menu, window, cx| {
+                let order_by = root_entity.read(cx).order;
+                let color_filter = root_entity.read(cx).color_filter;
+                let group_by_tag = root_entity.read(cx).group_by_tag;
+                let bucket = root_entity.read(cx).bucket;
+                let read_only = root_entity.read(cx).store.is_read_only();
+                let menu = menu
+                    .item(
+                        PopupMenuItem::new(order_label(StickerOrderBy::PinnedFirst))
+                            .checked(order_by == StickerOrderBy::PinnedFirst)
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.order = StickerOrderBy::PinnedFirst;
+                                this.spawn_load_stickers(cx);
+                            })),
+                    )
+                    .item(
+                        PopupMenuItem::new(order_label(StickerOrderBy::CreatedDesc))
+                            .checked(order_by == StickerOrderBy::CreatedDesc)
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.order = StickerOrderBy::CreatedDesc;
+                                this.spawn_load_stickers(cx);
+                            })),
+                    )
+                    .item(
+                        PopupMenuItem::new(order_label(StickerOrderBy::CreatedAsc))
+                            .checked(order_by == StickerOrderBy::CreatedAsc)
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.order = StickerOrderBy::CreatedAsc;
+                                this.spawn_load_stickers(cx);
+                            })),
+                    )
+                    .item(
+                        PopupMenuItem::new(order_label(StickerOrderBy::UpdatedDesc))
+                            .checked(order_by == StickerOrderBy::UpdatedDesc)
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.order = StickerOrderBy::UpdatedDesc;
+                                this.spawn_load_stickers(cx);
+                            })),
+                    )
+                    .item(
+                        PopupMenuItem::new(order_label(StickerOrderBy::UpdatedAsc))
+                            .checked(order_by == StickerOrderBy::UpdatedAsc)
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.order = StickerOrderBy::UpdatedAsc;
+                                this.spawn_load_stickers(cx);
+                            })),
+                    )
+                    .separator()
+                    .item(
+                        PopupMenuItem::new("All colors")
+                            .checked(color_filter.is_none())
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.color_filter = None;
+                                this.spawn_load_stickers(cx);
+                            })),
+                    );
+
+                let menu = StickerColor::ALL
+                    .into_iter()
+                    .fold(menu, |menu, color| {
+                        menu.item(
+                            PopupMenuItem::new(SettingsStore::get(cx).color_display_name(color))
+                                .checked(color_filter == Some(color))
+                                .on_click(window.listener_for(
+                                    &root_entity,
+                                    move |this, _, _, cx| {
+                                        this.color_filter = Some(color);
+                                        this.spawn_load_stickers(cx);
+                                    },
+                                )),
+                        )
+                    })
+                    .separator()
+                    .item(
+                        PopupMenuItem::new("Group by tag")
+                            .checked(group_by_tag)
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.group_by_tag = !this.group_by_tag;
+                                cx.notify();
+                            })),
+                    )
+                    .separator();
+
+                StickerBucket::ALL
+                    .into_iter()
+                    .fold(menu, |menu, item| {
+                        menu.item(
+                            PopupMenuItem::new(item.label())
+                                .checked(bucket == item)
+                                .on_click(window.listener_for(
+                                    &root_entity,
+                                    move |this, _, _, cx| {
+                                        this.bucket = item;
+                                        this.spawn_load_stickers(cx);
+                                    },
+                                )),
+                        )
+                    })
+                    .when(bucket == StickerBucket::Trash, |menu| {
+                        menu.item(
+                            PopupMenuItem::new("Empty trash…")
+                                .disabled(read_only)
+                                .on_click(
+                                    window
+                                        .listener_for(&root_entity, move |this, _, window, cx| {
+                                            this.empty_trash(window, cx)
+                                        }),
+                                ),
+                        )
+                    })
+                    .separator()
+                    .item(PopupMenuItem::new("Recolor…").disabled(read_only).on_click(
+                        window.listener_for(&root_entity, move |this, _, window, cx| {
+                            this.open_recolor_dialog(window, cx);
+                        }),
+                    ))
+                    .item(
+                        PopupMenuItem::new("Restore from backup…")
+                            .disabled(read_only)
+                            .on_click(
+                                window.listener_for(&root_entity, move |this, _, window, cx| {
+                                    this.open_restore_dialog(window, cx)
+                                }),
+                            ),
+                    )
+                    .item(PopupMenuItem::new("Export encrypted backup…").on_click(
+                        window.listener_for(&root_entity, move |this, _, window, cx| {
+                            this.open_encrypted_backup_dialog(false, window, cx)
+                        }),
+                    ))
+                    .item(
+                        PopupMenuItem::new("Import encrypted backup…")
+                            .disabled(read_only)
+                            .on_click(window.listener_for(
+                                &root_entity,
+                                move |this, _, window, cx| {
+                                    this.open_encrypted_backup_dialog(true, window, cx)
+                                },
+                            )),
+                    )
+                    .separator()
+                    .item(
+                        PopupMenuItem::new("Import from Sticky Notes…")
+                            .disabled(read_only)
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.import_notes(NoteSource::StickyNotes, cx)
+                            })),
+                    )
+                    .item(
+                        PopupMenuItem::new("Import from Google Keep…")
+                            .disabled(read_only)
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.import_notes(NoteSource::GoogleKeep, cx)
+                            })),
+                    )
+            })
+            .into_any_element()
+    }
+
+    fn tag_button(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        let root_entity = cx.entity();
+        Button::new("tag-filter")
+            .icon(IconName::Hash)
+            .bg(rgba(0x00000000))
+            .border_0()
+            .opacity(if self.tag_filter.is_some() { 1.0 } else { 0.8 })
+            .when(self.tag_filter.is_some(), |button| {
+                button.text_color(cx.theme().primary)
+            })
+            .tooltip(match &self.tag_filter {
+                Some(tag) => format!("Only #{tag}"),
+                None => "Filter by tag".to_string(),
+            })
+            .dropdown_menu(move |menu, window, cx| {
+                let tag_filter = root_entity.read(cx).tag_filter.clone();
+                let tags = root_entity.read(cx).tags.clone();
+                let menu = menu.item(
+                    PopupMenuItem::new("All tags")
+                        .checked(tag_filter.is_none())
+                        .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                            this.tag_filter = None;
+                            this.spawn_load_stickers(cx);
+                        })),
+                );
+                if tags.is_empty() {
+                    return menu.item(PopupMenuItem::new("No tags yet").disabled(true));
+                }
+
+                tags.into_iter().fold(menu.separator(), |menu, tag| {
+                    let checked = tag_filter.as_deref() == Some(tag.as_str());
+                    menu.item(
+                        PopupMenuItem::new(format!("#{tag}"))
+                            .checked(checked)
+                            .on_click(window.listener_for(&root_entity, move |this, _, _, cx| {
+                                this.tag_filter = Some(tag.clone());
+                                this.spawn_load_stickers(cx);
+                            })),
+                    )
+                })
+            })
+            .into_any_element()
+    }
+
+    fn collections_button(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        Button::new("collections")
+            .icon(IconName::Folder)
+            .bg(rgba(0x00000000))
+            .border_0()
+            .opacity(if self.show_collections { 1.0 } else { 0.8 })
+            .when(self.collection_filter.is_some(), |button| {
+                button.text_color(cx.theme().primary)
+            })
+            .tooltip("Collections")
+            .on_click(cx.listener(|this, _, _, cx| {
+                this.show_collections = !this.show_collections;
+                cx.notify();
+            }))
+            .into_any_element()
+    }
+
+    /// Filter by collection, dropping a sticker card on a collection moves it there
+    /// and on "All" takes it out of its collection.
+    fn collections_sidebar(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        let read_only = self.store.is_read_only();
+        let rows = std::iter::once((None, "All".to_string(), None)).chain(
+            self.collections
+                .iter()
+                .map(|x| (Some(x.id), x.name.clone(), Some(x.sticker_count))),
+        );
+
+        v_flex()
+            .id("collections-sidebar")
+            .w(px(110.0))
+            .flex_shrink_0()
+            .h_full()
+            .gap_1()
+            .pl_2()
+            .overflow_y_scroll()
+            .children(rows.map(|(collection, name, count)| {
+                let selected = self.collection_filter == collection;
+                h_flex()
+                    .id(("collection", collection.unwrap_or(0) as u64))
+                    .gap_1()
+                    .px_1()
+                    .rounded_sm()
+                    .text_xs()
+                    .cursor_pointer()
+                    .opacity(if selected { 1.0 } else { 0.75 })
+                    .when(selected, |row| row.bg(rgb(0x333333)))
+                    .hover(|s| s.bg(rgb(0x333333)))
+                    .drag_over::<DraggedSticker>(|style, _, _, cx| style.bg(cx.theme().accent))
+                    .on_drop(cx.listener(move |this, sticker: &DraggedSticker, _, cx| {
+                        this.set_sticker_collection(sticker.id, collection, cx);
+                    }))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.collection_filter = collection;
+                        this.spawn_load_stickers(cx);
+                    }))
+                    .child(
+                        div()
+                            .flex_1()
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .child(name),
+                    )
+                    .when_some(count, |row, count| row.child(count.to_string()))
+                    .when_some(collection.filter(|_| !read_only), |row, id| {
+                        row.child(
+                            Button::new(("delete-collection", id as u64))
+                                .icon(IconName::Close)
+                                .xsmall()
+                                .border_0()
+                                .bg(rgba(0x00000000))
+                                .tooltip("Delete collection, its stickers are kept")
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.delete_collection(id, cx);
+                                })),
+                        )
+                    })
+            }))
+            .when(!read_only, |sidebar| {
+                sidebar.child(Input::new(&self.collection_name).xsmall())
+            })
+            .into_any_element()
+    }
+
+    fn create_button(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        let root_entity = cx.entity();
+        let temporary = self.create_temporary;
+        Button::new("create")
+            .border_0()
+            .bg(rgba(0x00000000))
+            .icon(IconName::Plus)
+            .disabled(self.store.is_read_only())
+            .dropdown_menu(move |menu, window, cx| {
+                let root_entity = root_entity.clone();
+                let clipboard_entity = root_entity.clone();
+                menu.item(PopupMenuItem::new("temporary").checked(temporary).on_click(
+                    window.listener_for(&root_entity, |this, _, _, cx| {
+                        this.create_temporary = !this.create_temporary;
+                        cx.notify();
+                    }),
+                ))
+                .separator()
+                .item(
+                    PopupMenuItem::new("text")
+                        .icon(sticker_type_icon(&StickerType::Markdown))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Markdown);
+                        })),
                 )
                 .item(
                     PopupMenuItem::new("timer")
                         .icon(sticker_type_icon(&StickerType::Timer))
-                        .on_click(window.listener_for(&root_entity, |this, _, _, cx| {
-                            this.create_sticker(cx, &StickerType::Timer);
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Timer);
                         })),
                 )
                 .item(
                     PopupMenuItem::new("command")
                         .icon(sticker_type_icon(&StickerType::Command))
-                        .on_click(window.listener_for(&root_entity, |this, _, _, cx| {
-                            this.create_sticker(cx, &StickerType::Command);
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Command);
                         })),
                 )
                 .item(
                     PopupMenuItem::new("paint")
                         .icon(sticker_type_icon(&StickerType::Paint))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Paint);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("image")
+                        .icon(sticker_type_icon(&StickerType::Image))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Image);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("web")
+                        .icon(sticker_type_icon(&StickerType::Web))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Web);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("clock")
+                        .icon(sticker_type_icon(&StickerType::Clock))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Clock);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("pomodoro")
+                        .icon(sticker_type_icon(&StickerType::Pomodoro))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Pomodoro);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("system")
+                        .icon(sticker_type_icon(&StickerType::System))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::System);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("agenda")
+                        .icon(sticker_type_icon(&StickerType::Agenda))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Agenda);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("habit")
+                        .icon(sticker_type_icon(&StickerType::Habit))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Habit);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("kanban")
+                        .icon(sticker_type_icon(&StickerType::Kanban))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Kanban);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("code")
+                        .icon(sticker_type_icon(&StickerType::Code))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Code);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("counter")
+                        .icon(sticker_type_icon(&StickerType::Counter))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Counter);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("chart")
+                        .icon(sticker_type_icon(&StickerType::Chart))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Chart);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("currency")
+                        .icon(sticker_type_icon(&StickerType::Currency))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Currency);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("animation")
+                        .icon(sticker_type_icon(&StickerType::Animation))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Animation);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("github")
+                        .icon(sticker_type_icon(&StickerType::GitHub))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::GitHub);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("reminder")
+                        .icon(sticker_type_icon(&StickerType::Reminder))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Reminder);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("http")
+                        .icon(sticker_type_icon(&StickerType::Http))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Http);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("palette")
+                        .icon(sticker_type_icon(&StickerType::Palette))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Palette);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("units")
+                        .icon(sticker_type_icon(&StickerType::Units))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Units);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("uptime")
+                        .icon(sticker_type_icon(&StickerType::Uptime))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Uptime);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("git")
+                        .icon(sticker_type_icon(&StickerType::Git))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Git);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("launcher")
+                        .icon(sticker_type_icon(&StickerType::Launcher))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Launcher);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("media")
+                        .icon(sticker_type_icon(&StickerType::Media))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Media);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("calculator")
+                        .icon(sticker_type_icon(&StickerType::Calculator))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::Calculator);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("mindmap")
+                        .icon(sticker_type_icon(&StickerType::MindMap))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::MindMap);
+                        })),
+                )
+                .item(
+                    PopupMenuItem::new("time tracker")
+                        .icon(sticker_type_icon(&StickerType::TimeTracker))
+                        .on_click(window.listener_for(&root_entity, |this, _, window, cx| {
+                            this.create_sticker(window, cx, &StickerType::TimeTracker);
+                        })),
+                )
+                .separator()
+                .submenu("from clipboard", window, cx, move |menu, window, cx| {
+                    clipboard_menu(menu, window, cx, &clipboard_entity)
+                })
+                .item(
+                    PopupMenuItem::new("weekly review")
+                        .icon(IconName::Calendar)
                         .on_click(window.listener_for(&root_entity, |this, _, _, cx| {
-                            this.create_sticker(cx, &StickerType::Paint);
+                            this.create_weekly_review(cx);
                         })),
                 )
             })
             .into_any_element()
     }
 
-    fn sticker_card(sticker: &StickerBrief, cx: &mut Context<Self>) -> gpui::AnyElement {
+    fn sticker_card(
+        sticker: &StickerBrief,
+        bucket: StickerBucket,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
         let id = sticker.id;
+        let pinned = sticker.pinned;
         let title = sticker.title.clone();
+        let summary = sticker.summary.clone();
         let updated = crate::utils::time::format_unix_millis(sticker.updated_at);
+        let color_label = SettingsStore::get(cx)
+            .color_label(sticker.color)
+            .map(|label| label.to_string());
 
         let main = div()
             .flex_shrink_0()
@@ -452,6 +2040,9 @@ impl MainWindow {
                     .child(div().text_color(sticker.color.swatch()).child(
                         Icon::new(sticker_type_icon(&sticker.sticker_type)).with_size(px(14.)),
                     ))
+                    .when(pinned, |row| {
+                        row.child(Icon::new(IconName::Pin).with_size(px(12.)).opacity(0.75))
+                    })
                     .child(
                         div()
                             .text_sm()
@@ -466,15 +2057,39 @@ impl MainWindow {
                             }),
                     ),
             )
+            .when(!summary.is_empty(), |card| {
+                card.child(
+                    div()
+                        .text_xs()
+                        .opacity(0.75)
+                        .overflow_hidden()
+                        .line_clamp(2)
+                        .text_ellipsis()
+                        .child(summary),
+                )
+            })
             .child(
-                div()
+                h_flex()
+                    .gap_2()
+                    .justify_end()
                     .text_xs()
                     .opacity(0.75)
-                    .text_right()
+                    .when_some(color_label, |row, label| {
+                        row.child(div().text_color(sticker.color.swatch()).child(label))
+                    })
                     .child(format!("Updated: {updated}")),
             );
 
+        let dragged = DraggedSticker {
+            id,
+            title: SharedString::from(title.clone()),
+        };
+
         div()
+            .id(("sticker-card", id as u64))
+            .on_drag(dragged, |sticker, _, _, cx: &mut App| {
+                cx.new(|_| sticker.clone())
+            })
             .bg(sticker.color.bg())
             .opacity(if sticker.state == StickerState::Close {
                 0.6
@@ -485,45 +2100,236 @@ impl MainWindow {
             .border_1()
             .border_color(rgb(0x3a3a3a))
             .rounded_md()
+            .when(sticker.state == StickerState::Open, |card| {
+                card.on_hover(move |hovered, _, cx| {
+                    StickerWindow::try_peek(id, *hovered, cx);
+                })
+            })
             .on_mouse_up(
                 MouseButton::Left,
-                cx.listener(move |this, event: &MouseUpEvent, _, cx| {
-                    if event.click_count >= 2 {
-                        if let Some(sticker) = this.stickers.iter_mut().find(|s| s.id == id) {
-                            sticker.state = StickerState::Open;
-                        }
-                        let store = this.store.clone();
-                        let sticker_events_tx = this.sticker_events_sender.clone();
-                        cx.spawn(async move |_, cx| {
-                            let _ =
-                                StickerWindow::open_async(cx, sticker_events_tx, store, id).await;
-                        })
-                        .detach();
+                cx.listener(move |this, event: &MouseUpEvent, window, cx| {
+                    // Archived and trashed stickers have to be restored before they open,
+                    // and dropping the card somewhere is no click.
+                    if bucket != StickerBucket::Active || cx.has_active_drag() {
+                        return;
+                    }
+                    let settings = SettingsStore::get(cx);
+                    let clicks = if settings.single_click_open { 1 } else { 2 };
+                    if event.click_count < clicks {
+                        return;
                     }
+
+                    if let Some(sticker) = this.stickers.iter_mut().find(|s| s.id == id) {
+                        sticker.state = StickerState::Open;
+                    }
+                    if settings.minimize_on_open {
+                        window.minimize_window();
+                    }
+                    let store = this.store.clone();
+                    let sticker_events_tx = this.sticker_events_sender.clone();
+                    cx.spawn(async move |_, cx| {
+                        let _ = StickerWindow::open_async(cx, sticker_events_tx, store, id).await;
+                    })
+                    .detach();
                 }),
             )
             .child(main)
             .child(
-                Button::new(("delete", id as u64))
+                h_flex()
                     .absolute()
                     .top_0()
                     .right_0()
-                    .icon(IconName::Close)
-                    .border_0()
-                    .bg(rgba(0x00000000))
                     .opacity(0.8)
-                    .on_click(cx.listener(move |this, _, window, cx| {
-                        this.delete_sticker(id, title.clone(), window, cx);
+                    .map(|row| match bucket {
+                        StickerBucket::Active => row
+                            .child(
+                                Button::new(("pin", id as u64))
+                                    .icon(IconName::Pin)
+                                    .border_0()
+                                    .bg(rgba(0x00000000))
+                                    .when(!pinned, |button| button.opacity(0.5))
+                                    .tooltip(if pinned { "Unpin" } else { "Pin to the top" })
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        // Not a click on the card, which opens the sticker.
+                                        cx.stop_propagation();
+                                        this.set_sticker_pinned(id, !pinned, cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new(("archive", id as u64))
+                                    .icon(IconName::Archive)
+                                    .border_0()
+                                    .bg(rgba(0x00000000))
+                                    .tooltip("Archive")
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        cx.stop_propagation();
+                                        this.move_sticker(id, StickerBucket::Archived, cx);
+                                    })),
+                            ),
+                        StickerBucket::Archived | StickerBucket::Trash => row.child(
+                            Button::new(("restore", id as u64))
+                                .icon(IconName::Undo)
+                                .border_0()
+                                .bg(rgba(0x00000000))
+                                .tooltip("Restore")
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    cx.stop_propagation();
+                                    this.move_sticker(id, StickerBucket::Active, cx);
+                                })),
+                        ),
+                    })
+                    .child(
+                        Button::new(("delete", id as u64))
+                            .icon(IconName::Close)
+                            .border_0()
+                            .bg(rgba(0x00000000))
+                            .tooltip(if bucket == StickerBucket::Trash {
+                                "Delete forever"
+                            } else {
+                                "Move to trash"
+                            })
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                cx.stop_propagation();
+                                if bucket == StickerBucket::Trash {
+                                    this.purge_sticker(id, title.clone(), window, cx);
+                                } else {
+                                    this.move_sticker(id, StickerBucket::Trash, cx);
+                                }
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    /// Shown below the loaded stickers while more match. The next page loads when it is
+    /// scrolled into view, or on click when the list is too short to scroll.
+    fn load_more_row(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        let loaded = self.stickers.len();
+        let label = match self.stickers_total {
+            Some(total) => format!("{loaded} of {total}, load more"),
+            None => format!("{loaded}, load more"),
+        };
+        h_flex()
+            .id("load-more")
+            .justify_center()
+            .gap_2()
+            .p_2()
+            .text_xs()
+            .opacity(0.6)
+            .cursor_pointer()
+            .when(self.loading, |row| row.child(Spinner::new().xsmall()))
+            .child(label)
+            .on_click(cx.listener(|this, _, _, cx| this.spawn_load_more(cx)))
+            .into_any_element()
+    }
+
+    fn sticker_list(&self, cx: &mut Context<Self>) -> Vec<gpui::AnyElement> {
+        if !self.group_by_tag {
+            return self
+                .stickers
+                .iter()
+                .map(|s| {
+                    div()
+                        .pl_2()
+                        .pr_2()
+                        .pb_2()
+                        .child(Self::sticker_card(s, self.bucket, cx))
+                        .into_any_element()
+                })
+                .collect();
+        }
+
+        // A sticker with several tags shows up under each of them.
+        let mut groups: BTreeMap<String, Vec<&StickerBrief>> = BTreeMap::new();
+        let mut untagged: Vec<&StickerBrief> = Vec::new();
+        for sticker in &self.stickers {
+            match self.sticker_tags.get(&sticker.id) {
+                Some(tags) if !tags.is_empty() => {
+                    for tag in tags {
+                        groups.entry(tag.clone()).or_default().push(sticker);
+                    }
+                }
+                _ => untagged.push(sticker),
+            }
+        }
+
+        let mut sections: Vec<(Option<String>, Vec<&StickerBrief>)> = groups
+            .into_iter()
+            .map(|(tag, stickers)| (Some(tag), stickers))
+            .collect();
+        if !untagged.is_empty() {
+            sections.push((None, untagged));
+        }
+
+        sections
+            .into_iter()
+            .enumerate()
+            .map(|(ix, (tag, stickers))| self.tag_section(ix, tag, stickers, cx))
+            .collect()
+    }
+
+    fn tag_section(
+        &self,
+        ix: usize,
+        tag: Option<String>,
+        stickers: Vec<&StickerBrief>,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        let key = tag.clone().unwrap_or_default();
+        let bucket = self.bucket;
+        let collapsed = self.collapsed_tags.contains(&key);
+        let label = tag.unwrap_or_else(|| "Untagged".to_string());
+
+        v_flex()
+            .id(("tag-section", ix))
+            .child(
+                h_flex()
+                    .id(("tag-header", ix))
+                    .px_2()
+                    .pb_1()
+                    .gap_1()
+                    .items_center()
+                    .text_xs()
+                    .opacity(0.8)
+                    .cursor_pointer()
+                    .child(
+                        Icon::new(if collapsed {
+                            IconName::ArrowUp
+                        } else {
+                            IconName::ArrowDown
+                        })
+                        .with_size(px(12.)),
+                    )
+                    .child(format!("{label} ({})", stickers.len()))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        if !this.collapsed_tags.remove(&key) {
+                            this.collapsed_tags.insert(key.clone());
+                        }
+                        cx.notify();
                     })),
             )
+            .when(!collapsed, |section| {
+                section.children(stickers.into_iter().map(|s| {
+                    div()
+                        .pl_2()
+                        .pr_2()
+                        .pb_2()
+                        .child(Self::sticker_card(s, bucket, cx))
+                }))
+            })
             .into_any_element()
     }
 
     fn title_bar(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        let native_controls = decorations::has_native_window_controls();
+
         h_flex()
             .gap_2()
             .justify_between()
-            .window_control_area(WindowControlArea::Drag)
+            .when(native_controls, |view| {
+                view.pl(decorations::TRAFFIC_LIGHTS_WIDTH)
+            })
+            .window_drag_area()
             .child(
                 h_flex()
                     .gap_1()
@@ -536,30 +2342,69 @@ impl MainWindow {
                             .tab_index(0)
                             .prefix(Icon::new(IconName::Search)),
                     )
-                    .child(self.sort_button(cx)),
+                    .child(self.sort_button(cx))
+                    .child(self.tag_button(cx))
+                    .child(self.collections_button(cx)),
             )
             .child(
                 h_flex()
+                    .when(TOP_MOST_SUPPORTED, |view| {
+                        view.child(
+                            Button::new("pin")
+                                .icon(IconName::Pin)
+                                .border_0()
+                                .bg(rgba(0x00000000))
+                                .opacity(if SettingsStore::get(cx).main_window_top_most {
+                                    1.0
+                                } else {
+                                    0.4
+                                })
+                                .tooltip("Keep on top")
+                                .on_click(cx.listener(|_, _, window, cx| {
+                                    let top_most = !SettingsStore::get(cx).main_window_top_most;
+                                    SettingsStore::update(cx, |s| {
+                                        s.main_window_top_most = top_most
+                                    });
+                                    set_window_top_most(window, top_most);
+                                    cx.notify();
+                                })),
+                        )
+                    })
                     .child(
-                        Button::new("minimize")
-                            .icon(IconName::Minus)
-                            .border_0()
-                            .bg(rgba(0x00000000))
-                            .opacity(0.8)
-                            .on_click(cx.listener(|_, _, window, _| {
-                                window.minimize_window();
-                            })),
-                    )
-                    .child(
-                        Button::new("close")
-                            .icon(IconName::Close)
+                        Button::new("settings")
+                            .icon(IconName::Adjustments)
                             .border_0()
                             .bg(rgba(0x00000000))
                             .opacity(0.8)
-                            .on_click(cx.listener(|_, _, _, cx| {
-                                cx.quit();
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                if let Err(err) = SettingsWindow::open(cx) {
+                                    this.error = Some(format!("Failed to open settings: {err:#}"));
+                                    cx.notify();
+                                }
                             })),
                     )
+                    .when(!native_controls, |view| {
+                        view.child(
+                            Button::new("minimize")
+                                .icon(IconName::Minus)
+                                .border_0()
+                                .bg(rgba(0x00000000))
+                                .opacity(0.8)
+                                .on_click(cx.listener(|_, _, window, _| {
+                                    window.minimize_window();
+                                })),
+                        )
+                        .child(
+                            Button::new("close")
+                                .icon(IconName::Close)
+                                .border_0()
+                                .bg(rgba(0x00000000))
+                                .opacity(0.8)
+                                .on_click(cx.listener(|_, _, _, cx| {
+                                    cx.quit();
+                                })),
+                        )
+                    })
                     .child(self.create_button(cx)),
             )
             .into_any_element()
@@ -568,8 +2413,11 @@ impl MainWindow {
 
 impl Render for MainWindow {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.load_more_when_near_end(cx);
+
         v_flex()
             .gap_2()
+            .relative()
             .size_full()
             .bg(black().opacity(0.85))
             .on_mouse_down(MouseButton::Left, |_, window, _| {
@@ -579,31 +2427,159 @@ impl Render for MainWindow {
             })
             .child(self.title_bar(cx))
             .child(
-                div().h_full().flex_shrink().overflow_hidden().child(
-                    v_flex().overflow_y_scrollbar().children(
-                        self.stickers
-                            .iter()
-                            .map(|s| div().pl_2().pr_2().pb_2().child(Self::sticker_card(s, cx))),
+                h_flex()
+                    .items_start()
+                    .h_full()
+                    .flex_shrink()
+                    .overflow_hidden()
+                    .when(self.show_collections, |view| {
+                        view.child(self.collections_sidebar(cx))
+                    })
+                    .child(
+                        div()
+                            .relative()
+                            .flex_1()
+                            .h_full()
+                            .overflow_hidden()
+                            .child(
+                                v_flex()
+                                    .id("sticker-list")
+                                    .size_full()
+                                    .track_scroll(&self.list_scroll)
+                                    .overflow_y_scroll()
+                                    .children(self.sticker_list(cx))
+                                    .when(self.has_more, |list| list.child(self.load_more_row(cx))),
+                            )
+                            .vertical_scrollbar(&self.list_scroll),
                     ),
-                ),
             )
             .child(
                 v_flex()
                     .gap_2()
                     .px_2()
-                    .window_control_area(WindowControlArea::Drag)
+                    .window_drag_area()
+                    .when(self.store.is_read_only(), |view| {
+                        view.child(
+                            div()
+                                .p(px(8.0))
+                                .child(Alert::warning("main-read-only", READ_ONLY_MESSAGE).small()),
+                        )
+                    })
+                    .child(self.focus_stats(cx))
                     .child(self.status_banner(cx)),
             )
+            .children(decorations::resize_handles(window))
+            .when(app_lock::is_locked(cx), |view| {
+                view.child(app_lock::lock_overlay(window, cx))
+            })
             .children(Root::render_dialog_layer(window, cx))
     }
 }
 
+/// A sticker card being dragged onto a collection.
+#[derive(Debug, Clone)]
+struct DraggedSticker {
+    id: i64,
+    title: SharedString,
+}
+
+impl Render for DraggedSticker {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .w(px(140.0))
+            .p_1()
+            .rounded_sm()
+            .text_xs()
+            .overflow_hidden()
+            .text_ellipsis()
+            .whitespace_nowrap()
+            .bg(cx.theme().background.opacity(0.9))
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(self.title.clone())
+    }
+}
+
+/// The best fitting sticker for the clipboard, plus plain text when it is not that already.
+fn clipboard_menu(
+    menu: PopupMenu,
+    window: &mut Window,
+    cx: &mut Context<PopupMenu>,
+    root_entity: &Entity<MainWindow>,
+) -> PopupMenu {
+    let item = cx
+        .read_from_clipboard()
+        .and_then(|item| inbox::clipboard_item(&item));
+    let Some(item) = item else {
+        return menu.item(PopupMenuItem::new("clipboard is empty").disabled(true));
+    };
+
+    let mut drafts = Vec::new();
+    match Draft::from_item(item.clone()) {
+        Ok(draft) => drafts.push(draft),
+        Err(err) => {
+            tracing::warn!(error = ?err, "Failed to read clipboard item");
+        }
+    }
+    if let InboxItem::Text(text) = item
+        && drafts
+            .first()
+            .is_none_or(|x| x.sticker_type != StickerType::Markdown)
+    {
+        drafts.push(Draft::text(text));
+    }
+
+    drafts.into_iter().fold(menu, |menu, draft| {
+        let mut title = draft.title.clone();
+        if title.chars().count() > CLIPBOARD_LABEL_MAX_CHARS {
+            title = title
+                .chars()
+                .take(CLIPBOARD_LABEL_MAX_CHARS)
+                .collect::<String>()
+                + "…";
+        }
+        let label = format!("{}: {title}", draft.sticker_type.label().to_lowercase());
+        menu.item(
+            PopupMenuItem::new(label)
+                .icon(sticker_type_icon(&draft.sticker_type))
+                .on_click(window.listener_for(root_entity, move |this, _, _, cx| {
+                    this.create_from_clipboard(draft.clone(), cx);
+                })),
+        )
+    })
+}
+
 fn sticker_type_icon(sticker_type: &StickerType) -> IconName {
     match sticker_type {
         StickerType::Markdown => IconName::DocumentText,
         StickerType::Command => IconName::Command,
         StickerType::Timer => IconName::Bell,
         StickerType::Paint => IconName::Paint,
+        StickerType::Image => IconName::Image,
+        StickerType::Web => IconName::Globe,
+        StickerType::Clock => IconName::Clock,
+        StickerType::Pomodoro => IconName::Timer,
+        StickerType::System => IconName::Activity,
+        StickerType::Agenda => IconName::Calendar,
+        StickerType::Habit => IconName::CalendarCheck,
+        StickerType::Kanban => IconName::Kanban,
+        StickerType::Code => IconName::Code,
+        StickerType::Counter => IconName::Hash,
+        StickerType::Chart => IconName::ChartLine,
+        StickerType::Currency => IconName::ArrowRightLeft,
+        StickerType::Animation => IconName::Film,
+        StickerType::GitHub => IconName::GitHub,
+        StickerType::Reminder => IconName::AlarmClock,
+        StickerType::Http => IconName::Send,
+        StickerType::Palette => IconName::Palette,
+        StickerType::Units => IconName::Ruler,
+        StickerType::Uptime => IconName::HeartPulse,
+        StickerType::Git => IconName::GitBranch,
+        StickerType::Launcher => IconName::Rocket,
+        StickerType::Media => IconName::Music,
+        StickerType::Calculator => IconName::Calculator,
+        StickerType::MindMap => IconName::Network,
+        StickerType::TimeTracker => IconName::Briefcase,
     }
 }
 
@@ -613,5 +2589,6 @@ fn order_label(order_by: StickerOrderBy) -> &'static str {
         StickerOrderBy::CreatedAsc => "Created ↑",
         StickerOrderBy::UpdatedDesc => "Updated ↓",
         StickerOrderBy::UpdatedAsc => "Updated ↑",
+        StickerOrderBy::PinnedFirst => "Pinned first",
     }
 }
@@ -1,11 +1,53 @@
 use crate::model::sticker::StickerColor;
 
+pub mod annotation;
+pub mod eyedropper;
+pub mod inbox;
+pub mod lock;
 pub mod main;
+pub mod settings;
+pub mod startup_error;
 pub mod sticker;
 
 #[derive(Debug, Clone)]
 pub enum StickerWindowEvent {
-    Closed { id: i64 },
-    ColorChanged { id: i64, color: StickerColor },
-    TitleChanged { id: i64, title: String },
+    Closed {
+        id: i64,
+    },
+    ColorChanged {
+        id: i64,
+        color: StickerColor,
+    },
+    TitleChanged {
+        id: i64,
+        title: String,
+    },
+    /// The content was saved, `summary` is the new card preview (empty for types without one).
+    ContentChanged {
+        id: i64,
+        summary: String,
+    },
+    /// A sticker was added to the store, its window may not be open.
+    Created {
+        id: i64,
+    },
+    /// A timer sticker ran down.
+    TimerFinished {
+        id: i64,
+    },
+    /// A command sticker's command exited, `exit_code` is `None` when it was killed.
+    /// `scheduled` when the command runs on a schedule or watch.
+    CommandDone {
+        id: i64,
+        exit_code: Option<i32>,
+        scheduled: bool,
+    },
+    /// A reminder sticker became due while its window is open.
+    ReminderDue {
+        id: i64,
+    },
+    /// A pomodoro finished a focus session, totals shown elsewhere should reload.
+    FocusSessionRecorded,
+    /// Stickers were added or changed outside of the sticker windows, lists should reload.
+    StoreChanged,
 }
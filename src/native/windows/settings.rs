@@ -0,0 +1,1307 @@
+use gpui::{
+    AnyElement, AnyWindowHandle, App, AppContext, Bounds, Context, Entity, IntoElement,
+    MouseButton, Render, Window, WindowBackgroundAppearance, WindowBounds, WindowOptions, div,
+    prelude::*, px, rgba, size, transparent_black,
+};
+use gpui_component::{
+    ActiveTheme, Root, Sizable,
+    alert::Alert,
+    black,
+    button::{Button, ButtonVariants as _},
+    form::{field, v_form},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    scroll::ScrollableElement,
+    switch::Switch,
+    v_flex,
+};
+use std::sync::RwLock;
+
+use crate::model::sticker::{StickerColor, StickerType};
+use crate::native::app_lock;
+use crate::native::components::IconName;
+use crate::native::decorations::{self, WindowDragArea};
+use crate::native::dialogs;
+use crate::native::hooks::Hook;
+use crate::native::hotkey;
+use crate::native::sound::{self, Sound};
+use crate::storage::settings::{AppSettings, SettingsStore, SoundSettings, TaskProvider};
+
+static SETTINGS_WINDOW: RwLock<Option<AnyWindowHandle>> = RwLock::new(None);
+
+pub struct SettingsWindow {
+    color_labels: Vec<(StickerColor, Entity<InputState>)>,
+    title_templates: Vec<(StickerType, Entity<InputState>)>,
+    daily_note_time: Entity<InputState>,
+    daily_note_template: Entity<InputState>,
+    temporary_ttl: Entity<InputState>,
+    max_content: Entity<InputState>,
+    todoist_token: Entity<InputState>,
+    todoist_project_id: Entity<InputState>,
+    caldav_url: Entity<InputState>,
+    caldav_username: Entity<InputState>,
+    caldav_password: Entity<InputState>,
+    task_sync_interval: Entity<InputState>,
+    folder_sync_folder: Entity<InputState>,
+    hooks: Vec<(Hook, Entity<InputState>)>,
+    webhook_url: Entity<InputState>,
+    archive_closed_after: Entity<InputState>,
+    purge_trash_after: Entity<InputState>,
+    backup_interval: Entity<InputState>,
+    backup_keep: Entity<InputState>,
+    sound_volume: Entity<InputState>,
+    app_lock_idle: Entity<InputState>,
+    app_lock_pin: Entity<InputState>,
+    focus_goal_daily: Entity<InputState>,
+    focus_goal_weekly: Entity<InputState>,
+    status: Option<Result<String, String>>,
+}
+
+impl SettingsWindow {
+    pub fn open(cx: &mut App) -> anyhow::Result<()> {
+        if let Ok(settings_window) = SETTINGS_WINDOW.read()
+            && let Some(handle) = settings_window.as_ref()
+            && handle
+                .update(cx, |_, window, _| window.activate_window())
+                .is_ok()
+        {
+            return Ok(());
+        }
+
+        let bounds = Bounds::centered(None, size(px(360.), px(480.0)), cx);
+
+        let handle = cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(300.0), px(300.0))),
+                window_background: WindowBackgroundAppearance::Transparent,
+                titlebar: Some(decorations::titlebar_options("Rustickers Settings")),
+                window_decorations: decorations::window_decorations(),
+                ..Default::default()
+            },
+            |window, cx| {
+                let view = cx.new(|cx| SettingsWindow::new(window, cx));
+                cx.new(|cx| Root::new(view, window, cx).bg(transparent_black().alpha(0.0)))
+            },
+        )?;
+
+        if let Ok(mut settings_window) = SETTINGS_WINDOW.write() {
+            *settings_window = Some(handle.into());
+        }
+
+        Ok(())
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        window.on_window_should_close(cx, |_, _| {
+            Self::forget_window();
+            true
+        });
+
+        let color_labels = StickerColor::ALL
+            .into_iter()
+            .map(|color| {
+                let label = SettingsStore::get(cx)
+                    .color_label(color)
+                    .unwrap_or_default()
+                    .to_string();
+                let input = cx.new(|cx| {
+                    InputState::new(window, cx)
+                        .placeholder("What this color means")
+                        .default_value(label)
+                });
+                cx.subscribe(&input, move |_, input, event: &InputEvent, cx| {
+                    if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                        let label = input.read(cx).value().to_string();
+                        SettingsStore::update(cx, |s| s.set_color_label(color, &label));
+                    }
+                })
+                .detach();
+                (color, input)
+            })
+            .collect();
+
+        let title_templates = StickerType::ALL
+            .into_iter()
+            .map(|sticker_type| {
+                let template = SettingsStore::get(cx)
+                    .title_template(sticker_type)
+                    .unwrap_or_default()
+                    .to_string();
+                let input = cx.new(|cx| {
+                    InputState::new(window, cx)
+                        .placeholder(format!("New {} Sticker", sticker_type.label()))
+                        .default_value(template)
+                });
+                cx.subscribe(&input, move |_, input, event: &InputEvent, cx| {
+                    if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                        let template = input.read(cx).value().to_string();
+                        SettingsStore::update(cx, |s| {
+                            s.set_title_template(sticker_type, &template)
+                        });
+                    }
+                })
+                .detach();
+                (sticker_type, input)
+            })
+            .collect();
+
+        let daily_note = SettingsStore::get(cx).daily_note.clone();
+        let daily_note_time = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("HH:MM")
+                .default_value(daily_note.time)
+        });
+        cx.subscribe(&daily_note_time, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let time = input.read(cx).value().trim().to_string();
+                if chrono::NaiveTime::parse_from_str(&time, "%H:%M").is_err() {
+                    this.set_status(Err(format!("Invalid daily note time: {time}")), cx);
+                    return;
+                }
+                SettingsStore::update(cx, |s| s.daily_note.time = time);
+            }
+        })
+        .detach();
+
+        let daily_note_template = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(3)
+                .default_value(daily_note.template)
+        });
+        cx.subscribe(&daily_note_template, |_, input, event: &InputEvent, cx| {
+            if let InputEvent::Blur = event {
+                let template = input.read(cx).value().to_string();
+                SettingsStore::update(cx, |s| s.daily_note.template = template);
+            }
+        })
+        .detach();
+
+        let temporary_ttl = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("0")
+                .default_value(SettingsStore::get(cx).temporary_ttl_minutes.to_string())
+        });
+        cx.subscribe(&temporary_ttl, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let text = input.read(cx).value().trim().to_string();
+                let Ok(minutes) = text.parse::<u32>() else {
+                    this.set_status(Err(format!("Invalid number of minutes: {text}")), cx);
+                    return;
+                };
+                SettingsStore::update(cx, |s| s.temporary_ttl_minutes = minutes);
+            }
+        })
+        .detach();
+
+        let max_content = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("0")
+                .default_value(SettingsStore::get(cx).max_content_kb.to_string())
+        });
+        cx.subscribe(&max_content, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let text = input.read(cx).value().trim().to_string();
+                let Ok(kb) = text.parse::<u32>() else {
+                    this.set_status(Err(format!("Invalid size: {text}")), cx);
+                    return;
+                };
+                SettingsStore::update(cx, |s| s.max_content_kb = kb);
+            }
+        })
+        .detach();
+
+        let task_sync = SettingsStore::get(cx).task_sync.clone();
+        let todoist_token = Self::text_input(
+            window,
+            cx,
+            task_sync.todoist_token,
+            "Todoist API token",
+            true,
+            |s, value| s.task_sync.todoist_token = value,
+        );
+        let todoist_project_id = Self::text_input(
+            window,
+            cx,
+            task_sync.todoist_project_id,
+            "Project id, all projects when empty",
+            false,
+            |s, value| s.task_sync.todoist_project_id = value,
+        );
+        let caldav_url = Self::text_input(
+            window,
+            cx,
+            task_sync.caldav_url,
+            "CalDAV task list URL",
+            false,
+            |s, value| s.task_sync.caldav_url = value,
+        );
+        let caldav_username = Self::text_input(
+            window,
+            cx,
+            task_sync.caldav_username,
+            "User name",
+            false,
+            |s, value| s.task_sync.caldav_username = value,
+        );
+        let caldav_password = Self::text_input(
+            window,
+            cx,
+            task_sync.caldav_password,
+            "Password",
+            true,
+            |s, value| s.task_sync.caldav_password = value,
+        );
+
+        let folder_sync_folder = Self::text_input(
+            window,
+            cx,
+            SettingsStore::get(cx).folder_sync.folder.clone(),
+            "Folder kept in sync by Syncthing, Dropbox, …",
+            false,
+            |s, value| s.folder_sync.folder = value,
+        );
+
+        let hooks = Hook::ALL
+            .into_iter()
+            .map(|hook| {
+                let command = hook.command(&SettingsStore::get(cx).hooks).to_string();
+                let input = cx.new(|cx| {
+                    InputState::new(window, cx)
+                        .placeholder("Command line, off when empty")
+                        .default_value(command)
+                });
+                cx.subscribe(&input, move |_, input, event: &InputEvent, cx| {
+                    if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                        let command = input.read(cx).value().trim().to_string();
+                        SettingsStore::update(cx, |s| hook.set_command(&mut s.hooks, command));
+                    }
+                })
+                .detach();
+                (hook, input)
+            })
+            .collect();
+
+        let webhook_url = Self::text_input(
+            window,
+            cx,
+            SettingsStore::get(cx).webhook.url.clone(),
+            "Webhook URL, e.g. of Slack, Discord or ntfy",
+            false,
+            |s, value| s.webhook.url = value,
+        );
+
+        let task_sync_interval = cx.new(|cx| {
+            InputState::new(window, cx).default_value(task_sync.interval_minutes.to_string())
+        });
+        cx.subscribe(
+            &task_sync_interval,
+            |this, input, event: &InputEvent, cx| {
+                if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                    let text = input.read(cx).value().trim().to_string();
+                    let Ok(minutes @ 1..) = text.parse::<u32>() else {
+                        this.set_status(Err(format!("Invalid sync interval: {text}")), cx);
+                        return;
+                    };
+                    SettingsStore::update(cx, |s| s.task_sync.interval_minutes = minutes);
+                }
+            },
+        )
+        .detach();
+
+        let archive = SettingsStore::get(cx).archive.clone();
+        let archive_closed_after = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("0")
+                .default_value(archive.archive_closed_after_days.to_string())
+        });
+        cx.subscribe(
+            &archive_closed_after,
+            |this, input, event: &InputEvent, cx| {
+                if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                    let text = input.read(cx).value().trim().to_string();
+                    let Ok(days) = text.parse::<u32>() else {
+                        this.set_status(Err(format!("Invalid number of days: {text}")), cx);
+                        return;
+                    };
+                    SettingsStore::update(cx, |s| s.archive.archive_closed_after_days = days);
+                }
+            },
+        )
+        .detach();
+
+        let purge_trash_after = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("0")
+                .default_value(archive.purge_trash_after_days.to_string())
+        });
+        cx.subscribe(&purge_trash_after, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let text = input.read(cx).value().trim().to_string();
+                let Ok(days) = text.parse::<u32>() else {
+                    this.set_status(Err(format!("Invalid number of days: {text}")), cx);
+                    return;
+                };
+                SettingsStore::update(cx, |s| s.archive.purge_trash_after_days = days);
+            }
+        })
+        .detach();
+
+        let backup = SettingsStore::get(cx).backup.clone();
+        let backup_interval = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("0")
+                .default_value(backup.interval_hours.to_string())
+        });
+        cx.subscribe(&backup_interval, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let text = input.read(cx).value().trim().to_string();
+                let Ok(hours) = text.parse::<u32>() else {
+                    this.set_status(Err(format!("Invalid number of hours: {text}")), cx);
+                    return;
+                };
+                SettingsStore::update(cx, |s| s.backup.interval_hours = hours);
+            }
+        })
+        .detach();
+
+        let backup_keep = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("7")
+                .default_value(backup.keep.to_string())
+        });
+        cx.subscribe(&backup_keep, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let text = input.read(cx).value().trim().to_string();
+                let Some(keep) = text.parse::<u32>().ok().filter(|x| *x > 0) else {
+                    this.set_status(Err(format!("Invalid number of backups: {text}")), cx);
+                    return;
+                };
+                SettingsStore::update(cx, |s| s.backup.keep = keep);
+            }
+        })
+        .detach();
+
+        let sound_volume = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("70")
+                .default_value(SettingsStore::get(cx).sound.volume.to_string())
+        });
+        cx.subscribe(&sound_volume, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let text = input.read(cx).value().trim().to_string();
+                let Some(volume) = text.parse::<u8>().ok().filter(|v| *v <= 100) else {
+                    this.set_status(Err(format!("Invalid volume: {text}")), cx);
+                    return;
+                };
+                SettingsStore::update(cx, |s| s.sound.volume = volume);
+            }
+        })
+        .detach();
+
+        let app_lock_idle = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("0")
+                .default_value(SettingsStore::get(cx).app_lock.idle_minutes.to_string())
+        });
+        cx.subscribe(&app_lock_idle, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let text = input.read(cx).value().trim().to_string();
+                let Ok(minutes) = text.parse::<u32>() else {
+                    this.set_status(Err(format!("Invalid number of minutes: {text}")), cx);
+                    return;
+                };
+                SettingsStore::update(cx, |s| s.app_lock.idle_minutes = minutes);
+            }
+        })
+        .detach();
+
+        let app_lock_pin = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("New PIN, press Enter to save")
+                .masked(true)
+        });
+        cx.subscribe_in(
+            &app_lock_pin,
+            window,
+            |this, input, event: &InputEvent, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    let pin = input.read(cx).value().trim().to_string();
+                    if pin.len() < 4 {
+                        this.set_status(Err("The PIN needs at least 4 characters".to_string()), cx);
+                        return;
+                    }
+                    let pin_hash = match app_lock::hash_pin(&pin) {
+                        Ok(x) => x,
+                        Err(err) => {
+                            tracing::error!(error = ?err, "Failed to hash the PIN");
+                            this.set_status(Err(format!("Failed to save the PIN: {err}")), cx);
+                            return;
+                        }
+                    };
+                    SettingsStore::update(cx, |s| s.app_lock.pin_hash = pin_hash);
+                    input.update(cx, |input, cx| input.set_value("", window, cx));
+                    this.set_status(Ok("PIN saved".to_string()), cx);
+                }
+            },
+        )
+        .detach();
+
+        let focus_goal = SettingsStore::get(cx).focus_goal.clone();
+        let focus_goal_daily = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("0")
+                .default_value(focus_goal.daily_sessions.to_string())
+        });
+        cx.subscribe(&focus_goal_daily, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let text = input.read(cx).value().trim().to_string();
+                let Ok(sessions) = text.parse::<u32>() else {
+                    this.set_status(Err(format!("Invalid number of sessions: {text}")), cx);
+                    return;
+                };
+                SettingsStore::update(cx, |s| s.focus_goal.daily_sessions = sessions);
+            }
+        })
+        .detach();
+
+        let focus_goal_weekly = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("0")
+                .default_value(focus_goal.weekly_sessions.to_string())
+        });
+        cx.subscribe(&focus_goal_weekly, |this, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let text = input.read(cx).value().trim().to_string();
+                let Ok(sessions) = text.parse::<u32>() else {
+                    this.set_status(Err(format!("Invalid number of sessions: {text}")), cx);
+                    return;
+                };
+                SettingsStore::update(cx, |s| s.focus_goal.weekly_sessions = sessions);
+            }
+        })
+        .detach();
+
+        Self {
+            color_labels,
+            title_templates,
+            daily_note_time,
+            daily_note_template,
+            temporary_ttl,
+            max_content,
+            todoist_token,
+            todoist_project_id,
+            caldav_url,
+            caldav_username,
+            caldav_password,
+            task_sync_interval,
+            folder_sync_folder,
+            hooks,
+            webhook_url,
+            archive_closed_after,
+            purge_trash_after,
+            backup_interval,
+            backup_keep,
+            sound_volume,
+            app_lock_idle,
+            app_lock_pin,
+            focus_goal_daily,
+            focus_goal_weekly,
+            status: None,
+        }
+    }
+
+    /// An input which writes its trimmed value into the settings on enter and blur.
+    fn text_input(
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        value: String,
+        placeholder: &'static str,
+        masked: bool,
+        apply: fn(&mut AppSettings, String),
+    ) -> Entity<InputState> {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(placeholder)
+                .masked(masked)
+                .default_value(value)
+        });
+        cx.subscribe(&input, move |_, input, event: &InputEvent, cx| {
+            if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+                let value = input.read(cx).value().trim().to_string();
+                SettingsStore::update(cx, |s| apply(s, value));
+            }
+        })
+        .detach();
+        input
+    }
+
+    fn sound_field(&self, cx: &mut Context<Self>) -> AnyElement {
+        let sound = SettingsStore::get(cx).sound.clone();
+        let kind_switch = |id: &'static str,
+                           label: &'static str,
+                           checked: bool,
+                           set: fn(&mut SoundSettings, bool)| {
+            Switch::new(id)
+                .label(label)
+                .small()
+                .checked(checked)
+                .on_click(cx.listener(move |_, checked: &bool, _, cx| {
+                    let checked = *checked;
+                    SettingsStore::update(cx, |s| set(&mut s.sound, checked));
+                    cx.notify();
+                }))
+        };
+
+        v_flex()
+            .gap_1()
+            .child(
+                Switch::new("sound_muted")
+                    .label("mute all sounds")
+                    .small()
+                    .checked(sound.muted)
+                    .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                        let checked = *checked;
+                        SettingsStore::update(cx, |s| s.sound.muted = checked);
+                        cx.notify();
+                    })),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(div().text_sm().child("volume"))
+                    .child(Input::new(&self.sound_volume).small().w(px(48.0)))
+                    .child(div().text_sm().child("%"))
+                    .child(
+                        Button::new("sound_test")
+                            .label("Test")
+                            .xsmall()
+                            .disabled(sound.muted)
+                            .on_click(|_, _, cx| sound::play(cx, Sound::Ding)),
+                    ),
+            )
+            .child(kind_switch(
+                "sound_alarms",
+                "timers, pomodoros and reminders",
+                sound.alarms,
+                |s, checked| s.alarms = checked,
+            ))
+            .child(kind_switch(
+                "sound_dings",
+                "desktop notifications",
+                sound.dings,
+                |s, checked| s.dings = checked,
+            ))
+            .child(kind_switch(
+                "sound_command_failures",
+                "failed commands",
+                sound.command_failures,
+                |s, checked| s.command_failures = checked,
+            ))
+            .into_any_element()
+    }
+
+    fn app_lock_field(&self, cx: &mut Context<Self>) -> AnyElement {
+        let app_lock = SettingsStore::get(cx).app_lock.clone();
+        let hint = if app_lock.pin_hash.is_empty() {
+            "Set a PIN first, the app only locks with one"
+        } else {
+            "Lock now with Ctrl + Alt + L or `rustickers lock`, 0 minutes only locks on demand"
+        };
+
+        v_flex()
+            .gap_1()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Switch::new("app_lock")
+                            .label("lock after being idle for")
+                            .small()
+                            .checked(app_lock.enabled)
+                            .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                                let checked = *checked;
+                                SettingsStore::update(cx, |s| s.app_lock.enabled = checked);
+                                cx.notify();
+                            })),
+                    )
+                    .child(Input::new(&self.app_lock_idle).small().w(px(48.0)))
+                    .child(div().text_sm().child("minutes")),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Input::new(&self.app_lock_pin).small())
+                    .child(
+                        Button::new("app_lock_now")
+                            .label("Lock now")
+                            .small()
+                            .disabled(!app_lock::is_set_up(&app_lock))
+                            .on_click(|_, _, cx| app_lock::lock(cx)),
+                    ),
+            )
+            .child(div().text_xs().opacity(0.75).child(hint))
+            .into_any_element()
+    }
+
+    fn task_sync_field(&self, cx: &mut Context<Self>) -> AnyElement {
+        let task_sync = SettingsStore::get(cx).task_sync.clone();
+        let provider_button = |id: &'static str, label: &'static str, provider: TaskProvider| {
+            Button::new(id)
+                .label(label)
+                .small()
+                .when(task_sync.provider == provider, |button| button.primary())
+                .on_click(cx.listener(move |_, _, _, cx| {
+                    SettingsStore::update(cx, |s| s.task_sync.provider = provider);
+                    cx.notify();
+                }))
+        };
+
+        let sticker = match task_sync.sticker_id {
+            Some(id) => format!("Tasks are shown in sticker #{id}, check them off there and save"),
+            None => "The first sync creates a Markdown sticker for the tasks".to_string(),
+        };
+
+        v_flex()
+            .gap_1()
+            .child(
+                Switch::new("task_sync")
+                    .label("show open tasks in a sticker")
+                    .small()
+                    .checked(task_sync.enabled)
+                    .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                        let checked = *checked;
+                        SettingsStore::update(cx, |s| s.task_sync.enabled = checked);
+                        cx.notify();
+                    })),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .items_center()
+                    .child(provider_button(
+                        "task_sync_todoist",
+                        "Todoist",
+                        TaskProvider::Todoist,
+                    ))
+                    .child(provider_button(
+                        "task_sync_caldav",
+                        "CalDAV",
+                        TaskProvider::CalDav,
+                    ))
+                    .child(div().text_sm().child("every"))
+                    .child(Input::new(&self.task_sync_interval).small().w(px(48.0)))
+                    .child(div().text_sm().child("minutes")),
+            )
+            .map(|view| match task_sync.provider {
+                TaskProvider::Todoist => view
+                    .child(Input::new(&self.todoist_token).small())
+                    .child(Input::new(&self.todoist_project_id).small()),
+                TaskProvider::CalDav => view.child(Input::new(&self.caldav_url).small()).child(
+                    h_flex()
+                        .gap_1()
+                        .child(Input::new(&self.caldav_username).small())
+                        .child(Input::new(&self.caldav_password).small()),
+                ),
+            })
+            .child(div().text_xs().opacity(0.75).child(sticker))
+            .into_any_element()
+    }
+
+    fn folder_sync_field(&self, cx: &mut Context<Self>) -> AnyElement {
+        let enabled = SettingsStore::get(cx).folder_sync.enabled;
+
+        v_flex()
+            .gap_1()
+            .child(
+                Switch::new("folder_sync")
+                    .label("mirror every sticker to a file in a folder")
+                    .small()
+                    .checked(enabled)
+                    .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                        let checked = *checked;
+                        SettingsStore::update(cx, |s| s.folder_sync.enabled = checked);
+                        cx.notify();
+                    })),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Input::new(&self.folder_sync_folder).small())
+                    .child(
+                        Button::new("folder_sync_pick")
+                            .label("Choose…")
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.pick_sync_folder(window, cx)
+                            })),
+                    ),
+            )
+            .child(div().text_xs().opacity(0.75).child(
+                "Markdown stickers as .md, the others as .json. Edits of the files are merged back, deleting one trashes its sticker",
+            ))
+            .into_any_element()
+    }
+
+    fn webhook_field(&self, cx: &mut Context<Self>) -> AnyElement {
+        let webhook = SettingsStore::get(cx).webhook.clone();
+        let event_switch = |id: &'static str,
+                            label: &'static str,
+                            checked: bool,
+                            apply: fn(&mut AppSettings, bool)| {
+            Switch::new(id)
+                .label(label)
+                .small()
+                .checked(checked)
+                .on_click(cx.listener(move |_, checked: &bool, _, cx| {
+                    let checked = *checked;
+                    SettingsStore::update(cx, |s| apply(s, checked));
+                    cx.notify();
+                }))
+        };
+
+        v_flex()
+            .gap_1()
+            .child(Input::new(&self.webhook_url).small())
+            .child(
+                h_flex()
+                    .gap_2()
+                    .flex_wrap()
+                    .child(event_switch(
+                        "webhook_timer",
+                        "timer finished",
+                        webhook.timer_finished,
+                        |s, checked| s.webhook.timer_finished = checked,
+                    ))
+                    .child(event_switch(
+                        "webhook_command",
+                        "scheduled command failed",
+                        webhook.command_failed,
+                        |s, checked| s.webhook.command_failed = checked,
+                    ))
+                    .child(event_switch(
+                        "webhook_reminder",
+                        "reminder due",
+                        webhook.reminder_due,
+                        |s, checked| s.webhook.reminder_due = checked,
+                    )),
+            )
+            .child(div().text_xs().opacity(0.75).child(
+                "JSON is posted with the message as text and content, the event, sticker id and title",
+            ))
+            .into_any_element()
+    }
+
+    fn mouse_shortcuts_field(&self, cx: &mut Context<Self>) -> AnyElement {
+        let shortcuts = SettingsStore::get(cx).mouse_shortcuts.clone();
+        let shortcut_switch = |id: &'static str,
+                               label: &'static str,
+                               checked: bool,
+                               apply: fn(&mut AppSettings, bool)| {
+            Switch::new(id)
+                .label(label)
+                .small()
+                .checked(checked)
+                .on_click(cx.listener(move |_, checked: &bool, _, cx| {
+                    let checked = *checked;
+                    SettingsStore::update(cx, |s| apply(s, checked));
+                    cx.notify();
+                }))
+        };
+
+        v_flex()
+            .gap_1()
+            .child(shortcut_switch(
+                "middle_click_close",
+                "middle-click the header to close",
+                shortcuts.middle_click_close,
+                |s, checked| s.mouse_shortcuts.middle_click_close = checked,
+            ))
+            .child(shortcut_switch(
+                "double_click_collapse",
+                "double-click the header to collapse and expand",
+                shortcuts.double_click_collapse,
+                |s, checked| s.mouse_shortcuts.double_click_collapse = checked,
+            ))
+            .child(shortcut_switch(
+                "ctrl_drag_duplicate",
+                if cfg!(target_os = "macos") {
+                    "Cmd+drag the header to leave a copy behind"
+                } else {
+                    "Ctrl+drag the header to leave a copy behind"
+                },
+                shortcuts.ctrl_drag_duplicate,
+                |s, checked| s.mouse_shortcuts.ctrl_drag_duplicate = checked,
+            ))
+            .into_any_element()
+    }
+
+    fn pick_sync_folder(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let entity = cx.entity();
+        window
+            .spawn(cx, async move |window| {
+                let Some(path) = dialogs::pick_folder("Choose the sync folder").await else {
+                    return;
+                };
+
+                let _ = window.update_window_entity(&entity, |this, window, cx| {
+                    let folder = path.to_string_lossy().to_string();
+                    this.folder_sync_folder
+                        .update(cx, |input, cx| input.set_value(folder.clone(), window, cx));
+                    SettingsStore::update(cx, |s| s.folder_sync.folder = folder);
+                });
+            })
+            .detach();
+    }
+
+    /// Whether the settings window is the active window.
+    pub fn is_active(cx: &App) -> bool {
+        let Some(active) = cx.active_window() else {
+            return false;
+        };
+        SETTINGS_WINDOW
+            .read()
+            .is_ok_and(|settings_window| *settings_window == Some(active))
+    }
+
+    fn forget_window() {
+        if let Ok(mut settings_window) = SETTINGS_WINDOW.write() {
+            *settings_window = None;
+        }
+    }
+
+    fn set_status(&mut self, status: Result<String, String>, cx: &mut Context<Self>) {
+        self.status = Some(status);
+        cx.notify();
+    }
+
+    fn export_settings(&mut self, cx: &mut Context<Self>) {
+        let settings = SettingsStore::get(cx).clone();
+
+        cx.spawn(async move |this, cx| {
+            let Some(path) = dialogs::save_file(
+                "Export settings",
+                "rustickers-settings.json",
+                &[("JSON", &["json"])],
+            )
+            .await
+            else {
+                return;
+            };
+
+            let status = match settings.export_portable(&path) {
+                Ok(()) => Ok(format!("Settings exported to {}", path.display())),
+                Err(err) => Err(format!("Failed to export settings: {err:#}")),
+            };
+            let _ = this.update(cx, |this, cx| this.set_status(status, cx));
+        })
+        .detach();
+    }
+
+    fn import_settings(&mut self, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let Some(path) = dialogs::pick_file("Import settings", &[("JSON", &["json"])]).await
+            else {
+                return;
+            };
+
+            let _ = this.update(cx, |this, cx| {
+                let status = match SettingsStore::get(cx).import_portable(&path) {
+                    Ok(imported) => {
+                        SettingsStore::update(cx, |s| *s = imported);
+                        Ok(format!("Settings imported from {}", path.display()))
+                    }
+                    Err(err) => Err(format!("Failed to import settings: {err:#}")),
+                };
+                this.set_status(status, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn title_bar(&self, cx: &mut Context<Self>) -> AnyElement {
+        let native_controls = decorations::has_native_window_controls();
+
+        h_flex()
+            .gap_2()
+            .pl_2()
+            .justify_between()
+            .when(native_controls, |view| {
+                view.pl(decorations::TRAFFIC_LIGHTS_WIDTH)
+            })
+            .window_drag_area()
+            .child(div().text_sm().child("Settings"))
+            .when(!native_controls, |view| {
+                view.child(
+                    Button::new("close")
+                        .icon(IconName::Close)
+                        .border_0()
+                        .bg(rgba(0x00000000))
+                        .opacity(0.8)
+                        .on_click(cx.listener(|_, _, window, _| {
+                            Self::forget_window();
+                            window.remove_window();
+                        })),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn form(&self, cx: &mut Context<Self>) -> AnyElement {
+        let settings = SettingsStore::get(cx).clone();
+
+        v_form()
+            .child(
+                field().label("Popup mode").child(
+                    Switch::new("popup_mode")
+                        .label("hide the main window when it loses focus")
+                        .small()
+                        .checked(settings.popup_mode)
+                        .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                            let checked = *checked;
+                            SettingsStore::update(cx, |s| s.popup_mode = checked);
+                            cx.notify();
+                        })),
+                ),
+            )
+            .child(
+                field().label("Opening stickers").child(
+                    v_flex()
+                        .gap_1()
+                        .child(
+                            Switch::new("single_click_open")
+                                .label("open cards with a single click")
+                                .small()
+                                .checked(settings.single_click_open)
+                                .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                                    let checked = *checked;
+                                    SettingsStore::update(cx, |s| s.single_click_open = checked);
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Switch::new("minimize_on_open")
+                                .label("minimize the main window after opening")
+                                .small()
+                                .checked(settings.minimize_on_open)
+                                .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                                    let checked = *checked;
+                                    SettingsStore::update(cx, |s| s.minimize_on_open = checked);
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Switch::new("open_at_cursor")
+                                .label("create new stickers at the mouse cursor")
+                                .small()
+                                .checked(settings.open_at_cursor)
+                                .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                                    let checked = *checked;
+                                    SettingsStore::update(cx, |s| s.open_at_cursor = checked);
+                                    cx.notify();
+                                })),
+                        ),
+                ),
+            )
+            .child(field().label("Mouse shortcuts").child(self.mouse_shortcuts_field(cx)))
+            .child(
+                field().label("Temporary stickers").child(
+                    h_flex()
+                        .gap_2()
+                        .child(div().text_sm().child("delete after"))
+                        .child(Input::new(&self.temporary_ttl).small().w(px(64.0)))
+                        .child(div().text_sm().child("minutes, 0 only when closed")),
+                ),
+            )
+            .child(
+                field().label("Content size").child(
+                    h_flex()
+                        .gap_2()
+                        .child(div().text_sm().child("ask before saving command output over"))
+                        .child(Input::new(&self.max_content).small().w(px(64.0)))
+                        .child(div().text_sm().child("KB, 0 never asks")),
+                ),
+            )
+            .child(
+                field().label("Archive").child(
+                    v_flex()
+                        .gap_1()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(div().text_sm().child("archive closed stickers after"))
+                                .child(Input::new(&self.archive_closed_after).small().w(px(48.0)))
+                                .child(div().text_sm().child("days")),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(div().text_sm().child("empty the trash after"))
+                                .child(Input::new(&self.purge_trash_after).small().w(px(48.0)))
+                                .child(div().text_sm().child("days")),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .opacity(0.75)
+                                .child("Checked once a day, 0 turns a rule off"),
+                        ),
+                ),
+            )
+            .child(
+                field().label("Backups").child(
+                    v_flex()
+                        .gap_1()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(div().text_sm().child("back up the database every"))
+                                .child(Input::new(&self.backup_interval).small().w(px(48.0)))
+                                .child(div().text_sm().child("hours, keep the last"))
+                                .child(Input::new(&self.backup_keep).small().w(px(48.0))),
+                        )
+                        .child(div().text_xs().opacity(0.75).child(
+                            "Into the backups folder of the data directory, 0 hours turns it off. Restore one from the sort menu of the main window",
+                        )),
+                ),
+            )
+            .child(
+                field().label("Animations").child(
+                    Switch::new("disable_animations")
+                        .label("open and close sticker windows without fading")
+                        .small()
+                        .checked(settings.disable_animations)
+                        .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                            let checked = *checked;
+                            SettingsStore::update(cx, |s| s.disable_animations = checked);
+                            cx.notify();
+                        })),
+                ),
+            )
+            .child(
+                field().label("Focus goal").child(
+                    v_flex()
+                        .gap_1()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(div().text_sm().child("pomodoro sessions a day"))
+                                .child(Input::new(&self.focus_goal_daily).small().w(px(48.0)))
+                                .child(div().text_sm().child("a week"))
+                                .child(Input::new(&self.focus_goal_weekly).small().w(px(48.0))),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .opacity(0.75)
+                                .child("Shown on pomodoro stickers and the main window, 0 for no goal"),
+                        ),
+                ),
+            )
+            .child(field().label("Sounds").child(self.sound_field(cx)))
+            .child(
+                field().label("Inbox").child(
+                    Switch::new("inbox_bubble")
+                        .label("show a drop bubble above other windows")
+                        .small()
+                        .checked(settings.inbox_bubble)
+                        .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                            let checked = *checked;
+                            SettingsStore::update(cx, |s| s.inbox_bubble = checked);
+                            cx.notify();
+                        })),
+                ),
+            )
+            .child(
+                field().label("Safe mode").child(
+                    Switch::new("safe_mode")
+                        .label("never run command stickers")
+                        .small()
+                        .checked(settings.safe_mode)
+                        .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                            let checked = *checked;
+                            SettingsStore::update(cx, |s| s.safe_mode = checked);
+                            cx.notify();
+                        })),
+                ),
+            )
+            .child(field().label("App lock").child(self.app_lock_field(cx)))
+            .child(
+                field().label("Global hotkeys").child(
+                    div()
+                        .text_sm()
+                        .child(hotkey::active_backend().label()),
+                ),
+            )
+            .child(
+                field().label("Daily note").child(
+                    v_flex()
+                        .gap_1()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Switch::new("daily_note")
+                                        .label("open a dated note every day at")
+                                        .small()
+                                        .checked(settings.daily_note.enabled)
+                                        .on_click(cx.listener(|_, checked: &bool, _, cx| {
+                                            let checked = *checked;
+                                            SettingsStore::update(cx, |s| {
+                                                s.daily_note.enabled = checked
+                                            });
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(Input::new(&self.daily_note_time).small().w(px(64.0))),
+                        )
+                        .child(Input::new(&self.daily_note_template).small()),
+                ),
+            )
+            .child(field().label("Task sync").child(self.task_sync_field(cx)))
+            .child(field().label("Folder sync").child(self.folder_sync_field(cx)))
+            .child(field().label("Webhook").child(self.webhook_field(cx)))
+            .child(
+                field().label("Hooks").child(
+                    v_flex()
+                        .gap_1()
+                        .children(self.hooks.iter().map(|(hook, input)| {
+                            h_flex()
+                                .gap_1()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .w(px(112.0))
+                                        .flex_shrink_0()
+                                        .text_sm()
+                                        .child(hook.name()),
+                                )
+                                .child(Input::new(input).small())
+                        }))
+                        .child(div().text_xs().opacity(0.75).child(
+                            "Run on sticker events with RUSTICKERS_HOOK, RUSTICKERS_STICKER_ID, _TITLE, _TYPE, _COLOR (and RUSTICKERS_EXIT_CODE) set and the content on stdin. Not in safe mode",
+                        )),
+                ),
+            )
+            .child(
+                field()
+                    .label("Color labels")
+                    .child(v_flex().gap_1().children(self.color_labels.iter().map(
+                        |(color, input)| {
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .size(px(12.0))
+                                        .flex_shrink_0()
+                                        .rounded_full()
+                                        .bg(color.swatch()),
+                                )
+                                .child(Input::new(input).small())
+                        },
+                    ))),
+            )
+            .child(
+                field()
+                    .label("New stickers")
+                    .child(v_flex().gap_1().children(self.title_templates.iter().map(
+                        |(sticker_type, input)| {
+                            let sticker_type = *sticker_type;
+                            let default_color = settings.default_color(sticker_type);
+                            h_flex()
+                                .gap_1()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .w(px(72.0))
+                                        .flex_shrink_0()
+                                        .text_sm()
+                                        .child(sticker_type.label()),
+                                )
+                                .children(StickerColor::ALL.map(|color| {
+                                    div()
+                                        .size(px(14.0))
+                                        .flex_shrink_0()
+                                        .rounded_full()
+                                        .bg(color.swatch())
+                                        .border_2()
+                                        .border_color(if color == default_color {
+                                            rgba(0xffffffcc)
+                                        } else {
+                                            rgba(0x00000000)
+                                        })
+                                        .cursor_pointer()
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(move |_, _, _, cx| {
+                                                SettingsStore::update(cx, |s| {
+                                                    s.set_default_color(sticker_type, color)
+                                                });
+                                                cx.notify();
+                                            }),
+                                        )
+                                }))
+                                .child(Input::new(input).small())
+                        },
+                    )))
+                    .child(
+                        div()
+                            .text_xs()
+                            .opacity(0.75)
+                            .child("Titles may use {date}, {time}, {datetime} and {type}, command titles also {command}"),
+                    ),
+            )
+            .child(
+                field().label("Backup").child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("export_settings")
+                                .label("Export…")
+                                .small()
+                                .on_click(cx.listener(|this, _, _, cx| this.export_settings(cx))),
+                        )
+                        .child(
+                            Button::new("import_settings")
+                                .label("Import…")
+                                .small()
+                                .on_click(cx.listener(|this, _, _, cx| this.import_settings(cx))),
+                        ),
+                ),
+            )
+            .when_some(self.status.as_ref(), |form, status| {
+                form.child(match status {
+                    Ok(msg) => Alert::success("settings-status", msg.as_str()).small(),
+                    Err(msg) => Alert::error("settings-status", msg.as_str()).small(),
+                })
+            })
+            .into_any_element()
+    }
+}
+
+impl Render for SettingsWindow {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .relative()
+            .size_full()
+            .text_color(cx.theme().foreground)
+            .bg(black().opacity(0.85))
+            .child(self.title_bar(cx))
+            .child(
+                div()
+                    .h_full()
+                    .flex_shrink()
+                    .overflow_hidden()
+                    .px_2()
+                    .child(v_flex().overflow_y_scrollbar().child(self.form(cx))),
+            )
+            .children(decorations::resize_handles(window))
+            .when(app_lock::is_locked(cx), |view| {
+                view.child(app_lock::lock_overlay(window, cx))
+            })
+            .children(Root::render_dialog_layer(window, cx))
+    }
+}
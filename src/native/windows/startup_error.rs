@@ -0,0 +1,86 @@
+use gpui::{
+    App, AppContext, Bounds, Context, IntoElement, Render, SharedString, Window,
+    WindowBackgroundAppearance, WindowBounds, WindowOptions, div, prelude::*, px, size,
+    transparent_black,
+};
+use gpui_component::{
+    ActiveTheme, Root, Sizable,
+    alert::Alert,
+    black,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    scroll::ScrollableElement,
+    v_flex,
+};
+
+use crate::native::decorations::{self, WindowDragArea};
+
+/// Shown instead of the main window when the app can not start, e.g. a failed
+/// database upgrade, so the reason is not only in the log file.
+pub struct StartupErrorWindow {
+    message: SharedString,
+}
+
+impl StartupErrorWindow {
+    pub fn open(cx: &mut App, message: String) -> anyhow::Result<()> {
+        let bounds = Bounds::centered(None, size(px(420.), px(280.0)), cx);
+
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(300.0), px(200.0))),
+                window_background: WindowBackgroundAppearance::Transparent,
+                titlebar: Some(decorations::titlebar_options("Rustickers")),
+                window_decorations: decorations::window_decorations(),
+                ..Default::default()
+            },
+            |window, cx| {
+                window.on_window_should_close(cx, |_, cx| {
+                    cx.quit();
+                    true
+                });
+                let view = cx.new(|_| StartupErrorWindow {
+                    message: message.into(),
+                });
+                cx.new(|cx| Root::new(view, window, cx).bg(transparent_black().alpha(0.0)))
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Render for StartupErrorWindow {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .p_2()
+            .size_full()
+            .text_color(cx.theme().foreground)
+            .bg(black().opacity(0.85))
+            .child(
+                div()
+                    .text_sm()
+                    .window_drag_area()
+                    .child("Rustickers could not start"),
+            )
+            .child(
+                div().h_full().flex_shrink().overflow_hidden().child(
+                    v_flex()
+                        .overflow_y_scrollbar()
+                        .child(Alert::error("startup-error", self.message.clone()).small()),
+                ),
+            )
+            .child(
+                h_flex().justify_end().child(
+                    Button::new("quit")
+                        .label("Quit")
+                        .small()
+                        .primary()
+                        .on_click(|_, _, cx| cx.quit()),
+                ),
+            )
+            .children(decorations::resize_handles(window))
+            .children(Root::render_dialog_layer(window, cx))
+    }
+}
@@ -1,15 +1,20 @@
 use gpui::{
-    AnyElement, AnyWindowHandle, App, AppContext, AsyncApp, Bounds, Context, IntoElement,
-    MouseButton, Render, SharedString, TitlebarOptions, Window, WindowBackgroundAppearance,
-    WindowBounds, WindowControlArea, WindowOptions, div, prelude::*, px, rgba, size,
-    transparent_black,
+    Animation, AnimationExt, AnyElement, AnyWindowHandle, App, AppContext, AsyncApp, Bounds,
+    Context, Entity, IntoElement, MouseButton, MouseDownEvent, MouseMoveEvent, Pixels, Point,
+    Render, SharedString, Window, WindowBackgroundAppearance, WindowBounds, WindowOptions, div,
+    prelude::*, px, rgba, size, transparent_black, transparent_white,
 };
 use gpui_component::{
-    ActiveTheme, Root, TitleBar,
+    ActiveTheme, Icon, Root, Sizable, WindowExt,
     alert::Alert,
-    button::Button,
-    h_flex,
-    input::{InputEvent, InputState},
+    black,
+    button::{Button, ButtonVariants as _},
+    green_500, h_flex,
+    input::{Input, InputEvent, InputState},
+    menu::{DropdownMenu, PopupMenuItem},
+    red_500,
+    scroll::ScrollableElement,
+    tooltip::Tooltip,
     v_flex,
 };
 use std::{
@@ -17,18 +22,57 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::model::sticker::{StickerColor, StickerDetail, StickerState, StickerType};
+use crate::model::revision::StickerRevision;
+use crate::model::stack::Stack;
+use crate::model::sticker::{
+    StickerColor, StickerDetail, StickerSettings, StickerState, StickerType, normalize_tag,
+};
+use crate::native::app_lock;
+use crate::native::components::stickers::convert::{Conversion, conversions_from};
 use crate::native::components::{
     IconName,
+    rem_size::with_rem_size,
     stickers::{
-        command::CommandSticker, markdown::MarkdownSticker, paint::PaintSticker,
-        timer::TimerSticker, *,
+        agenda::AgendaSticker, animation::AnimationSticker, calculator::CalculatorSticker,
+        chart::ChartSticker, clock::ClockSticker, code::CodeSticker, command::CommandSticker,
+        counter::CounterSticker, currency::CurrencySticker, git::GitSticker, github::GitHubSticker,
+        habit::HabitSticker, http::HttpSticker, image::ImageSticker, kanban::KanbanSticker,
+        launcher::LauncherSticker, markdown::MarkdownSticker, media::MediaSticker,
+        mindmap::MindMapSticker, paint::PaintSticker, palette::PaletteSticker,
+        pomodoro::PomodoroSticker, reminder::ReminderSticker, system::SystemSticker,
+        time_tracker::TimeTrackerSticker, timer::TimerSticker, units::UnitsSticker,
+        uptime::UptimeSticker, web::WebSticker, *,
     },
 };
+use crate::native::decorations::{self, WindowDragArea};
+use crate::native::monitor_layout;
+use crate::native::top_most::{self, PeekRestore};
 use crate::native::windows::StickerWindowEvent;
 use crate::storage::ArcStickerStore;
+use crate::storage::settings::SettingsStore;
+use crate::utils::diff::{self, DiffLine};
 
 const BOUNDS_SAVE_DEBOUNCE: Duration = Duration::from_millis(200);
+const FADE_DURATION: Duration = Duration::from_millis(150);
+/// Matches the corners Windows 11 gives the window.
+const CORNER_RADIUS: Pixels = px(8.0);
+/// Unchanged lines shown around a change in the history, the others are folded.
+const HISTORY_CONTEXT_LINES: usize = 2;
+/// Height of the tabs of a collapsed stack.
+const STACK_TABS_HEIGHT: Pixels = px(28.0);
+/// Characters of a sticker title shown on its tab.
+const STACK_TAB_MAX_CHARS: usize = 16;
+/// Height of a sticker collapsed to its header, the tabs of a collapsed stack come on top.
+const COLLAPSED_HEIGHT: Pixels = px(36.0);
+/// Opacities offered in the view menu.
+const OPACITIES: [f32; 5] = [1.0, 0.9, 0.8, 0.7, 0.5];
+/// Font sizes offered in the view menu, as the rem size of the sticker view.
+const FONT_SIZES: [(&str, Option<f32>); 4] = [
+    ("Default", None),
+    ("Small", Some(12.0)),
+    ("Large", Some(16.0)),
+    ("Larger", Some(18.0)),
+];
 
 static OPEN_STICKERS: RwLock<Vec<(i64, AnyWindowHandle)>> = RwLock::new(Vec::new());
 
@@ -38,10 +82,61 @@ pub struct StickerWindow {
     detail: StickerDetail,
 
     view: Box<dyn StickerView>,
+    settings: StickerSettings,
+    tag_names: Vec<String>,
+    /// Comma separated tags, edited in the tags dialog.
+    tags: Entity<InputState>,
     error: Option<String>,
 
     last_bounds: Option<(i32, i32, i32, i32)>,
     last_bounds_change_at: Option<Instant>,
+    /// Fading out before the window is removed.
+    closing: bool,
+    /// Raised while its card in the main window is hovered.
+    peek: Option<PeekRestore>,
+    /// Earlier versions shown over the sticker, `None` while closed.
+    history: Option<HistoryPanel>,
+    /// The stack of the sticker, see [`Stack`].
+    stack: Option<Stack>,
+    /// Where the stack handle was grabbed, in window coordinates, while it is dragged.
+    stack_drag: Option<Point<Pixels>>,
+}
+
+struct HistoryPanel {
+    /// The saved content, the versions are compared to it.
+    current: String,
+    revisions: Vec<StickerRevision>,
+    selected: usize,
+    /// What restoring the selected version would change.
+    diff: Vec<DiffLine>,
+}
+
+impl HistoryPanel {
+    fn select(&mut self, ix: usize) {
+        let Some(revision) = self.revisions.get(ix) else {
+            return;
+        };
+        self.selected = ix;
+        self.diff = diff::diff_lines(&diff_text(&self.current), &diff_text(&revision.content));
+    }
+}
+
+/// A sticker title cut to fit on a stack tab.
+fn tab_title(title: &str) -> String {
+    match title.char_indices().nth(STACK_TAB_MAX_CHARS) {
+        Some((end, _)) => format!("{}…", title[..end].trim_end()),
+        None => title.to_string(),
+    }
+}
+
+/// JSON contents are compared pretty printed, a field per line.
+fn diff_text(content: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) => {
+            serde_json::to_string_pretty(&value).unwrap_or_else(|_| content.to_string())
+        }
+        _ => content.to_string(),
+    }
 }
 
 impl StickerWindow {
@@ -62,14 +157,55 @@ impl StickerWindow {
             }
         }
 
-        let detail = match store.get_sticker(id).await {
+        let mut detail = match store.get_sticker(id).await {
             Ok(detail) => detail,
             Err(err) => {
                 return Err(anyhow::anyhow!("Failed to open sticker: {err:#}"));
             }
         };
 
+        // Prefer the position remembered for the current monitor setup.
+        let layout = cx.update(|cx| monitor_layout::layout_key(cx))?;
+        match store.get_sticker_layout_bounds(id, layout).await {
+            Ok(Some((left, top, width, height))) => {
+                detail.left = left;
+                detail.top = top;
+                detail.width = width;
+                detail.height = height;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                tracing::warn!(id, error = ?err, "Failed to get sticker layout bounds");
+            }
+        }
+
+        // A collapsed stack shows one sticker at a time, this one takes the place of the
+        // shown one.
+        if !store.is_read_only()
+            && let Ok(Some(stack)) = store.get_sticker_stack(id).await
+            && stack.collapsed
+            && stack.active_id != Some(id)
+        {
+            store.update_stack(stack.id, true, Some(id)).await?;
+            let shown = stack.active_id.and_then(Self::open_handle);
+            if let Some(handle) = shown {
+                let bounds = cx.update(|cx| {
+                    Self::update_window(handle, cx, |this, cx| {
+                        this.close(cx);
+                        let d = &this.detail;
+                        (d.left, d.top, d.width, d.height)
+                    })
+                })?;
+                if let Some((left, top, width, height)) = bounds {
+                    (detail.left, detail.top) = (left, top);
+                    (detail.width, detail.height) = (width, height);
+                }
+            }
+        }
+
         if detail.state != StickerState::Open
+            && !detail.temporary
+            && !store.is_read_only()
             && let Err(err) = store.update_sticker_state(id, StickerState::Open).await
         {
             return Err(anyhow::anyhow!(
@@ -95,6 +231,134 @@ impl StickerWindow {
         false
     }
 
+    fn open_handle(id: i64) -> Option<AnyWindowHandle> {
+        OPEN_STICKERS.read().ok().and_then(|open_stickers| {
+            open_stickers
+                .iter()
+                .find(|(open_id, _)| *open_id == id)
+                .map(|(_, handle)| *handle)
+        })
+    }
+
+    pub fn is_open(id: i64) -> bool {
+        OPEN_STICKERS
+            .read()
+            .is_ok_and(|open_stickers| open_stickers.iter().any(|(open_id, _)| *open_id == id))
+    }
+
+    /// Show a color which was changed outside of the window, e.g. by a bulk recolor.
+    pub fn try_set_color(id: i64, color: StickerColor, cx: &mut App) -> bool {
+        let handle = OPEN_STICKERS.read().ok().and_then(|open_stickers| {
+            open_stickers
+                .iter()
+                .find(|(open_id, _)| *open_id == id)
+                .map(|(_, handle)| *handle)
+        });
+        let Some(handle) = handle else {
+            return false;
+        };
+
+        Self::update_window(handle, cx, |this, cx| {
+            this.detail.color = color;
+            this.view.set_color(cx, color);
+            cx.notify();
+        })
+        .is_some()
+    }
+
+    /// Show content which was saved outside of the window, `None` when it is not open.
+    /// `Some(false)` means the sticker did not take it, e.g. because it is being edited.
+    pub fn try_set_content(id: i64, content: &str, cx: &mut App) -> Option<bool> {
+        let handle = OPEN_STICKERS.read().ok().and_then(|open_stickers| {
+            open_stickers
+                .iter()
+                .find(|(open_id, _)| *open_id == id)
+                .map(|(_, handle)| *handle)
+        })?;
+
+        handle
+            .update(cx, |root, window, cx| {
+                let sticker = root
+                    .downcast::<Root>()
+                    .ok()
+                    .and_then(|root| root.read(cx).view().clone().downcast::<Self>().ok())?;
+                Some(sticker.update(cx, |this, cx| this.view.set_content(content, window, cx)))
+            })
+            .ok()
+            .flatten()
+    }
+
+    /// Raise the window above all others while `peek` is set, so a buried sticker can be
+    /// found from its card, and put it back afterwards.
+    pub fn try_peek(id: i64, peek: bool, cx: &mut App) -> bool {
+        let handle = OPEN_STICKERS.read().ok().and_then(|open_stickers| {
+            open_stickers
+                .iter()
+                .find(|(open_id, _)| *open_id == id)
+                .map(|(_, handle)| *handle)
+        });
+        let Some(handle) = handle else {
+            return false;
+        };
+
+        handle
+            .update(cx, |root, window, cx| {
+                let Some(sticker) = root
+                    .downcast::<Root>()
+                    .ok()
+                    .and_then(|root| root.read(cx).view().clone().downcast::<Self>().ok())
+                else {
+                    return false;
+                };
+                sticker.update(cx, |this, _| match (peek, this.peek.take()) {
+                    (true, None) => this.peek = top_most::start_peek(window),
+                    (true, Some(restore)) => this.peek = Some(restore),
+                    // Opened from the card meanwhile, it stays in front then.
+                    (false, Some(_)) if window.is_window_active() => {
+                        top_most::set_window_top_most(window, false)
+                    }
+                    (false, Some(restore)) => top_most::end_peek(window, restore),
+                    (false, None) => {}
+                });
+                true
+            })
+            .unwrap_or(false)
+    }
+
+    /// Id, type and scheduler state of every open sticker window.
+    pub fn open_window_states(cx: &mut App) -> Vec<(i64, StickerType, Option<String>)> {
+        let handles = match OPEN_STICKERS.read() {
+            Ok(open_stickers) => open_stickers.clone(),
+            Err(_) => return Vec::new(),
+        };
+
+        handles
+            .into_iter()
+            .filter_map(|(id, handle)| {
+                Self::update_window(handle, cx, |this, cx| {
+                    (id, this.detail.sticker_type, this.view.scheduler_state(cx))
+                })
+            })
+            .collect()
+    }
+
+    fn update_window<R>(
+        handle: AnyWindowHandle,
+        cx: &mut App,
+        f: impl FnOnce(&mut Self, &mut Context<Self>) -> R,
+    ) -> Option<R> {
+        handle
+            .update(cx, |root, _, cx| {
+                let sticker = root
+                    .downcast::<Root>()
+                    .ok()
+                    .and_then(|root| root.read(cx).view().clone().downcast::<Self>().ok())?;
+                Some(sticker.update(cx, f))
+            })
+            .ok()
+            .flatten()
+    }
+
     fn open_with_detail(
         cx: &mut App,
         sticker_events_tx: mpsc::Sender<StickerWindowEvent>,
@@ -116,6 +380,31 @@ impl StickerWindow {
             StickerType::Markdown => MarkdownSticker::min_window_size(),
             StickerType::Command => CommandSticker::min_window_size(),
             StickerType::Paint => PaintSticker::min_window_size(),
+            StickerType::Image => ImageSticker::min_window_size(),
+            StickerType::Web => WebSticker::min_window_size(),
+            StickerType::Clock => ClockSticker::min_window_size(),
+            StickerType::Pomodoro => PomodoroSticker::min_window_size(),
+            StickerType::System => SystemSticker::min_window_size(),
+            StickerType::Agenda => AgendaSticker::min_window_size(),
+            StickerType::Habit => HabitSticker::min_window_size(),
+            StickerType::Kanban => KanbanSticker::min_window_size(),
+            StickerType::Code => CodeSticker::min_window_size(),
+            StickerType::Counter => CounterSticker::min_window_size(),
+            StickerType::Chart => ChartSticker::min_window_size(),
+            StickerType::Currency => CurrencySticker::min_window_size(),
+            StickerType::Animation => AnimationSticker::min_window_size(),
+            StickerType::GitHub => GitHubSticker::min_window_size(),
+            StickerType::Reminder => ReminderSticker::min_window_size(),
+            StickerType::Http => HttpSticker::min_window_size(),
+            StickerType::Palette => PaletteSticker::min_window_size(),
+            StickerType::Units => UnitsSticker::min_window_size(),
+            StickerType::Uptime => UptimeSticker::min_window_size(),
+            StickerType::Git => GitSticker::min_window_size(),
+            StickerType::Launcher => LauncherSticker::min_window_size(),
+            StickerType::Media => MediaSticker::min_window_size(),
+            StickerType::Calculator => CalculatorSticker::min_window_size(),
+            StickerType::MindMap => MindMapSticker::min_window_size(),
+            StickerType::TimeTracker => TimeTrackerSticker::min_window_size(),
         };
 
         let current_size = if detail.width > 0 && detail.height > 0 {
@@ -126,6 +415,31 @@ impl StickerWindow {
                 StickerType::Markdown => MarkdownSticker::default_window_size(),
                 StickerType::Command => CommandSticker::default_window_size(),
                 StickerType::Paint => PaintSticker::default_window_size(),
+                StickerType::Image => ImageSticker::default_window_size(),
+                StickerType::Web => WebSticker::default_window_size(),
+                StickerType::Clock => ClockSticker::default_window_size(),
+                StickerType::Pomodoro => PomodoroSticker::default_window_size(),
+                StickerType::System => SystemSticker::default_window_size(),
+                StickerType::Agenda => AgendaSticker::default_window_size(),
+                StickerType::Habit => HabitSticker::default_window_size(),
+                StickerType::Kanban => KanbanSticker::default_window_size(),
+                StickerType::Code => CodeSticker::default_window_size(),
+                StickerType::Counter => CounterSticker::default_window_size(),
+                StickerType::Chart => ChartSticker::default_window_size(),
+                StickerType::Currency => CurrencySticker::default_window_size(),
+                StickerType::Animation => AnimationSticker::default_window_size(),
+                StickerType::GitHub => GitHubSticker::default_window_size(),
+                StickerType::Reminder => ReminderSticker::default_window_size(),
+                StickerType::Http => HttpSticker::default_window_size(),
+                StickerType::Palette => PaletteSticker::default_window_size(),
+                StickerType::Units => UnitsSticker::default_window_size(),
+                StickerType::Uptime => UptimeSticker::default_window_size(),
+                StickerType::Git => GitSticker::default_window_size(),
+                StickerType::Launcher => LauncherSticker::default_window_size(),
+                StickerType::Media => MediaSticker::default_window_size(),
+                StickerType::Calculator => CalculatorSticker::default_window_size(),
+                StickerType::MindMap => MindMapSticker::default_window_size(),
+                StickerType::TimeTracker => TimeTrackerSticker::default_window_size(),
             }
         };
 
@@ -134,19 +448,20 @@ impl StickerWindow {
             gpui::point(px(detail.left as f32), px(detail.top as f32)),
             current_size.map(|x| px(x as f32)),
         );
+        // A collapsed sticker is only as high as its header.
+        let min_size = size(px(min_size.width as f32), COLLAPSED_HEIGHT);
 
         let handle = cx.open_window(
             WindowOptions {
                 window_bounds: Some(WindowBounds::Windowed(bounds)),
-                window_min_size: Some(min_size.map(|x| px(x as f32))),
+                window_min_size: Some(min_size),
                 window_background: WindowBackgroundAppearance::Transparent,
-                titlebar: Some(TitlebarOptions {
-                    title: Some(SharedString::new(detail.title.clone())),
-                    ..TitleBar::title_bar_options()
-                }),
+                titlebar: Some(decorations::titlebar_options(detail.title.clone())),
+                window_decorations: decorations::window_decorations(),
                 ..Default::default()
             },
             |window, cx| {
+                decorations::round_corners(window);
                 let view =
                     cx.new(|cx| StickerWindow::new(detail, store, sticker_events_tx, window, cx));
                 cx.new(|cx| Root::new(view, window, cx).bg(transparent_black().alpha(0.0)))
@@ -174,6 +489,24 @@ impl StickerWindow {
             Self::create_sticker_view(&detail, &store, window, cx, sticker_events_tx.clone());
 
         view.set_color(cx, detail.color);
+        let settings = detail.sticker_settings();
+        view.apply_settings(cx, &settings);
+        if detail.top_most {
+            top_most::set_window_top_most(window, true);
+        }
+
+        let tags = cx.new(|cx| InputState::new(window, cx).placeholder("work, ideas"));
+        let id = detail.id;
+        let tags_store = store.clone();
+        cx.spawn(
+            async move |entity, cx| match tags_store.get_sticker_tags(id).await {
+                Ok(tag_names) => {
+                    let _ = entity.update(cx, |this, _| this.tag_names = tag_names);
+                }
+                Err(err) => tracing::warn!(id, error = ?err, "Failed to load sticker tags"),
+            },
+        )
+        .detach();
 
         cx.subscribe_in(&title, window, |this, input_state, event, _, cx| {
             if let InputEvent::PressEnter { .. } = event {
@@ -195,15 +528,25 @@ impl StickerWindow {
         })
         .detach();
 
-        Self {
+        let mut this = Self {
             store,
             detail,
             sticker_events_tx,
             view,
+            settings,
+            tag_names: Vec::new(),
+            tags,
             last_bounds: None,
             last_bounds_change_at: None,
+            closing: false,
+            peek: None,
+            history: None,
+            stack: None,
+            stack_drag: None,
             error: None,
-        }
+        };
+        this.load_stack(cx);
+        this
     }
 
     fn create_sticker_view(
@@ -252,212 +595,1632 @@ impl StickerWindow {
                     sticker_events_tx.clone(),
                 )
             }))),
-            StickerType::Paint => {
-                Box::new(StickerViewEntity::new(cx.new(|_| {
-                    PaintSticker::new(id, color, store, content, sticker_events_tx.clone())
-                })))
-            }
-        }
-    }
-
-    fn set_error(&mut self, message: impl Into<String>, cx: &mut Context<Self>) {
-        self.error = Some(message.into());
-        cx.notify();
-    }
-
-    fn tick_bounds_state(&mut self, window: &Window, cx: &mut Context<Self>) {
-        let current = self.current_bounds(window);
-
-        let changed = self.last_bounds.map(|prev| prev != current).unwrap_or(true);
-
-        if changed {
-            self.last_bounds = Some(current);
-            self.last_bounds_change_at = Some(Instant::now());
-            window.request_animation_frame();
-            return;
-        }
-
-        if let Some(changed_at) = self.last_bounds_change_at {
-            if changed_at.elapsed() >= BOUNDS_SAVE_DEBOUNCE {
-                self.last_bounds_change_at = None;
-                self.change_bounds(window, cx);
-            } else {
-                window.request_animation_frame();
-            }
-        }
-    }
-
-    fn try_tick(&mut self, window: &Window, cx: &mut Context<Self>) {
-        if window.is_window_hovered() {
-            if self.last_bounds.is_none() {
-                self.last_bounds = Some(self.current_bounds(window));
-            }
-            self.tick_bounds_state(window, cx);
-        }
-    }
-
-    fn current_bounds(&self, window: &Window) -> (i32, i32, i32, i32) {
-        let bounds = window.bounds();
-        (
-            bounds.left().to_f64() as i32,
-            bounds.top().to_f64() as i32,
-            bounds.size.width.to_f64() as i32,
-            bounds.size.height.to_f64() as i32,
-        )
-    }
-
-    fn change_bounds(&mut self, window: &Window, cx: &mut Context<Self>) {
-        let bounds = window.bounds();
-
-        let (left, top, width, height) = (
-            bounds.left().to_f64() as i32,
-            bounds.top().to_f64() as i32,
-            bounds.size.width.to_f64() as i32,
-            bounds.size.height.to_f64() as i32,
-        );
-
-        if left != self.detail.left
-            || top != self.detail.top
-            || width != self.detail.width
-            || height != self.detail.height
-        {
-            let id = self.detail.id;
-            let store = self.store.clone();
-            cx.spawn(async move |this, cx| {
-                if let Err(err) = store
-                    .update_sticker_bounds(id, left, top, width, height)
-                    .await
-                {
-                    let _ = this.update(cx, |this, cx| {
-                        this.set_error(format!("Failed to save window bounds: {err}"), cx);
-                    });
-                } else {
-                    let _ = this.update(cx, |this, _| {
-                        this.detail.left = left;
-                        this.detail.top = top;
-                        this.detail.width = width;
-                        this.detail.height = height;
-                    });
-                }
-            })
-            .detach();
-        }
-    }
-
-    fn change_color(&mut self, theme: StickerColor, cx: &mut Context<Self>) {
-        self.detail.color = theme;
-        self.view.set_color(cx, theme);
-        let id = self.detail.id;
-        let store = self.store.clone();
-        let events = self.sticker_events_tx.clone();
-        cx.spawn(async move |entity, cx| {
-            if let Err(err) = store
-                .update_sticker_color(id, theme.as_str().to_string())
-                .await
-            {
-                let _ = entity.update(cx, |this, cx| {
-                    this.set_error(format!("Failed to save color: {err}"), cx);
-                });
-            } else {
-                let _ = events.send(StickerWindowEvent::ColorChanged { id, color: theme });
-            }
-        })
-        .detach();
-        cx.notify();
-    }
-
-    fn close(&mut self, cx: &mut gpui::App) {
-        if !self.view.save_on_close(cx) {
-            return;
-        }
-
-        let id = self.detail.id;
-        let store = self.store.clone();
-        let events = self.sticker_events_tx.clone();
-
-        cx.spawn(async move |cx| {
-            if let Err(err) = store.update_sticker_state(id, StickerState::Close).await {
-                tracing::error!(id, error = %err, "Error saving state on close");
-            }
-
-            let _ = events.send(StickerWindowEvent::Closed { id });
-
-            let _ = cx.update(|cx| {
-                if !Self::try_close(id, cx) {
-                    // Fallback in case not tracked.
-                }
-            });
-        })
-        .detach();
-    }
-
-    fn header_view(&mut self, cx: &mut Context<Self>) -> AnyElement {
-        h_flex()
-            .absolute()
-            .left_0()
-            .top_0()
-            .right_0()
-            .items_center()
-            .gap_2()
-            .child(div().size_full().cursor_move()) // Drag handle area
-            .child(
-                Button::new("close")
-                    .bg(rgba(0x000000))
-                    .border_0()
-                    .cursor_pointer()
-                    .icon(IconName::Close)
-                    .on_click(cx.listener(|this, _, _, cx| this.close(cx))),
-            )
-            .into_any_element()
-    }
-
-    fn footer_view(&mut self, cx: &mut Context<Self>) -> AnyElement {
-        let color_options = h_flex()
-            .gap_1()
-            .children(StickerColor::ALL.iter().map(|&theme| {
-                div()
-                    .w(px(16.0))
-                    .h(px(16.0))
-                    .bg(theme.swatch())
-                    .rounded_full()
-                    .cursor_pointer()
-                    .on_mouse_down(
-                        MouseButton::Left,
-                        cx.listener(move |this, _, window, cx| {
-                            cx.stop_propagation();
-                            window.prevent_default();
-                            this.change_color(theme, cx);
-                        }),
-                    )
-            }));
-
-        h_flex()
-            .absolute()
-            .justify_end()
-            .bottom_0()
-            .left_0()
-            .right_0()
-            .p_2()
-            .gap_2()
-            .window_control_area(WindowControlArea::Drag)
-            .when(!self.view.disable_color_picker(cx), move |v| {
-                v.child(color_options)
-            })
-            .into_any_element()
-    }
-}
-
-impl Render for StickerWindow {
-    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        self.try_tick(window, cx);
+            StickerType::Paint => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                PaintSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    detail.created_at,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Image => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                ImageSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Web => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                WebSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Clock => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                ClockSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Pomodoro => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                PomodoroSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::System => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                SystemSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Agenda => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                AgendaSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Habit => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                HabitSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Kanban => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                KanbanSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Code => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                CodeSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Counter => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                CounterSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Chart => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                ChartSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Currency => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                CurrencySticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Animation => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                AnimationSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::GitHub => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                GitHubSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Reminder => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                ReminderSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Http => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                HttpSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Palette => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                PaletteSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Units => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                UnitsSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Uptime => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                UptimeSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Git => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                GitSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Launcher => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                LauncherSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Media => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                MediaSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::Calculator => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                CalculatorSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::MindMap => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                MindMapSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+            StickerType::TimeTracker => Box::new(StickerViewEntity::new(cx.new(|cx| {
+                TimeTrackerSticker::new(
+                    id,
+                    color,
+                    store,
+                    content,
+                    window,
+                    cx,
+                    sticker_events_tx.clone(),
+                )
+            }))),
+        }
+    }
+
+    fn set_error(&mut self, message: impl Into<String>, cx: &mut Context<Self>) {
+        self.error = Some(message.into());
+        cx.notify();
+    }
+
+    fn tick_bounds_state(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let current = self.current_bounds(window);
+
+        let changed = self.last_bounds.map(|prev| prev != current).unwrap_or(true);
+
+        if changed {
+            self.last_bounds = Some(current);
+            self.last_bounds_change_at = Some(Instant::now());
+            window.request_animation_frame();
+            return;
+        }
+
+        if let Some(changed_at) = self.last_bounds_change_at {
+            if changed_at.elapsed() >= BOUNDS_SAVE_DEBOUNCE {
+                self.last_bounds_change_at = None;
+                self.change_bounds(window, cx);
+            } else {
+                window.request_animation_frame();
+            }
+        }
+    }
+
+    fn try_tick(&mut self, window: &Window, cx: &mut Context<Self>) {
+        if window.is_window_hovered() {
+            if self.last_bounds.is_none() {
+                self.last_bounds = Some(self.current_bounds(window));
+            }
+            self.tick_bounds_state(window, cx);
+        }
+    }
+
+    fn current_bounds(&self, window: &Window) -> (i32, i32, i32, i32) {
+        let bounds = window.bounds();
+        (
+            bounds.left().to_f64() as i32,
+            bounds.top().to_f64() as i32,
+            bounds.size.width.to_f64() as i32,
+            bounds.size.height.to_f64() as i32,
+        )
+    }
+
+    fn change_bounds(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let bounds = window.bounds();
+
+        let (left, top, width, height) = (
+            bounds.left().to_f64() as i32,
+            bounds.top().to_f64() as i32,
+            bounds.size.width.to_f64() as i32,
+            bounds.size.height.to_f64() as i32,
+        );
+
+        if self.store.is_read_only() {
+            return;
+        }
+
+        if left != self.detail.left
+            || top != self.detail.top
+            || width != self.detail.width
+            || height != self.detail.height
+        {
+            let id = self.detail.id;
+            let store = self.store.clone();
+            let layout = monitor_layout::layout_key(cx);
+            cx.spawn(async move |this, cx| {
+                if let Err(err) = store
+                    .update_sticker_layout_bounds(id, layout, left, top, width, height)
+                    .await
+                {
+                    tracing::warn!(id, error = ?err, "Failed to save sticker layout bounds");
+                }
+
+                if let Err(err) = store
+                    .update_sticker_bounds(id, left, top, width, height)
+                    .await
+                {
+                    let _ = this.update(cx, |this, cx| {
+                        this.set_error(format!("Failed to save window bounds: {err}"), cx);
+                    });
+                } else {
+                    let _ = this.update(cx, |this, _| {
+                        this.detail.left = left;
+                        this.detail.top = top;
+                        this.detail.width = width;
+                        this.detail.height = height;
+                    });
+                }
+            })
+            .detach();
+        }
+    }
+
+    fn change_color(&mut self, theme: StickerColor, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+        self.detail.color = theme;
+        self.view.set_color(cx, theme);
+        let id = self.detail.id;
+        let store = self.store.clone();
+        let events = self.sticker_events_tx.clone();
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store
+                .update_sticker_color(id, theme.as_str().to_string())
+                .await
+            {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to save color: {err}"), cx);
+                });
+            } else {
+                let _ = events.send(StickerWindowEvent::ColorChanged { id, color: theme });
+            }
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn change_settings(&mut self, cx: &mut Context<Self>, f: impl FnOnce(&mut StickerSettings)) {
+        f(&mut self.settings);
+        self.view.apply_settings(cx, &self.settings);
+
+        let id = self.detail.id;
+        let json = self.settings.to_json();
+        self.detail.settings = json.clone();
+        if self.store.is_read_only() {
+            cx.notify();
+            return;
+        }
+
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_settings(id, json).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to save sticker settings: {err}"), cx);
+                });
+            }
+        })
+        .detach();
+        cx.notify();
+    }
+
+    /// Keep the window above all others, remembered for the sticker.
+    fn toggle_top_most(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let top_most = !self.detail.top_most;
+        self.detail.top_most = top_most;
+        top_most::set_window_top_most(window, top_most);
+        cx.notify();
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let id = self.detail.id;
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_top_most(id, top_most).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to save keep on top: {err}"), cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn close(&mut self, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            Self::try_close(self.detail.id, cx);
+            return;
+        }
+        if !self.view.save_on_close(cx) {
+            return;
+        }
+
+        let id = self.detail.id;
+        let temporary = self.detail.temporary;
+        let store = self.store.clone();
+        let events = self.sticker_events_tx.clone();
+
+        let fade = !SettingsStore::get(cx).disable_animations;
+        if fade {
+            self.closing = true;
+            cx.notify();
+        }
+
+        cx.spawn(async move |_, cx| {
+            if fade {
+                cx.background_executor().timer(FADE_DURATION).await;
+            }
+
+            if temporary {
+                if let Err(err) = store.delete_sticker(id).await {
+                    tracing::error!(id, error = %err, "Error deleting temporary sticker on close");
+                }
+            } else if let Err(err) = store.update_sticker_state(id, StickerState::Close).await {
+                tracing::error!(id, error = %err, "Error saving state on close");
+            }
+
+            let _ = events.send(StickerWindowEvent::Closed { id });
+
+            let _ = cx.update(|cx| {
+                if !Self::try_close(id, cx) {
+                    // Fallback in case not tracked.
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn is_collapsed(&self) -> bool {
+        self.settings.expanded_height.is_some()
+    }
+
+    /// Collapse the window to its header, or expand it to the height it had before.
+    fn toggle_collapsed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let width = window.bounds().size.width;
+        match self.settings.expanded_height {
+            Some(height) => {
+                window.resize(size(width, px(height as f32)));
+                self.change_settings(cx, |s| s.expanded_height = None);
+            }
+            None => {
+                let height = window.bounds().size.height.to_f64() as i32;
+                let tabs = match self.stack.as_ref().is_some_and(|stack| stack.collapsed) {
+                    true => STACK_TABS_HEIGHT,
+                    false => px(0.0),
+                };
+                window.resize(size(width, COLLAPSED_HEIGHT + tabs));
+                self.change_settings(cx, |s| s.expanded_height = Some(height));
+                self.load_title(cx);
+            }
+        }
+    }
+
+    /// The title may have been changed by the sticker since the window was opened.
+    fn load_title(&mut self, cx: &mut Context<Self>) {
+        let id = self.detail.id;
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| match store.get_sticker(id).await {
+            Ok(detail) => {
+                let _ = entity.update(cx, |this, cx| {
+                    this.detail.title = detail.title;
+                    cx.notify();
+                });
+            }
+            Err(err) => tracing::warn!(id, error = ?err, "Failed to load sticker title"),
+        })
+        .detach();
+    }
+
+    /// Leave a copy of the sticker where it is, with the content being edited, its tags
+    /// and attachments. Called when a drag begins, the copy stays behind while this one is moved.
+    fn duplicate(&mut self, window: &Window, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let unsaved = self.view.content(cx);
+        let (left, top, width, height) = self.current_bounds(window);
+        let id = self.detail.id;
+        let store = self.store.clone();
+        let events = self.sticker_events_tx.clone();
+        cx.spawn(async move |entity, cx| {
+            let created = async {
+                let mut detail = store.get_sticker(id).await?;
+                let tags = store.get_sticker_tags(id).await?;
+                detail.id = 0;
+                if let Some(content) = unsaved {
+                    detail.content = content;
+                }
+                (detail.left, detail.top) = (left, top);
+                (detail.width, detail.height) = (width, height);
+                (detail.created_at, detail.updated_at) = (0, 0);
+                let copy_id = store.insert_sticker(detail).await?;
+                if !tags.is_empty() {
+                    store.set_sticker_tags(copy_id, tags).await?;
+                }
+                store.copy_attachments(id, copy_id).await?;
+                anyhow::Ok(copy_id)
+            }
+            .await;
+
+            let copy_id = match created {
+                Ok(copy_id) => copy_id,
+                Err(err) => {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.set_error(format!("Failed to duplicate sticker: {err:#}"), cx);
+                    });
+                    return;
+                }
+            };
+            tracing::info!(id, copy_id, "Sticker duplicated");
+            let _ = events.send(StickerWindowEvent::Created { id: copy_id });
+            let _ = events.send(StickerWindowEvent::StoreChanged);
+            if let Err(err) = Self::open_async(cx, events, store, copy_id).await {
+                tracing::error!(copy_id, error = ?err, "Failed to open duplicated sticker");
+            }
+        })
+        .detach();
+    }
+
+    /// Where the window is dragged from, with the mouse shortcuts of the settings.
+    fn drag_area(&self, cx: &mut Context<Self>) -> gpui::Div {
+        let shortcuts = SettingsStore::get(cx).mouse_shortcuts.clone();
+        let collapsed = self.is_collapsed();
+
+        let area = div()
+            .size_full()
+            .cursor_move()
+            .when(shortcuts.middle_click_close, |area| {
+                area.on_mouse_down(
+                    MouseButton::Middle,
+                    cx.listener(|this, _, _, cx| {
+                        cx.stop_propagation();
+                        this.close(cx);
+                    }),
+                )
+            })
+            .when(shortcuts.ctrl_drag_duplicate, |area| {
+                area.on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, event: &MouseDownEvent, window, cx| {
+                        if event.modifiers.secondary() && event.click_count == 1 {
+                            this.duplicate(window, cx);
+                        }
+                    }),
+                )
+            });
+
+        // Expanding stays possible after the shortcut was turned off.
+        if shortcuts.double_click_collapse || collapsed {
+            area.window_drag_area_on_double_click(
+                cx.listener(|this, _: &MouseDownEvent, window, cx| {
+                    this.toggle_collapsed(window, cx)
+                }),
+            )
+        } else {
+            area.window_drag_area()
+        }
+    }
+
+    /// The title in place of the content while the sticker is collapsed.
+    fn collapsed_view(&self, cx: &mut Context<Self>) -> AnyElement {
+        let title = match self.detail.title.trim() {
+            "" => "...".to_string(),
+            title => title.to_string(),
+        };
+
+        h_flex()
+            .relative()
+            .flex_shrink_0()
+            .h(COLLAPSED_HEIGHT)
+            .px_3()
+            .items_center()
+            .bg(self.detail.color.bg())
+            .child(div().text_sm().truncate().child(title))
+            .child(
+                div()
+                    .absolute()
+                    .left_0()
+                    .top_0()
+                    .right_0()
+                    .bottom_0()
+                    .child(self.drag_area(cx)),
+            )
+            .into_any_element()
+    }
+
+    fn print(&mut self, cx: &mut Context<Self>) {
+        let Some(body) = self.view.print_html(cx) else {
+            return;
+        };
+        let title = self.detail.title.clone();
+
+        cx.spawn(async move |entity, cx| {
+            let printed = cx
+                .background_executor()
+                .spawn(async move { crate::utils::print::print_html(&title, &body) })
+                .await;
+            if let Err(err) = printed {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to print: {err:#}"), cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Turn the sticker into another type: its content is converted and stored with the
+    /// new type, then the window is opened again with the same id, color and bounds.
+    /// Convert the sticker, asking first when lines of it would be left out.
+    fn convert(
+        &mut self,
+        conversion: &'static Conversion,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.store.is_read_only() {
+            return;
+        }
+        let Some(content) = self.view.content(cx) else {
+            return;
+        };
+        let dropped = (conversion.dropped_lines)(&content);
+        if dropped == 0 {
+            self.save_conversion(conversion, content, cx);
+            return;
+        }
+
+        let entity = cx.entity();
+        let message = format!(
+            "{dropped} {} can not be part of the {} and will be left out. The current text stays in the history.",
+            if dropped == 1 { "line" } else { "lines" },
+            conversion.to.label().to_lowercase()
+        );
+        window.open_dialog(cx, move |dialog, _, _| {
+            let entity = entity.clone();
+            let content = content.clone();
+            dialog
+                .confirm()
+                .title(format!("Convert to {}?", conversion.to.label()))
+                .child(div().child(message.clone()))
+                .w(px(280.0))
+                .text_sm()
+                .on_cancel(|_, _, _| true)
+                .on_ok(move |_, _, cx| {
+                    let content = content.clone();
+                    entity.update(cx, |this, cx| this.save_conversion(conversion, content, cx));
+                    true
+                })
+        });
+    }
+
+    fn save_conversion(
+        &mut self,
+        conversion: &'static Conversion,
+        content: String,
+        cx: &mut Context<Self>,
+    ) {
+        let content = match (conversion.convert)(&content) {
+            Ok(content) => content,
+            Err(err) => {
+                self.set_error(
+                    format!(
+                        "Failed to convert to {}: {err:#}",
+                        conversion.to.label().to_lowercase()
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+
+        let id = self.detail.id;
+        let store = self.store.clone();
+        let events = self.sticker_events_tx.clone();
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_sticker_type(id, conversion.to, content).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to save converted sticker: {err:#}"), cx);
+                });
+                return;
+            }
+            let _ = events.send(StickerWindowEvent::StoreChanged);
+
+            let _ = cx.update(|cx| Self::try_close(id, cx));
+            if let Err(err) = Self::open_async(cx, events, store, id).await {
+                tracing::error!(id, error = ?err, "Failed to open converted sticker");
+            }
+        })
+        .detach();
+    }
+
+    fn load_stack(&mut self, cx: &mut Context<Self>) {
+        let id = self.detail.id;
+        let store = self.store.clone();
+        cx.spawn(
+            async move |entity, cx| match store.get_sticker_stack(id).await {
+                Ok(stack) => {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.stack = stack;
+                        cx.notify();
+                    });
+                }
+                Err(err) => tracing::warn!(id, error = ?err, "Failed to load sticker stack"),
+            },
+        )
+        .detach();
+    }
+
+    /// Load the stack again in the open windows of these stickers, never the calling one.
+    fn reload_stack_windows(ids: Vec<i64>, cx: &mut App) {
+        for handle in ids.into_iter().filter_map(Self::open_handle) {
+            Self::update_window(handle, cx, |this, cx| this.load_stack(cx));
+        }
+    }
+
+    /// Other stickers of the stack, empty when not stacked.
+    fn stack_others(&self) -> Vec<i64> {
+        self.stack
+            .iter()
+            .flat_map(|stack| stack.members.iter().map(|x| x.id))
+            .filter(|x| *x != self.detail.id)
+            .collect()
+    }
+
+    /// Titles of the open stickers which could join the stack of this one.
+    fn stack_candidates(except: &[i64], cx: &mut App) -> Vec<(i64, String)> {
+        let handles = match OPEN_STICKERS.read() {
+            Ok(open_stickers) => open_stickers.clone(),
+            Err(_) => return Vec::new(),
+        };
+        handles
+            .into_iter()
+            .filter(|(id, _)| !except.contains(id))
+            .filter_map(|(id, handle)| {
+                Self::update_window(handle, cx, |this, _| (id, this.detail.title.clone()))
+            })
+            .collect()
+    }
+
+    fn stack_with(&mut self, other_id: i64, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let id = self.detail.id;
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let stacked = async {
+                store.stack_stickers(id, other_id).await?;
+                store.get_sticker_stack(id).await
+            }
+            .await;
+            let _ = entity.update(cx, |this, cx| match stacked {
+                Ok(stack) => {
+                    this.stack = stack;
+                    Self::reload_stack_windows(this.stack_others(), cx);
+                    cx.notify();
+                }
+                Err(err) => this.set_error(format!("Failed to stack stickers: {err:#}"), cx),
+            });
+        })
+        .detach();
+    }
+
+    fn leave_stack(&mut self, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let id = self.detail.id;
+        let others = self.stack_others();
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let left = store.unstack_sticker(id).await;
+            let _ = entity.update(cx, |this, cx| match left {
+                Ok(()) => {
+                    this.stack = None;
+                    Self::reload_stack_windows(others, cx);
+                    cx.notify();
+                }
+                Err(err) => this.set_error(format!("Failed to leave stack: {err:#}"), cx),
+            });
+        })
+        .detach();
+    }
+
+    /// Collapse the stack into this window, which gets a tab per sticker, or open the
+    /// windows of all of them again.
+    fn set_stack_collapsed(&mut self, collapsed: bool, cx: &mut Context<Self>) {
+        let Some(stack) = self.stack.clone() else {
+            return;
+        };
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let id = self.detail.id;
+        let others = self.stack_others();
+        let store = self.store.clone();
+        let events = self.sticker_events_tx.clone();
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.update_stack(stack.id, collapsed, Some(id)).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to save stack: {err:#}"), cx);
+                });
+                return;
+            }
+
+            if collapsed {
+                let _ = cx.update(|cx| {
+                    for handle in others.iter().copied().filter_map(Self::open_handle) {
+                        Self::update_window(handle, cx, |this, cx| this.close(cx));
+                    }
+                });
+            }
+            let _ = entity.update(cx, |this, cx| this.load_stack(cx));
+            if !collapsed {
+                for other in others {
+                    if let Err(err) =
+                        Self::open_async(cx, events.clone(), store.clone(), other).await
+                    {
+                        tracing::warn!(id = other, error = ?err, "Failed to open stacked sticker");
+                    }
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Show another sticker of the collapsed stack in place of this one.
+    fn switch_tab(&mut self, other_id: i64, window: &Window, cx: &mut Context<Self>) {
+        let Some(stack) = self.stack.clone() else {
+            return;
+        };
+        if other_id == self.detail.id || self.store.is_read_only() {
+            return;
+        }
+
+        let (left, top, width, height) = self.current_bounds(window);
+        let layout = monitor_layout::layout_key(cx);
+        let store = self.store.clone();
+        let events = self.sticker_events_tx.clone();
+        cx.spawn(async move |entity, cx| {
+            let moved = async {
+                store
+                    .update_sticker_layout_bounds(other_id, layout, left, top, width, height)
+                    .await?;
+                store
+                    .update_sticker_bounds(other_id, left, top, width, height)
+                    .await?;
+                store.update_stack(stack.id, true, Some(other_id)).await
+            }
+            .await;
+            if let Err(err) = moved {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to switch tab: {err:#}"), cx);
+                });
+                return;
+            }
+
+            match Self::open_async(cx, events, store, other_id).await {
+                Ok(()) => {
+                    let _ = entity.update(cx, |this, cx| this.close(cx));
+                }
+                Err(err) => {
+                    let _ = entity.update(cx, |this, cx| {
+                        this.set_error(format!("Failed to switch tab: {err:#}"), cx);
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Follow the mouse while the stack handle is dragged, the other open windows of the
+    /// stack move by as much.
+    fn drag_stack(&mut self, event: &MouseMoveEvent, window: &Window, cx: &mut Context<Self>) {
+        let Some(start) = self.stack_drag else {
+            return;
+        };
+        if event.pressed_button != Some(MouseButton::Left) {
+            self.end_stack_drag(window, cx);
+            return;
+        }
+
+        let delta = event.position - start;
+        let (dx, dy) = (delta.x.to_f64() as i32, delta.y.to_f64() as i32);
+        if dx == 0 && dy == 0 {
+            return;
+        }
+
+        let (left, top, _, _) = self.current_bounds(window);
+        top_most::move_window(window, left + dx, top + dy);
+        for handle in self
+            .stack_others()
+            .into_iter()
+            .filter_map(Self::open_handle)
+        {
+            let _ = handle.update(cx, |_, window, _| {
+                let bounds = window.bounds();
+                top_most::move_window(
+                    window,
+                    bounds.left().to_f64() as i32 + dx,
+                    bounds.top().to_f64() as i32 + dy,
+                );
+            });
+        }
+    }
+
+    /// Save where the windows of the stack were dragged to.
+    fn end_stack_drag(&mut self, window: &Window, cx: &mut Context<Self>) {
+        if self.stack_drag.take().is_none() {
+            return;
+        }
+
+        self.change_bounds(window, cx);
+        for handle in self
+            .stack_others()
+            .into_iter()
+            .filter_map(Self::open_handle)
+        {
+            let _ = handle.update(cx, |root, window, cx| {
+                let sticker = root
+                    .downcast::<Root>()
+                    .ok()
+                    .and_then(|root| root.read(cx).view().clone().downcast::<Self>().ok());
+                if let Some(sticker) = sticker {
+                    sticker.update(cx, |this, cx| this.change_bounds(window, cx));
+                }
+            });
+        }
+    }
+
+    fn stack_button(&self, cx: &mut Context<Self>) -> AnyElement {
+        let entity = cx.entity();
+        let id = self.detail.id;
+        let stack = self.stack.clone();
+
+        Button::new("stack")
+            .bg(rgba(0x000000))
+            .border_0()
+            .cursor_pointer()
+            .icon(IconName::Layers)
+            .tooltip("Stack")
+            .dropdown_menu(move |menu, window, cx| {
+                let mut except = stack
+                    .iter()
+                    .flat_map(|stack| stack.members.iter().map(|x| x.id))
+                    .collect::<Vec<_>>();
+                except.push(id);
+                let candidates = Self::stack_candidates(&except, cx);
+
+                let menu = if candidates.is_empty() {
+                    menu.item(
+                        PopupMenuItem::new("Open another sticker to stack with it").disabled(true),
+                    )
+                } else {
+                    candidates
+                        .into_iter()
+                        .fold(menu, |menu, (other_id, title)| {
+                            let title = if title.is_empty() { "..." } else { &title };
+                            menu.item(
+                                PopupMenuItem::new(format!("Stack with {}", tab_title(title)))
+                                    .on_click(
+                                        window.listener_for(&entity, move |this, _, _, cx| {
+                                            this.stack_with(other_id, cx)
+                                        }),
+                                    ),
+                            )
+                        })
+                };
+
+                let Some(stack) = &stack else {
+                    return menu;
+                };
+                let collapsed = stack.collapsed;
+                menu.separator()
+                    .item(
+                        PopupMenuItem::new(if collapsed {
+                            "Expand stack"
+                        } else {
+                            "Collapse into tabs"
+                        })
+                        .on_click(
+                            window.listener_for(&entity, move |this, _, _, cx| {
+                                this.set_stack_collapsed(!collapsed, cx)
+                            }),
+                        ),
+                    )
+                    .item(PopupMenuItem::new("Leave stack").on_click(
+                        window.listener_for(&entity, |this, _, _, cx| this.leave_stack(cx)),
+                    ))
+            })
+            .into_any_element()
+    }
+
+    /// A tab per sticker of the collapsed stack, always shown above the sticker.
+    fn stack_tabs_view(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let stack = self.stack.as_ref().filter(|stack| stack.collapsed)?;
+        let id = self.detail.id;
+
+        let tabs = stack.members.iter().map(|member| {
+            let member_id = member.id;
+            let active = member_id == id;
+            let title = if member.title.is_empty() {
+                "...".to_string()
+            } else {
+                tab_title(&member.title)
+            };
+            div()
+                .id(("stack-tab", member_id as u64))
+                .px_2()
+                .py_0p5()
+                .rounded_sm()
+                .text_xs()
+                .whitespace_nowrap()
+                .cursor_pointer()
+                .opacity(if active { 1.0 } else { 0.6 })
+                .when(active, |tab| tab.bg(black().opacity(0.25)))
+                .hover(|tab| tab.opacity(1.0))
+                .child(title)
+                .on_click(
+                    cx.listener(move |this, _, window, cx| this.switch_tab(member_id, window, cx)),
+                )
+        });
+
+        Some(
+            h_flex()
+                .flex_shrink_0()
+                .h(STACK_TABS_HEIGHT)
+                .px_1()
+                .gap_1()
+                .items_center()
+                .overflow_hidden()
+                .children(tabs)
+                .child(div().h_full().flex_1().window_drag_area())
+                .into_any_element(),
+        )
+    }
+
+    fn open_tags_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+
+        let value = self.tag_names.join(", ");
+        self.tags
+            .update(cx, |input, cx| input.set_value(value, window, cx));
+
+        let entity = cx.entity();
+        let tags = self.tags.clone();
+        window.open_dialog(cx, move |dialog, _, _| {
+            let entity = entity.clone();
+            dialog
+                .confirm()
+                .title("Tags")
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(Input::new(&tags).small())
+                        .child(div().text_xs().opacity(0.75).child("Separated by commas")),
+                )
+                .w(px(240.0))
+                .text_sm()
+                .on_cancel(|_, _, _| true)
+                .on_ok(move |_, _, cx| {
+                    entity.update(cx, |this, cx| this.save_tags(cx));
+                    true
+                })
+        });
+    }
+
+    fn save_tags(&mut self, cx: &mut Context<Self>) {
+        let tags = self
+            .tags
+            .read(cx)
+            .value()
+            .split(',')
+            .filter_map(normalize_tag)
+            .collect::<Vec<_>>();
+        self.tag_names = tags.clone();
+
+        let id = self.detail.id;
+        let store = self.store.clone();
+        let events = self.sticker_events_tx.clone();
+        cx.spawn(async move |entity, cx| {
+            if let Err(err) = store.set_sticker_tags(id, tags).await {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to save tags: {err:#}"), cx);
+                });
+                return;
+            }
+            let _ = events.send(StickerWindowEvent::StoreChanged);
+        })
+        .detach();
+    }
+
+    fn open_history(&mut self, cx: &mut Context<Self>) {
+        let id = self.detail.id;
+        let store = self.store.clone();
+        cx.spawn(async move |entity, cx| {
+            let loaded = async {
+                let current = store.get_sticker(id).await?.content;
+                let revisions = store.get_sticker_revisions(id).await?;
+                anyhow::Ok((current, revisions))
+            }
+            .await;
+
+            let _ = entity.update(cx, |this, cx| match loaded {
+                Ok((current, revisions)) => {
+                    let mut history = HistoryPanel {
+                        current,
+                        revisions,
+                        selected: 0,
+                        diff: Vec::new(),
+                    };
+                    history.select(0);
+                    this.history = Some(history);
+                    cx.notify();
+                }
+                Err(err) => this.set_error(format!("Failed to load history: {err:#}"), cx),
+            });
+        })
+        .detach();
+    }
+
+    /// Save the selected version as the content, the replaced one goes to the history
+    /// too, then open the window again to show it.
+    fn restore_revision(&mut self, cx: &mut Context<Self>) {
+        if self.store.is_read_only() {
+            return;
+        }
+        let Some((sticker_type, content)) = self
+            .history
+            .as_ref()
+            .and_then(|history| history.revisions.get(history.selected))
+            .map(|revision| (revision.sticker_type, revision.content.clone()))
+        else {
+            return;
+        };
+
+        let id = self.detail.id;
+        // A version from before a conversion turns the sticker back into its type.
+        let converted = sticker_type != self.detail.sticker_type;
+        let store = self.store.clone();
+        let events = self.sticker_events_tx.clone();
+        cx.spawn(async move |entity, cx| {
+            let restored = match converted {
+                true => store.update_sticker_type(id, sticker_type, content).await,
+                false => store.update_sticker_content(id, content).await,
+            };
+            if let Err(err) = restored {
+                let _ = entity.update(cx, |this, cx| {
+                    this.set_error(format!("Failed to restore version: {err:#}"), cx);
+                });
+                return;
+            }
+            let _ = events.send(StickerWindowEvent::StoreChanged);
+
+            let _ = cx.update(|cx| Self::try_close(id, cx));
+            if let Err(err) = Self::open_async(cx, events, store, id).await {
+                tracing::error!(id, error = ?err, "Failed to open restored sticker");
+            }
+        })
+        .detach();
+    }
+
+    fn history_view(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let history = self.history.as_ref()?;
+        let sticker_type = self.detail.sticker_type;
+
+        let revisions = history
+            .revisions
+            .iter()
+            .enumerate()
+            .map(|(ix, revision)| {
+                div()
+                    .id(("revision", ix))
+                    .px_1()
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .when(ix == history.selected, |v| v.bg(rgba(0xffffff33)))
+                    .child(format!(
+                        "{}, {} chars{}",
+                        crate::utils::time::format_unix_millis(revision.created_at),
+                        revision.content.chars().count(),
+                        match revision.sticker_type == sticker_type {
+                            true => String::new(),
+                            false => format!(", {}", revision.sticker_type.label()),
+                        }
+                    ))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        if let Some(history) = &mut this.history {
+                            history.select(ix);
+                        }
+                        cx.notify();
+                    }))
+            })
+            .collect::<Vec<_>>();
+
+        let near_change = |ix: usize| {
+            let from = ix.saturating_sub(HISTORY_CONTEXT_LINES);
+            let to = (ix + HISTORY_CONTEXT_LINES + 1).min(history.diff.len());
+            history.diff[from..to]
+                .iter()
+                .any(|x| !matches!(x, DiffLine::Same(_)))
+        };
+        let mut lines = Vec::new();
+        let mut folded = false;
+        for (ix, line) in history.diff.iter().enumerate() {
+            if !near_change(ix) {
+                if !folded {
+                    lines.push(div().opacity(0.5).child("…"));
+                    folded = true;
+                }
+                continue;
+            }
+            folded = false;
+            lines.push(match line {
+                DiffLine::Same(text) => div().opacity(0.75).child(format!("  {text}")),
+                DiffLine::Added(text) => div()
+                    .bg(green_500().opacity(0.25))
+                    .child(format!("+ {text}")),
+                DiffLine::Removed(text) => {
+                    div().bg(red_500().opacity(0.25)).child(format!("- {text}"))
+                }
+            });
+        }
+        let unchanged = !history.diff.iter().any(|x| !matches!(x, DiffLine::Same(_)));
+
+        let body = if history.revisions.is_empty() {
+            div()
+                .opacity(0.75)
+                .child("No earlier versions yet, they are kept from the next save on")
+                .into_any_element()
+        } else {
+            v_flex()
+                .flex_1()
+                .gap_1()
+                .overflow_hidden()
+                .child(
+                    div()
+                        .max_h(px(96.0))
+                        .flex_shrink_0()
+                        .overflow_hidden()
+                        .child(v_flex().overflow_y_scrollbar().children(revisions)),
+                )
+                .child(div().opacity(0.75).child(match unchanged {
+                    true => "Same as now",
+                    false => "Restoring it: + comes back, - goes away",
+                }))
+                .child(
+                    div().flex_1().overflow_hidden().child(
+                        v_flex()
+                            .overflow_y_scrollbar()
+                            .font_family(cx.theme().mono_font_family.clone())
+                            .children(lines),
+                    ),
+                )
+                .into_any_element()
+        };
+
+        Some(
+            v_flex()
+                .absolute()
+                .top_0()
+                .left_0()
+                .right_0()
+                .bottom_0()
+                .p_2()
+                .gap_1()
+                .bg(black().opacity(0.9))
+                .text_xs()
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .items_center()
+                        .child(div().flex_1().text_sm().window_drag_area().child("History"))
+                        .when(
+                            !self.store.is_read_only() && !history.revisions.is_empty(),
+                            |v| {
+                                v.child(
+                                    Button::new("restore_revision")
+                                        .label("Restore")
+                                        .xsmall()
+                                        .primary()
+                                        .on_click(
+                                            cx.listener(|this, _, _, cx| this.restore_revision(cx)),
+                                        ),
+                                )
+                            },
+                        )
+                        .child(
+                            Button::new("close_history")
+                                .icon(IconName::Close)
+                                .bg(transparent_white())
+                                .border_0()
+                                .xsmall()
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.history = None;
+                                    cx.notify();
+                                })),
+                        ),
+                )
+                .child(body)
+                .into_any_element(),
+        )
+    }
+
+    fn header_view(&mut self, cx: &mut Context<Self>) -> AnyElement {
+        let conversions = if self.store.is_read_only() {
+            Vec::new()
+        } else {
+            conversions_from(self.detail.sticker_type).collect::<Vec<_>>()
+        };
+        let entity = cx.entity();
+        let stack_collapsed = self.stack.as_ref().map(|stack| stack.collapsed);
+
+        h_flex()
+            .absolute()
+            .left_0()
+            .top(match stack_collapsed {
+                Some(true) => STACK_TABS_HEIGHT,
+                _ => px(0.0),
+            })
+            .right_0()
+            .items_center()
+            .gap_2()
+            .when(stack_collapsed == Some(false), |v| {
+                v.child(
+                    div()
+                        .id("stack-handle")
+                        .pl_2()
+                        .cursor_move()
+                        .child(Icon::new(IconName::Move).small())
+                        .tooltip(|window, cx| Tooltip::new("Move the stack").build(window, cx))
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, event: &gpui::MouseDownEvent, _, cx| {
+                                cx.stop_propagation();
+                                this.stack_drag = Some(event.position);
+                            }),
+                        ),
+                )
+            })
+            .child(self.drag_area(cx))
+            .when(!conversions.is_empty(), |v| {
+                v.child(
+                    Button::new("convert")
+                        .bg(rgba(0x000000))
+                        .border_0()
+                        .cursor_pointer()
+                        .icon(IconName::ArrowRightLeft)
+                        .tooltip("Convert to...")
+                        .dropdown_menu(move |menu, window, _| {
+                            conversions.iter().fold(menu, |menu, &conversion| {
+                                menu.item(
+                                    PopupMenuItem::new(format!(
+                                        "Convert to {}",
+                                        conversion.to.label()
+                                    ))
+                                    .on_click(
+                                        window.listener_for(&entity, move |this, _, window, cx| {
+                                            this.convert(conversion, window, cx)
+                                        }),
+                                    ),
+                                )
+                            })
+                        }),
+                )
+            })
+            .when(!self.store.is_read_only(), |v| {
+                v.child(self.stack_button(cx))
+                    .child(
+                        Button::new("tags")
+                            .bg(rgba(0x000000))
+                            .border_0()
+                            .cursor_pointer()
+                            .icon(IconName::Hash)
+                            .tooltip("Tags")
+                            .on_click(
+                                cx.listener(|this, _, window, cx| {
+                                    this.open_tags_dialog(window, cx)
+                                }),
+                            ),
+                    )
+                    .child(
+                        Button::new("history")
+                            .bg(rgba(0x000000))
+                            .border_0()
+                            .cursor_pointer()
+                            .icon(IconName::History)
+                            .tooltip("History")
+                            .on_click(cx.listener(|this, _, _, cx| this.open_history(cx))),
+                    )
+            })
+            .child(self.view_button(cx))
+            .when(self.view.can_print(cx), |v| {
+                v.child(
+                    Button::new("print")
+                        .bg(rgba(0x000000))
+                        .border_0()
+                        .cursor_pointer()
+                        .icon(IconName::Printer)
+                        .tooltip("Print")
+                        .on_click(cx.listener(|this, _, _, cx| this.print(cx))),
+                )
+            })
+            .child(
+                Button::new("close")
+                    .bg(rgba(0x000000))
+                    .border_0()
+                    .cursor_pointer()
+                    .icon(IconName::Close)
+                    .on_click(cx.listener(|this, _, _, cx| this.close(cx))),
+            )
+            .into_any_element()
+    }
+
+    /// Keep on top, opacity and font size of the window.
+    fn view_button(&self, cx: &mut Context<Self>) -> AnyElement {
+        let entity = cx.entity();
+        let top_most = self.detail.top_most;
+        let opacity = self.settings.opacity();
+        let rem_size = self.settings.rem_size();
+
+        Button::new("view")
+            .bg(rgba(0x000000))
+            .border_0()
+            .cursor_pointer()
+            .icon(IconName::Adjustments)
+            .tooltip("View")
+            .dropdown_menu(move |menu, window, _| {
+                let menu = menu
+                    .item(
+                        PopupMenuItem::new("Keep on top")
+                            .checked(top_most)
+                            .on_click(window.listener_for(&entity, |this, _, window, cx| {
+                                this.toggle_top_most(window, cx)
+                            })),
+                    )
+                    .separator();
+                let menu = OPACITIES
+                    .iter()
+                    .fold(menu, |menu, &value| {
+                        let label = format!("Opacity {}%", (value * 100.0).round() as i32);
+                        menu.item(
+                            PopupMenuItem::new(label)
+                                .checked((opacity - value).abs() < 0.01)
+                                .on_click(window.listener_for(&entity, move |this, _, _, cx| {
+                                    this.change_settings(cx, |s| {
+                                        s.opacity = (value < 1.0).then_some(value)
+                                    });
+                                })),
+                        )
+                    })
+                    .separator();
+                FONT_SIZES.iter().fold(menu, |menu, &(label, value)| {
+                    menu.item(
+                        PopupMenuItem::new(format!("Font size: {label}"))
+                            .checked(rem_size == value)
+                            .on_click(window.listener_for(&entity, move |this, _, _, cx| {
+                                this.change_settings(cx, |s| s.rem_size = value);
+                            })),
+                    )
+                })
+            })
+            .into_any_element()
+    }
+
+    fn footer_view(&mut self, cx: &mut Context<Self>) -> AnyElement {
+        let color_options = h_flex()
+            .gap_1()
+            .children(StickerColor::ALL.iter().map(|&theme| {
+                let tooltip: SharedString = SettingsStore::get(cx).color_display_name(theme).into();
+                div()
+                    .id(SharedString::from(format!("color-{}", theme.as_str())))
+                    .w(px(16.0))
+                    .h(px(16.0))
+                    .bg(theme.swatch())
+                    .rounded_full()
+                    .cursor_pointer()
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _, window, cx| {
+                            cx.stop_propagation();
+                            window.prevent_default();
+                            this.change_color(theme, cx);
+                        }),
+                    )
+                    .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+            }));
+
+        let text_options = h_flex()
+            .gap_1()
+            .child(
+                Button::new("no_wrap")
+                    .label("No wrap")
+                    .xsmall()
+                    .when(self.settings.no_wrap, |v| v.primary())
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.change_settings(cx, |s| s.no_wrap = !s.no_wrap);
+                    })),
+            )
+            .child(
+                Button::new("monospace")
+                    .label("Mono")
+                    .xsmall()
+                    .when(self.settings.monospace, |v| v.primary())
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.change_settings(cx, |s| s.monospace = !s.monospace);
+                    })),
+            );
+
+        h_flex()
+            .absolute()
+            .justify_end()
+            .bottom_0()
+            .left_0()
+            .right_0()
+            .p_2()
+            .gap_2()
+            .window_drag_area()
+            .when(self.view.has_text_output(cx), move |v| {
+                v.child(text_options)
+            })
+            .when(!self.view.disable_color_picker(cx), move |v| {
+                v.child(color_options)
+            })
+            .into_any_element()
+    }
+}
+
+impl Render for StickerWindow {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.try_tick(window, cx);
 
         window.set_rem_size(cx.theme().font_size);
 
-        v_flex()
+        let locked = app_lock::is_locked(cx);
+        let show_chrome = window.is_window_hovered()
+            && !self.view.hide_window_chrome(cx)
+            && !locked
+            && self.history.is_none();
+
+        let body = v_flex()
             .text_color(cx.theme().foreground)
             .font_family(cx.theme().font_family.clone())
             .relative()
             .size_full()
+            .rounded(CORNER_RADIUS)
+            .overflow_hidden()
             .on_mouse_down(MouseButton::Left, |_, window, _| {
                 if !window.is_window_active() {
                     window.activate_window();
@@ -466,9 +2229,23 @@ impl Render for StickerWindow {
             .on_mouse_up(
                 MouseButton::Left,
                 cx.listener(|this, _, window, cx| {
+                    this.end_stack_drag(window, cx);
                     this.change_bounds(window, cx);
                 }),
             )
+            .on_mouse_up_out(
+                MouseButton::Left,
+                cx.listener(|this, _, window, cx| this.end_stack_drag(window, cx)),
+            )
+            .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, window, cx| {
+                this.drag_stack(event, window, cx);
+            }))
+            .children(self.stack_tabs_view(cx))
+            .when(self.store.is_read_only(), |view| {
+                view.child(div().p_1().child(
+                    Alert::warning("sticker-read-only", "Read-only, changes are not saved").small(),
+                ))
+            })
             .when_some(self.error.as_ref(), |view, msg| {
                 view.child(
                     div()
@@ -476,12 +2253,41 @@ impl Render for StickerWindow {
                         .child(Alert::error("sticker-error", msg.as_str())),
                 )
             })
-            .child(self.view.element())
-            .when(window.is_window_hovered(), |view| {
-                view.child(self.header_view(cx))
+            .map(|view| match self.is_collapsed() {
+                true => view.child(self.collapsed_view(cx)),
+                false => view.child(with_rem_size(
+                    self.settings.rem_size().map(px),
+                    self.view.element(),
+                )),
             })
-            .when(window.is_window_hovered(), |view| {
+            .children(self.history_view(cx))
+            .when(show_chrome, |view| view.child(self.header_view(cx)))
+            .when(show_chrome && !self.is_collapsed(), |view| {
                 view.child(self.footer_view(cx))
             })
+            .children(decorations::resize_grips(window, show_chrome))
+            .when(locked, |view| {
+                view.child(app_lock::lock_overlay(window, cx))
+            })
+            .children(Root::render_dialog_layer(window, cx));
+
+        let opacity = self.settings.opacity();
+        if SettingsStore::get(cx).disable_animations {
+            body.opacity(opacity).into_any_element()
+        } else if self.closing {
+            body.with_animation(
+                "sticker-fade-out",
+                Animation::new(FADE_DURATION),
+                move |v, delta| v.opacity((1.0 - delta) * opacity),
+            )
+            .into_any_element()
+        } else {
+            body.with_animation(
+                "sticker-fade-in",
+                Animation::new(FADE_DURATION),
+                move |v, delta| v.opacity(delta * opacity),
+            )
+            .into_any_element()
+        }
     }
 }
@@ -1,20 +1,45 @@
 pub mod paths;
+pub mod settings;
 pub mod sqlite;
+pub mod templates;
 
-use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::model::attachment::{Attachment, AttachmentCollection};
+use crate::model::collection::Collection;
+use crate::model::command::CommandRun;
+use crate::model::focus::FocusSession;
+use crate::model::revision::StickerRevision;
+use crate::model::stack::Stack;
 use crate::model::sticker::*;
+use crate::model::sync::SyncedFile;
+use crate::model::time_entry::TimeEntry;
+use crate::storage::paths::AppPaths;
 
 #[allow(dead_code)]
 #[async_trait::async_trait]
 pub trait StickerStore: Send + Sync {
+    /// `created_at` and `updated_at` of 0 mean now, others are kept, e.g. of imported notes.
     async fn insert_sticker(&self, sticker: StickerDetail) -> anyhow::Result<i64>;
     async fn delete_sticker(&self, id: i64) -> anyhow::Result<()>;
+    /// Archive, trash or restore the sticker.
+    async fn move_sticker(&self, id: i64, bucket: StickerBucket) -> anyhow::Result<()>;
+    /// Move the sticker out of the archive or trash back to the active ones.
+    async fn restore_sticker(&self, id: i64) -> anyhow::Result<()>;
+    /// Delete the sticker for good if it is in the trash, returns whether it was.
+    async fn purge_sticker(&self, id: i64) -> anyhow::Result<bool>;
     async fn get_sticker(&self, id: i64) -> anyhow::Result<StickerDetail>;
 
     async fn update_sticker_color(&self, id: i64, color: String) -> anyhow::Result<()>;
     async fn update_sticker_title(&self, id: i64, title: String) -> anyhow::Result<()>;
+    /// Recolor every sticker matching the search and filter, returns the changed ids.
+    async fn update_stickers_color(
+        &self,
+        search: Option<String>,
+        filter: StickerFilter,
+        color: StickerColor,
+    ) -> anyhow::Result<Vec<i64>>;
     async fn update_sticker_bounds(
         &self,
         id: i64,
@@ -23,25 +48,201 @@ pub trait StickerStore: Send + Sync {
         width: i32,
         height: i32,
     ) -> anyhow::Result<()>;
+    /// Bounds of the sticker for one monitor layout, see [`crate::native::monitor_layout`].
+    async fn update_sticker_layout_bounds(
+        &self,
+        id: i64,
+        layout: String,
+        left: i32,
+        top: i32,
+        width: i32,
+        height: i32,
+    ) -> anyhow::Result<()>;
+    async fn get_sticker_layout_bounds(
+        &self,
+        id: i64,
+        layout: String,
+    ) -> anyhow::Result<Option<(i32, i32, i32, i32)>>;
+    /// Also keeps the replaced content, see [`StickerStore::get_sticker_revisions`].
     async fn update_sticker_content(&self, id: i64, content: String) -> anyhow::Result<()>;
+    /// Turn the sticker into another type, `content` is already in the format of that type.
+    /// The replaced content is kept in the history with its type.
+    async fn update_sticker_type(
+        &self,
+        id: i64,
+        sticker_type: StickerType,
+        content: String,
+    ) -> anyhow::Result<()>;
     async fn update_sticker_state(&self, id: i64, state: StickerState) -> anyhow::Result<()>;
-    #[allow(dead_code)]
     async fn update_sticker_top_most(&self, id: i64, top_most: bool) -> anyhow::Result<()>;
+    /// Hidden text which is included in search but never shown, e.g. OCR output of images.
+    async fn update_sticker_search_text(&self, id: i64, search_text: String) -> anyhow::Result<()>;
+    /// JSON of [`StickerSettings`].
+    async fn update_sticker_settings(&self, id: i64, settings: String) -> anyhow::Result<()>;
 
     async fn query_stickers(
         &self,
         search: Option<String>,
+        filter: StickerFilter,
         order_by: StickerOrderBy,
         limit: i64,
         offset: i64,
     ) -> anyhow::Result<Vec<StickerBrief>>;
-    async fn count_stickers(&self, search: Option<String>) -> anyhow::Result<i64>;
+    /// Forget the sticker list kept for [`Self::query_stickers`], e.g. after another
+    /// program changed the DB. Writes through the store forget it by themselves.
+    fn invalidate_sticker_cache(&self);
+    async fn count_stickers(
+        &self,
+        search: Option<String>,
+        filter: StickerFilter,
+    ) -> anyhow::Result<i64>;
     async fn get_open_sticker_ids(&self) -> anyhow::Result<Vec<i64>>;
+    /// Delete temporary stickers which expired at `now`, or all of them without it,
+    /// returns the deleted ids.
+    async fn delete_temporary_stickers(&self, now: Option<i64>) -> anyhow::Result<Vec<i64>>;
+    /// Archive closed stickers not updated since `updated_before`, returns (id, title) of
+    /// the archived ones.
+    async fn archive_stale_stickers(
+        &self,
+        updated_before: i64,
+    ) -> anyhow::Result<Vec<(i64, String)>>;
+    /// Delete stickers trashed before `deleted_before` for good, returns (id, title) of
+    /// the deleted ones.
+    async fn purge_trash(&self, deleted_before: i64) -> anyhow::Result<Vec<(i64, String)>>;
+    /// Replace all tags of the sticker.
+    async fn set_sticker_tags(&self, id: i64, tags: Vec<String>) -> anyhow::Result<()>;
+    /// Every (sticker id, tag name) pair, ordered by tag name.
+    async fn get_all_sticker_tags(&self) -> anyhow::Result<Vec<(i64, String)>>;
+    /// Tags of the sticker, ordered by name.
+    async fn get_sticker_tags(&self, id: i64) -> anyhow::Result<Vec<String>>;
+    /// Every tag which is on at least one sticker, ordered by name.
+    async fn get_tags(&self) -> anyhow::Result<Vec<String>>;
+    async fn add_sticker_tag(&self, id: i64, tag: String) -> anyhow::Result<()>;
+    async fn remove_sticker_tag(&self, id: i64, tag: String) -> anyhow::Result<()>;
+    /// Rename the tag on every sticker, merged into `new_name` when that tag exists.
+    async fn rename_tag(&self, name: String, new_name: String) -> anyhow::Result<()>;
+    /// Remove the tag from every sticker.
+    async fn delete_tag(&self, name: String) -> anyhow::Result<()>;
+    /// Every collection with its number of active stickers, ordered by name.
+    async fn get_collections(&self) -> anyhow::Result<Vec<Collection>>;
+    /// Returns the id of the collection, also when one with the name exists already.
+    async fn create_collection(&self, name: String) -> anyhow::Result<i64>;
+    /// Delete the collection, its stickers are kept without one.
+    async fn delete_collection(&self, id: i64) -> anyhow::Result<()>;
+    /// Move the sticker into the collection, `None` takes it out of its collection.
+    async fn set_sticker_collection(
+        &self,
+        id: i64,
+        collection_id: Option<i64>,
+    ) -> anyhow::Result<()>;
+    async fn set_sticker_pinned(&self, id: i64, pinned: bool) -> anyhow::Result<()>;
+    /// The stack of the sticker with its members, `None` when it is not stacked.
+    async fn get_sticker_stack(&self, id: i64) -> anyhow::Result<Option<Stack>>;
+    /// Put `other_id` into the stack of the sticker, which gets a new stack when it has
+    /// none. Returns the stack id.
+    async fn stack_stickers(&self, id: i64, other_id: i64) -> anyhow::Result<i64>;
+    /// Take the sticker out of its stack, a stack left with one sticker is dissolved.
+    async fn unstack_sticker(&self, id: i64) -> anyhow::Result<()>;
+    async fn update_stack(
+        &self,
+        stack_id: i64,
+        collapsed: bool,
+        active_id: Option<i64>,
+    ) -> anyhow::Result<()>;
+    /// Like [`Self::query_stickers`], but only stickers with the tag.
+    async fn query_stickers_by_tag(
+        &self,
+        tag: String,
+        search: Option<String>,
+        filter: StickerFilter,
+        order_by: StickerOrderBy,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<StickerBrief>>;
+
+    /// Record a run of a command sticker, its runs older than 90 days are deleted.
+    async fn insert_command_run(&self, run: CommandRun) -> anyhow::Result<i64>;
+    /// Runs of a command sticker, oldest first.
+    async fn get_command_runs(&self, sticker_id: i64) -> anyhow::Result<Vec<CommandRun>>;
+    /// Runs of every command sticker started at `since` or later, oldest first.
+    async fn get_command_runs_since(&self, since: i64) -> anyhow::Result<Vec<CommandRun>>;
+    /// Earlier contents of a sticker, newest first.
+    async fn get_sticker_revisions(&self, sticker_id: i64) -> anyhow::Result<Vec<StickerRevision>>;
+    /// Link `data` to the sticker, returns the id of the attachment. Data the sticker
+    /// already has is not stored twice, its attachment id is returned instead. The
+    /// same data of other stickers shares one file.
+    async fn add_attachment(
+        &self,
+        sticker_id: i64,
+        name: String,
+        mime: String,
+        data: Vec<u8>,
+    ) -> anyhow::Result<i64>;
+    async fn get_attachment(&self, id: i64) -> anyhow::Result<Attachment>;
+    async fn get_attachment_data(&self, id: i64) -> anyhow::Result<Vec<u8>>;
+    /// Attachments of a sticker, oldest first. They are deleted together with it.
+    async fn get_sticker_attachments(&self, sticker_id: i64) -> anyhow::Result<Vec<Attachment>>;
+    /// Link the attachments of a sticker to another one, their data is shared.
+    async fn copy_attachments(
+        &self,
+        from_sticker_id: i64,
+        to_sticker_id: i64,
+    ) -> anyhow::Result<()>;
+    /// Data which no attachment refers to anymore is kept until this removes it.
+    async fn delete_attachment(&self, id: i64) -> anyhow::Result<()>;
+    /// Remove attachment data no sticker refers to, stray files of the attachments
+    /// folder and pictures of older versions no sticker names anymore.
+    async fn collect_orphan_attachments(&self) -> anyhow::Result<AttachmentCollection>;
+    async fn insert_focus_session(&self, session: FocusSession) -> anyhow::Result<i64>;
+    /// Focus sessions finished at `since` or later, oldest first.
+    async fn get_focus_sessions_since(&self, since: i64) -> anyhow::Result<Vec<FocusSession>>;
+    async fn insert_time_entry(&self, entry: TimeEntry) -> anyhow::Result<i64>;
+    /// Time entries which ended at `since` or later, oldest first.
+    async fn get_time_entries_since(&self, since: i64) -> anyhow::Result<Vec<TimeEntry>>;
+    /// Stickers which are not trashed or temporary and were updated at `since` or
+    /// later, newest first.
+    async fn get_stickers_updated_since(&self, since: i64) -> anyhow::Result<Vec<StickerDetail>>;
+    /// Id and `updated_at` of the stickers [`Self::get_stickers_updated_since`] returns,
+    /// without loading them.
+    async fn get_sticker_update_times(&self) -> anyhow::Result<Vec<(i64, i64)>>;
+    /// Files mirroring stickers in the sync folder `folder`.
+    async fn get_synced_files(&self, folder: String) -> anyhow::Result<Vec<SyncedFile>>;
+    /// Insert or replace the file of the sticker in its folder.
+    async fn set_synced_file(&self, file: SyncedFile) -> anyhow::Result<()>;
+    async fn delete_synced_file(&self, folder: String, sticker_id: i64) -> anyhow::Result<()>;
+
+    /// Consistent copy of the whole database into a new file at `path`, also while
+    /// it is in use.
+    async fn backup_to(&self, path: PathBuf) -> anyhow::Result<()>;
+
+    /// The database could only be opened for reading, every write fails.
+    fn is_read_only(&self) -> bool;
+
+    /// Changes whenever another connection commits to the DB, see `PRAGMA data_version`.
+    async fn data_version(&self) -> anyhow::Result<i64>;
+
+    /// Index the searchable text of every sticker again, triggers keep it current
+    /// afterwards. Also pulls the index into the page cache for the first search.
+    async fn rebuild_search_index(&self) -> anyhow::Result<()>;
+
+    /// The newest sticker of the type with exactly this title.
+    async fn find_sticker_by_title(
+        &self,
+        title: String,
+        sticker_type: StickerType,
+    ) -> anyhow::Result<Option<i64>>;
+
+    /// The newest sticker of the type with the same content, ignoring surrounding whitespace.
+    async fn find_sticker_by_content(
+        &self,
+        content: String,
+        sticker_type: StickerType,
+    ) -> anyhow::Result<Option<i64>>;
 }
 
 pub type ArcStickerStore = Arc<dyn StickerStore>;
 
-pub async fn open_sqlite(db_path: impl AsRef<Path>) -> anyhow::Result<ArcStickerStore> {
-    let store = sqlite::SqliteStore::open(db_path).await?;
+pub async fn open_sqlite(app_paths: &AppPaths) -> anyhow::Result<ArcStickerStore> {
+    let store = sqlite::SqliteStore::open(app_paths).await?;
     Ok(Arc::new(store))
 }
@@ -1,32 +1,97 @@
 use anyhow::Context as _;
 use directories::ProjectDirs;
 use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::PathBuf;
 
+/// Environment variable which overrides the data directory, like `--data-dir`.
+pub const DATA_DIR_ENV: &str = "RUSTICKERS_DATA_DIR";
+
 #[derive(Debug, Clone)]
 pub struct AppPaths {
     pub db_path: PathBuf,
+    /// Set when the data directory comes from `--data-dir` or [`DATA_DIR_ENV`].
+    pub custom_data_dir: bool,
 }
 
+// Available to stickers which keep files next to the database.
+impl gpui::Global for AppPaths {}
+
 impl AppPaths {
-    pub fn new() -> anyhow::Result<Self> {
-        let project_dirs =
-            ProjectDirs::from("", "", "rustickers").context("resolve AppData project directory")?;
+    /// Resolve the paths, the data directory is taken from `data_dir` (the CLI flag),
+    /// then [`DATA_DIR_ENV`], then the OS AppData location.
+    pub fn new(data_dir: Option<PathBuf>) -> anyhow::Result<Self> {
+        let custom_data_dir = data_dir.or_else(|| {
+            std::env::var_os(DATA_DIR_ENV)
+                .filter(|x| !x.is_empty())
+                .map(PathBuf::from)
+        });
+
+        let (data_dir, custom) = match custom_data_dir {
+            Some(data_dir) => (data_dir, true),
+            None => {
+                let project_dirs = ProjectDirs::from("", "", "rustickers")
+                    .context("resolve AppData project directory")?;
+                (project_dirs.data_local_dir().to_path_buf(), false)
+            }
+        };
 
-        let data_dir = project_dirs.data_local_dir().to_path_buf();
+        fs::create_dir_all(&data_dir)
+            .with_context(|| format!("create data dir {}", data_dir.display()))?;
+        // Keep the path stable for the instance id, whichever way it was written.
+        let data_dir = std::path::absolute(&data_dir).unwrap_or(data_dir);
         let db_path = data_dir.join("stickers.db");
 
-        fs::create_dir_all(&data_dir).context("create AppData data dir")?;
+        Ok(Self {
+            db_path,
+            custom_data_dir: custom,
+        })
+    }
+
+    /// Name of the single instance, a custom data directory runs as its own instance
+    /// so a sandbox does not hand over to the everyday app.
+    pub fn instance_id(&self) -> String {
+        if !self.custom_data_dir {
+            return "rustickers".to_string();
+        }
 
-        Ok(Self { db_path })
+        let mut hasher = DefaultHasher::new();
+        self.data_dir().hash(&mut hasher);
+        format!("rustickers-{:x}", hasher.finish())
     }
 
     pub fn log_dir(&self) -> PathBuf {
         // Keep logs in a dedicated folder alongside the database.
         // If the db path changes in the future, logs follow automatically.
+        self.data_dir().join("logs")
+    }
+
+    pub fn settings_path(&self) -> PathBuf {
+        self.data_dir().join("settings.json")
+    }
+
+    pub fn templates_dir(&self) -> PathBuf {
+        self.data_dir().join("templates")
+    }
+
+    /// Database snapshots taken before schema upgrades.
+    pub fn backups_dir(&self) -> PathBuf {
+        self.data_dir().join("backups")
+    }
+
+    /// Attachment data, named by the hash of its content.
+    pub fn attachments_dir(&self) -> PathBuf {
+        self.data_dir().join("attachments")
+    }
+
+    pub fn images_dir(&self) -> PathBuf {
+        self.data_dir().join("images")
+    }
+
+    pub fn data_dir(&self) -> PathBuf {
         self.db_path
             .parent()
             .unwrap_or_else(|| std::path::Path::new("."))
-            .join("logs")
+            .to_path_buf()
     }
 }
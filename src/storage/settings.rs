@@ -0,0 +1,519 @@
+use anyhow::Context as _;
+use gpui::{App, Global};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::model::sticker::{StickerColor, StickerType};
+
+/// Settings which only make sense on the machine they were created on or hold
+/// credentials, they are left out of exports and never overwritten by imports.
+const MACHINE_SPECIFIC_KEYS: &[&str] = &[
+    "recent_working_dirs",
+    "task_sync",
+    "folder_sync",
+    "hooks",
+    "webhook",
+    "github_token",
+    "app_lock",
+];
+const MAX_RECENT_WORKING_DIRS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Hide the main window when it loses focus, like a launcher.
+    pub popup_mode: bool,
+    /// Keep the main window above other windows.
+    pub main_window_top_most: bool,
+    /// Never execute command stickers, e.g. on a shared or demo machine.
+    pub safe_mode: bool,
+    /// Open sticker cards in the main window with a single click instead of a double click.
+    pub single_click_open: bool,
+    /// Minimize the main window after opening a sticker from it.
+    pub minimize_on_open: bool,
+    /// Put stickers created from the main window under the mouse cursor.
+    pub open_at_cursor: bool,
+    /// Open and close sticker windows without fading.
+    pub disable_animations: bool,
+    /// Minutes after which temporary stickers are deleted, 0 keeps them until closed.
+    pub temporary_ttl_minutes: u32,
+    /// Sticker contents larger than this many KB ask before they are saved, 0 never asks.
+    pub max_content_kb: u32,
+    /// Show the always-on-top bubble which turns dropped or pasted content into stickers.
+    pub inbox_bubble: bool,
+    /// What each sticker color stands for, keyed by the color name.
+    pub color_labels: BTreeMap<String, String>,
+    /// Color of new stickers, color names keyed by the sticker type, Yellow when missing.
+    pub default_colors: BTreeMap<String, String>,
+    /// Title of new stickers keyed by the sticker type, with `{date}` like placeholders.
+    pub title_templates: BTreeMap<String, String>,
+    pub daily_note: DailyNoteSettings,
+    pub task_sync: TaskSyncSettings,
+    pub folder_sync: FolderSyncSettings,
+    pub hooks: HookSettings,
+    pub webhook: WebhookSettings,
+    pub archive: ArchiveSettings,
+    pub backup: BackupSettings,
+    pub sound: SoundSettings,
+    pub app_lock: AppLockSettings,
+    pub focus_goal: FocusGoalSettings,
+    pub mouse_shortcuts: MouseShortcutSettings,
+    /// Personal access token used by all GitHub stickers.
+    pub github_token: String,
+    /// Working directories used by command stickers, most recent first.
+    pub recent_working_dirs: Vec<String>,
+}
+
+/// A dated Markdown sticker which is created (or opened) once a day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyNoteSettings {
+    pub enabled: bool,
+    /// Local time as `HH:MM`.
+    pub time: String,
+    /// Markdown template, its first line becomes the title and identifies the note of the day.
+    pub template: String,
+}
+
+impl Default for DailyNoteSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: "09:00".to_string(),
+            template: "## {date} Plan\n\n- [ ] ".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskProvider {
+    Todoist,
+    CalDav,
+}
+
+/// Open tasks of a Todoist or CalDAV account shown as a checklist in a Markdown sticker,
+/// checking one off there completes the task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskSyncSettings {
+    pub enabled: bool,
+    pub provider: TaskProvider,
+    /// API token from Todoist settings → Integrations → Developer.
+    pub todoist_token: String,
+    /// Only tasks of this project, every open task when empty.
+    pub todoist_project_id: String,
+    /// URL of the CalDAV task list, e.g. `https://cloud.example.com/remote.php/dav/calendars/me/tasks/`.
+    pub caldav_url: String,
+    pub caldav_username: String,
+    pub caldav_password: String,
+    /// The Markdown sticker which shows the tasks, created by the first sync when missing.
+    pub sticker_id: Option<i64>,
+    pub interval_minutes: u32,
+}
+
+impl Default for TaskSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: TaskProvider::Todoist,
+            todoist_token: String::new(),
+            todoist_project_id: String::new(),
+            caldav_url: String::new(),
+            caldav_username: String::new(),
+            caldav_password: String::new(),
+            sticker_id: None,
+            interval_minutes: 15,
+        }
+    }
+}
+
+/// Every sticker mirrored to a Markdown or JSON file in a folder kept in sync by e.g.
+/// Syncthing or Dropbox, edits of the files are merged back.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FolderSyncSettings {
+    pub enabled: bool,
+    pub folder: String,
+}
+
+/// Command lines run on sticker lifecycle events, empty ones are off. See
+/// [`crate::native::hooks`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookSettings {
+    pub on_create: String,
+    pub on_save: String,
+    pub on_close: String,
+    pub on_timer_finish: String,
+    pub on_command_done: String,
+}
+
+/// JSON posted to a URL on the selected events, e.g. a Slack or Discord webhook. See
+/// [`crate::native::webhook`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    /// Nothing is posted when empty.
+    pub url: String,
+    pub timer_finished: bool,
+    /// A command on a schedule or watch exited with an error.
+    pub command_failed: bool,
+    pub reminder_due: bool,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            timer_finished: true,
+            command_failed: true,
+            reminder_due: true,
+        }
+    }
+}
+
+/// Rules applied once a day by the archive job, 0 days turns a rule off.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveSettings {
+    /// Archive closed stickers which were not updated for this many days.
+    pub archive_closed_after_days: u32,
+    /// Delete stickers which are in the trash for this many days.
+    pub purge_trash_after_days: u32,
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        Self {
+            archive_closed_after_days: 90,
+            purge_trash_after_days: 30,
+        }
+    }
+}
+
+/// Snapshots of the database taken by the backup job into the backups directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    /// Hours between two backups, 0 turns them off.
+    pub interval_hours: u32,
+    /// Scheduled backups kept, older ones are deleted.
+    pub keep: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            interval_hours: 24,
+            keep: 7,
+        }
+    }
+}
+
+/// Hide every window behind a lock screen until the PIN is entered, for shared offices.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppLockSettings {
+    pub enabled: bool,
+    /// Lock after this many minutes without keyboard or mouse input, 0 only locks on demand.
+    pub idle_minutes: u32,
+    /// Salted hash of the PIN, see [`crate::native::app_lock::hash_pin`].
+    pub pin_hash: String,
+}
+
+impl Default for AppLockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: 10,
+            pin_hash: String::new(),
+        }
+    }
+}
+
+/// Pomodoro focus sessions aimed for, shown against the recorded ones. 0 means no goal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusGoalSettings {
+    pub daily_sessions: u32,
+    pub weekly_sessions: u32,
+}
+
+impl Default for FocusGoalSettings {
+    fn default() -> Self {
+        Self {
+            daily_sessions: 8,
+            weekly_sessions: 40,
+        }
+    }
+}
+
+/// Mouse shortcuts on the header of sticker windows, for handling many small stickers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MouseShortcutSettings {
+    /// Middle-click closes the sticker, saving it like the close button.
+    pub middle_click_close: bool,
+    /// Double-click collapses the sticker to its header and expands it again.
+    pub double_click_collapse: bool,
+    /// Ctrl+drag (Cmd+drag on macOS) leaves a copy of the sticker behind.
+    pub ctrl_drag_duplicate: bool,
+}
+
+impl Default for MouseShortcutSettings {
+    fn default() -> Self {
+        Self {
+            middle_click_close: true,
+            double_click_collapse: true,
+            ctrl_drag_duplicate: true,
+        }
+    }
+}
+
+/// Tones played by timers, notifications and failing commands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundSettings {
+    pub muted: bool,
+    /// Percent, 0 to 100.
+    pub volume: u8,
+    /// Timers, pomodoro phases and reminders which are due.
+    pub alarms: bool,
+    /// Desktop notifications.
+    pub dings: bool,
+    /// Commands which exit with an error.
+    pub command_failures: bool,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            muted: false,
+            volume: 70,
+            alarms: true,
+            dings: true,
+            command_failures: false,
+        }
+    }
+}
+
+impl DailyNoteSettings {
+    pub fn parse_time(&self) -> Option<chrono::NaiveTime> {
+        chrono::NaiveTime::parse_from_str(self.time.trim(), "%H:%M").ok()
+    }
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            popup_mode: false,
+            main_window_top_most: false,
+            safe_mode: false,
+            single_click_open: false,
+            minimize_on_open: false,
+            open_at_cursor: false,
+            disable_animations: false,
+            temporary_ttl_minutes: 0,
+            max_content_kb: 1024,
+            inbox_bubble: false,
+            color_labels: BTreeMap::new(),
+            default_colors: BTreeMap::new(),
+            title_templates: BTreeMap::new(),
+            daily_note: DailyNoteSettings::default(),
+            task_sync: TaskSyncSettings::default(),
+            folder_sync: FolderSyncSettings::default(),
+            hooks: HookSettings::default(),
+            webhook: WebhookSettings::default(),
+            archive: ArchiveSettings::default(),
+            backup: BackupSettings::default(),
+            sound: SoundSettings::default(),
+            app_lock: AppLockSettings::default(),
+            focus_goal: FocusGoalSettings::default(),
+            mouse_shortcuts: MouseShortcutSettings::default(),
+            github_token: String::new(),
+            recent_working_dirs: Vec::new(),
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn color_label(&self, color: StickerColor) -> Option<&str> {
+        self.color_labels
+            .get(color.as_str())
+            .map(|label| label.trim())
+            .filter(|label| !label.is_empty())
+    }
+
+    /// The color name followed by its label, e.g. "Yellow — urgent".
+    pub fn color_display_name(&self, color: StickerColor) -> String {
+        match self.color_label(color) {
+            Some(label) => format!("{} — {label}", color.name()),
+            None => color.name().to_string(),
+        }
+    }
+
+    pub fn set_color_label(&mut self, color: StickerColor, label: &str) {
+        let label = label.trim();
+        if label.is_empty() {
+            self.color_labels.remove(color.as_str());
+        } else {
+            self.color_labels
+                .insert(color.as_str().to_string(), label.to_string());
+        }
+    }
+
+    pub fn default_color(&self, sticker_type: StickerType) -> StickerColor {
+        self.default_colors
+            .get(sticker_type.as_str())
+            .and_then(|color| color.parse().ok())
+            .unwrap_or(StickerColor::Yellow)
+    }
+
+    pub fn set_default_color(&mut self, sticker_type: StickerType, color: StickerColor) {
+        if color == StickerColor::Yellow {
+            self.default_colors.remove(sticker_type.as_str());
+        } else {
+            self.default_colors.insert(
+                sticker_type.as_str().to_string(),
+                color.as_str().to_string(),
+            );
+        }
+    }
+
+    pub fn title_template(&self, sticker_type: StickerType) -> Option<&str> {
+        self.title_templates
+            .get(sticker_type.as_str())
+            .map(|template| template.trim())
+            .filter(|template| !template.is_empty())
+    }
+
+    pub fn set_title_template(&mut self, sticker_type: StickerType, template: &str) {
+        let template = template.trim();
+        if template.is_empty() {
+            self.title_templates.remove(sticker_type.as_str());
+        } else {
+            self.title_templates
+                .insert(sticker_type.as_str().to_string(), template.to_string());
+        }
+    }
+
+    /// Move the directory to the front of the recent working directories.
+    pub fn remember_working_dir(&mut self, dir: &str) {
+        let dir = dir.trim();
+        if dir.is_empty() {
+            return;
+        }
+
+        self.recent_working_dirs.retain(|x| x != dir);
+        self.recent_working_dirs.insert(0, dir.to_string());
+        self.recent_working_dirs.truncate(MAX_RECENT_WORKING_DIRS);
+    }
+
+    pub fn load(path: &Path) -> Self {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(err) => {
+                tracing::warn!(error = %err, path = %path.display(), "Failed to read settings; using defaults");
+                return Self::default();
+            }
+        };
+
+        serde_json::from_str(&text).unwrap_or_else(|err| {
+            tracing::warn!(error = %err, path = %path.display(), "Failed to parse settings; using defaults");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("create settings directory")?;
+        }
+
+        let json = serde_json::to_string_pretty(self).context("serialize settings")?;
+
+        // Write to a temp file first so a crash can not leave half written settings.
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, json).context("write settings")?;
+        fs::rename(&temp_path, path).context("replace settings")?;
+
+        Ok(())
+    }
+
+    /// Write the settings which can be shared with other machines. The global hotkeys
+    /// and the theme are built in, not settings, so there is nothing of them to export.
+    pub fn export_portable(&self, path: &Path) -> anyhow::Result<()> {
+        let json = self.portable_json()?;
+        fs::write(path, json).context("write exported settings")?;
+
+        Ok(())
+    }
+
+    /// The settings without the machine specific keys, as written by [`Self::export_portable`].
+    pub fn portable_json(&self) -> anyhow::Result<String> {
+        let mut value = serde_json::to_value(self).context("serialize settings")?;
+        if let Some(map) = value.as_object_mut() {
+            for key in MACHINE_SPECIFIC_KEYS {
+                map.remove(*key);
+            }
+        }
+
+        serde_json::to_string_pretty(&value).context("serialize settings")
+    }
+
+    /// Apply an exported settings file on top of the current settings.
+    pub fn import_portable(&self, path: &Path) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(path).context("read settings file")?;
+        let imported: serde_json::Value =
+            serde_json::from_str(&text).context("parse settings file")?;
+        let Some(imported) = imported.as_object() else {
+            anyhow::bail!("Settings file must contain a JSON object");
+        };
+
+        let mut value = serde_json::to_value(self).context("serialize settings")?;
+        let map = value
+            .as_object_mut()
+            .context("settings must serialize to an object")?;
+        for (key, v) in imported {
+            if !MACHINE_SPECIFIC_KEYS.contains(&key.as_str()) {
+                map.insert(key.clone(), v.clone());
+            }
+        }
+
+        serde_json::from_value(value).context("apply imported settings")
+    }
+}
+
+/// App wide settings, available to every window as a gpui global.
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: AppSettings,
+}
+
+impl Global for SettingsStore {}
+
+impl SettingsStore {
+    pub fn init(cx: &mut App, path: PathBuf) {
+        let settings = AppSettings::load(&path);
+        // Not the settings themselves, they hold tokens, passwords and the PIN hash.
+        tracing::debug!(path = %path.display(), "Settings loaded");
+        cx.set_global(Self { path, settings });
+    }
+
+    pub fn get(cx: &App) -> &AppSettings {
+        &cx.global::<Self>().settings
+    }
+
+    /// Change the settings and persist them right away.
+    pub fn update(cx: &mut App, f: impl FnOnce(&mut AppSettings)) {
+        let this = cx.global_mut::<Self>();
+        f(&mut this.settings);
+
+        if let Err(err) = this.settings.save(&this.path) {
+            tracing::error!(error = ?err, "Failed to save settings");
+        }
+    }
+}
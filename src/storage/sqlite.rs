@@ -1,12 +1,26 @@
 use anyhow::Context as _;
+use futures::TryStreamExt as _;
+use sha2::{Digest as _, Sha256};
 use sqlx::{
-    SqlitePool,
+    QueryBuilder, Sqlite, SqlitePool,
     sqlite::{SqliteConnectOptions, SqlitePoolOptions},
 };
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
+use crate::model::attachment::{Attachment, AttachmentCollection};
+use crate::model::collection::Collection;
+use crate::model::command::CommandRun;
+use crate::model::focus::FocusSession;
+use crate::model::revision::StickerRevision;
+use crate::model::stack::{Stack, StackMember};
 use crate::model::sticker::*;
+use crate::model::sync::SyncedFile;
+use crate::model::time_entry::TimeEntry;
+use crate::storage::paths::AppPaths;
 
 impl StickerOrderBy {
     fn to_sql(self) -> &'static str {
@@ -15,24 +29,275 @@ impl StickerOrderBy {
             Self::CreatedDesc => "created_at DESC",
             Self::UpdatedAsc => "updated_at ASC",
             Self::UpdatedDesc => "updated_at DESC",
+            Self::PinnedFirst => "pinned DESC, created_at DESC",
         }
     }
+
+    /// Same order as [`Self::to_sql`], ties by id so it is stable.
+    fn compare(self, a: &StickerBrief, b: &StickerBrief) -> Ordering {
+        match self {
+            Self::CreatedAsc => (a.created_at, a.id).cmp(&(b.created_at, b.id)),
+            Self::CreatedDesc => (b.created_at, b.id).cmp(&(a.created_at, a.id)),
+            Self::UpdatedAsc => (a.updated_at, a.id).cmp(&(b.updated_at, b.id)),
+            Self::UpdatedDesc => (b.updated_at, b.id).cmp(&(a.updated_at, a.id)),
+            Self::PinnedFirst => {
+                (b.pinned, b.created_at, b.id).cmp(&(a.pinned, a.created_at, a.id))
+            }
+        }
+    }
+}
+
+/// Earlier contents kept per sticker, the oldest are deleted first.
+const MAX_REVISIONS: i64 = 50;
+
+/// Command runs older than this are deleted when the sticker runs again, in ms.
+const COMMAND_RUN_MAX_AGE: i64 = 90 * 24 * 60 * 60 * 1000;
+
+/// Longer lists are not cached, they could not be sorted again without the rows
+/// left out.
+const BRIEF_CACHE_LIMIT: i64 = 10000;
+
+/// The stickers of one search and bucket, of all colors and types, behind
+/// [`super::StickerStore::query_stickers`], so sorting and filtering the main window
+/// does not read every row again.
+#[derive(Debug, Default)]
+struct BriefCache {
+    /// Bumped when the cache is dropped, a query which started before is not cached.
+    generation: u64,
+    entry: Option<BriefCacheEntry>,
+}
+
+#[derive(Debug)]
+struct BriefCacheEntry {
+    search: Option<String>,
+    bucket: StickerBucket,
+    rows: Vec<StickerBrief>,
+}
+
+fn select_briefs(
+    rows: &[StickerBrief],
+    filter: &StickerFilter,
+    order_by: StickerOrderBy,
+    limit: i64,
+    offset: i64,
+) -> Vec<StickerBrief> {
+    let mut rows = rows
+        .iter()
+        .filter(|x| filter.color.is_none_or(|color| x.color == color))
+        .filter(|x| filter.sticker_type.is_none_or(|t| x.sticker_type == t))
+        .filter(|x| filter.collection.is_none_or(|c| x.collection_id == Some(c)))
+        .cloned()
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| order_by.compare(a, b));
+    rows.into_iter()
+        .skip(offset.max(0) as usize)
+        .take(limit.max(0) as usize)
+        .collect()
+}
+
+/// Drop the tag and its links to stickers, not relying on `ON DELETE CASCADE`.
+async fn delete_tag_in(tx: &mut sqlx::SqliteConnection, name: &str) -> anyhow::Result<()> {
+    sqlx::query("DELETE FROM sticker_tags WHERE tag_id IN (SELECT id FROM tags WHERE name = ?1)")
+        .bind(name)
+        .execute(&mut *tx)
+        .await
+        .context("delete sticker tags")?;
+
+    sqlx::query("DELETE FROM tags WHERE name = ?1")
+        .bind(name)
+        .execute(&mut *tx)
+        .await
+        .context("delete tag")?;
+
+    Ok(())
+}
+
+/// Keep the content of the sticker in its history before it is replaced by `content`,
+/// saved at its own update time. Empty contents are not kept.
+async fn insert_revision_in(
+    tx: &mut sqlx::SqliteConnection,
+    id: i64,
+    content: &str,
+    sticker_type: Option<StickerType>,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO sticker_revisions (sticker_id, type, content, created_at)
+        SELECT id, type, content, updated_at FROM stickers
+        WHERE id = ?1 AND content <> '' AND (content <> ?2 OR type <> COALESCE(?3, type))
+        "#,
+    )
+    .bind(id)
+    .bind(content)
+    .bind(sticker_type)
+    .execute(&mut *tx)
+    .await
+    .context("insert sticker revision")?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM sticker_revisions
+        WHERE sticker_id = ?1
+          AND id NOT IN (
+            SELECT id FROM sticker_revisions WHERE sticker_id = ?1 ORDER BY id DESC LIMIT ?2
+          )
+        "#,
+    )
+    .bind(id)
+    .bind(MAX_REVISIONS)
+    .execute(&mut *tx)
+    .await
+    .context("delete old sticker revisions")?;
+
+    Ok(())
+}
+
+/// Searches this long are looked up in the trigram index, shorter ones scan the table.
+const MIN_INDEXED_SEARCH_CHARS: usize = 3;
+
+/// Searches too short for the index look at this many characters of each content, so
+/// one huge sticker can not stall the list.
+const SCANNED_CONTENT_CHARS: i64 = 100_000;
+
+/// Append the WHERE clause shared by listing and counting stickers.
+fn push_filter(
+    builder: &mut QueryBuilder<'_, Sqlite>,
+    search: Option<String>,
+    filter: &StickerFilter,
+) {
+    // Temporary stickers are scratch windows, they never show up in lists.
+    builder.push(" WHERE temporary = 0");
+
+    if let Some(search) = search
+        && search.chars().count() >= MIN_INDEXED_SEARCH_CHARS
+    {
+        // A quoted phrase of trigrams matches the text anywhere, like LIKE '%…%'.
+        let phrase = format!("\"{}\"", search.replace('"', "\"\""));
+        builder
+            .push(" AND id IN (SELECT rowid FROM stickers_fts WHERE stickers_fts MATCH ")
+            .push_bind(phrase)
+            .push(")");
+    } else if let Some(search) = search {
+        let pattern = format!("%{}%", search);
+        builder
+            .push(" AND (title LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR substr(content, 1, ")
+            .push_bind(SCANNED_CONTENT_CHARS)
+            .push(") LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR search_text LIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+
+    if let Some(color) = filter.color {
+        builder.push(" AND color = ").push_bind(color);
+    }
+
+    if let Some(sticker_type) = filter.sticker_type {
+        builder.push(" AND type = ").push_bind(sticker_type);
+    }
+
+    if let Some(collection) = filter.collection {
+        builder.push(" AND collection_id = ").push_bind(collection);
+    }
+
+    builder.push(match filter.bucket {
+        StickerBucket::Active => " AND archived_at IS NULL AND deleted_at IS NULL",
+        StickerBucket::Archived => " AND archived_at IS NOT NULL AND deleted_at IS NULL",
+        StickerBucket::Trash => " AND deleted_at IS NOT NULL",
+    });
+}
+
+/// A schema upgrade failed, the database was put back to how it was before.
+#[derive(Debug)]
+pub struct MigrationFailed {
+    pub backup_path: PathBuf,
+    pub message: String,
+}
+
+impl std::fmt::Display for MigrationFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Upgrading the database failed, nothing was changed. A copy from before the upgrade is kept at {}.\n\n{}",
+            self.backup_path.display(),
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for MigrationFailed {}
+
+/// A schema upgrade is pending but the database could not be backed up first, so it
+/// was not upgraded.
+#[derive(Debug)]
+pub struct UpgradeNotBackedUp {
+    pub message: String,
+}
+
+impl std::fmt::Display for UpgradeNotBackedUp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The database needs an upgrade, but backing it up first failed, so nothing was changed.\n\n{}",
+            self.message
+        )
+    }
 }
 
+impl std::error::Error for UpgradeNotBackedUp {}
+
+/// Snapshots taken before schema upgrades which are kept, the oldest are deleted first.
+const KEEP_UPGRADE_SNAPSHOTS: usize = 3;
+
 #[derive(Debug, Clone)]
 pub struct SqliteStore {
     pool: SqlitePool,
+    read_only: bool,
+    brief_cache: Arc<Mutex<BriefCache>>,
+    /// Attachment data, one file per content named by its hash.
+    attachments_dir: PathBuf,
+    /// Pictures of older versions, until their stickers move them into attachments.
+    images_dir: PathBuf,
 }
 
 impl SqliteStore {
-    pub async fn open(db_path: impl AsRef<Path>) -> anyhow::Result<Self> {
-        let db_path = db_path.as_ref().to_path_buf();
+    /// Open for writing, or read-only when an existing database can not be written,
+    /// e.g. it is locked by a cloud sync client or the file permissions forbid it.
+    ///
+    /// Schema upgrades of an existing database are tried on a copy first, and the
+    /// database is snapshotted into the backups directory before they run for real.
+    pub async fn open(app_paths: &AppPaths) -> anyhow::Result<Self> {
+        let db_path = &app_paths.db_path;
+
+        match Self::open_read_write(app_paths).await {
+            Ok(store) => Ok(store),
+            // The database has the old schema, a read-only view would break.
+            Err(err)
+                if err.downcast_ref::<MigrationFailed>().is_some()
+                    || err.downcast_ref::<UpgradeNotBackedUp>().is_some() =>
+            {
+                Err(err)
+            }
+            Err(err) if db_path.exists() => {
+                tracing::warn!(error = ?err, "Failed to open sqlite db for writing, opening read-only");
+                Self::open_read_only(app_paths)
+                    .await
+                    .with_context(|| format!("{err:#}"))
+            }
+            Err(err) => Err(err),
+        }
+    }
 
+    async fn open_read_write(app_paths: &AppPaths) -> anyhow::Result<Self> {
+        let db_path = &app_paths.db_path;
         if let Some(parent) = db_path.parent() {
             fs::create_dir_all(parent).context("create sqlite db parent directory")?;
         }
         let options = SqliteConnectOptions::new()
-            .filename(&db_path)
+            .filename(db_path)
             .create_if_missing(true);
 
         let pool = SqlitePoolOptions::new()
@@ -42,13 +307,509 @@ impl SqliteStore {
             .await
             .context("connect sqlite pool")?;
 
-        sqlx::migrate!("./migrations")
-            .run(&pool)
+        migrate(&pool, app_paths).await?;
+
+        // Migrations do not write when there is nothing to do, make sure we can.
+        let probe = pool
+            .begin_with("BEGIN IMMEDIATE")
+            .await
+            .context("lock sqlite db for writing")?;
+        probe
+            .rollback()
+            .await
+            .context("release sqlite write lock")?;
+
+        let store = Self {
+            pool,
+            read_only: false,
+            brief_cache: Arc::default(),
+            attachments_dir: app_paths.attachments_dir(),
+            images_dir: app_paths.images_dir(),
+        };
+        store.move_attachment_data_to_files().await?;
+        Ok(store)
+    }
+
+    async fn open_read_only(app_paths: &AppPaths) -> anyhow::Result<Self> {
+        let options = SqliteConnectOptions::new()
+            .filename(&app_paths.db_path)
+            .read_only(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .context("connect read-only sqlite pool")?;
+
+        // Every query expects the latest schema, and an older one can not be upgraded
+        // without writing.
+        let latest = sqlx::migrate!("./migrations")
+            .iter()
+            .map(|m| m.version)
+            .max()
+            .unwrap_or(0);
+        let version = sqlx::query_scalar::<_, Option<i64>>(
+            "SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1",
+        )
+        .fetch_one(&pool)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(0);
+        if version < latest {
+            pool.close().await;
+            anyhow::bail!(
+                "The database is from an older version of Rustickers (schema v{version}, v{latest} is needed) and can not be upgraded while it is read-only"
+            );
+        }
+
+        Ok(Self {
+            pool,
+            read_only: true,
+            brief_cache: Arc::default(),
+            attachments_dir: app_paths.attachments_dir(),
+            images_dir: app_paths.images_dir(),
+        })
+    }
+
+    /// `<attachments_dir>/<first two hash chars>/<hash>`, so no folder gets too big.
+    fn attachment_path(&self, hash: &str) -> PathBuf {
+        self.attachments_dir
+            .join(&hash[..2.min(hash.len())])
+            .join(hash)
+    }
+
+    /// Write the data of `hash` unless its file is already there. Written to a temporary
+    /// file first, so a crash never leaves a partial file under the hash name.
+    fn write_attachment_file(&self, hash: &str, data: &[u8]) -> anyhow::Result<()> {
+        let path = self.attachment_path(hash);
+        if path.exists() {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("create attachments directory")?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, data)
+            .with_context(|| format!("write attachment {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &path)
+            .with_context(|| format!("move attachment to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Data kept in the DB by earlier versions is moved to files, once.
+    async fn move_attachment_data_to_files(&self) -> anyhow::Result<()> {
+        let rows = sqlx::query_as::<_, (String, Vec<u8>)>(
+            "SELECT hash, data FROM attachment_blobs WHERE data IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("get attachment data to move")?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        for (hash, data) in &rows {
+            self.write_attachment_file(hash, data)?;
+            sqlx::query("UPDATE attachment_blobs SET data = NULL WHERE hash = ?1")
+                .bind(hash)
+                .execute(&self.pool)
+                .await
+                .context("clear moved attachment data")?;
+        }
+        tracing::info!(count = rows.len(), "Attachment data moved to files");
+
+        Ok(())
+    }
+
+    /// One page of the sticker list, straight from the DB. With `tag`, only the stickers
+    /// which have it.
+    async fn fetch_briefs(
+        &self,
+        search: Option<String>,
+        tag: Option<&str>,
+        filter: &StickerFilter,
+        order_by: StickerOrderBy,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<StickerBrief>> {
+        // Only the start of Markdown content is read, enough for the card preview.
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT id, title, state, color, type, created_at, updated_at, \
+             CASE WHEN type = 'markdown' THEN substr(content, 1, 1000) ELSE '' END AS summary, \
+             collection_id, pinned FROM stickers",
+        );
+        push_filter(&mut builder, search, filter);
+        if let Some(tag) = tag {
+            builder
+                .push(
+                    " AND id IN (SELECT st.sticker_id FROM sticker_tags st \
+                     JOIN tags t ON t.id = st.tag_id WHERE t.name = ",
+                )
+                .push_bind(tag.to_string())
+                .push(")");
+        }
+        builder.push(format!(" ORDER BY {}", order_by.to_sql()));
+        builder.push(" LIMIT ").push_bind(limit);
+        builder.push(" OFFSET ").push_bind(offset);
+
+        let mut rows = builder
+            .build_query_as::<StickerBrief>()
+            .fetch_all(&self.pool)
+            .await
+            .context("list stickers")?;
+        for row in &mut rows {
+            row.summary = markdown_summary(&row.summary);
+        }
+
+        Ok(rows)
+    }
+}
+
+/// Run pending migrations, with a dry run on a copy and a backup for existing databases.
+async fn migrate(pool: &SqlitePool, app_paths: &AppPaths) -> anyhow::Result<()> {
+    let (db_path, backups_dir) = (&app_paths.db_path, &app_paths.backups_dir());
+    let migrator = sqlx::migrate!("./migrations");
+
+    // Missing on a fresh database, which has nothing to protect.
+    let applied = sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations")
+        .fetch_all(pool)
+        .await
+        .unwrap_or_default();
+    let pending = migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration() && !applied.contains(&m.version))
+        .map(|m| m.version)
+        .max();
+
+    let Some(target_version) = pending.filter(|_| !applied.is_empty()) else {
+        return migrator.run(pool).await.context("run sqlx migrations");
+    };
+
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let backup_path = backups_dir.join(format!("stickers-{stamp}-before-v{target_version}.db"));
+    let backed_up = async {
+        fs::create_dir_all(backups_dir).context("create backups directory")?;
+        snapshot(pool, &backup_path).await?;
+        back_up_files(app_paths).await
+    };
+    if let Err(err) = backed_up.await {
+        tracing::error!(error = ?err, "Failed to back up the database before upgrade");
+        return Err(UpgradeNotBackedUp {
+            message: format!("{err:#}"),
+        }
+        .into());
+    }
+    tracing::info!(backup = %backup_path.display(), target_version, "Database backed up before upgrade");
+
+    let dry_run_path = backups_dir.join(format!("stickers-{stamp}-dry-run.db"));
+    let dry_run = dry_run_migrate(&migrator, &backup_path, &dry_run_path).await;
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = fs::remove_file(format!("{}{suffix}", dry_run_path.display()));
+    }
+    if let Err(err) = dry_run {
+        tracing::error!(error = ?err, "Database upgrade dry run failed");
+        return Err(MigrationFailed {
+            backup_path,
+            message: format!("{err:#}"),
+        }
+        .into());
+    }
+
+    if let Err(err) = migrator.run(pool).await {
+        tracing::error!(error = ?err, "Database upgrade failed, restoring the backup");
+        pool.close().await;
+        let restored = restore(&backup_path, db_path);
+        let message = match restored {
+            Ok(()) => format!("{err:#}"),
+            Err(restore_err) => format!(
+                "{err:#}\n\nRestoring the backup failed too ({restore_err:#}), copy it over {} by hand.",
+                db_path.display()
+            ),
+        };
+        return Err(MigrationFailed {
+            backup_path,
+            message,
+        }
+        .into());
+    }
+
+    prune_upgrade_snapshots(backups_dir);
+    Ok(())
+}
+
+/// Delete all but the newest [`KEEP_UPGRADE_SNAPSHOTS`] snapshots taken before upgrades,
+/// failures are only logged.
+fn prune_upgrade_snapshots(backups_dir: &Path) {
+    let entries = match file_names(backups_dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            tracing::warn!(error = ?err, "Failed to list upgrade snapshots");
+            return;
+        }
+    };
+    let mut snapshots = entries
+        .into_iter()
+        .map(|x| x.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|x| x.to_str())
+                .is_some_and(|x| x.contains("-before-v") && x.ends_with(".db"))
+        })
+        .collect::<Vec<_>>();
+    // Named after the time they were taken, so newest last.
+    snapshots.sort();
+    let old = snapshots.len().saturating_sub(KEEP_UPGRADE_SNAPSHOTS);
+    for path in &snapshots[..old] {
+        match fs::remove_file(path) {
+            Ok(()) => tracing::info!(backup = %path.display(), "Old upgrade snapshot deleted"),
+            Err(err) => {
+                tracing::warn!(backup = %path.display(), error = %err, "Failed to delete old upgrade snapshot")
+            }
+        }
+    }
+}
+
+/// Folders of the data directory whose files the database refers to by name, with
+/// where their copies for the backups go. The files never change once written, so all
+/// backups share one copy of each.
+fn backed_up_dirs(app_paths: &AppPaths) -> [(PathBuf, PathBuf); 2] {
+    let backups_dir = app_paths.backups_dir();
+    [
+        (app_paths.attachments_dir(), backups_dir.join("attachments")),
+        (app_paths.images_dir(), backups_dir.join("images")),
+    ]
+}
+
+/// Copy attachments and pictures into the backups directory, next to the database
+/// snapshots, so a restored snapshot finds them even after they were collected.
+/// Returns how many files were new. The copies run on a blocking thread.
+pub async fn back_up_files(app_paths: &AppPaths) -> anyhow::Result<usize> {
+    let dirs = backed_up_dirs(app_paths);
+    smol::unblock(move || -> anyhow::Result<usize> {
+        let mut copied = 0;
+        for (dir, backup_dir) in dirs {
+            copied += copy_missing_files(&dir, &backup_dir)?;
+        }
+        Ok(copied)
+    })
+    .await
+}
+
+/// Remove the copies of [`back_up_files`] which none of the `snapshots` refers to and
+/// which are gone from the data directory too. Returns how many were removed, nothing
+/// is removed when a snapshot can not be read.
+pub async fn prune_backed_up_files(
+    app_paths: &AppPaths,
+    snapshots: &[PathBuf],
+) -> anyhow::Result<usize> {
+    let [
+        (attachments_dir, attachments_backup),
+        (images_dir, images_backup),
+    ] = backed_up_dirs(app_paths);
+    let pictures = file_names(&images_backup)?
+        .into_iter()
+        .map(|x| x.file_name().to_string_lossy().to_string())
+        .collect::<HashSet<_>>();
+
+    let mut hashes = HashSet::new();
+    let mut named = HashSet::new();
+    for snapshot in snapshots {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(
+                SqliteConnectOptions::new()
+                    .filename(snapshot)
+                    .read_only(true),
+            )
+            .await
+            .with_context(|| format!("open backup {}", snapshot.display()))?;
+        let referenced = referenced_by_snapshot(&pool, &pictures).await;
+        pool.close().await;
+        let (snapshot_hashes, snapshot_named) =
+            referenced.with_context(|| format!("read backup {}", snapshot.display()))?;
+        hashes.extend(snapshot_hashes);
+        named.extend(snapshot_named);
+    }
+
+    let mut removed = 0;
+    for folder in file_names(&attachments_backup)? {
+        for file in file_names(&folder.path())? {
+            let name = file.file_name().to_string_lossy().to_string();
+            let live = attachments_dir.join(folder.file_name()).join(&name);
+            if !hashes.contains(&name) && !live.exists() && fs::remove_file(file.path()).is_ok() {
+                removed += 1;
+            }
+        }
+        // Only succeeds once the folder is empty.
+        let _ = fs::remove_dir(folder.path());
+    }
+    for file in file_names(&images_backup)? {
+        let name = file.file_name().to_string_lossy().to_string();
+        if !named.contains(&name)
+            && !images_dir.join(&name).exists()
+            && fs::remove_file(file.path()).is_ok()
+        {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Attachment hashes and the names of `pictures` a database snapshot refers to, any
+/// schema version.
+async fn referenced_by_snapshot(
+    pool: &SqlitePool,
+    pictures: &HashSet<String>,
+) -> anyhow::Result<(HashSet<String>, HashSet<String>)> {
+    let mut conn = pool.acquire().await.context("acquire connection")?;
+    let hashes = if has_table(&mut conn, "attachments").await? {
+        sqlx::query_scalar::<_, String>("SELECT DISTINCT hash FROM attachments")
+            .fetch_all(&mut *conn)
             .await
-            .context("run sqlx migrations")?;
+            .context("get attachment hashes")?
+            .into_iter()
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    let named = referenced_file_names(&mut conn, pictures).await?;
+    Ok((hashes, named))
+}
+
+async fn has_table(conn: &mut sqlx::SqliteConnection, name: &str) -> anyhow::Result<bool> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+    )
+    .bind(name)
+    .fetch_one(&mut *conn)
+    .await
+    .context("look up table")
+}
+
+/// The `candidates` a sticker or one of its revisions mentions, in one pass over the
+/// contents. File names are split off at anything which can not be part of one.
+async fn referenced_file_names(
+    conn: &mut sqlx::SqliteConnection,
+    candidates: &HashSet<String>,
+) -> anyhow::Result<HashSet<String>> {
+    let mut found = HashSet::new();
+    if candidates.is_empty() {
+        return Ok(found);
+    }
+
+    for table in ["stickers", "sticker_revisions"] {
+        if !has_table(conn, table).await? {
+            continue;
+        }
+        let query = format!("SELECT content FROM {table}");
+        let mut contents = sqlx::query_scalar::<_, String>(&query).fetch(&mut *conn);
+        while let Some(content) = contents.try_next().await.context("read sticker contents")? {
+            let tokens = content
+                .split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')));
+            for token in tokens {
+                // A name at the end of a sentence.
+                for token in [token, token.trim_end_matches('.')] {
+                    if let Some(name) = candidates.get(token) {
+                        found.insert(name.clone());
+                    }
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// The entries of `dir`, none when it does not exist.
+fn file_names(dir: &Path) -> anyhow::Result<Vec<fs::DirEntry>> {
+    match fs::read_dir(dir) {
+        Ok(entries) => Ok(entries.filter_map(|x| x.ok()).collect()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err).with_context(|| format!("read {}", dir.display())),
+    }
+}
+
+/// Put files saved by [`back_up_files`] back when they are missing, e.g. after a
+/// restored snapshot. Returns how many files came back.
+pub fn restore_backed_up_files(app_paths: &AppPaths) -> anyhow::Result<usize> {
+    let mut copied = 0;
+    for (dir, backup_dir) in backed_up_dirs(app_paths) {
+        copied += copy_missing_files(&backup_dir, &dir)?;
+    }
+    Ok(copied)
+}
+
+fn copy_missing_files(from: &Path, to: &Path) -> anyhow::Result<usize> {
+    let entries = match fs::read_dir(from) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err).with_context(|| format!("read {}", from.display())),
+    };
+
+    let mut copied = 0;
+    for entry in entries.filter_map(|x| x.ok()) {
+        let (path, target) = (entry.path(), to.join(entry.file_name()));
+        if path.is_dir() {
+            copied += copy_missing_files(&path, &target)?;
+            continue;
+        }
+        // Left by an interrupted write, see `write_attachment_file`.
+        if target.exists() || path.extension().is_some_and(|x| x == "tmp") {
+            continue;
+        }
+        fs::create_dir_all(to).with_context(|| format!("create directory {}", to.display()))?;
+        fs::copy(&path, &target).with_context(|| format!("copy {}", path.display()))?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+fn remove_orphan_file(file: &fs::DirEntry, collection: &mut AttachmentCollection) {
+    let size = file.metadata().map(|x| x.len()).unwrap_or(0);
+    match fs::remove_file(file.path()) {
+        Ok(()) => {
+            collection.files += 1;
+            collection.bytes += size;
+        }
+        Err(err) => {
+            tracing::warn!(path = %file.path().display(), error = ?err, "Failed to remove orphan attachment")
+        }
+    }
+}
+
+/// Consistent copy of the open database, also fine while the WAL holds changes.
+async fn snapshot(pool: &SqlitePool, path: &Path) -> anyhow::Result<()> {
+    sqlx::query("VACUUM INTO ?1")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await
+        .with_context(|| format!("snapshot database to {}", path.display()))?;
+    Ok(())
+}
 
-        Ok(Self { pool })
+async fn dry_run_migrate(
+    migrator: &sqlx::migrate::Migrator,
+    backup_path: &Path,
+    dry_run_path: &Path,
+) -> anyhow::Result<()> {
+    fs::copy(backup_path, dry_run_path).context("copy database for the dry run")?;
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(SqliteConnectOptions::new().filename(dry_run_path))
+        .await
+        .context("connect dry run database")?;
+    let result = migrator.run(&pool).await.context("run sqlx migrations");
+    pool.close().await;
+    result
+}
+
+fn restore(backup_path: &Path, db_path: &Path) -> anyhow::Result<()> {
+    for suffix in ["-wal", "-shm"] {
+        let _ = fs::remove_file(format!("{}{suffix}", db_path.display()));
     }
+    fs::copy(backup_path, db_path).context("copy backup over the database")?;
+    Ok(())
 }
 
 #[async_trait::async_trait]
@@ -61,13 +822,15 @@ impl super::StickerStore for SqliteStore {
         );
 
         let now = crate::utils::time::now_unix_millis();
+        let or_now = |x: i64| if x > 0 { x } else { now };
+        let (created_at, updated_at) = (or_now(sticker.created_at), or_now(sticker.updated_at));
 
         let row = sqlx::query_scalar::<_, i64>(
             r#"
             INSERT INTO stickers (
-                title, state, left, top, width, height, color, type, content, created_at, updated_at
+                title, state, left, top, width, height, color, type, content, settings, temporary, expires_at, created_at, updated_at
             ) VALUES (
-                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14
             )
             RETURNING id
             "#,
@@ -81,12 +844,16 @@ impl super::StickerStore for SqliteStore {
         .bind(sticker.color)
         .bind(sticker.sticker_type)
         .bind(sticker.content)
-        .bind(now)
-        .bind(now)
+        .bind(sticker.settings)
+        .bind(sticker.temporary)
+        .bind(sticker.expires_at)
+        .bind(created_at)
+        .bind(updated_at)
         .fetch_one(&self.pool)
         .await
         .context("insert sticker")?;
 
+        self.invalidate_sticker_cache();
         Ok(row)
     }
 
@@ -97,13 +864,52 @@ impl super::StickerStore for SqliteStore {
             .execute(&self.pool)
             .await
             .context("delete sticker")?;
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn move_sticker(&self, id: i64, bucket: StickerBucket) -> anyhow::Result<()> {
+        tracing::debug!(id, bucket = ?bucket, "Move sticker");
+
+        let now = crate::utils::time::now_unix_millis();
+        let sql = match bucket {
+            StickerBucket::Active => {
+                "UPDATE stickers SET archived_at = NULL, deleted_at = NULL WHERE id = ?1"
+            }
+            StickerBucket::Archived => {
+                "UPDATE stickers SET archived_at = ?2, deleted_at = NULL WHERE id = ?1"
+            }
+            StickerBucket::Trash => "UPDATE stickers SET deleted_at = ?2 WHERE id = ?1",
+        };
+        sqlx::query(sql)
+            .bind(id)
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .context("move sticker")?;
+        self.invalidate_sticker_cache();
         Ok(())
     }
 
+    async fn restore_sticker(&self, id: i64) -> anyhow::Result<()> {
+        self.move_sticker(id, StickerBucket::Active).await
+    }
+
+    async fn purge_sticker(&self, id: i64) -> anyhow::Result<bool> {
+        tracing::debug!(id, "Purge sticker");
+        let result = sqlx::query("DELETE FROM stickers WHERE id = ?1 AND deleted_at IS NOT NULL")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("purge sticker")?;
+        self.invalidate_sticker_cache();
+        Ok(result.rows_affected() > 0)
+    }
+
     async fn get_sticker(&self, id: i64) -> anyhow::Result<StickerDetail> {
         tracing::debug!(id, "Get sticker detail");
         let row = sqlx::query_as::<_, StickerDetail>(
-            "SELECT id, title, state, left, top, width, height, top_most, color, type, content, created_at, updated_at FROM stickers WHERE id = ?1",
+            "SELECT id, title, state, left, top, width, height, top_most, color, type, content, settings, temporary, expires_at, created_at, updated_at FROM stickers WHERE id = ?1",
         )
         .bind(id)
         .fetch_one(&self.pool)
@@ -133,6 +939,7 @@ impl super::StickerStore for SqliteStore {
         .await
         .context("update sticker color")?;
 
+        self.invalidate_sticker_cache();
         Ok(())
     }
 
@@ -155,6 +962,7 @@ impl super::StickerStore for SqliteStore {
         .await
         .context("update sticker title")?;
 
+        self.invalidate_sticker_cache();
         Ok(())
     }
 
@@ -191,14 +999,77 @@ impl super::StickerStore for SqliteStore {
         .await
         .context("update sticker bounds")?;
 
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn update_sticker_layout_bounds(
+        &self,
+        id: i64,
+        layout: String,
+        left: i32,
+        top: i32,
+        width: i32,
+        height: i32,
+    ) -> anyhow::Result<()> {
+        tracing::debug!(id, layout = %layout, left, top, width, height, "Update sticker layout bounds");
+
+        sqlx::query(
+            r#"
+            INSERT INTO sticker_layout_bounds (sticker_id, layout, left, top, width, height)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT (sticker_id, layout) DO UPDATE
+            SET left = excluded.left,
+                top = excluded.top,
+                width = excluded.width,
+                height = excluded.height
+            "#,
+        )
+        .bind(id)
+        .bind(layout)
+        .bind(left)
+        .bind(top)
+        .bind(width)
+        .bind(height)
+        .execute(&self.pool)
+        .await
+        .context("update sticker layout bounds")?;
+
         Ok(())
     }
 
+    async fn get_sticker_layout_bounds(
+        &self,
+        id: i64,
+        layout: String,
+    ) -> anyhow::Result<Option<(i32, i32, i32, i32)>> {
+        tracing::debug!(id, layout = %layout, "Get sticker layout bounds");
+
+        let bounds = sqlx::query_as::<_, (i32, i32, i32, i32)>(
+            "SELECT left, top, width, height FROM sticker_layout_bounds WHERE sticker_id = ?1 AND layout = ?2",
+        )
+        .bind(id)
+        .bind(layout)
+        .fetch_optional(&self.pool)
+        .await
+        .context("get sticker layout bounds")?;
+
+        Ok(bounds)
+    }
+
     async fn update_sticker_content(&self, id: i64, content: String) -> anyhow::Result<()> {
         tracing::debug!(id, content_len = content.len(), "Update sticker content");
 
         let now = crate::utils::time::now_unix_millis();
 
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("begin update sticker content")?;
+
+        insert_revision_in(&mut tx, id, &content, None).await?;
+
         sqlx::query(
             r#"
             UPDATE stickers
@@ -210,45 +1081,93 @@ impl super::StickerStore for SqliteStore {
         .bind(content)
         .bind(now)
         .bind(id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .context("update sticker content")?;
 
+        tx.commit().await.context("commit update sticker content")?;
+
+        self.invalidate_sticker_cache();
         Ok(())
     }
 
-    async fn update_sticker_state(&self, id: i64, state: StickerState) -> anyhow::Result<()> {
-        tracing::debug!(id, state = ?state, "Update sticker state");
+    async fn update_sticker_type(
+        &self,
+        id: i64,
+        sticker_type: StickerType,
+        content: String,
+    ) -> anyhow::Result<()> {
+        tracing::debug!(id, sticker_type = ?sticker_type, "Update sticker type");
 
         let now = crate::utils::time::now_unix_millis();
 
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("begin update sticker type")?;
+
+        // A conversion may leave parts out, the old content stays in the history.
+        insert_revision_in(&mut tx, id, &content, Some(sticker_type)).await?;
+
+        // The hidden search text belonged to the old type, e.g. OCR output of an image.
         sqlx::query(
             r#"
             UPDATE stickers
-            SET state = ?1,
-                updated_at = ?2
-            WHERE id = ?3
+            SET type = ?1,
+                content = ?2,
+                search_text = '',
+                updated_at = ?3
+            WHERE id = ?4
             "#,
         )
-        .bind(state)
+        .bind(sticker_type)
+        .bind(content)
         .bind(now)
         .bind(id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
-        .context("update sticker state")?;
+        .context("update sticker type")?;
+
+        tx.commit().await.context("commit update sticker type")?;
 
+        self.invalidate_sticker_cache();
         Ok(())
     }
 
-    async fn update_sticker_top_most(&self, id: i64, top_most: bool) -> anyhow::Result<()> {
-        tracing::debug!(id, top_most, "Update sticker top_most");
+    async fn update_sticker_state(&self, id: i64, state: StickerState) -> anyhow::Result<()> {
+        tracing::debug!(id, state = ?state, "Update sticker state");
 
         let now = crate::utils::time::now_unix_millis();
 
         sqlx::query(
             r#"
             UPDATE stickers
-            SET top_most = ?1,
+            SET state = ?1,
+                updated_at = ?2
+            WHERE id = ?3
+            "#,
+        )
+        .bind(state)
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("update sticker state")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn update_sticker_top_most(&self, id: i64, top_most: bool) -> anyhow::Result<()> {
+        tracing::debug!(id, top_most, "Update sticker top_most");
+
+        let now = crate::utils::time::now_unix_millis();
+
+        sqlx::query(
+            r#"
+            UPDATE stickers
+            SET top_most = ?1,
                 updated_at = ?2
             WHERE id = ?3
             "#,
@@ -260,56 +1179,153 @@ impl super::StickerStore for SqliteStore {
         .await
         .context("update sticker top_most")?;
 
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn update_sticker_search_text(&self, id: i64, search_text: String) -> anyhow::Result<()> {
+        tracing::debug!(
+            id,
+            search_text_len = search_text.len(),
+            "Update sticker search text"
+        );
+
+        // Not a user visible change, so keep updated_at untouched.
+        sqlx::query("UPDATE stickers SET search_text = ?1 WHERE id = ?2")
+            .bind(search_text)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("update sticker search text")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn update_sticker_settings(&self, id: i64, settings: String) -> anyhow::Result<()> {
+        tracing::debug!(id, settings = %settings, "Update sticker settings");
+
+        sqlx::query("UPDATE stickers SET settings = ?1 WHERE id = ?2")
+            .bind(settings)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("update sticker settings")?;
+
         Ok(())
     }
 
+    async fn update_stickers_color(
+        &self,
+        search: Option<String>,
+        filter: StickerFilter,
+        color: StickerColor,
+    ) -> anyhow::Result<Vec<i64>> {
+        tracing::debug!(
+            has_search = search.as_ref().map(|s| !s.is_empty()).unwrap_or(false),
+            filter = ?filter,
+            color = color.as_str(),
+            "Update stickers color"
+        );
+
+        let now = crate::utils::time::now_unix_millis();
+
+        let mut builder = QueryBuilder::<Sqlite>::new("UPDATE stickers SET color = ");
+        builder
+            .push_bind(color)
+            .push(", updated_at = ")
+            .push_bind(now);
+        push_filter(&mut builder, search, &filter);
+        builder.push(" RETURNING id");
+
+        let ids = builder
+            .build_query_scalar::<i64>()
+            .fetch_all(&self.pool)
+            .await
+            .context("update stickers color")?;
+
+        self.invalidate_sticker_cache();
+        Ok(ids)
+    }
+
     async fn query_stickers(
         &self,
         search: Option<String>,
+        filter: StickerFilter,
         order_by: StickerOrderBy,
         limit: i64,
         offset: i64,
     ) -> anyhow::Result<Vec<StickerBrief>> {
-        tracing::debug!(has_search = search.as_ref().map(|s| !s.is_empty()).unwrap_or(false), order_by = ?order_by, limit, offset, "Query stickers");
-
-        let search_pattern: Option<String> = search.map(|s| format!("%{}%", s));
-        let order_sql = order_by.to_sql();
-
-        let sql = format!(
-            "SELECT id, title, state, color, type, created_at, updated_at \
-             FROM stickers \
-             WHERE (?1 IS NULL) OR title LIKE ?1 OR content LIKE ?1 \
-             ORDER BY {} \
-             LIMIT ?2 OFFSET ?3",
-            order_sql
-        );
+        tracing::debug!(has_search = search.as_ref().map(|s| !s.is_empty()).unwrap_or(false), filter = ?filter, order_by = ?order_by, limit, offset, "Query stickers");
 
-        let rows = sqlx::query_as::<_, StickerBrief>(&sql)
-            .bind(search_pattern)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&self.pool)
-            .await
-            .context("list stickers")?;
+        let generation = {
+            let cache = self
+                .brief_cache
+                .lock()
+                .map_err(|_| anyhow::anyhow!("sticker cache poisoned"))?;
+            if let Some(entry) = &cache.entry
+                && entry.search == search
+                && entry.bucket == filter.bucket
+            {
+                return Ok(select_briefs(&entry.rows, &filter, order_by, limit, offset));
+            }
+            cache.generation
+        };
 
-        Ok(rows)
+        let all = StickerFilter {
+            color: None,
+            sticker_type: None,
+            collection: None,
+            bucket: filter.bucket,
+        };
+        let rows = self
+            .fetch_briefs(search.clone(), None, &all, order_by, BRIEF_CACHE_LIMIT, 0)
+            .await?;
+        if (rows.len() as i64) >= BRIEF_CACHE_LIMIT {
+            return self
+                .fetch_briefs(search, None, &filter, order_by, limit, offset)
+                .await;
+        }
+
+        let selected = select_briefs(&rows, &filter, order_by, limit, offset);
+        if let Ok(mut cache) = self.brief_cache.lock()
+            && cache.generation == generation
+        {
+            cache.entry = Some(BriefCacheEntry {
+                search,
+                bucket: filter.bucket,
+                rows,
+            });
+        }
+        Ok(selected)
+    }
+
+    fn invalidate_sticker_cache(&self) {
+        if let Ok(mut cache) = self.brief_cache.lock() {
+            cache.generation += 1;
+            cache.entry = None;
+        }
     }
 
-    async fn count_stickers(&self, search: Option<String>) -> anyhow::Result<i64> {
+    async fn count_stickers(
+        &self,
+        search: Option<String>,
+        filter: StickerFilter,
+    ) -> anyhow::Result<i64> {
         tracing::debug!(
             has_search = search.as_ref().map(|s| !s.is_empty()).unwrap_or(false),
+            filter = ?filter,
             "Count stickers"
         );
 
-        let search_pattern: Option<String> = search.map(|s| format!("%{}%", s));
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT COUNT(1) FROM stickers");
+        push_filter(&mut builder, search, &filter);
 
-        let count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(1) FROM stickers WHERE (?1 IS NULL) OR title LIKE ?1 OR content LIKE ?1",
-        )
-        .bind(search_pattern)
-        .fetch_one(&self.pool)
-        .await
-        .context("count stickers")?;
+        let count = builder
+            .build_query_scalar::<i64>()
+            .fetch_one(&self.pool)
+            .await
+            .context("count stickers")?;
 
         Ok(count)
     }
@@ -317,11 +1333,1018 @@ impl super::StickerStore for SqliteStore {
     async fn get_open_sticker_ids(&self) -> anyhow::Result<Vec<i64>> {
         tracing::debug!("Get open sticker ids");
 
-        let rows = sqlx::query_scalar::<_, i64>("SELECT id FROM stickers WHERE state = 'open'")
+        let rows = sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM stickers WHERE state = 'open' AND temporary = 0 \
+             AND archived_at IS NULL AND deleted_at IS NULL",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("get open sticker ids")?;
+
+        Ok(rows)
+    }
+
+    async fn delete_temporary_stickers(&self, now: Option<i64>) -> anyhow::Result<Vec<i64>> {
+        tracing::debug!(now, "Delete temporary stickers");
+
+        let mut builder = QueryBuilder::<Sqlite>::new("DELETE FROM stickers WHERE temporary = 1");
+        if let Some(now) = now {
+            builder.push(" AND expires_at <= ").push_bind(now);
+        }
+        builder.push(" RETURNING id");
+
+        let ids = builder
+            .build_query_scalar::<i64>()
             .fetch_all(&self.pool)
             .await
-            .context("get open sticker ids")?;
+            .context("delete temporary stickers")?;
+
+        self.invalidate_sticker_cache();
+        Ok(ids)
+    }
+
+    async fn archive_stale_stickers(
+        &self,
+        updated_before: i64,
+    ) -> anyhow::Result<Vec<(i64, String)>> {
+        tracing::debug!(updated_before, "Archive stale stickers");
+
+        let now = crate::utils::time::now_unix_millis();
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            "UPDATE stickers SET archived_at = ?1 \
+             WHERE state = 'close' AND temporary = 0 AND archived_at IS NULL \
+             AND deleted_at IS NULL AND updated_at < ?2 \
+             RETURNING id, title",
+        )
+        .bind(now)
+        .bind(updated_before)
+        .fetch_all(&self.pool)
+        .await
+        .context("archive stale stickers")?;
+
+        self.invalidate_sticker_cache();
+        Ok(rows)
+    }
+
+    async fn purge_trash(&self, deleted_before: i64) -> anyhow::Result<Vec<(i64, String)>> {
+        tracing::debug!(deleted_before, "Purge trash");
+
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            "DELETE FROM stickers WHERE deleted_at IS NOT NULL AND deleted_at < ?1 \
+             RETURNING id, title",
+        )
+        .bind(deleted_before)
+        .fetch_all(&self.pool)
+        .await
+        .context("purge trash")?;
+
+        self.invalidate_sticker_cache();
+        Ok(rows)
+    }
+
+    async fn set_sticker_tags(&self, id: i64, tags: Vec<String>) -> anyhow::Result<()> {
+        tracing::debug!(id, tags = ?tags, "Set sticker tags");
+
+        let mut tx = self.pool.begin().await.context("begin set sticker tags")?;
+
+        sqlx::query("DELETE FROM sticker_tags WHERE sticker_id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("clear sticker tags")?;
+
+        for tag in tags.iter().filter_map(|x| normalize_tag(x)) {
+            sqlx::query("INSERT INTO tags (name) VALUES (?1) ON CONFLICT (name) DO NOTHING")
+                .bind(&tag)
+                .execute(&mut *tx)
+                .await
+                .context("insert tag")?;
+
+            sqlx::query(
+                "INSERT OR IGNORE INTO sticker_tags (sticker_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2",
+            )
+            .bind(id)
+            .bind(&tag)
+            .execute(&mut *tx)
+            .await
+            .context("insert sticker tag")?;
+        }
+
+        tx.commit().await.context("commit set sticker tags")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn get_all_sticker_tags(&self) -> anyhow::Result<Vec<(i64, String)>> {
+        tracing::debug!("Get all sticker tags");
+
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            "SELECT st.sticker_id, t.name FROM sticker_tags st JOIN tags t ON t.id = st.tag_id ORDER BY t.name COLLATE NOCASE",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("get all sticker tags")?;
+
+        Ok(rows)
+    }
+
+    async fn get_sticker_tags(&self, id: i64) -> anyhow::Result<Vec<String>> {
+        tracing::debug!(id, "Get sticker tags");
+
+        let rows = sqlx::query_scalar::<_, String>(
+            "SELECT t.name FROM sticker_tags st JOIN tags t ON t.id = st.tag_id WHERE st.sticker_id = ?1 ORDER BY t.name COLLATE NOCASE",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+        .context("get sticker tags")?;
+
+        Ok(rows)
+    }
+
+    async fn get_tags(&self) -> anyhow::Result<Vec<String>> {
+        tracing::debug!("Get tags");
+
+        let rows = sqlx::query_scalar::<_, String>(
+            "SELECT name FROM tags WHERE id IN (SELECT tag_id FROM sticker_tags) ORDER BY name COLLATE NOCASE",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("get tags")?;
+
+        Ok(rows)
+    }
+
+    async fn add_sticker_tag(&self, id: i64, tag: String) -> anyhow::Result<()> {
+        tracing::debug!(id, tag = %tag, "Add sticker tag");
+
+        let tag = normalize_tag(&tag).ok_or_else(|| anyhow::anyhow!("The tag is empty"))?;
+        let mut tx = self.pool.begin().await.context("begin add sticker tag")?;
+
+        sqlx::query("INSERT INTO tags (name) VALUES (?1) ON CONFLICT (name) DO NOTHING")
+            .bind(&tag)
+            .execute(&mut *tx)
+            .await
+            .context("insert tag")?;
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO sticker_tags (sticker_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2",
+        )
+        .bind(id)
+        .bind(&tag)
+        .execute(&mut *tx)
+        .await
+        .context("insert sticker tag")?;
+
+        tx.commit().await.context("commit add sticker tag")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn remove_sticker_tag(&self, id: i64, tag: String) -> anyhow::Result<()> {
+        tracing::debug!(id, tag = %tag, "Remove sticker tag");
+
+        sqlx::query(
+            "DELETE FROM sticker_tags WHERE sticker_id = ?1 AND tag_id IN (SELECT id FROM tags WHERE name = ?2)",
+        )
+        .bind(id)
+        .bind(tag.trim())
+        .execute(&self.pool)
+        .await
+        .context("remove sticker tag")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn rename_tag(&self, name: String, new_name: String) -> anyhow::Result<()> {
+        tracing::debug!(name = %name, new_name = %new_name, "Rename tag");
+
+        let new_name =
+            normalize_tag(&new_name).ok_or_else(|| anyhow::anyhow!("The new tag is empty"))?;
+        let mut tx = self.pool.begin().await.context("begin rename tag")?;
+
+        // Names compare without case, so only the spelling changes.
+        if name.trim().eq_ignore_ascii_case(&new_name) {
+            sqlx::query("UPDATE tags SET name = ?2 WHERE name = ?1")
+                .bind(name.trim())
+                .bind(&new_name)
+                .execute(&mut *tx)
+                .await
+                .context("update tag name")?;
+        } else {
+            sqlx::query("INSERT INTO tags (name) VALUES (?1) ON CONFLICT (name) DO NOTHING")
+                .bind(&new_name)
+                .execute(&mut *tx)
+                .await
+                .context("insert tag")?;
+
+            sqlx::query(
+                "INSERT OR IGNORE INTO sticker_tags (sticker_id, tag_id) \
+                 SELECT st.sticker_id, n.id FROM sticker_tags st \
+                 JOIN tags o ON o.id = st.tag_id JOIN tags n ON n.name = ?2 \
+                 WHERE o.name = ?1",
+            )
+            .bind(name.trim())
+            .bind(&new_name)
+            .execute(&mut *tx)
+            .await
+            .context("move sticker tags")?;
+
+            delete_tag_in(&mut tx, name.trim()).await?;
+        }
+
+        tx.commit().await.context("commit rename tag")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn delete_tag(&self, name: String) -> anyhow::Result<()> {
+        tracing::debug!(name = %name, "Delete tag");
+
+        let mut tx = self.pool.begin().await.context("begin delete tag")?;
+        delete_tag_in(&mut tx, name.trim()).await?;
+        tx.commit().await.context("commit delete tag")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn get_collections(&self) -> anyhow::Result<Vec<Collection>> {
+        tracing::debug!("Get collections");
+
+        let rows = sqlx::query_as::<_, Collection>(
+            "SELECT c.id, c.name, COUNT(s.id) AS sticker_count FROM collections c \
+             LEFT JOIN stickers s ON s.collection_id = c.id AND s.temporary = 0 \
+             AND s.archived_at IS NULL AND s.deleted_at IS NULL \
+             GROUP BY c.id ORDER BY c.name COLLATE NOCASE",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("get collections")?;
+
+        Ok(rows)
+    }
+
+    async fn create_collection(&self, name: String) -> anyhow::Result<i64> {
+        tracing::debug!(name = %name, "Create collection");
+
+        let name = name.trim();
+        anyhow::ensure!(!name.is_empty(), "The collection name is empty");
+        let now = crate::utils::time::now_unix_millis();
+
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO collections (name, created_at) VALUES (?1, ?2) \
+             ON CONFLICT (name) DO UPDATE SET name = name RETURNING id",
+        )
+        .bind(name)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .context("create collection")?;
+
+        Ok(id)
+    }
+
+    async fn delete_collection(&self, id: i64) -> anyhow::Result<()> {
+        tracing::debug!(id, "Delete collection");
+
+        // Not relying on `ON DELETE SET NULL`.
+        let mut tx = self.pool.begin().await.context("begin delete collection")?;
+        sqlx::query("UPDATE stickers SET collection_id = NULL WHERE collection_id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("clear sticker collection")?;
+        sqlx::query("DELETE FROM collections WHERE id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("delete collection")?;
+        tx.commit().await.context("commit delete collection")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn set_sticker_collection(
+        &self,
+        id: i64,
+        collection_id: Option<i64>,
+    ) -> anyhow::Result<()> {
+        tracing::debug!(id, collection_id = ?collection_id, "Set sticker collection");
+
+        sqlx::query("UPDATE stickers SET collection_id = ?2 WHERE id = ?1")
+            .bind(id)
+            .bind(collection_id)
+            .execute(&self.pool)
+            .await
+            .context("set sticker collection")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn set_sticker_pinned(&self, id: i64, pinned: bool) -> anyhow::Result<()> {
+        tracing::debug!(id, pinned, "Set sticker pinned");
+
+        sqlx::query("UPDATE stickers SET pinned = ?2 WHERE id = ?1")
+            .bind(id)
+            .bind(pinned)
+            .execute(&self.pool)
+            .await
+            .context("set sticker pinned")?;
+
+        self.invalidate_sticker_cache();
+        Ok(())
+    }
+
+    async fn get_sticker_stack(&self, id: i64) -> anyhow::Result<Option<Stack>> {
+        tracing::debug!(id, "Get sticker stack");
+
+        let stack = sqlx::query_as::<_, Stack>(
+            "SELECT k.id, k.collapsed, k.active_id FROM stacks k \
+             JOIN stickers s ON s.stack_id = k.id WHERE s.id = ?1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("get sticker stack")?;
+        let Some(mut stack) = stack else {
+            return Ok(None);
+        };
+
+        stack.members = sqlx::query_as::<_, StackMember>(
+            "SELECT id, title FROM stickers WHERE stack_id = ?1 \
+             AND archived_at IS NULL AND deleted_at IS NULL ORDER BY id",
+        )
+        .bind(stack.id)
+        .fetch_all(&self.pool)
+        .await
+        .context("get stack members")?;
+
+        Ok(Some(stack))
+    }
+
+    async fn stack_stickers(&self, id: i64, other_id: i64) -> anyhow::Result<i64> {
+        tracing::debug!(id, other_id, "Stack stickers");
+
+        let mut tx = self.pool.begin().await.context("begin stack stickers")?;
+        let existing =
+            sqlx::query_scalar::<_, Option<i64>>("SELECT stack_id FROM stickers WHERE id = ?1")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("get sticker stack id")?;
+        let stack_id = match existing {
+            Some(stack_id) => stack_id,
+            None => {
+                let stack_id = sqlx::query_scalar::<_, i64>(
+                    "INSERT INTO stacks (collapsed, active_id) VALUES (0, ?1) RETURNING id",
+                )
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("insert stack")?;
+                sqlx::query("UPDATE stickers SET stack_id = ?2 WHERE id = ?1")
+                    .bind(id)
+                    .bind(stack_id)
+                    .execute(&mut *tx)
+                    .await
+                    .context("set sticker stack")?;
+                stack_id
+            }
+        };
+
+        // The whole stack of the other sticker comes along, its stack is dropped then.
+        let other_stack =
+            sqlx::query_scalar::<_, Option<i64>>("SELECT stack_id FROM stickers WHERE id = ?1")
+                .bind(other_id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("get other sticker stack id")?;
+        match other_stack {
+            Some(other_stack) if other_stack != stack_id => {
+                sqlx::query("UPDATE stickers SET stack_id = ?2 WHERE stack_id = ?1")
+                    .bind(other_stack)
+                    .bind(stack_id)
+                    .execute(&mut *tx)
+                    .await
+                    .context("merge stacks")?;
+                sqlx::query("DELETE FROM stacks WHERE id = ?1")
+                    .bind(other_stack)
+                    .execute(&mut *tx)
+                    .await
+                    .context("delete merged stack")?;
+            }
+            Some(_) => {}
+            None => {
+                sqlx::query("UPDATE stickers SET stack_id = ?2 WHERE id = ?1")
+                    .bind(other_id)
+                    .bind(stack_id)
+                    .execute(&mut *tx)
+                    .await
+                    .context("join stack")?;
+            }
+        }
+        tx.commit().await.context("commit stack stickers")?;
+
+        Ok(stack_id)
+    }
+
+    async fn unstack_sticker(&self, id: i64) -> anyhow::Result<()> {
+        tracing::debug!(id, "Unstack sticker");
+
+        let mut tx = self.pool.begin().await.context("begin unstack sticker")?;
+        let stack_id =
+            sqlx::query_scalar::<_, Option<i64>>("SELECT stack_id FROM stickers WHERE id = ?1")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("get sticker stack id")?;
+        let Some(stack_id) = stack_id else {
+            return Ok(());
+        };
+
+        sqlx::query("UPDATE stickers SET stack_id = NULL WHERE id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("clear sticker stack")?;
+        let left =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(1) FROM stickers WHERE stack_id = ?1")
+                .bind(stack_id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("count stack members")?;
+        if left < 2 {
+            sqlx::query("UPDATE stickers SET stack_id = NULL WHERE stack_id = ?1")
+                .bind(stack_id)
+                .execute(&mut *tx)
+                .await
+                .context("dissolve stack")?;
+            sqlx::query("DELETE FROM stacks WHERE id = ?1")
+                .bind(stack_id)
+                .execute(&mut *tx)
+                .await
+                .context("delete stack")?;
+        }
+        tx.commit().await.context("commit unstack sticker")?;
+
+        Ok(())
+    }
+
+    async fn update_stack(
+        &self,
+        stack_id: i64,
+        collapsed: bool,
+        active_id: Option<i64>,
+    ) -> anyhow::Result<()> {
+        tracing::debug!(stack_id, collapsed, active_id = ?active_id, "Update stack");
+
+        sqlx::query("UPDATE stacks SET collapsed = ?2, active_id = ?3 WHERE id = ?1")
+            .bind(stack_id)
+            .bind(collapsed)
+            .bind(active_id)
+            .execute(&self.pool)
+            .await
+            .context("update stack")?;
+
+        Ok(())
+    }
+
+    async fn query_stickers_by_tag(
+        &self,
+        tag: String,
+        search: Option<String>,
+        filter: StickerFilter,
+        order_by: StickerOrderBy,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<StickerBrief>> {
+        tracing::debug!(tag = %tag, filter = ?filter, order_by = ?order_by, limit, offset, "Query stickers by tag");
+
+        self.fetch_briefs(search, Some(tag.trim()), &filter, order_by, limit, offset)
+            .await
+    }
+
+    async fn insert_command_run(&self, run: CommandRun) -> anyhow::Result<i64> {
+        tracing::debug!(sticker_id = run.sticker_id, exit_code = ?run.exit_code, "Insert command run");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("begin insert command run")?;
+        let row = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO command_runs (sticker_id, started_at, duration_ms, exit_code, value)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            RETURNING id
+            "#,
+        )
+        .bind(run.sticker_id)
+        .bind(run.started_at)
+        .bind(run.duration_ms)
+        .bind(run.exit_code)
+        .bind(run.value)
+        .fetch_one(&mut *tx)
+        .await
+        .context("insert command run")?;
+
+        sqlx::query("DELETE FROM command_runs WHERE sticker_id = ?1 AND started_at < ?2")
+            .bind(run.sticker_id)
+            .bind(run.started_at - COMMAND_RUN_MAX_AGE)
+            .execute(&mut *tx)
+            .await
+            .context("delete old command runs")?;
+        tx.commit().await.context("commit insert command run")?;
+
+        Ok(row)
+    }
+
+    async fn get_command_runs(&self, sticker_id: i64) -> anyhow::Result<Vec<CommandRun>> {
+        tracing::debug!(sticker_id, "Get command runs");
+
+        let rows = sqlx::query_as::<_, CommandRun>(
+            "SELECT id, sticker_id, started_at, duration_ms, exit_code, value FROM command_runs WHERE sticker_id = ?1 ORDER BY started_at ASC",
+        )
+        .bind(sticker_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("get command runs")?;
+
+        Ok(rows)
+    }
+
+    async fn get_command_runs_since(&self, since: i64) -> anyhow::Result<Vec<CommandRun>> {
+        tracing::debug!(since, "Get command runs since");
+
+        let rows = sqlx::query_as::<_, CommandRun>(
+            "SELECT id, sticker_id, started_at, duration_ms, exit_code, value FROM command_runs WHERE started_at >= ?1 ORDER BY started_at ASC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .context("get command runs since")?;
+
+        Ok(rows)
+    }
+
+    async fn get_sticker_revisions(&self, sticker_id: i64) -> anyhow::Result<Vec<StickerRevision>> {
+        tracing::debug!(sticker_id, "Get sticker revisions");
+
+        let rows = sqlx::query_as::<_, StickerRevision>(
+            "SELECT id, sticker_id, type, content, created_at FROM sticker_revisions WHERE sticker_id = ?1 ORDER BY id DESC",
+        )
+        .bind(sticker_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("get sticker revisions")?;
+
+        Ok(rows)
+    }
+
+    async fn add_attachment(
+        &self,
+        sticker_id: i64,
+        name: String,
+        mime: String,
+        data: Vec<u8>,
+    ) -> anyhow::Result<i64> {
+        tracing::debug!(sticker_id, name = %name, size = data.len(), "Add attachment");
+
+        let hash = Sha256::digest(&data)
+            .iter()
+            .map(|x| format!("{x:02x}"))
+            .collect::<String>();
+        let now = crate::utils::time::now_unix_millis();
+
+        // The transaction holds the only connection, so a concurrent collection of
+        // orphans can not remove the file before the attachment refers to it.
+        let mut tx = self.pool.begin().await.context("begin add attachment")?;
+
+        // The same data added again, e.g. the picture pasted twice, keeps its attachment.
+        let existing = sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM attachments WHERE sticker_id = ?1 AND hash = ?2",
+        )
+        .bind(sticker_id)
+        .bind(&hash)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("look up attachment")?;
+        if let Some(id) = existing {
+            tx.commit().await.context("commit add attachment")?;
+            return Ok(id);
+        }
+
+        self.write_attachment_file(&hash, &data)?;
+        sqlx::query(
+            r#"
+            INSERT INTO attachment_blobs (hash, size, created_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT (hash) DO NOTHING
+            "#,
+        )
+        .bind(&hash)
+        .bind(data.len() as i64)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .context("add attachment blob")?;
+
+        let row = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO attachments (sticker_id, name, mime, size, hash, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            RETURNING id
+            "#,
+        )
+        .bind(sticker_id)
+        .bind(name)
+        .bind(mime)
+        .bind(data.len() as i64)
+        .bind(&hash)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await
+        .context("add attachment")?;
+
+        tx.commit().await.context("commit add attachment")?;
+
+        Ok(row)
+    }
+
+    async fn copy_attachments(
+        &self,
+        from_sticker_id: i64,
+        to_sticker_id: i64,
+    ) -> anyhow::Result<()> {
+        tracing::debug!(from_sticker_id, to_sticker_id, "Copy attachments");
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO attachments (sticker_id, name, mime, size, hash, created_at)
+            SELECT ?2, name, mime, size, hash, created_at FROM attachments WHERE sticker_id = ?1
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(from_sticker_id)
+        .bind(to_sticker_id)
+        .execute(&self.pool)
+        .await
+        .context("copy attachments")?;
+
+        Ok(())
+    }
+
+    async fn get_attachment(&self, id: i64) -> anyhow::Result<Attachment> {
+        tracing::debug!(id, "Get attachment");
+
+        let row = sqlx::query_as::<_, Attachment>(
+            "SELECT id, sticker_id, name, mime, size, hash, created_at FROM attachments WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .context("get attachment")?;
+
+        Ok(row)
+    }
+
+    async fn get_attachment_data(&self, id: i64) -> anyhow::Result<Vec<u8>> {
+        tracing::debug!(id, "Get attachment data");
+
+        let (hash, data) = sqlx::query_as::<_, (String, Option<Vec<u8>>)>(
+            "SELECT b.hash, b.data FROM attachments a JOIN attachment_blobs b ON b.hash = a.hash WHERE a.id = ?1",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .context("get attachment data")?;
+
+        // Still in the DB when it was opened read-only since the upgrade.
+        if let Some(data) = data {
+            return Ok(data);
+        }
+        let path = self.attachment_path(&hash);
+        fs::read(&path).with_context(|| format!("read attachment {}", path.display()))
+    }
+
+    async fn get_sticker_attachments(&self, sticker_id: i64) -> anyhow::Result<Vec<Attachment>> {
+        tracing::debug!(sticker_id, "Get sticker attachments");
+
+        let rows = sqlx::query_as::<_, Attachment>(
+            "SELECT id, sticker_id, name, mime, size, hash, created_at FROM attachments WHERE sticker_id = ?1 ORDER BY id ASC",
+        )
+        .bind(sticker_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("get sticker attachments")?;
+
+        Ok(rows)
+    }
+
+    async fn delete_attachment(&self, id: i64) -> anyhow::Result<()> {
+        tracing::debug!(id, "Delete attachment");
+
+        sqlx::query("DELETE FROM attachments WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("delete attachment")?;
+
+        Ok(())
+    }
+
+    async fn collect_orphan_attachments(&self) -> anyhow::Result<AttachmentCollection> {
+        tracing::debug!("Collect orphan attachments");
+
+        // Held while the files are removed, see `add_attachment`.
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("begin collect orphan attachments")?;
+
+        let blobs = sqlx::query_scalar::<_, String>(
+            "DELETE FROM attachment_blobs WHERE ref_count <= 0 RETURNING hash",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("delete orphan attachment blobs")?
+        .len();
+        let mut collection = AttachmentCollection {
+            blobs,
+            ..Default::default()
+        };
+
+        let kept = sqlx::query_scalar::<_, String>("SELECT hash FROM attachment_blobs")
+            .fetch_all(&mut *tx)
+            .await
+            .context("get attachment blobs")?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        // Files of deleted blobs, and those left by a failed add or a crash.
+        let folders = match fs::read_dir(&self.attachments_dir) {
+            Ok(folders) => folders.filter_map(|x| x.ok()).map(|x| x.path()).collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err).context("read attachments directory"),
+        };
+        for folder in folders {
+            let Ok(files) = fs::read_dir(&folder) else {
+                continue;
+            };
+            for file in files.filter_map(|x| x.ok()) {
+                let name = file.file_name().to_string_lossy().to_string();
+                if !kept.contains(&name) {
+                    remove_orphan_file(&file, &mut collection);
+                }
+            }
+            // Only succeeds once the folder is empty.
+            let _ = fs::remove_dir(&folder);
+        }
+
+        // Pictures of older versions, of stickers which are gone or moved them into
+        // attachments. Kept while a sticker or its history still names them.
+        let images = file_names(&self.images_dir).context("read images directory")?;
+        let names = images
+            .iter()
+            .map(|x| x.file_name().to_string_lossy().to_string())
+            .collect::<HashSet<_>>();
+        let referenced = referenced_file_names(&mut *tx, &names).await?;
+        for file in images {
+            if !referenced.contains(file.file_name().to_string_lossy().as_ref()) {
+                remove_orphan_file(&file, &mut collection);
+            }
+        }
+
+        tx.commit()
+            .await
+            .context("commit collect orphan attachments")?;
+
+        Ok(collection)
+    }
+
+    async fn insert_focus_session(&self, session: FocusSession) -> anyhow::Result<i64> {
+        tracing::debug!(sticker_id = ?session.sticker_id, "Insert focus session");
+
+        let row = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO focus_sessions (sticker_id, finished_at, duration_ms)
+            VALUES (?1, ?2, ?3)
+            RETURNING id
+            "#,
+        )
+        .bind(session.sticker_id)
+        .bind(session.finished_at)
+        .bind(session.duration_ms)
+        .fetch_one(&self.pool)
+        .await
+        .context("insert focus session")?;
+
+        Ok(row)
+    }
+
+    async fn get_focus_sessions_since(&self, since: i64) -> anyhow::Result<Vec<FocusSession>> {
+        tracing::debug!(since, "Get focus sessions since");
+
+        let rows = sqlx::query_as::<_, FocusSession>(
+            "SELECT id, sticker_id, finished_at, duration_ms FROM focus_sessions WHERE finished_at >= ?1 ORDER BY finished_at ASC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .context("get focus sessions since")?;
+
+        Ok(rows)
+    }
+
+    async fn insert_time_entry(&self, entry: TimeEntry) -> anyhow::Result<i64> {
+        tracing::debug!(sticker_id = ?entry.sticker_id, project = %entry.project, "Insert time entry");
+
+        let row = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO time_entries (sticker_id, project, task, started_at, ended_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            RETURNING id
+            "#,
+        )
+        .bind(entry.sticker_id)
+        .bind(entry.project)
+        .bind(entry.task)
+        .bind(entry.started_at)
+        .bind(entry.ended_at)
+        .fetch_one(&self.pool)
+        .await
+        .context("insert time entry")?;
+
+        Ok(row)
+    }
+
+    async fn get_time_entries_since(&self, since: i64) -> anyhow::Result<Vec<TimeEntry>> {
+        tracing::debug!(since, "Get time entries since");
+
+        let rows = sqlx::query_as::<_, TimeEntry>(
+            "SELECT id, sticker_id, project, task, started_at, ended_at FROM time_entries WHERE ended_at >= ?1 ORDER BY started_at ASC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .context("get time entries since")?;
+
+        Ok(rows)
+    }
+
+    async fn get_stickers_updated_since(&self, since: i64) -> anyhow::Result<Vec<StickerDetail>> {
+        tracing::debug!(since, "Get stickers updated since");
+
+        let rows = sqlx::query_as::<_, StickerDetail>(
+            "SELECT id, title, state, left, top, width, height, top_most, color, type, content, settings, temporary, expires_at, created_at, updated_at FROM stickers \
+             WHERE updated_at >= ?1 AND temporary = 0 AND deleted_at IS NULL \
+             ORDER BY updated_at DESC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .context("get stickers updated since")?;
+
+        Ok(rows)
+    }
+
+    async fn get_sticker_update_times(&self) -> anyhow::Result<Vec<(i64, i64)>> {
+        tracing::debug!("Get sticker update times");
+
+        let rows = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT id, updated_at FROM stickers WHERE temporary = 0 AND deleted_at IS NULL",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("get sticker update times")?;
+
+        Ok(rows)
+    }
+
+    async fn get_synced_files(&self, folder: String) -> anyhow::Result<Vec<SyncedFile>> {
+        tracing::debug!(folder = %folder, "Get synced files");
+
+        let rows = sqlx::query_as::<_, SyncedFile>(
+            "SELECT folder, sticker_id, file_name, hash FROM synced_files WHERE folder = ?1",
+        )
+        .bind(folder)
+        .fetch_all(&self.pool)
+        .await
+        .context("get synced files")?;
 
         Ok(rows)
     }
+
+    async fn set_synced_file(&self, file: SyncedFile) -> anyhow::Result<()> {
+        tracing::debug!(sticker_id = file.sticker_id, file_name = %file.file_name, "Set synced file");
+
+        sqlx::query(
+            r#"
+            INSERT INTO synced_files (folder, sticker_id, file_name, hash)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (folder, sticker_id) DO UPDATE SET file_name = excluded.file_name, hash = excluded.hash
+            "#,
+        )
+        .bind(file.folder)
+        .bind(file.sticker_id)
+        .bind(file.file_name)
+        .bind(file.hash)
+        .execute(&self.pool)
+        .await
+        .context("set synced file")?;
+
+        Ok(())
+    }
+
+    async fn delete_synced_file(&self, folder: String, sticker_id: i64) -> anyhow::Result<()> {
+        tracing::debug!(folder = %folder, sticker_id, "Delete synced file");
+
+        sqlx::query("DELETE FROM synced_files WHERE folder = ?1 AND sticker_id = ?2")
+            .bind(folder)
+            .bind(sticker_id)
+            .execute(&self.pool)
+            .await
+            .context("delete synced file")?;
+
+        Ok(())
+    }
+
+    async fn find_sticker_by_title(
+        &self,
+        title: String,
+        sticker_type: StickerType,
+    ) -> anyhow::Result<Option<i64>> {
+        tracing::debug!(title = %title, sticker_type = ?sticker_type, "Find sticker by title");
+
+        let id = sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM stickers WHERE title = ?1 AND type = ?2 AND deleted_at IS NULL ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(title)
+        .bind(sticker_type)
+        .fetch_optional(&self.pool)
+        .await
+        .context("find sticker by title")?;
+
+        Ok(id)
+    }
+
+    async fn find_sticker_by_content(
+        &self,
+        content: String,
+        sticker_type: StickerType,
+    ) -> anyhow::Result<Option<i64>> {
+        tracing::debug!(sticker_type = ?sticker_type, "Find sticker by content");
+
+        let id = sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM stickers WHERE type = ?2 AND trim(content, ' ' || char(9, 10, 13)) = ?1 AND deleted_at IS NULL ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(content.trim())
+        .bind(sticker_type)
+        .fetch_optional(&self.pool)
+        .await
+        .context("find sticker by content")?;
+
+        Ok(id)
+    }
+
+    async fn backup_to(&self, path: PathBuf) -> anyhow::Result<()> {
+        tracing::debug!(path = %path.display(), "Back up database");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context("create backups directory")?;
+        }
+        snapshot(&self.pool, &path).await
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    async fn data_version(&self) -> anyhow::Result<i64> {
+        // The pool holds a single connection, so the value is comparable between calls.
+        let version = sqlx::query_scalar::<_, i64>("PRAGMA data_version")
+            .fetch_one(&self.pool)
+            .await
+            .context("read data version")?;
+
+        Ok(version)
+    }
+
+    async fn rebuild_search_index(&self) -> anyhow::Result<()> {
+        tracing::debug!("Rebuild search index");
+
+        sqlx::query("INSERT INTO stickers_fts (stickers_fts) VALUES ('rebuild')")
+            .execute(&self.pool)
+            .await
+            .context("rebuild search index")?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,93 @@
+use anyhow::Context as _;
+use serde::Deserialize;
+
+use crate::model::sticker::*;
+use crate::storage::paths::AppPaths;
+use crate::utils::template::{builtin_vars, render_template};
+
+/// A parametrized sticker stored as `<templates dir>/<name>.yaml`.
+///
+/// ```yaml
+/// type: markdown
+/// title: "Incident: {service}"
+/// color: pink
+/// tags: [incident, "{service}"]
+/// content: |
+///   ## {service} incident ({date})
+///   - [ ] Page on-call
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct StickerTemplate {
+    #[serde(rename = "type")]
+    pub sticker_type: StickerType,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub width: Option<i32>,
+    #[serde(default)]
+    pub height: Option<i32>,
+}
+
+impl StickerTemplate {
+    pub fn load(app_paths: &AppPaths, name: &str) -> anyhow::Result<Self> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            anyhow::bail!("Invalid template name: {name}");
+        }
+
+        let path = app_paths.templates_dir().join(format!("{name}.yaml"));
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("read template {}", path.display()))?;
+
+        serde_yaml::from_str(&text).with_context(|| format!("parse template {}", path.display()))
+    }
+
+    pub fn render_tags(&self, vars: &[(String, String)]) -> Vec<String> {
+        let mut all_vars = vars.to_vec();
+        all_vars.extend(builtin_vars());
+
+        self.tags
+            .iter()
+            .map(|tag| render_template(tag, &all_vars))
+            .collect()
+    }
+
+    /// Build a new sticker from the template, the user vars override builtin ones.
+    pub fn instantiate(&self, vars: &[(String, String)]) -> StickerDetail {
+        let mut all_vars = vars.to_vec();
+        all_vars.extend(builtin_vars());
+
+        StickerDetail {
+            id: 0,
+            title: render_template(&self.title, &all_vars),
+            state: StickerState::Open,
+            left: 100,
+            top: 100,
+            // Zero means the default window size of the sticker type.
+            width: self.width.unwrap_or(0),
+            height: self.height.unwrap_or(0),
+            top_most: false,
+            color: self
+                .color
+                .as_deref()
+                .and_then(|x| x.parse().ok())
+                .unwrap_or(StickerColor::Yellow),
+            sticker_type: self.sticker_type,
+            content: render_template(&self.content, &all_vars),
+            settings: String::new(),
+            temporary: false,
+            expires_at: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+}
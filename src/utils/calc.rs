@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+
+/// Name of the variable holding the last result.
+pub const ANSWER: &str = "ans";
+
+/// The value of a line, `name` is set when the line assigned it to a variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub name: Option<String>,
+    pub value: f64,
+}
+
+/// Evaluate `expression` or `name = expression` with the given variables.
+///
+/// Supports `+ - * / % ^`, parentheses, unary minus, `sqrt`, `abs`, `round`, `floor`,
+/// `ceil`, `ln`, `log`, `sin`, `cos`, `tan` and the constants `pi` and `e`.
+pub fn evaluate(line: &str, vars: &BTreeMap<String, f64>) -> anyhow::Result<Evaluation> {
+    let (name, expression) = match split_assignment(line) {
+        Some((name, expression)) => (Some(name.to_string()), expression),
+        None => (None, line),
+    };
+
+    let tokens = tokenize(expression)?;
+    anyhow::ensure!(!tokens.is_empty(), "Nothing to calculate");
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        vars,
+    };
+    let value = parser.expression()?;
+    if let Some(token) = parser.peek() {
+        anyhow::bail!("Unexpected {token}");
+    }
+    anyhow::ensure!(value.is_finite(), "The result is not a finite number");
+
+    Ok(Evaluation { name, value })
+}
+
+/// "12.5", "1000000", "0.333333333333", no exponent for everyday numbers.
+pub fn format_number(value: f64) -> String {
+    if value == value.trunc() && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    if value.abs() >= 1e15 || value.abs() < 1e-9 {
+        return format!("{value:e}");
+    }
+    let text = format!("{value:.12}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// `x = 5` -> ("x", "5"), but not `x == 5` or `5 = x`.
+fn split_assignment(line: &str) -> Option<(&str, &str)> {
+    let (name, expression) = line.split_once('=')?;
+    let name = name.trim();
+    let is_name = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    (is_name && !expression.starts_with('=')).then_some((name, expression))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "{}", format_number(*value)),
+            Token::Name(name) => write!(f, "{name}"),
+            Token::Op(op) => write!(f, "{op}"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                // Digit separators are skipped, "1_000" is 1000.
+                if c.is_ascii_digit() || c == '.' || c == '_' {
+                    if c != '_' {
+                        number.push(c);
+                    }
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let value = number
+                .parse::<f64>()
+                .map_err(|_| anyhow::anyhow!("Invalid number {number}"))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    name.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Name(name));
+        } else if "+-*/%^()×÷".contains(c) {
+            let op = match c {
+                '×' => '*',
+                '÷' => '/',
+                c => c,
+            };
+            tokens.push(Token::Op(op));
+            chars.next();
+        } else {
+            anyhow::bail!("Unexpected {c}");
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent, one method per precedence level.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    vars: &'a BTreeMap<String, f64>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// `term (('+' | '-') term)*`
+    fn expression(&mut self) -> anyhow::Result<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// `unary (('*' | '/' | '%') unary)*`
+    fn term(&mut self) -> anyhow::Result<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                let divisor = self.unary()?;
+                anyhow::ensure!(divisor != 0.0, "Division by zero");
+                value /= divisor;
+            } else if self.eat('%') {
+                let divisor = self.unary()?;
+                anyhow::ensure!(divisor != 0.0, "Division by zero");
+                value %= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// `'-' unary | power`, so `-2^2` is -4.
+    fn unary(&mut self) -> anyhow::Result<f64> {
+        if self.eat('-') {
+            return Ok(-self.unary()?);
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    /// `atom ('^' unary)?`, right associative.
+    fn power(&mut self) -> anyhow::Result<f64> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            return Ok(base.powf(self.unary()?));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> anyhow::Result<f64> {
+        match self.next().cloned() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Op('(')) => {
+                let value = self.expression()?;
+                anyhow::ensure!(self.eat(')'), "Missing )");
+                Ok(value)
+            }
+            Some(Token::Name(name)) => {
+                if self.peek() == Some(&Token::Op('(')) {
+                    let argument = self.atom()?;
+                    return call(&name, argument);
+                }
+                if let Some(value) = self.vars.get(&name) {
+                    return Ok(*value);
+                }
+                match name.as_str() {
+                    "pi" => Ok(std::f64::consts::PI),
+                    "e" => Ok(std::f64::consts::E),
+                    _ => anyhow::bail!("Unknown variable {name}"),
+                }
+            }
+            Some(token) => anyhow::bail!("Unexpected {token}"),
+            None => anyhow::bail!("Unexpected end of expression"),
+        }
+    }
+}
+
+fn call(function: &str, argument: f64) -> anyhow::Result<f64> {
+    Ok(match function {
+        "sqrt" => {
+            anyhow::ensure!(argument >= 0.0, "Square root of a negative number");
+            argument.sqrt()
+        }
+        "abs" => argument.abs(),
+        "round" => argument.round(),
+        "floor" => argument.floor(),
+        "ceil" => argument.ceil(),
+        "ln" => argument.ln(),
+        "log" => argument.log10(),
+        "sin" => argument.sin(),
+        "cos" => argument.cos(),
+        "tan" => argument.tan(),
+        _ => anyhow::bail!("Unknown function {function}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(line: &str) -> anyhow::Result<f64> {
+        evaluate(line, &BTreeMap::new()).map(|x| x.value)
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("1 + 2 * 3").unwrap(), 7.0);
+        assert_eq!(eval("(1 + 2) * 3").unwrap(), 9.0);
+        assert_eq!(eval("10 - 4 - 3").unwrap(), 3.0);
+        assert_eq!(eval("2 ^ 3 ^ 2").unwrap(), 512.0);
+        assert_eq!(eval("7 % 4 * 2").unwrap(), 6.0);
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval("-3 + 5").unwrap(), 2.0);
+        assert_eq!(eval("-2 ^ 2").unwrap(), -4.0);
+        assert_eq!(eval("2 ^ -1").unwrap(), 0.5);
+        assert_eq!(eval("--4").unwrap(), 4.0);
+        assert_eq!(eval("3 * -(1 + 1)").unwrap(), -6.0);
+    }
+
+    #[test]
+    fn variables() {
+        let vars = BTreeMap::from([("x".to_string(), 4.0), (ANSWER.to_string(), 10.0)]);
+
+        let assigned = evaluate("y = x * 2", &vars).unwrap();
+        assert_eq!(assigned.name.as_deref(), Some("y"));
+        assert_eq!(assigned.value, 8.0);
+
+        assert_eq!(evaluate("ans / x", &vars).unwrap().value, 2.5);
+        assert_eq!(
+            evaluate("sqrt(x) + pi", &vars).unwrap().value,
+            2.0 + std::f64::consts::PI
+        );
+        assert!(evaluate("z + 1", &vars).is_err());
+    }
+
+    #[test]
+    fn comparison_is_not_assignment() {
+        assert!(split_assignment("x == 5").is_none());
+        assert!(split_assignment("5 = x").is_none());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(eval("").is_err());
+        assert!(eval("1 +").is_err());
+        assert!(eval("(1 + 2").is_err());
+        assert!(eval("1 2").is_err());
+        assert!(eval("2 $ 3").is_err());
+        assert!(eval("nope(1)").is_err());
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(eval("1 / 0").unwrap_err().to_string(), "Division by zero");
+        assert_eq!(eval("5 % 0").unwrap_err().to_string(), "Division by zero");
+        assert!(eval("sqrt(-1)").is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! Turn CSV or JSON text into numeric series for charts.
+//!
+//! Accepted shapes:
+//! - CSV/TSV with a header row, e.g. `day,cpu,mem`; a non numeric first column labels the rows
+//! - a JSON array of objects, e.g. `[{"day": "Mon", "cpu": 12}]`
+//! - a JSON array of numbers, a single series named `value`
+//! - a JSON object of number arrays, e.g. `{"cpu": [1, 2], "mem": [3, 4]}`
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default)]
+pub struct Dataset {
+    /// One label per row, the row number when the data has none.
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub name: String,
+    /// `None` where the row has no number for the series.
+    pub values: Vec<Option<f64>>,
+}
+
+impl Dataset {
+    /// Smallest and largest value of the named series, `None` without any value.
+    pub fn range<'a>(&self, names: impl Iterator<Item = &'a str> + Clone) -> Option<(f64, f64)> {
+        let mut values = self
+            .series
+            .iter()
+            .filter(|s| names.clone().any(|name| name == s.name))
+            .flat_map(|s| s.values.iter().flatten().copied());
+        let first = values.next()?;
+        Some(values.fold((first, first), |(min, max), x| (min.min(x), max.max(x))))
+    }
+}
+
+pub fn parse(text: &str) -> anyhow::Result<Dataset> {
+    let text = text.trim();
+    anyhow::ensure!(!text.is_empty(), "No data");
+
+    let dataset = if text.starts_with('[') || text.starts_with('{') {
+        parse_json(text)?
+    } else {
+        parse_csv(text)
+    };
+    anyhow::ensure!(
+        !dataset.series.is_empty() && !dataset.labels.is_empty(),
+        "No numeric columns found"
+    );
+    Ok(dataset)
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    let text = text.trim().trim_matches('"').trim().trim_end_matches('%');
+    text.parse::<f64>().ok().filter(|x| x.is_finite())
+}
+
+fn parse_csv(text: &str) -> Dataset {
+    let separator = if text.contains('\t') { '\t' } else { ',' };
+    let mut rows = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.split(separator)
+                .map(|cell| cell.trim().trim_matches('"').to_string())
+                .collect::<Vec<_>>()
+        });
+
+    let Some(header) = rows.next() else {
+        return Dataset::default();
+    };
+    let rows = rows.collect::<Vec<_>>();
+
+    // The first column labels the rows when it is not a number, e.g. a date.
+    let labeled = rows
+        .iter()
+        .any(|row| row.first().is_some_and(|x| parse_number(x).is_none()));
+    let first_value_column = usize::from(labeled);
+
+    let labels = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| match labeled {
+            true => row.first().cloned().unwrap_or_default(),
+            false => (i + 1).to_string(),
+        })
+        .collect();
+
+    let series = header
+        .iter()
+        .enumerate()
+        .skip(first_value_column)
+        .map(|(column, name)| Series {
+            name: name.clone(),
+            values: rows
+                .iter()
+                .map(|row| row.get(column).and_then(|x| parse_number(x)))
+                .collect(),
+        })
+        .filter(|s| s.values.iter().any(Option::is_some))
+        .collect();
+
+    Dataset { labels, series }
+}
+
+fn json_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(x) => x.as_f64(),
+        Value::String(x) => parse_number(x),
+        _ => None,
+    }
+}
+
+fn parse_json(text: &str) -> anyhow::Result<Dataset> {
+    let value = serde_json::from_str::<Value>(text)?;
+
+    match value {
+        Value::Array(items) if items.iter().all(|x| !x.is_object()) => Ok(Dataset {
+            labels: (1..=items.len()).map(|i| i.to_string()).collect(),
+            series: vec![Series {
+                name: "value".to_string(),
+                values: items.iter().map(json_number).collect(),
+            }],
+        }),
+        Value::Array(items) => {
+            let rows = items
+                .iter()
+                .filter_map(Value::as_object)
+                .collect::<Vec<_>>();
+
+            let mut keys: Vec<&String> = Vec::new();
+            for row in &rows {
+                for key in row.keys() {
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+            }
+
+            let label_key = keys
+                .iter()
+                .find(|key| {
+                    rows.iter().any(|row| {
+                        row.get(**key)
+                            .is_some_and(|x| x.is_string() && json_number(x).is_none())
+                    })
+                })
+                .copied();
+
+            let labels = rows
+                .iter()
+                .enumerate()
+                .map(|(i, row)| match label_key.and_then(|key| row.get(key)) {
+                    Some(Value::String(x)) => x.clone(),
+                    Some(x) => x.to_string(),
+                    None => (i + 1).to_string(),
+                })
+                .collect();
+
+            let series = keys
+                .into_iter()
+                .filter(|key| Some(*key) != label_key)
+                .map(|key| Series {
+                    name: key.clone(),
+                    values: rows
+                        .iter()
+                        .map(|row| row.get(key).and_then(json_number))
+                        .collect(),
+                })
+                .filter(|s| s.values.iter().any(Option::is_some))
+                .collect();
+
+            Ok(Dataset { labels, series })
+        }
+        Value::Object(map) => {
+            let series = map
+                .iter()
+                .filter_map(|(name, values)| {
+                    let values = values.as_array()?;
+                    Some(Series {
+                        name: name.clone(),
+                        values: values.iter().map(json_number).collect(),
+                    })
+                })
+                .filter(|s| s.values.iter().any(Option::is_some))
+                .collect::<Vec<_>>();
+            let rows = series.iter().map(|s| s.values.len()).max().unwrap_or(0);
+
+            Ok(Dataset {
+                labels: (1..=rows).map(|i| i.to_string()).collect(),
+                series,
+            })
+        }
+        _ => anyhow::bail!("Expected a JSON array or object"),
+    }
+}
@@ -0,0 +1,85 @@
+//! Line by line differences between two texts, for the sticker history.
+
+/// Beyond this many line pairs the texts are shown as fully replaced, the table of
+/// common lines would take too long and too much memory.
+const MAX_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// The lines of `new` compared to `old`, in order, removed lines before added ones.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // Common head and tail need no table, usually only a few lines in between changed.
+    let head = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let tail = old[head..]
+        .iter()
+        .rev()
+        .zip(new[head..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[head..old.len() - tail];
+    let new_mid = &new[head..new.len() - tail];
+
+    let mut lines = old[..head]
+        .iter()
+        .map(|x| DiffLine::Same(x.to_string()))
+        .collect::<Vec<_>>();
+    lines.extend(diff_middle(old_mid, new_mid));
+    lines.extend(
+        old[old.len() - tail..]
+            .iter()
+            .map(|x| DiffLine::Same(x.to_string())),
+    );
+    lines
+}
+
+fn diff_middle(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    let removed = |x: &&str| DiffLine::Removed(x.to_string());
+    let added = |x: &&str| DiffLine::Added(x.to_string());
+    if old.is_empty() || new.is_empty() || old.len() * new.len() > MAX_CELLS {
+        return old
+            .iter()
+            .map(removed)
+            .chain(new.iter().map(added))
+            .collect();
+    }
+
+    // Length of the longest common subsequence of old[i..] and new[j..].
+    let width = new.len() + 1;
+    let mut common = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i * width + j] = if old[i] == new[j] {
+                common[(i + 1) * width + j + 1] + 1
+            } else {
+                common[(i + 1) * width + j].max(common[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if common[(i + 1) * width + j] >= common[i * width + j + 1] {
+            lines.push(removed(&old[i]));
+            i += 1;
+        } else {
+            lines.push(added(&new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(removed));
+    lines.extend(new[j..].iter().map(added));
+    lines
+}
@@ -0,0 +1,71 @@
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{FontStyle, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+/// A dark theme, stickers always use the dark app theme.
+static THEME: LazyLock<Theme> = LazyLock::new(|| {
+    ThemeSet::load_defaults()
+        .themes
+        .remove("base16-ocean.dark")
+        .unwrap_or_default()
+});
+
+/// Styling of a byte range of the highlighted text.
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub range: Range<usize>,
+    /// `0xRRGGBB`
+    pub color: u32,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+fn find_syntax(language: &str) -> &'static SyntaxReference {
+    let language = language.trim();
+    SYNTAXES
+        .find_syntax_by_name(language)
+        .or_else(|| SYNTAXES.find_syntax_by_token(language))
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text())
+}
+
+/// Whether the language is known by its name (`Rust`) or a file extension (`rs`).
+pub fn is_known_language(language: &str) -> bool {
+    !std::ptr::eq(find_syntax(language), SYNTAXES.find_syntax_plain_text())
+}
+
+/// Highlight the code as the language, unknown languages stay plain text.
+pub fn highlight(code: &str, language: &str) -> Vec<Span> {
+    let mut lines = HighlightLines::new(find_syntax(language), &THEME);
+    let mut spans = Vec::new();
+    let mut offset = 0;
+
+    for line in LinesWithEndings::from(code) {
+        let ranges = match lines.highlight_line(line, &SYNTAXES) {
+            Ok(ranges) => ranges,
+            Err(err) => {
+                // The rest is shown unstyled.
+                tracing::warn!(error = %err, language, "Failed to highlight code");
+                break;
+            }
+        };
+
+        for (style, text) in ranges {
+            let c = style.foreground;
+            spans.push(Span {
+                range: offset..offset + text.len(),
+                color: u32::from_be_bytes([0, c.r, c.g, c.b]),
+                bold: style.font_style.contains(FontStyle::BOLD),
+                italic: style.font_style.contains(FontStyle::ITALIC),
+            });
+            offset += text.len();
+        }
+    }
+
+    spans
+}
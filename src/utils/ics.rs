@@ -0,0 +1,752 @@
+//! Just enough iCalendar (RFC 5545) to list upcoming events: VEVENTs with their
+//! start/end, simple RRULEs (FREQ, INTERVAL, COUNT, UNTIL, weekly BYDAY),
+//! EXDATE and moved instances (RECURRENCE-ID). VTODOs are read and completed
+//! for the task sync.
+
+use std::collections::HashSet;
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+/// Stop expanding pathological rules.
+const MAX_RECURRENCES: usize = 5000;
+const MAX_PERIODS: u32 = 100_000;
+
+#[derive(Debug, Clone)]
+pub struct Occurrence {
+    pub summary: String,
+    pub location: Option<String>,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub all_day: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+    Utc,
+    Tz(Tz),
+    /// Floating times and unknown TZIDs, e.g. Windows zone names.
+    Local,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EventTime {
+    naive: NaiveDateTime,
+    zone: Zone,
+}
+
+impl EventTime {
+    fn to_utc(self) -> Option<DateTime<Utc>> {
+        match self.zone {
+            Zone::Utc => Some(Utc.from_utc_datetime(&self.naive)),
+            Zone::Tz(tz) => tz
+                .from_local_datetime(&self.naive)
+                .earliest()
+                .map(|x| x.with_timezone(&Utc)),
+            Zone::Local => Local
+                .from_local_datetime(&self.naive)
+                .earliest()
+                .map(|x| x.with_timezone(&Utc)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone)]
+struct RRule {
+    freq: Frequency,
+    interval: u32,
+    count: Option<usize>,
+    until: Option<DateTime<Utc>>,
+    by_day: Vec<Weekday>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Event {
+    uid: String,
+    summary: String,
+    location: Option<String>,
+    start: Option<EventTime>,
+    end: Option<EventTime>,
+    all_day: bool,
+    rrule: Option<RRule>,
+    exdates: Vec<DateTime<Utc>>,
+    recurrence_id: Option<DateTime<Utc>>,
+}
+
+/// Every occurrence overlapping `from..to`, sorted by start.
+pub fn occurrences(text: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<Occurrence> {
+    let events = parse_events(text);
+    let moved: HashSet<(String, DateTime<Utc>)> = events
+        .iter()
+        .filter_map(|e| e.recurrence_id.map(|id| (e.uid.clone(), id)))
+        .collect();
+
+    let mut result = Vec::new();
+    for event in &events {
+        let Some(start) = event.start else {
+            continue;
+        };
+        let duration = match event.end {
+            Some(end) => end.naive - start.naive,
+            None if event.all_day => Duration::days(1),
+            None => Duration::zero(),
+        };
+
+        let starts = match (&event.rrule, event.recurrence_id) {
+            (Some(rrule), None) => expand(start, rrule, from - duration, to),
+            _ => vec![start],
+        };
+
+        for occurrence in starts {
+            let (Some(start_utc), Some(end_utc)) = (
+                occurrence.to_utc(),
+                EventTime {
+                    naive: occurrence.naive + duration,
+                    ..occurrence
+                }
+                .to_utc(),
+            ) else {
+                continue;
+            };
+
+            if event.recurrence_id.is_none()
+                && event.rrule.is_some()
+                && (event.exdates.contains(&start_utc)
+                    || moved.contains(&(event.uid.clone(), start_utc)))
+            {
+                continue;
+            }
+
+            let overlaps = if end_utc > start_utc {
+                end_utc > from && start_utc < to
+            } else {
+                start_utc >= from && start_utc < to
+            };
+            if overlaps {
+                result.push(Occurrence {
+                    summary: event.summary.clone(),
+                    location: event.location.clone(),
+                    start: start_utc.with_timezone(&Local),
+                    end: end_utc.with_timezone(&Local),
+                    all_day: event.all_day,
+                });
+            }
+        }
+    }
+
+    result.sort_by_key(|x| x.start);
+    result
+}
+
+/// Starts of the rule between `from` and `to`, earlier ones still count for COUNT.
+fn expand(
+    start: EventTime,
+    rrule: &RRule,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<EventTime> {
+    let mut result = Vec::new();
+    let mut counted = 0usize;
+    let interval = rrule.interval.max(1);
+
+    let mut period = 0u32;
+    'periods: while period < MAX_PERIODS && result.len() < MAX_RECURRENCES {
+        let candidates = match rrule.freq {
+            Frequency::Daily => vec![start.naive + Duration::days((period * interval) as i64)],
+            Frequency::Weekly if rrule.by_day.is_empty() => {
+                vec![start.naive + Duration::weeks((period * interval) as i64)]
+            }
+            Frequency::Weekly => {
+                // Days of the week which contains the start, shifted by whole weeks.
+                let week_start = start.naive
+                    - Duration::days(start.naive.weekday().num_days_from_monday() as i64)
+                    + Duration::weeks((period * interval) as i64);
+                let mut days = rrule
+                    .by_day
+                    .iter()
+                    .map(|day| week_start + Duration::days(day.num_days_from_monday() as i64))
+                    .filter(|x| *x >= start.naive)
+                    .collect::<Vec<_>>();
+                days.sort();
+                days
+            }
+            Frequency::Monthly => add_months(start.naive, period * interval)
+                .into_iter()
+                .collect(),
+            Frequency::Yearly => add_months(start.naive, period * interval * 12)
+                .into_iter()
+                .collect(),
+        };
+
+        for naive in candidates {
+            let time = EventTime { naive, ..start };
+            let Some(utc) = time.to_utc() else {
+                continue;
+            };
+            if utc >= to
+                || rrule.until.is_some_and(|until| utc > until)
+                || rrule.count.is_some_and(|count| counted >= count)
+            {
+                break 'periods;
+            }
+            counted += 1;
+            if utc >= from {
+                result.push(time);
+            }
+        }
+
+        period += 1;
+    }
+
+    result
+}
+
+/// `None` when the day does not exist in that month, e.g. the 31st, which is
+/// skipped like RFC 5545 says.
+fn add_months(naive: NaiveDateTime, months: u32) -> Option<NaiveDateTime> {
+    let shifted = naive.checked_add_months(Months::new(months))?;
+    (shifted.day() == naive.day()).then_some(shifted)
+}
+
+/// A task of a calendar object, see [`todos`].
+#[derive(Debug, Clone, Default)]
+pub struct Todo {
+    pub summary: String,
+    /// Completed or cancelled.
+    pub done: bool,
+}
+
+pub fn todos(text: &str) -> Vec<Todo> {
+    let mut todos = Vec::new();
+    let mut current: Option<Todo> = None;
+
+    for line in unfold(text) {
+        let Some((name, _, value)) = split_property(&line) else {
+            continue;
+        };
+
+        match (name.as_str(), value) {
+            ("BEGIN", "VTODO") => current = Some(Todo::default()),
+            ("END", "VTODO") => todos.extend(current.take()),
+            _ => {
+                let Some(todo) = current.as_mut() else {
+                    continue;
+                };
+                match name.as_str() {
+                    "SUMMARY" => todo.summary = unescape(value),
+                    "STATUS" => {
+                        todo.done |= matches!(value.trim(), "COMPLETED" | "CANCELLED");
+                    }
+                    "COMPLETED" => todo.done = true,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    todos
+}
+
+/// The calendar object with its VTODO marked as completed at `at`, other lines kept as they are.
+pub fn complete_todo(text: &str, at: DateTime<Utc>) -> String {
+    const REPLACED: [&str; 3] = ["STATUS", "COMPLETED", "PERCENT-COMPLETE"];
+
+    let mut lines = Vec::new();
+    let mut in_todo = false;
+    let mut skip_folded = false;
+
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if skip_folded && line.starts_with([' ', '\t']) {
+            continue;
+        }
+        skip_folded = false;
+
+        let upper = line.to_ascii_uppercase();
+        let name = upper.split([':', ';']).next().unwrap_or_default();
+        if upper == "BEGIN:VTODO" {
+            in_todo = true;
+        } else if in_todo && upper == "END:VTODO" {
+            in_todo = false;
+            lines.push("STATUS:COMPLETED".to_string());
+            lines.push(format!("COMPLETED:{}", at.format("%Y%m%dT%H%M%SZ")));
+            lines.push("PERCENT-COMPLETE:100".to_string());
+        } else if in_todo && REPLACED.contains(&name) {
+            skip_folded = true;
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+
+    lines.join("\r\n") + "\r\n"
+}
+
+fn parse_events(text: &str) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut current: Option<Event> = None;
+
+    for line in unfold(text) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+
+        match (name.as_str(), value) {
+            ("BEGIN", "VEVENT") => current = Some(Event::default()),
+            ("END", "VEVENT") => {
+                if let Some(event) = current.take() {
+                    events.push(event);
+                }
+            }
+            _ => {
+                let Some(event) = current.as_mut() else {
+                    continue;
+                };
+                match name.as_str() {
+                    "UID" => event.uid = value.to_string(),
+                    "SUMMARY" => event.summary = unescape(value),
+                    "LOCATION" => event.location = Some(unescape(value)).filter(|x| !x.is_empty()),
+                    "DTSTART" => {
+                        if let Some((time, all_day)) = parse_time(value, &params) {
+                            event.start = Some(time);
+                            event.all_day = all_day;
+                        }
+                    }
+                    "DTEND" => event.end = parse_time(value, &params).map(|(time, _)| time),
+                    "RRULE" => event.rrule = parse_rrule(value),
+                    "EXDATE" => event.exdates.extend(
+                        value
+                            .split(',')
+                            .filter_map(|x| parse_time(x, &params))
+                            .filter_map(|(time, _)| time.to_utc()),
+                    ),
+                    "RECURRENCE-ID" => {
+                        event.recurrence_id =
+                            parse_time(value, &params).and_then(|(time, _)| time.to_utc())
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    events
+}
+
+/// Long lines are folded with a leading space or tab on the next line.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if let Some(rest) = line.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(rest);
+        } else if !line.is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+    lines
+}
+
+/// `NAME;PARAM=x:VALUE` -> (NAME, [(PARAM, x)], VALUE).
+fn split_property(line: &str) -> Option<(String, Vec<(String, String)>, &str)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|x| x.split_once('='))
+        .map(|(k, v)| (k.to_ascii_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, value))
+}
+
+fn parse_time(value: &str, params: &[(String, String)]) -> Option<(EventTime, bool)> {
+    let value = value.trim();
+    let is_date = params
+        .iter()
+        .any(|(k, v)| k == "VALUE" && v.eq_ignore_ascii_case("DATE"))
+        || !value.contains('T');
+
+    if is_date {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        let time = EventTime {
+            naive: date.and_hms_opt(0, 0, 0)?,
+            zone: Zone::Local,
+        };
+        return Some((time, true));
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((
+            EventTime {
+                naive,
+                zone: Zone::Utc,
+            },
+            false,
+        ));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let zone = params
+        .iter()
+        .find(|(k, _)| k == "TZID")
+        .and_then(|(_, tzid)| tzid.parse::<Tz>().ok())
+        .map(Zone::Tz)
+        .unwrap_or(Zone::Local);
+    Some((EventTime { naive, zone }, false))
+}
+
+fn parse_rrule(value: &str) -> Option<RRule> {
+    let mut freq = None;
+    let mut rrule = RRule {
+        freq: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        by_day: Vec::new(),
+    };
+
+    for (key, v) in value.split(';').filter_map(|x| x.split_once('=')) {
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                freq = match v.to_ascii_uppercase().as_str() {
+                    "DAILY" => Some(Frequency::Daily),
+                    "WEEKLY" => Some(Frequency::Weekly),
+                    "MONTHLY" => Some(Frequency::Monthly),
+                    "YEARLY" => Some(Frequency::Yearly),
+                    // Hourly and finer rules are not worth an agenda line each.
+                    _ => None,
+                }
+            }
+            "INTERVAL" => rrule.interval = v.parse().unwrap_or(1),
+            "COUNT" => rrule.count = v.parse().ok(),
+            "UNTIL" => {
+                rrule.until = parse_time(v, &[]).and_then(|(time, all_day)| {
+                    // A date includes the whole day.
+                    let until = time.to_utc()?;
+                    Some(if all_day {
+                        until + Duration::days(1) - Duration::seconds(1)
+                    } else {
+                        until
+                    })
+                })
+            }
+            "BYDAY" => rrule.by_day = v.split(',').filter_map(parse_weekday).collect(),
+            _ => {}
+        }
+    }
+
+    rrule.freq = freq?;
+    // "Every second Tuesday of the month" style rules are not supported.
+    if rrule.freq != Frequency::Weekly && !rrule.by_day.is_empty() {
+        return None;
+    }
+    Some(rrule)
+}
+
+/// "MO", or "1MO" / "-1FR" with an ordinal.
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    let code = value.trim_start_matches(|c: char| c == '+' || c == '-' || c.is_ascii_digit());
+    match code.to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ics(lines: &[&str]) -> String {
+        lines.join("\r\n")
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn starts(text: &str, from: &str, to: &str) -> Vec<DateTime<Utc>> {
+        occurrences(text, utc(from), utc(to))
+            .into_iter()
+            .map(|x| x.start.with_timezone(&Utc))
+            .collect()
+    }
+
+    #[test]
+    fn single_event() {
+        let text = ics(&[
+            "BEGIN:VCALENDAR",
+            "BEGIN:VEVENT",
+            "UID:1",
+            "SUMMARY:Team\\, weekly",
+            "  sync",
+            "LOCATION:Room 2",
+            "DTSTART:20260105T090000Z",
+            "DTEND:20260105T093000Z",
+            "END:VEVENT",
+            "END:VCALENDAR",
+        ]);
+        let found = occurrences(
+            &text,
+            utc("2026-01-05T00:00:00Z"),
+            utc("2026-01-06T00:00:00Z"),
+        );
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].summary, "Team, weekly sync");
+        assert_eq!(found[0].location.as_deref(), Some("Room 2"));
+        assert_eq!(
+            found[0].start.with_timezone(&Utc),
+            utc("2026-01-05T09:00:00Z")
+        );
+        assert_eq!(
+            found[0].end.with_timezone(&Utc),
+            utc("2026-01-05T09:30:00Z")
+        );
+        assert!(!found[0].all_day);
+
+        // Overlapping the start of the range counts, ending before it does not.
+        assert_eq!(
+            starts(&text, "2026-01-05T09:15:00Z", "2026-01-06T00:00:00Z").len(),
+            1
+        );
+        assert!(starts(&text, "2026-01-05T09:30:00Z", "2026-01-06T00:00:00Z").is_empty());
+    }
+
+    #[test]
+    fn time_zones_and_all_day() {
+        let text = ics(&[
+            "BEGIN:VEVENT",
+            "UID:1",
+            "SUMMARY:Berlin",
+            "DTSTART;TZID=Europe/Berlin:20260701T090000",
+            "END:VEVENT",
+            "BEGIN:VEVENT",
+            "UID:2",
+            "SUMMARY:Holiday",
+            "DTSTART;VALUE=DATE:20260702",
+            "END:VEVENT",
+        ]);
+        let found = occurrences(
+            &text,
+            utc("2026-06-30T00:00:00Z"),
+            utc("2026-07-04T00:00:00Z"),
+        );
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            found[0].start.with_timezone(&Utc),
+            utc("2026-07-01T07:00:00Z")
+        );
+        assert!(found[1].all_day);
+        assert_eq!(
+            found[1].start.date_naive(),
+            NaiveDate::from_ymd_opt(2026, 7, 2).unwrap()
+        );
+        assert_eq!(found[1].end - found[1].start, Duration::days(1));
+    }
+
+    #[test]
+    fn weekly_by_day_with_count() {
+        let text = ics(&[
+            "BEGIN:VEVENT",
+            "UID:1",
+            "DTSTART:20260105T090000Z",
+            "RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4",
+            "END:VEVENT",
+        ]);
+        assert_eq!(
+            starts(&text, "2026-01-01T00:00:00Z", "2026-03-01T00:00:00Z"),
+            [
+                utc("2026-01-05T09:00:00Z"),
+                utc("2026-01-07T09:00:00Z"),
+                utc("2026-01-12T09:00:00Z"),
+                utc("2026-01-14T09:00:00Z"),
+            ]
+        );
+        // Earlier occurrences still count towards COUNT.
+        assert_eq!(
+            starts(&text, "2026-01-10T00:00:00Z", "2026-03-01T00:00:00Z"),
+            [utc("2026-01-12T09:00:00Z"), utc("2026-01-14T09:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn interval_and_until() {
+        let text = ics(&[
+            "BEGIN:VEVENT",
+            "UID:1",
+            "DTSTART:20260101T080000Z",
+            "RRULE:FREQ=DAILY;INTERVAL=2;UNTIL=20260105T080000Z",
+            "END:VEVENT",
+        ]);
+        assert_eq!(
+            starts(&text, "2026-01-01T00:00:00Z", "2026-02-01T00:00:00Z"),
+            [
+                utc("2026-01-01T08:00:00Z"),
+                utc("2026-01-03T08:00:00Z"),
+                utc("2026-01-05T08:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_skips_missing_days() {
+        let text = ics(&[
+            "BEGIN:VEVENT",
+            "UID:1",
+            "DTSTART:20260131T120000Z",
+            "RRULE:FREQ=MONTHLY;COUNT=3",
+            "END:VEVENT",
+        ]);
+        assert_eq!(
+            starts(&text, "2026-01-01T00:00:00Z", "2027-01-01T00:00:00Z"),
+            [
+                utc("2026-01-31T12:00:00Z"),
+                utc("2026-03-31T12:00:00Z"),
+                utc("2026-05-31T12:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn exdates_and_moved_instances() {
+        let text = ics(&[
+            "BEGIN:VEVENT",
+            "UID:standup",
+            "SUMMARY:Standup",
+            "DTSTART:20260302T100000Z",
+            "RRULE:FREQ=DAILY;COUNT=3",
+            "EXDATE:20260303T100000Z",
+            "END:VEVENT",
+            "BEGIN:VEVENT",
+            "UID:standup",
+            "SUMMARY:Late standup",
+            "RECURRENCE-ID:20260304T100000Z",
+            "DTSTART:20260304T150000Z",
+            "END:VEVENT",
+        ]);
+        let found = occurrences(
+            &text,
+            utc("2026-03-01T00:00:00Z"),
+            utc("2026-03-10T00:00:00Z"),
+        );
+        let found = found
+            .iter()
+            .map(|x| (x.summary.as_str(), x.start.with_timezone(&Utc)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                ("Standup", utc("2026-03-02T10:00:00Z")),
+                ("Late standup", utc("2026-03-04T15:00:00Z")),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_rules() {
+        assert!(parse_rrule("FREQ=HOURLY").is_none());
+        assert!(parse_rrule("FREQ=MONTHLY;BYDAY=2TU").is_none());
+        assert_eq!(
+            parse_rrule("FREQ=WEEKLY;BYDAY=1MO,FR").unwrap().by_day,
+            [Weekday::Mon, Weekday::Fri]
+        );
+    }
+
+    #[test]
+    fn todos_are_completed() {
+        let text = ics(&[
+            "BEGIN:VCALENDAR",
+            "BEGIN:VTODO",
+            "UID:1",
+            "SUMMARY:Buy milk",
+            "STATUS:NEEDS-ACTION",
+            "PERCENT-COMPLETE:",
+            " 0",
+            "END:VTODO",
+            "BEGIN:VTODO",
+            "UID:2",
+            "SUMMARY:Call mom",
+            "STATUS:CANCELLED",
+            "END:VTODO",
+            "END:VCALENDAR",
+        ]);
+        let found = todos(&text);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].summary, "Buy milk");
+        assert!(!found[0].done);
+        assert!(found[1].done);
+
+        let one = ics(&[
+            "BEGIN:VCALENDAR",
+            "BEGIN:VTODO",
+            "UID:1",
+            "SUMMARY:Buy milk",
+            "STATUS:NEEDS-ACTION",
+            "PERCENT-COMPLETE:",
+            " 0",
+            "END:VTODO",
+            "END:VCALENDAR",
+        ]);
+        let completed = complete_todo(&one, utc("2026-01-05T09:00:00Z"));
+        assert_eq!(
+            completed,
+            ics(&[
+                "BEGIN:VCALENDAR",
+                "BEGIN:VTODO",
+                "UID:1",
+                "SUMMARY:Buy milk",
+                "STATUS:COMPLETED",
+                "COMPLETED:20260105T090000Z",
+                "PERCENT-COMPLETE:100",
+                "END:VTODO",
+                "END:VCALENDAR",
+                "",
+            ])
+        );
+        assert!(todos(&completed)[0].done);
+    }
+}
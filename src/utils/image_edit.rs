@@ -0,0 +1,175 @@
+//! Crop, rotate and draw on images without touching the original file.
+
+use image::{Rgba, RgbaImage, imageops};
+use serde::{Deserialize, Serialize};
+
+/// A rectangle in pixels of the original image.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Crop {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Crop and rotate of an image, both kept apart from the original pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Transform {
+    /// Clockwise quarter turns, 0 to 3.
+    pub turns: u8,
+    pub crop: Option<Crop>,
+}
+
+impl Transform {
+    pub fn is_identity(&self) -> bool {
+        self.turns % 4 == 0 && self.crop.is_none()
+    }
+
+    /// Width and height of the result for an original of `size`.
+    pub fn size(&self, size: (u32, u32)) -> (u32, u32) {
+        let (width, height) = match self.crop {
+            Some(crop) => (crop.width, crop.height),
+            None => size,
+        };
+        match self.turns % 2 {
+            0 => (width, height),
+            _ => (height, width),
+        }
+    }
+
+    /// A point of the original to the result.
+    pub fn forward(&self, size: (u32, u32), x: f32, y: f32) -> (f32, f32) {
+        let (x, y) = match self.crop {
+            Some(crop) => (x - crop.x as f32, y - crop.y as f32),
+            None => (x, y),
+        };
+        let (width, height) = self.cropped_size(size);
+        match self.turns % 4 {
+            1 => (height - y, x),
+            2 => (width - x, height - y),
+            3 => (y, width - x),
+            _ => (x, y),
+        }
+    }
+
+    /// A point of the result back to the original.
+    pub fn backward(&self, size: (u32, u32), x: f32, y: f32) -> (f32, f32) {
+        let (width, height) = self.cropped_size(size);
+        let (x, y) = match self.turns % 4 {
+            1 => (y, height - x),
+            2 => (width - x, height - y),
+            3 => (width - y, x),
+            _ => (x, y),
+        };
+        match self.crop {
+            Some(crop) => (x + crop.x as f32, y + crop.y as f32),
+            None => (x, y),
+        }
+    }
+
+    /// Crop further to the rectangle between two corners given in result coordinates.
+    pub fn crop_to(&mut self, size: (u32, u32), a: (f32, f32), b: (f32, f32)) {
+        let a = self.backward(size, a.0, a.1);
+        let b = self.backward(size, b.0, b.1);
+        let (width, height) = (size.0 as f32, size.1 as f32);
+        let left = a.0.min(b.0).clamp(0.0, width).round() as u32;
+        let top = a.1.min(b.1).clamp(0.0, height).round() as u32;
+        let right = a.0.max(b.0).clamp(0.0, width).round() as u32;
+        let bottom = a.1.max(b.1).clamp(0.0, height).round() as u32;
+        if right <= left || bottom <= top {
+            return;
+        }
+        self.crop = Some(Crop {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        });
+    }
+
+    pub fn apply(&self, image: &RgbaImage) -> RgbaImage {
+        let cropped = match self.crop {
+            Some(crop) => {
+                imageops::crop_imm(image, crop.x, crop.y, crop.width, crop.height).to_image()
+            }
+            None => image.clone(),
+        };
+        match self.turns % 4 {
+            1 => imageops::rotate90(&cropped),
+            2 => imageops::rotate180(&cropped),
+            3 => imageops::rotate270(&cropped),
+            _ => cropped,
+        }
+    }
+
+    fn cropped_size(&self, size: (u32, u32)) -> (f32, f32) {
+        match self.crop {
+            Some(crop) => (crop.width as f32, crop.height as f32),
+            None => (size.0 as f32, size.1 as f32),
+        }
+    }
+}
+
+/// Draw a line through the points with round ends, `color` as 0xRRGGBBAA.
+pub fn draw_polyline(image: &mut RgbaImage, points: &[(f32, f32)], color: u32, width: f32) {
+    let [r, g, b, a] = color.to_be_bytes();
+    let radius = (width / 2.0).max(0.5);
+    let mut stamp = |x: f32, y: f32| {
+        let (left, top) = ((x - radius).floor() as i64, (y - radius).floor() as i64);
+        let (right, bottom) = ((x + radius).ceil() as i64, (y + radius).ceil() as i64);
+        for py in top.max(0)..=bottom.min(image.height() as i64 - 1) {
+            for px in left.max(0)..=right.min(image.width() as i64 - 1) {
+                let distance =
+                    ((px as f32 + 0.5 - x).powi(2) + (py as f32 + 0.5 - y).powi(2)).sqrt();
+                // A pixel wide soft edge instead of jagged steps.
+                let coverage = (radius + 0.5 - distance).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    blend(
+                        image.get_pixel_mut(px as u32, py as u32),
+                        [r, g, b],
+                        a as f32 / 255.0 * coverage,
+                    );
+                }
+            }
+        }
+    };
+
+    match points {
+        [] => {}
+        [(x, y)] => stamp(*x, *y),
+        _ => {
+            for pair in points.windows(2) {
+                let ((ax, ay), (bx, by)) = (pair[0], pair[1]);
+                let length = ((bx - ax).powi(2) + (by - ay).powi(2)).sqrt();
+                let steps = (length / (radius / 2.0).max(0.5)).ceil().max(1.0) as usize;
+                for step in 0..=steps {
+                    let t = step as f32 / steps as f32;
+                    stamp(ax + (bx - ax) * t, ay + (by - ay) * t);
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the highest coverage per pixel, so overlapping stamps do not darken a line.
+fn blend(pixel: &mut Rgba<u8>, color: [u8; 3], alpha: f32) {
+    let [r, g, b, a] = pixel.0;
+    let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * alpha).round() as u8;
+    let target = [mix(r, color[0]), mix(g, color[1]), mix(b, color[2])];
+    if target == [r, g, b] {
+        return;
+    }
+    pixel.0 = [
+        target[0],
+        target[1],
+        target[2],
+        a.max((alpha * 255.0).round() as u8),
+    ];
+}
+
+pub fn encode_png(image: &RgbaImage) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut bytes, image::ImageFormat::Png)?;
+    Ok(bytes.into_inner())
+}
@@ -1,3 +1,12 @@
+pub mod calc;
+pub mod dataset;
+pub mod diff;
+pub mod highlight;
+pub mod ics;
+pub mod image_edit;
 pub mod logging;
+pub mod ocr;
+pub mod print;
+pub mod template;
 pub mod time;
 pub mod url;
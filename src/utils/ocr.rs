@@ -0,0 +1,94 @@
+use anyhow::Context as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// Whether an OCR engine (tesseract) is available on this machine.
+pub fn is_available() -> bool {
+    which::which("tesseract").is_ok()
+}
+
+/// Extract text from an image file with the tesseract CLI.
+///
+/// This is blocking, call it from a background thread.
+pub fn extract_text_from_file(image_path: &Path) -> anyhow::Result<String> {
+    let tesseract = which::which("tesseract").context("tesseract not found in PATH")?;
+
+    let mut cmd = Command::new(tesseract);
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd
+        .arg(image_path)
+        .arg("stdout")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .context("run tesseract")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("tesseract failed: {}", stderr.trim());
+    }
+
+    Ok(normalize_text(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Extract text from encoded image bytes (png/jpeg...).
+pub fn extract_text_from_bytes(bytes: &[u8], extension: &str) -> anyhow::Result<String> {
+    let path = std::env::temp_dir().join(format!(
+        "rustickers-ocr-{}-{}.{}",
+        std::process::id(),
+        crate::utils::time::now_unix_millis(),
+        extension
+    ));
+    std::fs::write(&path, bytes).context("write temp image for ocr")?;
+    let result = extract_text_from_file(&path);
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+fn normalize_text(text: &str) -> String {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Local image files linked from Markdown, e.g. `![screenshot](/home/me/shot.png)`.
+/// URLs and missing files are skipped.
+pub fn local_image_paths(markdown: &str) -> Vec<PathBuf> {
+    const EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "bmp", "tif", "tiff"];
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    let mut rest = markdown;
+    while let Some(start) = rest.find("![") {
+        rest = &rest[start + 2..];
+        let Some(link) = rest.find("](").map(|i| &rest[i + 2..]) else {
+            break;
+        };
+        let Some(end) = link.find(')') else {
+            break;
+        };
+        let target = link[..end].trim().trim_matches(['<', '>']);
+        rest = &link[end..];
+
+        let path = PathBuf::from(target.strip_prefix("file://").unwrap_or(target));
+        let is_image = path
+            .extension()
+            .and_then(|x| x.to_str())
+            .is_some_and(|x| EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()));
+        if is_image && path.is_file() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
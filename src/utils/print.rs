@@ -0,0 +1,101 @@
+use anyhow::Context as _;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+const PAGE_STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2em; color: #111; background: #fff; }
+pre, code { font-family: ui-monospace, monospace; }
+pre { white-space: pre-wrap; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #999; padding: 4px 8px; }
+img, svg { max-width: 100%; }
+ul:has(> li > input[type=checkbox]) { list-style: none; padding-left: 1em; }
+@media print { body { margin: 0; } }
+"#;
+/// Long enough for the browser to load the page, it is not needed after that.
+const PAGE_LIFETIME: Duration = Duration::from_secs(60);
+
+fn print_dir() -> PathBuf {
+    std::env::temp_dir().join("rustickers-print")
+}
+
+/// Write the content as a standalone page and open it in the default browser,
+/// which shows the OS print dialog as soon as it is loaded. Browsers offer
+/// "Save as PDF" there, so this doubles as the PDF export.
+///
+/// This is blocking, call it from a background thread.
+pub fn print_html(title: &str, body: &str) -> anyhow::Result<PathBuf> {
+    let dir = print_dir();
+    std::fs::create_dir_all(&dir).context("create print directory")?;
+
+    let page = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}\n<script>window.addEventListener('load', () => window.print());</script>\n</body>\n</html>\n",
+        escape_html(title),
+        PAGE_STYLE,
+        body
+    );
+    // Only the user can read it (0600 on Unix), the temp directory is shared.
+    let mut file = tempfile::Builder::new()
+        .prefix("page-")
+        .suffix(".html")
+        .tempfile_in(&dir)
+        .context("create print page")?;
+    file.write_all(page.as_bytes())
+        .context("write print page")?;
+    let (_, path) = file.keep().context("keep print page")?;
+
+    let mut url = path.to_string_lossy().replace('\\', "/");
+    if !url.starts_with('/') {
+        url.insert(0, '/');
+    }
+    let opened = webbrowser::open(&format!("file://{url}")).context("open print page in browser");
+
+    let page = path.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(PAGE_LIFETIME);
+        let _ = std::fs::remove_file(page);
+    });
+    opened?;
+    Ok(path)
+}
+
+/// Remove print pages an earlier run left behind, e.g. when it exited right after
+/// printing.
+pub fn remove_old_pages() {
+    let Ok(entries) = std::fs::read_dir(print_dir()) else {
+        return;
+    };
+    for entry in entries.filter_map(|x| x.ok()) {
+        let old = entry
+            .metadata()
+            .and_then(|x| x.modified())
+            .ok()
+            .and_then(|x| SystemTime::now().duration_since(x).ok())
+            .is_some_and(|age| age >= PAGE_LIFETIME);
+        if old && let Err(err) = std::fs::remove_file(entry.path()) {
+            tracing::debug!(path = %entry.path().display(), error = %err, "Failed to remove print page");
+        }
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render markdown the same way GitHub does, task lists included.
+pub fn markdown_to_html(markdown: &str) -> String {
+    markdown::to_html_with_options(markdown, &markdown::Options::gfm())
+        .unwrap_or_else(|_| format!("<pre>{}</pre>", escape_html(markdown)))
+}
@@ -0,0 +1,130 @@
+/// Replace `{name}` placeholders with the matching variable value.
+///
+/// Unknown placeholders are kept as they are, so JSON content or plain braces
+/// survive the rendering untouched.
+pub fn render_template(template: &str, vars: &[(String, String)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let replaced = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            if !is_var_name(name) {
+                return None;
+            }
+            vars.iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| (value, end))
+        });
+
+        match replaced {
+            Some((value, end)) => {
+                result.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Variables which are always available for templates.
+pub fn builtin_vars() -> Vec<(String, String)> {
+    let now = chrono::Local::now();
+    vec![
+        ("date".to_string(), now.format("%Y-%m-%d").to_string()),
+        ("time".to_string(), now.format("%H:%M").to_string()),
+        (
+            "datetime".to_string(),
+            now.format("%Y-%m-%d %H:%M").to_string(),
+        ),
+    ]
+}
+
+/// Parse `key=value` pairs, entries without `=` get an empty value.
+pub fn parse_vars<'a>(items: impl IntoIterator<Item = &'a str>) -> Vec<(String, String)> {
+    items
+        .into_iter()
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| match item.split_once('=') {
+            Some((k, v)) => (k.trim().to_string(), v.trim().to_string()),
+            None => (item.to_string(), String::new()),
+        })
+        .collect()
+}
+
+fn is_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(items: &[&str]) -> Vec<(String, String)> {
+        parse_vars(items.iter().copied())
+    }
+
+    #[test]
+    fn render() {
+        let vars = vars(&["service=db", "on_call=ann", "sev-level=1"]);
+        assert_eq!(
+            render_template(
+                "{service} incident, page {on_call} (sev {sev-level})",
+                &vars
+            ),
+            "db incident, page ann (sev 1)"
+        );
+        assert_eq!(render_template("{service}{service}", &vars), "dbdb");
+    }
+
+    #[test]
+    fn render_keeps_unknown_braces() {
+        let vars = vars(&["service=db"]);
+        assert_eq!(
+            render_template("{unknown} {service}", &vars),
+            "{unknown} db"
+        );
+        assert_eq!(
+            render_template(r#"{"items":[],"title":"{service}"}"#, &vars),
+            r#"{"items":[],"title":"db"}"#
+        );
+        assert_eq!(
+            render_template("{} { service } {service", &vars),
+            "{} { service } {service"
+        );
+        assert_eq!(render_template("{{service}}", &vars), "{db}");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            vars(&[" a = 1 ", "b=x=y", "flag", "  "]),
+            [
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "x=y".to_string()),
+                ("flag".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn builtins() {
+        let vars = builtin_vars();
+        let date = &vars.iter().find(|(key, _)| key == "date").unwrap().1;
+        assert_eq!(date.len(), "2024-01-31".len());
+        assert!(render_template("{datetime}", &vars).starts_with(date.as_str()));
+    }
+}